@@ -1,6 +1,6 @@
 use futures::stream::Stream;
 use futures::StreamExt;
-use opentelemetry::global::shutdown_tracer_provider;
+use opentelemetry::global::{shutdown_meter_provider, shutdown_tracer_provider};
 use opentelemetry::sdk::metrics::{selectors, PushController};
 use opentelemetry::trace::TraceError;
 use opentelemetry::{
@@ -104,6 +104,7 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
     tokio::time::sleep(Duration::from_secs(60)).await;
 
     shutdown_tracer_provider();
+    shutdown_meter_provider();
 
     Ok(())
 }
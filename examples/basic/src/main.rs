@@ -1,6 +1,6 @@
 use futures::stream::{Stream, StreamExt};
 use opentelemetry::global;
-use opentelemetry::global::shutdown_tracer_provider;
+use opentelemetry::global::{shutdown_meter_provider, shutdown_tracer_provider};
 use opentelemetry::sdk::{metrics::PushController, trace as sdktrace};
 use opentelemetry::trace::TraceError;
 use opentelemetry::{
@@ -100,6 +100,7 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
     });
 
     shutdown_tracer_provider(); // sending remaining spans.
+    shutdown_meter_provider(); // exporting remaining metrics.
 
     Ok(())
 }
@@ -13,10 +13,8 @@ use opentelemetry::{
     trace::{TraceContextExt, Tracer},
     Key,
 };
-use tonic::{
-    metadata::{MetadataKey, MetadataMap},
-    transport::ClientTlsConfig,
-};
+use opentelemetry_otlp::TlsConfig;
+use tonic::metadata::{MetadataKey, MetadataMap};
 use url::Url;
 
 use opentelemetry::global::shutdown_tracer_provider;
@@ -58,13 +56,15 @@ fn init_tracer() -> Result<sdktrace::Tracer, TraceError> {
     opentelemetry_otlp::new_pipeline()
         .with_endpoint(endpoint.as_str())
         .with_metadata(dbg!(metadata))
-        .with_tls_config(
-            ClientTlsConfig::new().domain_name(
+        .with_tls_config(TlsConfig {
+            domain_name: Some(
                 endpoint
                     .host_str()
-                    .expect("the specified endpoint should have a valid host"),
+                    .expect("the specified endpoint should have a valid host")
+                    .to_string(),
             ),
-        )
+            ..Default::default()
+        })
         .install()
 }
 
@@ -0,0 +1,204 @@
+//! Ec2ResourceDetector
+//!
+//! Implementation of `AsyncResourceDetector` to describe an EC2 instance
+//! using the instance metadata service (IMDSv2).
+use super::{boxed_delay, detect_with_timeout, Delay};
+use futures_util::future::{BoxFuture, FutureExt};
+use http::{Method, Request};
+use opentelemetry::sdk::resource::AsyncResourceDetector;
+use opentelemetry::sdk::Resource;
+use opentelemetry::trace::TraceError;
+use opentelemetry::KeyValue;
+use opentelemetry_http::HttpClient;
+use serde::Deserialize;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+const IMDS_ENDPOINT: &str = "http://169.254.169.254";
+const TOKEN_PATH: &str = "/latest/api/token";
+const IDENTITY_DOCUMENT_PATH: &str = "/latest/dynamic/instance-identity/document";
+const HOSTNAME_PATH: &str = "/latest/meta-data/hostname";
+
+const TOKEN_HEADER: &str = "X-aws-ec2-metadata-token";
+const TOKEN_TTL_HEADER: &str = "X-aws-ec2-metadata-token-ttl-seconds";
+/// Session tokens are only used for a single detection.
+const TOKEN_TTL_SECONDS: &str = "60";
+
+/// Resource detector describing the EC2 instance the process is running on.
+///
+/// Populates `cloud.provider`, `cloud.account.id`, `cloud.region`,
+/// `cloud.zone`, `host.id`, `host.type`, `host.image.id` and `host.name` from
+/// the instance identity document served by the instance metadata service.
+/// Only IMDSv2 (session token based) requests are made.
+#[derive(Clone)]
+pub struct Ec2ResourceDetector {
+    client: Arc<dyn HttpClient>,
+    delay: Delay,
+    endpoint: String,
+}
+
+impl fmt::Debug for Ec2ResourceDetector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ec2ResourceDetector")
+            .field("client", &self.client)
+            .field("endpoint", &self.endpoint)
+            .finish()
+    }
+}
+
+impl Ec2ResourceDetector {
+    /// Create `Ec2ResourceDetector` instance querying metadata with `client`.
+    ///
+    /// `delay` creates a future resolving after the given duration, e.g.
+    /// `tokio::time::sleep`, and is used to enforce the detection timeout.
+    pub fn new<C, D, DS>(client: C, delay: D) -> Self
+    where
+        C: HttpClient + 'static,
+        D: Fn(Duration) -> DS + Send + Sync + 'static,
+        DS: Future<Output = ()> + Send + 'static,
+    {
+        Ec2ResourceDetector::with_client(Arc::new(client), boxed_delay(delay))
+    }
+
+    /// Create an `Ec2ResourceDetector` sharing the client and delay of
+    /// another detector.
+    pub(super) fn with_client(client: Arc<dyn HttpClient>, delay: Delay) -> Self {
+        Ec2ResourceDetector {
+            client,
+            delay,
+            endpoint: IMDS_ENDPOINT.to_string(),
+        }
+    }
+
+    pub(super) async fn detect_instance(&self) -> Result<Resource, TraceError> {
+        let token = self
+            .get(
+                Request::builder()
+                    .method(Method::PUT)
+                    .uri(format!("{}{}", self.endpoint, TOKEN_PATH))
+                    .header(TOKEN_TTL_HEADER, TOKEN_TTL_SECONDS),
+            )
+            .await?;
+        let document = self.metadata(IDENTITY_DOCUMENT_PATH, &token).await?;
+        let document: IdentityDocument = serde_json::from_str(&document)
+            .map_err(|err| TraceError::from(format!("invalid identity document: {}", err)))?;
+
+        let mut attributes = vec![
+            KeyValue::new("cloud.provider", "aws"),
+            KeyValue::new("cloud.account.id", document.account_id),
+            KeyValue::new("cloud.region", document.region),
+            KeyValue::new("cloud.zone", document.availability_zone),
+            KeyValue::new("host.id", document.instance_id),
+            KeyValue::new("host.type", document.instance_type),
+            KeyValue::new("host.image.id", document.image_id),
+        ];
+        // the host name is optional, instances may be launched without one
+        if let Ok(host_name) = self.metadata(HOSTNAME_PATH, &token).await {
+            attributes.push(KeyValue::new("host.name", host_name));
+        }
+
+        Ok(Resource::new(attributes))
+    }
+
+    async fn metadata(&self, path: &str, token: &str) -> Result<String, TraceError> {
+        self.get(
+            Request::builder()
+                .method(Method::GET)
+                .uri(format!("{}{}", self.endpoint, path))
+                .header(TOKEN_HEADER, token),
+        )
+        .await
+    }
+
+    async fn get(&self, request: http::request::Builder) -> Result<String, TraceError> {
+        let request = request
+            .body(Vec::new())
+            .map_err(|err| TraceError::from(err.to_string()))?;
+        let response = self.client.send(request).await?;
+        String::from_utf8(response.into_body())
+            .map(|body| body.trim().to_string())
+            .map_err(|err| TraceError::from(err.to_string()))
+    }
+}
+
+impl AsyncResourceDetector for Ec2ResourceDetector {
+    fn detect(&self, timeout: Duration) -> BoxFuture<'_, Resource> {
+        detect_with_timeout(&self.delay, timeout, self.detect_instance()).boxed()
+    }
+}
+
+/// Subset of the instance identity document.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IdentityDocument {
+    account_id: String,
+    availability_zone: String,
+    image_id: String,
+    instance_id: String,
+    instance_type: String,
+    region: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detector::tests::MetadataClient;
+
+    const IDENTITY_DOCUMENT: &str = r#"{
+        "accountId": "123456789012",
+        "architecture": "x86_64",
+        "availabilityZone": "us-west-2b",
+        "imageId": "ami-5fb8c835",
+        "instanceId": "i-1234567890abcdef0",
+        "instanceType": "t2.micro",
+        "pendingTime": "2016-11-19T16:32:11Z",
+        "privateIp": "10.158.112.84",
+        "region": "us-west-2",
+        "version": "2017-09-30"
+    }"#;
+
+    #[test]
+    fn detect_ec2_instance() {
+        let client = MetadataClient::default()
+            .with_response(TOKEN_PATH, "token\n")
+            .with_response(IDENTITY_DOCUMENT_PATH, IDENTITY_DOCUMENT)
+            .with_response(HOSTNAME_PATH, "ip-10-158-112-84.us-west-2.compute.internal");
+        let client = Arc::new(client);
+        let detector = Ec2ResourceDetector {
+            client: client.clone(),
+            delay: boxed_delay(|_| futures_util::future::pending()),
+            endpoint: IMDS_ENDPOINT.to_string(),
+        };
+
+        let resource = futures::executor::block_on(detector.detect(Duration::from_secs(1)));
+        assert_eq!(
+            resource,
+            Resource::new(vec![
+                KeyValue::new("cloud.provider", "aws"),
+                KeyValue::new("cloud.account.id", "123456789012"),
+                KeyValue::new("cloud.region", "us-west-2"),
+                KeyValue::new("cloud.zone", "us-west-2b"),
+                KeyValue::new("host.id", "i-1234567890abcdef0"),
+                KeyValue::new("host.type", "t2.micro"),
+                KeyValue::new("host.image.id", "ami-5fb8c835"),
+                KeyValue::new("host.name", "ip-10-158-112-84.us-west-2.compute.internal"),
+            ])
+        );
+
+        let requests = client.requests.lock().unwrap();
+        assert_eq!(requests[0].method(), Method::PUT);
+        assert_eq!(requests[0].headers()[TOKEN_TTL_HEADER], TOKEN_TTL_SECONDS);
+        assert!(requests[1..]
+            .iter()
+            .all(|request| request.headers()[TOKEN_HEADER] == "token"));
+    }
+
+    #[test]
+    fn detect_outside_ec2() {
+        let detector = Ec2ResourceDetector::new(MetadataClient::default(), |_| async {});
+        let resource = futures::executor::block_on(detector.detect(Duration::from_secs(1)));
+        assert!(resource.is_empty());
+    }
+}
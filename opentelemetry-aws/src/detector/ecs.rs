@@ -0,0 +1,233 @@
+//! EcsResourceDetector
+//!
+//! Implementation of `AsyncResourceDetector` to describe an ECS task using
+//! the task metadata endpoint version 4.
+use super::{boxed_delay, detect_with_timeout, Delay};
+use futures_util::future::{self, BoxFuture, FutureExt};
+use http::{Method, Request};
+use opentelemetry::sdk::resource::AsyncResourceDetector;
+use opentelemetry::sdk::Resource;
+use opentelemetry::trace::TraceError;
+use opentelemetry::KeyValue;
+use opentelemetry_http::HttpClient;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::env;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Environment variable injected by the ECS container agent holding the
+/// container's metadata endpoint.
+const ECS_CONTAINER_METADATA_URI_V4: &str = "ECS_CONTAINER_METADATA_URI_V4";
+
+/// Resource detector describing the ECS task and container the process is
+/// running in.
+///
+/// Populates `cloud.provider`, `cloud.account.id`, `cloud.region`,
+/// `cloud.zone`, `container.id`, `container.name` and the `aws.ecs.*`
+/// attributes. Detection is skipped if the `ECS_CONTAINER_METADATA_URI_V4`
+/// environment variable is not set.
+#[derive(Clone)]
+pub struct EcsResourceDetector {
+    client: Arc<dyn HttpClient>,
+    delay: Delay,
+}
+
+impl fmt::Debug for EcsResourceDetector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EcsResourceDetector")
+            .field("client", &self.client)
+            .finish()
+    }
+}
+
+impl EcsResourceDetector {
+    /// Create `EcsResourceDetector` instance querying metadata with `client`.
+    ///
+    /// `delay` creates a future resolving after the given duration, e.g.
+    /// `tokio::time::sleep`, and is used to enforce the detection timeout.
+    pub fn new<C, D, DS>(client: C, delay: D) -> Self
+    where
+        C: HttpClient + 'static,
+        D: Fn(Duration) -> DS + Send + Sync + 'static,
+        DS: Future<Output = ()> + Send + 'static,
+    {
+        EcsResourceDetector {
+            client: Arc::new(client),
+            delay: boxed_delay(delay),
+        }
+    }
+
+    async fn detect_task(&self, metadata_uri: String) -> Result<Resource, TraceError> {
+        let container: ContainerMetadata = self.get(&metadata_uri).await?;
+        let task: TaskMetadata = self.get(&format!("{}/task", metadata_uri)).await?;
+
+        // arn:aws:ecs:<region>:<account>:task/<cluster>/<id>
+        let mut arn = task.task_arn.split(':');
+        let (region, account_id) = match (arn.nth(3), arn.next()) {
+            (Some(region), Some(account_id)) => (region.to_string(), account_id.to_string()),
+            _ => return Err(format!("invalid task arn {}", task.task_arn).into()),
+        };
+        let cluster_arn = if task.cluster.starts_with("arn:") {
+            task.cluster
+        } else {
+            format!(
+                "arn:aws:ecs:{}:{}:cluster/{}",
+                region, account_id, task.cluster
+            )
+        };
+
+        let mut attributes = vec![
+            KeyValue::new("cloud.provider", "aws"),
+            KeyValue::new("cloud.account.id", account_id),
+            KeyValue::new("cloud.region", region),
+            KeyValue::new("container.id", container.docker_id),
+            KeyValue::new("container.name", container.name),
+            KeyValue::new("aws.ecs.cluster.arn", cluster_arn),
+            KeyValue::new("aws.ecs.task.arn", task.task_arn),
+            KeyValue::new("aws.ecs.task.family", task.family),
+            KeyValue::new("aws.ecs.task.revision", task.revision),
+        ];
+        if let Some(container_arn) = container.container_arn {
+            attributes.push(KeyValue::new("aws.ecs.container.arn", container_arn));
+        }
+        if let Some(launch_type) = task.launch_type {
+            attributes.push(KeyValue::new(
+                "aws.ecs.launchtype",
+                launch_type.to_lowercase(),
+            ));
+        }
+        if let Some(zone) = task.availability_zone {
+            attributes.push(KeyValue::new("cloud.zone", zone));
+        }
+
+        Ok(Resource::new(attributes))
+    }
+
+    async fn get<T: DeserializeOwned>(&self, uri: &str) -> Result<T, TraceError> {
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri(uri)
+            .body(Vec::new())
+            .map_err(|err| TraceError::from(err.to_string()))?;
+        let response = self.client.send(request).await?;
+        serde_json::from_slice(response.body())
+            .map_err(|err| TraceError::from(format!("invalid ecs metadata: {}", err)))
+    }
+}
+
+impl AsyncResourceDetector for EcsResourceDetector {
+    fn detect(&self, timeout: Duration) -> BoxFuture<'_, Resource> {
+        match env::var(ECS_CONTAINER_METADATA_URI_V4) {
+            Ok(metadata_uri) if !metadata_uri.is_empty() => {
+                detect_with_timeout(&self.delay, timeout, self.detect_task(metadata_uri)).boxed()
+            }
+            _ => future::ready(Resource::empty()).boxed(),
+        }
+    }
+}
+
+/// Subset of the container metadata response.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ContainerMetadata {
+    docker_id: String,
+    name: String,
+    #[serde(rename = "ContainerARN")]
+    container_arn: Option<String>,
+}
+
+/// Subset of the task metadata response.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TaskMetadata {
+    cluster: String,
+    #[serde(rename = "TaskARN")]
+    task_arn: String,
+    family: String,
+    revision: String,
+    availability_zone: Option<String>,
+    launch_type: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detector::tests::MetadataClient;
+
+    const CONTAINER_METADATA: &str = r#"{
+        "DockerId": "ea32192c8553fbff06c9340478a2ff089b2bb5646fb718b4ee206641c9086d66",
+        "Name": "curl",
+        "DockerName": "ecs-curltest-24-curl-cca48e8dcadd97805600",
+        "Image": "111122223333.dkr.ecr.us-west-2.amazonaws.com/curltest:latest",
+        "ContainerARN": "arn:aws:ecs:us-west-2:111122223333:container/0206b271-b33f-47ab-86c6-a0ba208a70a9"
+    }"#;
+
+    const TASK_METADATA: &str = r#"{
+        "Cluster": "default",
+        "TaskARN": "arn:aws:ecs:us-west-2:111122223333:task/default/158d1c8083dd49d6b527399fd6414f5c",
+        "Family": "curltest",
+        "Revision": "26",
+        "DesiredStatus": "RUNNING",
+        "KnownStatus": "RUNNING",
+        "AvailabilityZone": "us-west-2d",
+        "LaunchType": "EC2"
+    }"#;
+
+    #[test]
+    fn detect_ecs_task() {
+        let client = MetadataClient::default()
+            .with_response("/v4/abc", CONTAINER_METADATA)
+            .with_response("/v4/abc/task", TASK_METADATA);
+        let detector = EcsResourceDetector::new(client, |_| future::pending());
+
+        let resource = futures::executor::block_on(
+            detector.detect_task("http://169.254.170.2/v4/abc".to_string()),
+        )
+        .unwrap();
+        assert_eq!(
+            resource,
+            Resource::new(vec![
+                KeyValue::new("cloud.provider", "aws"),
+                KeyValue::new("cloud.account.id", "111122223333"),
+                KeyValue::new("cloud.region", "us-west-2"),
+                KeyValue::new("cloud.zone", "us-west-2d"),
+                KeyValue::new(
+                    "container.id",
+                    "ea32192c8553fbff06c9340478a2ff089b2bb5646fb718b4ee206641c9086d66"
+                ),
+                KeyValue::new("container.name", "curl"),
+                KeyValue::new(
+                    "aws.ecs.cluster.arn",
+                    "arn:aws:ecs:us-west-2:111122223333:cluster/default"
+                ),
+                KeyValue::new(
+                    "aws.ecs.task.arn",
+                    "arn:aws:ecs:us-west-2:111122223333:task/default/158d1c8083dd49d6b527399fd6414f5c"
+                ),
+                KeyValue::new("aws.ecs.task.family", "curltest"),
+                KeyValue::new("aws.ecs.task.revision", "26"),
+                KeyValue::new(
+                    "aws.ecs.container.arn",
+                    "arn:aws:ecs:us-west-2:111122223333:container/0206b271-b33f-47ab-86c6-a0ba208a70a9"
+                ),
+                KeyValue::new("aws.ecs.launchtype", "ec2"),
+            ])
+        );
+    }
+
+    #[test]
+    fn detect_invalid_metadata() {
+        let client = MetadataClient::default()
+            .with_response("/v4/abc", CONTAINER_METADATA)
+            .with_response("/v4/abc/task", "{}");
+        let detector = EcsResourceDetector::new(client, |_| future::pending());
+
+        let result = futures::executor::block_on(
+            detector.detect_task("http://169.254.170.2/v4/abc".to_string()),
+        );
+        assert!(result.is_err());
+    }
+}
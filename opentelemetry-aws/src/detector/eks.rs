@@ -0,0 +1,250 @@
+//! EksResourceDetector
+//!
+//! Implementation of `AsyncResourceDetector` to describe an EKS cluster node,
+//! on top of the EC2 instance it is running on.
+use super::{boxed_delay, detect_with_timeout, Delay, Ec2ResourceDetector};
+use futures_util::future::{self, BoxFuture, FutureExt};
+use http::{header::AUTHORIZATION, Method, Request};
+use opentelemetry::sdk::resource::AsyncResourceDetector;
+use opentelemetry::sdk::Resource;
+use opentelemetry::trace::TraceError;
+use opentelemetry::KeyValue;
+use opentelemetry_http::HttpClient;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+const K8S_API_ENDPOINT: &str = "https://kubernetes.default.svc";
+/// Token of the service account mounted into every pod.
+const SERVICE_ACCOUNT_TOKEN_PATH: &str = "/var/run/secrets/kubernetes.io/serviceaccount/token";
+/// Config map mapping IAM identities to cluster users, only present on EKS.
+const AWS_AUTH_PATH: &str = "/api/v1/namespaces/kube-system/configmaps/aws-auth";
+/// Config map published by the CloudWatch agent, holding the cluster name.
+const CLUSTER_INFO_PATH: &str = "/api/v1/namespaces/amazon-cloudwatch/configmaps/cluster-info";
+const CLUSTER_NAME_KEY: &str = "cluster.name";
+
+/// Resource detector describing the EKS cluster the process is running in.
+///
+/// Populates `cloud.provider`, `cloud.platform` and `k8s.cluster.name`, and
+/// the attributes of the [`Ec2ResourceDetector`] if the pod can reach the
+/// instance metadata service. The cluster name is read from the
+/// `amazon-cloudwatch/cluster-info` config map, it is omitted if the
+/// CloudWatch agent is not installed.
+///
+/// Requests to the Kubernetes API are authenticated with the token of the
+/// pod's service account, `client` must trust the cluster's certificate
+/// authority (`/var/run/secrets/kubernetes.io/serviceaccount/ca.crt`) and the
+/// service account must be allowed to read the config maps. Detection is
+/// skipped if no service account token is mounted.
+#[derive(Clone)]
+pub struct EksResourceDetector {
+    client: Arc<dyn HttpClient>,
+    delay: Delay,
+    ec2: Ec2ResourceDetector,
+    endpoint: String,
+    token_path: PathBuf,
+}
+
+impl fmt::Debug for EksResourceDetector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EksResourceDetector")
+            .field("client", &self.client)
+            .field("endpoint", &self.endpoint)
+            .finish()
+    }
+}
+
+impl EksResourceDetector {
+    /// Create `EksResourceDetector` instance querying the Kubernetes API and
+    /// instance metadata with `client`.
+    ///
+    /// `delay` creates a future resolving after the given duration, e.g.
+    /// `tokio::time::sleep`, and is used to enforce the detection timeout.
+    pub fn new<C, D, DS>(client: C, delay: D) -> Self
+    where
+        C: HttpClient + 'static,
+        D: Fn(Duration) -> DS + Send + Sync + 'static,
+        DS: Future<Output = ()> + Send + 'static,
+    {
+        let client: Arc<dyn HttpClient> = Arc::new(client);
+        let delay = boxed_delay(delay);
+        EksResourceDetector {
+            ec2: Ec2ResourceDetector::with_client(client.clone(), delay.clone()),
+            client,
+            delay,
+            endpoint: K8S_API_ENDPOINT.to_string(),
+            token_path: PathBuf::from(SERVICE_ACCOUNT_TOKEN_PATH),
+        }
+    }
+
+    async fn detect_cluster(&self, token: String) -> Result<Resource, TraceError> {
+        self.config_map(AWS_AUTH_PATH, &token).await?;
+
+        let mut attributes = vec![
+            KeyValue::new("cloud.provider", "aws"),
+            KeyValue::new("cloud.platform", "aws_eks"),
+        ];
+        if let Ok(mut cluster_info) = self.config_map(CLUSTER_INFO_PATH, &token).await {
+            if let Some(cluster_name) = cluster_info.data.remove(CLUSTER_NAME_KEY) {
+                attributes.push(KeyValue::new("k8s.cluster.name", cluster_name));
+            }
+        }
+        let cluster = Resource::new(attributes);
+
+        // pods may not reach the instance metadata service, e.g. if the hop
+        // limit of the instance is 1
+        Ok(match self.ec2.detect_instance().await {
+            Ok(instance) => cluster.merge(&instance),
+            Err(_) => cluster,
+        })
+    }
+
+    async fn config_map(&self, path: &str, token: &str) -> Result<ConfigMap, TraceError> {
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri(format!("{}{}", self.endpoint, path))
+            .header(AUTHORIZATION, format!("Bearer {}", token))
+            .body(Vec::new())
+            .map_err(|err| TraceError::from(err.to_string()))?;
+        let response = self.client.send(request).await?;
+        serde_json::from_slice(response.body())
+            .map_err(|err| TraceError::from(format!("invalid config map: {}", err)))
+    }
+}
+
+impl AsyncResourceDetector for EksResourceDetector {
+    fn detect(&self, timeout: Duration) -> BoxFuture<'_, Resource> {
+        match fs::read_to_string(&self.token_path) {
+            Ok(token) if !token.trim().is_empty() => detect_with_timeout(
+                &self.delay,
+                timeout,
+                self.detect_cluster(token.trim().to_string()),
+            )
+            .boxed(),
+            _ => future::ready(Resource::empty()).boxed(),
+        }
+    }
+}
+
+/// Subset of a config map.
+#[derive(Debug, Deserialize)]
+struct ConfigMap {
+    #[serde(default)]
+    data: HashMap<String, String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detector::tests::MetadataClient;
+
+    const AWS_AUTH: &str = r#"{
+        "kind": "ConfigMap",
+        "apiVersion": "v1",
+        "metadata": {"name": "aws-auth", "namespace": "kube-system"},
+        "data": {"mapRoles": "- rolearn: arn:aws:iam::123456789012:role/node\n"}
+    }"#;
+
+    const CLUSTER_INFO: &str = r#"{
+        "kind": "ConfigMap",
+        "apiVersion": "v1",
+        "metadata": {"name": "cluster-info", "namespace": "amazon-cloudwatch"},
+        "data": {"cluster.name": "my-cluster", "logs.region": "us-west-2"}
+    }"#;
+
+    const IDENTITY_DOCUMENT: &str = r#"{
+        "accountId": "123456789012",
+        "availabilityZone": "us-west-2b",
+        "imageId": "ami-5fb8c835",
+        "instanceId": "i-1234567890abcdef0",
+        "instanceType": "t2.micro",
+        "region": "us-west-2"
+    }"#;
+
+    fn detector(client: MetadataClient, token: &str) -> (EksResourceDetector, Arc<MetadataClient>) {
+        let token_path = std::env::temp_dir().join(format!(
+            "opentelemetry-aws-eks-token-{}-{}",
+            std::process::id(),
+            token
+        ));
+        fs::write(&token_path, token).unwrap();
+
+        let client = Arc::new(client);
+        let delay = boxed_delay(|_| future::pending());
+        let detector = EksResourceDetector {
+            client: client.clone(),
+            delay: delay.clone(),
+            ec2: Ec2ResourceDetector::with_client(client.clone(), delay),
+            endpoint: K8S_API_ENDPOINT.to_string(),
+            token_path,
+        };
+        (detector, client)
+    }
+
+    #[test]
+    fn detect_eks_node() {
+        let client = MetadataClient::default()
+            .with_response(AWS_AUTH_PATH, AWS_AUTH)
+            .with_response(CLUSTER_INFO_PATH, CLUSTER_INFO)
+            .with_response("/latest/api/token", "token")
+            .with_response(
+                "/latest/dynamic/instance-identity/document",
+                IDENTITY_DOCUMENT,
+            );
+        let (detector, client) = detector(client, "node");
+
+        let resource = futures::executor::block_on(detector.detect(Duration::from_secs(1)));
+        fs::remove_file(&detector.token_path).unwrap();
+        assert_eq!(
+            resource,
+            Resource::new(vec![
+                KeyValue::new("cloud.provider", "aws"),
+                KeyValue::new("cloud.platform", "aws_eks"),
+                KeyValue::new("k8s.cluster.name", "my-cluster"),
+                KeyValue::new("cloud.account.id", "123456789012"),
+                KeyValue::new("cloud.region", "us-west-2"),
+                KeyValue::new("cloud.zone", "us-west-2b"),
+                KeyValue::new("host.id", "i-1234567890abcdef0"),
+                KeyValue::new("host.type", "t2.micro"),
+                KeyValue::new("host.image.id", "ami-5fb8c835"),
+            ])
+        );
+
+        let requests = client.requests.lock().unwrap();
+        assert_eq!(requests[0].headers()[AUTHORIZATION], "Bearer node");
+    }
+
+    #[test]
+    fn detect_without_instance_metadata() {
+        let client = MetadataClient::default().with_response(AWS_AUTH_PATH, AWS_AUTH);
+        let (detector, _) = detector(client, "pod");
+
+        let resource = futures::executor::block_on(detector.detect(Duration::from_secs(1)));
+        fs::remove_file(&detector.token_path).unwrap();
+        assert_eq!(
+            resource,
+            Resource::new(vec![
+                KeyValue::new("cloud.provider", "aws"),
+                KeyValue::new("cloud.platform", "aws_eks"),
+            ])
+        );
+    }
+
+    #[test]
+    fn detect_outside_eks() {
+        let (detector, _) = detector(MetadataClient::default(), "other");
+        let resource = futures::executor::block_on(detector.detect(Duration::from_secs(1)));
+        fs::remove_file(&detector.token_path).unwrap();
+        assert!(resource.is_empty());
+
+        let mut detector = EksResourceDetector::new(MetadataClient::default(), |_| async {});
+        detector.token_path = PathBuf::from("/nonexistent/token");
+        let resource = futures::executor::block_on(detector.detect(Duration::from_secs(1)));
+        assert!(resource.is_empty());
+    }
+}
@@ -0,0 +1,130 @@
+//! # AWS Resource Detectors
+//!
+//! Asynchronous [`AsyncResourceDetector`]s which query AWS metadata endpoints
+//! to describe the compute environment the process is running in.
+//!
+//! Metadata endpoints are only reachable from within AWS, the detectors
+//! therefore accept a delay function (e.g. `tokio::time::sleep`) used to give
+//! up on detection once the timeout passed to `detect` elapses. Detectors
+//! resolve to an empty [`Resource`] if the metadata cannot be retrieved.
+//!
+//! ```no_run
+//! use opentelemetry::{runtime, sdk::Resource};
+//! use opentelemetry_aws::detector::{
+//!     Ec2ResourceDetector, EcsResourceDetector, EksResourceDetector,
+//! };
+//! use std::time::Duration;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let resource = Resource::from_detectors_async(
+//!         Duration::from_secs(1),
+//!         vec![
+//!             Box::new(EcsResourceDetector::new(reqwest::Client::new(), tokio::time::sleep)),
+//!             Box::new(EksResourceDetector::new(reqwest::Client::new(), tokio::time::sleep)),
+//!             Box::new(Ec2ResourceDetector::new(reqwest::Client::new(), tokio::time::sleep)),
+//!         ],
+//!         runtime::Tokio,
+//!     )
+//!     .await;
+//! }
+//! ```
+//!
+//! [`AsyncResourceDetector`]: opentelemetry::sdk::resource::AsyncResourceDetector
+//! [`Resource`]: opentelemetry::sdk::Resource
+mod ec2;
+mod ecs;
+mod eks;
+
+pub use ec2::Ec2ResourceDetector;
+pub use ecs::EcsResourceDetector;
+pub use eks::EksResourceDetector;
+
+use futures_util::future::{self, BoxFuture, Either, FutureExt};
+use opentelemetry::sdk::Resource;
+use opentelemetry::trace::TraceError;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Creates a future which resolves once the given duration has elapsed.
+type Delay = Arc<dyn Fn(Duration) -> BoxFuture<'static, ()> + Send + Sync>;
+
+fn boxed_delay<D, DS>(delay: D) -> Delay
+where
+    D: Fn(Duration) -> DS + Send + Sync + 'static,
+    DS: Future<Output = ()> + Send + 'static,
+{
+    Arc::new(move |duration| delay(duration).boxed())
+}
+
+/// Runs `detection`, resolving to an empty resource if it fails or does not
+/// complete within `timeout`.
+async fn detect_with_timeout<F>(delay: &Delay, timeout: Duration, detection: F) -> Resource
+where
+    F: Future<Output = Result<Resource, TraceError>>,
+{
+    futures_util::pin_mut!(detection);
+    match future::select(detection, delay(timeout)).await {
+        Either::Left((Ok(resource), _)) => resource,
+        Either::Left((Err(_), _)) | Either::Right(_) => Resource::empty(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use http::{Request, Response};
+    use opentelemetry::KeyValue;
+    use opentelemetry_http::HttpClient;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    /// Replies to requests with canned bodies keyed by request path.
+    #[derive(Debug, Default)]
+    pub(super) struct MetadataClient {
+        responses: HashMap<String, String>,
+        pub(super) requests: Mutex<Vec<Request<Vec<u8>>>>,
+    }
+
+    impl MetadataClient {
+        pub(super) fn with_response(mut self, path: &str, body: &str) -> Self {
+            self.responses.insert(path.to_string(), body.to_string());
+            self
+        }
+    }
+
+    #[async_trait]
+    impl HttpClient for MetadataClient {
+        async fn send(&self, request: Request<Vec<u8>>) -> Result<Response<Vec<u8>>, TraceError> {
+            let body = self.responses.get(request.uri().path()).cloned();
+            self.requests.lock().unwrap().push(request);
+            match body {
+                Some(body) => Ok(Response::new(body.into_bytes())),
+                None => Err("not found".into()),
+            }
+        }
+    }
+
+    #[test]
+    fn detection_times_out() {
+        let delay = boxed_delay(|_| future::ready(()));
+        let detection = future::pending::<Result<Resource, TraceError>>();
+        let resource = futures::executor::block_on(detect_with_timeout(
+            &delay,
+            Duration::from_millis(1),
+            detection,
+        ));
+        assert!(resource.is_empty());
+
+        let delay = boxed_delay(|_| future::pending());
+        let detection = future::ok(Resource::new(vec![KeyValue::new("cloud.provider", "aws")]));
+        let resource = futures::executor::block_on(detect_with_timeout(
+            &delay,
+            Duration::from_millis(1),
+            detection,
+        ));
+        assert_eq!(resource.len(), 1);
+    }
+}
@@ -1,7 +1,8 @@
 //! This crate provides unofficial integration with AWS services.
 //!
 //! # Components
-//! As for now, the only components provided in this crate is AWS X-Ray propagator.
+//! This crate provides the AWS X-Ray propagator, and resource detectors for
+//! ECS tasks, EKS clusters and EC2 instances behind the `detector` feature.
 //!
 //! ### AWS X-Ray Propagator
 //! This propagator helps propagate tracing information from upstream services to downstream services.
@@ -34,6 +35,22 @@
 //! }
 //! ```
 //! A more detailed example can be found in [opentelemetry-rust](https://github.com/open-telemetry/opentelemetry-rust/tree/main/examples/aws-xray) repo
+//!
+//! ### AWS Resource Detectors
+//! The [`detector`] module provides asynchronous resource detectors querying
+//! the ECS task metadata endpoint, the Kubernetes API of EKS clusters and the
+//! EC2 instance metadata service.
+//!
+//! ### SQS and SNS Message Carriers
+//! The [`messaging`] module propagates trace context through the message
+//! attributes of SQS and SNS messages, falling back to the `AWSTraceHeader`
+//! system attribute set by AWS services.
+#[cfg(feature = "detector")]
+#[cfg_attr(docsrs, doc(cfg(feature = "detector")))]
+pub mod detector;
+#[cfg(feature = "trace")]
+pub mod messaging;
+
 #[cfg(feature = "trace")]
 pub mod trace {
     use opentelemetry::{
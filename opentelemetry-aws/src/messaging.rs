@@ -0,0 +1,280 @@
+//! # SQS and SNS Message Carriers
+//!
+//! Trace context travels with SQS and SNS messages in their message
+//! attributes, e.g. a `traceparent` attribute set by an instrumented
+//! producer. Services publishing to SQS themselves, like SNS or EventBridge,
+//! send the X-Ray trace header in the `AWSTraceHeader` system attribute
+//! instead.
+//!
+//! The carriers adapt the message attribute maps of the AWS SDKs to the
+//! [`Extractor`] and [`Injector`] of the propagators. They are generic over
+//! the attribute value type, so they work with any SDK version, given the
+//! functions reading and creating string values.
+//!
+//! # Examples
+//!
+//! ```
+//! use opentelemetry::{global, sdk::propagation::TraceContextPropagator};
+//! use opentelemetry_aws::messaging::{extract_message_context, MessageAttributesExtractor};
+//! use std::collections::HashMap;
+//!
+//! // stand-in for the SDK's `MessageAttributeValue`
+//! struct MessageAttributeValue(String);
+//!
+//! impl MessageAttributeValue {
+//!     fn string_value(&self) -> Option<&str> {
+//!         Some(&self.0)
+//!     }
+//! }
+//!
+//! global::set_text_map_propagator(TraceContextPropagator::new());
+//!
+//! let mut message_attributes = HashMap::new();
+//! message_attributes.insert(
+//!     "traceparent".to_string(),
+//!     MessageAttributeValue("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".to_string()),
+//! );
+//! let aws_trace_header = None;
+//!
+//! let extractor =
+//!     MessageAttributesExtractor::new(&message_attributes, MessageAttributeValue::string_value);
+//! let parent_cx = extract_message_context(&extractor, aws_trace_header);
+//! ```
+use crate::trace::XrayPropagator;
+use opentelemetry::{
+    global,
+    propagation::{Extractor, Injector, TextMapPropagator},
+    trace::TraceContextExt,
+    Context,
+};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Header of the X-Ray propagator, the name it reads the `AWSTraceHeader`
+/// value with.
+const AWS_XRAY_TRACE_HEADER: &str = "x-amzn-trace-id";
+
+/// Extracts trace context from the message attributes of an SQS or SNS
+/// message.
+///
+/// Attributes are looked up by their exact name and only string attributes
+/// are read.
+pub struct MessageAttributesExtractor<'a, V, F> {
+    attributes: &'a HashMap<String, V>,
+    string_value: F,
+}
+
+impl<'a, V, F> MessageAttributesExtractor<'a, V, F>
+where
+    F: for<'v> Fn(&'v V) -> Option<&'v str>,
+{
+    /// Create an extractor reading the string values of `attributes` with
+    /// `string_value`, e.g. `MessageAttributeValue::string_value` of the SDK.
+    pub fn new(attributes: &'a HashMap<String, V>, string_value: F) -> Self {
+        MessageAttributesExtractor {
+            attributes,
+            string_value,
+        }
+    }
+}
+
+impl<V, F> fmt::Debug for MessageAttributesExtractor<'_, V, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MessageAttributesExtractor")
+            .field("attributes", &self.attributes.keys())
+            .finish()
+    }
+}
+
+impl<V, F> Extractor for MessageAttributesExtractor<'_, V, F>
+where
+    F: for<'v> Fn(&'v V) -> Option<&'v str>,
+{
+    fn get(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .get(key)
+            .and_then(|value| (self.string_value)(value))
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.attributes.keys().map(String::as_str).collect()
+    }
+}
+
+/// Injects trace context into the message attributes of an SQS or SNS
+/// message.
+///
+/// SQS and SNS accept at most 10 message attributes per message, the
+/// propagators in use add one attribute per field they propagate, e.g.
+/// `traceparent` and `tracestate`.
+pub struct MessageAttributesInjector<'a, V, F> {
+    attributes: &'a mut HashMap<String, V>,
+    new_value: F,
+}
+
+impl<'a, V, F> MessageAttributesInjector<'a, V, F>
+where
+    F: Fn(String) -> V,
+{
+    /// Create an injector inserting values created by `new_value` into
+    /// `attributes`, e.g. values of data type `String` built with the SDK's
+    /// `MessageAttributeValue::builder`.
+    pub fn new(attributes: &'a mut HashMap<String, V>, new_value: F) -> Self {
+        MessageAttributesInjector {
+            attributes,
+            new_value,
+        }
+    }
+}
+
+impl<V, F> fmt::Debug for MessageAttributesInjector<'_, V, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MessageAttributesInjector")
+            .field("attributes", &self.attributes.keys())
+            .finish()
+    }
+}
+
+impl<V, F> Injector for MessageAttributesInjector<'_, V, F>
+where
+    F: Fn(String) -> V,
+{
+    fn set(&mut self, key: &str, value: String) {
+        self.attributes
+            .insert(key.to_string(), (self.new_value)(value));
+    }
+}
+
+/// Extracts trace context from the `AWSTraceHeader` system attribute of an
+/// SQS message with the [`XrayPropagator`].
+#[derive(Clone, Copy, Debug)]
+pub struct AwsTraceHeaderExtractor<'a>(pub Option<&'a str>);
+
+impl Extractor for AwsTraceHeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        if key.eq_ignore_ascii_case(AWS_XRAY_TRACE_HEADER) {
+            self.0
+        } else {
+            None
+        }
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.iter().map(|_| AWS_XRAY_TRACE_HEADER).collect()
+    }
+}
+
+/// Extract the parent context of a received message.
+///
+/// The message attributes are read with the global propagator, if they carry
+/// no trace context the `AWSTraceHeader` system attribute is read with the
+/// [`XrayPropagator`].
+pub fn extract_message_context(
+    message_attributes: &dyn Extractor,
+    aws_trace_header: Option<&str>,
+) -> Context {
+    let cx = global::get_text_map_propagator(|propagator| propagator.extract(message_attributes));
+    if has_remote_parent(&cx) || aws_trace_header.is_none() {
+        return cx;
+    }
+
+    let xray_cx = XrayPropagator::new().extract(&AwsTraceHeaderExtractor(aws_trace_header));
+    if has_remote_parent(&xray_cx) {
+        xray_cx
+    } else {
+        cx
+    }
+}
+
+fn has_remote_parent(cx: &Context) -> bool {
+    cx.remote_span_context()
+        .map(|span_context| span_context.is_valid())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::sdk::propagation::TraceContextPropagator;
+    use opentelemetry::testing::trace::TestSpan;
+    use opentelemetry::trace::{SpanContext, SpanId, TraceId, TraceState, TRACE_FLAG_SAMPLED};
+
+    #[derive(Debug, PartialEq)]
+    struct AttributeValue {
+        data_type: &'static str,
+        string_value: Option<String>,
+    }
+
+    impl AttributeValue {
+        fn string(value: String) -> Self {
+            AttributeValue {
+                data_type: "String",
+                string_value: Some(value),
+            }
+        }
+
+        fn string_value(&self) -> Option<&str> {
+            self.string_value.as_deref()
+        }
+    }
+
+    #[test]
+    fn inject_and_extract_message_attributes() {
+        let span_context = SpanContext::new(
+            TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736),
+            SpanId::from_u64(0x00f0_67aa_0ba9_02b7),
+            TRACE_FLAG_SAMPLED,
+            true,
+            TraceState::default(),
+        );
+        let cx = Context::current_with_span(TestSpan(span_context.clone()));
+
+        let mut attributes = HashMap::new();
+        attributes.insert(
+            "binary".to_string(),
+            AttributeValue {
+                data_type: "Binary",
+                string_value: None,
+            },
+        );
+        let propagator = TraceContextPropagator::new();
+        propagator.inject_context(
+            &cx,
+            &mut MessageAttributesInjector::new(&mut attributes, AttributeValue::string),
+        );
+        assert_eq!(
+            attributes.get("traceparent"),
+            Some(&AttributeValue::string(
+                "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".to_string()
+            ))
+        );
+
+        let extractor = MessageAttributesExtractor::new(&attributes, AttributeValue::string_value);
+        assert_eq!(extractor.get("binary"), None);
+        let extracted = propagator.extract(&extractor);
+        assert_eq!(extracted.remote_span_context(), Some(&span_context));
+    }
+
+    #[test]
+    fn fall_back_to_aws_trace_header() {
+        let attributes: HashMap<String, AttributeValue> = HashMap::new();
+        let extractor = MessageAttributesExtractor::new(&attributes, AttributeValue::string_value);
+        let cx = extract_message_context(
+            &extractor,
+            Some("Root=1-58406520-a006649127e371903a2de979;Parent=4c721bf33e3caf8f;Sampled=1"),
+        );
+        assert_eq!(
+            cx.remote_span_context(),
+            Some(&SpanContext::new(
+                TraceId::from_hex("58406520a006649127e371903a2de979"),
+                SpanId::from_hex("4c721bf33e3caf8f"),
+                TRACE_FLAG_SAMPLED,
+                true,
+                TraceState::default(),
+            ))
+        );
+
+        let cx = extract_message_context(&extractor, None);
+        assert!(!has_remote_parent(&cx));
+    }
+}
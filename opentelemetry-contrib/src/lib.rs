@@ -11,6 +11,12 @@
 //!
 //! * `binary-propagator`: Adds Experimental binary propagator to propagate trace context using binary format.
 //! * `base64-format`: Enables base64 format support for binary propagators.
+//! * `azure_detector`: Adds an asynchronous resource detector for Azure virtual machines.
+//! * `gcp_detector`: Adds an asynchronous resource detector for Google Compute Engine instances.
+//! * `zpages`: Adds a span processor and HTTP handlers serving the tracez and
+//!   statusz pages.
+//! * `opamp`: Adds an experimental OpAMP client applying remotely managed
+//!   sampling and span limit settings.
 //! * `datadog`: Adds a Datadog trace exporter.
 //! * `reqwest-blocking-client`: Export spans using the reqwest blocking http
 //!   client.
@@ -31,4 +37,5 @@
 )]
 #![cfg_attr(test, deny(warnings))]
 
+pub mod resource;
 pub mod trace;
@@ -0,0 +1,233 @@
+//! # Azure Resource Detector
+//!
+//! Implementation of `AsyncResourceDetector` to describe an Azure virtual
+//! machine using the instance metadata service.
+//!
+//! ```no_run
+//! use opentelemetry::sdk::resource::AsyncResourceDetector;
+//! use opentelemetry_contrib::resource::azure::AzureVmResourceDetector;
+//! use std::time::Duration;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let detector = AzureVmResourceDetector::new(reqwest::Client::new(), tokio::time::sleep);
+//!     let resource = detector.detect(Duration::from_secs(1)).await;
+//! }
+//! ```
+use futures_util::future::{self, BoxFuture, Either, FutureExt};
+use http::{Method, Request};
+use opentelemetry::sdk::resource::AsyncResourceDetector;
+use opentelemetry::sdk::Resource;
+use opentelemetry::trace::TraceError;
+use opentelemetry::KeyValue;
+use opentelemetry_http::HttpClient;
+use serde::Deserialize;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+const METADATA_ENDPOINT: &str = "http://169.254.169.254";
+const COMPUTE_PATH: &str = "/metadata/instance/compute";
+const API_VERSION: &str = "2021-02-01";
+
+/// Header required by the instance metadata service on every request.
+const METADATA_HEADER: &str = "Metadata";
+
+type Delay = Arc<dyn Fn(Duration) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// Resource detector describing the Azure virtual machine the process is
+/// running on.
+///
+/// Populates `cloud.provider`, `cloud.platform`, `cloud.account.id` (the
+/// subscription id), `cloud.region`, `host.id`, `host.name`, `host.type`,
+/// `os.type`, `os.version` and the `azure.*` attributes. Detection resolves to
+/// an empty resource if the metadata service cannot be reached before the
+/// timeout elapses.
+#[derive(Clone)]
+pub struct AzureVmResourceDetector {
+    client: Arc<dyn HttpClient>,
+    delay: Delay,
+}
+
+impl fmt::Debug for AzureVmResourceDetector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AzureVmResourceDetector")
+            .field("client", &self.client)
+            .finish()
+    }
+}
+
+impl AzureVmResourceDetector {
+    /// Create `AzureVmResourceDetector` instance querying metadata with
+    /// `client`.
+    ///
+    /// `delay` creates a future resolving after the given duration, e.g.
+    /// `tokio::time::sleep`, and is used to enforce the detection timeout.
+    pub fn new<C, D, DS>(client: C, delay: D) -> Self
+    where
+        C: HttpClient + 'static,
+        D: Fn(Duration) -> DS + Send + Sync + 'static,
+        DS: Future<Output = ()> + Send + 'static,
+    {
+        AzureVmResourceDetector {
+            client: Arc::new(client),
+            delay: Arc::new(move |duration| delay(duration).boxed()),
+        }
+    }
+
+    async fn detect_vm(&self) -> Result<Resource, TraceError> {
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri(format!(
+                "{}{}?api-version={}&format=json",
+                METADATA_ENDPOINT, COMPUTE_PATH, API_VERSION
+            ))
+            .header(METADATA_HEADER, "true")
+            .body(Vec::new())
+            .map_err(|err| TraceError::from(err.to_string()))?;
+        let response = self.client.send(request).await?;
+        let compute: ComputeMetadata = serde_json::from_slice(response.body())
+            .map_err(|err| TraceError::from(format!("invalid compute metadata: {}", err)))?;
+
+        let mut attributes = vec![
+            KeyValue::new("cloud.provider", "azure"),
+            KeyValue::new("cloud.platform", "azure_vm"),
+            KeyValue::new("cloud.account.id", compute.subscription_id),
+            KeyValue::new("cloud.region", compute.location),
+            KeyValue::new("host.id", compute.vm_id),
+            KeyValue::new("host.name", compute.name),
+            KeyValue::new("host.type", compute.vm_size),
+            KeyValue::new("os.type", compute.os_type.to_lowercase()),
+            KeyValue::new("os.version", compute.version),
+            KeyValue::new("azure.resourcegroup.name", compute.resource_group_name),
+            KeyValue::new("azure.vm.resource_id", compute.resource_id),
+        ];
+        // only set for virtual machines of a scale set
+        if !compute.vm_scale_set_name.is_empty() {
+            attributes.push(KeyValue::new(
+                "azure.vm.scaleset.name",
+                compute.vm_scale_set_name,
+            ));
+        }
+
+        Ok(Resource::new(attributes))
+    }
+}
+
+impl AsyncResourceDetector for AzureVmResourceDetector {
+    fn detect(&self, timeout: Duration) -> BoxFuture<'_, Resource> {
+        async move {
+            let detection = self.detect_vm();
+            futures_util::pin_mut!(detection);
+            match future::select(detection, (self.delay)(timeout)).await {
+                Either::Left((Ok(resource), _)) => resource,
+                Either::Left((Err(_), _)) | Either::Right(_) => Resource::empty(),
+            }
+        }
+        .boxed()
+    }
+}
+
+/// Subset of the compute metadata response.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ComputeMetadata {
+    location: String,
+    name: String,
+    os_type: String,
+    resource_group_name: String,
+    resource_id: String,
+    subscription_id: String,
+    version: String,
+    vm_id: String,
+    #[serde(default)]
+    vm_scale_set_name: String,
+    vm_size: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use http::Response;
+
+    const COMPUTE_METADATA: &str = r#"{
+        "azEnvironment": "AzurePublicCloud",
+        "location": "westeurope",
+        "name": "examplevmname",
+        "offer": "UbuntuServer",
+        "osType": "Linux",
+        "resourceGroupName": "macikgo-test-may-23",
+        "resourceId": "/subscriptions/xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx/resourceGroups/macikgo-test-may-23/providers/Microsoft.Compute/virtualMachines/examplevmname",
+        "sku": "18.04-LTS",
+        "subscriptionId": "xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx",
+        "version": "18.04.202103250",
+        "vmId": "02aab8a4-74ef-476e-8182-f6d2ba4166a6",
+        "vmScaleSetName": "crpteste9vflji9",
+        "vmSize": "Standard_A3"
+    }"#;
+
+    #[derive(Debug)]
+    struct MetadataService;
+
+    #[async_trait]
+    impl HttpClient for MetadataService {
+        async fn send(&self, request: Request<Vec<u8>>) -> Result<Response<Vec<u8>>, TraceError> {
+            if request.headers().get(METADATA_HEADER).is_none() {
+                return Err("missing metadata header".into());
+            }
+            match request.uri().path() {
+                COMPUTE_PATH => Ok(Response::new(COMPUTE_METADATA.as_bytes().to_vec())),
+                _ => Err("not found".into()),
+            }
+        }
+    }
+
+    #[test]
+    fn detect_azure_vm() {
+        let detector = AzureVmResourceDetector::new(MetadataService, |_| future::pending());
+        let resource = futures::executor::block_on(detector.detect(Duration::from_secs(1)));
+
+        assert_eq!(
+            resource,
+            Resource::new(vec![
+                KeyValue::new("cloud.provider", "azure"),
+                KeyValue::new("cloud.platform", "azure_vm"),
+                KeyValue::new("cloud.account.id", "xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx"),
+                KeyValue::new("cloud.region", "westeurope"),
+                KeyValue::new("host.id", "02aab8a4-74ef-476e-8182-f6d2ba4166a6"),
+                KeyValue::new("host.name", "examplevmname"),
+                KeyValue::new("host.type", "Standard_A3"),
+                KeyValue::new("os.type", "linux"),
+                KeyValue::new("os.version", "18.04.202103250"),
+                KeyValue::new("azure.resourcegroup.name", "macikgo-test-may-23"),
+                KeyValue::new(
+                    "azure.vm.resource_id",
+                    "/subscriptions/xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx/resourceGroups/macikgo-test-may-23/providers/Microsoft.Compute/virtualMachines/examplevmname"
+                ),
+                KeyValue::new("azure.vm.scaleset.name", "crpteste9vflji9"),
+            ])
+        );
+    }
+
+    #[test]
+    fn detect_outside_azure() {
+        #[derive(Debug)]
+        struct Unreachable;
+
+        #[async_trait]
+        impl HttpClient for Unreachable {
+            async fn send(
+                &self,
+                _request: Request<Vec<u8>>,
+            ) -> Result<Response<Vec<u8>>, TraceError> {
+                Err("connection refused".into())
+            }
+        }
+
+        let detector = AzureVmResourceDetector::new(Unreachable, |_| future::pending());
+        let resource = futures::executor::block_on(detector.detect(Duration::from_secs(1)));
+        assert!(resource.is_empty());
+    }
+}
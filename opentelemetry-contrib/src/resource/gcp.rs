@@ -0,0 +1,218 @@
+//! # GCP Resource Detector
+//!
+//! Implementation of `AsyncResourceDetector` to describe a Google Compute
+//! Engine instance using the metadata server.
+//!
+//! ```no_run
+//! use opentelemetry::sdk::resource::AsyncResourceDetector;
+//! use opentelemetry_contrib::resource::gcp::GceResourceDetector;
+//! use std::time::Duration;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let detector = GceResourceDetector::new(reqwest::Client::new(), tokio::time::sleep);
+//!     let resource = detector.detect(Duration::from_secs(1)).await;
+//! }
+//! ```
+use futures_util::future::{self, BoxFuture, Either, FutureExt};
+use http::{Method, Request};
+use opentelemetry::sdk::resource::AsyncResourceDetector;
+use opentelemetry::sdk::Resource;
+use opentelemetry::trace::TraceError;
+use opentelemetry::KeyValue;
+use opentelemetry_http::HttpClient;
+use serde::Deserialize;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+const METADATA_ENDPOINT: &str = "http://metadata.google.internal";
+const INSTANCE_PATH: &str = "/computeMetadata/v1/instance/";
+const PROJECT_ID_PATH: &str = "/computeMetadata/v1/project/project-id";
+
+/// Header required by the metadata server on every request.
+const METADATA_FLAVOR_HEADER: &str = "Metadata-Flavor";
+const METADATA_FLAVOR: &str = "Google";
+
+type Delay = Arc<dyn Fn(Duration) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// Resource detector describing the Compute Engine instance the process is
+/// running on.
+///
+/// Populates `cloud.provider`, `cloud.account.id` (the project id),
+/// `cloud.region`, `cloud.zone`, `host.id`, `host.name` and `host.type`.
+/// Detection resolves to an empty resource if the metadata server cannot be
+/// reached before the timeout elapses.
+#[derive(Clone)]
+pub struct GceResourceDetector {
+    client: Arc<dyn HttpClient>,
+    delay: Delay,
+}
+
+impl fmt::Debug for GceResourceDetector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GceResourceDetector")
+            .field("client", &self.client)
+            .finish()
+    }
+}
+
+impl GceResourceDetector {
+    /// Create `GceResourceDetector` instance querying metadata with `client`.
+    ///
+    /// `delay` creates a future resolving after the given duration, e.g.
+    /// `tokio::time::sleep`, and is used to enforce the detection timeout.
+    pub fn new<C, D, DS>(client: C, delay: D) -> Self
+    where
+        C: HttpClient + 'static,
+        D: Fn(Duration) -> DS + Send + Sync + 'static,
+        DS: Future<Output = ()> + Send + 'static,
+    {
+        GceResourceDetector {
+            client: Arc::new(client),
+            delay: Arc::new(move |duration| delay(duration).boxed()),
+        }
+    }
+
+    async fn detect_instance(&self) -> Result<Resource, TraceError> {
+        let project_id = self.get(PROJECT_ID_PATH).await?;
+        let instance = self
+            .get(&format!("{}?recursive=true", INSTANCE_PATH))
+            .await?;
+        let instance: InstanceMetadata = serde_json::from_str(&instance)
+            .map_err(|err| TraceError::from(format!("invalid instance metadata: {}", err)))?;
+
+        // zones and machine types are reported as `projects/<number>/zones/<zone>`
+        let zone = last_segment(&instance.zone);
+        let mut attributes = vec![
+            KeyValue::new("cloud.provider", "gcp"),
+            KeyValue::new("cloud.account.id", project_id),
+            KeyValue::new("cloud.zone", zone.to_string()),
+            KeyValue::new("host.id", instance.id.to_string()),
+            KeyValue::new("host.name", instance.name),
+            KeyValue::new(
+                "host.type",
+                last_segment(&instance.machine_type).to_string(),
+            ),
+        ];
+        // zones are named `<region>-<zone letter>`, e.g. us-central1-a
+        if let Some(idx) = zone.rfind('-') {
+            attributes.push(KeyValue::new("cloud.region", zone[..idx].to_string()));
+        }
+
+        Ok(Resource::new(attributes))
+    }
+
+    async fn get(&self, path: &str) -> Result<String, TraceError> {
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri(format!("{}{}", METADATA_ENDPOINT, path))
+            .header(METADATA_FLAVOR_HEADER, METADATA_FLAVOR)
+            .body(Vec::new())
+            .map_err(|err| TraceError::from(err.to_string()))?;
+        let response = self.client.send(request).await?;
+        String::from_utf8(response.into_body()).map_err(|err| TraceError::from(err.to_string()))
+    }
+}
+
+impl AsyncResourceDetector for GceResourceDetector {
+    fn detect(&self, timeout: Duration) -> BoxFuture<'_, Resource> {
+        async move {
+            let detection = self.detect_instance();
+            futures_util::pin_mut!(detection);
+            match future::select(detection, (self.delay)(timeout)).await {
+                Either::Left((Ok(resource), _)) => resource,
+                Either::Left((Err(_), _)) | Either::Right(_) => Resource::empty(),
+            }
+        }
+        .boxed()
+    }
+}
+
+fn last_segment(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Subset of the recursive instance metadata response.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InstanceMetadata {
+    id: u64,
+    name: String,
+    machine_type: String,
+    zone: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use http::Response;
+
+    const INSTANCE_METADATA: &str = r#"{
+        "hostname": "instance-1.us-central1-a.c.my-project.internal",
+        "id": 4520031799277581759,
+        "image": "projects/debian-cloud/global/images/debian-10-buster-v20210316",
+        "machineType": "projects/123456789012/machineTypes/e2-medium",
+        "name": "instance-1",
+        "zone": "projects/123456789012/zones/us-central1-a"
+    }"#;
+
+    #[derive(Debug)]
+    struct MetadataServer;
+
+    #[async_trait]
+    impl HttpClient for MetadataServer {
+        async fn send(&self, request: Request<Vec<u8>>) -> Result<Response<Vec<u8>>, TraceError> {
+            if request.headers().get(METADATA_FLAVOR_HEADER).is_none() {
+                return Err("missing metadata flavor header".into());
+            }
+            let body = match request.uri().path() {
+                INSTANCE_PATH => INSTANCE_METADATA,
+                PROJECT_ID_PATH => "my-project",
+                _ => return Err("not found".into()),
+            };
+            Ok(Response::new(body.as_bytes().to_vec()))
+        }
+    }
+
+    #[test]
+    fn detect_gce_instance() {
+        let detector = GceResourceDetector::new(MetadataServer, |_| future::pending());
+        let resource = futures::executor::block_on(detector.detect(Duration::from_secs(1)));
+
+        assert_eq!(
+            resource,
+            Resource::new(vec![
+                KeyValue::new("cloud.provider", "gcp"),
+                KeyValue::new("cloud.account.id", "my-project"),
+                KeyValue::new("cloud.region", "us-central1"),
+                KeyValue::new("cloud.zone", "us-central1-a"),
+                KeyValue::new("host.id", "4520031799277581759"),
+                KeyValue::new("host.name", "instance-1"),
+                KeyValue::new("host.type", "e2-medium"),
+            ])
+        );
+    }
+
+    #[test]
+    fn detect_times_out() {
+        #[derive(Debug)]
+        struct Unreachable;
+
+        #[async_trait]
+        impl HttpClient for Unreachable {
+            async fn send(
+                &self,
+                _request: Request<Vec<u8>>,
+            ) -> Result<Response<Vec<u8>>, TraceError> {
+                future::pending().await
+            }
+        }
+
+        let detector = GceResourceDetector::new(Unreachable, |_| future::ready(()));
+        let resource = futures::executor::block_on(detector.detect(Duration::from_millis(1)));
+        assert!(resource.is_empty());
+    }
+}
@@ -0,0 +1,11 @@
+//! # Opentelemetry resource contrib
+//!
+//! Resource detectors for environments not covered by the SDK or vendor
+//! specific crates.
+
+#[cfg(feature = "azure_detector")]
+#[cfg_attr(docsrs, doc(cfg(feature = "azure_detector")))]
+pub mod azure;
+#[cfg(feature = "gcp_detector")]
+#[cfg_attr(docsrs, doc(cfg(feature = "gcp_detector")))]
+pub mod gcp;
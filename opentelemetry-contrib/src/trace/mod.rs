@@ -1,4 +1,11 @@
 //! # Opentelemetry trace contrib
 //!
 
+#[cfg(feature = "opamp")]
+#[cfg_attr(docsrs, doc(cfg(feature = "opamp")))]
+pub mod opamp;
 pub mod propagator;
+
+#[cfg(feature = "zpages")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zpages")))]
+pub mod zpages;
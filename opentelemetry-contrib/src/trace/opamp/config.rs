@@ -0,0 +1,126 @@
+use opentelemetry::sdk::trace::{ConfigUpdate, Sampler};
+use serde::Deserialize;
+use std::time::Duration;
+
+/// The tracing configuration sent by the server, a JSON document like
+///
+/// ```json
+/// {
+///     "sampler": "parentbased_traceidratio",
+///     "sampler_arg": 0.25,
+///     "max_attributes_per_span": 64,
+///     "scheduled_delay_millis": 10000
+/// }
+/// ```
+///
+/// All fields are optional, settings that are left out keep their current
+/// value. Sampler names are the values of the `OTEL_TRACES_SAMPLER`
+/// environment variable of the specification.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RemoteConfig {
+    sampler: Option<String>,
+    sampler_arg: Option<f64>,
+    max_events_per_span: Option<u32>,
+    max_attributes_per_span: Option<u32>,
+    max_links_per_span: Option<u32>,
+    scheduled_delay_millis: Option<u64>,
+}
+
+impl RemoteConfig {
+    pub(crate) fn parse(body: &[u8]) -> Result<Self, String> {
+        serde_json::from_slice(body).map_err(|err| format!("invalid config: {}", err))
+    }
+
+    /// The update applying this config to a tracer provider.
+    pub(crate) fn to_update(&self) -> Result<ConfigUpdate, String> {
+        let mut update = ConfigUpdate::new();
+        if let Some(sampler) = &self.sampler {
+            update = update.with_default_sampler(self.parse_sampler(sampler)?);
+        } else if self.sampler_arg.is_some() {
+            return Err("sampler_arg requires a sampler".to_string());
+        }
+        if let Some(max_events) = self.max_events_per_span {
+            update = update.with_max_events_per_span(max_events);
+        }
+        if let Some(max_attributes) = self.max_attributes_per_span {
+            update = update.with_max_attributes_per_span(max_attributes);
+        }
+        if let Some(max_links) = self.max_links_per_span {
+            update = update.with_max_links_per_span(max_links);
+        }
+        match self.scheduled_delay_millis {
+            Some(0) => return Err("scheduled_delay_millis must be positive".to_string()),
+            Some(millis) => update = update.with_scheduled_delay(Duration::from_millis(millis)),
+            None => {}
+        }
+        Ok(update)
+    }
+
+    fn parse_sampler(&self, name: &str) -> Result<Sampler, String> {
+        let sampler = match name {
+            "always_on" => Sampler::AlwaysOn,
+            "always_off" => Sampler::AlwaysOff,
+            "traceidratio" => Sampler::TraceIdRatioBased(self.ratio()?),
+            "parentbased_always_on" => Sampler::ParentBased(Box::new(Sampler::AlwaysOn)),
+            "parentbased_always_off" => Sampler::ParentBased(Box::new(Sampler::AlwaysOff)),
+            "parentbased_traceidratio" => {
+                Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(self.ratio()?)))
+            }
+            _ => return Err(format!("unknown sampler {:?}", name)),
+        };
+        if self.sampler_arg.is_some() && !name.ends_with("traceidratio") {
+            return Err(format!("sampler {:?} takes no sampler_arg", name));
+        }
+        Ok(sampler)
+    }
+
+    /// The ratio of ratio based samplers, all traces are sampled by default.
+    fn ratio(&self) -> Result<f64, String> {
+        match self.sampler_arg {
+            Some(ratio) if (0.0..=1.0).contains(&ratio) => Ok(ratio),
+            Some(ratio) => Err(format!("sampler_arg {} is not within [0, 1]", ratio)),
+            None => Ok(1.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_config() {
+        let config = RemoteConfig::parse(
+            br#"{"sampler": "parentbased_traceidratio", "sampler_arg": 0.5, "max_links_per_span": 4}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config,
+            RemoteConfig {
+                sampler: Some("parentbased_traceidratio".to_string()),
+                sampler_arg: Some(0.5),
+                max_links_per_span: Some(4),
+                ..Default::default()
+            }
+        );
+        assert!(config.to_update().is_ok());
+        assert_eq!(RemoteConfig::parse(b"{}"), Ok(RemoteConfig::default()));
+    }
+
+    #[test]
+    fn reject_invalid_config() {
+        for body in &[
+            r#"{"sampler": "sometimes"}"#,
+            r#"{"sampler": "traceidratio", "sampler_arg": 2}"#,
+            r#"{"sampler": "always_on", "sampler_arg": 0.5}"#,
+            r#"{"sampler_arg": 0.5}"#,
+            r#"{"scheduled_delay_millis": 0}"#,
+        ] {
+            let config = RemoteConfig::parse(body.as_bytes()).unwrap();
+            assert!(config.to_update().is_err(), "{}", body);
+        }
+        assert!(RemoteConfig::parse(br#"{"exporter": "otlp"}"#).is_err());
+        assert!(RemoteConfig::parse(b"sampler: always_on").is_err());
+    }
+}
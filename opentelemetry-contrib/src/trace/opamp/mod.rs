@@ -0,0 +1,481 @@
+//! # Experimental OpAMP client
+//!
+//! Connects a tracer provider to an [OpAMP] server, so the sampler, the span
+//! limits and the schedule of batch processors can be tuned for a whole
+//! fleet from one place. The client polls the server over the plain HTTP
+//! transport of the protocol, applies the remote configuration it receives
+//! with [`TracerProvider::update_config`] and reports whether it was
+//! applied. The WebSocket transport is not supported.
+//!
+//! The server is expected to send a single JSON config file, or one file
+//! named by [`OpampClient::with_config_name`], like
+//!
+//! ```json
+//! {
+//!     "sampler": "parentbased_traceidratio",
+//!     "sampler_arg": 0.25,
+//!     "max_events_per_span": 64,
+//!     "max_attributes_per_span": 64,
+//!     "max_links_per_span": 64,
+//!     "scheduled_delay_millis": 10000
+//! }
+//! ```
+//!
+//! All fields are optional. The sampler names are the values of the
+//! `OTEL_TRACES_SAMPLER` environment variable, and the ratio of ratio based
+//! samplers is passed as `sampler_arg`. Exporters can't be changed remotely.
+//!
+//! This module is experimental, the protocol is still in development and
+//! breaking changes may be made in any release.
+//!
+//! ```no_run
+//! use opentelemetry::sdk::trace::TracerProvider;
+//! use opentelemetry_contrib::trace::opamp::OpampClient;
+//! use std::time::Duration;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let provider = TracerProvider::default();
+//!     let client = OpampClient::new(
+//!         &provider,
+//!         "http://localhost:4320/v1/opamp",
+//!         reqwest::Client::new(),
+//!     );
+//!     tokio::spawn(async move {
+//!         client.run(Duration::from_secs(30), tokio::time::sleep).await
+//!     });
+//! }
+//! ```
+//!
+//! [OpAMP]: https://github.com/open-telemetry/opamp-spec
+use http::header::CONTENT_TYPE;
+use http::{Method, Request};
+use opentelemetry::sdk::trace::{Tracer, TracerProvider};
+use opentelemetry::trace::{TraceError, TracerProvider as _};
+use opentelemetry::{global, Value};
+use opentelemetry_http::HttpClient;
+use prost::Message;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+mod config;
+mod proto;
+
+use config::RemoteConfig;
+use proto::{
+    any_value, AgentDescription, AgentRemoteConfig, AgentToServer, AnyValue, KeyValue,
+    RemoteConfigStatus, RemoteConfigStatuses, ServerToAgent,
+};
+
+const PROTOBUF_CONTENT_TYPE: &str = "application/x-protobuf";
+
+/// Resource attributes identifying the agent, the others describe it.
+const IDENTIFYING_ATTRIBUTES: [&str; 4] = [
+    "service.name",
+    "service.namespace",
+    "service.version",
+    "service.instance.id",
+];
+
+/// Client applying the configuration of an OpAMP server to a tracer
+/// provider.
+///
+/// The client does not keep the provider alive, once the provider is dropped
+/// polls fail and [`OpampClient::run`] returns.
+pub struct OpampClient {
+    client: Arc<dyn HttpClient>,
+    endpoint: String,
+    config_name: Option<String>,
+    tracer: Tracer,
+    instance_uid: Vec<u8>,
+    state: Mutex<State>,
+}
+
+impl fmt::Debug for OpampClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OpampClient")
+            .field("client", &self.client)
+            .field("endpoint", &self.endpoint)
+            .field("config_name", &self.config_name)
+            .finish()
+    }
+}
+
+/// What was reported to the server and what is left to report.
+#[derive(Debug)]
+struct State {
+    sequence_num: u64,
+    /// Whether the next message describes the agent, which the server asks
+    /// for e.g. after it restarted.
+    report_full_state: bool,
+    /// The status of the last remote config, if it was not reported yet.
+    pending_status: Option<RemoteConfigStatus>,
+    /// The status of the last remote config, reported again with the full
+    /// state.
+    status: Option<RemoteConfigStatus>,
+}
+
+impl OpampClient {
+    /// Create a client applying the configuration served at `endpoint` to
+    /// `provider`, talking to the server with `client`.
+    ///
+    /// The instance uid identifying the process to the server is a random
+    /// UUID taken from the id generator of the provider.
+    pub fn new<T, C>(provider: &TracerProvider, endpoint: T, client: C) -> Self
+    where
+        T: Into<String>,
+        C: HttpClient + 'static,
+    {
+        let mut instance_uid = provider
+            .config()
+            .id_generator
+            .new_trace_id()
+            .to_byte_array();
+        // version 4 and variant bits of a random UUID
+        instance_uid[6] = (instance_uid[6] & 0x0f) | 0x40;
+        instance_uid[8] = (instance_uid[8] & 0x3f) | 0x80;
+
+        OpampClient {
+            client: Arc::new(client),
+            endpoint: endpoint.into(),
+            config_name: None,
+            tracer: provider.get_tracer("opentelemetry-contrib/opamp", None),
+            instance_uid: instance_uid.to_vec(),
+            state: Mutex::new(State {
+                sequence_num: 0,
+                report_full_state: true,
+                pending_status: None,
+                status: None,
+            }),
+        }
+    }
+
+    /// Apply the config file named `name`, for servers sending several files.
+    pub fn with_config_name<T: Into<String>>(mut self, name: T) -> Self {
+        self.config_name = Some(name.into());
+        self
+    }
+
+    /// Poll the server every `interval` until the provider is dropped.
+    ///
+    /// `delay` creates a future resolving after the given duration, e.g.
+    /// `tokio::time::sleep`. Failed polls are reported to the global error
+    /// handler and retried after the interval.
+    pub async fn run<D, DS>(&self, interval: Duration, delay: D)
+    where
+        D: Fn(Duration) -> DS,
+        DS: Future<Output = ()>,
+    {
+        while self.tracer.provider().is_some() {
+            match self.poll().await {
+                // report the status of the new config right away
+                Ok(true) => continue,
+                Ok(false) => {}
+                Err(err) => global::handle_error(err),
+            }
+            delay(interval).await;
+        }
+    }
+
+    /// Send the status of the provider to the server once and apply the
+    /// configuration it replies with.
+    ///
+    /// Resolves to whether the server sent a configuration that was not seen
+    /// before. Errors applying the configuration are reported to the server
+    /// with the next poll rather than returned.
+    pub async fn poll(&self) -> Result<bool, TraceError> {
+        // the provider is not kept alive while waiting for the server
+        let message = self.next_message(&self.provider()?);
+        let mut body = Vec::with_capacity(message.encoded_len());
+        message
+            .encode(&mut body)
+            .map_err(|err| TraceError::from(err.to_string()))?;
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(&self.endpoint)
+            .header(CONTENT_TYPE, PROTOBUF_CONTENT_TYPE)
+            .body(body)
+            .map_err(|err| TraceError::from(err.to_string()))?;
+        let response = self.client.send(request).await?;
+        let response = ServerToAgent::decode(response.body().as_slice())
+            .map_err(|err| TraceError::from(format!("invalid OpAMP response: {}", err)))?;
+
+        let provider = self.provider()?;
+        let mut state = self.lock();
+        // the status was delivered, unless the server lost track of us
+        if message.remote_config_status == state.pending_status {
+            state.pending_status = None;
+        }
+        state.report_full_state = response.flags & proto::REPORT_FULL_STATE != 0;
+        if let Some(error) = response.error_response {
+            return Err(TraceError::from(format!(
+                "OpAMP server error: {}",
+                error.error_message
+            )));
+        }
+
+        let remote_config = match response.remote_config {
+            Some(remote_config) => remote_config,
+            None => return Ok(false),
+        };
+        let last_hash = state
+            .status
+            .as_ref()
+            .map(|status| status.last_remote_config_hash.as_slice());
+        if last_hash == Some(remote_config.config_hash.as_slice()) {
+            return Ok(false);
+        }
+        let status = match self.apply(&provider, &remote_config) {
+            Ok(()) => RemoteConfigStatus {
+                last_remote_config_hash: remote_config.config_hash,
+                status: RemoteConfigStatuses::Applied as i32,
+                error_message: String::new(),
+            },
+            Err(error_message) => RemoteConfigStatus {
+                last_remote_config_hash: remote_config.config_hash,
+                status: RemoteConfigStatuses::Failed as i32,
+                error_message,
+            },
+        };
+        state.pending_status = Some(status.clone());
+        state.status = Some(status);
+        Ok(true)
+    }
+
+    fn provider(&self) -> Result<TracerProvider, TraceError> {
+        self.tracer
+            .provider()
+            .ok_or_else(|| TraceError::from("the tracer provider was dropped"))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn next_message(&self, provider: &TracerProvider) -> AgentToServer {
+        let mut state = self.lock();
+        state.sequence_num += 1;
+        let (agent_description, remote_config_status) = if state.report_full_state {
+            (Some(agent_description(provider)), state.status.clone())
+        } else {
+            (None, state.pending_status.clone())
+        };
+        AgentToServer {
+            instance_uid: self.instance_uid.clone(),
+            sequence_num: state.sequence_num,
+            agent_description,
+            capabilities: proto::REPORTS_STATUS
+                | proto::ACCEPTS_REMOTE_CONFIG
+                | proto::REPORTS_REMOTE_CONFIG,
+            remote_config_status,
+            flags: 0,
+        }
+    }
+
+    fn apply(
+        &self,
+        provider: &TracerProvider,
+        remote_config: &AgentRemoteConfig,
+    ) -> Result<(), String> {
+        let files = match &remote_config.config {
+            Some(config) if !config.config_map.is_empty() => &config.config_map,
+            // nothing to change
+            _ => return Ok(()),
+        };
+        let file = match &self.config_name {
+            Some(name) => files
+                .get(name)
+                .ok_or_else(|| format!("the config has no file named {:?}", name))?,
+            None if files.len() == 1 => files.values().next().expect("one file"),
+            None => {
+                return Err(format!(
+                    "expected a single config file, got {}",
+                    files.len()
+                ))
+            }
+        };
+        match file.content_type.as_str() {
+            "" | "application/json" | "text/json" => {}
+            content_type => return Err(format!("unsupported content type {:?}", content_type)),
+        }
+
+        let update = RemoteConfig::parse(&file.body)?.to_update()?;
+        provider.update_config(update);
+        Ok(())
+    }
+}
+
+/// Describe the agent with the attributes of the provider's resource.
+fn agent_description(provider: &TracerProvider) -> AgentDescription {
+    let mut description = AgentDescription::default();
+    for (key, value) in provider.resource().iter() {
+        let attribute = KeyValue {
+            key: key.as_str().to_string(),
+            value: Some(AnyValue {
+                value: Some(match value {
+                    Value::Bool(value) => any_value::Value::BoolValue(*value),
+                    Value::I64(value) => any_value::Value::IntValue(*value),
+                    Value::F64(value) => any_value::Value::DoubleValue(*value),
+                    value => any_value::Value::StringValue(value.as_str().into_owned()),
+                }),
+            }),
+        };
+        if IDENTIFYING_ATTRIBUTES.contains(&key.as_str()) {
+            description.identifying_attributes.push(attribute);
+        } else {
+            description.non_identifying_attributes.push(attribute);
+        }
+    }
+    description
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use http::Response;
+    use opentelemetry::sdk::trace::{Config, Sampler};
+    use opentelemetry::sdk::Resource;
+    use std::collections::HashMap;
+
+    /// Replies with the queued responses and records the requests.
+    #[derive(Clone, Debug, Default)]
+    struct FakeServer {
+        requests: Arc<Mutex<Vec<AgentToServer>>>,
+        responses: Arc<Mutex<Vec<ServerToAgent>>>,
+    }
+
+    #[async_trait]
+    impl HttpClient for FakeServer {
+        async fn send(&self, request: Request<Vec<u8>>) -> Result<Response<Vec<u8>>, TraceError> {
+            assert_eq!(request.headers()[CONTENT_TYPE], PROTOBUF_CONTENT_TYPE);
+            let message = AgentToServer::decode(request.body().as_slice()).unwrap();
+            self.requests.lock().unwrap().push(message);
+            let mut responses = self.responses.lock().unwrap();
+            let response = if responses.is_empty() {
+                ServerToAgent::default()
+            } else {
+                responses.remove(0)
+            };
+            let mut body = Vec::new();
+            response.encode(&mut body).unwrap();
+            Ok(Response::new(body))
+        }
+    }
+
+    fn remote_config(hash: &[u8], body: &str) -> ServerToAgent {
+        let mut config_map = HashMap::new();
+        config_map.insert(
+            "tracing".to_string(),
+            proto::AgentConfigFile {
+                body: body.as_bytes().to_vec(),
+                content_type: "application/json".to_string(),
+            },
+        );
+        ServerToAgent {
+            remote_config: Some(AgentRemoteConfig {
+                config: Some(proto::AgentConfigMap { config_map }),
+                config_hash: hash.to_vec(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn poll(client: &OpampClient) -> bool {
+        futures::executor::block_on(client.poll()).unwrap()
+    }
+
+    #[test]
+    fn applies_remote_config() {
+        let provider = TracerProvider::builder()
+            .with_config(
+                Config::default()
+                    .with_default_sampler(Sampler::AlwaysOn)
+                    .with_resource(Resource::new(vec![
+                        opentelemetry::KeyValue::new("service.name", "checkout"),
+                        opentelemetry::KeyValue::new("host.name", "web-1"),
+                    ])),
+            )
+            .build();
+        let server = FakeServer::default();
+        server.responses.lock().unwrap().extend(vec![
+            remote_config(
+                b"1",
+                r#"{"sampler": "always_off", "max_events_per_span": 4}"#,
+            ),
+            remote_config(
+                b"1",
+                r#"{"sampler": "always_off", "max_events_per_span": 4}"#,
+            ),
+            remote_config(b"2", r#"{"sampler": "sometimes"}"#),
+        ]);
+        let client = OpampClient::new(&provider, "http://localhost:4320/v1/opamp", server.clone());
+
+        assert!(poll(&client));
+        assert_eq!(provider.diagnostics().sampler, "AlwaysOff");
+        // the same config is applied once only
+        assert!(!poll(&client));
+        assert!(poll(&client));
+        assert!(!poll(&client));
+        assert_eq!(provider.diagnostics().sampler, "AlwaysOff");
+
+        let requests = server.requests.lock().unwrap();
+        let sequence: Vec<_> = requests.iter().map(|r| r.sequence_num).collect();
+        assert_eq!(sequence, vec![1, 2, 3, 4]);
+        assert_eq!(requests[0].instance_uid.len(), 16);
+        assert_eq!(requests[0].instance_uid[6] >> 4, 4);
+        assert!(requests
+            .iter()
+            .all(|r| r.instance_uid == requests[0].instance_uid));
+
+        let description = requests[0].agent_description.as_ref().unwrap();
+        assert_eq!(description.identifying_attributes.len(), 1);
+        assert_eq!(description.identifying_attributes[0].key, "service.name");
+        assert_eq!(description.non_identifying_attributes.len(), 1);
+        assert!(requests[1].agent_description.is_none());
+
+        assert_eq!(requests[0].remote_config_status, None);
+        let applied = requests[1].remote_config_status.as_ref().unwrap();
+        assert_eq!(applied.status, RemoteConfigStatuses::Applied as i32);
+        assert_eq!(applied.last_remote_config_hash, b"1");
+        // the status is only reported once
+        assert_eq!(requests[2].remote_config_status, None);
+        let failed = requests[3].remote_config_status.as_ref().unwrap();
+        assert_eq!(failed.status, RemoteConfigStatuses::Failed as i32);
+        assert_eq!(failed.error_message, "unknown sampler \"sometimes\"");
+    }
+
+    #[test]
+    fn reports_full_state_on_request() {
+        let provider = TracerProvider::default();
+        let server = FakeServer::default();
+        server.responses.lock().unwrap().extend(vec![
+            remote_config(b"1", "{}"),
+            ServerToAgent {
+                flags: proto::REPORT_FULL_STATE,
+                ..Default::default()
+            },
+        ]);
+        let client = OpampClient::new(&provider, "http://localhost:4320/v1/opamp", server.clone())
+            .with_config_name("logging");
+
+        assert!(poll(&client));
+        assert!(!poll(&client));
+        assert!(!poll(&client));
+
+        let requests = server.requests.lock().unwrap();
+        let full_state = &requests[2];
+        assert!(full_state.agent_description.is_some());
+        let status = full_state.remote_config_status.as_ref().unwrap();
+        assert_eq!(status.status, RemoteConfigStatuses::Failed as i32);
+        assert_eq!(
+            status.error_message,
+            "the config has no file named \"logging\""
+        );
+
+        drop(provider);
+        assert!(futures::executor::block_on(client.poll()).is_err());
+    }
+}
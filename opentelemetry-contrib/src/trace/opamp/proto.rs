@@ -0,0 +1,132 @@
+//! The subset of the OpAMP protocol messages used by the client, see
+//! [opamp.proto].
+//!
+//! The messages are defined by hand rather than generated, so building the
+//! crate does not need `protoc`. Field tags must match the specification.
+//!
+//! [opamp.proto]: https://github.com/open-telemetry/opamp-spec/blob/main/proto/opamp.proto
+#![allow(clippy::enum_variant_names)]
+
+use std::collections::HashMap;
+
+/// Capabilities announced by the client, see `AgentCapabilities`.
+pub(crate) const REPORTS_STATUS: u64 = 0x1;
+pub(crate) const ACCEPTS_REMOTE_CONFIG: u64 = 0x2;
+pub(crate) const REPORTS_REMOTE_CONFIG: u64 = 0x1000;
+
+/// The server asks for the full state of the agent, see `ServerToAgentFlags`.
+pub(crate) const REPORT_FULL_STATE: u64 = 0x1;
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct AgentToServer {
+    #[prost(bytes = "vec", tag = "1")]
+    pub(crate) instance_uid: Vec<u8>,
+    #[prost(uint64, tag = "2")]
+    pub(crate) sequence_num: u64,
+    #[prost(message, optional, tag = "3")]
+    pub(crate) agent_description: Option<AgentDescription>,
+    #[prost(uint64, tag = "4")]
+    pub(crate) capabilities: u64,
+    #[prost(message, optional, tag = "7")]
+    pub(crate) remote_config_status: Option<RemoteConfigStatus>,
+    #[prost(uint64, tag = "10")]
+    pub(crate) flags: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct ServerToAgent {
+    #[prost(bytes = "vec", tag = "1")]
+    pub(crate) instance_uid: Vec<u8>,
+    #[prost(message, optional, tag = "2")]
+    pub(crate) error_response: Option<ServerErrorResponse>,
+    #[prost(message, optional, tag = "3")]
+    pub(crate) remote_config: Option<AgentRemoteConfig>,
+    #[prost(uint64, tag = "6")]
+    pub(crate) flags: u64,
+    #[prost(uint64, tag = "7")]
+    pub(crate) capabilities: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct ServerErrorResponse {
+    #[prost(int32, tag = "1")]
+    pub(crate) r#type: i32,
+    #[prost(string, tag = "2")]
+    pub(crate) error_message: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct AgentRemoteConfig {
+    #[prost(message, optional, tag = "1")]
+    pub(crate) config: Option<AgentConfigMap>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub(crate) config_hash: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct AgentConfigMap {
+    #[prost(map = "string, message", tag = "1")]
+    pub(crate) config_map: HashMap<String, AgentConfigFile>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct AgentConfigFile {
+    #[prost(bytes = "vec", tag = "1")]
+    pub(crate) body: Vec<u8>,
+    #[prost(string, tag = "2")]
+    pub(crate) content_type: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct RemoteConfigStatus {
+    #[prost(bytes = "vec", tag = "1")]
+    pub(crate) last_remote_config_hash: Vec<u8>,
+    #[prost(enumeration = "RemoteConfigStatuses", tag = "2")]
+    pub(crate) status: i32,
+    #[prost(string, tag = "3")]
+    pub(crate) error_message: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+pub(crate) enum RemoteConfigStatuses {
+    Unset = 0,
+    Applied = 1,
+    Applying = 2,
+    Failed = 3,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct AgentDescription {
+    #[prost(message, repeated, tag = "1")]
+    pub(crate) identifying_attributes: Vec<KeyValue>,
+    #[prost(message, repeated, tag = "2")]
+    pub(crate) non_identifying_attributes: Vec<KeyValue>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct KeyValue {
+    #[prost(string, tag = "1")]
+    pub(crate) key: String,
+    #[prost(message, optional, tag = "2")]
+    pub(crate) value: Option<AnyValue>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct AnyValue {
+    #[prost(oneof = "any_value::Value", tags = "1, 2, 3, 4")]
+    pub(crate) value: Option<any_value::Value>,
+}
+
+pub(crate) mod any_value {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub(crate) enum Value {
+        #[prost(string, tag = "1")]
+        StringValue(String),
+        #[prost(bool, tag = "2")]
+        BoolValue(bool),
+        #[prost(int64, tag = "3")]
+        IntValue(i64),
+        #[prost(double, tag = "4")]
+        DoubleValue(f64),
+    }
+}
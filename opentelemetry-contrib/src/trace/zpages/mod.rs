@@ -0,0 +1,39 @@
+//! # zPages
+//!
+//! In-process pages showing the spans of the process without any backend,
+//! e.g. to debug sampling and latency.
+//!
+//! The [`ZPagesSpanProcessor`] aggregates the spans by name into the running
+//! spans, the ended spans per latency bucket and the spans that ended with
+//! an error, keeping a few recent spans of each as samples. Its [`Tracez`]
+//! handle answers [`http`] requests for the tracez page with HTML or JSON,
+//! so it can be served with any HTTP server or framework.
+//!
+//! The [`Statusz`] handler serves the self-diagnostics of a tracer provider
+//! on the statusz page: the outcome of recent exports, the queues of the span
+//! processors, the sampler and the resource.
+//!
+//! ```
+//! use opentelemetry::sdk::trace::TracerProvider;
+//! use opentelemetry::trace::{Tracer, TracerProvider as _};
+//! use opentelemetry_contrib::trace::zpages::ZPagesSpanProcessor;
+//!
+//! let processor = ZPagesSpanProcessor::new();
+//! let tracez = processor.tracez();
+//! let provider = TracerProvider::builder()
+//!     .with_span_processor(processor)
+//!     .build();
+//!
+//! provider.get_tracer("example", None).in_span("work", |_cx| {});
+//!
+//! let request = http::Request::get("/debug/tracez").body(()).unwrap();
+//! let response = tracez.handle(&request);
+//! assert!(String::from_utf8_lossy(response.body()).contains("work"));
+//! ```
+mod processor;
+mod statusz;
+mod tracez;
+
+pub use processor::{SampleKind, SpanSummary, ZPagesSpanProcessor};
+pub use statusz::Statusz;
+pub use tracez::Tracez;
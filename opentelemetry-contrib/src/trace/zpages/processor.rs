@@ -0,0 +1,414 @@
+use super::tracez::Tracez;
+use opentelemetry::sdk::export::trace::SpanData;
+use opentelemetry::sdk::trace::{Span, SpanProcessor};
+use opentelemetry::trace::{SpanId, StatusCode, TraceId, TraceResult};
+use opentelemetry::Context;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+/// The number of spans kept per latency bucket and for errors by default.
+const DEFAULT_MAX_SAMPLES: usize = 5;
+
+/// Upper bounds of the latency buckets by default.
+const DEFAULT_LATENCY_BOUNDS: [Duration; 8] = [
+    Duration::from_micros(10),
+    Duration::from_micros(100),
+    Duration::from_millis(1),
+    Duration::from_millis(10),
+    Duration::from_millis(100),
+    Duration::from_secs(1),
+    Duration::from_secs(10),
+    Duration::from_secs(100),
+];
+
+/// Span processor aggregating spans by name for the [tracez] page.
+///
+/// Keeps a snapshot of every running span taken when it starts, counts ended
+/// spans by latency and errors, and keeps the most recent spans of each
+/// latency bucket and of the errors as samples. Spans that ended with an
+/// error status are counted as errors only.
+///
+/// The aggregated spans are served by the [`Tracez`] handle of the
+/// processor, which stays usable after the processor was moved into a
+/// tracer provider.
+///
+/// [tracez]: super
+#[derive(Debug)]
+pub struct ZPagesSpanProcessor {
+    store: Arc<Store>,
+}
+
+impl Default for ZPagesSpanProcessor {
+    fn default() -> Self {
+        ZPagesSpanProcessor::with_max_samples(DEFAULT_MAX_SAMPLES)
+    }
+}
+
+impl ZPagesSpanProcessor {
+    /// Create a processor keeping 5 samples per latency bucket and for errors.
+    pub fn new() -> Self {
+        ZPagesSpanProcessor::default()
+    }
+
+    /// Create a processor keeping `max_samples` samples per latency bucket
+    /// and for errors of each span name.
+    pub fn with_max_samples(max_samples: usize) -> Self {
+        ZPagesSpanProcessor {
+            store: Arc::new(Store {
+                max_samples,
+                inner: Mutex::default(),
+            }),
+        }
+    }
+
+    /// Set the upper bounds of the latency buckets.
+    ///
+    /// Ended spans are counted in the bucket of the first bound they are
+    /// shorter than, the last bucket holds all longer spans. The bounds are
+    /// sorted and duplicates removed, spans aggregated so far are dropped.
+    /// Defaults to powers of ten from 10µs to 100s.
+    pub fn with_latency_bounds(self, mut bounds: Vec<Duration>) -> Self {
+        bounds.sort();
+        bounds.dedup();
+        let mut inner = self.store.lock();
+        inner.ended.clear();
+        inner.latency_bounds = bounds;
+        drop(inner);
+        self
+    }
+
+    /// The handle serving the spans aggregated by this processor.
+    pub fn tracez(&self) -> Tracez {
+        Tracez::new(self.store.clone())
+    }
+}
+
+impl SpanProcessor for ZPagesSpanProcessor {
+    fn on_start(&self, span: &Span, _cx: &Context) {
+        if let Some(data) = span.exported_data() {
+            self.store.start(data);
+        }
+    }
+
+    fn on_end(&self, span: Arc<SpanData>) {
+        self.store.end(span);
+    }
+
+    fn force_flush(&self) -> TraceResult<()> {
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> TraceResult<()> {
+        Ok(())
+    }
+}
+
+/// Which spans of a name to list.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SampleKind {
+    /// All running spans.
+    Running,
+    /// The samples of the latency bucket with the given index, see
+    /// [`Tracez::latency_bounds`].
+    Latency(usize),
+    /// The samples of spans that ended with an error status.
+    Error,
+}
+
+/// The spans seen with one name.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SpanSummary {
+    /// The name of the spans.
+    pub name: String,
+    /// The number of spans still running.
+    pub running: usize,
+    /// The number of spans that ended successfully per latency bucket.
+    pub latency: Vec<u64>,
+    /// The number of spans that ended with an error status.
+    pub errors: u64,
+}
+
+/// The spans aggregated by a processor, shared with its [`Tracez`] handles.
+#[derive(Debug)]
+pub(crate) struct Store {
+    max_samples: usize,
+    inner: Mutex<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    latency_bounds: Vec<Duration>,
+    running: HashMap<(TraceId, SpanId), Arc<SpanData>>,
+    ended: HashMap<String, Ended>,
+}
+
+impl Default for Inner {
+    fn default() -> Self {
+        Inner {
+            latency_bounds: DEFAULT_LATENCY_BOUNDS.to_vec(),
+            running: HashMap::new(),
+            ended: HashMap::new(),
+        }
+    }
+}
+
+/// The spans that ended with one name.
+#[derive(Debug)]
+struct Ended {
+    latency: Vec<u64>,
+    latency_samples: Vec<VecDeque<Arc<SpanData>>>,
+    errors: u64,
+    error_samples: VecDeque<Arc<SpanData>>,
+}
+
+impl Ended {
+    fn new(buckets: usize) -> Self {
+        Ended {
+            latency: vec![0; buckets],
+            latency_samples: vec![VecDeque::new(); buckets],
+            errors: 0,
+            error_samples: VecDeque::new(),
+        }
+    }
+}
+
+impl Store {
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn start(&self, span: SpanData) {
+        let key = (span.span_context.trace_id(), span.span_context.span_id());
+        self.lock().running.insert(key, Arc::new(span));
+    }
+
+    fn end(&self, span: Arc<SpanData>) {
+        let key = (span.span_context.trace_id(), span.span_context.span_id());
+        let mut inner = self.lock();
+        inner.running.remove(&key);
+
+        let inner = &mut *inner;
+        let buckets = inner.latency_bounds.len() + 1;
+        if !inner.ended.contains_key(span.name.as_ref()) {
+            inner
+                .ended
+                .insert(span.name.to_string(), Ended::new(buckets));
+        }
+        let ended = inner
+            .ended
+            .get_mut(span.name.as_ref())
+            .expect("inserted above");
+        let samples = if span.status_code == StatusCode::Error {
+            ended.errors += 1;
+            &mut ended.error_samples
+        } else {
+            let bucket = latency_bucket(&inner.latency_bounds, span.duration());
+            ended.latency[bucket] += 1;
+            &mut ended.latency_samples[bucket]
+        };
+        if self.max_samples > 0 {
+            if samples.len() == self.max_samples {
+                samples.pop_front();
+            }
+            samples.push_back(span);
+        }
+    }
+
+    /// The upper bounds of the latency buckets.
+    pub(crate) fn latency_bounds(&self) -> Vec<Duration> {
+        self.lock().latency_bounds.clone()
+    }
+
+    /// The spans seen so far, ordered by name.
+    pub(crate) fn summaries(&self) -> Vec<SpanSummary> {
+        let inner = self.lock();
+        let buckets = inner.latency_bounds.len() + 1;
+        let mut summaries: BTreeMap<&str, SpanSummary> = BTreeMap::new();
+        for span in inner.running.values() {
+            summary(&mut summaries, &span.name, buckets).running += 1;
+        }
+        for (name, ended) in &inner.ended {
+            let summary = summary(&mut summaries, name, buckets);
+            summary.latency = ended.latency.clone();
+            summary.errors = ended.errors;
+        }
+
+        summaries.into_iter().map(|entry| entry.1).collect()
+    }
+
+    /// The running spans or samples of spans named `name`, oldest first.
+    pub(crate) fn samples(&self, name: &str, kind: SampleKind) -> Vec<Arc<SpanData>> {
+        let inner = self.lock();
+        let ended = inner.ended.get(name);
+        let mut samples: Vec<Arc<SpanData>> = match kind {
+            SampleKind::Running => inner
+                .running
+                .values()
+                .filter(|span| span.name == name)
+                .cloned()
+                .collect(),
+            SampleKind::Latency(bucket) => ended
+                .and_then(|ended| ended.latency_samples.get(bucket))
+                .map(|samples| samples.iter().cloned().collect())
+                .unwrap_or_default(),
+            SampleKind::Error => ended
+                .map(|ended| ended.error_samples.iter().cloned().collect())
+                .unwrap_or_default(),
+        };
+        if kind == SampleKind::Running {
+            samples.sort_by_key(|span| span.start_time);
+        }
+        samples
+    }
+}
+
+fn summary<'m, 'a>(
+    summaries: &'m mut BTreeMap<&'a str, SpanSummary>,
+    name: &'a str,
+    buckets: usize,
+) -> &'m mut SpanSummary {
+    summaries.entry(name).or_insert_with(|| SpanSummary {
+        name: name.to_string(),
+        running: 0,
+        latency: vec![0; buckets],
+        errors: 0,
+    })
+}
+
+/// The index of the latency bucket of a span with the given duration.
+fn latency_bucket(bounds: &[Duration], duration: Duration) -> usize {
+    bounds
+        .iter()
+        .position(|bound| duration < *bound)
+        .unwrap_or(bounds.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::sdk::trace::TracerProvider;
+    use opentelemetry::trace::{Span as _, Tracer as _, TracerProvider as _};
+    use std::time::SystemTime;
+
+    fn provider(processor: ZPagesSpanProcessor) -> TracerProvider {
+        TracerProvider::builder()
+            .with_span_processor(processor)
+            .build()
+    }
+
+    /// End a span started at `start` after `duration`.
+    fn record(provider: &TracerProvider, name: &'static str, duration: Duration, error: bool) {
+        let tracer = provider.get_tracer("zpages", None);
+        let start = SystemTime::UNIX_EPOCH;
+        let span = tracer
+            .span_builder(name)
+            .with_start_time(start)
+            .start(&tracer);
+        if error {
+            span.set_status(StatusCode::Error, "failed".to_string());
+        }
+        span.end_with_timestamp(start + duration);
+    }
+
+    #[test]
+    fn latency_buckets() {
+        let bounds = &DEFAULT_LATENCY_BOUNDS;
+        assert_eq!(latency_bucket(bounds, Duration::from_secs(0)), 0);
+        assert_eq!(latency_bucket(bounds, Duration::from_micros(10)), 1);
+        assert_eq!(latency_bucket(bounds, Duration::from_millis(5)), 3);
+        assert_eq!(latency_bucket(bounds, Duration::from_secs(100)), 8);
+    }
+
+    #[test]
+    fn custom_latency_bounds() {
+        let processor = ZPagesSpanProcessor::new().with_latency_bounds(vec![
+            Duration::from_secs(1),
+            Duration::from_millis(250),
+            Duration::from_secs(1),
+        ]);
+        let tracez = processor.tracez();
+        assert_eq!(
+            tracez.latency_bounds(),
+            vec![Duration::from_millis(250), Duration::from_secs(1)]
+        );
+        let provider = provider(processor);
+
+        record(&provider, "request", Duration::from_millis(100), false);
+        record(&provider, "request", Duration::from_millis(500), false);
+        record(&provider, "request", Duration::from_millis(700), false);
+        record(&provider, "request", Duration::from_secs(3), false);
+
+        assert_eq!(tracez.summaries()[0].latency, vec![1, 2, 1]);
+        assert_eq!(tracez.samples("request", SampleKind::Latency(1)).len(), 2);
+        assert!(tracez.samples("request", SampleKind::Latency(3)).is_empty());
+    }
+
+    #[test]
+    fn aggregates_spans_by_name() {
+        let processor = ZPagesSpanProcessor::new();
+        let tracez = processor.tracez();
+        let provider = provider(processor);
+
+        let tracer = provider.get_tracer("zpages", None);
+        let running = tracer
+            .span_builder("request")
+            .with_start_time(SystemTime::UNIX_EPOCH)
+            .start(&tracer);
+        record(&provider, "request", Duration::from_millis(5), false);
+        record(&provider, "request", Duration::from_millis(7), false);
+        record(&provider, "request", Duration::from_millis(50), true);
+        record(&provider, "query", Duration::from_secs(2), false);
+
+        let mut request = SpanSummary {
+            name: "request".to_string(),
+            running: 1,
+            latency: vec![0; 9],
+            errors: 1,
+        };
+        request.latency[3] = 2;
+        let mut query = SpanSummary {
+            name: "query".to_string(),
+            running: 0,
+            latency: vec![0; 9],
+            errors: 0,
+        };
+        query.latency[6] = 1;
+        assert_eq!(tracez.summaries(), vec![query, request.clone()]);
+
+        running.end_with_timestamp(SystemTime::UNIX_EPOCH);
+        request.running = 0;
+        request.latency[0] += 1;
+        assert_eq!(tracez.summaries()[1], request);
+    }
+
+    #[test]
+    fn keeps_most_recent_samples() {
+        let processor = ZPagesSpanProcessor::with_max_samples(2);
+        let tracez = processor.tracez();
+        let provider = provider(processor);
+
+        for millis in 2..6 {
+            record(&provider, "request", Duration::from_millis(millis), false);
+        }
+        record(&provider, "request", Duration::from_millis(1), true);
+
+        let durations: Vec<_> = tracez
+            .samples("request", SampleKind::Latency(3))
+            .iter()
+            .map(|span| span.duration())
+            .collect();
+        assert_eq!(
+            durations,
+            vec![Duration::from_millis(4), Duration::from_millis(5)]
+        );
+        assert_eq!(tracez.samples("request", SampleKind::Error).len(), 1);
+        assert!(tracez.samples("request", SampleKind::Running).is_empty());
+        assert!(tracez.samples("unknown", SampleKind::Latency(3)).is_empty());
+
+        let tracer = provider.get_tracer("zpages", None);
+        let _running = tracer.start("request");
+        assert_eq!(tracez.samples("request", SampleKind::Running).len(), 1);
+    }
+}
@@ -0,0 +1,220 @@
+use super::tracez::{
+    error, escape, html_head, html_response, json_response, method_not_allowed, unix_nanos,
+};
+use http::{Method, Request, Response, StatusCode};
+use opentelemetry::sdk::trace::{Diagnostics, ProcessorDiagnostics, Tracer, TracerProvider};
+use opentelemetry::trace::TracerProvider as _;
+use std::fmt::Write;
+use std::time::{Duration, SystemTime};
+
+/// Serves the self-diagnostics of a tracer provider on the statusz page, see
+/// [`TracerProvider::diagnostics`].
+///
+/// The handler does not keep the provider alive, once the provider is
+/// dropped requests are answered with `503 Service Unavailable`.
+#[derive(Clone, Debug)]
+pub struct Statusz {
+    tracer: Tracer,
+}
+
+impl Statusz {
+    /// Create a handler serving the diagnostics of `provider`.
+    pub fn new(provider: &TracerProvider) -> Self {
+        Statusz {
+            tracer: provider.get_tracer("opentelemetry-contrib/zpages", None),
+        }
+    }
+
+    /// The diagnostics of the provider, if it is still alive.
+    pub fn diagnostics(&self) -> Option<Diagnostics> {
+        self.tracer
+            .provider()
+            .map(|provider| provider.diagnostics())
+    }
+
+    /// Answer a request for the statusz page.
+    ///
+    /// Requests to a path ending in `/statusz` are answered with HTML, to
+    /// `/statusz/json` with JSON, so the handler can be mounted under any
+    /// prefix.
+    pub fn handle<B>(&self, request: &Request<B>) -> Response<Vec<u8>> {
+        let path = request.uri().path().trim_end_matches('/');
+        let json = if path.ends_with("/statusz") {
+            false
+        } else if path.ends_with("/statusz/json") {
+            true
+        } else {
+            return error(StatusCode::NOT_FOUND, "not found");
+        };
+        if request.method() != Method::GET {
+            return method_not_allowed();
+        }
+
+        match self.diagnostics() {
+            Some(diagnostics) if json => json_response(&diagnostics),
+            Some(diagnostics) => html_response(&status_page(&diagnostics)),
+            None => error(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "the tracer provider was dropped",
+            ),
+        }
+    }
+}
+
+fn status_page(diagnostics: &Diagnostics) -> String {
+    let mut page = html_head("statusz");
+    let _ = writeln!(
+        page,
+        "<h1>statusz</h1>\n<p>Sampler: <code>{}</code></p>\n<h2>Resource</h2>\n<table>",
+        escape(&diagnostics.sampler)
+    );
+    for (key, value) in &diagnostics.resource {
+        let _ = writeln!(
+            page,
+            "<tr><td>{}</td><td>{}</td></tr>",
+            escape(key),
+            escape(value)
+        );
+    }
+    page.push_str(
+        "</table>\n<h2>Span processors</h2>\n<table>\n<tr><th>Processor</th><th>Queued</th>\
+         <th>Dropped</th><th>Exports</th><th>Failures</th><th>Last success (unix)</th>\
+         <th>Last failure (unix)</th><th>Last error</th><th>Last latency</th></tr>\n",
+    );
+    for processor in &diagnostics.processors {
+        processor_rows(&mut page, processor, 0);
+    }
+    page.push_str("</table>\n</body></html>\n");
+    page
+}
+
+/// Table rows of a processor and the processors it passes spans on to,
+/// indented by `depth`.
+fn processor_rows(page: &mut String, processor: &ProcessorDiagnostics, depth: usize) {
+    let _ = write!(
+        page,
+        "<tr><td>{}{}{}</td>",
+        "&nbsp;&nbsp;".repeat(depth),
+        escape(&processor.name),
+        if processor.quarantined {
+            " (quarantined)"
+        } else {
+            ""
+        }
+    );
+    match &processor.queue {
+        Some(queue) => {
+            let _ = write!(
+                page,
+                "<td>{}/{}</td><td>{}</td>",
+                queue.len, queue.capacity, queue.dropped
+            );
+        }
+        None => page.push_str("<td></td><td></td>"),
+    }
+    match &processor.exporter {
+        Some(exporter) => {
+            let _ = write!(
+                page,
+                "<td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td>",
+                exporter.exports,
+                exporter.failures,
+                time_cell(exporter.last_success),
+                time_cell(exporter.last_failure),
+                escape(exporter.last_error.as_deref().unwrap_or("")),
+                exporter
+                    .last_latency
+                    .map(|latency| format!("{:?}", latency))
+                    .unwrap_or_default()
+            );
+        }
+        None => page.push_str("<td></td><td></td><td></td><td></td><td></td><td></td>"),
+    }
+    page.push_str("</tr>\n");
+    for child in &processor.processors {
+        processor_rows(page, child, depth + 1);
+    }
+}
+
+fn time_cell(time: Option<SystemTime>) -> String {
+    match time {
+        Some(time) => {
+            let since_epoch = Duration::from_nanos(unix_nanos(time));
+            format!(
+                "{}.{:06}",
+                since_epoch.as_secs(),
+                since_epoch.subsec_micros()
+            )
+        }
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::header::CONTENT_TYPE;
+    use opentelemetry::sdk::trace::BatchSpanProcessor;
+    use opentelemetry::sdk::{trace::BatchConfig, Resource};
+    use opentelemetry::trace::{NoopSpanExporter, Span, Tracer as _};
+    use opentelemetry::KeyValue;
+
+    fn get(statusz: &Statusz, uri: &str) -> Response<Vec<u8>> {
+        statusz.handle(&Request::get(uri).body(()).unwrap())
+    }
+
+    fn body(response: &Response<Vec<u8>>) -> &str {
+        std::str::from_utf8(response.body()).unwrap()
+    }
+
+    #[test]
+    fn serves_diagnostics() {
+        let provider = TracerProvider::builder()
+            .with_config(
+                opentelemetry::sdk::trace::config().with_resource(Resource::new(vec![
+                    KeyValue::new("service.name", "<checkout>"),
+                ])),
+            )
+            .with_simple_exporter(NoopSpanExporter::new())
+            .with_batch_exporter(BatchSpanProcessor::with_own_thread(
+                NoopSpanExporter::new(),
+                BatchConfig::default().with_scheduled_delay(Duration::from_secs(3600)),
+            ))
+            .build();
+        let statusz = Statusz::new(&provider);
+        provider.get_tracer("statusz", None).start("work").end();
+
+        let page = get(&statusz, "/debug/statusz/");
+        assert_eq!(page.status(), StatusCode::OK);
+        assert_eq!(page.headers()[CONTENT_TYPE], "text/html; charset=utf-8");
+        assert!(body(&page).contains("<tr><td>service.name</td><td>&lt;checkout&gt;</td></tr>"));
+        assert!(body(&page).contains("<tr><td>BatchSpanProcessor</td><td>1/2048</td><td>0</td>"));
+
+        let json = get(&statusz, "/statusz/json");
+        let diagnostics: Diagnostics = serde_json::from_slice(json.body()).unwrap();
+        assert_eq!(diagnostics.resource["service.name"], "<checkout>");
+        assert_eq!(diagnostics.processors[0].name, "SimpleSpanProcessor");
+        assert_eq!(
+            diagnostics.processors[0]
+                .exporter
+                .as_ref()
+                .map(|exporter| exporter.exports),
+            Some(1)
+        );
+
+        drop(provider);
+        assert_eq!(
+            get(&statusz, "/statusz").status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_requests() {
+        let provider = TracerProvider::default();
+        let statusz = Statusz::new(&provider);
+        assert_eq!(get(&statusz, "/tracez").status(), StatusCode::NOT_FOUND);
+        let post = statusz.handle(&Request::post("/statusz").body(()).unwrap());
+        assert_eq!(post.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+}
@@ -0,0 +1,471 @@
+use super::processor::{SampleKind, SpanSummary, Store};
+use http::header::{ALLOW, CONTENT_TYPE};
+use http::{Method, Request, Response, StatusCode};
+use opentelemetry::sdk::export::trace::SpanData;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Handle to the spans aggregated by a [`ZPagesSpanProcessor`], serving the
+/// tracez page.
+///
+/// [`ZPagesSpanProcessor`]: super::ZPagesSpanProcessor
+#[derive(Clone, Debug)]
+pub struct Tracez {
+    store: Arc<Store>,
+}
+
+impl Tracez {
+    pub(crate) fn new(store: Arc<Store>) -> Self {
+        Tracez { store }
+    }
+
+    /// The counts of the spans seen so far by name, ordered by name.
+    pub fn summaries(&self) -> Vec<SpanSummary> {
+        self.store.summaries()
+    }
+
+    /// The upper bounds of the latency buckets, the last bucket holds all
+    /// spans longer than the last bound.
+    pub fn latency_bounds(&self) -> Vec<Duration> {
+        self.store.latency_bounds()
+    }
+
+    /// The running spans or the samples of ended spans named `name`, oldest
+    /// first.
+    ///
+    /// Running spans are snapshots taken when they started.
+    pub fn samples(&self, name: &str, kind: SampleKind) -> Vec<Arc<SpanData>> {
+        self.store.samples(name, kind)
+    }
+
+    /// Answer a request for the tracez page.
+    ///
+    /// Requests to a path ending in `/tracez` are answered with HTML, to
+    /// `/tracez/json` with JSON, so the handler can be mounted under any
+    /// prefix. Without query the page lists the summaries of all span names,
+    /// with `name=<span name>&type=running|latency|error` it lists the
+    /// running spans or samples of the name, latency samples also need the
+    /// index of the latency bucket as `bucket=<index>`.
+    pub fn handle<B>(&self, request: &Request<B>) -> Response<Vec<u8>> {
+        let path = request.uri().path().trim_end_matches('/');
+        let json = if path.ends_with("/tracez") {
+            false
+        } else if path.ends_with("/tracez/json") {
+            true
+        } else {
+            return error(StatusCode::NOT_FOUND, "not found");
+        };
+        if request.method() != Method::GET {
+            return method_not_allowed();
+        }
+
+        let query = request.uri().query().unwrap_or("");
+        let bounds = self.latency_bounds();
+        let name = match query_param(query, "name") {
+            Some(name) => name,
+            None if json => return json_response(&self.summaries()),
+            None => return html_response(&summaries_page(&bounds, &self.summaries())),
+        };
+        let bucket = query_param(query, "bucket").and_then(|bucket| bucket.parse().ok());
+        let kind = match (query_param(query, "type").as_deref(), bucket) {
+            (Some("running"), _) => SampleKind::Running,
+            (Some("error"), _) => SampleKind::Error,
+            (Some("latency"), Some(bucket)) if bucket <= bounds.len() => {
+                SampleKind::Latency(bucket)
+            }
+            _ => return error(StatusCode::BAD_REQUEST, "invalid span type or bucket"),
+        };
+
+        let samples = self.samples(&name, kind);
+        if json {
+            let samples: Vec<_> = samples.iter().map(|span| SpanJson::new(span)).collect();
+            json_response(&samples)
+        } else {
+            html_response(&samples_page(&bounds, &name, kind, &samples))
+        }
+    }
+}
+
+/// A span as served in JSON.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpanJson<'a> {
+    trace_id: String,
+    span_id: String,
+    parent_span_id: String,
+    name: &'a str,
+    kind: String,
+    start_time_unix_nano: u64,
+    duration_nanos: u64,
+    status_code: &'static str,
+    status_message: &'a str,
+    attributes: BTreeMap<&'a str, String>,
+    events: Vec<EventJson<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EventJson<'a> {
+    name: &'a str,
+    time_unix_nano: u64,
+}
+
+impl<'a> SpanJson<'a> {
+    fn new(span: &'a SpanData) -> Self {
+        SpanJson {
+            trace_id: span.span_context.trace_id().to_hex(),
+            span_id: span.span_context.span_id().to_hex(),
+            parent_span_id: span.parent_span_id.to_hex(),
+            name: &span.name,
+            kind: span.span_kind.to_string(),
+            start_time_unix_nano: unix_nanos(span.start_time),
+            duration_nanos: duration(span).as_nanos() as u64,
+            status_code: span.status_code.as_str(),
+            status_message: &span.status_message,
+            attributes: span
+                .attributes
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.to_string()))
+                .collect(),
+            events: span
+                .message_events
+                .iter()
+                .map(|event| EventJson {
+                    name: &event.name,
+                    time_unix_nano: unix_nanos(event.timestamp),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The duration of an ended span, or how long a running span runs so far.
+///
+/// Snapshots of running spans end at their start time.
+fn duration(span: &SpanData) -> Duration {
+    if span.end_time == span.start_time {
+        SystemTime::now()
+            .duration_since(span.start_time)
+            .unwrap_or_default()
+    } else {
+        span.duration()
+    }
+}
+
+pub(super) fn unix_nanos(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+fn latency_label(bounds: &[Duration], bucket: usize) -> String {
+    match bucket.checked_sub(1).and_then(|bound| bounds.get(bound)) {
+        Some(bound) => format!(">{:?}", bound),
+        None => ">0s".to_string(),
+    }
+}
+
+/// The start of a page titled `title`, up to the opening body tag.
+pub(super) fn html_head(title: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{}</title>\
+<style>body{{font-family:sans-serif}}table{{border-collapse:collapse}}\
+td,th{{border:1px solid #ccc;padding:2px 6px;text-align:left}}</style></head><body>\n",
+        title
+    )
+}
+
+fn summaries_page(bounds: &[Duration], summaries: &[SpanSummary]) -> String {
+    let mut page = html_head("tracez");
+    page.push_str("<h1>tracez</h1>\n<table>\n<tr><th>Span name</th><th>Running</th>");
+    for bucket in 0..=bounds.len() {
+        let _ = write!(page, "<th>{}</th>", escape(&latency_label(bounds, bucket)));
+    }
+    page.push_str("<th>Errors</th></tr>\n");
+
+    for summary in summaries {
+        let name = percent_encode(&summary.name);
+        let _ = write!(page, "<tr><td>{}</td>", escape(&summary.name));
+        count_cell(&mut page, summary.running as u64, &name, "running");
+        for (bucket, count) in summary.latency.iter().enumerate() {
+            count_cell(
+                &mut page,
+                *count,
+                &name,
+                &format!("latency&amp;bucket={}", bucket),
+            );
+        }
+        count_cell(&mut page, summary.errors, &name, "error");
+        page.push_str("</tr>\n");
+    }
+    page.push_str("</table>\n</body></html>\n");
+    page
+}
+
+/// A table cell with a count, linking to the spans counted if there are any.
+fn count_cell(page: &mut String, count: u64, name: &str, kind: &str) {
+    if count == 0 {
+        page.push_str("<td>0</td>");
+    } else {
+        let _ = write!(
+            page,
+            "<td><a href=\"?name={}&amp;type={}\">{}</a></td>",
+            name, kind, count
+        );
+    }
+}
+
+fn samples_page(
+    bounds: &[Duration],
+    name: &str,
+    kind: SampleKind,
+    samples: &[Arc<SpanData>],
+) -> String {
+    let title = match kind {
+        SampleKind::Running => "running".to_string(),
+        SampleKind::Latency(bucket) => format!("latency {}", latency_label(bounds, bucket)),
+        SampleKind::Error => "errors".to_string(),
+    };
+    let mut page = html_head("tracez");
+    let _ = writeln!(
+        page,
+        "<h1>tracez</h1>\n<p><a href=\"?\">all spans</a></p>\n<h2>{}: {}</h2>",
+        escape(name),
+        escape(&title)
+    );
+    page.push_str(
+        "<table>\n<tr><th>Start (unix)</th><th>Duration</th><th>Trace id</th><th>Span id</th>\
+         <th>Parent span id</th><th>Status</th><th>Attributes</th><th>Events</th></tr>\n",
+    );
+    for span in samples {
+        let start = Duration::from_nanos(unix_nanos(span.start_time));
+        let attributes: Vec<_> = span
+            .attributes
+            .iter()
+            .map(|(key, value)| format!("{}={}", key.as_str(), value))
+            .collect();
+        let events: Vec<_> = span
+            .message_events
+            .iter()
+            .map(|event| event.name.as_ref())
+            .collect();
+        let _ = writeln!(
+            page,
+            "<tr><td>{}.{:06}</td><td>{:?}</td><td>{}</td><td>{}</td><td>{}</td><td>{} {}</td>\
+             <td>{}</td><td>{}</td></tr>",
+            start.as_secs(),
+            start.subsec_micros(),
+            duration(span),
+            span.span_context.trace_id().to_hex(),
+            span.span_context.span_id().to_hex(),
+            span.parent_span_id.to_hex(),
+            span.status_code.as_str(),
+            escape(&span.status_message),
+            escape(&attributes.join(", ")),
+            escape(&events.join(", ")),
+        );
+    }
+    page.push_str("</table>\n</body></html>\n");
+    page
+}
+
+pub(super) fn html_response(page: &str) -> Response<Vec<u8>> {
+    response(
+        StatusCode::OK,
+        "text/html; charset=utf-8",
+        page.as_bytes().to_vec(),
+    )
+}
+
+pub(super) fn json_response<T: Serialize>(value: &T) -> Response<Vec<u8>> {
+    match serde_json::to_vec(value) {
+        Ok(body) => response(StatusCode::OK, "application/json", body),
+        Err(err) => error(StatusCode::INTERNAL_SERVER_ERROR, &err.to_string()),
+    }
+}
+
+pub(super) fn error(status: StatusCode, message: &str) -> Response<Vec<u8>> {
+    response(
+        status,
+        "text/plain; charset=utf-8",
+        message.as_bytes().to_vec(),
+    )
+}
+
+pub(super) fn method_not_allowed() -> Response<Vec<u8>> {
+    let mut response = error(StatusCode::METHOD_NOT_ALLOWED, "method not allowed");
+    response
+        .headers_mut()
+        .insert(ALLOW, http::HeaderValue::from_static("GET"));
+    response
+}
+
+fn response(status: StatusCode, content_type: &'static str, body: Vec<u8>) -> Response<Vec<u8>> {
+    let mut response = Response::new(body);
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, http::HeaderValue::from_static(content_type));
+    response
+}
+
+/// Escape text for HTML element content and attribute values.
+pub(super) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Percent-encode a query parameter value.
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            byte => {
+                let _ = write!(encoded, "%{:02X}", byte);
+            }
+        }
+    }
+    encoded
+}
+
+/// The decoded value of the first query parameter named `key`.
+fn query_param(query: &str, key: &str) -> Option<String> {
+    query.split('&').find_map(|pair| {
+        let mut parts = pair.splitn(2, '=');
+        if parts.next()? == key {
+            Some(percent_decode(parts.next().unwrap_or("")))
+        } else {
+            None
+        }
+    })
+}
+
+/// Decode a form encoded query parameter value, invalid escapes are kept
+/// as they are.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let hex = bytes
+            .get(idx + 1..idx + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[idx], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                idx += 3;
+            }
+            (b'+', _) => {
+                decoded.push(b' ');
+                idx += 1;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                idx += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace::zpages::ZPagesSpanProcessor;
+    use opentelemetry::sdk::trace::TracerProvider;
+    use opentelemetry::trace::{Span, Tracer, TracerProvider as _};
+
+    fn get(tracez: &Tracez, uri: &str) -> Response<Vec<u8>> {
+        tracez.handle(&Request::get(uri).body(()).unwrap())
+    }
+
+    fn body(response: &Response<Vec<u8>>) -> &str {
+        std::str::from_utf8(response.body()).unwrap()
+    }
+
+    #[test]
+    fn serves_summaries_and_samples() {
+        let processor = ZPagesSpanProcessor::new();
+        let tracez = processor.tracez();
+        let provider = TracerProvider::builder()
+            .with_span_processor(processor)
+            .build();
+        let tracer = provider.get_tracer("zpages", None);
+        let span = tracer.start("GET /users/<id>");
+        span.set_status(opentelemetry::trace::StatusCode::Error, "denied".into());
+        span.end();
+        let _running = tracer.start("GET /users/<id>");
+
+        let page = get(&tracez, "/debug/tracez");
+        assert_eq!(page.status(), StatusCode::OK);
+        assert_eq!(page.headers()[CONTENT_TYPE], "text/html; charset=utf-8");
+        assert!(body(&page).contains("<td>GET /users/&lt;id&gt;</td>"));
+        assert!(body(&page).contains("?name=GET%20%2Fusers%2F%3Cid%3E&amp;type=error"));
+
+        let summaries = get(&tracez, "/tracez/json/");
+        let summaries: serde_json::Value = serde_json::from_slice(summaries.body()).unwrap();
+        assert_eq!(summaries[0]["name"], "GET /users/<id>");
+        assert_eq!(summaries[0]["running"], 1);
+        assert_eq!(summaries[0]["errors"], 1);
+
+        let errors = get(
+            &tracez,
+            "/tracez/json?name=GET+%2Fusers%2F%3Cid%3E&type=error",
+        );
+        let errors: serde_json::Value = serde_json::from_slice(errors.body()).unwrap();
+        assert_eq!(errors[0]["statusCode"], "ERROR");
+        assert_eq!(errors[0]["statusMessage"], "denied");
+
+        let running = get(
+            &tracez,
+            "/tracez?name=GET%20%2Fusers%2F%3Cid%3E&type=running",
+        );
+        assert!(body(&running).contains("<h2>GET /users/&lt;id&gt;: running</h2>"));
+    }
+
+    #[test]
+    fn rejects_invalid_requests() {
+        let tracez = ZPagesSpanProcessor::new().tracez();
+        assert_eq!(get(&tracez, "/metricz").status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            get(&tracez, "/tracez?name=a&type=latency&bucket=9").status(),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            get(&tracez, "/tracez?name=a&type=latency&bucket=8").status(),
+            StatusCode::OK
+        );
+
+        let post = tracez.handle(&Request::post("/tracez").body(()).unwrap());
+        assert_eq!(post.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(post.headers()[ALLOW], "GET");
+    }
+
+    #[test]
+    fn decodes_query_params() {
+        assert_eq!(
+            query_param("type=error&name=a%2Fb+c%zz", "name").as_deref(),
+            Some("a/b c%zz")
+        );
+        assert_eq!(query_param("name", "name").as_deref(), Some(""));
+        assert_eq!(query_param("type=error", "name"), None);
+        assert_eq!(percent_encode("a/b c"), "a%2Fb%20c");
+    }
+}
@@ -0,0 +1,149 @@
+//! # Datadog Agent Environment
+//!
+//! Detects the agent endpoint from the environment variables of the Datadog
+//! tracing libraries and the id of the container the process runs in.
+use std::env;
+
+/// URL of the agent, takes precedence over the host and port.
+/// e.g. "http://datadog-agent:8126"
+const DD_TRACE_AGENT_URL: &str = "DD_TRACE_AGENT_URL";
+
+/// Host of the agent.
+/// e.g. "datadog-agent"
+const DD_AGENT_HOST: &str = "DD_AGENT_HOST";
+
+/// Port of the agent's trace endpoint.
+/// e.g. 8126
+const DD_TRACE_AGENT_PORT: &str = "DD_TRACE_AGENT_PORT";
+
+const DEFAULT_AGENT_PORT: &str = "8126";
+
+/// The agent endpoint configured in the environment, if any.
+pub(crate) fn agent_endpoint() -> Option<String> {
+    if let Some(url) = var(DD_TRACE_AGENT_URL) {
+        return Some(url.trim_end_matches('/').to_string());
+    }
+
+    let host = var(DD_AGENT_HOST)?;
+    let port = var(DD_TRACE_AGENT_PORT).unwrap_or_else(|| DEFAULT_AGENT_PORT.to_string());
+    if host.contains(':') {
+        // IPv6 address
+        Some(format!("http://[{}]:{}", host, port))
+    } else {
+        Some(format!("http://{}:{}", host, port))
+    }
+}
+
+fn var(name: &str) -> Option<String> {
+    env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// The id of the container the process runs in, read from its cgroups.
+pub(crate) fn container_id() -> Option<String> {
+    std::fs::read_to_string("/proc/self/cgroup")
+        .ok()
+        .and_then(|cgroup| parse_container_id(&cgroup))
+}
+
+/// Find the container id in the cgroup paths of the process, supporting the
+/// layouts of docker, containerd, CRI-O, kubernetes and ECS.
+fn parse_container_id(cgroup: &str) -> Option<String> {
+    cgroup
+        .lines()
+        // hierarchy-id:controllers:path
+        .filter_map(|line| line.splitn(3, ':').nth(2))
+        .filter_map(|path| path.rsplit('/').next())
+        .map(|id| {
+            let id = id.trim_end_matches(".scope");
+            id.rsplit('-')
+                .next()
+                .filter(|id| id.len() == 64)
+                .unwrap_or(id)
+        })
+        .find(|id| is_container_id(id))
+        .map(str::to_string)
+}
+
+fn is_container_id(id: &str) -> bool {
+    let is_hex = |part: &str| part.bytes().all(|b| b.is_ascii_hexdigit());
+    let parts: Vec<&str> = id.split('-').collect();
+    match parts.as_slice() {
+        // docker, containerd and CRI-O
+        [id] => id.len() == 64 && is_hex(id),
+        // ECS on Fargate, task id followed by a number
+        [task, number] => {
+            task.len() == 32 && is_hex(task) && number.bytes().all(|b| b.is_ascii_digit())
+        }
+        // UUID
+        [a, b, c, d, e] => [(a, 8), (b, 4), (c, 4), (d, 4), (e, 12)]
+            .iter()
+            .all(|(part, len)| part.len() == *len && is_hex(part)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCKER_ID: &str = "3726184226f5d3147c25fdeab5b60097e378e8a720503a5e19ecfdf29f869860";
+
+    #[test]
+    fn agent_endpoint_from_env() {
+        env::remove_var(DD_TRACE_AGENT_URL);
+        env::set_var(DD_AGENT_HOST, "datadog-agent");
+        assert_eq!(
+            agent_endpoint().as_deref(),
+            Some("http://datadog-agent:8126")
+        );
+
+        env::set_var(DD_AGENT_HOST, "fd00::1");
+        env::set_var(DD_TRACE_AGENT_PORT, "9126");
+        assert_eq!(agent_endpoint().as_deref(), Some("http://[fd00::1]:9126"));
+
+        env::set_var(DD_TRACE_AGENT_URL, "http://localhost:8126/");
+        assert_eq!(agent_endpoint().as_deref(), Some("http://localhost:8126"));
+
+        env::remove_var(DD_TRACE_AGENT_URL);
+        env::remove_var(DD_AGENT_HOST);
+        env::remove_var(DD_TRACE_AGENT_PORT);
+        assert_eq!(agent_endpoint(), None);
+    }
+
+    #[test]
+    fn parse_container_ids() {
+        let docker = format!(
+            "13:name=systemd:/docker/{id}\n12:pids:/docker/{id}\n",
+            id = DOCKER_ID
+        );
+        assert_eq!(parse_container_id(&docker).as_deref(), Some(DOCKER_ID));
+
+        let kubernetes = format!(
+            "1:name=systemd:/kubepods.slice/kubepods-pod2b2a.slice/cri-containerd-{}.scope\n",
+            DOCKER_ID
+        );
+        assert_eq!(parse_container_id(&kubernetes).as_deref(), Some(DOCKER_ID));
+
+        let fargate = "1:name=systemd:/ecs/55091c13-b8cf-4801-b527-f4601742204d/432624d2150b349fe35ba397284dea788c2bf66b885d14dfc1569b01890ca7da\n\
+            2:cpu:/ecs/34dc0b5e626f2c5c4c5170e34b10e765-1234567890\n";
+        assert_eq!(
+            parse_container_id(fargate).as_deref(),
+            Some("432624d2150b349fe35ba397284dea788c2bf66b885d14dfc1569b01890ca7da")
+        );
+        assert_eq!(
+            parse_container_id("2:cpu:/ecs/34dc0b5e626f2c5c4c5170e34b10e765-1234567890\n")
+                .as_deref(),
+            Some("34dc0b5e626f2c5c4c5170e34b10e765-1234567890")
+        );
+        assert_eq!(
+            parse_container_id("1:cpu:/pod/55091c13-b8cf-4801-b527-f4601742204d\n").as_deref(),
+            Some("55091c13-b8cf-4801-b527-f4601742204d")
+        );
+
+        assert_eq!(parse_container_id("9:name=systemd:/\n8:pids:/\n"), None);
+        assert_eq!(parse_container_id("0::/user.slice/user-1000.slice\n"), None);
+    }
+}
@@ -1,3 +1,4 @@
+mod env;
 mod intern;
 mod model;
 
@@ -22,6 +23,9 @@ const DEFAULT_SERVICE_NAME: &str = "OpenTelemetry";
 /// Header name used to inform the Datadog agent of the number of traces in the payload
 const DATADOG_TRACE_COUNT_HEADER: &str = "X-Datadog-Trace-Count";
 
+/// Header name used to tag the traces with the container they were created in
+const DATADOG_CONTAINER_ID_HEADER: &str = "Datadog-Container-ID";
+
 /// Datadog span exporter
 #[derive(Debug)]
 pub struct DatadogExporter {
@@ -29,6 +33,8 @@ pub struct DatadogExporter {
     request_url: Uri,
     service_name: String,
     version: ApiVersion,
+    hostname: Option<String>,
+    container_id: Option<String>,
 }
 
 impl DatadogExporter {
@@ -43,6 +49,8 @@ impl DatadogExporter {
             request_url,
             service_name,
             version,
+            hostname: None,
+            container_id: None,
         }
     }
 }
@@ -60,15 +68,20 @@ pub struct DatadogPipelineBuilder {
     trace_config: Option<sdk::trace::Config>,
     version: ApiVersion,
     client: Option<Box<dyn HttpClient>>,
+    hostname: Option<String>,
+    container_id: Option<String>,
 }
 
 impl Default for DatadogPipelineBuilder {
     fn default() -> Self {
         DatadogPipelineBuilder {
             service_name: DEFAULT_SERVICE_NAME.to_string(),
-            agent_endpoint: DEFAULT_AGENT_ENDPOINT.to_string(),
+            agent_endpoint: env::agent_endpoint()
+                .unwrap_or_else(|| DEFAULT_AGENT_ENDPOINT.to_string()),
             trace_config: None,
             version: ApiVersion::Version05,
+            hostname: None,
+            container_id: env::container_id(),
             #[cfg(all(
                 not(feature = "reqwest-client"),
                 not(feature = "reqwest-blocking-client"),
@@ -98,12 +111,14 @@ impl DatadogPipelineBuilder {
     pub fn install(mut self) -> Result<sdk::trace::Tracer, TraceError> {
         if let Some(client) = self.client {
             let endpoint = self.agent_endpoint + self.version.path();
-            let exporter = DatadogExporter::new(
+            let mut exporter = DatadogExporter::new(
                 self.service_name.clone(),
                 endpoint.parse().map_err::<Error, _>(Into::into)?,
                 self.version,
                 client,
             );
+            exporter.hostname = self.hostname;
+            exporter.container_id = self.container_id;
             let mut provider_builder =
                 sdk::trace::TracerProvider::builder().with_exporter(exporter);
             if let Some(config) = self.trace_config.take() {
@@ -126,6 +141,9 @@ impl DatadogPipelineBuilder {
     }
 
     /// Assign the Datadog collector endpoint
+    ///
+    /// Defaults to `DD_TRACE_AGENT_URL` or `DD_AGENT_HOST` and
+    /// `DD_TRACE_AGENT_PORT` if set, and `http://127.0.0.1:8126` otherwise.
     pub fn with_agent_endpoint<T: Into<String>>(mut self, endpoint: T) -> Self {
         self.agent_endpoint = endpoint.into();
         self
@@ -146,6 +164,21 @@ impl DatadogPipelineBuilder {
         self
     }
 
+    /// Report the host the spans were created on as `_dd.hostname`.
+    pub fn with_hostname<T: Into<String>>(mut self, hostname: T) -> Self {
+        self.hostname = Some(hostname.into());
+        self
+    }
+
+    /// Assign the id of the container the spans were created in.
+    ///
+    /// The id is detected from the cgroups of the process by default, the
+    /// agent uses it to tag the traces with the container's metadata.
+    pub fn with_container_id<T: Into<String>>(mut self, container_id: T) -> Self {
+        self.container_id = Some(container_id.into());
+        self
+    }
+
     /// Set version of Datadog trace ingestion API
     pub fn with_version(mut self, version: ApiVersion) -> Self {
         self.version = version;
@@ -168,15 +201,20 @@ impl trace::SpanExporter for DatadogExporter {
     async fn export(&mut self, batch: Vec<SpanData>) -> trace::ExportResult {
         let traces: Vec<Vec<SpanData>> = group_into_traces(batch);
         let trace_count = traces.len();
-        let data = self.version.encode(&self.service_name, traces)?;
-        let req = Request::builder()
+        let data = self
+            .version
+            .encode(&self.service_name, self.hostname.as_deref(), traces)?;
+        let mut req = Request::builder()
             .method(Method::POST)
             .uri(self.request_url.clone())
             .header(http::header::CONTENT_TYPE, self.version.content_type())
-            .header(DATADOG_TRACE_COUNT_HEADER, trace_count)
-            .body(data)
-            .map_err::<Error, _>(Into::into)?;
-        self.client.send(req).await
+            .header(DATADOG_TRACE_COUNT_HEADER, trace_count);
+        if let Some(container_id) = &self.container_id {
+            req = req.header(DATADOG_CONTAINER_ID_HEADER, container_id);
+        }
+        let req = req.body(data).map_err::<Error, _>(Into::into)?;
+        self.client.send(req).await?;
+        Ok(())
     }
 }
 
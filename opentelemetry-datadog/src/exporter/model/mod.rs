@@ -1,8 +1,17 @@
 use opentelemetry::sdk::export::{trace, ExportError};
+use opentelemetry::trace::StatusCode;
+
+/// Span meta key of the host the span was created on
+const DD_HOSTNAME_KEY: &str = "_dd.hostname";
 
 mod v03;
 mod v05;
 
+/// The `error` field of a span, 1 if the span has an error status, 0 otherwise
+fn is_error(status_code: StatusCode) -> i32 {
+    (status_code == StatusCode::Error) as i32
+}
+
 /// Wrap type for errors from opentelemetry datadog exporter
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -62,11 +71,12 @@ impl ApiVersion {
     pub(crate) fn encode(
         self,
         service_name: &str,
+        hostname: Option<&str>,
         traces: Vec<Vec<trace::SpanData>>,
     ) -> Result<Vec<u8>, Error> {
         match self {
-            Self::Version03 => v03::encode(service_name, traces),
-            Self::Version05 => v05::encode(service_name, traces),
+            Self::Version03 => v03::encode(service_name, hostname, traces),
+            Self::Version05 => v05::encode(service_name, hostname, traces),
         }
     }
 }
@@ -77,7 +87,7 @@ pub(crate) mod tests {
     use opentelemetry::sdk;
     use opentelemetry::sdk::InstrumentationLibrary;
     use opentelemetry::{
-        trace::{SpanContext, SpanId, SpanKind, StatusCode, TraceId, TraceState},
+        trace::{SpanContext, SpanId, SpanKind, TraceId, TraceState},
         Key,
     };
     use std::sync::Arc;
@@ -110,7 +120,7 @@ pub(crate) mod tests {
             span_context,
             parent_span_id: SpanId::from_u64(parent_span_id),
             span_kind: SpanKind::Client,
-            name: "resource".to_string(),
+            name: "resource".into(),
             start_time,
             end_time,
             attributes,
@@ -126,9 +136,9 @@ pub(crate) mod tests {
     #[test]
     fn test_encode_v03() -> Result<(), Box<dyn std::error::Error>> {
         let traces = get_traces();
-        let encoded = base64::encode(ApiVersion::Version03.encode("service_name", traces)?);
+        let encoded = base64::encode(ApiVersion::Version03.encode("service_name", None, traces)?);
 
-        assert_eq!(encoded.as_str(), "kZGLpHR5cGWjd2Vip3NlcnZpY2Wsc2VydmljZV9uYW1lpG5hbWWpY29tcG9uZW50qHJlc291cmNlqHJlc291cmNlqHRyYWNlX2lkzwAAAAAAAAAHp3NwYW5faWTPAAAAAAAAAGOpcGFyZW50X2lkzwAAAAAAAAABpXN0YXJ00wAAAAAAAAAAqGR1cmF0aW9u0wAAAAA7msoApWVycm9y0gAAAAGkbWV0YYGpc3Bhbi50eXBlo3dlYg==");
+        assert_eq!(encoded.as_str(), "kZGLpHR5cGWjd2Vip3NlcnZpY2Wsc2VydmljZV9uYW1lpG5hbWWpY29tcG9uZW50qHJlc291cmNlqHJlc291cmNlqHRyYWNlX2lkzwAAAAAAAAAHp3NwYW5faWTPAAAAAAAAAGOpcGFyZW50X2lkzwAAAAAAAAABpXN0YXJ00wAAAAAAAAAAqGR1cmF0aW9u0wAAAAA7msoApWVycm9y0gAAAACkbWV0YYGpc3Bhbi50eXBlo3dlYg==");
 
         Ok(())
     }
@@ -136,9 +146,24 @@ pub(crate) mod tests {
     #[test]
     fn test_encode_v05() -> Result<(), Box<dyn std::error::Error>> {
         let traces = get_traces();
-        let encoded = base64::encode(ApiVersion::Version05.encode("service_name", traces)?);
+        let encoded = base64::encode(ApiVersion::Version05.encode("service_name", None, traces)?);
 
-        assert_eq!(encoded.as_str(), "kpWsc2VydmljZV9uYW1lo3dlYqljb21wb25lbnSocmVzb3VyY2Wpc3Bhbi50eXBlkZGczgAAAADOAAAAAs4AAAADzwAAAAAAAAAHzwAAAAAAAABjzwAAAAAAAAAB0wAAAAAAAAAA0wAAAAA7msoA0gAAAAGBzgAAAATOAAAAAYDOAAAAAQ==");
+        assert_eq!(encoded.as_str(), "kpWsc2VydmljZV9uYW1lo3dlYqljb21wb25lbnSocmVzb3VyY2Wpc3Bhbi50eXBlkZGczgAAAADOAAAAAs4AAAADzwAAAAAAAAAHzwAAAAAAAABjzwAAAAAAAAAB0wAAAAAAAAAA0wAAAAA7msoA0gAAAACBzgAAAATOAAAAAYDOAAAAAQ==");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_hostname() -> Result<(), Box<dyn std::error::Error>> {
+        for version in &[ApiVersion::Version03, ApiVersion::Version05] {
+            let encoded = version.encode("service_name", Some("my-host"), get_traces())?;
+            let without_hostname = version.encode("service_name", None, get_traces())?;
+
+            let contains = |needle: &[u8]| encoded.windows(needle.len()).any(|w| w == needle);
+            assert!(contains(b"_dd.hostname"));
+            assert!(contains(b"my-host"));
+            assert!(encoded.len() > without_hostname.len());
+        }
 
         Ok(())
     }
@@ -1,10 +1,11 @@
-use crate::exporter::model::Error;
+use crate::exporter::model::{is_error, Error, DD_HOSTNAME_KEY};
 use opentelemetry::sdk::export::trace;
 use opentelemetry::{Key, Value};
 use std::time::SystemTime;
 
 pub(crate) fn encode(
     service_name: &str,
+    hostname: Option<&str>,
     traces: Vec<Vec<trace::SpanData>>,
 ) -> Result<Vec<u8>, Error> {
     let mut encoded = Vec::new();
@@ -21,11 +22,7 @@ pub(crate) fn encode(
                 .unwrap()
                 .as_nanos() as i64;
 
-            let duration = span
-                .end_time
-                .duration_since(span.start_time)
-                .map(|x| x.as_nanos() as i64)
-                .unwrap_or(0);
+            let duration = span.duration().as_nanos() as i64;
 
             if let Some(Value::String(s)) = span.attributes.get(&Key::new("span.type")) {
                 rmp::encode::write_map_len(&mut encoded, 11)?;
@@ -61,14 +58,19 @@ pub(crate) fn encode(
             rmp::encode::write_i64(&mut encoded, duration)?;
 
             rmp::encode::write_str(&mut encoded, "error")?;
-            rmp::encode::write_i32(&mut encoded, span.status_code as i32)?;
+            rmp::encode::write_i32(&mut encoded, is_error(span.status_code))?;
 
             rmp::encode::write_str(&mut encoded, "meta")?;
-            rmp::encode::write_map_len(&mut encoded, span.attributes.len() as u32)?;
+            let meta_len = span.attributes.len() + hostname.iter().len();
+            rmp::encode::write_map_len(&mut encoded, meta_len as u32)?;
             for (key, value) in span.attributes.iter() {
                 rmp::encode::write_str(&mut encoded, key.as_str())?;
                 rmp::encode::write_str(&mut encoded, value.as_str().as_ref())?;
             }
+            if let Some(hostname) = hostname {
+                rmp::encode::write_str(&mut encoded, DD_HOSTNAME_KEY)?;
+                rmp::encode::write_str(&mut encoded, hostname)?;
+            }
         }
     }
 
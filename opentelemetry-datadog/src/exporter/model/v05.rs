@@ -1,4 +1,5 @@
 use crate::exporter::intern::StringInterner;
+use crate::exporter::model::{is_error, DD_HOSTNAME_KEY};
 use crate::exporter::Error;
 use opentelemetry::sdk::export::trace;
 use opentelemetry::{Key, Value};
@@ -51,10 +52,11 @@ use std::time::SystemTime;
 //
 pub(crate) fn encode(
     service_name: &str,
+    hostname: Option<&str>,
     traces: Vec<Vec<trace::SpanData>>,
 ) -> Result<Vec<u8>, Error> {
     let mut interner = StringInterner::new();
-    let mut encoded_traces = encode_traces(&mut interner, service_name, traces)?;
+    let mut encoded_traces = encode_traces(&mut interner, service_name, hostname, traces)?;
 
     let mut payload = Vec::new();
     rmp::encode::write_array_len(&mut payload, 2)?;
@@ -72,6 +74,7 @@ pub(crate) fn encode(
 fn encode_traces(
     interner: &mut StringInterner,
     service_name: &str,
+    hostname: Option<&str>,
     traces: Vec<Vec<trace::SpanData>>,
 ) -> Result<Vec<u8>, Error> {
     let mut encoded = Vec::new();
@@ -90,11 +93,7 @@ fn encode_traces(
                 .unwrap()
                 .as_nanos() as i64;
 
-            let duration = span
-                .end_time
-                .duration_since(span.start_time)
-                .map(|x| x.as_nanos() as i64)
-                .unwrap_or(0);
+            let duration = span.duration().as_nanos() as i64;
 
             let span_type = match span.attributes.get(&Key::new("span.type")) {
                 Some(Value::String(s)) => interner.intern(s.as_ref()),
@@ -111,12 +110,17 @@ fn encode_traces(
             rmp::encode::write_u64(&mut encoded, span.parent_span_id.to_u64())?;
             rmp::encode::write_i64(&mut encoded, start)?;
             rmp::encode::write_i64(&mut encoded, duration)?;
-            rmp::encode::write_i32(&mut encoded, span.status_code as i32)?;
-            rmp::encode::write_map_len(&mut encoded, span.attributes.len() as u32)?;
+            rmp::encode::write_i32(&mut encoded, is_error(span.status_code))?;
+            let meta_len = span.attributes.len() + hostname.iter().len();
+            rmp::encode::write_map_len(&mut encoded, meta_len as u32)?;
             for (key, value) in span.attributes.iter() {
                 rmp::encode::write_u32(&mut encoded, interner.intern(key.as_str()))?;
                 rmp::encode::write_u32(&mut encoded, interner.intern(value.as_str().as_ref()))?;
             }
+            if let Some(hostname) = hostname {
+                rmp::encode::write_u32(&mut encoded, interner.intern(DD_HOSTNAME_KEY))?;
+                rmp::encode::write_u32(&mut encoded, interner.intern(hostname))?;
+            }
             rmp::encode::write_map_len(&mut encoded, 0)?;
             rmp::encode::write_u32(&mut encoded, span_type)?;
         }
@@ -63,6 +63,18 @@
 //!
 //! Users can always use their own http clients by implementing `HttpClient` trait.
 //!
+//! ## Agent Discovery and Container Tagging
+//!
+//! The agent endpoint defaults to the `DD_TRACE_AGENT_URL` environment
+//! variable, or to `DD_AGENT_HOST` and `DD_TRACE_AGENT_PORT` (`8126` by
+//! default) as set by the Datadog helm chart and ECS integrations. Without
+//! them the agent is expected at `http://127.0.0.1:8126`.
+//!
+//! When running in a container, its id is read from the cgroups of the process
+//! and sent to the agent in the `Datadog-Container-ID` header, so the agent can
+//! tag the traces with the container's metadata. The host the spans were
+//! created on can be reported with `with_hostname`.
+//!
 //! ## Kitchen Sink Full Configuration
 //!
 //! Example showing how to override all configuration options. See the
@@ -73,7 +85,7 @@
 //! ```no_run
 //! use opentelemetry::{KeyValue, trace::Tracer};
 //! use opentelemetry::sdk::{trace::{self, IdGenerator, Sampler}, Resource};
-//! use opentelemetry::sdk::export::trace::ExportResult;
+//! use opentelemetry::trace::TraceError;
 //! use opentelemetry_datadog::{new_pipeline, ApiVersion, Error};
 //! use opentelemetry::global::shutdown_tracer_provider;
 //! use opentelemetry_http::HttpClient;
@@ -87,11 +99,12 @@
 //!
 //! #[async_trait]
 //! impl HttpClient for IsahcClient {
-//!   async fn send(&self, request: http::Request<Vec<u8>>) -> ExportResult {
+//!   async fn send(&self, request: http::Request<Vec<u8>>) -> Result<http::Response<Vec<u8>>, TraceError> {
 //!     let result = self.0.send_async(request).await.map_err(|err| Error::Other(err.to_string()))?;
 //!
 //!     if result.status().is_success() {
-//!       Ok(())
+//!       // the response body is not needed to export spans
+//!       Ok(result.map(|_body| Vec::new()))
 //!     } else {
 //!       Err(Error::Other(result.status().to_string()).into())
 //!     }
@@ -103,6 +116,7 @@
 //!         .with_service_name("my_app")
 //!         .with_version(ApiVersion::Version05)
 //!         .with_agent_endpoint("http://localhost:8126")
+//!         .with_hostname("my-host")
 //!         .with_trace_config(
 //!             trace::config()
 //!                 .with_default_sampler(Sampler::AlwaysOn)
@@ -0,0 +1,280 @@
+//! # HTTP Client Addresses
+//!
+//! Requests passing through proxies reach the server from the address of the
+//! last proxy, the address of the client is only known from the `Forwarded`
+//! or `X-Forwarded-For` headers. These headers can be set by anyone, so they
+//! are only believed for hops added by trusted proxies.
+use http::header::{HeaderMap, FORWARDED};
+use opentelemetry::{Key, KeyValue};
+use std::net::{IpAddr, SocketAddr};
+
+const CLIENT_ADDRESS: Key = Key::from_static_str("client.address");
+const NETWORK_PEER_ADDRESS: Key = Key::from_static_str("network.peer.address");
+const NETWORK_PEER_PORT: Key = Key::from_static_str("network.peer.port");
+
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+
+/// Resolves the address of the client that sent a request.
+///
+/// Forwarded addresses are walked from the closest hop outwards and the first
+/// address not belonging to a trusted proxy is the client. Without trusted
+/// proxies the headers are ignored and the peer of the connection is the
+/// client, so a client cannot spoof its address.
+///
+/// # Examples
+///
+/// ```
+/// use http::HeaderMap;
+/// use opentelemetry_http::ClientAddressResolver;
+///
+/// // requests reach the server through a load balancer in 10.0.0.0/8
+/// let resolver = ClientAddressResolver::new().with_trusted_proxy("10.0.0.0".parse().unwrap(), 8);
+///
+/// let mut headers = HeaderMap::new();
+/// headers.insert("x-forwarded-for", "1.1.1.1, 203.0.113.7".parse().unwrap());
+/// let peer = "10.1.2.3".parse().ok();
+///
+/// // 1.1.1.1 was added by the client itself and is not trusted
+/// assert_eq!(
+///     resolver.client_address(&headers, peer),
+///     Some("203.0.113.7".to_string())
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ClientAddressResolver {
+    trusted_proxies: Vec<(IpAddr, u8)>,
+}
+
+impl ClientAddressResolver {
+    /// Create a resolver that trusts no proxies.
+    pub fn new() -> Self {
+        ClientAddressResolver::default()
+    }
+
+    /// Trust the proxies in the network of `addr` with the given prefix
+    /// length, e.g. `10.0.0.0` and `8` for `10.0.0.0/8`.
+    pub fn with_trusted_proxy(mut self, addr: IpAddr, prefix_len: u8) -> Self {
+        self.trusted_proxies.push((addr, prefix_len));
+        self
+    }
+
+    /// Whether `addr` belongs to a trusted proxy.
+    pub fn is_trusted(&self, addr: IpAddr) -> bool {
+        self.trusted_proxies
+            .iter()
+            .any(|(network, prefix_len)| in_network(addr, *network, *prefix_len))
+    }
+
+    /// The address of the client, given the request headers and the address
+    /// of the connection's peer.
+    ///
+    /// Forwarded hops with an obfuscated identifier instead of an address are
+    /// returned as is, `unknown` hops resolve to `None`.
+    pub fn client_address(&self, headers: &HeaderMap, peer: Option<IpAddr>) -> Option<String> {
+        match peer {
+            Some(peer) if self.is_trusted(peer) => {}
+            _ => return peer.map(|peer| peer.to_string()),
+        }
+
+        let hops = forwarded_for(headers);
+        let mut client = peer.map(|peer| peer.to_string());
+        for hop in hops.iter().rev() {
+            match parse_node(hop) {
+                Some(addr) if self.is_trusted(addr) => client = Some(addr.to_string()),
+                Some(addr) => return Some(addr.to_string()),
+                None if hop.eq_ignore_ascii_case("unknown") => return None,
+                None => return Some(hop.clone()),
+            }
+        }
+        client
+    }
+
+    /// The `client.address`, `network.peer.address` and `network.peer.port`
+    /// attributes of a request.
+    pub fn attributes(&self, headers: &HeaderMap, peer: Option<SocketAddr>) -> Vec<KeyValue> {
+        let mut attributes = Vec::with_capacity(3);
+        if let Some(client) = self.client_address(headers, peer.map(|peer| peer.ip())) {
+            attributes.push(CLIENT_ADDRESS.string(client));
+        }
+        if let Some(peer) = peer {
+            attributes.push(NETWORK_PEER_ADDRESS.string(peer.ip().to_string()));
+            attributes.push(NETWORK_PEER_PORT.i64(i64::from(peer.port())));
+        }
+        attributes
+    }
+}
+
+/// The forwarded hops, from the client to the closest proxy.
+///
+/// The standard `Forwarded` header takes precedence over `X-Forwarded-For`.
+fn forwarded_for(headers: &HeaderMap) -> Vec<String> {
+    let forwarded: Vec<String> = headers
+        .get_all(FORWARDED)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|element| {
+            element.split(';').find_map(|pair| {
+                let mut pair = pair.splitn(2, '=');
+                let key = pair.next()?.trim();
+                let value = pair.next()?.trim();
+                if key.eq_ignore_ascii_case("for") {
+                    Some(value.trim_matches('"').to_string())
+                } else {
+                    None
+                }
+            })
+        })
+        .collect();
+    if !forwarded.is_empty() {
+        return forwarded;
+    }
+
+    headers
+        .get_all(X_FORWARDED_FOR)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|hop| hop.trim().to_string())
+        .filter(|hop| !hop.is_empty())
+        .collect()
+}
+
+/// Parse a forwarded node, an address optionally followed by a port and with
+/// IPv6 addresses in brackets.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    if let Ok(addr) = node.parse::<IpAddr>() {
+        return Some(addr);
+    }
+    if let Ok(addr) = node.parse::<SocketAddr>() {
+        return Some(addr.ip());
+    }
+    // IPv6 in brackets without a port
+    node.strip_prefix('[')
+        .and_then(|node| node.strip_suffix(']'))
+        .and_then(|node| node.parse().ok())
+}
+
+fn in_network(addr: IpAddr, network: IpAddr, prefix_len: u8) -> bool {
+    match (addr, network) {
+        (IpAddr::V4(addr), IpAddr::V4(network)) => {
+            prefix_matches(&addr.octets(), &network.octets(), prefix_len)
+        }
+        (IpAddr::V6(addr), IpAddr::V6(network)) => {
+            prefix_matches(&addr.octets(), &network.octets(), prefix_len)
+        }
+        _ => false,
+    }
+}
+
+fn prefix_matches(addr: &[u8], network: &[u8], prefix_len: u8) -> bool {
+    let prefix_len = std::cmp::min(usize::from(prefix_len), addr.len() * 8);
+    let (bytes, bits) = (prefix_len / 8, prefix_len % 8);
+    if addr[..bytes] != network[..bytes] {
+        return false;
+    }
+    bits == 0 || {
+        let mask = 0xffu8 << (8 - bits);
+        addr[bytes] & mask == network[bytes] & mask
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    fn resolver() -> ClientAddressResolver {
+        ClientAddressResolver::new()
+            .with_trusted_proxy("10.0.0.0".parse().unwrap(), 8)
+            .with_trusted_proxy("fd00::".parse().unwrap(), 8)
+    }
+
+    #[test]
+    fn headers_of_untrusted_peers_are_ignored() {
+        let headers = headers(&[("x-forwarded-for", "203.0.113.7")]);
+        assert_eq!(
+            resolver().client_address(&headers, "198.51.100.1".parse().ok()),
+            Some("198.51.100.1".to_string())
+        );
+        assert_eq!(
+            ClientAddressResolver::new().client_address(&headers, "10.0.0.1".parse().ok()),
+            Some("10.0.0.1".to_string())
+        );
+    }
+
+    #[test]
+    fn skip_trusted_hops() {
+        let peer = "10.0.0.1".parse().ok();
+        let forwarded = headers(&[("x-forwarded-for", "192.0.2.1, 203.0.113.7, 10.0.0.2")]);
+        assert_eq!(
+            resolver().client_address(&forwarded, peer),
+            Some("203.0.113.7".to_string())
+        );
+
+        // all hops are trusted, the furthest one is the client
+        let forwarded = headers(&[("x-forwarded-for", "10.0.0.3, 10.0.0.2")]);
+        assert_eq!(
+            resolver().client_address(&forwarded, peer),
+            Some("10.0.0.3".to_string())
+        );
+    }
+
+    #[test]
+    fn forwarded_header_takes_precedence() {
+        let peer = "10.0.0.1".parse().ok();
+        let forwarded = headers(&[
+            ("x-forwarded-for", "192.0.2.1"),
+            (
+                "forwarded",
+                "for=\"[2001:db8::1]:4711\";proto=https, For=10.0.0.2",
+            ),
+            ("forwarded", "for=\"[fd00::1]\""),
+        ]);
+        assert_eq!(
+            resolver().client_address(&forwarded, peer),
+            Some("2001:db8::1".to_string())
+        );
+
+        let forwarded = headers(&[("forwarded", "for=_hidden, for=10.0.0.2")]);
+        assert_eq!(
+            resolver().client_address(&forwarded, peer),
+            Some("_hidden".to_string())
+        );
+
+        let forwarded = headers(&[("forwarded", "for=unknown")]);
+        assert_eq!(resolver().client_address(&forwarded, peer), None);
+    }
+
+    #[test]
+    fn network_attributes() {
+        let headers = headers(&[("x-forwarded-for", "203.0.113.7")]);
+        let attributes = resolver().attributes(&headers, "10.0.0.1:5000".parse().ok());
+        assert_eq!(
+            attributes,
+            vec![
+                KeyValue::new("client.address", "203.0.113.7"),
+                KeyValue::new("network.peer.address", "10.0.0.1"),
+                KeyValue::new("network.peer.port", 5000),
+            ]
+        );
+    }
+
+    #[test]
+    fn match_networks() {
+        let network = "192.168.0.0".parse().unwrap();
+        assert!(in_network("192.168.255.1".parse().unwrap(), network, 16));
+        assert!(!in_network("192.169.0.1".parse().unwrap(), network, 16));
+        assert!(in_network("192.168.0.1".parse().unwrap(), network, 23));
+        assert!(!in_network("192.168.2.1".parse().unwrap(), network, 23));
+        assert!(in_network("1.2.3.4".parse().unwrap(), network, 0));
+        assert!(!in_network("::1".parse().unwrap(), network, 0));
+    }
+}
@@ -1,9 +1,18 @@
 use std::fmt::Debug;
 
 use async_trait::async_trait;
-use http::Request;
+use http::{Request, Response};
 use opentelemetry::propagation::{Extractor, Injector};
-use opentelemetry::trace::TraceError;
+use opentelemetry::trace::{Span, SpanBuilder, SpanContext, TraceError, Tracer};
+
+mod client_address;
+mod route;
+
+pub use client_address::ClientAddressResolver;
+pub use route::{
+    server_span_builder, server_span_builder_with_namer, DefaultSpanNamer, RouteResolver,
+    RouteTemplates, SpanNamer,
+};
 
 pub struct HeaderInjector<'a>(pub &'a mut http::HeaderMap);
 
@@ -35,45 +44,126 @@ impl<'a> Extractor for HeaderExtractor<'a> {
     }
 }
 
+/// Tracks the attempts of an outgoing request so that each retry span is
+/// linked to the span of the previous attempt.
+///
+/// Resent attempts also record `http.resend_count`, following the semantic
+/// conventions for HTTP retries.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::trace::{Span, Tracer};
+/// use opentelemetry_http::RequestAttempts;
+///
+/// let tracer = opentelemetry::global::tracer("my-client");
+/// let mut attempts = RequestAttempts::new();
+/// for _ in 0..3 {
+///     let span = attempts.start(&tracer, tracer.span_builder("HTTP GET"));
+///     // send the request, break out of the loop on success
+///     span.end();
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RequestAttempts {
+    previous: Option<SpanContext>,
+    resend_count: u32,
+}
+
+impl RequestAttempts {
+    /// Create a tracker for a request that has not been sent yet.
+    pub fn new() -> Self {
+        RequestAttempts::default()
+    }
+
+    /// Start the span of the next attempt to send the request.
+    pub fn start<T: Tracer>(&mut self, tracer: &T, builder: SpanBuilder) -> T::Span {
+        let span = self.span_builder(builder).start(tracer);
+        self.record_attempt(span.span_context().clone());
+        span
+    }
+
+    /// Prepare the span builder of the next attempt to send the request.
+    ///
+    /// The first attempt is left unchanged, later attempts link to the span
+    /// of the previous attempt recorded with [`RequestAttempts::record_attempt`].
+    pub fn span_builder(&self, builder: SpanBuilder) -> SpanBuilder {
+        match &self.previous {
+            Some(previous) => builder.with_resend_of(previous.clone(), self.resend_count + 1),
+            None => builder,
+        }
+    }
+
+    /// Record the span of an attempt started without [`RequestAttempts::start`].
+    pub fn record_attempt(&mut self, span_context: SpanContext) {
+        if self.previous.is_some() {
+            self.resend_count += 1;
+        }
+        self.previous = Some(span_context);
+    }
+
+    /// The number of times the request has been resent.
+    pub fn resend_count(&self) -> u32 {
+        self.resend_count
+    }
+}
+
 /// A minimal interface necessary for export spans over HTTP.
 ///
 /// Users sometime choose http clients that relay on certain runtime. This trait
 /// allows users to bring their choice of http clients.
 #[async_trait]
 pub trait HttpClient: Debug + Send + Sync {
-    /// Send a batch of spans to collectors
-    async fn send(&self, request: Request<Vec<u8>>) -> Result<(), TraceError>;
+    /// Send a request, e.g. a batch of spans to collectors.
+    ///
+    /// Resolves to the response if the server replied with a success status
+    /// code, and to an error otherwise.
+    async fn send(&self, request: Request<Vec<u8>>) -> Result<Response<Vec<u8>>, TraceError>;
 }
 
 #[cfg(feature = "reqwest")]
 mod reqwest {
-    use super::{async_trait, HttpClient, Request, TraceError};
+    use super::{async_trait, HttpClient, Request, Response, TraceError};
     use opentelemetry::sdk::export::ExportError;
     use std::convert::TryInto;
     use thiserror::Error;
 
     #[async_trait]
     impl HttpClient for reqwest::Client {
-        async fn send(&self, request: Request<Vec<u8>>) -> Result<(), TraceError> {
+        async fn send(&self, request: Request<Vec<u8>>) -> Result<Response<Vec<u8>>, TraceError> {
             let request = request.try_into().map_err(ReqwestError::from)?;
-            let _ = self
+            let rsp = self
                 .execute(request)
                 .await
                 .and_then(|rsp| rsp.error_for_status())
                 .map_err(ReqwestError::from)?;
-            Ok(())
+            let mut response = Response::builder().status(rsp.status());
+            if let Some(headers) = response.headers_mut() {
+                *headers = rsp.headers().clone();
+            }
+            let body = rsp.bytes().await.map_err(ReqwestError::from)?;
+            Ok(response
+                .body(body.to_vec())
+                .expect("response should always be valid"))
         }
     }
 
     #[async_trait]
     impl HttpClient for reqwest::blocking::Client {
-        async fn send(&self, request: Request<Vec<u8>>) -> Result<(), TraceError> {
-            let _ = request
+        async fn send(&self, request: Request<Vec<u8>>) -> Result<Response<Vec<u8>>, TraceError> {
+            let rsp = request
                 .try_into()
                 .and_then(|req| self.execute(req))
                 .and_then(|rsp| rsp.error_for_status())
                 .map_err(ReqwestError::from)?;
-            Ok(())
+            let mut response = Response::builder().status(rsp.status());
+            if let Some(headers) = response.headers_mut() {
+                *headers = rsp.headers().clone();
+            }
+            let body = rsp.bytes().map_err(ReqwestError::from)?;
+            Ok(response
+                .body(body.to_vec())
+                .expect("response should always be valid"))
         }
     }
 
@@ -90,27 +180,52 @@ mod reqwest {
 
 #[cfg(feature = "surf")]
 mod surf {
-    use super::{async_trait, HttpClient, Request, TraceError};
+    use super::{async_trait, HttpClient, Request, Response, TraceError};
     use opentelemetry::sdk::export::ExportError;
     use std::fmt::{Display, Formatter};
+    use std::str::FromStr;
 
     #[async_trait]
     impl HttpClient for surf::Client {
-        async fn send(&self, request: Request<Vec<u8>>) -> Result<(), TraceError> {
+        async fn send(&self, request: Request<Vec<u8>>) -> Result<Response<Vec<u8>>, TraceError> {
             let (parts, body) = request.into_parts();
             let uri = parts
                 .uri
                 .to_string()
                 .parse()
                 .map_err(|_err: surf::http::url::ParseError| TraceError::from("error parse url"))?;
-
-            let req = surf::Request::builder(surf::http::Method::Post, uri)
-                .content_type("application/json")
-                .body(body);
-            let result = self.send(req).await.map_err::<SurfError, _>(Into::into)?;
+            let method = surf::http::Method::from_str(parts.method.as_str())
+                .map_err(|_err| TraceError::from("unsupported http method"))?;
+
+            // exporters send JSON unless they say otherwise, metadata GETs have no body
+            let default_content_type =
+                !body.is_empty() && !parts.headers.contains_key(http::header::CONTENT_TYPE);
+            let mut req = surf::Request::builder(method, uri);
+            if default_content_type {
+                req = req.content_type("application/json");
+            }
+            req = req.body(body);
+            for (name, value) in parts.headers.iter() {
+                if let Ok(value) = value.to_str() {
+                    req = req.header(name.as_str(), value);
+                }
+            }
+            let mut result = self.send(req).await.map_err::<SurfError, _>(Into::into)?;
 
             if result.status().is_success() {
-                Ok(())
+                let body = result
+                    .body_bytes()
+                    .await
+                    .map_err::<SurfError, _>(Into::into)?;
+                let mut response = Response::builder().status(u16::from(result.status()));
+                for (name, values) in result.iter() {
+                    for value in values.iter() {
+                        response = response.header(name.as_str(), value.as_str());
+                    }
+                }
+                Ok(response
+                    .body(body)
+                    .map_err(|_err| TraceError::from("invalid http response"))?)
             } else {
                 Err(SurfError(surf::Error::from_str(
                     result.status(),
@@ -145,16 +260,139 @@ mod surf {
     }
 }
 
+#[cfg(all(feature = "fetch", target_arch = "wasm32"))]
+pub use fetch::FetchClient;
+
+#[cfg(all(feature = "fetch", target_arch = "wasm32"))]
+mod fetch {
+    use super::{async_trait, HttpClient, Request, Response, TraceError};
+    use js_sys::{Array, Promise, Uint8Array};
+    use opentelemetry::sdk::export::ExportError;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use thiserror::Error;
+    use wasm_bindgen::{prelude::wasm_bindgen, JsCast, JsValue};
+    use wasm_bindgen_futures::JsFuture;
+
+    /// An [`HttpClient`] sending requests with the `fetch` API of browsers and
+    /// other JavaScript environments, for `wasm32` targets.
+    ///
+    /// Cross origin requests require the collector to allow the origin of the
+    /// page, e.g. with the `cors` settings of the OpenTelemetry collector.
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct FetchClient;
+
+    #[wasm_bindgen]
+    extern "C" {
+        // the global `fetch`, available in windows as well as workers
+        #[wasm_bindgen(js_name = fetch)]
+        fn fetch_with_request(request: &web_sys::Request) -> Promise;
+    }
+
+    #[async_trait]
+    impl HttpClient for FetchClient {
+        async fn send(&self, request: Request<Vec<u8>>) -> Result<Response<Vec<u8>>, TraceError> {
+            AssertSend(Box::pin(send_request(request))).await
+        }
+    }
+
+    async fn send_request(request: Request<Vec<u8>>) -> Result<Response<Vec<u8>>, TraceError> {
+        let (parts, body) = request.into_parts();
+        let headers = web_sys::Headers::new().map_err(FetchError::from)?;
+        for (name, value) in parts.headers.iter() {
+            if let Ok(value) = value.to_str() {
+                headers
+                    .append(name.as_str(), value)
+                    .map_err(FetchError::from)?;
+            }
+        }
+        let init = web_sys::RequestInit::new();
+        init.set_method(parts.method.as_str());
+        init.set_headers(&headers);
+        init.set_body(&Uint8Array::from(body.as_slice()));
+        let request = web_sys::Request::new_with_str_and_init(&parts.uri.to_string(), &init)
+            .map_err(FetchError::from)?;
+
+        let rsp: web_sys::Response = JsFuture::from(fetch_with_request(&request))
+            .await
+            .map_err(FetchError::from)?
+            .unchecked_into();
+        if !rsp.ok() {
+            return Err(TraceError::from(format!(
+                "Expected success response, got {} ({})",
+                rsp.status(),
+                rsp.status_text()
+            )));
+        }
+
+        let mut response = Response::builder().status(rsp.status());
+        if let Ok(Some(entries)) = js_sys::try_iter(&rsp.headers()) {
+            for entry in entries.flatten() {
+                let entry: Array = entry.unchecked_into();
+                if let (Some(name), Some(value)) =
+                    (entry.get(0).as_string(), entry.get(1).as_string())
+                {
+                    response = response.header(name.as_str(), value.as_str());
+                }
+            }
+        }
+        let body = JsFuture::from(rsp.array_buffer().map_err(FetchError::from)?)
+            .await
+            .map_err(FetchError::from)?;
+        response
+            .body(Uint8Array::new(&body).to_vec())
+            .map_err(|_err| TraceError::from("invalid http response"))
+    }
+
+    /// Wrapper of the web fetch API future marked as Send.
+    ///
+    /// The module is only built for `wasm32` targets, whose web APIs are single
+    /// threaded, so the future never moves across threads. Since all
+    /// opentelemetry futures are required to be Send, we mark this future as Send.
+    struct AssertSend<F>(Pin<Box<F>>);
+
+    unsafe impl<F> Send for AssertSend<F> {}
+
+    impl<F: Future> Future for AssertSend<F> {
+        type Output = F::Output;
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            self.0.as_mut().poll(cx)
+        }
+    }
+
+    #[derive(Debug, Error)]
+    #[error("{0}")]
+    struct FetchError(String);
+
+    impl From<JsValue> for FetchError {
+        fn from(value: JsValue) -> Self {
+            FetchError(
+                js_sys::JSON::stringify(&value)
+                    .map(String::from)
+                    .unwrap_or_else(|_| "unknown error".to_string()),
+            )
+        }
+    }
+
+    impl ExportError for FetchError {
+        fn exporter_name(&self) -> &'static str {
+            "fetch"
+        }
+    }
+}
+
 #[cfg(feature = "isahc")]
 mod isahc {
-    use super::{async_trait, HttpClient, Request, TraceError};
+    use super::{async_trait, HttpClient, Request, Response, TraceError};
+    use futures_util::io::AsyncReadExt;
     use opentelemetry::sdk::export::ExportError;
     use thiserror::Error;
 
     #[async_trait]
     impl HttpClient for isahc::HttpClient {
-        async fn send(&self, request: Request<Vec<u8>>) -> Result<(), TraceError> {
-            let res = self.send_async(request).await.map_err(IsahcError::from)?;
+        async fn send(&self, request: Request<Vec<u8>>) -> Result<Response<Vec<u8>>, TraceError> {
+            let mut res = self.send_async(request).await.map_err(IsahcError::from)?;
 
             if !res.status().is_success() {
                 return Err(TraceError::from(format!(
@@ -163,7 +401,19 @@ mod isahc {
                 )));
             }
 
-            Ok(())
+            let mut body = Vec::new();
+            res.body_mut()
+                .read_to_end(&mut body)
+                .await
+                .map_err(IsahcIoError::from)?;
+            let mut response = Response::builder().status(res.status());
+            if let Some(headers) = response.headers_mut() {
+                *headers = res.headers().clone();
+            }
+
+            Ok(response
+                .body(body)
+                .expect("response should always be valid"))
         }
     }
 
@@ -171,6 +421,16 @@ mod isahc {
     #[error(transparent)]
     struct IsahcError(#[from] isahc::Error);
 
+    #[derive(Debug, Error)]
+    #[error(transparent)]
+    struct IsahcIoError(#[from] std::io::Error);
+
+    impl ExportError for IsahcIoError {
+        fn exporter_name(&self) -> &'static str {
+            "isahc"
+        }
+    }
+
     impl ExportError for IsahcError {
         fn exporter_name(&self) -> &'static str {
             "isahc"
@@ -194,6 +454,55 @@ mod tests {
         )
     }
 
+    #[test]
+    fn request_attempts_link_previous_attempt() {
+        use opentelemetry::trace::{SpanId, TraceId, TRACE_FLAG_SAMPLED};
+        use opentelemetry::{Key, KeyValue};
+
+        let span_context = |id| {
+            SpanContext::new(
+                TraceId::from_u128(1),
+                SpanId::from_u64(id),
+                TRACE_FLAG_SAMPLED,
+                false,
+                Default::default(),
+            )
+        };
+        let resend_count = |builder: &SpanBuilder| {
+            builder.attributes.as_ref().and_then(|attributes| {
+                attributes
+                    .iter()
+                    .find(|kv| kv.key == Key::new("http.resend_count"))
+                    .map(|kv| kv.value.clone())
+            })
+        };
+        let mut attempts = RequestAttempts::new();
+
+        let first = attempts.span_builder(SpanBuilder::from_name("HTTP GET"));
+        assert!(first.links.is_none());
+        assert_eq!(resend_count(&first), None);
+        attempts.record_attempt(span_context(1));
+
+        let second = attempts.span_builder(
+            SpanBuilder::from_name("HTTP GET").with_attributes(vec![KeyValue::new("a", "b")]),
+        );
+        assert_eq!(
+            second.links.as_ref().unwrap()[0].span_context(),
+            &span_context(1)
+        );
+        assert_eq!(second.attributes.as_ref().unwrap().len(), 2);
+        assert_eq!(resend_count(&second), Some(1i64.into()));
+        attempts.record_attempt(span_context(2));
+
+        let third = attempts.span_builder(SpanBuilder::from_name("HTTP GET"));
+        assert_eq!(
+            third.links.as_ref().unwrap()[0].span_context(),
+            &span_context(2)
+        );
+        assert_eq!(resend_count(&third), Some(2i64.into()));
+        assert_eq!(attempts.resend_count(), 1);
+    }
+
     #[test]
     fn http_headers_keys() {
         let mut carrier = http::HeaderMap::new();
@@ -0,0 +1,300 @@
+//! # HTTP Server Routes
+//!
+//! Span names of server requests should have a low cardinality, so they are
+//! named after the route template matched by the request, e.g. `/users/{id}`,
+//! instead of the raw path. Web frameworks usually know the matched route,
+//! middlewares expose it through a [`RouteResolver`]. How the method and route
+//! are combined into the span name is decided by a [`SpanNamer`], so naming
+//! conventions can be configured in one place for all middlewares.
+use http::{Method, Request};
+use opentelemetry::trace::{SpanBuilder, SpanKind};
+use opentelemetry::Key;
+use std::borrow::Cow;
+
+const HTTP_METHOD: Key = Key::from_static_str("http.method");
+const HTTP_TARGET: Key = Key::from_static_str("http.target");
+const HTTP_ROUTE: Key = Key::from_static_str("http.route");
+
+/// Resolves the route template a server request was matched against.
+///
+/// Closures taking the method and path of the request implement this trait,
+/// so a middleware can pass the route known to its framework.
+pub trait RouteResolver: Send + Sync {
+    /// The template of the route matching the request, e.g. `/users/{id}`,
+    /// or `None` if no route matches.
+    fn resolve(&self, method: &Method, path: &str) -> Option<String>;
+}
+
+impl<F> RouteResolver for F
+where
+    F: Fn(&Method, &str) -> Option<String> + Send + Sync,
+{
+    fn resolve(&self, method: &Method, path: &str) -> Option<String> {
+        self(method, path)
+    }
+}
+
+/// Names the spans of requests received by a server.
+///
+/// Closures taking the method and resolved route of the request implement
+/// this trait.
+///
+/// # Examples
+///
+/// ```
+/// use http::Method;
+/// use opentelemetry_http::SpanNamer;
+/// use std::borrow::Cow;
+///
+/// // name spans after the route only
+/// let namer = |method: &Method, route: Option<&str>| -> Cow<'static, str> {
+///     match route {
+///         Some(route) => route.to_string().into(),
+///         None => format!("HTTP {}", method).into(),
+///     }
+/// };
+/// assert_eq!(namer.name(&Method::GET, Some("/users/{id}")), "/users/{id}");
+/// ```
+pub trait SpanNamer: Send + Sync {
+    /// The name of the span of a request with the given method, `route` is
+    /// `None` if the request did not match a route.
+    fn name(&self, method: &Method, route: Option<&str>) -> Cow<'static, str>;
+}
+
+impl<F> SpanNamer for F
+where
+    F: Fn(&Method, Option<&str>) -> Cow<'static, str> + Send + Sync,
+{
+    fn name(&self, method: &Method, route: Option<&str>) -> Cow<'static, str> {
+        self(method, route)
+    }
+}
+
+/// Names spans `{method} {route}`, e.g. `GET /users/{id}`, or `HTTP {method}`
+/// if the request did not match a route.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultSpanNamer;
+
+impl SpanNamer for DefaultSpanNamer {
+    fn name(&self, method: &Method, route: Option<&str>) -> Cow<'static, str> {
+        match route {
+            Some(route) => format!("{} {}", method, route).into(),
+            None => format!("HTTP {}", method).into(),
+        }
+    }
+}
+
+/// Resolves routes by matching the path against a list of templates.
+///
+/// Path parameters are written as `{name}` or `:name` and match exactly one
+/// segment, `{*name}` or `*name` match the remaining segments. Templates are
+/// tried in the order they were added.
+///
+/// # Examples
+///
+/// ```
+/// use http::Method;
+/// use opentelemetry_http::{RouteResolver, RouteTemplates};
+///
+/// let routes = RouteTemplates::new()
+///     .with_route("/users/{id}")
+///     .with_route("/static/{*file}");
+///
+/// assert_eq!(
+///     routes.resolve(&Method::GET, "/users/42"),
+///     Some("/users/{id}".to_string())
+/// );
+/// assert_eq!(routes.resolve(&Method::GET, "/login"), None);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RouteTemplates {
+    routes: Vec<(String, Vec<Segment>)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Literal(String),
+    Param,
+    Wildcard,
+}
+
+impl RouteTemplates {
+    /// Create a resolver without any routes.
+    pub fn new() -> Self {
+        RouteTemplates::default()
+    }
+
+    /// Add a route template.
+    pub fn with_route<T: Into<String>>(mut self, template: T) -> Self {
+        let template = template.into();
+        let segments = segments(&template)
+            .map(|segment| {
+                if segment.starts_with("{*") || segment.starts_with('*') {
+                    Segment::Wildcard
+                } else if segment.starts_with('{') || segment.starts_with(':') {
+                    Segment::Param
+                } else {
+                    Segment::Literal(segment.to_string())
+                }
+            })
+            .collect();
+        self.routes.push((template, segments));
+        self
+    }
+}
+
+impl RouteResolver for RouteTemplates {
+    fn resolve(&self, _method: &Method, path: &str) -> Option<String> {
+        let path: Vec<&str> = segments(path).collect();
+        self.routes
+            .iter()
+            .find(|(_, template)| matches(template, &path))
+            .map(|(route, _)| route.clone())
+    }
+}
+
+fn segments(path: &str) -> impl Iterator<Item = &str> {
+    path.trim_matches('/').split('/')
+}
+
+fn matches(template: &[Segment], path: &[&str]) -> bool {
+    match (template.split_first(), path.split_first()) {
+        (Some((Segment::Wildcard, _)), _) => true,
+        (Some((Segment::Param, template)), Some((_, path))) => matches(template, path),
+        (Some((Segment::Literal(literal), template)), Some((segment, path))) => {
+            literal == segment && matches(template, path)
+        }
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Prepare the span of a request received by a server.
+///
+/// The span is named by the [`DefaultSpanNamer`] after the route resolved for
+/// the request, and carries the `http.method`, `http.target` and `http.route`
+/// attributes.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::trace::Tracer;
+/// use opentelemetry_http::{server_span_builder, RouteTemplates};
+///
+/// let routes = RouteTemplates::new().with_route("/users/{id}");
+/// let request = http::Request::get("/users/42").body(()).unwrap();
+///
+/// let tracer = opentelemetry::global::tracer("my-server");
+/// let span = server_span_builder(&request, &routes).start(&tracer);
+/// ```
+pub fn server_span_builder<B>(request: &Request<B>, resolver: &dyn RouteResolver) -> SpanBuilder {
+    server_span_builder_with_namer(request, resolver, &DefaultSpanNamer)
+}
+
+/// Prepare the span of a request received by a server, named by `namer`.
+pub fn server_span_builder_with_namer<B>(
+    request: &Request<B>,
+    resolver: &dyn RouteResolver,
+    namer: &dyn SpanNamer,
+) -> SpanBuilder {
+    let method = request.method();
+    let target = request
+        .uri()
+        .path_and_query()
+        .map(|target| target.as_str())
+        .unwrap_or_else(|| request.uri().path());
+    let mut attributes = vec![
+        HTTP_METHOD.string(method.as_str().to_string()),
+        HTTP_TARGET.string(target.to_string()),
+    ];
+
+    let route = resolver.resolve(method, request.uri().path());
+    let name = namer.name(method, route.as_deref());
+    if let Some(route) = route {
+        attributes.push(HTTP_ROUTE.string(route));
+    }
+
+    SpanBuilder::from_name(name)
+        .with_kind(SpanKind::Server)
+        .with_attributes(attributes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::KeyValue;
+
+    #[test]
+    fn match_route_templates() {
+        let routes = RouteTemplates::new()
+            .with_route("/")
+            .with_route("/users/{id}")
+            .with_route("/users/:id/orders/:order")
+            .with_route("/static/{*file}");
+        let resolve = |path| routes.resolve(&Method::GET, path);
+
+        assert_eq!(resolve("/"), Some("/".to_string()));
+        assert_eq!(resolve("/users/42"), Some("/users/{id}".to_string()));
+        assert_eq!(resolve("/users/42/"), Some("/users/{id}".to_string()));
+        assert_eq!(
+            resolve("/users/42/orders/7"),
+            Some("/users/:id/orders/:order".to_string())
+        );
+        assert_eq!(
+            resolve("/static/css/main.css"),
+            Some("/static/{*file}".to_string())
+        );
+        assert_eq!(resolve("/users"), None);
+        assert_eq!(resolve("/users/42/orders"), None);
+    }
+
+    #[test]
+    fn span_named_after_route() {
+        let resolver = |_: &Method, path: &str| {
+            if path.starts_with("/users/") {
+                Some("/users/{id}".to_string())
+            } else {
+                None
+            }
+        };
+
+        let request = Request::get("/users/42?verbose=1").body(()).unwrap();
+        let builder = server_span_builder(&request, &resolver);
+        assert_eq!(builder.name, "GET /users/{id}");
+        assert_eq!(builder.span_kind, Some(SpanKind::Server));
+        assert_eq!(
+            builder.attributes,
+            Some(vec![
+                KeyValue::new("http.method", "GET"),
+                KeyValue::new("http.target", "/users/42?verbose=1"),
+                KeyValue::new("http.route", "/users/{id}"),
+            ])
+        );
+
+        let request = Request::post("/login").body(()).unwrap();
+        let builder = server_span_builder(&request, &resolver);
+        assert_eq!(builder.name, "HTTP POST");
+        assert_eq!(builder.attributes.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn custom_span_namer() {
+        let routes = RouteTemplates::new().with_route("/users/{id}");
+        let namer = |method: &Method, route: Option<&str>| -> Cow<'static, str> {
+            format!(
+                "{}:{}",
+                route.unwrap_or("unknown"),
+                method.as_str().to_lowercase()
+            )
+            .into()
+        };
+
+        let request = Request::get("/users/42").body(()).unwrap();
+        let builder = server_span_builder_with_namer(&request, &routes, &namer);
+        assert_eq!(builder.name, "/users/{id}:get");
+
+        let request = Request::delete("/login").body(()).unwrap();
+        let builder = server_span_builder_with_namer(&request, &routes, &namer);
+        assert_eq!(builder.name, "unknown:delete");
+    }
+}
@@ -4,8 +4,8 @@ use crate::exporter::thrift::{
     jaeger,
 };
 use crate::exporter::transport::{TBufferChannel, TNoopChannel};
-use std::fmt;
 use std::net::{ToSocketAddrs, UdpSocket};
+use std::{fmt, io, mem};
 use thrift::{
     protocol::{TCompactInputProtocol, TCompactOutputProtocol},
     transport::{ReadHalf, TIoChannel, WriteHalf},
@@ -14,6 +14,10 @@ use thrift::{
 /// The max size of UDP packet we want to send, synced with jaeger-agent
 const UDP_PACKET_MAX_LENGTH: usize = 65_000;
 
+/// Bytes reserved for the span list header and the sequence number, whose
+/// encoded length grows with their values.
+const BATCH_SIZE_SLACK: usize = 10;
+
 struct BufferClient {
     buffer: ReadHalf<TBufferChannel>,
     client: agent::AgentSyncClient<
@@ -61,12 +65,24 @@ impl AgentAsyncClientUDP {
 
         let conn = UdpSocket::bind("0.0.0.0:0")?;
         conn.connect(host_port)?;
+        #[cfg(any(feature = "async-std", feature = "tokio"))]
+        conn.set_nonblocking(true)?;
+
+        #[cfg(feature = "tokio")]
+        let conn = {
+            // the socket is registered with the reactor of the current runtime
+            if let Err(err) = tokio::runtime::Handle::try_current() {
+                return Err(thrift::new_transport_error(
+                    thrift::TransportErrorKind::NotOpen,
+                    err.to_string(),
+                ));
+            }
+            tokio::net::UdpSocket::from_std(conn)?
+        };
 
         Ok(AgentAsyncClientUDP {
-            #[cfg(all(not(feature = "async-std"), not(feature = "tokio")))]
+            #[cfg(any(feature = "tokio", not(feature = "async-std")))]
             conn,
-            #[cfg(feature = "tokio")]
-            conn: tokio::net::UdpSocket::from_std(conn)?,
             #[cfg(all(feature = "async-std", not(feature = "tokio")))]
             conn: async_std::net::UdpSocket::from(conn),
             buffer_client: BufferClient { buffer, client },
@@ -75,9 +91,76 @@ impl AgentAsyncClientUDP {
     }
 
     /// Emit standard Jaeger batch
+    ///
+    /// Batches exceeding the max packet size are split into several packets.
+    /// Spans too large to fit into a packet on their own are dropped and
+    /// reported as an error once the other spans are sent.
     pub(crate) async fn emit_batch(&mut self, batch: jaeger::Batch) -> thrift::Result<()> {
+        let (payloads, dropped) = self.serialize_batch(batch)?;
+
+        // Write async to socket, reading from buffer
+        for payload in payloads {
+            write_to_socket(self, payload).await?;
+        }
+
+        if dropped > 0 {
+            return Err(thrift::ProtocolError::new(
+                thrift::ProtocolErrorKind::SizeLimit,
+                format!(
+                    "jaeger exporter dropped {} spans over max UDP packet size of {} bytes",
+                    dropped, self.max_packet_size,
+                ),
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Split the batch into payloads under the max packet size, returns the
+    /// payloads and the number of dropped spans.
+    fn serialize_batch(&mut self, batch: jaeger::Batch) -> thrift::Result<(Vec<Vec<u8>>, usize)> {
+        let jaeger::Batch { process, spans } = batch;
+
+        // size of a batch without spans, each span adds its own encoded size
+        self.buffer_client
+            .client
+            .emit_batch(jaeger::Batch::new(process.clone(), Vec::new()))?;
+        let overhead = self.buffer_client.buffer.take_bytes().len() + BATCH_SIZE_SLACK;
+
+        let mut payloads = Vec::new();
+        let mut dropped = 0;
+        let mut chunk = Vec::new();
+        let mut chunk_size = overhead;
+        for span in spans {
+            let span_size = encoded_size(&span)?;
+            if overhead + span_size > self.max_packet_size {
+                dropped += 1;
+                continue;
+            }
+            if chunk_size + span_size > self.max_packet_size {
+                payloads.push(self.serialize(process.clone(), mem::take(&mut chunk))?);
+                chunk_size = overhead;
+            }
+            chunk_size += span_size;
+            chunk.push(span);
+        }
+        if !chunk.is_empty() {
+            payloads.push(self.serialize(process, chunk)?);
+        }
+
+        Ok((payloads, dropped))
+    }
+
+    fn serialize(
+        &mut self,
+        process: jaeger::Process,
+        spans: Vec<jaeger::Span>,
+    ) -> thrift::Result<Vec<u8>> {
         // Write payload to buffer
-        self.buffer_client.client.emit_batch(batch)?;
+        self.buffer_client
+            .client
+            .emit_batch(jaeger::Batch::new(process, spans))?;
         let payload = self.buffer_client.buffer.take_bytes();
 
         if payload.len() > self.max_packet_size {
@@ -92,9 +175,27 @@ impl AgentAsyncClientUDP {
             .into());
         }
 
-        // Write async to socket, reading from buffer
-        write_to_socket(self, payload).await?;
+        Ok(payload)
+    }
+}
+
+/// Number of bytes the span takes up in a compact encoded batch.
+fn encoded_size(span: &jaeger::Span) -> thrift::Result<usize> {
+    let mut counter = ByteCounter(0);
+    span.write_to_out_protocol(&mut TCompactOutputProtocol::new(&mut counter))?;
+    Ok(counter.0)
+}
+
+/// Writer discarding the bytes written to it, only counting them.
+struct ByteCounter(usize);
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
 
+    fn flush(&mut self) -> io::Result<()> {
         Ok(())
     }
 }
@@ -119,3 +220,27 @@ async fn write_to_socket(client: &mut AgentAsyncClientUDP, payload: Vec<u8>) ->
 
     Ok(())
 }
+
+#[cfg(all(test, not(feature = "tokio")))]
+mod tests {
+    use super::*;
+
+    fn span(operation_name: String) -> jaeger::Span {
+        jaeger::Span::new(1, 2, 3, 0, operation_name, None, 1, 0, 1, None, None)
+    }
+
+    #[test]
+    fn split_batches_over_max_packet_size() -> thrift::Result<()> {
+        let mut client = AgentAsyncClientUDP::new("127.0.0.1:6831", Some(1_000))?;
+        let mut spans: Vec<_> = (0..50).map(|i| span(format!("span-{}", i))).collect();
+        spans.push(span("x".repeat(2_000)));
+        let process = jaeger::Process::new("test".to_string(), None);
+
+        let (payloads, dropped) = client.serialize_batch(jaeger::Batch::new(process, spans))?;
+
+        assert_eq!(dropped, 1);
+        assert!(payloads.len() > 1);
+        assert!(payloads.iter().all(|payload| payload.len() <= 1_000));
+        Ok(())
+    }
+}
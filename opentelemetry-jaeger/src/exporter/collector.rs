@@ -68,7 +68,8 @@ mod collector_client {
                 .expect("request should always be valid");
 
             // Send request to collector
-            self.client.send(req).await
+            self.client.send(req).await?;
+            Ok(())
         }
     }
 }
@@ -12,6 +12,10 @@ const ENV_AGENT_HOST: &str = "OTEL_EXPORTER_JAEGER_AGENT_HOST";
 /// e.g. 6832
 const ENV_AGENT_PORT: &str = "OTEL_EXPORTER_JAEGER_AGENT_PORT";
 
+/// The max size of UDP packets sent to the Jaeger agent in bytes.
+/// e.g. 65000
+const ENV_AGENT_MAX_PACKET_SIZE: &str = "OTEL_EXPORTER_JAEGER_AGENT_MAX_PACKET_SIZE";
+
 /// HTTP endpoint for Jaeger collector.
 /// e.g. "http://localhost:14250"
 #[cfg(feature = "collector_client")]
@@ -35,6 +39,13 @@ pub(crate) fn assign_attrs(mut builder: PipelineBuilder) -> PipelineBuilder {
         builder = builder.with_agent_endpoint(format!("{}:{}", host.trim(), port.trim()));
     }
 
+    if let Some(max_packet_size) = env::var(ENV_AGENT_MAX_PACKET_SIZE)
+        .ok()
+        .and_then(|size| size.trim().parse().ok())
+    {
+        builder = builder.with_max_packet_size(max_packet_size);
+    }
+
     #[cfg(feature = "collector_client")]
     {
         if let Some(endpoint) = env::var(ENV_ENDPOINT).ok().filter(|var| !var.is_empty()) {
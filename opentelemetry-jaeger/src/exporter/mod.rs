@@ -20,7 +20,7 @@ use collector::CollectorAsyncClientHttp;
 #[allow(unused_imports)] // this is actually used to configure authentication
 use isahc::prelude::Configurable;
 
-use opentelemetry::sdk::export::ExportError;
+use opentelemetry::sdk::export::{trace::factory::ExporterFactory, ExportError};
 use opentelemetry::trace::TraceError;
 use opentelemetry::{
     global, sdk,
@@ -63,6 +63,22 @@ pub fn new_pipeline() -> PipelineBuilder {
     PipelineBuilder::default()
 }
 
+/// Register the `jaeger` span exporter with `factory`.
+///
+/// The exporter is configured like a [`new_pipeline`] without further
+/// settings, i.e. from the `OTEL_EXPORTER_JAEGER_*` environment variables.
+/// With the `tokio` feature the agent socket is registered with the current
+/// tokio runtime, building the exporter outside of one returns an error.
+pub fn register_exporter(factory: ExporterFactory) -> ExporterFactory {
+    factory.with_exporter("jaeger", || Ok(Box::new(new_pipeline().init_exporter()?)))
+}
+
+/// Create an [`ExporterFactory`] providing the `jaeger` span exporter in
+/// addition to the exporters of the SDK.
+pub fn exporter_factory() -> ExporterFactory {
+    register_exporter(ExporterFactory::new())
+}
+
 /// Jaeger span exporter
 #[derive(Debug)]
 pub struct Exporter {
@@ -236,6 +252,9 @@ impl PipelineBuilder {
     }
 
     /// Assign the max packet size in bytes. Jaeger defaults is 65000.
+    ///
+    /// Batches sent to the agent are split into packets of at most this size.
+    /// Can also be set with `OTEL_EXPORTER_JAEGER_AGENT_MAX_PACKET_SIZE`.
     pub fn with_max_packet_size(mut self, max_packet_size: usize) -> Self {
         self.max_packet_size = Some(max_packet_size);
         self
@@ -249,7 +268,11 @@ impl PipelineBuilder {
         }
     }
 
-    /// Assign the http client to use
+    /// Assign the http client used to submit spans to the collector.
+    ///
+    /// The client takes precedence over the default client of the enabled
+    /// `*_collector_client` feature, the collector username and password are
+    /// not applied to it.
     #[cfg(feature = "collector_client")]
     pub fn with_http_client<T: HttpClient + 'static>(mut self, client: T) -> Self {
         self.client = Some(Box::new(client));
@@ -309,81 +332,11 @@ impl PipelineBuilder {
             .transpose()
             .map_err::<Error, _>(Into::into)?
         {
-            #[cfg(all(
-                not(feature = "isahc_collector_client"),
-                not(feature = "surf_collector_client"),
-                not(feature = "reqwest_collector_client"),
-                not(feature = "reqwest_blocking_collector_client")
-            ))]
-            let client = self.client.ok_or(crate::Error::NoHttpClient)?;
-
-            #[cfg(feature = "isahc_collector_client")]
-            let client = self.client.unwrap_or({
-                let mut builder = isahc::HttpClient::builder();
-                if let (Some(username), Some(password)) =
-                    (self.collector_username, self.collector_password)
-                {
-                    builder = builder
-                        .authentication(isahc::auth::Authentication::basic())
-                        .credentials(isahc::auth::Credentials::new(username, password));
-                }
-
-                Box::new(builder.build().map_err(|err| {
-                    crate::Error::ThriftAgentError(::thrift::Error::from(std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        err.to_string(),
-                    )))
-                })?)
-            });
-
-            #[cfg(all(
-                not(feature = "isahc_collector_client"),
-                not(feature = "surf_collector_client"),
-                any(
-                    feature = "reqwest_collector_client",
-                    feature = "reqwest_blocking_collector_client"
-                )
-            ))]
-            let client = self.client.unwrap_or({
-                #[cfg(feature = "reqwest_collector_client")]
-                let mut builder = reqwest::ClientBuilder::new();
-                #[cfg(all(
-                    not(feature = "reqwest_collector_client"),
-                    feature = "reqwest_blocking_collector_client"
-                ))]
-                let mut builder = reqwest::blocking::ClientBuilder::new();
-                if let (Some(username), Some(password)) =
-                    (self.collector_username, self.collector_password)
-                {
-                    let mut map = http::HeaderMap::with_capacity(1);
-                    let auth_header_val =
-                        headers::Authorization::basic(username.as_str(), password.as_str());
-                    map.insert(http::header::AUTHORIZATION, auth_header_val.0.encode());
-                    builder = builder.default_headers(map);
-                }
-                let client: Box<dyn HttpClient> =
-                    Box::new(builder.build().map_err::<crate::Error, _>(Into::into)?);
-                client
-            });
-
-            #[cfg(all(
-                not(feature = "isahc_collector_client"),
-                feature = "surf_collector_client",
-                not(feature = "reqwest_collector_client"),
-                not(feature = "reqwest_blocking_collector_client")
-            ))]
-            let client = self.client.unwrap_or({
-                let client = if let (Some(username), Some(password)) =
-                    (self.collector_username, self.collector_password)
-                {
-                    let auth = surf::http::auth::BasicAuth::new(username, password);
-                    surf::Client::new().with(BasicAuthMiddleware(auth))
-                } else {
-                    surf::Client::new()
-                };
-
-                Box::new(client)
-            });
+            // only build a default client if none was provided
+            let client = match self.client {
+                Some(client) => client,
+                None => default_http_client(self.collector_username, self.collector_password)?,
+            };
 
             let collector = CollectorAsyncClientHttp::new(collector_endpoint, client);
             Ok((self.process, uploader::BatchUploader::Collector(collector)))
@@ -418,6 +371,89 @@ impl PipelineBuilder {
     }
 }
 
+/// No default client is enabled, users have to provide their own.
+#[cfg(all(
+    feature = "collector_client",
+    not(feature = "isahc_collector_client"),
+    not(feature = "surf_collector_client"),
+    not(feature = "reqwest_collector_client"),
+    not(feature = "reqwest_blocking_collector_client")
+))]
+fn default_http_client(
+    _username: Option<String>,
+    _password: Option<String>,
+) -> Result<Box<dyn HttpClient>, crate::Error> {
+    Err(crate::Error::NoHttpClient)
+}
+
+#[cfg(feature = "isahc_collector_client")]
+fn default_http_client(
+    username: Option<String>,
+    password: Option<String>,
+) -> Result<Box<dyn HttpClient>, crate::Error> {
+    let mut builder = isahc::HttpClient::builder();
+    if let (Some(username), Some(password)) = (username, password) {
+        builder = builder
+            .authentication(isahc::auth::Authentication::basic())
+            .credentials(isahc::auth::Credentials::new(username, password));
+    }
+
+    Ok(Box::new(builder.build().map_err(|err| {
+        crate::Error::ThriftAgentError(::thrift::Error::from(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            err.to_string(),
+        )))
+    })?))
+}
+
+#[cfg(all(
+    not(feature = "isahc_collector_client"),
+    not(feature = "surf_collector_client"),
+    any(
+        feature = "reqwest_collector_client",
+        feature = "reqwest_blocking_collector_client"
+    )
+))]
+fn default_http_client(
+    username: Option<String>,
+    password: Option<String>,
+) -> Result<Box<dyn HttpClient>, crate::Error> {
+    #[cfg(feature = "reqwest_collector_client")]
+    let mut builder = reqwest::ClientBuilder::new();
+    #[cfg(all(
+        not(feature = "reqwest_collector_client"),
+        feature = "reqwest_blocking_collector_client"
+    ))]
+    let mut builder = reqwest::blocking::ClientBuilder::new();
+    if let (Some(username), Some(password)) = (username, password) {
+        let mut map = http::HeaderMap::with_capacity(1);
+        let auth_header_val = headers::Authorization::basic(username.as_str(), password.as_str());
+        map.insert(http::header::AUTHORIZATION, auth_header_val.0.encode());
+        builder = builder.default_headers(map);
+    }
+    Ok(Box::new(builder.build()?))
+}
+
+#[cfg(all(
+    not(feature = "isahc_collector_client"),
+    feature = "surf_collector_client",
+    not(feature = "reqwest_collector_client"),
+    not(feature = "reqwest_blocking_collector_client")
+))]
+fn default_http_client(
+    username: Option<String>,
+    password: Option<String>,
+) -> Result<Box<dyn HttpClient>, crate::Error> {
+    let client = if let (Some(username), Some(password)) = (username, password) {
+        let auth = surf::http::auth::BasicAuth::new(username, password);
+        surf::Client::new().with(BasicAuthMiddleware(auth))
+    } else {
+        surf::Client::new()
+    };
+
+    Ok(Box::new(client))
+}
+
 #[derive(Debug)]
 #[cfg(feature = "surf_collector_client")]
 struct BasicAuthMiddleware(surf::http::auth::BasicAuth);
@@ -470,12 +506,13 @@ fn convert_otel_span_into_jaeger_span(
     let trace_id = span.span_context.trace_id().to_u128();
     let trace_id_high = (trace_id >> 64) as i64;
     let trace_id_low = trace_id as i64;
+    let duration = span.duration();
     jaeger::Span {
         trace_id_low,
         trace_id_high,
         span_id: span.span_context.span_id().to_u64() as i64,
         parent_span_id: span.parent_span_id.to_u64() as i64,
-        operation_name: span.name,
+        operation_name: span.name.into_owned(),
         references: links_to_references(span.links),
         flags: span.span_context.trace_flags() as i32,
         start_time: span
@@ -483,11 +520,7 @@ fn convert_otel_span_into_jaeger_span(
             .duration_since(SystemTime::UNIX_EPOCH)
             .unwrap_or_else(|_| Duration::from_secs(0))
             .as_micros() as i64,
-        duration: span
-            .end_time
-            .duration_since(span.start_time)
-            .unwrap_or_else(|_| Duration::from_secs(0))
-            .as_micros() as i64,
+        duration: duration.as_micros() as i64,
         tags: Some(build_span_tags(
             span.attributes,
             if export_instrument_lib {
@@ -547,13 +580,9 @@ fn build_span_tags(
         tags.push(Key::new(SPAN_KIND).string(kind.to_string()).into());
     }
 
-    if !user_overrides.status_code {
-        tags.push(KeyValue::new(STATUS_CODE, status_code as i64).into());
-    }
-
     if status_code != StatusCode::Unset {
         // Ensure error status is set unless user has already overrided it
-        if status_code == StatusCode::Error || !user_overrides.error {
+        if status_code == StatusCode::Error && !user_overrides.error {
             tags.push(Key::new(ERROR).bool(true).into());
         }
         tags.push(
@@ -561,15 +590,8 @@ fn build_span_tags(
                 .string::<&'static str>(status_code.as_str())
                 .into(),
         );
-        // set status message if there is one
-        if !status_message.is_empty() {
-            if !user_overrides.status_message {
-                tags.push(
-                    Key::new(STATUS_MESSAGE)
-                        .string(status_message.clone())
-                        .into(),
-                );
-            }
+        // set status description if there is one, only errors have one
+        if status_code == StatusCode::Error && !status_message.is_empty() {
             tags.push(
                 Key::new(OTEL_STATUS_DESCRIPTION)
                     .string(status_message)
@@ -583,8 +605,6 @@ fn build_span_tags(
 
 const ERROR: &str = "error";
 const SPAN_KIND: &str = "span.kind";
-const STATUS_CODE: &str = "status.code";
-const STATUS_MESSAGE: &str = "status.message";
 const OTEL_STATUS_CODE: &str = "otel.status_code";
 const OTEL_STATUS_DESCRIPTION: &str = "otel.status_description";
 
@@ -592,8 +612,6 @@ const OTEL_STATUS_DESCRIPTION: &str = "otel.status_description";
 struct UserOverrides {
     error: bool,
     span_kind: bool,
-    status_code: bool,
-    status_message: bool,
 }
 
 impl UserOverrides {
@@ -601,8 +619,6 @@ impl UserOverrides {
         match attr {
             ERROR => self.error = true,
             SPAN_KIND => self.span_kind = true,
-            STATUS_CODE => self.status_code = true,
-            STATUS_MESSAGE => self.status_message = true,
             _ => (),
         }
     }
@@ -660,11 +676,11 @@ mod collector_client_tests {
 
     mod test_http_client {
         use async_trait::async_trait;
-        use http::Request;
-        use opentelemetry::sdk::export::trace::ExportResult;
+        use http::{Request, Response};
         use opentelemetry::trace::TraceError;
         use opentelemetry_http::HttpClient;
         use std::fmt::Debug;
+        use std::sync::{Arc, Mutex};
 
         pub(crate) struct TestHttpClient;
 
@@ -676,10 +692,28 @@ mod collector_client_tests {
 
         #[async_trait]
         impl HttpClient for TestHttpClient {
-            async fn send(&self, _request: Request<Vec<u8>>) -> ExportResult {
+            async fn send(
+                &self,
+                _request: Request<Vec<u8>>,
+            ) -> Result<Response<Vec<u8>>, TraceError> {
                 Err(TraceError::from("wrong uri set in http client"))
             }
         }
+
+        /// Keeps the requests it was asked to send.
+        #[derive(Debug, Default)]
+        pub(crate) struct RecordingHttpClient(pub(crate) Arc<Mutex<Vec<Request<Vec<u8>>>>>);
+
+        #[async_trait]
+        impl HttpClient for RecordingHttpClient {
+            async fn send(
+                &self,
+                request: Request<Vec<u8>>,
+            ) -> Result<Response<Vec<u8>>, TraceError> {
+                self.0.lock().unwrap().push(request);
+                Ok(Response::new(Vec::new()))
+            }
+        }
     }
 
     #[test]
@@ -701,6 +735,29 @@ mod collector_client_tests {
         Ok(())
     }
 
+    #[test]
+    fn test_custom_client_submits_batch() -> Result<(), TraceError> {
+        let client = test_http_client::RecordingHttpClient::default();
+        let requests = client.0.clone();
+        let (process, mut uploader) = new_pipeline()
+            .with_collector_endpoint("http://localhost:14268/api/traces")
+            .with_collector_username("ignored")
+            .with_http_client(client)
+            .init_uploader()?;
+        futures::executor::block_on(uploader.upload(Batch::new(process.into(), Vec::new())))?;
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].uri(), "http://localhost:14268/api/traces");
+        assert_eq!(
+            requests[0].headers()["Content-Type"],
+            "application/vnd.apache.thrift.binary"
+        );
+        assert!(!requests[0].body().is_empty());
+
+        Ok(())
+    }
+
     #[test]
     #[cfg(any(
         feature = "isahc_collector_client",
@@ -729,10 +786,35 @@ mod collector_client_tests {
 #[cfg(test)]
 mod tests {
     use crate::exporter::thrift::jaeger::Tag;
-    use crate::exporter::{build_span_tags, OTEL_STATUS_CODE, OTEL_STATUS_DESCRIPTION};
+    use crate::exporter::{build_span_tags, ERROR, OTEL_STATUS_CODE, OTEL_STATUS_DESCRIPTION};
     use opentelemetry::sdk::trace::EvictedHashMap;
     use opentelemetry::trace::{SpanKind, StatusCode};
 
+    fn assert_factory_provides_jaeger() {
+        let factory = crate::exporter_factory();
+        assert_eq!(factory.names(), vec!["stdout", "jaeger"]);
+        assert_eq!(factory.build("jaeger").unwrap().len(), 1);
+    }
+
+    #[cfg(not(feature = "tokio"))]
+    #[test]
+    fn exporter_factory_provides_jaeger() {
+        assert_factory_provides_jaeger()
+    }
+
+    // the agent socket of the tokio exporter needs a runtime
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn exporter_factory_provides_jaeger() {
+        assert_factory_provides_jaeger()
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn exporter_factory_fails_outside_tokio_runtime() {
+        assert!(crate::exporter_factory().build("jaeger").is_err());
+    }
+
     fn assert_tag_contains(tags: Vec<Tag>, key: &'static str, expect_val: &'static str) {
         assert_eq!(
             tags.into_iter()
@@ -776,6 +858,7 @@ mod tests {
                 Some("have message"),
             ),
             (StatusCode::Unset, "have message".into(), None, None),
+            (StatusCode::Ok, "have message".into(), Some("OK"), None),
         ]
     }
 
@@ -800,6 +883,13 @@ mod tests {
             } else {
                 assert_tag_not_contains(tags.clone(), OTEL_STATUS_DESCRIPTION);
             }
+
+            let error_tags = tags
+                .iter()
+                .filter(|tag| tag.key == ERROR && tag.v_bool == Some(true))
+                .count();
+            assert_eq!(error_tags, (status_code == StatusCode::Error) as usize);
+            assert_tag_not_contains(tags, "status.code");
         }
     }
 }
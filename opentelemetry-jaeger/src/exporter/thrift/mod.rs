@@ -28,7 +28,7 @@ impl From<Event> for jaeger::Log {
             .as_micros() as i64;
         let mut event_set_via_attribute = false;
         let mut fields = event
-            .attributes
+            .attributes_with_severity()
             .into_iter()
             .map(|attr| {
                 if attr.key.as_str() == "event" {
@@ -98,6 +98,43 @@
 //! }
 //! ```
 //!
+//! ### Custom HTTP Client
+//!
+//! Spans can be submitted to the collector with any HTTP client implementing
+//! the [`HttpClient`] trait, e.g. to reuse a client that already handles
+//! authentication and proxies. A client passed to [`with_http_client`] is used
+//! instead of the default client of the enabled feature, so the collector
+//! username and password have to be handled by the client itself.
+//!
+//! [`with_http_client`]: PipelineBuilder::with_http_client()
+//!
+//! ```ignore
+//! // Note that this requires the `collector_client` feature.
+//! use async_trait::async_trait;
+//! use http::{Request, Response};
+//! use opentelemetry::trace::TraceError;
+//! use opentelemetry_jaeger::HttpClient;
+//!
+//! #[derive(Debug)]
+//! struct InternalClient(my_http::Client);
+//!
+//! #[async_trait]
+//! impl HttpClient for InternalClient {
+//!     async fn send(&self, request: Request<Vec<u8>>) -> Result<Response<Vec<u8>>, TraceError> {
+//!         self.0.send(request).await.map_err(|err| TraceError::from(err.to_string()))
+//!     }
+//! }
+//!
+//! fn main() -> Result<(), TraceError> {
+//!     let tracer = opentelemetry_jaeger::new_pipeline()
+//!         .with_collector_endpoint("http://localhost:14268/api/traces")
+//!         .with_http_client(InternalClient(my_http::Client::new()))
+//!         .install()?;
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
 //! ## Kitchen Sink Full Configuration
 //!
 //! Example showing how to override all configuration options. See the
@@ -573,5 +610,9 @@ mod propagator {
     }
 }
 
-pub use exporter::{new_pipeline, Error, Exporter, PipelineBuilder, Process};
+pub use exporter::{
+    exporter_factory, new_pipeline, register_exporter, Error, Exporter, PipelineBuilder, Process,
+};
+#[cfg(feature = "collector_client")]
+pub use opentelemetry_http::HttpClient;
 pub use propagator::Propagator;
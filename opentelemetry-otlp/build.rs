@@ -1,18 +1,50 @@
-#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+#[cfg(all(feature = "grpc-sys", not(feature = "tonic-build")))]
 extern crate protoc_grpcio;
 
-#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+#[cfg(all(feature = "grpc-sys", not(feature = "tonic-build")))]
 use protobuf_codegen::Customize;
 
-#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+#[cfg(all(feature = "grpc-sys", not(feature = "tonic-build")))]
 use protoc_grpcio::compile_grpc_protos;
 
 fn main() {
-    #[cfg(feature = "tonic")]
-    tonic_build::configure()
+    // OTLP/HTTP only needs the messages, the clients require tonic
+    #[cfg(feature = "tonic-build")]
+    let mut builder = tonic_build::configure()
         .build_server(std::env::var_os("CARGO_FEATURE_INTEGRATION_TESTING").is_some())
-        .build_client(true)
-        .format(false)
+        .build_client(std::env::var_os("CARGO_FEATURE_TONIC").is_some())
+        .format(false);
+
+    // OTLP/JSON uses the proto3 JSON mapping, except that trace and span ids
+    // are hex encoded.
+    #[cfg(feature = "tonic-build")]
+    if std::env::var_os("CARGO_FEATURE_HTTP_JSON").is_some() {
+        builder = builder
+            .type_attribute(".opentelemetry.proto", "#[derive(serde::Serialize)]")
+            .type_attribute(
+                ".opentelemetry.proto",
+                "#[serde(rename_all = \"camelCase\")]",
+            )
+            // suffix match, a prefix match would also apply to the oneof variants
+            .field_attribute("common.v1.AnyValue.value", "#[serde(flatten)]");
+        for id in &[
+            ".opentelemetry.proto.trace.v1.Span.trace_id",
+            ".opentelemetry.proto.trace.v1.Span.span_id",
+            ".opentelemetry.proto.trace.v1.Span.parent_span_id",
+            ".opentelemetry.proto.trace.v1.Span.Link.trace_id",
+            ".opentelemetry.proto.trace.v1.Span.Link.span_id",
+            ".opentelemetry.proto.logs.v1.LogRecord.trace_id",
+            ".opentelemetry.proto.logs.v1.LogRecord.span_id",
+        ] {
+            builder = builder.field_attribute(
+                id,
+                "#[serde(serialize_with = \"crate::transform::serialize_hex\")]",
+            );
+        }
+    }
+
+    #[cfg(feature = "tonic-build")]
+    builder
         .compile(
             &[
                 "src/proto/opentelemetry-proto/opentelemetry/proto/common/v1/common.proto",
@@ -22,12 +54,14 @@ fn main() {
                 "src/proto/opentelemetry-proto/opentelemetry/proto/collector/trace/v1/trace_service.proto",
                 "src/proto/opentelemetry-proto/opentelemetry/proto/metrics/v1/metrics.proto",
                 "src/proto/opentelemetry-proto/opentelemetry/proto/collector/metrics/v1/metrics_service.proto",
+                "src/proto/opentelemetry-proto/opentelemetry/proto/logs/v1/logs.proto",
+                "src/proto/opentelemetry-proto/opentelemetry/proto/collector/logs/v1/logs_service.proto",
             ],
             &["src/proto/opentelemetry-proto"],
         )
         .expect("Error generating protobuf");
 
-    #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+    #[cfg(all(feature = "grpc-sys", not(feature = "tonic-build")))]
     compile_grpc_protos(
         &[
             "src/proto/opentelemetry-proto/opentelemetry/proto/common/v1/common.proto",
@@ -0,0 +1,184 @@
+//! # OTLP - Transport Diagnostics
+//!
+//! Exports fail either because the collector could not be reached or because
+//! it responded with an error. The first points at the network, DNS or TLS
+//! setup, the second at the collector or the exported data. Every export
+//! attempt, retries included, is classified and recorded in [`ExportStats`],
+//! so operators can tell the two apart from a health check.
+//!
+//! [`ExportStats`]: crate::ExportStats
+use std::time::SystemTime;
+
+/// State of the connection to the collector, as observed by the exports.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum ConnectionState {
+    /// No export was attempted yet.
+    Idle,
+    /// The last export reached the collector, whether it accepted the spans
+    /// or not.
+    Connected,
+    /// The last export failed to reach the collector.
+    Disconnected,
+}
+
+impl Default for ConnectionState {
+    fn default() -> Self {
+        ConnectionState::Idle
+    }
+}
+
+/// Where an export attempt failed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum FailureKind {
+    /// The collector could not be reached, e.g. the connection was refused,
+    /// the TLS handshake failed or the request timed out.
+    Transport,
+    /// The collector responded with an error status.
+    Rejected,
+    /// The request could not be sent, e.g. because encoding it failed.
+    Other,
+}
+
+/// A failed export attempt.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportFailure {
+    /// Where the attempt failed.
+    pub kind: FailureKind,
+    /// The error the attempt failed with.
+    pub message: String,
+    /// When the attempt failed.
+    pub time: SystemTime,
+}
+
+/// Transport state shared by the clones of an [`ExportStats`].
+///
+/// [`ExportStats`]: crate::ExportStats
+#[derive(Debug, Default)]
+pub(crate) struct TransportState {
+    pub(crate) connection_state: ConnectionState,
+    pub(crate) reconnects: u64,
+    pub(crate) transport_errors: u64,
+    pub(crate) rejected_exports: u64,
+    pub(crate) last_error: Option<ExportFailure>,
+}
+
+impl TransportState {
+    /// Update the state with the outcome of an export attempt.
+    pub(crate) fn record<T>(&mut self, result: &Result<T, crate::Error>) {
+        let kind = match result {
+            Ok(_) => None,
+            Err(err) => Some(err.failure_kind()),
+        };
+        let reached_collector = kind != Some(FailureKind::Transport);
+        match kind {
+            // the request never left the exporter
+            Some(FailureKind::Other) => {}
+            _ if reached_collector => {
+                if self.connection_state == ConnectionState::Disconnected {
+                    self.reconnects += 1;
+                }
+                self.connection_state = ConnectionState::Connected;
+            }
+            _ => self.connection_state = ConnectionState::Disconnected,
+        }
+
+        if let (Err(err), Some(kind)) = (result, kind) {
+            match kind {
+                FailureKind::Transport => self.transport_errors += 1,
+                FailureKind::Rejected => self.rejected_exports += 1,
+                FailureKind::Other => {}
+            }
+            self.last_error = Some(ExportFailure {
+                kind,
+                message: err.to_string(),
+                time: SystemTime::now(),
+            });
+        }
+    }
+}
+
+impl crate::Error {
+    /// Classify where an export attempt failed.
+    ///
+    /// Connection failures of gRPC clients surface as statuses generated by
+    /// the client, `Unavailable`, `Unknown` or `DeadlineExceeded`, so these
+    /// codes are attributed to the transport.
+    pub(crate) fn failure_kind(&self) -> FailureKind {
+        match self {
+            #[cfg(feature = "tonic")]
+            crate::Error::Transport(_) => FailureKind::Transport,
+            #[cfg(feature = "tonic")]
+            crate::Error::Status(status) => match status.code() {
+                tonic::Code::Unavailable
+                | tonic::Code::Unknown
+                | tonic::Code::DeadlineExceeded
+                | tonic::Code::Cancelled => FailureKind::Transport,
+                _ => FailureKind::Rejected,
+            },
+            #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+            crate::Error::Grpcio(grpcio::Error::RpcFailure(status)) => match status.status {
+                grpcio::RpcStatusCode::UNAVAILABLE
+                | grpcio::RpcStatusCode::UNKNOWN
+                | grpcio::RpcStatusCode::DEADLINE_EXCEEDED
+                | grpcio::RpcStatusCode::CANCELLED => FailureKind::Transport,
+                _ => FailureKind::Rejected,
+            },
+            #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+            crate::Error::Grpcio(_) => FailureKind::Transport,
+            #[cfg(feature = "http-proto")]
+            crate::Error::HttpStatus { .. } => FailureKind::Rejected,
+            #[cfg(feature = "http-proto")]
+            crate::Error::HttpClient(_) => FailureKind::Transport,
+            #[cfg(feature = "reqwest-client")]
+            crate::Error::Reqwest(_) => FailureKind::Transport,
+            _ => FailureKind::Other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_failures_keep_connection_state() {
+        let mut state = TransportState::default();
+        assert_eq!(state.connection_state, ConnectionState::Idle);
+
+        state.record(&Ok(()));
+        assert_eq!(state.connection_state, ConnectionState::Connected);
+        assert_eq!(state.reconnects, 0);
+
+        let unsupported: Result<(), _> = Err(crate::Error::InvalidTlsConfig("unsupported"));
+        state.record(&unsupported);
+        assert_eq!(state.connection_state, ConnectionState::Connected);
+        assert_eq!(state.last_error.as_ref().unwrap().kind, FailureKind::Other);
+        assert_eq!(state.transport_errors + state.rejected_exports, 0);
+    }
+
+    #[cfg(feature = "tonic")]
+    #[test]
+    fn distinguish_transport_errors_from_rejections() {
+        let mut state = TransportState::default();
+        let unavailable: Result<(), _> = Err(crate::Error::Status(tonic::Status::unavailable(
+            "connection refused",
+        )));
+        state.record(&unavailable);
+        state.record(&unavailable);
+        assert_eq!(state.connection_state, ConnectionState::Disconnected);
+        assert_eq!(state.transport_errors, 2);
+
+        let rejected: Result<(), _> = Err(crate::Error::Status(tonic::Status::unauthenticated(
+            "missing api key",
+        )));
+        state.record(&rejected);
+        assert_eq!(state.connection_state, ConnectionState::Connected);
+        assert_eq!(state.reconnects, 1);
+        assert_eq!(state.rejected_exports, 1);
+        let last_error = state.last_error.unwrap();
+        assert_eq!(last_error.kind, FailureKind::Rejected);
+        assert!(last_error.message.contains("missing api key"));
+    }
+}
@@ -0,0 +1,630 @@
+//! # OTLP/HTTP - Span Exporter
+//!
+//! Defines a [SpanExporter] sending trace data to the collector over HTTP/1.1,
+//! encoded either as binary protobuf or as JSON.
+
+use crate::partial_success::{ExportStats, ExportTracePartialSuccess, ExportTraceServiceResponse};
+use crate::proto::collector::trace::v1::ExportTraceServiceRequest;
+use crate::retry::{retry, RetryConfig};
+use crate::transform::map_chunks;
+#[cfg(feature = "http-json")]
+use crate::transform::resource_spans;
+#[cfg(feature = "reqwest-client")]
+use crate::TlsConfig;
+use crate::{Compression, ExporterConfig, Protocol, OTEL_EXPORTER_OTLP_ENDPOINT_DEFAULT};
+use async_trait::async_trait;
+use http::{
+    header::{CONTENT_ENCODING, CONTENT_TYPE, RETRY_AFTER},
+    HeaderMap, Method, Request, Uri,
+};
+use opentelemetry::sdk::export::trace::{ExportResult, SpanData, SpanExporter};
+use opentelemetry::trace::TraceError;
+use opentelemetry_http::HttpClient;
+use prost::Message;
+use std::fmt;
+#[cfg(feature = "gzip-http")]
+use std::io::Write;
+use std::time::Duration;
+
+/// Default target of the OTLP/HTTP exporter.
+const OTEL_EXPORTER_OTLP_HTTP_ENDPOINT_DEFAULT: &str = "http://localhost:4318";
+/// Path of the trace export service.
+const OTLP_TRACES_PATH: &str = "/v1/traces";
+
+/// Exporter that sends data in OTLP format over HTTP.
+pub struct HttpTraceExporter {
+    client: Box<dyn HttpClient>,
+    endpoint: Uri,
+    headers: HeaderMap,
+    protocol: Protocol,
+    compression: Option<Compression>,
+    retry: Option<RetryConfig>,
+    stats: ExportStats,
+    encoding_parallelism: usize,
+}
+
+impl fmt::Debug for HttpTraceExporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpTraceExporter")
+            .field("endpoint", &self.endpoint)
+            .field("headers", &self.headers)
+            .field("protocol", &self.protocol)
+            .field("compression", &self.compression)
+            .field("retry", &self.retry)
+            .field("stats", &self.stats)
+            .field("encoding_parallelism", &self.encoding_parallelism)
+            .finish()
+    }
+}
+
+impl HttpTraceExporter {
+    /// Builds a new span exporter sending requests with the given client.
+    ///
+    /// If the endpoint has no path the spans are sent to `/v1/traces`. The
+    /// request timeout is left to the client. Compressing requests requires
+    /// the `gzip-http` or `zstd-http` feature for the configured algorithm.
+    pub fn new(config: ExporterConfig, client: Box<dyn HttpClient>) -> Result<Self, crate::Error> {
+        match config.compression {
+            #[cfg(feature = "gzip-http")]
+            Some(Compression::Gzip) => {}
+            #[cfg(feature = "zstd-http")]
+            Some(Compression::Zstd) => {}
+            Some(compression) => return Err(crate::Error::UnsupportedCompression(compression)),
+            None => {}
+        }
+
+        let endpoint = if config.endpoint == OTEL_EXPORTER_OTLP_ENDPOINT_DEFAULT {
+            OTEL_EXPORTER_OTLP_HTTP_ENDPOINT_DEFAULT
+        } else {
+            config.endpoint.as_str()
+        };
+        let endpoint = match endpoint.parse::<Uri>().map_err(http::Error::from)? {
+            uri if uri.path() == "/" => format!(
+                "{}{}",
+                uri.to_string().trim_end_matches('/'),
+                OTLP_TRACES_PATH
+            )
+            .parse()
+            .map_err(http::Error::from)?,
+            uri => uri,
+        };
+
+        #[cfg(feature = "tonic")]
+        let headers = config
+            .metadata
+            .map(|metadata| metadata.into_headers())
+            .unwrap_or_default();
+        #[cfg(not(feature = "tonic"))]
+        let headers = header_map(config.headers.unwrap_or_default())?;
+
+        Ok(HttpTraceExporter {
+            client,
+            endpoint,
+            headers,
+            protocol: config.protocol,
+            compression: config.compression,
+            retry: config.retry,
+            stats: config.stats,
+            encoding_parallelism: config.encoding_parallelism,
+        })
+    }
+
+    /// Check that the collector is reachable and accepts exports.
+    ///
+    /// Sends an empty export request, so connection, TLS and authentication
+    /// problems surface without having to wait for the first batch of spans.
+    /// The request is not retried.
+    pub async fn ready(&self) -> Result<(), TraceError> {
+        let (content_type, body) = self.encode(ExportTraceServiceRequest {
+            resource_spans: Vec::new(),
+        })?;
+        self.stats
+            .record_attempt(self.post(content_type, body).await)?;
+        Ok(())
+    }
+
+    /// Send an encoded request, retrying transient failures.
+    async fn send(&self, content_type: &'static str, body: Vec<u8>) -> Result<(), crate::Error> {
+        let response = retry(self.retry, || {
+            let attempt = self.post(content_type, body.clone());
+            async move { self.stats.record_attempt(attempt.await) }
+        })
+        .await?;
+        self.stats.record(self.partial_success(&response));
+        Ok(())
+    }
+
+    /// Decode the partial success of a response body, if any.
+    fn partial_success(&self, response: &[u8]) -> Option<ExportTracePartialSuccess> {
+        match self.protocol {
+            #[cfg(feature = "http-json")]
+            Protocol::HttpJson => crate::partial_success::from_json(response),
+            _ => {
+                ExportTraceServiceResponse::decode(response)
+                    .ok()?
+                    .partial_success
+            }
+        }
+    }
+
+    fn encode(
+        &self,
+        request: ExportTraceServiceRequest,
+    ) -> Result<(&'static str, Vec<u8>), crate::Error> {
+        let (content_type, body) = match self.protocol {
+            #[cfg(feature = "http-json")]
+            Protocol::HttpJson => ("application/json", serde_json::to_vec(&request)?),
+            _ => ("application/x-protobuf", encode_protobuf(&request)?),
+        };
+        self.compress(content_type, body)
+    }
+
+    /// Encode a batch of spans, converting and encoding large batches on
+    /// [`ExporterConfig::encoding_parallelism`] threads.
+    fn encode_batch(&self, batch: Vec<SpanData>) -> Result<(&'static str, Vec<u8>), crate::Error> {
+        match self.protocol {
+            #[cfg(feature = "http-json")]
+            Protocol::HttpJson => self.encode(ExportTraceServiceRequest {
+                resource_spans: resource_spans(batch, self.encoding_parallelism),
+            }),
+            _ => {
+                // the resource spans are a repeated field, so the encoded
+                // requests of consecutive chunks concatenate to the request
+                // of the whole batch
+                let mut chunks = map_chunks(batch, self.encoding_parallelism, |chunk| {
+                    encode_protobuf(&ExportTraceServiceRequest {
+                        resource_spans: chunk.into_iter().map(Into::into).collect(),
+                    })
+                })
+                .into_iter();
+                let mut body = chunks.next().unwrap_or_else(|| Ok(Vec::new()))?;
+                for chunk in chunks {
+                    body.extend_from_slice(&chunk?);
+                }
+                self.compress("application/x-protobuf", body)
+            }
+        }
+    }
+
+    fn compress(
+        &self,
+        content_type: &'static str,
+        body: Vec<u8>,
+    ) -> Result<(&'static str, Vec<u8>), crate::Error> {
+        match self.compression {
+            Some(compression) => Ok((content_type, compress(compression, body)?)),
+            None => Ok((content_type, body)),
+        }
+    }
+
+    /// Send a request body, returning the body of a successful response.
+    async fn post(
+        &self,
+        content_type: &'static str,
+        body: Vec<u8>,
+    ) -> Result<Vec<u8>, crate::Error> {
+        let mut request = Request::builder()
+            .method(Method::POST)
+            .uri(self.endpoint.clone())
+            .header(CONTENT_TYPE, content_type);
+        if let Some(compression) = self.compression {
+            request = request.header(CONTENT_ENCODING, compression.to_string());
+        }
+        let mut request = request.body(body)?;
+        request.headers_mut().extend(self.headers.clone());
+
+        let response = self
+            .client
+            .send(request)
+            .await
+            .map_err(crate::Error::HttpClient)?;
+        if !response.status().is_success() {
+            return Err(crate::Error::HttpStatus {
+                status: response.status(),
+                retry_after: retry_after(response.headers()),
+            });
+        }
+        Ok(response.into_body())
+    }
+}
+
+/// Convert the configured headers, failing on invalid names or values.
+#[cfg(not(feature = "tonic"))]
+fn header_map(
+    headers: std::collections::HashMap<String, String>,
+) -> Result<HeaderMap, http::Error> {
+    let mut map = HeaderMap::with_capacity(headers.len());
+    for (key, value) in headers {
+        map.append(
+            http::header::HeaderName::from_bytes(key.as_bytes())?,
+            http::header::HeaderValue::from_str(&value)?,
+        );
+    }
+    Ok(map)
+}
+
+fn encode_protobuf(request: &ExportTraceServiceRequest) -> Result<Vec<u8>, prost::EncodeError> {
+    let mut body = Vec::with_capacity(request.encoded_len());
+    request.encode(&mut body)?;
+    Ok(body)
+}
+
+/// The delay requested by a `Retry-After` header in seconds.
+///
+/// HTTP dates are not supported, the exporter falls back to its backoff.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// A `reqwest::Client` returning error responses instead of failing, so the
+/// exporter can honor their status and `Retry-After` header.
+#[cfg(feature = "reqwest-client")]
+#[derive(Debug, Default)]
+pub(crate) struct ReqwestClient(reqwest::Client);
+
+#[cfg(feature = "reqwest-client")]
+impl ReqwestClient {
+    /// Build a client verifying the collector with the given TLS settings,
+    /// which require the `reqwest-rustls` feature.
+    pub(crate) fn new(tls_config: &Option<TlsConfig>) -> Result<Self, crate::Error> {
+        let tls_config = match tls_config {
+            Some(tls_config) => tls_config,
+            None => return Ok(ReqwestClient::default()),
+        };
+        if tls_config.domain_name.is_some() {
+            return Err(crate::Error::InvalidTlsConfig(
+                "overriding the domain name is not supported by OTLP/HTTP",
+            ));
+        }
+
+        #[cfg(feature = "reqwest-rustls")]
+        {
+            let mut builder = reqwest::Client::builder().use_rustls_tls();
+            if let Some(ca_cert) = &tls_config.ca_cert {
+                builder = builder.add_root_certificate(reqwest::Certificate::from_pem(ca_cert)?);
+            }
+            if let Some((cert, key)) = tls_config.identity()? {
+                let pem = [cert, b"\n", key].concat();
+                builder = builder.identity(reqwest::Identity::from_pem(&pem)?);
+            }
+            Ok(ReqwestClient(builder.build()?))
+        }
+
+        #[cfg(not(feature = "reqwest-rustls"))]
+        Err(crate::Error::InvalidTlsConfig(
+            "TLS settings require the `reqwest-rustls` feature",
+        ))
+    }
+}
+
+#[cfg(feature = "reqwest-client")]
+#[async_trait]
+impl HttpClient for ReqwestClient {
+    async fn send(&self, request: Request<Vec<u8>>) -> Result<http::Response<Vec<u8>>, TraceError> {
+        use std::convert::TryInto;
+
+        let request = request.try_into().map_err(crate::Error::from)?;
+        let response = self.0.execute(request).await.map_err(crate::Error::from)?;
+        let mut builder = http::Response::builder().status(response.status());
+        if let Some(headers) = builder.headers_mut() {
+            *headers = response.headers().clone();
+        }
+        let body = response.bytes().await.map_err(crate::Error::from)?;
+        Ok(builder.body(body.to_vec()).map_err(crate::Error::from)?)
+    }
+}
+
+/// Compress a request body with an algorithm enabled by the crate features.
+#[cfg_attr(
+    not(any(feature = "gzip-http", feature = "zstd-http")),
+    allow(unused_variables)
+)]
+fn compress(compression: Compression, body: Vec<u8>) -> Result<Vec<u8>, crate::Error> {
+    match compression {
+        #[cfg(feature = "gzip-http")]
+        Compression::Gzip => {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(&body)?;
+            Ok(encoder.finish()?)
+        }
+        #[cfg(feature = "zstd-http")]
+        Compression::Zstd => Ok(zstd::stream::encode_all(body.as_slice(), 0)?),
+        #[allow(unreachable_patterns)]
+        compression => Err(crate::Error::UnsupportedCompression(compression)),
+    }
+}
+
+#[async_trait]
+impl SpanExporter for HttpTraceExporter {
+    async fn export(&mut self, batch: Vec<SpanData>) -> ExportResult {
+        let (content_type, body) = self.encode_batch(batch)?;
+        self.send(content_type, body).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::Response;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Default, Clone)]
+    struct RecordingClient(Arc<Mutex<Vec<Request<Vec<u8>>>>>);
+
+    #[async_trait]
+    impl HttpClient for RecordingClient {
+        async fn send(&self, request: Request<Vec<u8>>) -> Result<Response<Vec<u8>>, TraceError> {
+            self.0.lock().unwrap().push(request);
+            Ok(Response::new(Vec::new()))
+        }
+    }
+
+    fn exporter(endpoint: &str, protocol: Protocol) -> (HttpTraceExporter, RecordingClient) {
+        let client = RecordingClient::default();
+        let config = ExporterConfig {
+            endpoint: endpoint.to_string(),
+            protocol,
+            ..Default::default()
+        };
+        (
+            HttpTraceExporter::new(config, Box::new(client.clone())).unwrap(),
+            client,
+        )
+    }
+
+    #[test]
+    fn endpoint_defaults_to_traces_path() {
+        let (default, _) = exporter(OTEL_EXPORTER_OTLP_ENDPOINT_DEFAULT, Protocol::HttpBinary);
+        assert_eq!(default.endpoint, "http://localhost:4318/v1/traces");
+
+        let (base, _) = exporter("https://collector:4318/", Protocol::HttpBinary);
+        assert_eq!(base.endpoint, "https://collector:4318/v1/traces");
+
+        let (full, _) = exporter("https://collector/custom/traces", Protocol::HttpBinary);
+        assert_eq!(full.endpoint, "https://collector/custom/traces");
+    }
+
+    #[test]
+    fn export_binary_protobuf() {
+        let (exporter, client) = exporter("http://collector:4318", Protocol::HttpBinary);
+        futures::executor::block_on(exporter.ready()).unwrap();
+
+        let requests = client.0.lock().unwrap();
+        assert_eq!(requests[0].method(), Method::POST);
+        assert_eq!(requests[0].uri(), "http://collector:4318/v1/traces");
+        assert_eq!(
+            requests[0].headers()[CONTENT_TYPE],
+            "application/x-protobuf"
+        );
+        assert_eq!(
+            ExportTraceServiceRequest::decode(requests[0].body().as_slice()).unwrap(),
+            ExportTraceServiceRequest {
+                resource_spans: Vec::new()
+            }
+        );
+    }
+
+    #[test]
+    fn parallel_encoding_matches_sequential() {
+        let (mut sequential, sequential_client) =
+            exporter("http://collector:4318", Protocol::HttpBinary);
+        let parallel_client = RecordingClient::default();
+        let config = ExporterConfig {
+            endpoint: "http://collector:4318".to_string(),
+            protocol: Protocol::HttpBinary,
+            encoding_parallelism: 4,
+            ..Default::default()
+        };
+        let mut parallel =
+            HttpTraceExporter::new(config, Box::new(parallel_client.clone())).unwrap();
+
+        futures::executor::block_on(sequential.export(crate::transform::test_batch(1000))).unwrap();
+        futures::executor::block_on(parallel.export(crate::transform::test_batch(1000))).unwrap();
+
+        let sequential_body = sequential_client.0.lock().unwrap()[0].body().clone();
+        let parallel_body = parallel_client.0.lock().unwrap()[0].body().clone();
+        assert_eq!(parallel_body, sequential_body);
+        let request = ExportTraceServiceRequest::decode(parallel_body.as_slice()).unwrap();
+        assert_eq!(request.resource_spans.len(), 1000);
+    }
+
+    /// Responds with the given responses in order, then with `200 OK`.
+    #[derive(Debug, Clone)]
+    struct ScriptedClient(Arc<Mutex<Vec<Response<Vec<u8>>>>>);
+
+    #[async_trait]
+    impl HttpClient for ScriptedClient {
+        async fn send(&self, _request: Request<Vec<u8>>) -> Result<Response<Vec<u8>>, TraceError> {
+            let mut responses = self.0.lock().unwrap();
+            if responses.is_empty() {
+                Ok(Response::new(Vec::new()))
+            } else {
+                Ok(responses.remove(0))
+            }
+        }
+    }
+
+    fn scripted_exporter(statuses: &[u16]) -> (HttpTraceExporter, ScriptedClient) {
+        let responses = statuses
+            .iter()
+            .map(|status| {
+                Response::builder()
+                    .status(*status)
+                    .header(RETRY_AFTER, "0")
+                    .body(Vec::new())
+                    .unwrap()
+            })
+            .collect();
+        let client = ScriptedClient(Arc::new(Mutex::new(responses)));
+        let exporter =
+            HttpTraceExporter::new(ExporterConfig::default(), Box::new(client.clone())).unwrap();
+        (exporter, client)
+    }
+
+    #[test]
+    fn export_retries_throttled_requests() {
+        let (mut exporter, client) = scripted_exporter(&[503, 429]);
+        assert!(futures::executor::block_on(exporter.export(Vec::new())).is_ok());
+        assert!(client.0.lock().unwrap().is_empty());
+
+        // the collector was reachable, it rejected the first attempts
+        assert_eq!(exporter.stats.rejected_exports(), 2);
+        assert_eq!(exporter.stats.transport_errors(), 0);
+        assert_eq!(
+            exporter.stats.connection_state(),
+            crate::ConnectionState::Connected
+        );
+        assert_eq!(
+            exporter.stats.last_error().map(|failure| failure.kind),
+            Some(crate::FailureKind::Rejected)
+        );
+    }
+
+    #[test]
+    fn export_counts_rejected_spans() {
+        let mut body = Vec::new();
+        ExportTraceServiceResponse {
+            partial_success: Some(ExportTracePartialSuccess {
+                rejected_spans: 2,
+                error_message: "invalid span".to_string(),
+            }),
+        }
+        .encode(&mut body)
+        .unwrap();
+        let client = ScriptedClient(Arc::new(Mutex::new(vec![Response::new(body)])));
+        let mut exporter =
+            HttpTraceExporter::new(ExporterConfig::default(), Box::new(client)).unwrap();
+
+        assert!(futures::executor::block_on(exporter.export(Vec::new())).is_ok());
+        assert!(futures::executor::block_on(exporter.export(Vec::new())).is_ok());
+        assert_eq!(exporter.stats.rejected_spans(), 2);
+    }
+
+    #[test]
+    fn export_does_not_retry_rejected_requests() {
+        let (mut exporter, client) = scripted_exporter(&[400]);
+        assert!(futures::executor::block_on(exporter.export(Vec::new())).is_err());
+
+        let (exporter, client_ready) = scripted_exporter(&[503]);
+        assert!(futures::executor::block_on(exporter.ready()).is_err());
+        assert!(client.0.lock().unwrap().is_empty());
+        assert!(client_ready.0.lock().unwrap().is_empty());
+    }
+
+    #[cfg(not(feature = "zstd-http"))]
+    #[test]
+    fn compression_requires_feature() {
+        let config = ExporterConfig {
+            compression: Some(Compression::Zstd),
+            ..Default::default()
+        };
+        assert!(matches!(
+            HttpTraceExporter::new(config, Box::new(RecordingClient::default())),
+            Err(crate::Error::UnsupportedCompression(Compression::Zstd))
+        ));
+    }
+
+    #[cfg(feature = "reqwest-client")]
+    #[test]
+    fn reqwest_client_tls_config() {
+        assert!(ReqwestClient::new(&None).is_ok());
+
+        let tls_config = TlsConfig {
+            domain_name: Some("collector".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            ReqwestClient::new(&Some(tls_config)),
+            Err(crate::Error::InvalidTlsConfig(_))
+        ));
+
+        // the client key is missing
+        let tls_config = TlsConfig {
+            client_cert: Some(Vec::new()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            ReqwestClient::new(&Some(tls_config)),
+            Err(crate::Error::InvalidTlsConfig(_))
+        ));
+    }
+
+    #[cfg(feature = "gzip-http")]
+    #[test]
+    fn export_gzip() {
+        use std::io::Read;
+
+        let client = RecordingClient::default();
+        let config = ExporterConfig {
+            endpoint: "http://collector:4318".to_string(),
+            protocol: Protocol::HttpBinary,
+            compression: Some(Compression::Gzip),
+            ..Default::default()
+        };
+        let exporter = HttpTraceExporter::new(config, Box::new(client.clone())).unwrap();
+        futures::executor::block_on(exporter.ready()).unwrap();
+
+        let requests = client.0.lock().unwrap();
+        assert_eq!(requests[0].headers()[CONTENT_ENCODING], "gzip");
+        let mut body = Vec::new();
+        flate2::read::GzDecoder::new(requests[0].body().as_slice())
+            .read_to_end(&mut body)
+            .unwrap();
+        assert_eq!(
+            ExportTraceServiceRequest::decode(body.as_slice()).unwrap(),
+            ExportTraceServiceRequest {
+                resource_spans: Vec::new()
+            }
+        );
+    }
+
+    #[cfg(feature = "http-json")]
+    #[test]
+    fn export_json() {
+        use crate::proto::common::v1::{any_value, AnyValue, KeyValue};
+        use crate::proto::trace::v1::{InstrumentationLibrarySpans, ResourceSpans, Span};
+
+        let (exporter, client) = exporter("http://collector:4318", Protocol::HttpJson);
+        let (content_type, body) = exporter
+            .encode(ExportTraceServiceRequest {
+                resource_spans: vec![ResourceSpans {
+                    resource: None,
+                    instrumentation_library_spans: vec![InstrumentationLibrarySpans {
+                        instrumentation_library: None,
+                        spans: vec![Span {
+                            trace_id: vec![0, 1, 0xab],
+                            span_id: vec![0xff],
+                            attributes: vec![KeyValue {
+                                key: "k".to_string(),
+                                value: Some(AnyValue {
+                                    value: Some(any_value::Value::StringValue("v".to_string())),
+                                }),
+                            }],
+                            ..Default::default()
+                        }],
+                        schema_url: String::new(),
+                    }],
+                    schema_url: String::new(),
+                }],
+            })
+            .unwrap();
+        futures::executor::block_on(exporter.send(content_type, body)).unwrap();
+
+        let requests = client.0.lock().unwrap();
+        assert_eq!(requests[0].headers()[CONTENT_TYPE], "application/json");
+        let json: serde_json::Value = serde_json::from_slice(requests[0].body()).unwrap();
+        let span = &json["resourceSpans"][0]["instrumentationLibrarySpans"][0]["spans"][0];
+        assert_eq!(span["traceId"], "0001ab");
+        assert_eq!(span["spanId"], "ff");
+        assert_eq!(span["attributes"][0]["value"]["stringValue"], "v");
+    }
+}
@@ -44,6 +44,51 @@
 //! | TLS library | rustls | OpenSSL |
 //! | Supported .proto generator | [`prost`](https://crates.io/crates/prost) | [`prost`](https://crates.io/crates/prost), [`protobuf`](https://crates.io/crates/protobuf) |
 //!
+//! ## HTTP
+//!
+//! Spans can also be sent as binary protobuf or JSON over HTTP/1.1 by
+//! enabling the `http-proto` or `http-json` feature and selecting the
+//! protocol with [`OtlpPipelineBuilder::with_protocol`] or the
+//! `OTEL_EXPORTER_OTLP_PROTOCOL` environment variable. Requests are sent
+//! with any [`HttpClient`], the `reqwest-client` feature uses a
+//! `reqwest::Client` by default.
+//!
+//! ```text, no_run
+//! let tracer = opentelemetry_otlp::new_pipeline()
+//!     .with_endpoint("http://localhost:4318")
+//!     .with_protocol(opentelemetry_otlp::Protocol::HttpBinary)
+//!     .with_http_client(reqwest::Client::new())
+//!     .install()?;
+//! ```
+//!
+//! The HTTP transport doesn't depend on tonic or tokio, so it can be used on
+//! `wasm32` targets, e.g. in browsers, without the default features. The
+//! `fetch-client` feature sends the requests with the `fetch` API and exports
+//! the spans in batches on the JavaScript event loop:
+//!
+//! ```toml
+//! [dependencies]
+//! opentelemetry-otlp = { version = "*", default-features = false, features = ["fetch-client"] }
+//! ```
+//!
+//! Requests can be compressed with gzip or zstd by enabling the `gzip-http`
+//! or `zstd-http` feature and setting [`OtlpPipelineBuilder::with_compression`]
+//! or the `OTEL_EXPORTER_OTLP_COMPRESSION` environment variable.
+//!
+//! ## Logs
+//!
+//! The `logs` feature adds a [`LogsExporter`] sending the records of the
+//! unstable logs SDK over gRPC with the same [`ExporterConfig`] as spans. It
+//! requires the tonic transport.
+//!
+//! ## TLS
+//!
+//! Custom root certificates, client certificates for mutual TLS and the
+//! domain name to verify are set with [`OtlpPipelineBuilder::with_tls_config`]
+//! for all transports, see [`TlsConfig`] for the required features. The root
+//! certificate can also be read from the file named by the
+//! `OTEL_EXPORTER_OTLP_CERTIFICATE` environment variable.
+//!
 //! ## Performance
 //!
 //! For optimal performance, a batch exporter is recommended as the simple
@@ -60,6 +105,19 @@
 //! [`tokio`]: https://tokio.rs
 //! [`async-std`]: https://async.rs
 //!
+//! ## Exporter selection
+//!
+//! [`exporter_factory`] registers the `otlp` exporter with the SDK exporter
+//! factory, so the exporters selected by the `OTEL_TRACES_EXPORTER`
+//! environment variable, `otlp` by default, can be added with
+//! `TracerProvider::builder().with_exporters_from_env`.
+//!
+//! ## Testing
+//!
+//! The `integration-testing` feature adds a [`testing::FakeCollector`] to
+//! check the spans exporters send and how they handle failing, slow and
+//! partially successful exports in end-to-end tests.
+//!
 //! ## Kitchen Sink Full Configuration
 //!
 //! Example showing how to override all configuration options. See the
@@ -119,20 +177,20 @@
 
 use opentelemetry::{global, sdk, trace::TracerProvider};
 
-#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+#[cfg(not(feature = "tonic"))]
 use std::collections::HashMap;
 
 use std::str::FromStr;
 use std::time::Duration;
 
-#[cfg(all(feature = "tonic", not(feature = "integration-testing")))]
+#[cfg(all(feature = "prost", not(feature = "integration-testing")))]
 #[rustfmt::skip]
-#[allow(clippy::all, unreachable_pub)]
+#[allow(clippy::all, unreachable_pub, dead_code)]
 mod proto;
 
 #[cfg(all(
     feature = "grpc-sys",
-    not(feature = "tonic"),
+    not(feature = "prost"),
     not(feature = "integration-testing")
 ))]
 #[allow(clippy::all, unreachable_pub, dead_code)]
@@ -142,27 +200,63 @@ mod proto;
 #[allow(missing_docs, unreachable_pub)]
 pub mod proto;
 
+#[cfg(all(feature = "grpc-sys", feature = "http-proto", not(feature = "tonic")))]
+compile_error!("the `http-proto` feature requires the `tonic` feature when `grpc-sys` is enabled");
+
+#[cfg(all(
+    feature = "http-proto",
+    target_arch = "wasm32",
+    not(feature = "fetch-client")
+))]
+compile_error!("OTLP/HTTP on `wasm32` targets requires the `fetch-client` feature");
+
+mod diagnostics;
+#[cfg(feature = "http-proto")]
+mod http_exporter;
+#[cfg(all(feature = "tonic", feature = "logs"))]
+mod logs;
 #[cfg(feature = "metrics")]
 #[allow(warnings)]
 mod metric;
+mod partial_success;
+mod retry;
 mod span;
+#[cfg(feature = "integration-testing")]
+pub mod testing;
+mod tls;
 mod transform;
 
 #[cfg(feature = "tonic")]
 use tonic::metadata::MetadataMap;
 
-#[cfg(all(feature = "tonic", feature = "tls"))]
-use tonic::transport::ClientTlsConfig;
+pub use crate::diagnostics::{ConnectionState, ExportFailure, FailureKind};
+pub use crate::partial_success::ExportStats;
+pub use crate::retry::RetryConfig;
+pub use crate::span::ExporterConfig;
+#[cfg(any(feature = "tonic", feature = "grpc-sys"))]
+pub use crate::span::TraceExporter;
+pub use crate::tls::TlsConfig;
 
-pub use crate::span::{ExporterConfig, TraceExporter};
+#[cfg(feature = "http-proto")]
+pub use crate::http_exporter::HttpTraceExporter;
+#[cfg(feature = "http-proto")]
+pub use opentelemetry_http::HttpClient;
+
+#[cfg(all(feature = "tonic", feature = "logs"))]
+pub use crate::logs::LogsExporter;
 
 #[cfg(feature = "metrics")]
 pub use crate::metric::{new_metrics_pipeline, MetricsExporter, OtlpMetricPipelineBuilder};
 
+pub use crate::span::Compression;
+
 #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
-pub use crate::span::{Compression, Credentials};
+pub use crate::span::Credentials;
 
-use opentelemetry::sdk::export::ExportError;
+use opentelemetry::sdk::export::{
+    trace::{factory::ExporterFactory, SpanExporter},
+    ExportError,
+};
 use opentelemetry::trace::TraceError;
 
 /// Create a new pipeline builder with the recommended configuration.
@@ -180,6 +274,35 @@ pub fn new_pipeline() -> OtlpPipelineBuilder {
     OtlpPipelineBuilder::default()
 }
 
+/// Register the `otlp` span exporter with `factory`.
+///
+/// The exporter is configured from the `OTEL_EXPORTER_OTLP_*` environment
+/// variables, see [`OtlpPipelineBuilder::with_env`]. With the `async` feature
+/// enabled the gRPC exporter must be created within a Tokio runtime.
+pub fn register_exporter(factory: ExporterFactory) -> ExporterFactory {
+    factory.with_exporter("otlp", || new_pipeline().with_env().span_exporter())
+}
+
+/// Create an [`ExporterFactory`] providing the `otlp` span exporter in
+/// addition to the exporters of the SDK.
+///
+/// As `otlp` is the exporter selected when `OTEL_TRACES_EXPORTER` is not set,
+/// this factory creates the exporter the specification defines by default.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use opentelemetry::sdk::trace::TracerProvider;
+///
+/// let provider = TracerProvider::builder()
+///     .with_exporters_from_env(&opentelemetry_otlp::exporter_factory())
+///     .expect("invalid OTEL_TRACES_EXPORTER")
+///     .build();
+/// ```
+pub fn exporter_factory() -> ExporterFactory {
+    register_exporter(ExporterFactory::new())
+}
+
 /// Recommended configuration for an Otlp exporter pipeline.
 ///
 /// ## Examples
@@ -195,6 +318,8 @@ pub fn new_pipeline() -> OtlpPipelineBuilder {
 pub struct OtlpPipelineBuilder {
     exporter_config: ExporterConfig,
     trace_config: Option<sdk::trace::Config>,
+    #[cfg(feature = "http-proto")]
+    http_client: Option<Box<dyn HttpClient>>,
 }
 
 /// Target to which the exporter is going to send spans or metrics, defaults to https://localhost:4317.
@@ -208,9 +333,33 @@ const OTEL_EXPORTER_OTLP_TIMEOUT_DEFAULT: u64 = 10;
 
 /// Target to which the exporter is going to send spans, defaults to https://localhost:4317.
 const OTEL_EXPORTER_OTLP_TRACES_ENDPOINT: &str = "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT";
+/// Target to which the exporter is going to send metrics, defaults to https://localhost:4317.
+#[cfg(feature = "metrics")]
+const OTEL_EXPORTER_OTLP_METRICS_ENDPOINT: &str = "OTEL_EXPORTER_OTLP_METRICS_ENDPOINT";
+
 /// Max waiting time for the backend to process each spans batch, defaults to 10s.
 const OTEL_EXPORTER_OTLP_TRACES_TIMEOUT: &str = "OTEL_EXPORTER_OTLP_TRACES_TIMEOUT";
 
+/// Key-value pairs added as headers to every export request, e.g. `api-key=secret,tenant=a`.
+const OTEL_EXPORTER_OTLP_HEADERS: &str = "OTEL_EXPORTER_OTLP_HEADERS";
+/// Key-value pairs added as headers to every span export request.
+const OTEL_EXPORTER_OTLP_TRACES_HEADERS: &str = "OTEL_EXPORTER_OTLP_TRACES_HEADERS";
+
+/// Compression used for spans or metrics requests, one of `gzip`, `zstd` or `none`.
+const OTEL_EXPORTER_OTLP_COMPRESSION: &str = "OTEL_EXPORTER_OTLP_COMPRESSION";
+/// Compression used for span requests, one of `gzip`, `zstd` or `none`.
+const OTEL_EXPORTER_OTLP_TRACES_COMPRESSION: &str = "OTEL_EXPORTER_OTLP_TRACES_COMPRESSION";
+
+/// Path of the PEM encoded certificate used to verify the collector.
+const OTEL_EXPORTER_OTLP_CERTIFICATE: &str = "OTEL_EXPORTER_OTLP_CERTIFICATE";
+/// Path of the PEM encoded certificate used to verify the collector for spans.
+const OTEL_EXPORTER_OTLP_TRACES_CERTIFICATE: &str = "OTEL_EXPORTER_OTLP_TRACES_CERTIFICATE";
+
+/// Protocol used to send spans or metrics, one of `grpc`, `http/protobuf` or `http/json`.
+const OTEL_EXPORTER_OTLP_PROTOCOL: &str = "OTEL_EXPORTER_OTLP_PROTOCOL";
+/// Protocol used to send spans, one of `grpc`, `http/protobuf` or `http/json`.
+const OTEL_EXPORTER_OTLP_TRACES_PROTOCOL: &str = "OTEL_EXPORTER_OTLP_TRACES_PROTOCOL";
+
 impl OtlpPipelineBuilder {
     /// Set the address of the OTLP collector. If not set, the default address is used.
     pub fn with_endpoint<T: Into<String>>(mut self, endpoint: T) -> Self {
@@ -224,9 +373,23 @@ impl OtlpPipelineBuilder {
         self
     }
 
+    /// Set the HTTP client used to send spans with the `http/protobuf` and
+    /// `http/json` protocols.
+    ///
+    /// Defaults to a [`reqwest::Client`] if the `reqwest-client` feature is
+    /// enabled.
+    ///
+    /// [`reqwest::Client`]: https://docs.rs/reqwest/0.11/reqwest/struct.Client.html
+    #[cfg(feature = "http-proto")]
+    pub fn with_http_client<T: HttpClient + 'static>(mut self, client: T) -> Self {
+        self.http_client = Some(Box::new(client));
+        self
+    }
+
     /// Set the TLS settings for the collector endpoint.
-    #[cfg(all(feature = "tonic", feature = "tls"))]
-    pub fn with_tls_config(mut self, tls_config: ClientTlsConfig) -> Self {
+    ///
+    /// See [`TlsConfig`] for the features each transport requires.
+    pub fn with_tls_config(mut self, tls_config: TlsConfig) -> Self {
         self.exporter_config.tls_config = Some(tls_config);
         self
     }
@@ -246,14 +409,15 @@ impl OtlpPipelineBuilder {
     }
 
     /// Set Additional headers to send to the collector.
-    #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+    #[cfg(not(feature = "tonic"))]
     pub fn with_headers(mut self, headers: HashMap<String, String>) -> Self {
         self.exporter_config.headers = Some(headers);
         self
     }
 
     /// Set the compression algorithm to use when communicating with the collector.
-    #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+    ///
+    /// See [`Compression`] for the algorithms supported by each transport.
     pub fn with_compression(mut self, compression: Compression) -> Self {
         self.exporter_config.compression = Some(compression);
         self
@@ -272,6 +436,39 @@ impl OtlpPipelineBuilder {
         self
     }
 
+    /// Set how exports failing with a transient error are retried.
+    ///
+    /// Retries are enabled with the [`RetryConfig`] defaults unless disabled
+    /// with [`without_retry`].
+    ///
+    /// [`without_retry`]: OtlpPipelineBuilder::without_retry
+    pub fn with_retry_config(mut self, retry: RetryConfig) -> Self {
+        self.exporter_config.retry = Some(retry);
+        self
+    }
+
+    /// Drop batches that failed to export instead of retrying them.
+    pub fn without_retry(mut self) -> Self {
+        self.exporter_config.retry = None;
+        self
+    }
+
+    /// Count the spans the collector reports as rejected and record the
+    /// outcome of export attempts in `stats`.
+    ///
+    /// Rejected spans are also reported to the global error handler.
+    pub fn with_export_stats(mut self, stats: ExportStats) -> Self {
+        self.exporter_config.stats = stats;
+        self
+    }
+
+    /// Convert and encode batches of at least 512 spans on up to
+    /// `parallelism` threads, see [`ExporterConfig::encoding_parallelism`].
+    pub fn with_encoding_parallelism(mut self, parallelism: usize) -> Self {
+        self.exporter_config.encoding_parallelism = parallelism;
+        self
+    }
+
     /// Set the number of GRPC worker threads to poll queues.
     #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
     pub fn with_completion_queue_count(mut self, count: usize) -> Self {
@@ -287,58 +484,320 @@ impl OtlpPipelineBuilder {
 
     /// Set the trace provider configuration from the given environment variables.
     ///
+    /// The trace specific variables, e.g. `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT`,
+    /// take precedence over the generic ones like `OTEL_EXPORTER_OTLP_ENDPOINT`.
+    /// Endpoint and timeout set on the builder are kept, headers from
+    /// `OTEL_EXPORTER_OTLP_HEADERS` and `OTEL_EXPORTER_OTLP_TRACES_HEADERS` are
+    /// only added if the builder does not set the same key. The certificate
+    /// read from `OTEL_EXPORTER_OTLP_CERTIFICATE` is used unless the builder
+    /// sets a CA certificate.
+    ///
     /// If the value in environment variables is illegal, will fall back to use default value.
     pub fn with_env(mut self) -> Self {
-        let endpoint = match std::env::var(OTEL_EXPORTER_OTLP_TRACES_ENDPOINT) {
-            Ok(val) => val,
-            Err(_) => std::env::var(OTEL_EXPORTER_OTLP_ENDPOINT)
-                .unwrap_or_else(|_| OTEL_EXPORTER_OTLP_ENDPOINT_DEFAULT.to_string()),
-        };
-        self.exporter_config.endpoint = endpoint;
+        if self.exporter_config.endpoint == OTEL_EXPORTER_OTLP_ENDPOINT_DEFAULT {
+            let endpoint = match std::env::var(OTEL_EXPORTER_OTLP_TRACES_ENDPOINT) {
+                Ok(val) => val,
+                Err(_) => std::env::var(OTEL_EXPORTER_OTLP_ENDPOINT)
+                    .unwrap_or_else(|_| OTEL_EXPORTER_OTLP_ENDPOINT_DEFAULT.to_string()),
+            };
+            self.exporter_config.endpoint = endpoint;
+        }
 
-        let timeout = match std::env::var(OTEL_EXPORTER_OTLP_TRACES_TIMEOUT) {
-            Ok(val) => u64::from_str(&val).unwrap_or(OTEL_EXPORTER_OTLP_TIMEOUT_DEFAULT),
-            Err(_) => std::env::var(OTEL_EXPORTER_OTLP_TIMEOUT)
-                .map(|val| u64::from_str(&val).unwrap_or(OTEL_EXPORTER_OTLP_TIMEOUT_DEFAULT))
-                .unwrap_or(OTEL_EXPORTER_OTLP_TIMEOUT_DEFAULT),
-        };
-        self.exporter_config.timeout = Duration::from_secs(timeout);
+        if self.exporter_config.timeout == Duration::from_secs(OTEL_EXPORTER_OTLP_TIMEOUT_DEFAULT) {
+            let timeout = match std::env::var(OTEL_EXPORTER_OTLP_TRACES_TIMEOUT) {
+                Ok(val) => u64::from_str(&val).unwrap_or(OTEL_EXPORTER_OTLP_TIMEOUT_DEFAULT),
+                Err(_) => std::env::var(OTEL_EXPORTER_OTLP_TIMEOUT)
+                    .map(|val| u64::from_str(&val).unwrap_or(OTEL_EXPORTER_OTLP_TIMEOUT_DEFAULT))
+                    .unwrap_or(OTEL_EXPORTER_OTLP_TIMEOUT_DEFAULT),
+            };
+            self.exporter_config.timeout = Duration::from_secs(timeout);
+        }
+
+        let mut headers = Vec::new();
+        for var in &[
+            OTEL_EXPORTER_OTLP_TRACES_HEADERS,
+            OTEL_EXPORTER_OTLP_HEADERS,
+        ] {
+            if let Ok(val) = std::env::var(var) {
+                headers.extend(parse_headers(&val));
+            }
+        }
+        if !headers.is_empty() {
+            self.add_default_headers(headers);
+        }
+
+        if self.exporter_config.compression.is_none() {
+            self.exporter_config.compression = std::env::var(OTEL_EXPORTER_OTLP_TRACES_COMPRESSION)
+                .or_else(|_| std::env::var(OTEL_EXPORTER_OTLP_COMPRESSION))
+                .ok()
+                .and_then(|val| Compression::from_str(&val).ok());
+        }
+
+        let has_ca_cert = matches!(
+            self.exporter_config.tls_config,
+            Some(TlsConfig {
+                ca_cert: Some(_),
+                ..
+            })
+        );
+        if !has_ca_cert {
+            let path = std::env::var(OTEL_EXPORTER_OTLP_TRACES_CERTIFICATE)
+                .or_else(|_| std::env::var(OTEL_EXPORTER_OTLP_CERTIFICATE));
+            if let Ok(path) = path {
+                match std::fs::read(&path) {
+                    Ok(ca_cert) => {
+                        self.exporter_config
+                            .tls_config
+                            .get_or_insert_with(TlsConfig::default)
+                            .ca_cert = Some(ca_cert)
+                    }
+                    Err(err) => global::handle_error(TraceError::from(format!(
+                        "cannot read OTLP certificate {}: {}",
+                        path, err
+                    ))),
+                }
+            }
+        }
+
+        let protocol = std::env::var(OTEL_EXPORTER_OTLP_TRACES_PROTOCOL)
+            .or_else(|_| std::env::var(OTEL_EXPORTER_OTLP_PROTOCOL))
+            .ok()
+            .and_then(|val| Protocol::from_str(&val).ok());
+        if let Some(protocol) = protocol {
+            self.exporter_config.protocol = protocol;
+        }
+        self
+    }
+
+    /// Add headers whose keys are not set yet, earlier entries win.
+    #[cfg(feature = "tonic")]
+    fn add_default_headers(&mut self, headers: Vec<(String, String)>) {
+        let metadata = self
+            .exporter_config
+            .metadata
+            .get_or_insert_with(MetadataMap::new);
+        for (key, value) in headers {
+            match (
+                tonic::metadata::MetadataKey::from_bytes(key.as_bytes()),
+                value.parse(),
+            ) {
+                (Ok(key), Ok(value)) => {
+                    if !metadata.contains_key(&key) {
+                        metadata.insert(key, value);
+                    }
+                }
+                _ => global::handle_error(TraceError::from(format!("invalid OTLP header {}", key))),
+            }
+        }
+    }
+
+    /// Add headers whose keys are not set yet, earlier entries win.
+    #[cfg(not(feature = "tonic"))]
+    fn add_default_headers(&mut self, headers: Vec<(String, String)>) {
+        let map = self
+            .exporter_config
+            .headers
+            .get_or_insert_with(HashMap::new);
+        for (key, value) in headers {
+            map.entry(key.to_lowercase()).or_insert(value);
+        }
+    }
+
+    /// Connect to the collector when the pipeline is installed instead of on
+    /// the first export.
+    ///
+    /// Installing the pipeline fails if the collector can not be reached or
+    /// rejects an empty export request, so services can fail fast on a
+    /// misconfigured endpoint. Without this the connection is established
+    /// lazily and errors are only reported when a batch fails to export.
+    ///
+    /// With the `async` feature enabled use [`install_async`] to connect
+    /// eagerly.
+    ///
+    /// [`install_async`]: OtlpPipelineBuilder::install_async
+    pub fn connect_eagerly(mut self) -> Self {
+        self.exporter_config.connect_eagerly = true;
         self
     }
 
     /// Install the OTLP exporter pipeline with the recommended defaults.
+    ///
+    /// With the HTTP protocols and [`connect_eagerly`] the readiness check is
+    /// run with [`futures::executor::block_on`], clients relying on a runtime
+    /// must be installed from within that runtime.
+    ///
+    /// [`connect_eagerly`]: OtlpPipelineBuilder::connect_eagerly
     #[cfg(feature = "tonic")]
+    #[cfg_attr(not(feature = "http-proto"), allow(unused_mut))]
     pub fn install(mut self) -> Result<sdk::trace::Tracer, TraceError> {
-        let exporter = TraceExporter::new(self.exporter_config)?;
+        #[cfg(feature = "http-proto")]
+        {
+            if self.exporter_config.protocol.is_http() {
+                let connect_eagerly = self.exporter_config.connect_eagerly;
+                let exporter = self.http_exporter()?;
+                if connect_eagerly {
+                    futures::executor::block_on(exporter.ready())?;
+                }
+                return Ok(build_tracer(exporter, self.trace_config));
+            }
+        }
+
+        let connect_eagerly = self.exporter_config.connect_eagerly;
+        let mut exporter = TraceExporter::new(self.exporter_config)?;
+        if connect_eagerly {
+            futures::executor::block_on(exporter.ready())?;
+        }
+
+        Ok(build_tracer(exporter, self.trace_config))
+    }
+
+    /// Install the OTLP exporter pipeline with the recommended defaults,
+    /// connecting to the collector first if [`connect_eagerly`] is set.
+    ///
+    /// [`connect_eagerly`]: OtlpPipelineBuilder::connect_eagerly
+    #[cfg(all(feature = "tonic", feature = "async"))]
+    #[cfg_attr(not(feature = "http-proto"), allow(unused_mut))]
+    pub async fn install_async(mut self) -> Result<sdk::trace::Tracer, TraceError> {
+        #[cfg(feature = "http-proto")]
+        {
+            if self.exporter_config.protocol.is_http() {
+                let connect_eagerly = self.exporter_config.connect_eagerly;
+                let exporter = self.http_exporter()?;
+                if connect_eagerly {
+                    exporter.ready().await?;
+                }
+                return Ok(build_tracer(exporter, self.trace_config));
+            }
+        }
 
-        let mut provider_builder = sdk::trace::TracerProvider::builder().with_exporter(exporter);
-        if let Some(config) = self.trace_config.take() {
-            provider_builder = provider_builder.with_config(config);
+        let connect_eagerly = self.exporter_config.connect_eagerly;
+        let mut exporter = TraceExporter::connect(self.exporter_config).await?;
+        if connect_eagerly {
+            exporter.ready().await?;
         }
-        let provider = provider_builder.build();
-        let tracer = provider.get_tracer("opentelemetry-otlp", Some(env!("CARGO_PKG_VERSION")));
-        let _ = global::set_tracer_provider(provider);
 
-        Ok(tracer)
+        Ok(build_tracer(exporter, self.trace_config))
     }
 
     /// Install the OTLP exporter pipeline with the recommended defaults.
     #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+    pub fn install(self) -> Result<sdk::trace::Tracer, TraceError> {
+        let connect_eagerly = self.exporter_config.connect_eagerly;
+        let mut exporter = TraceExporter::new(self.exporter_config);
+        if connect_eagerly {
+            futures::executor::block_on(exporter.ready())?;
+        }
+
+        Ok(build_tracer(exporter, self.trace_config))
+    }
+
+    /// Build the span exporter of the configured protocol without connecting
+    /// to the collector.
+    #[cfg(feature = "tonic")]
+    #[cfg_attr(not(feature = "http-proto"), allow(unused_mut))]
+    fn span_exporter(mut self) -> Result<Box<dyn SpanExporter>, TraceError> {
+        #[cfg(feature = "http-proto")]
+        {
+            if self.exporter_config.protocol.is_http() {
+                return Ok(Box::new(self.http_exporter()?));
+            }
+        }
+
+        Ok(Box::new(TraceExporter::new(self.exporter_config)?))
+    }
+
+    /// Build the span exporter without connecting to the collector.
+    #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+    fn span_exporter(self) -> Result<Box<dyn SpanExporter>, TraceError> {
+        Ok(Box::new(TraceExporter::new(self.exporter_config)))
+    }
+
+    /// Install the OTLP/HTTP exporter pipeline with the recommended defaults.
+    ///
+    /// Without the `tonic` and `grpc-sys` features only the HTTP protocols
+    /// are available, `http/protobuf` being the default. With
+    /// [`connect_eagerly`] the readiness check is run with
+    /// [`futures::executor::block_on`], which `wasm32` targets don't support.
+    ///
+    /// [`connect_eagerly`]: OtlpPipelineBuilder::connect_eagerly
+    #[cfg(not(any(feature = "tonic", feature = "grpc-sys")))]
     pub fn install(mut self) -> Result<sdk::trace::Tracer, TraceError> {
-        let exporter = TraceExporter::new(self.exporter_config);
+        let connect_eagerly = self.exporter_config.connect_eagerly;
+        let exporter = self.http_exporter()?;
+        if connect_eagerly {
+            futures::executor::block_on(exporter.ready())?;
+        }
+
+        Ok(build_tracer(exporter, self.trace_config))
+    }
 
-        let mut provider_builder = sdk::trace::TracerProvider::builder().with_exporter(exporter);
-        if let Some(config) = self.trace_config.take() {
-            provider_builder = provider_builder.with_config(config);
+    /// Build the OTLP/HTTP span exporter.
+    #[cfg(not(any(feature = "tonic", feature = "grpc-sys")))]
+    fn span_exporter(mut self) -> Result<Box<dyn SpanExporter>, TraceError> {
+        Ok(Box::new(self.http_exporter()?))
+    }
+
+    /// Take the exporter config and client to build an OTLP/HTTP exporter.
+    #[cfg(feature = "http-proto")]
+    fn http_exporter(&mut self) -> Result<HttpTraceExporter, TraceError> {
+        #[cfg(not(any(feature = "tonic", feature = "grpc-sys")))]
+        {
+            if !self.exporter_config.protocol.is_http() {
+                return Err("grpc requires the `tonic` or `grpc-sys` feature".into());
+            }
         }
-        let provider = provider_builder.build();
-        let tracer = provider.get_tracer("opentelemetry-otlp", Some(env!("CARGO_PKG_VERSION")));
-        let _ = global::set_tracer_provider(provider);
 
-        Ok(tracer)
+        let client = match (self.http_client.take(), &self.exporter_config.tls_config) {
+            (Some(_), Some(_)) => {
+                return Err(Error::InvalidTlsConfig(
+                    "TLS settings only apply to the default http client",
+                )
+                .into())
+            }
+            (Some(client), None) => client,
+            #[cfg(all(feature = "fetch-client", target_arch = "wasm32"))]
+            (None, None) => Box::new(opentelemetry_http::FetchClient),
+            #[cfg(all(feature = "fetch-client", target_arch = "wasm32"))]
+            (None, Some(_)) => {
+                return Err(Error::InvalidTlsConfig(
+                    "TLS settings are left to the JavaScript environment on wasm32 targets",
+                )
+                .into())
+            }
+            #[cfg(all(
+                feature = "reqwest-client",
+                not(all(feature = "fetch-client", target_arch = "wasm32"))
+            ))]
+            (None, tls_config) => Box::new(http_exporter::ReqwestClient::new(tls_config)?),
+            #[cfg(not(any(
+                feature = "reqwest-client",
+                all(feature = "fetch-client", target_arch = "wasm32")
+            )))]
+            (None, _) => return Err("http/protobuf and http/json require a http client".into()),
+        };
+
+        Ok(HttpTraceExporter::new(
+            std::mem::take(&mut self.exporter_config),
+            client,
+        )?)
     }
 }
 
+fn build_tracer<E: SpanExporter + 'static>(
+    exporter: E,
+    trace_config: Option<sdk::trace::Config>,
+) -> sdk::trace::Tracer {
+    let mut provider_builder = sdk::trace::TracerProvider::builder().with_exporter(exporter);
+    if let Some(config) = trace_config {
+        provider_builder = provider_builder.with_config(config);
+    }
+    let provider = provider_builder.build();
+    let tracer = provider.get_tracer("opentelemetry-otlp", Some(env!("CARGO_PKG_VERSION")));
+    let _ = global::set_tracer_provider(provider);
+
+    tracer
+}
+
 /// Wrap type for errors from opentelemetry otel
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -357,6 +816,59 @@ pub enum Error {
     #[error("status error {0}")]
     Status(#[from] tonic::Status),
 
+    /// Eager connections need an async context when the `async` feature is enabled
+    #[cfg(all(feature = "tonic", feature = "async"))]
+    #[error("connecting eagerly requires `TraceExporter::connect` or `install_async` with the async feature")]
+    EagerConnectRequiresAsync,
+
+    /// Error encoding an OTLP/HTTP request
+    #[cfg(feature = "http-proto")]
+    #[error("encoding error {0}")]
+    Encode(#[from] prost::EncodeError),
+
+    /// Error encoding an OTLP/JSON request
+    #[cfg(feature = "http-json")]
+    #[error("json error {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// Error building an OTLP/HTTP request
+    #[cfg(feature = "http-proto")]
+    #[error("http error {0}")]
+    Http(#[from] http::Error),
+
+    /// The TLS settings are incomplete or not supported by the transport
+    #[error("invalid TLS settings: {0}")]
+    InvalidTlsConfig(&'static str),
+
+    /// The compression algorithm is not supported by the transport
+    #[error("{0} compression is not supported by the transport or enabled features")]
+    UnsupportedCompression(Compression),
+
+    /// Error compressing an OTLP/HTTP request
+    #[cfg(any(feature = "gzip-http", feature = "zstd-http"))]
+    #[error("compression error {0}")]
+    Compression(#[from] std::io::Error),
+
+    /// The collector responded to an OTLP/HTTP request with an error status
+    #[cfg(feature = "http-proto")]
+    #[error("http status {status}")]
+    HttpStatus {
+        /// Status of the response
+        status: http::StatusCode,
+        /// Delay requested by a `Retry-After` header
+        retry_after: Option<Duration>,
+    },
+
+    /// Error sending an OTLP/HTTP request
+    #[cfg(feature = "http-proto")]
+    #[error("http client error {0}")]
+    HttpClient(TraceError),
+
+    /// Error from reqwest
+    #[cfg(feature = "reqwest-client")]
+    #[error("reqwest error {0}")]
+    Reqwest(#[from] reqwest::Error),
+
     /// Error from grpcio module
     #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
     #[error("grpcio error {0}")]
@@ -370,22 +882,98 @@ impl ExportError for Error {
 }
 
 /// The communication protocol to use when sending data.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Protocol {
     /// GRPC protocol
     Grpc,
-    // TODO add support for other protocols
-    // HttpJson,
-    // HttpProto,
+    /// Binary protobuf encoded requests over HTTP
+    #[cfg(feature = "http-proto")]
+    HttpBinary,
+    /// JSON encoded requests over HTTP
+    #[cfg(feature = "http-json")]
+    HttpJson,
+}
+
+impl Protocol {
+    /// Whether requests are sent over HTTP/1.1 instead of gRPC.
+    #[cfg(feature = "http-proto")]
+    fn is_http(self) -> bool {
+        self != Protocol::Grpc
+    }
+}
+
+impl FromStr for Protocol {
+    type Err = String;
+
+    /// Parse a protocol from its `OTEL_EXPORTER_OTLP_PROTOCOL` value.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "grpc" => Ok(Protocol::Grpc),
+            #[cfg(feature = "http-proto")]
+            "http/protobuf" => Ok(Protocol::HttpBinary),
+            #[cfg(feature = "http-json")]
+            "http/json" => Ok(Protocol::HttpJson),
+            other => Err(format!("unsupported OTLP protocol {}", other)),
+        }
+    }
+}
+
+/// Parse a list of `key=value` pairs separated by commas, values may be
+/// percent-encoded.
+fn parse_headers(value: &str) -> Vec<(String, String)> {
+    value
+        .split(',')
+        .filter_map(|pair| {
+            let mut parts = pair.splitn(2, '=');
+            let key = parts.next()?.trim();
+            let value = parts.next()?.trim();
+            if key.is_empty() {
+                None
+            } else {
+                Some((key.to_string(), percent_decode(value)))
+            }
+        })
+        .collect()
+}
+
+/// Decode `%XX` escapes, invalid escapes are kept as they are.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = if bytes[i] == b'%' && i + 2 < bytes.len() {
+            std::str::from_utf8(&bytes[i + 1..i + 3])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        } else {
+            None
+        };
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        new_pipeline, OTEL_EXPORTER_OTLP_ENDPOINT, OTEL_EXPORTER_OTLP_TIMEOUT,
-        OTEL_EXPORTER_OTLP_TIMEOUT_DEFAULT, OTEL_EXPORTER_OTLP_TRACES_ENDPOINT,
+        new_pipeline, parse_headers, Compression, TlsConfig, OTEL_EXPORTER_OTLP_CERTIFICATE,
+        OTEL_EXPORTER_OTLP_COMPRESSION, OTEL_EXPORTER_OTLP_ENDPOINT, OTEL_EXPORTER_OTLP_HEADERS,
+        OTEL_EXPORTER_OTLP_TIMEOUT, OTEL_EXPORTER_OTLP_TIMEOUT_DEFAULT,
+        OTEL_EXPORTER_OTLP_TRACES_CERTIFICATE, OTEL_EXPORTER_OTLP_TRACES_COMPRESSION,
+        OTEL_EXPORTER_OTLP_TRACES_ENDPOINT, OTEL_EXPORTER_OTLP_TRACES_HEADERS,
         OTEL_EXPORTER_OTLP_TRACES_TIMEOUT,
     };
+    use std::time::Duration;
 
     #[test]
     fn test_pipeline_builder_from_env() {
@@ -436,5 +1024,190 @@ mod tests {
         std::env::remove_var(OTEL_EXPORTER_OTLP_TRACES_TIMEOUT);
         assert!(std::env::var(OTEL_EXPORTER_OTLP_TRACES_ENDPOINT).is_err());
         assert!(std::env::var(OTEL_EXPORTER_OTLP_TRACES_TIMEOUT).is_err());
+
+        // builder values take precedence
+        std::env::set_var(OTEL_EXPORTER_OTLP_ENDPOINT, "https://otlp_endpoint:4317");
+        std::env::set_var(OTEL_EXPORTER_OTLP_TIMEOUT, "60");
+        let pipeline_builder = new_pipeline()
+            .with_endpoint("https://builder:4317")
+            .with_timeout(Duration::from_secs(3))
+            .with_env();
+        assert_eq!(
+            pipeline_builder.exporter_config.endpoint,
+            "https://builder:4317"
+        );
+        assert_eq!(
+            pipeline_builder.exporter_config.timeout,
+            Duration::from_secs(3)
+        );
+        std::env::remove_var(OTEL_EXPORTER_OTLP_ENDPOINT);
+        std::env::remove_var(OTEL_EXPORTER_OTLP_TIMEOUT);
+    }
+
+    #[test]
+    #[cfg(feature = "tonic")]
+    fn test_headers_from_env() {
+        std::env::set_var(OTEL_EXPORTER_OTLP_HEADERS, "api-key=generic,tenant=a");
+        std::env::set_var(OTEL_EXPORTER_OTLP_TRACES_HEADERS, "api-key=traces");
+
+        let mut metadata = tonic::metadata::MetadataMap::new();
+        metadata.insert("tenant", "builder".parse().unwrap());
+        let pipeline_builder = new_pipeline().with_metadata(metadata).with_env();
+        let metadata = pipeline_builder.exporter_config.metadata.unwrap();
+        assert_eq!(metadata.get("api-key").unwrap(), "traces");
+        assert_eq!(metadata.get("tenant").unwrap(), "builder");
+
+        std::env::remove_var(OTEL_EXPORTER_OTLP_HEADERS);
+        std::env::remove_var(OTEL_EXPORTER_OTLP_TRACES_HEADERS);
+    }
+
+    #[test]
+    #[cfg(not(feature = "tonic"))]
+    fn test_headers_from_env() {
+        std::env::set_var(OTEL_EXPORTER_OTLP_HEADERS, "api-key=generic,tenant=a");
+        std::env::set_var(OTEL_EXPORTER_OTLP_TRACES_HEADERS, "api-key=traces");
+
+        let mut headers = std::collections::HashMap::new();
+        headers.insert("tenant".to_string(), "builder".to_string());
+        let pipeline_builder = new_pipeline().with_headers(headers).with_env();
+        let headers = pipeline_builder.exporter_config.headers.unwrap();
+        assert_eq!(headers["api-key"], "traces");
+        assert_eq!(headers["tenant"], "builder");
+
+        std::env::remove_var(OTEL_EXPORTER_OTLP_HEADERS);
+        std::env::remove_var(OTEL_EXPORTER_OTLP_TRACES_HEADERS);
+    }
+
+    #[test]
+    fn test_compression_from_env() {
+        std::env::set_var(OTEL_EXPORTER_OTLP_COMPRESSION, "gzip");
+        let pipeline_builder = new_pipeline().with_env();
+        assert_eq!(
+            pipeline_builder.exporter_config.compression,
+            Some(Compression::Gzip)
+        );
+
+        std::env::set_var(OTEL_EXPORTER_OTLP_TRACES_COMPRESSION, "none");
+        let pipeline_builder = new_pipeline().with_env();
+        assert_eq!(pipeline_builder.exporter_config.compression, None);
+
+        let pipeline_builder = new_pipeline()
+            .with_compression(Compression::Zstd)
+            .with_env();
+        assert_eq!(
+            pipeline_builder.exporter_config.compression,
+            Some(Compression::Zstd)
+        );
+
+        std::env::remove_var(OTEL_EXPORTER_OTLP_COMPRESSION);
+        std::env::remove_var(OTEL_EXPORTER_OTLP_TRACES_COMPRESSION);
+    }
+
+    #[test]
+    #[cfg(all(feature = "tonic", not(feature = "async")))]
+    fn test_tonic_compression_unsupported() {
+        let pipeline = new_pipeline().with_compression(Compression::Gzip);
+        assert!(matches!(
+            crate::TraceExporter::new(pipeline.exporter_config),
+            Err(crate::Error::UnsupportedCompression(Compression::Gzip))
+        ));
+    }
+
+    #[test]
+    fn test_certificate_from_env() {
+        let path = std::env::temp_dir().join("otlp-test-ca.pem");
+        std::fs::write(&path, "ca").unwrap();
+        std::env::set_var(OTEL_EXPORTER_OTLP_CERTIFICATE, &path);
+        let pipeline_builder = new_pipeline().with_env();
+        assert_eq!(
+            pipeline_builder.exporter_config.tls_config,
+            Some(TlsConfig {
+                ca_cert: Some(b"ca".to_vec()),
+                ..Default::default()
+            })
+        );
+
+        let tls_config = TlsConfig {
+            ca_cert: Some(b"builder".to_vec()),
+            domain_name: Some("collector".to_string()),
+            ..Default::default()
+        };
+        let pipeline_builder = new_pipeline()
+            .with_tls_config(tls_config.clone())
+            .with_env();
+        assert_eq!(
+            pipeline_builder.exporter_config.tls_config,
+            Some(tls_config)
+        );
+
+        std::env::set_var(
+            OTEL_EXPORTER_OTLP_TRACES_CERTIFICATE,
+            path.with_extension("missing"),
+        );
+        let pipeline_builder = new_pipeline().with_env();
+        assert_eq!(pipeline_builder.exporter_config.tls_config, None);
+
+        std::env::remove_var(OTEL_EXPORTER_OTLP_CERTIFICATE);
+        std::env::remove_var(OTEL_EXPORTER_OTLP_TRACES_CERTIFICATE);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    #[cfg(all(feature = "tonic", not(feature = "tls"), not(feature = "async")))]
+    fn test_tonic_tls_requires_feature() {
+        let pipeline = new_pipeline().with_tls_config(TlsConfig::default());
+        assert!(matches!(
+            crate::TraceExporter::new(pipeline.exporter_config),
+            Err(crate::Error::InvalidTlsConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_exporter_factory() {
+        let factory = crate::exporter_factory();
+        assert_eq!(factory.names(), vec!["stdout", "otlp"]);
+
+        // the channel of the async exporter is driven by the current runtime
+        #[cfg(feature = "async")]
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        #[cfg(feature = "async")]
+        let _guard = runtime.enter();
+
+        // otlp is selected by default
+        std::env::remove_var("OTEL_TRACES_EXPORTER");
+        match factory.build_from_env() {
+            Ok(exporters) => assert_eq!(exporters.len(), 1),
+            Err(err) => assert!(!err.to_string().contains("unknown span exporter")),
+        }
+    }
+
+    #[test]
+    fn test_parse_headers() {
+        assert_eq!(
+            parse_headers("api-key=a%3Db%2Cc, Tenant = x ,invalid,=empty"),
+            vec![
+                ("api-key".to_string(), "a=b,c".to_string()),
+                ("Tenant".to_string(), "x".to_string()),
+            ]
+        );
+        assert_eq!(
+            parse_headers("k=100%"),
+            vec![("k".to_string(), "100%".to_string())]
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "tonic", not(feature = "async")))]
+    fn test_connect_eagerly() {
+        // nothing listens on port 1, connecting lazily only fails on export
+        let pipeline = new_pipeline().with_endpoint("http://127.0.0.1:1");
+        assert!(!pipeline.exporter_config.connect_eagerly);
+        assert!(crate::TraceExporter::new(pipeline.exporter_config).is_ok());
+
+        let pipeline = new_pipeline()
+            .with_endpoint("http://127.0.0.1:1")
+            .connect_eagerly();
+        assert!(pipeline.exporter_config.connect_eagerly);
+        assert!(pipeline.install().is_err());
     }
 }
@@ -0,0 +1,200 @@
+//! # OTLP - Log Exporter
+//!
+//! Defines a [LogExporter] to send log records via the OpenTelemetry Protocol
+//! (OTLP) over gRPC.
+//!
+//! [LogExporter]: opentelemetry::sdk::export::logs::LogExporter
+
+use crate::partial_success::ExportStats;
+use crate::proto::collector::logs::v1::{ExportLogsServiceRequest, ExportLogsServiceResponse};
+use crate::retry::{retry, RetryConfig};
+use crate::span::{build_endpoint, grpc_client, send_export};
+use crate::ExporterConfig;
+use async_trait::async_trait;
+use opentelemetry::sdk::export::logs::{ExportResult, LogData, LogExporter};
+use std::fmt;
+use tonic::{client::Grpc, metadata::MetadataMap, transport::Channel};
+
+/// Path of the export method of the logs service.
+const LOGS_EXPORT_PATH: &str = "/opentelemetry.proto.collector.logs.v1.LogsService/Export";
+
+/// Exporter that sends log records in OTLP format.
+pub struct LogsExporter {
+    client: Grpc<Channel>,
+    metadata: Option<MetadataMap>,
+    retry: Option<RetryConfig>,
+    stats: ExportStats,
+    #[cfg(not(feature = "async"))]
+    runtime: tokio::runtime::Runtime,
+}
+
+impl fmt::Debug for LogsExporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LogsExporter")
+            .field("metadata", &self.metadata)
+            .field("retry", &self.retry)
+            .field("stats", &self.stats)
+            .field("client", &"LogsService")
+            .finish()
+    }
+}
+
+impl LogsExporter {
+    /// Builds a new log exporter with the given configuration.
+    ///
+    /// The endpoint, timeout, TLS settings, metadata, retries and export
+    /// stats are used as by [`TraceExporter::new`], including its limits:
+    /// compression is not supported by the tonic transport and TLS settings
+    /// require the `tls` feature.
+    ///
+    /// [`TraceExporter::new`]: crate::TraceExporter::new
+    pub fn new(config: ExporterConfig) -> Result<Self, crate::Error> {
+        if let Some(compression) = config.compression {
+            return Err(crate::Error::UnsupportedCompression(compression));
+        }
+
+        #[cfg(not(feature = "async"))]
+        let runtime = config.runtime.unwrap_or_else(|| {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+        });
+
+        let endpoint = build_endpoint(config.endpoint, config.timeout, config.tls_config)?;
+
+        #[cfg(not(feature = "async"))]
+        let channel = {
+            // the channel spawns its worker onto the runtime it is created in
+            let _guard = runtime.enter();
+            if config.connect_eagerly {
+                runtime.block_on(endpoint.connect())?
+            } else {
+                endpoint.connect_lazy()?
+            }
+        };
+
+        #[cfg(feature = "async")]
+        let channel = if config.connect_eagerly {
+            return Err(crate::Error::EagerConnectRequiresAsync);
+        } else {
+            endpoint.connect_lazy()?
+        };
+
+        Ok(LogsExporter {
+            client: grpc_client(channel, config.metadata.to_owned()),
+            metadata: config.metadata,
+            retry: config.retry,
+            stats: config.stats,
+            #[cfg(not(feature = "async"))]
+            runtime,
+        })
+    }
+}
+
+#[async_trait]
+impl LogExporter for LogsExporter {
+    async fn export(&mut self, batch: Vec<LogData>) -> ExportResult {
+        let request = ExportLogsServiceRequest {
+            resource_logs: batch.into_iter().map(Into::into).collect(),
+        };
+        let client = &self.client;
+        let stats = &self.stats;
+        let export = retry(self.retry, || {
+            let attempt = send_export::<_, ExportLogsServiceResponse>(
+                client.to_owned(),
+                request.clone(),
+                LOGS_EXPORT_PATH,
+            );
+            async move { stats.record_attempt(attempt.await) }
+        });
+
+        #[cfg(feature = "async")]
+        export.await?;
+
+        #[cfg(not(feature = "async"))]
+        self.runtime.block_on(export)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::logs::v1::{ResourceLogs, SeverityNumber};
+    use crate::Compression;
+    use opentelemetry::logs::LogRecord;
+    use opentelemetry::sdk::{InstrumentationLibrary, Resource};
+    use opentelemetry::trace::{SpanContext, SpanId, TraceId, TraceState};
+    use opentelemetry::{KeyValue, Severity};
+    use std::sync::Arc;
+
+    #[test]
+    fn compression_unsupported() {
+        let config = ExporterConfig {
+            compression: Some(Compression::Gzip),
+            ..Default::default()
+        };
+        assert!(matches!(
+            LogsExporter::new(config),
+            Err(crate::Error::UnsupportedCompression(Compression::Gzip))
+        ));
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn export_records_failed_attempts() {
+        // nothing listens on port 1
+        let stats = ExportStats::default();
+        let config = ExporterConfig {
+            endpoint: "http://127.0.0.1:1".to_string(),
+            retry: None,
+            stats: stats.clone(),
+            ..Default::default()
+        };
+        let mut exporter = LogsExporter::new(config).unwrap();
+
+        assert!(futures::executor::block_on(exporter.export(Vec::new())).is_err());
+        assert_eq!(stats.transport_errors(), 1);
+    }
+
+    #[test]
+    fn log_data_to_resource_logs() {
+        let span_context = SpanContext::new(
+            TraceId::from_u128(1),
+            SpanId::from_u64(2),
+            1,
+            false,
+            TraceState::default(),
+        );
+        let log_data = LogData {
+            record: LogRecord::builder()
+                .with_severity_number(Severity::Warn)
+                .with_severity_text("WARN")
+                .with_body("disk almost full".into())
+                .with_attribute(KeyValue::new("free", 10))
+                .with_span_context(&span_context)
+                .build(),
+            resource: Arc::new(Resource::new(vec![KeyValue::new("service.name", "test")])),
+            instrumentation_lib: InstrumentationLibrary::new("component", Some("0.1")),
+        };
+
+        let resource_logs = ResourceLogs::from(log_data);
+        let resource = resource_logs.resource.unwrap();
+        assert_eq!(resource.attributes[0].key, "service.name");
+        let library_logs = &resource_logs.instrumentation_library_logs[0];
+        assert_eq!(
+            library_logs.instrumentation_library.as_ref().unwrap().name,
+            "component"
+        );
+        let record = &library_logs.logs[0];
+        assert_eq!(record.severity_number, SeverityNumber::Warn as i32);
+        assert_eq!(record.severity_text, "WARN");
+        assert!(record.body.is_some());
+        assert_eq!(record.attributes[0].key, "free");
+        assert_eq!(record.flags, 1);
+        assert_eq!(record.trace_id, 1u128.to_be_bytes().to_vec());
+        assert_eq!(record.span_id, 2u64.to_be_bytes().to_vec());
+    }
+}
@@ -8,19 +8,24 @@
 use crate::proto::collector::metrics::v1::{
     metrics_service_client::MetricsServiceClient, ExportMetricsServiceRequest,
 };
+#[cfg(feature = "tonic")]
+use crate::span::build_endpoint;
 use crate::transform::{record_to_metric, sink, CheckpointedMetrics};
-use crate::ExporterConfig;
+use crate::{
+    ExporterConfig, OTEL_EXPORTER_OTLP_ENDPOINT, OTEL_EXPORTER_OTLP_ENDPOINT_DEFAULT,
+    OTEL_EXPORTER_OTLP_METRICS_ENDPOINT,
+};
 use futures::{SinkExt, Stream, StreamExt, TryFutureExt};
 use opentelemetry::labels::Iter;
 use opentelemetry::metrics::{Descriptor, Result};
 use opentelemetry::sdk::export::metrics::{AggregatorSelector, ExportKindSelector};
 use opentelemetry::sdk::export::ExportError;
-use opentelemetry::sdk::metrics::{PushController, PushControllerWorker};
+use opentelemetry::sdk::metrics::{PushController, PushControllerWorker, View};
 use opentelemetry::sdk::resource::IntoIter;
 use opentelemetry::sdk::{
     export::metrics::{CheckpointSet, ExportKind, ExportKindFor, Exporter},
     metrics::selectors,
-    InstrumentationLibrary, Resource,
+    Resource,
 };
 use opentelemetry::{global, KeyValue};
 use std::fmt::{Debug, Formatter};
@@ -29,8 +34,6 @@ use std::sync::Mutex;
 use std::time;
 use tonic::metadata::KeyAndValueRef;
 #[cfg(feature = "tonic")]
-use tonic::transport::Channel;
-#[cfg(feature = "tonic")]
 use tonic::Request;
 
 pub fn new_metrics_pipeline<SP, SO, I, IO>(
@@ -51,6 +54,7 @@ where
         stateful: None,
         period: None,
         timeout: None,
+        views: Vec::new(),
     }
 }
 
@@ -70,6 +74,7 @@ where
     stateful: Option<bool>,
     period: Option<time::Duration>,
     timeout: Option<time::Duration>,
+    views: Vec<View>,
 }
 
 impl<AS, ES, SP, SO, I, IO, IOI> OtlpMetricPipelineBuilder<AS, ES, SP, SO, I, IO>
@@ -99,6 +104,23 @@ where
         }
     }
 
+    /// Set the endpoint of the collector from the environment, unless the
+    /// export configuration sets one already.
+    ///
+    /// `OTEL_EXPORTER_OTLP_METRICS_ENDPOINT` takes precedence over the generic
+    /// `OTEL_EXPORTER_OTLP_ENDPOINT`.
+    pub fn with_env(mut self) -> Self {
+        let config = self.export_config.get_or_insert_with(Default::default);
+        if config.endpoint == OTEL_EXPORTER_OTLP_ENDPOINT_DEFAULT {
+            if let Ok(endpoint) = std::env::var(OTEL_EXPORTER_OTLP_METRICS_ENDPOINT)
+                .or_else(|_| std::env::var(OTEL_EXPORTER_OTLP_ENDPOINT))
+            {
+                config.endpoint = endpoint;
+            }
+        }
+        self
+    }
+
     /// Build with the aggregator selector
     pub fn with_aggregator_selector(mut self, aggregator_selector: AS) -> Self {
         OtlpMetricPipelineBuilder {
@@ -141,14 +163,38 @@ where
         OtlpMetricPipelineBuilder { interval, ..self }
     }
 
-    /// Build with export kind selector
-    pub fn with_export_kind(mut self, export_selector: ES) -> Self {
+    /// Build with export kind selector, e.g. an [`InstrumentKindSelector`]
+    /// exporting delta sums.
+    ///
+    /// [`InstrumentKindSelector`]: opentelemetry::sdk::export::metrics::InstrumentKindSelector
+    pub fn with_export_kind<E>(
+        self,
+        export_selector: E,
+    ) -> OtlpMetricPipelineBuilder<AS, E, SP, SO, I, IO>
+    where
+        E: ExportKindFor + Send + Sync + Clone + 'static,
+    {
         OtlpMetricPipelineBuilder {
+            aggregator_selector: self.aggregator_selector,
             export_selector,
-            ..self
+            spawn: self.spawn,
+            interval: self.interval,
+            export_config: self.export_config,
+            resource: self.resource,
+            stateful: self.stateful,
+            period: self.period,
+            timeout: self.timeout,
+            views: self.views,
         }
     }
 
+    /// Register a view customizing the metrics exported for the instruments it
+    /// selects, e.g. to drop high cardinality attributes.
+    pub fn with_view(mut self, view: View) -> Self {
+        self.views.push(view);
+        self
+    }
+
     /// Build push controller
     pub fn build(self) -> Result<PushController> {
         #[cfg(feature = "tonic")]
@@ -170,6 +216,9 @@ where
         if let Some(resource) = self.resource {
             builder = builder.with_resource(resource);
         }
+        for view in self.views {
+            builder = builder.with_view(view);
+        }
         let controller = builder.build();
         global::set_meter_provider(controller.provider());
         Ok(controller)
@@ -210,25 +259,12 @@ impl MetricsExporter {
         config: ExporterConfig,
         export_selector: T,
     ) -> Result<MetricsExporter> {
-        let endpoint =
-            Channel::from_shared(config.endpoint).map_err::<crate::Error, _>(Into::into)?;
-
-        #[cfg(all(feature = "tls"))]
-        let channel = match config.tls_config {
-            Some(tls_config) => endpoint
-                .tls_config(tls_config)
-                .map_err::<crate::Error, _>(Into::into)?,
-            None => endpoint,
+        if let Some(compression) = config.compression {
+            return Err(crate::Error::UnsupportedCompression(compression).into());
         }
-        .timeout(config.timeout)
-        .connect_lazy()
-        .map_err::<crate::Error, _>(Into::into)?;
 
-        #[cfg(not(feature = "tls"))]
-        let channel = endpoint
-            .timeout(config.timeout)
-            .connect_lazy()
-            .map_err::<crate::Error, _>(Into::into)?;
+        let channel = build_endpoint(config.endpoint, config.timeout, config.tls_config)
+            .and_then(|endpoint| Ok(endpoint.connect_lazy()?))?;
 
         let client = match config.metadata.to_owned() {
             None => MetricsServiceClient::new(channel),
@@ -271,28 +307,36 @@ impl MetricsExporter {
     }
 }
 
+/// Transform the records of a checkpoint set into an export request, grouping
+/// the metrics by resource and instrumentation library.
+///
+/// The temporality of each metric is the export kind `export_selector` selects
+/// for its instrument.
+#[cfg(feature = "tonic")]
+pub(crate) fn export_request(
+    checkpoint_set: &mut dyn CheckpointSet,
+    export_selector: &dyn ExportKindFor,
+) -> Result<ExportMetricsServiceRequest> {
+    let mut resource_metrics: Vec<CheckpointedMetrics> = Vec::default();
+    checkpoint_set.try_for_each(export_selector, &mut |record| {
+        let metric = record_to_metric(record, export_selector)?;
+        resource_metrics.push((
+            record.resource().clone().into(),
+            record.descriptor().instrumentation_library().clone(),
+            metric,
+        ));
+        Ok(())
+    })?;
+
+    Ok(sink(resource_metrics))
+}
+
 impl Exporter for MetricsExporter {
     fn export(&self, checkpoint_set: &mut dyn CheckpointSet) -> Result<()> {
-        let mut resource_metrics: Vec<CheckpointedMetrics> = Vec::default();
-        // transform the metrics into proto. Append the resource and instrumentation library information into it.
-        checkpoint_set.try_for_each(self.export_kind_selector.as_ref(), &mut |record| {
-            let metric_result = record_to_metric(record, self.export_kind_selector.as_ref());
-            match metric_result {
-                Ok(metrics) => {
-                    resource_metrics.push((
-                        record.resource().clone().into(),
-                        InstrumentationLibrary::new(
-                            record.descriptor().instrumentation_name(),
-                            record.descriptor().instrumentation_version(),
-                        ),
-                        metrics,
-                    ));
-                    Ok(())
-                }
-                Err(err) => Err(err),
-            }
-        })?;
-        let request = Request::new(sink(resource_metrics));
+        let request = Request::new(export_request(
+            checkpoint_set,
+            self.export_kind_selector.as_ref(),
+        )?);
         self.sender.lock().map(|mut sender| {
             sender.try_send(ExportMsg::Export(request));
         });
@@ -307,3 +351,140 @@ impl Drop for MetricsExporter {
         });
     }
 }
+
+#[cfg(all(test, feature = "tonic"))]
+mod tests {
+    use super::*;
+    use crate::proto::metrics::v1::{metric::Data, AggregationTemporality, IntSum};
+    use opentelemetry::metrics::{InstrumentKind, MeterProvider};
+    use opentelemetry::sdk::export::metrics::InstrumentKindSelector;
+    use opentelemetry::sdk::metrics::controllers;
+    use tokio_stream::wrappers::IntervalStream;
+
+    fn interval(period: time::Duration) -> IntervalStream {
+        IntervalStream::new(tokio::time::interval(period))
+    }
+
+    #[test]
+    fn test_endpoint_from_env() {
+        std::env::set_var(
+            OTEL_EXPORTER_OTLP_METRICS_ENDPOINT,
+            "https://otlp_metrics_endpoint:4317",
+        );
+        let builder = new_metrics_pipeline(tokio::spawn, interval).with_env();
+        assert_eq!(
+            builder.export_config.unwrap().endpoint,
+            "https://otlp_metrics_endpoint:4317"
+        );
+
+        // builder values take precedence
+        let builder = new_metrics_pipeline(tokio::spawn, interval)
+            .with_export_config(ExporterConfig {
+                endpoint: "https://builder:4317".to_string(),
+                ..ExporterConfig::default()
+            })
+            .with_env();
+        assert_eq!(
+            builder.export_config.unwrap().endpoint,
+            "https://builder:4317"
+        );
+        std::env::remove_var(OTEL_EXPORTER_OTLP_METRICS_ENDPOINT);
+    }
+
+    #[test]
+    fn test_export_request_grouped_by_library() -> Result<()> {
+        let export_selector = InstrumentKindSelector::new(ExportKind::Cumulative)
+            .with_export_kind(InstrumentKind::Counter, ExportKind::Delta);
+        let mut controller = controllers::pull(
+            Box::new(selectors::simple::Selector::Exact),
+            Box::new(export_selector.clone()),
+        )
+        .with_cache_period(time::Duration::from_secs(0))
+        // like the processor of push controllers
+        .with_memory(false)
+        .build();
+        let provider = controller.provider();
+        let requests = provider
+            .meter("server", Some("1.0"))
+            .u64_counter("requests")
+            .init();
+        let in_flight = provider
+            .meter("client", None)
+            .i64_up_down_counter("in_flight")
+            .init();
+
+        let mut sums = |controller: &mut controllers::PullController| -> Result<_> {
+            controller.collect()?;
+            let request = export_request(controller, &export_selector)?;
+            assert_eq!(request.resource_metrics.len(), 1);
+            let mut sums = Vec::new();
+            for library_metrics in &request.resource_metrics[0].instrumentation_library_metrics {
+                let library = library_metrics.instrumentation_library.as_ref().unwrap();
+                for metric in &library_metrics.metrics {
+                    if let Some(Data::IntSum(IntSum {
+                        data_points,
+                        aggregation_temporality,
+                        ..
+                    })) = &metric.data
+                    {
+                        let mut values = data_points.iter().map(|dp| dp.value).collect::<Vec<_>>();
+                        values.sort_unstable();
+                        sums.push((
+                            format!("{}@{}", library.name, library.version),
+                            metric.name.clone(),
+                            *aggregation_temporality,
+                            values,
+                        ));
+                    }
+                }
+            }
+            sums.sort();
+            Ok(sums)
+        };
+
+        requests.add(1, &[KeyValue::new("route", "/a")]);
+        requests.add(2, &[KeyValue::new("route", "/b")]);
+        in_flight.add(3, &[]);
+        let delta = AggregationTemporality::Delta as i32;
+        let cumulative = AggregationTemporality::Cumulative as i32;
+        assert_eq!(
+            sums(&mut controller)?,
+            vec![
+                (
+                    "client@".to_string(),
+                    "in_flight".to_string(),
+                    cumulative,
+                    vec![3]
+                ),
+                (
+                    "server@1.0".to_string(),
+                    "requests".to_string(),
+                    delta,
+                    vec![1, 2]
+                ),
+            ]
+        );
+
+        requests.add(5, &[KeyValue::new("route", "/a")]);
+        in_flight.add(-1, &[]);
+        assert_eq!(
+            sums(&mut controller)?,
+            vec![
+                (
+                    "client@".to_string(),
+                    "in_flight".to_string(),
+                    cumulative,
+                    vec![2]
+                ),
+                (
+                    "server@1.0".to_string(),
+                    "requests".to_string(),
+                    delta,
+                    vec![5]
+                ),
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,245 @@
+//! # OTLP - Partial Success
+//!
+//! Collectors accepting only some spans of an export respond successfully but
+//! set the `partial_success` field of the response to the number of rejected
+//! spans and the reason, see the [OTLP specification]. The field was added to the protocol after the proto
+//! version the exporter is generated from, so the response is declared here.
+//!
+//! [OTLP specification]: https://github.com/open-telemetry/opentelemetry-specification/blob/main/specification/protocol/otlp.md#partial-success
+use crate::diagnostics::{ConnectionState, ExportFailure, TransportState};
+use opentelemetry::{global, trace::TraceError};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Counters of the export outcomes and the state of the connection to the
+/// collector.
+///
+/// The counters are shared by all clones, so a clone kept before the exporter
+/// is installed observes its exports. Failed export attempts are classified
+/// as transport errors, when the collector could not be reached, or as
+/// rejected exports, when it responded with an error.
+///
+/// # Examples
+///
+/// ```no_run
+/// let stats = opentelemetry_otlp::ExportStats::default();
+/// let tracer = opentelemetry_otlp::new_pipeline()
+///     .with_export_stats(stats.clone())
+///     .install()?;
+///
+/// // later, e.g. in a health check
+/// let rejected = stats.rejected_spans();
+/// if let Some(failure) = stats.last_error() {
+///     println!("{:?} {:?}: {}", stats.connection_state(), failure.kind, failure.message);
+/// }
+/// # Ok::<(), opentelemetry::trace::TraceError>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ExportStats {
+    rejected_spans: Arc<AtomicU64>,
+    transport: Arc<Mutex<TransportState>>,
+}
+
+impl ExportStats {
+    /// Number of spans the collector rejected so far.
+    pub fn rejected_spans(&self) -> u64 {
+        self.rejected_spans.load(Ordering::Relaxed)
+    }
+
+    /// State of the connection to the collector, as observed by the last
+    /// export attempt.
+    pub fn connection_state(&self) -> ConnectionState {
+        self.transport(|state| state.connection_state)
+            .unwrap_or_default()
+    }
+
+    /// Number of times an export reached the collector again after the
+    /// connection was lost.
+    pub fn reconnects(&self) -> u64 {
+        self.transport(|state| state.reconnects).unwrap_or_default()
+    }
+
+    /// Number of export attempts that failed to reach the collector.
+    pub fn transport_errors(&self) -> u64 {
+        self.transport(|state| state.transport_errors)
+            .unwrap_or_default()
+    }
+
+    /// Number of export attempts the collector responded to with an error.
+    pub fn rejected_exports(&self) -> u64 {
+        self.transport(|state| state.rejected_exports)
+            .unwrap_or_default()
+    }
+
+    /// The last failed export attempt, if any.
+    pub fn last_error(&self) -> Option<ExportFailure> {
+        self.transport(|state| state.last_error.clone()).flatten()
+    }
+
+    fn transport<T>(&self, f: impl FnOnce(&TransportState) -> T) -> Option<T> {
+        self.transport.lock().ok().map(|state| f(&state))
+    }
+
+    /// Record the outcome of an export attempt, passing it through.
+    pub(crate) fn record_attempt<T>(
+        &self,
+        result: Result<T, crate::Error>,
+    ) -> Result<T, crate::Error> {
+        if let Ok(mut state) = self.transport.lock() {
+            state.record(&result);
+        }
+        result
+    }
+
+    /// Count the rejected spans of a response and report them to the global
+    /// error handler.
+    pub(crate) fn record(&self, partial_success: Option<ExportTracePartialSuccess>) {
+        let partial_success = match partial_success {
+            Some(partial_success) => partial_success,
+            None => return,
+        };
+        if partial_success.rejected_spans > 0 {
+            self.rejected_spans
+                .fetch_add(partial_success.rejected_spans as u64, Ordering::Relaxed);
+            global::handle_error(TraceError::from(format!(
+                "collector rejected {} spans: {}",
+                partial_success.rejected_spans, partial_success.error_message
+            )));
+        } else if !partial_success.error_message.is_empty() {
+            // all spans were accepted, the message is a warning
+            global::handle_error(TraceError::from(format!(
+                "collector accepted spans with warning: {}",
+                partial_success.error_message
+            )));
+        }
+    }
+}
+
+/// `opentelemetry.proto.collector.trace.v1.ExportTraceServiceResponse`
+#[cfg(feature = "prost")]
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct ExportTraceServiceResponse {
+    #[prost(message, optional, tag = "1")]
+    pub(crate) partial_success: Option<ExportTracePartialSuccess>,
+}
+
+/// `opentelemetry.proto.collector.trace.v1.ExportTracePartialSuccess`
+#[cfg_attr(feature = "prost", derive(Clone, PartialEq, prost::Message))]
+#[cfg_attr(not(feature = "prost"), derive(Clone, Debug, Default, PartialEq))]
+pub(crate) struct ExportTracePartialSuccess {
+    #[cfg_attr(feature = "prost", prost(int64, tag = "1"))]
+    pub(crate) rejected_spans: i64,
+    #[cfg_attr(feature = "prost", prost(string, tag = "2"))]
+    pub(crate) error_message: String,
+}
+
+/// Decode the partial success of an OTLP/JSON response body.
+///
+/// The proto3 JSON mapping encodes 64 bit integers as strings, numbers are
+/// accepted as well.
+#[cfg(feature = "http-json")]
+pub(crate) fn from_json(body: &[u8]) -> Option<ExportTracePartialSuccess> {
+    let response: serde_json::Value = serde_json::from_slice(body).ok()?;
+    let partial_success = response.get("partialSuccess")?;
+    let rejected_spans = match partial_success.get("rejectedSpans") {
+        Some(serde_json::Value::String(rejected)) => rejected.parse().ok()?,
+        Some(rejected) => rejected.as_i64()?,
+        None => 0,
+    };
+    let error_message = partial_success
+        .get("errorMessage")
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    Some(ExportTracePartialSuccess {
+        rejected_spans,
+        error_message,
+    })
+}
+
+/// Decode the partial success from the fields of a response the generated
+/// grpcio types do not know about.
+#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+pub(crate) fn from_unknown_fields(
+    fields: &protobuf::UnknownFields,
+) -> Option<ExportTracePartialSuccess> {
+    let bytes = fields.get(1)?.length_delimited.last()?;
+    let mut input = protobuf::CodedInputStream::from_bytes(bytes);
+    let mut partial_success = ExportTracePartialSuccess::default();
+    while !input.eof().ok()? {
+        let (field, wire_type) = input.read_tag_unpack().ok()?;
+        match field {
+            1 => partial_success.rejected_spans = input.read_int64().ok()?,
+            2 => partial_success.error_message = input.read_string().ok()?,
+            _ => input.skip_field(wire_type).ok()?,
+        }
+    }
+    Some(partial_success)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_counts_rejected_spans() {
+        let stats = ExportStats::default();
+        stats.clone().record(Some(ExportTracePartialSuccess {
+            rejected_spans: 3,
+            error_message: "span name is empty".to_string(),
+        }));
+        stats.record(Some(ExportTracePartialSuccess {
+            rejected_spans: 0,
+            error_message: "deprecated attribute".to_string(),
+        }));
+        stats.record(None);
+        assert_eq!(stats.rejected_spans(), 3);
+    }
+
+    #[cfg(feature = "prost")]
+    #[test]
+    fn decode_protobuf_response() {
+        use prost::Message;
+
+        let response = ExportTraceServiceResponse {
+            partial_success: Some(ExportTracePartialSuccess {
+                rejected_spans: 2,
+                error_message: "invalid trace id".to_string(),
+            }),
+        };
+        let mut body = Vec::new();
+        response.encode(&mut body).unwrap();
+        assert_eq!(
+            ExportTraceServiceResponse::decode(body.as_slice()).unwrap(),
+            response
+        );
+        // responses of collectors predating partial success are empty
+        assert_eq!(
+            ExportTraceServiceResponse::decode(&[][..])
+                .unwrap()
+                .partial_success,
+            None
+        );
+    }
+
+    #[cfg(feature = "http-json")]
+    #[test]
+    fn decode_json_response() {
+        assert_eq!(
+            from_json(br#"{"partialSuccess":{"rejectedSpans":"2","errorMessage":"bad"}}"#),
+            Some(ExportTracePartialSuccess {
+                rejected_spans: 2,
+                error_message: "bad".to_string(),
+            })
+        );
+        assert_eq!(
+            from_json(br#"{"partialSuccess":{"rejectedSpans":1}}"#),
+            Some(ExportTracePartialSuccess {
+                rejected_spans: 1,
+                error_message: String::new(),
+            })
+        );
+        assert_eq!(from_json(b"{}"), None);
+        assert_eq!(from_json(b""), None);
+    }
+}
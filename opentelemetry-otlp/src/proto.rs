@@ -1,47 +1,78 @@
-#[cfg(feature = "tonic")]
+#[cfg(feature = "prost")]
 pub mod collector {
+    pub mod logs {
+        pub mod v1 {
+            include!(concat!(
+                env!("OUT_DIR"),
+                "/opentelemetry.proto.collector.logs.v1.rs"
+            ));
+        }
+    }
+
     pub mod metrics {
         pub mod v1 {
-            tonic::include_proto!("opentelemetry.proto.collector.metrics.v1");
+            include!(concat!(
+                env!("OUT_DIR"),
+                "/opentelemetry.proto.collector.metrics.v1.rs"
+            ));
         }
     }
 
     pub mod trace {
         pub mod v1 {
-            tonic::include_proto!("opentelemetry.proto.collector.trace.v1");
+            include!(concat!(
+                env!("OUT_DIR"),
+                "/opentelemetry.proto.collector.trace.v1.rs"
+            ));
         }
     }
 }
 
-#[cfg(feature = "tonic")]
+#[cfg(feature = "prost")]
 pub mod common {
     pub mod v1 {
-        tonic::include_proto!("opentelemetry.proto.common.v1");
+        include!(concat!(
+            env!("OUT_DIR"),
+            "/opentelemetry.proto.common.v1.rs"
+        ));
+    }
+}
+
+#[cfg(feature = "prost")]
+pub mod logs {
+    pub mod v1 {
+        include!(concat!(env!("OUT_DIR"), "/opentelemetry.proto.logs.v1.rs"));
     }
 }
 
-#[cfg(feature = "tonic")]
+#[cfg(feature = "prost")]
 pub mod metrics {
     pub mod v1 {
-        tonic::include_proto!("opentelemetry.proto.metrics.v1");
+        include!(concat!(
+            env!("OUT_DIR"),
+            "/opentelemetry.proto.metrics.v1.rs"
+        ));
     }
 }
 
-#[cfg(feature = "tonic")]
+#[cfg(feature = "prost")]
 pub mod resource {
     pub mod v1 {
-        tonic::include_proto!("opentelemetry.proto.resource.v1");
+        include!(concat!(
+            env!("OUT_DIR"),
+            "/opentelemetry.proto.resource.v1.rs"
+        ));
     }
 }
 
-#[cfg(feature = "tonic")]
+#[cfg(feature = "prost")]
 pub mod trace {
     pub mod v1 {
-        tonic::include_proto!("opentelemetry.proto.trace.v1");
+        include!(concat!(env!("OUT_DIR"), "/opentelemetry.proto.trace.v1.rs"));
     }
 }
 
-#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+#[cfg(all(feature = "grpc-sys", not(feature = "prost")))]
 pub(crate) mod grpcio {
     pub(crate) mod common;
     pub(crate) mod metrics;
@@ -1,4 +1,4 @@
-// This file is generated by rust-protobuf 2.20.0. Do not edit
+// This file is generated by rust-protobuf 2.28.0. Do not edit
 // @generated
 
 // https://github.com/rust-lang/rust-clippy/issues/702
@@ -6,7 +6,7 @@
 #![allow(clippy::all)]
 
 #![allow(unused_attributes)]
-#![rustfmt::skip]
+#![cfg_attr(rustfmt, rustfmt::skip)]
 
 #![allow(box_pointers)]
 #![allow(dead_code)]
@@ -21,10 +21,10 @@
 
 /// Generated files are compatible only with the same version
 /// of protobuf runtime.
-// const _PROTOBUF_VERSION_CHECK: () = ::protobuf::VERSION_2_20_0;
+// const _PROTOBUF_VERSION_CHECK: () = ::protobuf::VERSION_2_28_0;
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct AnyValue {
     // message oneof groups
     pub value: ::std::option::Option<AnyValue_oneof_value>,
@@ -42,7 +42,7 @@ impl<'a> ::std::default::Default for &'a AnyValue {
 }
 
 #[derive(Clone,PartialEq,Debug)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub enum AnyValue_oneof_value {
     string_value(::std::string::String),
     bool_value(bool),
@@ -510,7 +510,7 @@ impl ::protobuf::reflect::ProtobufValue for AnyValue {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct ArrayValue {
     // message fields
     pub values: ::protobuf::RepeatedField<AnyValue>,
@@ -679,7 +679,7 @@ impl ::protobuf::reflect::ProtobufValue for ArrayValue {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct KeyValueList {
     // message fields
     pub values: ::protobuf::RepeatedField<KeyValue>,
@@ -848,7 +848,7 @@ impl ::protobuf::reflect::ProtobufValue for KeyValueList {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct KeyValue {
     // message fields
     pub key: ::std::string::String,
@@ -1067,7 +1067,7 @@ impl ::protobuf::reflect::ProtobufValue for KeyValue {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct StringKeyValue {
     // message fields
     pub key: ::std::string::String,
@@ -1271,7 +1271,7 @@ impl ::protobuf::reflect::ProtobufValue for StringKeyValue {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct InstrumentationLibrary {
     // message fields
     pub name: ::std::string::String,
@@ -1,4 +1,4 @@
-// This file is generated by rust-protobuf 2.20.0. Do not edit
+// This file is generated by rust-protobuf 2.28.0. Do not edit
 // @generated
 
 // https://github.com/rust-lang/rust-clippy/issues/702
@@ -6,7 +6,7 @@
 #![allow(clippy::all)]
 
 #![allow(unused_attributes)]
-#![rustfmt::skip]
+#![cfg_attr(rustfmt, rustfmt::skip)]
 
 #![allow(box_pointers)]
 #![allow(dead_code)]
@@ -21,14 +21,15 @@
 
 /// Generated files are compatible only with the same version
 /// of protobuf runtime.
-// const _PROTOBUF_VERSION_CHECK: () = ::protobuf::VERSION_2_20_0;
+// const _PROTOBUF_VERSION_CHECK: () = ::protobuf::VERSION_2_28_0;
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct ResourceMetrics {
     // message fields
     pub resource: ::protobuf::SingularPtrField<super::resource::Resource>,
     pub instrumentation_library_metrics: ::protobuf::RepeatedField<InstrumentationLibraryMetrics>,
+    pub schema_url: ::std::string::String,
     // special fields
     #[cfg_attr(feature = "with-serde", serde(skip))]
     pub unknown_fields: ::protobuf::UnknownFields,
@@ -104,6 +105,32 @@ impl ResourceMetrics {
     pub fn take_instrumentation_library_metrics(&mut self) -> ::protobuf::RepeatedField<InstrumentationLibraryMetrics> {
         ::std::mem::replace(&mut self.instrumentation_library_metrics, ::protobuf::RepeatedField::new())
     }
+
+    // string schema_url = 3;
+
+
+    pub fn get_schema_url(&self) -> &str {
+        &self.schema_url
+    }
+    pub fn clear_schema_url(&mut self) {
+        self.schema_url.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_schema_url(&mut self, v: ::std::string::String) {
+        self.schema_url = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_schema_url(&mut self) -> &mut ::std::string::String {
+        &mut self.schema_url
+    }
+
+    // Take field
+    pub fn take_schema_url(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.schema_url, ::std::string::String::new())
+    }
 }
 
 impl ::protobuf::Message for ResourceMetrics {
@@ -131,6 +158,9 @@ impl ::protobuf::Message for ResourceMetrics {
                 2 => {
                     ::protobuf::rt::read_repeated_message_into(wire_type, is, &mut self.instrumentation_library_metrics)?;
                 },
+                3 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.schema_url)?;
+                },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
                 },
@@ -151,6 +181,9 @@ impl ::protobuf::Message for ResourceMetrics {
             let len = value.compute_size();
             my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
         };
+        if !self.schema_url.is_empty() {
+            my_size += ::protobuf::rt::string_size(3, &self.schema_url);
+        }
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
         my_size
@@ -167,6 +200,9 @@ impl ::protobuf::Message for ResourceMetrics {
             os.write_raw_varint32(v.get_cached_size())?;
             v.write_to_with_cached_sizes(os)?;
         };
+        if !self.schema_url.is_empty() {
+            os.write_string(3, &self.schema_url)?;
+        }
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -215,6 +251,11 @@ impl ::protobuf::Message for ResourceMetrics {
                 |m: &ResourceMetrics| { &m.instrumentation_library_metrics },
                 |m: &mut ResourceMetrics| { &mut m.instrumentation_library_metrics },
             ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "schema_url",
+                |m: &ResourceMetrics| { &m.schema_url },
+                |m: &mut ResourceMetrics| { &mut m.schema_url },
+            ));
             ::protobuf::reflect::MessageDescriptor::new_pb_name::<ResourceMetrics>(
                 "ResourceMetrics",
                 fields,
@@ -233,6 +274,7 @@ impl ::protobuf::Clear for ResourceMetrics {
     fn clear(&mut self) {
         self.resource.clear();
         self.instrumentation_library_metrics.clear();
+        self.schema_url.clear();
         self.unknown_fields.clear();
     }
 }
@@ -250,11 +292,12 @@ impl ::protobuf::reflect::ProtobufValue for ResourceMetrics {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct InstrumentationLibraryMetrics {
     // message fields
     pub instrumentation_library: ::protobuf::SingularPtrField<super::common::InstrumentationLibrary>,
     pub metrics: ::protobuf::RepeatedField<Metric>,
+    pub schema_url: ::std::string::String,
     // special fields
     #[cfg_attr(feature = "with-serde", serde(skip))]
     pub unknown_fields: ::protobuf::UnknownFields,
@@ -330,6 +373,32 @@ impl InstrumentationLibraryMetrics {
     pub fn take_metrics(&mut self) -> ::protobuf::RepeatedField<Metric> {
         ::std::mem::replace(&mut self.metrics, ::protobuf::RepeatedField::new())
     }
+
+    // string schema_url = 3;
+
+
+    pub fn get_schema_url(&self) -> &str {
+        &self.schema_url
+    }
+    pub fn clear_schema_url(&mut self) {
+        self.schema_url.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_schema_url(&mut self, v: ::std::string::String) {
+        self.schema_url = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_schema_url(&mut self) -> &mut ::std::string::String {
+        &mut self.schema_url
+    }
+
+    // Take field
+    pub fn take_schema_url(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.schema_url, ::std::string::String::new())
+    }
 }
 
 impl ::protobuf::Message for InstrumentationLibraryMetrics {
@@ -357,6 +426,9 @@ impl ::protobuf::Message for InstrumentationLibraryMetrics {
                 2 => {
                     ::protobuf::rt::read_repeated_message_into(wire_type, is, &mut self.metrics)?;
                 },
+                3 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.schema_url)?;
+                },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
                 },
@@ -377,6 +449,9 @@ impl ::protobuf::Message for InstrumentationLibraryMetrics {
             let len = value.compute_size();
             my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
         };
+        if !self.schema_url.is_empty() {
+            my_size += ::protobuf::rt::string_size(3, &self.schema_url);
+        }
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
         my_size
@@ -393,6 +468,9 @@ impl ::protobuf::Message for InstrumentationLibraryMetrics {
             os.write_raw_varint32(v.get_cached_size())?;
             v.write_to_with_cached_sizes(os)?;
         };
+        if !self.schema_url.is_empty() {
+            os.write_string(3, &self.schema_url)?;
+        }
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -441,6 +519,11 @@ impl ::protobuf::Message for InstrumentationLibraryMetrics {
                 |m: &InstrumentationLibraryMetrics| { &m.metrics },
                 |m: &mut InstrumentationLibraryMetrics| { &mut m.metrics },
             ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "schema_url",
+                |m: &InstrumentationLibraryMetrics| { &m.schema_url },
+                |m: &mut InstrumentationLibraryMetrics| { &mut m.schema_url },
+            ));
             ::protobuf::reflect::MessageDescriptor::new_pb_name::<InstrumentationLibraryMetrics>(
                 "InstrumentationLibraryMetrics",
                 fields,
@@ -459,6 +542,7 @@ impl ::protobuf::Clear for InstrumentationLibraryMetrics {
     fn clear(&mut self) {
         self.instrumentation_library.clear();
         self.metrics.clear();
+        self.schema_url.clear();
         self.unknown_fields.clear();
     }
 }
@@ -476,7 +560,7 @@ impl ::protobuf::reflect::ProtobufValue for InstrumentationLibraryMetrics {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct Metric {
     // message fields
     pub name: ::std::string::String,
@@ -498,7 +582,7 @@ impl<'a> ::std::default::Default for &'a Metric {
 }
 
 #[derive(Clone,PartialEq,Debug)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub enum Metric_oneof_data {
     int_gauge(IntGauge),
     double_gauge(DoubleGauge),
@@ -1269,7 +1353,7 @@ impl ::protobuf::reflect::ProtobufValue for Metric {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct IntGauge {
     // message fields
     pub data_points: ::protobuf::RepeatedField<IntDataPoint>,
@@ -1438,7 +1522,7 @@ impl ::protobuf::reflect::ProtobufValue for IntGauge {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct DoubleGauge {
     // message fields
     pub data_points: ::protobuf::RepeatedField<DoubleDataPoint>,
@@ -1607,7 +1691,7 @@ impl ::protobuf::reflect::ProtobufValue for DoubleGauge {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct IntSum {
     // message fields
     pub data_points: ::protobuf::RepeatedField<IntDataPoint>,
@@ -1842,7 +1926,7 @@ impl ::protobuf::reflect::ProtobufValue for IntSum {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct DoubleSum {
     // message fields
     pub data_points: ::protobuf::RepeatedField<DoubleDataPoint>,
@@ -2077,7 +2161,7 @@ impl ::protobuf::reflect::ProtobufValue for DoubleSum {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct IntHistogram {
     // message fields
     pub data_points: ::protobuf::RepeatedField<IntHistogramDataPoint>,
@@ -2277,7 +2361,7 @@ impl ::protobuf::reflect::ProtobufValue for IntHistogram {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct DoubleHistogram {
     // message fields
     pub data_points: ::protobuf::RepeatedField<DoubleHistogramDataPoint>,
@@ -2477,7 +2561,7 @@ impl ::protobuf::reflect::ProtobufValue for DoubleHistogram {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct DoubleSummary {
     // message fields
     pub data_points: ::protobuf::RepeatedField<DoubleSummaryDataPoint>,
@@ -2646,7 +2730,7 @@ impl ::protobuf::reflect::ProtobufValue for DoubleSummary {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct IntDataPoint {
     // message fields
     pub labels: ::protobuf::RepeatedField<super::common::StringKeyValue>,
@@ -2969,7 +3053,7 @@ impl ::protobuf::reflect::ProtobufValue for IntDataPoint {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct DoubleDataPoint {
     // message fields
     pub labels: ::protobuf::RepeatedField<super::common::StringKeyValue>,
@@ -3292,7 +3376,7 @@ impl ::protobuf::reflect::ProtobufValue for DoubleDataPoint {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct IntHistogramDataPoint {
     // message fields
     pub labels: ::protobuf::RepeatedField<super::common::StringKeyValue>,
@@ -3728,7 +3812,7 @@ impl ::protobuf::reflect::ProtobufValue for IntHistogramDataPoint {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct DoubleHistogramDataPoint {
     // message fields
     pub labels: ::protobuf::RepeatedField<super::common::StringKeyValue>,
@@ -4164,7 +4248,7 @@ impl ::protobuf::reflect::ProtobufValue for DoubleHistogramDataPoint {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct DoubleSummaryDataPoint {
     // message fields
     pub labels: ::protobuf::RepeatedField<super::common::StringKeyValue>,
@@ -4522,7 +4606,7 @@ impl ::protobuf::reflect::ProtobufValue for DoubleSummaryDataPoint {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct DoubleSummaryDataPoint_ValueAtQuantile {
     // message fields
     pub quantile: f64,
@@ -4712,7 +4796,7 @@ impl ::protobuf::reflect::ProtobufValue for DoubleSummaryDataPoint_ValueAtQuanti
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct IntExemplar {
     // message fields
     pub filtered_labels: ::protobuf::RepeatedField<super::common::StringKeyValue>,
@@ -5035,7 +5119,7 @@ impl ::protobuf::reflect::ProtobufValue for IntExemplar {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct DoubleExemplar {
     // message fields
     pub filtered_labels: ::protobuf::RepeatedField<super::common::StringKeyValue>,
@@ -5358,7 +5442,7 @@ impl ::protobuf::reflect::ProtobufValue for DoubleExemplar {
 }
 
 #[derive(Clone,PartialEq,Eq,Debug,Hash)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub enum AggregationTemporality {
     AGGREGATION_TEMPORALITY_UNSPECIFIED = 0,
     AGGREGATION_TEMPORALITY_DELTA = 1,
@@ -5414,102 +5498,103 @@ impl ::protobuf::reflect::ProtobufValue for AggregationTemporality {
 static file_descriptor_proto_data: &'static [u8] = b"\
     \n,opentelemetry/proto/metrics/v1/metrics.proto\x12\x1eopentelemetry.pro\
     to.metrics.v1\x1a*opentelemetry/proto/common/v1/common.proto\x1a.opentel\
-    emetry/proto/resource/v1/resource.proto\"\xe0\x01\n\x0fResourceMetrics\
+    emetry/proto/resource/v1/resource.proto\"\xff\x01\n\x0fResourceMetrics\
     \x12E\n\x08resource\x18\x01\x20\x01(\x0b2).opentelemetry.proto.resource.\
     v1.ResourceR\x08resource\x12\x85\x01\n\x1finstrumentation_library_metric\
     s\x18\x02\x20\x03(\x0b2=.opentelemetry.proto.metrics.v1.InstrumentationL\
-    ibraryMetricsR\x1dinstrumentationLibraryMetrics\"\xd1\x01\n\x1dInstrumen\
-    tationLibraryMetrics\x12n\n\x17instrumentation_library\x18\x01\x20\x01(\
-    \x0b25.opentelemetry.proto.common.v1.InstrumentationLibraryR\x16instrume\
-    ntationLibrary\x12@\n\x07metrics\x18\x02\x20\x03(\x0b2&.opentelemetry.pr\
-    oto.metrics.v1.MetricR\x07metrics\"\x8f\x05\n\x06Metric\x12\x12\n\x04nam\
-    e\x18\x01\x20\x01(\tR\x04name\x12\x20\n\x0bdescription\x18\x02\x20\x01(\
-    \tR\x0bdescription\x12\x12\n\x04unit\x18\x03\x20\x01(\tR\x04unit\x12G\n\
-    \tint_gauge\x18\x04\x20\x01(\x0b2(.opentelemetry.proto.metrics.v1.IntGau\
-    geH\0R\x08intGauge\x12P\n\x0cdouble_gauge\x18\x05\x20\x01(\x0b2+.opentel\
-    emetry.proto.metrics.v1.DoubleGaugeH\0R\x0bdoubleGauge\x12A\n\x07int_sum\
-    \x18\x06\x20\x01(\x0b2&.opentelemetry.proto.metrics.v1.IntSumH\0R\x06int\
-    Sum\x12J\n\ndouble_sum\x18\x07\x20\x01(\x0b2).opentelemetry.proto.metric\
-    s.v1.DoubleSumH\0R\tdoubleSum\x12S\n\rint_histogram\x18\x08\x20\x01(\x0b\
-    2,.opentelemetry.proto.metrics.v1.IntHistogramH\0R\x0cintHistogram\x12\\\
-    \n\x10double_histogram\x18\t\x20\x01(\x0b2/.opentelemetry.proto.metrics.\
-    v1.DoubleHistogramH\0R\x0fdoubleHistogram\x12V\n\x0edouble_summary\x18\
-    \x0b\x20\x01(\x0b2-.opentelemetry.proto.metrics.v1.DoubleSummaryH\0R\rdo\
-    ubleSummaryB\x06\n\x04data\"Y\n\x08IntGauge\x12M\n\x0bdata_points\x18\
-    \x01\x20\x03(\x0b2,.opentelemetry.proto.metrics.v1.IntDataPointR\ndataPo\
-    ints\"_\n\x0bDoubleGauge\x12P\n\x0bdata_points\x18\x01\x20\x03(\x0b2/.op\
-    entelemetry.proto.metrics.v1.DoubleDataPointR\ndataPoints\"\xeb\x01\n\
-    \x06IntSum\x12M\n\x0bdata_points\x18\x01\x20\x03(\x0b2,.opentelemetry.pr\
-    oto.metrics.v1.IntDataPointR\ndataPoints\x12o\n\x17aggregation_temporali\
-    ty\x18\x02\x20\x01(\x0e26.opentelemetry.proto.metrics.v1.AggregationTemp\
-    oralityR\x16aggregationTemporality\x12!\n\x0cis_monotonic\x18\x03\x20\
-    \x01(\x08R\x0bisMonotonic\"\xf1\x01\n\tDoubleSum\x12P\n\x0bdata_points\
-    \x18\x01\x20\x03(\x0b2/.opentelemetry.proto.metrics.v1.DoubleDataPointR\
-    \ndataPoints\x12o\n\x17aggregation_temporality\x18\x02\x20\x01(\x0e26.op\
-    entelemetry.proto.metrics.v1.AggregationTemporalityR\x16aggregationTempo\
-    rality\x12!\n\x0cis_monotonic\x18\x03\x20\x01(\x08R\x0bisMonotonic\"\xd7\
-    \x01\n\x0cIntHistogram\x12V\n\x0bdata_points\x18\x01\x20\x03(\x0b25.open\
-    telemetry.proto.metrics.v1.IntHistogramDataPointR\ndataPoints\x12o\n\x17\
-    aggregation_temporality\x18\x02\x20\x01(\x0e26.opentelemetry.proto.metri\
-    cs.v1.AggregationTemporalityR\x16aggregationTemporality\"\xdd\x01\n\x0fD\
-    oubleHistogram\x12Y\n\x0bdata_points\x18\x01\x20\x03(\x0b28.opentelemetr\
-    y.proto.metrics.v1.DoubleHistogramDataPointR\ndataPoints\x12o\n\x17aggre\
-    gation_temporality\x18\x02\x20\x01(\x0e26.opentelemetry.proto.metrics.v1\
-    .AggregationTemporalityR\x16aggregationTemporality\"h\n\rDoubleSummary\
-    \x12W\n\x0bdata_points\x18\x01\x20\x03(\x0b26.opentelemetry.proto.metric\
-    s.v1.DoubleSummaryDataPointR\ndataPoints\"\x8d\x02\n\x0cIntDataPoint\x12\
-    E\n\x06labels\x18\x01\x20\x03(\x0b2-.opentelemetry.proto.common.v1.Strin\
-    gKeyValueR\x06labels\x12/\n\x14start_time_unix_nano\x18\x02\x20\x01(\x06\
-    R\x11startTimeUnixNano\x12$\n\x0etime_unix_nano\x18\x03\x20\x01(\x06R\
-    \x0ctimeUnixNano\x12\x14\n\x05value\x18\x04\x20\x01(\x10R\x05value\x12I\
-    \n\texemplars\x18\x05\x20\x03(\x0b2+.opentelemetry.proto.metrics.v1.IntE\
-    xemplarR\texemplars\"\x93\x02\n\x0fDoubleDataPoint\x12E\n\x06labels\x18\
-    \x01\x20\x03(\x0b2-.opentelemetry.proto.common.v1.StringKeyValueR\x06lab\
-    els\x12/\n\x14start_time_unix_nano\x18\x02\x20\x01(\x06R\x11startTimeUni\
-    xNano\x12$\n\x0etime_unix_nano\x18\x03\x20\x01(\x06R\x0ctimeUnixNano\x12\
-    \x14\n\x05value\x18\x04\x20\x01(\x01R\x05value\x12L\n\texemplars\x18\x05\
-    \x20\x03(\x0b2..opentelemetry.proto.metrics.v1.DoubleExemplarR\texemplar\
-    s\"\xf6\x02\n\x15IntHistogramDataPoint\x12E\n\x06labels\x18\x01\x20\x03(\
-    \x0b2-.opentelemetry.proto.common.v1.StringKeyValueR\x06labels\x12/\n\
-    \x14start_time_unix_nano\x18\x02\x20\x01(\x06R\x11startTimeUnixNano\x12$\
-    \n\x0etime_unix_nano\x18\x03\x20\x01(\x06R\x0ctimeUnixNano\x12\x14\n\x05\
-    count\x18\x04\x20\x01(\x06R\x05count\x12\x10\n\x03sum\x18\x05\x20\x01(\
-    \x10R\x03sum\x12#\n\rbucket_counts\x18\x06\x20\x03(\x06R\x0cbucketCounts\
-    \x12'\n\x0fexplicit_bounds\x18\x07\x20\x03(\x01R\x0eexplicitBounds\x12I\
-    \n\texemplars\x18\x08\x20\x03(\x0b2+.opentelemetry.proto.metrics.v1.IntE\
-    xemplarR\texemplars\"\xfc\x02\n\x18DoubleHistogramDataPoint\x12E\n\x06la\
-    bels\x18\x01\x20\x03(\x0b2-.opentelemetry.proto.common.v1.StringKeyValue\
-    R\x06labels\x12/\n\x14start_time_unix_nano\x18\x02\x20\x01(\x06R\x11star\
-    tTimeUnixNano\x12$\n\x0etime_unix_nano\x18\x03\x20\x01(\x06R\x0ctimeUnix\
-    Nano\x12\x14\n\x05count\x18\x04\x20\x01(\x06R\x05count\x12\x10\n\x03sum\
-    \x18\x05\x20\x01(\x01R\x03sum\x12#\n\rbucket_counts\x18\x06\x20\x03(\x06\
-    R\x0cbucketCounts\x12'\n\x0fexplicit_bounds\x18\x07\x20\x03(\x01R\x0eexp\
-    licitBounds\x12L\n\texemplars\x18\x08\x20\x03(\x0b2..opentelemetry.proto\
-    .metrics.v1.DoubleExemplarR\texemplars\"\x94\x03\n\x16DoubleSummaryDataP\
-    oint\x12E\n\x06labels\x18\x01\x20\x03(\x0b2-.opentelemetry.proto.common.\
-    v1.StringKeyValueR\x06labels\x12/\n\x14start_time_unix_nano\x18\x02\x20\
-    \x01(\x06R\x11startTimeUnixNano\x12$\n\x0etime_unix_nano\x18\x03\x20\x01\
-    (\x06R\x0ctimeUnixNano\x12\x14\n\x05count\x18\x04\x20\x01(\x06R\x05count\
-    \x12\x10\n\x03sum\x18\x05\x20\x01(\x01R\x03sum\x12o\n\x0fquantile_values\
-    \x18\x06\x20\x03(\x0b2F.opentelemetry.proto.metrics.v1.DoubleSummaryData\
-    Point.ValueAtQuantileR\x0equantileValues\x1aC\n\x0fValueAtQuantile\x12\
-    \x1a\n\x08quantile\x18\x01\x20\x01(\x01R\x08quantile\x12\x14\n\x05value\
-    \x18\x02\x20\x01(\x01R\x05value\"\xd5\x01\n\x0bIntExemplar\x12V\n\x0ffil\
-    tered_labels\x18\x01\x20\x03(\x0b2-.opentelemetry.proto.common.v1.String\
-    KeyValueR\x0efilteredLabels\x12$\n\x0etime_unix_nano\x18\x02\x20\x01(\
-    \x06R\x0ctimeUnixNano\x12\x14\n\x05value\x18\x03\x20\x01(\x10R\x05value\
-    \x12\x17\n\x07span_id\x18\x04\x20\x01(\x0cR\x06spanId\x12\x19\n\x08trace\
-    _id\x18\x05\x20\x01(\x0cR\x07traceId\"\xd8\x01\n\x0eDoubleExemplar\x12V\
-    \n\x0ffiltered_labels\x18\x01\x20\x03(\x0b2-.opentelemetry.proto.common.\
-    v1.StringKeyValueR\x0efilteredLabels\x12$\n\x0etime_unix_nano\x18\x02\
-    \x20\x01(\x06R\x0ctimeUnixNano\x12\x14\n\x05value\x18\x03\x20\x01(\x01R\
-    \x05value\x12\x17\n\x07span_id\x18\x04\x20\x01(\x0cR\x06spanId\x12\x19\n\
-    \x08trace_id\x18\x05\x20\x01(\x0cR\x07traceId*\x8c\x01\n\x16AggregationT\
-    emporality\x12'\n#AGGREGATION_TEMPORALITY_UNSPECIFIED\x10\0\x12!\n\x1dAG\
-    GREGATION_TEMPORALITY_DELTA\x10\x01\x12&\n\"AGGREGATION_TEMPORALITY_CUMU\
-    LATIVE\x10\x02Bt\n!io.opentelemetry.proto.metrics.v1B\x0cMetricsProtoP\
-    \x01Z?github.com/open-telemetry/opentelemetry-proto/gen/go/metrics/v1b\
-    \x06proto3\
+    ibraryMetricsR\x1dinstrumentationLibraryMetrics\x12\x1d\n\nschema_url\
+    \x18\x03\x20\x01(\tR\tschemaUrl\"\xf0\x01\n\x1dInstrumentationLibraryMet\
+    rics\x12n\n\x17instrumentation_library\x18\x01\x20\x01(\x0b25.openteleme\
+    try.proto.common.v1.InstrumentationLibraryR\x16instrumentationLibrary\
+    \x12@\n\x07metrics\x18\x02\x20\x03(\x0b2&.opentelemetry.proto.metrics.v1\
+    .MetricR\x07metrics\x12\x1d\n\nschema_url\x18\x03\x20\x01(\tR\tschemaUrl\
+    \"\x8f\x05\n\x06Metric\x12\x12\n\x04name\x18\x01\x20\x01(\tR\x04name\x12\
+    \x20\n\x0bdescription\x18\x02\x20\x01(\tR\x0bdescription\x12\x12\n\x04un\
+    it\x18\x03\x20\x01(\tR\x04unit\x12G\n\tint_gauge\x18\x04\x20\x01(\x0b2(.\
+    opentelemetry.proto.metrics.v1.IntGaugeH\0R\x08intGauge\x12P\n\x0cdouble\
+    _gauge\x18\x05\x20\x01(\x0b2+.opentelemetry.proto.metrics.v1.DoubleGauge\
+    H\0R\x0bdoubleGauge\x12A\n\x07int_sum\x18\x06\x20\x01(\x0b2&.opentelemet\
+    ry.proto.metrics.v1.IntSumH\0R\x06intSum\x12J\n\ndouble_sum\x18\x07\x20\
+    \x01(\x0b2).opentelemetry.proto.metrics.v1.DoubleSumH\0R\tdoubleSum\x12S\
+    \n\rint_histogram\x18\x08\x20\x01(\x0b2,.opentelemetry.proto.metrics.v1.\
+    IntHistogramH\0R\x0cintHistogram\x12\\\n\x10double_histogram\x18\t\x20\
+    \x01(\x0b2/.opentelemetry.proto.metrics.v1.DoubleHistogramH\0R\x0fdouble\
+    Histogram\x12V\n\x0edouble_summary\x18\x0b\x20\x01(\x0b2-.opentelemetry.\
+    proto.metrics.v1.DoubleSummaryH\0R\rdoubleSummaryB\x06\n\x04data\"Y\n\
+    \x08IntGauge\x12M\n\x0bdata_points\x18\x01\x20\x03(\x0b2,.opentelemetry.\
+    proto.metrics.v1.IntDataPointR\ndataPoints\"_\n\x0bDoubleGauge\x12P\n\
+    \x0bdata_points\x18\x01\x20\x03(\x0b2/.opentelemetry.proto.metrics.v1.Do\
+    ubleDataPointR\ndataPoints\"\xeb\x01\n\x06IntSum\x12M\n\x0bdata_points\
+    \x18\x01\x20\x03(\x0b2,.opentelemetry.proto.metrics.v1.IntDataPointR\nda\
+    taPoints\x12o\n\x17aggregation_temporality\x18\x02\x20\x01(\x0e26.opente\
+    lemetry.proto.metrics.v1.AggregationTemporalityR\x16aggregationTemporali\
+    ty\x12!\n\x0cis_monotonic\x18\x03\x20\x01(\x08R\x0bisMonotonic\"\xf1\x01\
+    \n\tDoubleSum\x12P\n\x0bdata_points\x18\x01\x20\x03(\x0b2/.opentelemetry\
+    .proto.metrics.v1.DoubleDataPointR\ndataPoints\x12o\n\x17aggregation_tem\
+    porality\x18\x02\x20\x01(\x0e26.opentelemetry.proto.metrics.v1.Aggregati\
+    onTemporalityR\x16aggregationTemporality\x12!\n\x0cis_monotonic\x18\x03\
+    \x20\x01(\x08R\x0bisMonotonic\"\xd7\x01\n\x0cIntHistogram\x12V\n\x0bdata\
+    _points\x18\x01\x20\x03(\x0b25.opentelemetry.proto.metrics.v1.IntHistogr\
+    amDataPointR\ndataPoints\x12o\n\x17aggregation_temporality\x18\x02\x20\
+    \x01(\x0e26.opentelemetry.proto.metrics.v1.AggregationTemporalityR\x16ag\
+    gregationTemporality\"\xdd\x01\n\x0fDoubleHistogram\x12Y\n\x0bdata_point\
+    s\x18\x01\x20\x03(\x0b28.opentelemetry.proto.metrics.v1.DoubleHistogramD\
+    ataPointR\ndataPoints\x12o\n\x17aggregation_temporality\x18\x02\x20\x01(\
+    \x0e26.opentelemetry.proto.metrics.v1.AggregationTemporalityR\x16aggrega\
+    tionTemporality\"h\n\rDoubleSummary\x12W\n\x0bdata_points\x18\x01\x20\
+    \x03(\x0b26.opentelemetry.proto.metrics.v1.DoubleSummaryDataPointR\ndata\
+    Points\"\x8d\x02\n\x0cIntDataPoint\x12E\n\x06labels\x18\x01\x20\x03(\x0b\
+    2-.opentelemetry.proto.common.v1.StringKeyValueR\x06labels\x12/\n\x14sta\
+    rt_time_unix_nano\x18\x02\x20\x01(\x06R\x11startTimeUnixNano\x12$\n\x0et\
+    ime_unix_nano\x18\x03\x20\x01(\x06R\x0ctimeUnixNano\x12\x14\n\x05value\
+    \x18\x04\x20\x01(\x10R\x05value\x12I\n\texemplars\x18\x05\x20\x03(\x0b2+\
+    .opentelemetry.proto.metrics.v1.IntExemplarR\texemplars\"\x93\x02\n\x0fD\
+    oubleDataPoint\x12E\n\x06labels\x18\x01\x20\x03(\x0b2-.opentelemetry.pro\
+    to.common.v1.StringKeyValueR\x06labels\x12/\n\x14start_time_unix_nano\
+    \x18\x02\x20\x01(\x06R\x11startTimeUnixNano\x12$\n\x0etime_unix_nano\x18\
+    \x03\x20\x01(\x06R\x0ctimeUnixNano\x12\x14\n\x05value\x18\x04\x20\x01(\
+    \x01R\x05value\x12L\n\texemplars\x18\x05\x20\x03(\x0b2..opentelemetry.pr\
+    oto.metrics.v1.DoubleExemplarR\texemplars\"\xf6\x02\n\x15IntHistogramDat\
+    aPoint\x12E\n\x06labels\x18\x01\x20\x03(\x0b2-.opentelemetry.proto.commo\
+    n.v1.StringKeyValueR\x06labels\x12/\n\x14start_time_unix_nano\x18\x02\
+    \x20\x01(\x06R\x11startTimeUnixNano\x12$\n\x0etime_unix_nano\x18\x03\x20\
+    \x01(\x06R\x0ctimeUnixNano\x12\x14\n\x05count\x18\x04\x20\x01(\x06R\x05c\
+    ount\x12\x10\n\x03sum\x18\x05\x20\x01(\x10R\x03sum\x12#\n\rbucket_counts\
+    \x18\x06\x20\x03(\x06R\x0cbucketCounts\x12'\n\x0fexplicit_bounds\x18\x07\
+    \x20\x03(\x01R\x0eexplicitBounds\x12I\n\texemplars\x18\x08\x20\x03(\x0b2\
+    +.opentelemetry.proto.metrics.v1.IntExemplarR\texemplars\"\xfc\x02\n\x18\
+    DoubleHistogramDataPoint\x12E\n\x06labels\x18\x01\x20\x03(\x0b2-.opentel\
+    emetry.proto.common.v1.StringKeyValueR\x06labels\x12/\n\x14start_time_un\
+    ix_nano\x18\x02\x20\x01(\x06R\x11startTimeUnixNano\x12$\n\x0etime_unix_n\
+    ano\x18\x03\x20\x01(\x06R\x0ctimeUnixNano\x12\x14\n\x05count\x18\x04\x20\
+    \x01(\x06R\x05count\x12\x10\n\x03sum\x18\x05\x20\x01(\x01R\x03sum\x12#\n\
+    \rbucket_counts\x18\x06\x20\x03(\x06R\x0cbucketCounts\x12'\n\x0fexplicit\
+    _bounds\x18\x07\x20\x03(\x01R\x0eexplicitBounds\x12L\n\texemplars\x18\
+    \x08\x20\x03(\x0b2..opentelemetry.proto.metrics.v1.DoubleExemplarR\texem\
+    plars\"\x94\x03\n\x16DoubleSummaryDataPoint\x12E\n\x06labels\x18\x01\x20\
+    \x03(\x0b2-.opentelemetry.proto.common.v1.StringKeyValueR\x06labels\x12/\
+    \n\x14start_time_unix_nano\x18\x02\x20\x01(\x06R\x11startTimeUnixNano\
+    \x12$\n\x0etime_unix_nano\x18\x03\x20\x01(\x06R\x0ctimeUnixNano\x12\x14\
+    \n\x05count\x18\x04\x20\x01(\x06R\x05count\x12\x10\n\x03sum\x18\x05\x20\
+    \x01(\x01R\x03sum\x12o\n\x0fquantile_values\x18\x06\x20\x03(\x0b2F.opent\
+    elemetry.proto.metrics.v1.DoubleSummaryDataPoint.ValueAtQuantileR\x0equa\
+    ntileValues\x1aC\n\x0fValueAtQuantile\x12\x1a\n\x08quantile\x18\x01\x20\
+    \x01(\x01R\x08quantile\x12\x14\n\x05value\x18\x02\x20\x01(\x01R\x05value\
+    \"\xd5\x01\n\x0bIntExemplar\x12V\n\x0ffiltered_labels\x18\x01\x20\x03(\
+    \x0b2-.opentelemetry.proto.common.v1.StringKeyValueR\x0efilteredLabels\
+    \x12$\n\x0etime_unix_nano\x18\x02\x20\x01(\x06R\x0ctimeUnixNano\x12\x14\
+    \n\x05value\x18\x03\x20\x01(\x10R\x05value\x12\x17\n\x07span_id\x18\x04\
+    \x20\x01(\x0cR\x06spanId\x12\x19\n\x08trace_id\x18\x05\x20\x01(\x0cR\x07\
+    traceId\"\xd8\x01\n\x0eDoubleExemplar\x12V\n\x0ffiltered_labels\x18\x01\
+    \x20\x03(\x0b2-.opentelemetry.proto.common.v1.StringKeyValueR\x0efiltere\
+    dLabels\x12$\n\x0etime_unix_nano\x18\x02\x20\x01(\x06R\x0ctimeUnixNano\
+    \x12\x14\n\x05value\x18\x03\x20\x01(\x01R\x05value\x12\x17\n\x07span_id\
+    \x18\x04\x20\x01(\x0cR\x06spanId\x12\x19\n\x08trace_id\x18\x05\x20\x01(\
+    \x0cR\x07traceId*\x8c\x01\n\x16AggregationTemporality\x12'\n#AGGREGATION\
+    _TEMPORALITY_UNSPECIFIED\x10\0\x12!\n\x1dAGGREGATION_TEMPORALITY_DELTA\
+    \x10\x01\x12&\n\"AGGREGATION_TEMPORALITY_CUMULATIVE\x10\x02Bt\n!io.opent\
+    elemetry.proto.metrics.v1B\x0cMetricsProtoP\x01Z?github.com/open-telemet\
+    ry/opentelemetry-proto/gen/go/metrics/v1b\x06proto3\
 ";
 
 static file_descriptor_proto_lazy: ::protobuf::rt::LazyV2<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::rt::LazyV2::INIT;
@@ -1,4 +1,4 @@
-// This file is generated by rust-protobuf 2.20.0. Do not edit
+// This file is generated by rust-protobuf 2.28.0. Do not edit
 // @generated
 
 // https://github.com/rust-lang/rust-clippy/issues/702
@@ -6,7 +6,7 @@
 #![allow(clippy::all)]
 
 #![allow(unused_attributes)]
-#![rustfmt::skip]
+#![cfg_attr(rustfmt, rustfmt::skip)]
 
 #![allow(box_pointers)]
 #![allow(dead_code)]
@@ -21,10 +21,10 @@
 
 /// Generated files are compatible only with the same version
 /// of protobuf runtime.
-// const _PROTOBUF_VERSION_CHECK: () = ::protobuf::VERSION_2_20_0;
+// const _PROTOBUF_VERSION_CHECK: () = ::protobuf::VERSION_2_28_0;
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct TraceConfig {
     // message fields
     pub max_number_of_attributes: i64,
@@ -48,7 +48,7 @@ impl<'a> ::std::default::Default for &'a TraceConfig {
 }
 
 #[derive(Clone,PartialEq,Debug)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub enum TraceConfig_oneof_sampler {
     constant_sampler(ConstantSampler),
     trace_id_ratio_based(TraceIdRatioBased),
@@ -562,7 +562,7 @@ impl ::protobuf::reflect::ProtobufValue for TraceConfig {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct ConstantSampler {
     // message fields
     pub decision: ConstantSampler_ConstantDecision,
@@ -713,7 +713,7 @@ impl ::protobuf::reflect::ProtobufValue for ConstantSampler {
 }
 
 #[derive(Clone,PartialEq,Eq,Debug,Hash)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub enum ConstantSampler_ConstantDecision {
     ALWAYS_OFF = 0,
     ALWAYS_ON = 1,
@@ -767,7 +767,7 @@ impl ::protobuf::reflect::ProtobufValue for ConstantSampler_ConstantDecision {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct TraceIdRatioBased {
     // message fields
     pub samplingRatio: f64,
@@ -922,7 +922,7 @@ impl ::protobuf::reflect::ProtobufValue for TraceIdRatioBased {
 }
 
 #[derive(PartialEq,Clone,Default)]
-#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "with-serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct RateLimitingSampler {
     // message fields
     pub qps: i64,
@@ -0,0 +1,360 @@
+//! # OTLP - Export Retries
+//!
+//! Implements the retry behaviour of the [OTLP specification]: exports failing
+//! with a transient error are retried with exponential backoff until a
+//! deadline, and throttling hints sent by the collector, gRPC `RetryInfo` or
+//! the HTTP `Retry-After` header, take precedence over the backoff.
+//!
+//! [OTLP specification]: https://github.com/open-telemetry/opentelemetry-specification/blob/main/specification/protocol/otlp.md#failures
+#[cfg(not(target_arch = "wasm32"))]
+use futures::channel::oneshot;
+use std::future::Future;
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+/// Spread of the backoff delays in percent, keeps many exporters that failed
+/// at the same moment from retrying in lockstep.
+const BACKOFF_JITTER: u8 = 20;
+
+/// Settings for retrying exports that failed with a transient error.
+///
+/// Note that the export timeout of the span processor also bounds the time
+/// spent retrying a batch.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RetryConfig {
+    /// Delay before the first retry, doubled after each further attempt.
+    pub initial_backoff: Duration,
+    /// Upper bound of the delay between two attempts.
+    pub max_backoff: Duration,
+    /// Time after which a failing batch is dropped instead of retried.
+    pub max_elapsed_time: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(10),
+            max_elapsed_time: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Whether and when a failed export can be retried.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Throttle {
+    /// The error is permanent, retrying would fail again.
+    Never,
+    /// The error is transient, retry after the next backoff delay.
+    Backoff,
+    /// The collector asked to retry after the given delay.
+    After(Duration),
+}
+
+impl crate::Error {
+    /// Classify the error according to the OTLP retry rules.
+    pub(crate) fn throttle(&self) -> Throttle {
+        match self {
+            #[cfg(feature = "tonic")]
+            crate::Error::Transport(_) => Throttle::Backoff,
+            #[cfg(feature = "tonic")]
+            crate::Error::Status(status) => grpc::throttle(status),
+            #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+            crate::Error::Grpcio(grpcio::Error::RpcFailure(status)) => {
+                grpcio_throttle(status.status)
+            }
+            #[cfg(feature = "http-proto")]
+            crate::Error::HttpStatus {
+                status,
+                retry_after,
+            } => match status.as_u16() {
+                429 | 502 | 503 | 504 => retry_after.map_or(Throttle::Backoff, Throttle::After),
+                _ => Throttle::Never,
+            },
+            #[cfg(feature = "http-proto")]
+            crate::Error::HttpClient(_) => Throttle::Backoff,
+            _ => Throttle::Never,
+        }
+    }
+}
+
+/// Run `attempt` until it succeeds, fails permanently or `config` gives up.
+///
+/// Without a config the export is attempted once.
+pub(crate) async fn retry<T, F, Fut>(
+    config: Option<RetryConfig>,
+    mut attempt: F,
+) -> Result<T, crate::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, crate::Error>>,
+{
+    let config = match config {
+        Some(config) => config,
+        None => return attempt().await,
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let start = Instant::now();
+    // wasm32 targets have no clock in std, only the delays waited count there
+    #[cfg(target_arch = "wasm32")]
+    let mut waited = Duration::from_secs(0);
+    let mut backoff = config.initial_backoff;
+    loop {
+        let err = match attempt().await {
+            Ok(value) => return Ok(value),
+            Err(err) => err,
+        };
+
+        let delay = match err.throttle() {
+            Throttle::Never => return Err(err),
+            Throttle::Backoff => {
+                let delay = opentelemetry::util::jittered(backoff, BACKOFF_JITTER);
+                backoff = std::cmp::min(backoff * 2, config.max_backoff);
+                delay
+            }
+            Throttle::After(delay) => delay,
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        let elapsed = start.elapsed();
+        #[cfg(target_arch = "wasm32")]
+        let elapsed = waited;
+        if elapsed + delay > config.max_elapsed_time {
+            return Err(err);
+        }
+
+        sleep(delay).await;
+        #[cfg(target_arch = "wasm32")]
+        {
+            waited += delay;
+        }
+    }
+}
+
+/// Wait for `duration` without depending on the executor running the export.
+#[cfg(not(target_arch = "wasm32"))]
+async fn sleep(duration: Duration) {
+    let (tx, rx) = oneshot::channel();
+    std::thread::spawn(move || {
+        std::thread::sleep(duration);
+        let _ = tx.send(());
+    });
+    let _ = rx.await;
+}
+
+/// Wait for `duration` on the JavaScript event loop, `wasm32` targets have no
+/// threads to sleep on.
+#[cfg(target_arch = "wasm32")]
+async fn sleep(duration: Duration) {
+    use opentelemetry::runtime::Runtime;
+
+    opentelemetry::runtime::WasmBindgen.delay(duration).await;
+}
+
+#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+fn grpcio_throttle(code: grpcio::RpcStatusCode) -> Throttle {
+    match code {
+        grpcio::RpcStatusCode::CANCELLED
+        | grpcio::RpcStatusCode::DEADLINE_EXCEEDED
+        | grpcio::RpcStatusCode::ABORTED
+        | grpcio::RpcStatusCode::OUT_OF_RANGE
+        | grpcio::RpcStatusCode::UNAVAILABLE
+        | grpcio::RpcStatusCode::DATA_LOSS => Throttle::Backoff,
+        _ => Throttle::Never,
+    }
+}
+
+#[cfg(feature = "tonic")]
+mod grpc {
+    use super::Throttle;
+    use prost::Message;
+    use std::time::Duration;
+    use tonic::{Code, Status};
+
+    const RETRY_INFO_TYPE_URL: &str = "type.googleapis.com/google.rpc.RetryInfo";
+
+    /// `google.rpc.Status`, sent in the `grpc-status-details-bin` trailer.
+    #[derive(Clone, PartialEq, Message)]
+    struct RpcStatus {
+        #[prost(int32, tag = "1")]
+        code: i32,
+        #[prost(string, tag = "2")]
+        message: String,
+        #[prost(message, repeated, tag = "3")]
+        details: Vec<Any>,
+    }
+
+    /// `google.protobuf.Any`
+    #[derive(Clone, PartialEq, Message)]
+    struct Any {
+        #[prost(string, tag = "1")]
+        type_url: String,
+        #[prost(bytes, tag = "2")]
+        value: Vec<u8>,
+    }
+
+    /// `google.rpc.RetryInfo`
+    #[derive(Clone, PartialEq, Message)]
+    struct RetryInfo {
+        #[prost(message, optional, tag = "1")]
+        retry_delay: Option<ProtoDuration>,
+    }
+
+    /// `google.protobuf.Duration`
+    #[derive(Clone, PartialEq, Message)]
+    struct ProtoDuration {
+        #[prost(int64, tag = "1")]
+        seconds: i64,
+        #[prost(int32, tag = "2")]
+        nanos: i32,
+    }
+
+    pub(super) fn throttle(status: &Status) -> Throttle {
+        let retry_delay = retry_delay(status);
+        match status.code() {
+            Code::Cancelled
+            | Code::DeadlineExceeded
+            | Code::Aborted
+            | Code::OutOfRange
+            | Code::Unavailable
+            | Code::DataLoss => retry_delay.map_or(Throttle::Backoff, Throttle::After),
+            // only retried if the collector signals that it can recover
+            Code::ResourceExhausted => retry_delay.map_or(Throttle::Never, Throttle::After),
+            _ => Throttle::Never,
+        }
+    }
+
+    /// The delay requested by a `RetryInfo` in the status details, if any.
+    fn retry_delay(status: &Status) -> Option<Duration> {
+        let details = RpcStatus::decode(status.details()).ok()?;
+        let retry_info = details
+            .details
+            .iter()
+            .find(|any| any.type_url == RETRY_INFO_TYPE_URL)?;
+        let delay = RetryInfo::decode(retry_info.value.as_slice())
+            .ok()?
+            .retry_delay?;
+        if delay.seconds < 0 || delay.nanos < 0 {
+            return None;
+        }
+        Some(Duration::new(delay.seconds as u64, delay.nanos as u32))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn status_with_retry_info(code: Code, delay: Option<ProtoDuration>) -> Status {
+            let mut retry_info = Vec::new();
+            RetryInfo { retry_delay: delay }
+                .encode(&mut retry_info)
+                .unwrap();
+            let mut details = Vec::new();
+            RpcStatus {
+                code: code as i32,
+                message: String::new(),
+                details: vec![Any {
+                    type_url: RETRY_INFO_TYPE_URL.to_string(),
+                    value: retry_info,
+                }],
+            }
+            .encode(&mut details)
+            .unwrap();
+            Status::with_details(code, "", details.into())
+        }
+
+        #[test]
+        fn throttle_by_status_code() {
+            assert_eq!(
+                throttle(&Status::unavailable("restarting")),
+                Throttle::Backoff
+            );
+            assert_eq!(
+                throttle(&Status::invalid_argument("bad span")),
+                Throttle::Never
+            );
+            assert_eq!(
+                throttle(&Status::resource_exhausted("over quota")),
+                Throttle::Never
+            );
+        }
+
+        #[test]
+        fn throttle_honors_retry_info() {
+            let delay = ProtoDuration {
+                seconds: 3,
+                nanos: 500,
+            };
+            assert_eq!(
+                throttle(&status_with_retry_info(
+                    Code::ResourceExhausted,
+                    Some(delay.clone())
+                )),
+                Throttle::After(Duration::new(3, 500))
+            );
+            assert_eq!(
+                throttle(&status_with_retry_info(Code::Unavailable, Some(delay))),
+                Throttle::After(Duration::new(3, 500))
+            );
+            assert_eq!(
+                throttle(&status_with_retry_info(Code::Unavailable, None)),
+                Throttle::Backoff
+            );
+        }
+    }
+}
+
+#[cfg(all(test, feature = "tonic"))]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn config() -> Option<RetryConfig> {
+        Some(RetryConfig {
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(2),
+            max_elapsed_time: Duration::from_secs(1),
+        })
+    }
+
+    #[test]
+    fn retries_transient_errors() {
+        let attempts = AtomicUsize::new(0);
+        let result = futures::executor::block_on(retry(config(), || async {
+            if attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+                Err(crate::Error::Status(tonic::Status::unavailable("")))
+            } else {
+                Ok(())
+            }
+        }));
+        assert!(result.is_ok());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn does_not_retry_permanent_errors() {
+        let attempts = AtomicUsize::new(0);
+        let result: Result<(), _> = futures::executor::block_on(retry(config(), || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(crate::Error::Status(tonic::Status::invalid_argument("")))
+        }));
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn gives_up_after_max_elapsed_time() {
+        let attempts = AtomicUsize::new(0);
+        let config = Some(RetryConfig {
+            max_elapsed_time: Duration::from_millis(20),
+            ..config().unwrap()
+        });
+        let result: Result<(), _> = futures::executor::block_on(retry(config, || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(crate::Error::Status(tonic::Status::unavailable("")))
+        }));
+        assert!(result.is_err());
+        assert!(attempts.load(Ordering::SeqCst) > 1);
+    }
+}
@@ -3,20 +3,21 @@
 //! Defines a [SpanExporter] to send trace data via the OpenTelemetry Protocol (OTLP)
 
 #[cfg(feature = "tonic")]
-use crate::proto::collector::trace::v1::{
-    trace_service_client::TraceServiceClient, ExportTraceServiceRequest,
-};
+use crate::proto::collector::trace::v1::ExportTraceServiceRequest;
+
+#[cfg(feature = "tonic")]
+use crate::partial_success::ExportTraceServiceResponse;
 
 #[cfg(feature = "tonic")]
 use tonic::{
+    client::Grpc,
+    codec::ProstCodec,
+    codegen::http::uri::PathAndQuery,
     metadata::{KeyAndValueRef, MetadataMap},
-    transport::Channel,
+    transport::{Channel, Endpoint},
     Request,
 };
 
-#[cfg(all(feature = "tonic", feature = "tls"))]
-use tonic::transport::ClientTlsConfig;
-
 #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
 use crate::proto::grpcio::trace_service::ExportTraceServiceRequest;
 
@@ -31,22 +32,44 @@ use grpcio::{
 #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
 use protobuf::RepeatedField;
 
+#[cfg(any(feature = "tonic", feature = "grpc-sys"))]
 use async_trait::async_trait;
 
-#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+#[cfg(not(feature = "tonic"))]
 use std::collections::HashMap;
 
 use std::fmt;
+#[cfg(any(feature = "tonic", feature = "grpc-sys"))]
 use std::fmt::Debug;
+use std::str::FromStr;
+
+#[cfg(any(
+    all(feature = "grpc-sys", not(feature = "tonic")),
+    all(feature = "tonic", feature = "tls")
+))]
+use std::convert::TryFrom;
 
 #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
 use std::sync::Arc;
 
-use crate::{Protocol, OTEL_EXPORTER_OTLP_ENDPOINT_DEFAULT, OTEL_EXPORTER_OTLP_TIMEOUT_DEFAULT};
+#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+use opentelemetry::{global, trace::TraceError};
+
+use crate::partial_success::ExportStats;
+#[cfg(any(feature = "tonic", feature = "grpc-sys"))]
+use crate::retry::retry;
+use crate::retry::RetryConfig;
+#[cfg(any(feature = "tonic", feature = "grpc-sys"))]
+use crate::transform::resource_spans;
+use crate::{
+    Protocol, TlsConfig, OTEL_EXPORTER_OTLP_ENDPOINT_DEFAULT, OTEL_EXPORTER_OTLP_TIMEOUT_DEFAULT,
+};
+#[cfg(any(feature = "tonic", feature = "grpc-sys"))]
 use opentelemetry::sdk::export::trace::{ExportResult, SpanData, SpanExporter};
 use std::time::Duration;
 
 /// Exporter that sends data in OTLP format.
+#[cfg(any(feature = "tonic", feature = "grpc-sys"))]
 pub struct TraceExporter {
     #[cfg(feature = "tonic")]
     metadata: Option<MetadataMap>,
@@ -56,8 +79,14 @@ pub struct TraceExporter {
 
     timeout: Duration,
 
+    retry: Option<RetryConfig>,
+
+    stats: ExportStats,
+
+    encoding_parallelism: usize,
+
     #[cfg(feature = "tonic")]
-    trace_exporter: TraceServiceClient<Channel>,
+    trace_exporter: Grpc<Channel>,
 
     #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
     trace_exporter: TraceServiceClient,
@@ -75,9 +104,8 @@ pub struct ExporterConfig {
     /// The protocol to use when communicating with the collector.
     pub protocol: Protocol,
 
-    #[cfg(all(feature = "tonic", feature = "tls"))]
     /// TLS settings for the collector endpoint.
-    pub tls_config: Option<ClientTlsConfig>,
+    pub tls_config: Option<TlsConfig>,
 
     #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
     /// The credentials to use when communicating with the collector.
@@ -88,11 +116,10 @@ pub struct ExporterConfig {
     pub metadata: Option<MetadataMap>,
 
     /// Additional headers to send to the collector.
-    #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+    #[cfg(not(feature = "tonic"))]
     pub headers: Option<HashMap<String, String>>,
 
     /// The compression algorithm to use when communicating with the collector.
-    #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
     pub compression: Option<Compression>,
 
     /// Use TLS without any specific certificate pinning.
@@ -102,6 +129,26 @@ pub struct ExporterConfig {
     /// The timeout to the collector.
     pub timeout: Duration,
 
+    /// Establish the connection to the collector when the exporter is built
+    /// instead of on the first export.
+    pub connect_eagerly: bool,
+
+    /// How span exports failing with a transient error are retried, `None` to
+    /// drop the batch instead.
+    pub retry: Option<RetryConfig>,
+
+    /// Counters of the export outcomes reported by the collector.
+    pub stats: ExportStats,
+
+    /// The number of threads converting batches of at least 512 spans to
+    /// protobuf, 1 to convert them on the export task.
+    ///
+    /// The batch is split into consecutive chunks, one per thread, and the
+    /// export waits for all of them, so this is best kept at or below the
+    /// number of idle cores. Ignored on `wasm32` targets, which have no
+    /// threads.
+    pub encoding_parallelism: usize,
+
     /// The number of GRPC worker threads to poll queues.
     #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
     pub completion_queue_count: usize,
@@ -122,52 +169,84 @@ pub struct Credentials {
 }
 
 /// The compression algorithm to use when sending data.
-#[derive(Clone, Copy, Debug)]
-#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+///
+/// gRPC requests can only be compressed with gzip when using the `grpc-sys`
+/// feature. OTLP/HTTP requests are compressed with the `gzip-http` and
+/// `zstd-http` features.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Compression {
     /// Compresses data using gzip.
     Gzip,
+    /// Compresses data using zstd.
+    Zstd,
 }
 
-#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
-impl Into<grpcio::CompressionAlgorithms> for Compression {
-    fn into(self) -> grpcio::CompressionAlgorithms {
-        match self {
-            Compression::Gzip => grpcio::CompressionAlgorithms::GRPC_COMPRESS_GZIP,
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+        })
+    }
+}
+
+impl FromStr for Compression {
+    type Err = String;
+
+    /// Parse a compression from its `OTEL_EXPORTER_OTLP_COMPRESSION` value.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "gzip" => Ok(Compression::Gzip),
+            "zstd" => Ok(Compression::Zstd),
+            other => Err(format!("unsupported OTLP compression {}", other)),
         }
     }
 }
 
-impl Default for ExporterConfig {
-    #[cfg(feature = "tonic")]
-    fn default() -> Self {
-        ExporterConfig {
-            endpoint: OTEL_EXPORTER_OTLP_ENDPOINT_DEFAULT.to_string(),
-            protocol: Protocol::Grpc,
-            #[cfg(all(feature = "tonic", feature = "tls"))]
-            tls_config: None,
-            metadata: None,
-            timeout: Duration::from_secs(OTEL_EXPORTER_OTLP_TIMEOUT_DEFAULT),
-            #[cfg(not(feature = "async"))]
-            runtime: None,
+#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+impl TryFrom<Compression> for grpcio::CompressionAlgorithms {
+    type Error = crate::Error;
+
+    fn try_from(compression: Compression) -> Result<Self, Self::Error> {
+        match compression {
+            Compression::Gzip => Ok(grpcio::CompressionAlgorithms::GRPC_COMPRESS_GZIP),
+            Compression::Zstd => Err(crate::Error::UnsupportedCompression(compression)),
         }
     }
+}
 
-    #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+impl Default for ExporterConfig {
     fn default() -> Self {
         ExporterConfig {
             endpoint: OTEL_EXPORTER_OTLP_ENDPOINT_DEFAULT.to_string(),
+            #[cfg(any(feature = "tonic", feature = "grpc-sys"))]
             protocol: Protocol::Grpc,
+            #[cfg(not(any(feature = "tonic", feature = "grpc-sys")))]
+            protocol: Protocol::HttpBinary,
+            tls_config: None,
+            #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
             credentials: None,
+            #[cfg(feature = "tonic")]
+            metadata: None,
+            #[cfg(not(feature = "tonic"))]
             headers: None,
             compression: None,
+            #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
             use_tls: None,
             timeout: Duration::from_secs(OTEL_EXPORTER_OTLP_TIMEOUT_DEFAULT),
+            connect_eagerly: false,
+            retry: Some(RetryConfig::default()),
+            stats: ExportStats::default(),
+            encoding_parallelism: 1,
+            #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
             completion_queue_count: 2,
+            #[cfg(all(feature = "tonic", not(feature = "async")))]
+            runtime: None,
         }
     }
 }
 
+#[cfg(any(feature = "tonic", feature = "grpc-sys"))]
 impl Default for TraceExporter {
     /// Return a Span Exporter with the default configuration
     #[cfg(feature = "tonic")]
@@ -179,8 +258,11 @@ impl Default for TraceExporter {
         let channel = endpoint.timeout(config.timeout).connect_lazy().unwrap();
 
         TraceExporter {
-            trace_exporter: TraceServiceClient::new(channel),
+            trace_exporter: Grpc::new(channel),
             timeout: config.timeout,
+            retry: config.retry,
+            stats: config.stats,
+            encoding_parallelism: config.encoding_parallelism,
             metadata: config.metadata,
             #[cfg(not(feature = "async"))]
             runtime: config.runtime.unwrap_or_else(|| {
@@ -204,18 +286,24 @@ impl Default for TraceExporter {
         TraceExporter {
             trace_exporter: TraceServiceClient::new(channel),
             timeout: config.timeout,
+            retry: config.retry,
+            stats: config.stats,
+            encoding_parallelism: config.encoding_parallelism,
             headers: None,
         }
     }
 }
 
+#[cfg(any(feature = "tonic", feature = "grpc-sys"))]
 impl Debug for TraceExporter {
     #[cfg(feature = "tonic")]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Exporter")
             .field("metadata", &self.metadata)
             .field("timeout", &self.timeout)
-            .field("trace_exporter", &"TraceServiceClient")
+            .field("retry", &self.retry)
+            .field("encoding_parallelism", &self.encoding_parallelism)
+            .field("trace_exporter", &"TraceService")
             .finish()
     }
 
@@ -224,79 +312,179 @@ impl Debug for TraceExporter {
         f.debug_struct("Exporter")
             .field("headers", &self.headers)
             .field("timeout", &self.timeout)
+            .field("retry", &self.retry)
+            .field("encoding_parallelism", &self.encoding_parallelism)
             .field("trace_exporter", &"TraceServiceClient")
             .finish()
     }
 }
 
+#[cfg(any(feature = "tonic", feature = "grpc-sys"))]
 impl TraceExporter {
     /// Builds a new span exporter with the given configuration
+    ///
+    /// Compression is not supported by the tonic transport, setting
+    /// [`ExporterConfig::compression`] returns an error, as does setting
+    /// [`ExporterConfig::tls_config`] without the `tls` feature.
+    ///
+    /// If [`ExporterConfig::connect_eagerly`] is set the connection to the
+    /// collector is established before returning, so a misconfigured endpoint
+    /// is reported here rather than on the first export. With the `async`
+    /// feature enabled eager connections can only be established by
+    /// [`TraceExporter::connect`].
     #[cfg(feature = "tonic")]
     pub fn new(config: ExporterConfig) -> Result<Self, crate::Error> {
-        let endpoint = Channel::from_shared(config.endpoint)?;
-
-        #[cfg(all(feature = "tonic", feature = "tls"))]
-        let channel = match config.tls_config {
-            Some(tls_config) => endpoint.tls_config(tls_config)?,
-            None => endpoint,
+        if let Some(compression) = config.compression {
+            return Err(crate::Error::UnsupportedCompression(compression));
         }
-        .timeout(config.timeout)
-        .connect_lazy()?;
-
-        #[cfg(not(feature = "tls"))]
-        let channel = endpoint.timeout(config.timeout).connect_lazy()?;
-
-        let client = match config.metadata.to_owned() {
-            None => TraceServiceClient::new(channel),
-            Some(metadata) => {
-                TraceServiceClient::with_interceptor(channel, move |mut req: Request<()>| {
-                    for key_and_value in metadata.iter() {
-                        match key_and_value {
-                            KeyAndValueRef::Ascii(key, value) => {
-                                req.metadata_mut().append(key, value.to_owned())
-                            }
-                            KeyAndValueRef::Binary(key, value) => {
-                                req.metadata_mut().append_bin(key, value.to_owned())
-                            }
-                        };
-                    }
 
-                    Ok(req)
-                })
+        #[cfg(not(feature = "async"))]
+        let runtime = config.runtime.unwrap_or_else(|| {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+        });
+
+        let endpoint = build_endpoint(config.endpoint, config.timeout, config.tls_config)?;
+
+        #[cfg(not(feature = "async"))]
+        let channel = {
+            // the channel spawns its worker onto the runtime it is created in
+            let _guard = runtime.enter();
+            if config.connect_eagerly {
+                runtime.block_on(endpoint.connect())?
+            } else {
+                endpoint.connect_lazy()?
             }
         };
 
-        Ok(TraceExporter {
-            trace_exporter: client,
-            timeout: config.timeout,
-            metadata: config.metadata,
+        #[cfg(feature = "async")]
+        let channel = if config.connect_eagerly {
+            return Err(crate::Error::EagerConnectRequiresAsync);
+        } else {
+            endpoint.connect_lazy()?
+        };
+
+        Ok(TraceExporter::from_channel(
+            channel,
+            config.timeout,
+            config.retry,
+            config.stats,
+            config.encoding_parallelism,
+            config.metadata,
             #[cfg(not(feature = "async"))]
-            runtime: config.runtime.unwrap_or_else(|| {
-                tokio::runtime::Builder::new_current_thread()
-                    .enable_all()
-                    .build()
-                    .unwrap()
-            }),
-        })
+            runtime,
+        ))
+    }
+
+    /// Builds a new span exporter with the given configuration, establishing
+    /// the connection to the collector first if
+    /// [`ExporterConfig::connect_eagerly`] is set.
+    #[cfg(all(feature = "tonic", feature = "async"))]
+    pub async fn connect(config: ExporterConfig) -> Result<Self, crate::Error> {
+        if let Some(compression) = config.compression {
+            return Err(crate::Error::UnsupportedCompression(compression));
+        }
+
+        let endpoint = build_endpoint(config.endpoint, config.timeout, config.tls_config)?;
+
+        let channel = if config.connect_eagerly {
+            endpoint.connect().await?
+        } else {
+            endpoint.connect_lazy()?
+        };
+
+        Ok(TraceExporter::from_channel(
+            channel,
+            config.timeout,
+            config.retry,
+            config.stats,
+            config.encoding_parallelism,
+            config.metadata,
+        ))
+    }
+
+    #[cfg(feature = "tonic")]
+    fn from_channel(
+        channel: Channel,
+        timeout: Duration,
+        retry: Option<RetryConfig>,
+        stats: ExportStats,
+        encoding_parallelism: usize,
+        metadata: Option<MetadataMap>,
+        #[cfg(not(feature = "async"))] runtime: tokio::runtime::Runtime,
+    ) -> Self {
+        TraceExporter {
+            trace_exporter: grpc_client(channel, metadata.to_owned()),
+            timeout,
+            retry,
+            stats,
+            encoding_parallelism,
+            metadata,
+            #[cfg(not(feature = "async"))]
+            runtime,
+        }
+    }
+
+    /// Check that the collector is reachable and accepts exports.
+    ///
+    /// Sends an empty export request, so connection, TLS and authentication
+    /// problems surface without having to wait for the first batch of spans.
+    #[cfg(feature = "tonic")]
+    pub async fn ready(&mut self) -> Result<(), crate::Error> {
+        let request = ExportTraceServiceRequest {
+            resource_spans: Vec::new(),
+        };
+
+        #[cfg(feature = "async")]
+        let result: Result<ExportTraceServiceResponse, _> =
+            send_export(self.trace_exporter.to_owned(), request, TRACE_EXPORT_PATH).await;
+
+        #[cfg(not(feature = "async"))]
+        let result: Result<ExportTraceServiceResponse, _> = self.runtime.block_on(send_export(
+            self.trace_exporter.to_owned(),
+            request,
+            TRACE_EXPORT_PATH,
+        ));
+
+        self.stats.record_attempt(result)?;
+        Ok(())
     }
 
     /// Builds a new span exporter with the given configuration
+    ///
+    /// Only gzip compression is supported, other algorithms are reported to
+    /// the global error handler and the requests are sent uncompressed.
+    ///
+    /// [`ExporterConfig::tls_config`] takes precedence over the credentials,
+    /// a client certificate without key is reported to the global error
+    /// handler and left out.
     #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
     pub fn new(config: ExporterConfig) -> Self {
         let mut builder: ChannelBuilder =
             ChannelBuilder::new(Arc::new(Environment::new(config.completion_queue_count)));
 
         if let Some(compression) = config.compression {
-            builder = builder.default_compression_algorithm(compression.into());
+            match grpcio::CompressionAlgorithms::try_from(compression) {
+                Ok(algorithm) => builder = builder.default_compression_algorithm(algorithm),
+                Err(err) => global::handle_error(TraceError::from(err)),
+            }
         }
 
-        let channel: Channel = match (config.credentials, config.use_tls) {
-            (None, Some(true)) => builder.secure_connect(
+        let channel: Channel = match (config.tls_config, config.credentials, config.use_tls) {
+            (Some(tls_config), _, _) => {
+                if let Some(domain_name) = tls_config.domain_name.clone() {
+                    builder = builder.override_ssl_target(domain_name);
+                }
+                builder.secure_connect(config.endpoint.as_str(), channel_credentials(tls_config))
+            }
+            (None, None, Some(true)) => builder.secure_connect(
                 config.endpoint.as_str(),
                 ChannelCredentialsBuilder::new().build(),
             ),
-            (None, _) => builder.connect(config.endpoint.as_str()),
-            (Some(credentials), _) => builder.secure_connect(
+            (None, None, _) => builder.connect(config.endpoint.as_str()),
+            (None, Some(credentials), _) => builder.secure_connect(
                 config.endpoint.as_str(),
                 ChannelCredentialsBuilder::new()
                     .cert(credentials.cert.into(), credentials.key.into())
@@ -307,59 +495,192 @@ impl TraceExporter {
         TraceExporter {
             trace_exporter: TraceServiceClient::new(channel),
             timeout: config.timeout,
+            retry: config.retry,
+            stats: config.stats,
+            encoding_parallelism: config.encoding_parallelism,
             headers: config.headers,
         }
     }
+
+    /// Check that the collector is reachable and accepts exports.
+    ///
+    /// Sends an empty export request, so connection, TLS and authentication
+    /// problems surface without having to wait for the first batch of spans.
+    #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+    pub async fn ready(&mut self) -> Result<(), crate::Error> {
+        let request = ExportTraceServiceRequest::default();
+        let receiver = self
+            .trace_exporter
+            .export_async_opt(&request, self.call_options())?;
+        receiver.await?;
+        Ok(())
+    }
+
+    #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+    fn call_options(&self) -> CallOption {
+        let mut call_options = CallOption::default().timeout(self.timeout);
+
+        if let Some(headers) = self.headers.clone() {
+            let mut metadata_builder: MetadataBuilder = MetadataBuilder::new();
+
+            for (key, value) in headers {
+                let _ = metadata_builder.add_str(key.as_str(), value.as_str());
+            }
+
+            call_options = call_options.headers(metadata_builder.build());
+        }
+
+        call_options
+    }
 }
 
+/// Create the credentials for a secure channel to the collector.
+#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+fn channel_credentials(tls_config: TlsConfig) -> grpcio::ChannelCredentials {
+    let mut credentials = ChannelCredentialsBuilder::new();
+    match tls_config.identity() {
+        Ok(Some((cert, key))) => credentials = credentials.cert(cert.to_vec(), key.to_vec()),
+        Ok(None) => {}
+        Err(err) => global::handle_error(TraceError::from(err)),
+    }
+    if let Some(ca_cert) = tls_config.ca_cert {
+        credentials = credentials.root_cert(ca_cert);
+    }
+    credentials.build()
+}
+
+/// Path of the export method of the trace service.
+#[cfg(feature = "tonic")]
+const TRACE_EXPORT_PATH: &str = "/opentelemetry.proto.collector.trace.v1.TraceService/Export";
+
+/// Send an export request to the service method at `path`.
+///
+/// The trace service response is decoded with the partial success the
+/// generated client does not know about.
+#[cfg(feature = "tonic")]
+pub(crate) async fn send_export<T, U>(
+    mut client: Grpc<Channel>,
+    request: T,
+    path: &'static str,
+) -> Result<U, crate::Error>
+where
+    T: prost::Message + Sync + 'static,
+    U: prost::Message + Default + Sync + 'static,
+{
+    client.ready().await?;
+    let response = client
+        .unary(
+            Request::new(request),
+            PathAndQuery::from_static(path),
+            ProstCodec::default(),
+        )
+        .await?;
+    Ok(response.into_inner())
+}
+
+/// Create a client for the collector channel adding the custom metadata to
+/// every request.
+#[cfg(feature = "tonic")]
+pub(crate) fn grpc_client(channel: Channel, metadata: Option<MetadataMap>) -> Grpc<Channel> {
+    match metadata {
+        None => Grpc::new(channel),
+        Some(metadata) => Grpc::with_interceptor(channel, move |mut req: Request<()>| {
+            for key_and_value in metadata.iter() {
+                match key_and_value {
+                    KeyAndValueRef::Ascii(key, value) => {
+                        req.metadata_mut().append(key, value.to_owned())
+                    }
+                    KeyAndValueRef::Binary(key, value) => {
+                        req.metadata_mut().append_bin(key, value.to_owned())
+                    }
+                };
+            }
+
+            Ok(req)
+        }),
+    }
+}
+
+/// Create the endpoint for the collector channel.
+#[cfg(feature = "tonic")]
+pub(crate) fn build_endpoint(
+    endpoint: String,
+    timeout: Duration,
+    tls_config: Option<TlsConfig>,
+) -> Result<Endpoint, crate::Error> {
+    let endpoint = Channel::from_shared(endpoint)?;
+
+    #[cfg(feature = "tls")]
+    let endpoint = match tls_config {
+        Some(tls_config) => {
+            endpoint.tls_config(tonic::transport::ClientTlsConfig::try_from(tls_config)?)?
+        }
+        None => endpoint,
+    };
+    #[cfg(not(feature = "tls"))]
+    if tls_config.is_some() {
+        return Err(crate::Error::InvalidTlsConfig(
+            "TLS settings require the `tls` feature",
+        ));
+    }
+
+    Ok(endpoint.timeout(timeout))
+}
+
+#[cfg(any(feature = "tonic", feature = "grpc-sys"))]
 #[async_trait]
 impl SpanExporter for TraceExporter {
     #[cfg(feature = "tonic")]
     async fn export(&mut self, batch: Vec<SpanData>) -> ExportResult {
-        let request = Request::new(ExportTraceServiceRequest {
-            resource_spans: batch.into_iter().map(Into::into).collect(),
+        let request = ExportTraceServiceRequest {
+            resource_spans: resource_spans(batch, self.encoding_parallelism),
+        };
+        let client = &self.trace_exporter;
+        let stats = &self.stats;
+        let export = retry(self.retry, || {
+            let attempt = send_export::<_, ExportTraceServiceResponse>(
+                client.to_owned(),
+                request.clone(),
+                TRACE_EXPORT_PATH,
+            );
+            async move { stats.record_attempt(attempt.await) }
         });
 
         #[cfg(feature = "async")]
-        self.trace_exporter
-            .to_owned()
-            .export(request)
-            .await
-            .map_err::<crate::Error, _>(Into::into)?;
+        let response = export.await?;
 
         #[cfg(not(feature = "async"))]
-        self.runtime
-            .block_on(self.trace_exporter.to_owned().export(request))
-            .map_err::<crate::Error, _>(Into::into)?;
+        let response = self.runtime.block_on(export)?;
 
+        self.stats.record(response.partial_success);
         Ok(())
     }
 
     #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
     async fn export(&mut self, batch: Vec<SpanData>) -> ExportResult {
         let request = ExportTraceServiceRequest {
-            resource_spans: RepeatedField::from_vec(batch.into_iter().map(Into::into).collect()),
+            resource_spans: RepeatedField::from_vec(resource_spans(
+                batch,
+                self.encoding_parallelism,
+            )),
             unknown_fields: Default::default(),
             cached_size: Default::default(),
         };
 
-        let mut call_options = CallOption::default().timeout(self.timeout);
-
-        if let Some(headers) = self.headers.clone() {
-            let mut metadata_builder: MetadataBuilder = MetadataBuilder::new();
-
-            for (key, value) in headers {
-                let _ = metadata_builder.add_str(key.as_str(), value.as_str());
-            }
-
-            call_options = call_options.headers(metadata_builder.build());
-        }
+        let stats = &self.stats;
+        let response = retry(self.retry, || {
+            let receiver = self
+                .trace_exporter
+                .export_async_opt(&request, self.call_options());
+            let attempt = async move { Ok(receiver?.await?) };
+            async move { stats.record_attempt(attempt.await) }
+        })
+        .await?;
 
-        let receiver = self
-            .trace_exporter
-            .export_async_opt(&request, call_options)
-            .map_err::<crate::Error, _>(Into::into)?;
-        receiver.await.map_err::<crate::Error, _>(Into::into)?;
+        self.stats
+            .record(crate::partial_success::from_unknown_fields(
+                &response.unknown_fields,
+            ));
         Ok(())
     }
 }
@@ -0,0 +1,382 @@
+//! # OTLP - Testing
+//!
+//! A fake collector for end-to-end tests of exporters and pipelines,
+//! available with the `integration-testing` feature.
+//!
+//! The [`FakeCollector`] serves the OTLP/gRPC trace service on a local port,
+//! records the export requests it receives and answers them as configured, so
+//! tests can check the spans that arrive as well as how exporters handle
+//! failing, slow and partially successful exports.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use opentelemetry::trace::Tracer;
+//! use opentelemetry_otlp::testing::{FakeCollector, FakeResponse};
+//! use std::time::Duration;
+//!
+//! let collector = FakeCollector::start()?;
+//! collector.respond_with(FakeResponse::PartialSuccess {
+//!     rejected_spans: 1,
+//!     error_message: "span name is empty".to_string(),
+//! });
+//!
+//! let tracer = opentelemetry_otlp::new_pipeline()
+//!     .with_endpoint(collector.endpoint())
+//!     .install()?;
+//! tracer.in_span("work", |_cx| {});
+//!
+//! let spans = collector.wait_for_spans(1, Duration::from_secs(5));
+//! assert_eq!(spans[0].name, "work");
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+use crate::partial_success::{ExportTracePartialSuccess, ExportTraceServiceResponse};
+use crate::proto::collector::trace::v1::ExportTraceServiceRequest;
+use crate::proto::trace::v1::Span;
+use futures::channel::oneshot;
+use futures::stream;
+use std::collections::VecDeque;
+use std::io;
+use std::net::{SocketAddr, TcpListener};
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+use tonic::codegen::{http, Arc, BoxFuture, Context, HttpBody, Never, Poll, Service, StdError};
+use tonic::server::{Grpc, UnaryService};
+use tonic::transport::{NamedService, Server};
+use tonic::{codec::ProstCodec, Request, Response, Status};
+
+/// Path of the export method of the trace service.
+const TRACE_EXPORT_PATH: &str = "/opentelemetry.proto.collector.trace.v1.TraceService/Export";
+
+/// How a [`FakeCollector`] answers an export request.
+#[derive(Clone, Debug)]
+pub enum FakeResponse {
+    /// Accept all spans.
+    Success,
+    /// Accept the request but report spans as rejected, see the
+    /// `partial_success` field of the OTLP response.
+    PartialSuccess {
+        /// The number of rejected spans.
+        rejected_spans: i64,
+        /// Why the spans were rejected, or a warning if none were.
+        error_message: String,
+    },
+    /// Fail the request with the status, e.g. `UNAVAILABLE` to test retries.
+    Error(Status),
+}
+
+/// An OTLP/gRPC collector recording the trace export requests it receives.
+///
+/// Requests are answered with the responses queued with [`respond_with`] in
+/// order, and with the default response, [`FakeResponse::Success`] unless
+/// changed, once the queue is empty. Requests are recorded when they are
+/// received, also the ones answered with an error.
+///
+/// The collector serves on a thread of its own, so it can be used from sync
+/// tests as well as from within any async runtime. It stops when dropped.
+///
+/// [`respond_with`]: FakeCollector::respond_with
+#[derive(Debug)]
+pub struct FakeCollector {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+    shutdown: Option<oneshot::Sender<()>>,
+    server: Option<thread::JoinHandle<()>>,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    state: Mutex<State>,
+    received: Condvar,
+}
+
+#[derive(Debug)]
+struct State {
+    requests: Vec<ExportTraceServiceRequest>,
+    responses: VecDeque<FakeResponse>,
+    default_response: FakeResponse,
+    delay: Duration,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State {
+            requests: Vec::new(),
+            responses: VecDeque::new(),
+            default_response: FakeResponse::Success,
+            delay: Duration::from_secs(0),
+        }
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl FakeCollector {
+    /// Start a collector on a free port of the loopback interface.
+    pub fn start() -> io::Result<FakeCollector> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+
+        let shared = Arc::new(Shared::default());
+        let service = TraceService {
+            shared: shared.clone(),
+        };
+        let (shutdown, stopped) = oneshot::channel();
+        let server = thread::Builder::new()
+            .name("opentelemetry-otlp-fake-collector".to_string())
+            .spawn(move || {
+                runtime.block_on(async move {
+                    let listener = match tokio::net::TcpListener::from_std(listener) {
+                        Ok(listener) => listener,
+                        Err(_) => return,
+                    };
+                    let incoming = stream::unfold(listener, |listener| async move {
+                        let stream = listener.accept().await.map(|(stream, _)| stream);
+                        Some((stream, listener))
+                    });
+                    let _ = Server::builder()
+                        .add_service(service)
+                        .serve_with_incoming_shutdown(incoming, async {
+                            let _ = stopped.await;
+                        })
+                        .await;
+                })
+            })?;
+
+        Ok(FakeCollector {
+            addr,
+            shared,
+            shutdown: Some(shutdown),
+            server: Some(server),
+        })
+    }
+
+    /// The address the collector listens on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The endpoint to configure exporters with, e.g. `http://127.0.0.1:4317`.
+    pub fn endpoint(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Queue a response for the next request without a queued response.
+    pub fn respond_with(&self, response: FakeResponse) {
+        self.shared.lock().responses.push_back(response);
+    }
+
+    /// Set the response to requests once the queued responses are used up.
+    pub fn set_default_response(&self, response: FakeResponse) {
+        self.shared.lock().default_response = response;
+    }
+
+    /// Delay the responses to all following requests, e.g. to test export
+    /// timeouts.
+    pub fn set_delay(&self, delay: Duration) {
+        self.shared.lock().delay = delay;
+    }
+
+    /// The export requests received so far, in the order they were received.
+    pub fn requests(&self) -> Vec<ExportTraceServiceRequest> {
+        self.shared.lock().requests.clone()
+    }
+
+    /// The spans of the requests received so far.
+    pub fn spans(&self) -> Vec<Span> {
+        spans(&self.shared.lock().requests)
+    }
+
+    /// Remove the received requests.
+    pub fn clear(&self) {
+        self.shared.lock().requests.clear();
+    }
+
+    /// Wait until at least `count` requests were received and return them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer requests are received within `timeout`.
+    pub fn wait_for_requests(
+        &self,
+        count: usize,
+        timeout: Duration,
+    ) -> Vec<ExportTraceServiceRequest> {
+        self.wait_until(timeout, |requests| requests.len() >= count)
+            .unwrap_or_else(|received| {
+                panic!(
+                    "expected {} export requests within {:?}, received {}",
+                    count, timeout, received
+                )
+            })
+    }
+
+    /// Wait until the received requests contain at least `count` spans and
+    /// return the spans.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer spans are received within `timeout`.
+    pub fn wait_for_spans(&self, count: usize, timeout: Duration) -> Vec<Span> {
+        self.wait_until(timeout, |requests| spans(requests).len() >= count)
+            .map(|requests| spans(&requests))
+            .unwrap_or_else(|_| {
+                panic!(
+                    "expected {} spans within {:?}, received {}",
+                    count,
+                    timeout,
+                    self.spans().len()
+                )
+            })
+    }
+
+    /// Wait until the requests satisfy `done`, returns the number of requests
+    /// received if they don't within `timeout`.
+    fn wait_until(
+        &self,
+        timeout: Duration,
+        done: impl Fn(&[ExportTraceServiceRequest]) -> bool,
+    ) -> Result<Vec<ExportTraceServiceRequest>, usize> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.shared.lock();
+        while !done(&state.requests) {
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(state.requests.len());
+            }
+            state = self
+                .shared
+                .received
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+        Ok(state.requests.clone())
+    }
+}
+
+impl Drop for FakeCollector {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(server) = self.server.take() {
+            let _ = server.join();
+        }
+    }
+}
+
+fn spans(requests: &[ExportTraceServiceRequest]) -> Vec<Span> {
+    requests
+        .iter()
+        .flat_map(|request| &request.resource_spans)
+        .flat_map(|resource_spans| &resource_spans.instrumentation_library_spans)
+        .flat_map(|library_spans| library_spans.spans.iter().cloned())
+        .collect()
+}
+
+/// The trace service of a [`FakeCollector`].
+///
+/// Written out instead of generated, as the generated response type predates
+/// the `partial_success` field.
+#[derive(Clone, Debug)]
+struct TraceService {
+    shared: Arc<Shared>,
+}
+
+impl TraceService {
+    async fn export(
+        self,
+        request: ExportTraceServiceRequest,
+    ) -> Result<ExportTraceServiceResponse, Status> {
+        let (response, delay) = {
+            let mut state = self.shared.lock();
+            state.requests.push(request);
+            self.shared.received.notify_all();
+            let response = match state.responses.pop_front() {
+                Some(response) => response,
+                None => state.default_response.clone(),
+            };
+            (response, state.delay)
+        };
+        if delay > Duration::from_secs(0) {
+            tokio::time::sleep(delay).await;
+        }
+        match response {
+            FakeResponse::Success => Ok(ExportTraceServiceResponse {
+                partial_success: None,
+            }),
+            FakeResponse::PartialSuccess {
+                rejected_spans,
+                error_message,
+            } => Ok(ExportTraceServiceResponse {
+                partial_success: Some(ExportTracePartialSuccess {
+                    rejected_spans,
+                    error_message,
+                }),
+            }),
+            FakeResponse::Error(status) => Err(status),
+        }
+    }
+}
+
+impl UnaryService<ExportTraceServiceRequest> for TraceService {
+    type Response = ExportTraceServiceResponse;
+    type Future = BoxFuture<Response<Self::Response>, Status>;
+
+    fn call(&mut self, request: Request<ExportTraceServiceRequest>) -> Self::Future {
+        let service = self.clone();
+        Box::pin(async move {
+            service
+                .export(request.into_inner())
+                .await
+                .map(Response::new)
+        })
+    }
+}
+
+impl<B> Service<http::Request<B>> for TraceService
+where
+    B: HttpBody + Send + Sync + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<tonic::body::BoxBody>;
+    type Error = Never;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let service = self.clone();
+        if request.uri().path() != TRACE_EXPORT_PATH {
+            return Box::pin(async move {
+                Ok(http::Response::builder()
+                    .status(200)
+                    // UNIMPLEMENTED
+                    .header("grpc-status", "12")
+                    .header("content-type", "application/grpc")
+                    .body(tonic::body::BoxBody::empty())
+                    .unwrap())
+            });
+        }
+        Box::pin(async move {
+            let mut grpc = Grpc::new(ProstCodec::default());
+            Ok(grpc.unary(service, request).await)
+        })
+    }
+}
+
+impl NamedService for TraceService {
+    const NAME: &'static str = "opentelemetry.proto.collector.trace.v1.TraceService";
+}
@@ -0,0 +1,110 @@
+//! # OTLP - TLS
+//!
+//! TLS settings shared by the gRPC and HTTP transports, so custom root
+//! certificates and client certificates for mutual TLS can be configured
+//! without depending on the TLS types of the transport library.
+
+/// TLS settings for the connection to the collector.
+///
+/// Certificates and keys are PEM encoded. Settings left empty fall back to the
+/// defaults of the transport, e.g. the system or bundled root certificates.
+///
+/// | Transport | Required feature |
+/// |---|---|
+/// | tonic | `tls` |
+/// | grpcio | none |
+/// | HTTP with the default client | `reqwest-rustls` |
+///
+/// Overriding the domain name is only supported by the gRPC transports. A
+/// client passed to [`OtlpPipelineBuilder::with_http_client`] has to be
+/// configured directly.
+///
+/// # Examples
+///
+/// ```no_run
+/// use opentelemetry_otlp::TlsConfig;
+///
+/// let tls = TlsConfig {
+///     ca_cert: Some(std::fs::read("ca.pem")?),
+///     client_cert: Some(std::fs::read("client.pem")?),
+///     client_key: Some(std::fs::read("client.key")?),
+///     domain_name: Some("collector.example.com".to_string()),
+/// };
+/// let pipeline = opentelemetry_otlp::new_pipeline().with_tls_config(tls);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// [`OtlpPipelineBuilder::with_http_client`]: crate::OtlpPipelineBuilder::with_http_client
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TlsConfig {
+    /// Certificate of the authority used to verify the collector.
+    pub ca_cert: Option<Vec<u8>>,
+    /// Certificate presented to the collector for mutual TLS.
+    pub client_cert: Option<Vec<u8>>,
+    /// Private key of the client certificate.
+    pub client_key: Option<Vec<u8>>,
+    /// Name used to verify the certificate of the collector instead of the
+    /// host of the endpoint.
+    pub domain_name: Option<String>,
+}
+
+/// PEM encoded client certificate and private key.
+type ClientIdentity<'a> = (&'a [u8], &'a [u8]);
+
+impl TlsConfig {
+    /// The client certificate and key, an error if only one of them is set.
+    #[cfg_attr(
+        not(any(feature = "tls", feature = "grpc-sys", feature = "reqwest-rustls")),
+        allow(dead_code)
+    )]
+    pub(crate) fn identity(&self) -> Result<Option<ClientIdentity<'_>>, crate::Error> {
+        match (&self.client_cert, &self.client_key) {
+            (Some(cert), Some(key)) => Ok(Some((cert, key))),
+            (None, None) => Ok(None),
+            _ => Err(crate::Error::InvalidTlsConfig(
+                "client certificate and key must be set together",
+            )),
+        }
+    }
+}
+
+#[cfg(all(feature = "tonic", feature = "tls"))]
+impl std::convert::TryFrom<TlsConfig> for tonic::transport::ClientTlsConfig {
+    type Error = crate::Error;
+
+    fn try_from(config: TlsConfig) -> Result<Self, Self::Error> {
+        use tonic::transport::{Certificate, Identity};
+
+        let mut tls = tonic::transport::ClientTlsConfig::new();
+        if let Some((cert, key)) = config.identity()? {
+            tls = tls.identity(Identity::from_pem(cert, key));
+        }
+        if let Some(ca_cert) = config.ca_cert {
+            tls = tls.ca_certificate(Certificate::from_pem(ca_cert));
+        }
+        if let Some(domain_name) = config.domain_name {
+            tls = tls.domain_name(domain_name);
+        }
+        Ok(tls)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identity_requires_cert_and_key() {
+        let mut config = TlsConfig::default();
+        assert!(matches!(config.identity(), Ok(None)));
+
+        config.client_cert = Some(b"cert".to_vec());
+        assert!(matches!(
+            config.identity(),
+            Err(crate::Error::InvalidTlsConfig(_))
+        ));
+
+        config.client_key = Some(b"key".to_vec());
+        assert!(matches!(config.identity(), Ok(Some((b"cert", b"key")))));
+    }
+}
@@ -1,18 +1,18 @@
-#[cfg(feature = "tonic")]
+#[cfg(feature = "prost")]
 use crate::proto::common::v1::{any_value, AnyValue, ArrayValue, InstrumentationLibrary, KeyValue};
 
-#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
-use crate::proto::grpcio::common::{AnyValue, ArrayValue, KeyValue};
+#[cfg(all(feature = "grpc-sys", not(feature = "prost")))]
+use crate::proto::grpcio::common::{AnyValue, ArrayValue, InstrumentationLibrary, KeyValue};
 
 use opentelemetry::sdk::trace::EvictedHashMap;
 use opentelemetry::{Array, Value};
 
-#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+#[cfg(all(feature = "grpc-sys", not(feature = "prost")))]
 use protobuf::RepeatedField;
 
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-#[cfg(feature = "tonic")]
+#[cfg(feature = "prost")]
 impl From<opentelemetry::sdk::InstrumentationLibrary> for InstrumentationLibrary {
     fn from(library: opentelemetry::sdk::InstrumentationLibrary) -> Self {
         InstrumentationLibrary {
@@ -22,16 +22,27 @@ impl From<opentelemetry::sdk::InstrumentationLibrary> for InstrumentationLibrary
     }
 }
 
-#[cfg(feature = "tonic")]
+#[cfg(all(feature = "grpc-sys", not(feature = "prost")))]
+impl From<opentelemetry::sdk::InstrumentationLibrary> for InstrumentationLibrary {
+    fn from(library: opentelemetry::sdk::InstrumentationLibrary) -> Self {
+        InstrumentationLibrary {
+            name: library.name.to_string(),
+            version: library.version.unwrap_or("").to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(feature = "prost")]
 pub(crate) struct Attributes(pub(crate) ::std::vec::Vec<crate::proto::common::v1::KeyValue>);
 
-#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+#[cfg(all(feature = "grpc-sys", not(feature = "prost")))]
 pub(crate) struct Attributes(
     pub(crate) ::protobuf::RepeatedField<crate::proto::grpcio::common::KeyValue>,
 );
 
 impl From<EvictedHashMap> for Attributes {
-    #[cfg(feature = "tonic")]
+    #[cfg(feature = "prost")]
     fn from(attributes: EvictedHashMap) -> Self {
         Attributes(
             attributes
@@ -44,7 +55,7 @@ impl From<EvictedHashMap> for Attributes {
         )
     }
 
-    #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+    #[cfg(all(feature = "grpc-sys", not(feature = "prost")))]
     fn from(attributes: EvictedHashMap) -> Self {
         Attributes(RepeatedField::from_vec(
             attributes
@@ -61,7 +72,7 @@ impl From<EvictedHashMap> for Attributes {
 }
 
 impl From<Vec<opentelemetry::KeyValue>> for Attributes {
-    #[cfg(feature = "tonic")]
+    #[cfg(feature = "prost")]
     fn from(kvs: Vec<opentelemetry::KeyValue>) -> Self {
         Attributes(
             kvs.into_iter()
@@ -73,7 +84,7 @@ impl From<Vec<opentelemetry::KeyValue>> for Attributes {
         )
     }
 
-    #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+    #[cfg(all(feature = "grpc-sys", not(feature = "prost")))]
     fn from(kvs: Vec<opentelemetry::KeyValue>) -> Self {
         Attributes(RepeatedField::from_vec(
             kvs.into_iter()
@@ -89,7 +100,7 @@ impl From<Vec<opentelemetry::KeyValue>> for Attributes {
 }
 
 impl From<Value> for AnyValue {
-    #[cfg(feature = "tonic")]
+    #[cfg(feature = "prost")]
     fn from(value: Value) -> Self {
         AnyValue {
             value: match value {
@@ -107,7 +118,7 @@ impl From<Value> for AnyValue {
         }
     }
 
-    #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+    #[cfg(all(feature = "grpc-sys", not(feature = "prost")))]
     fn from(value: Value) -> Self {
         let mut any_value = AnyValue::new();
         match value {
@@ -127,7 +138,7 @@ impl From<Value> for AnyValue {
     }
 }
 
-#[cfg(feature = "tonic")]
+#[cfg(feature = "prost")]
 fn array_into_proto<T>(vals: Vec<T>) -> ArrayValue
 where
     Value: From<T>,
@@ -140,7 +151,7 @@ where
     ArrayValue { values }
 }
 
-#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+#[cfg(all(feature = "grpc-sys", not(feature = "prost")))]
 fn array_into_proto<T>(vals: Vec<T>) -> ArrayValue
 where
     Value: From<T>,
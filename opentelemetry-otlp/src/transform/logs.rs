@@ -0,0 +1,73 @@
+use crate::proto::logs::v1::{InstrumentationLibraryLogs, LogRecord, ResourceLogs, SeverityNumber};
+use crate::proto::resource::v1::Resource;
+use crate::transform::common::{to_nanos, Attributes};
+use crate::transform::traces::resource_attributes;
+use opentelemetry::sdk::export::logs::LogData;
+use opentelemetry::Severity;
+
+impl From<Severity> for SeverityNumber {
+    fn from(severity: Severity) -> Self {
+        SeverityNumber::from_i32(severity as i32).unwrap_or(SeverityNumber::Unspecified)
+    }
+}
+
+impl From<LogData> for ResourceLogs {
+    fn from(log_data: LogData) -> Self {
+        let record = log_data.record;
+        let trace_context = record.trace_context;
+        ResourceLogs {
+            resource: Some(Resource {
+                attributes: resource_attributes(&log_data.resource).0,
+                dropped_attributes_count: 0,
+            }),
+            schema_url: log_data
+                .resource
+                .schema_url()
+                .unwrap_or_default()
+                .to_string(),
+            instrumentation_library_logs: vec![InstrumentationLibraryLogs {
+                schema_url: log_data
+                    .instrumentation_lib
+                    .schema_url
+                    .unwrap_or_default()
+                    .to_string(),
+                instrumentation_library: Some(log_data.instrumentation_lib.into()),
+                logs: vec![LogRecord {
+                    // the protocol has no observed timestamp yet, records
+                    // without one are stamped when they were observed
+                    time_unix_nano: record
+                        .timestamp
+                        .or(record.observed_timestamp)
+                        .map(to_nanos)
+                        .unwrap_or_default(),
+                    severity_number: record
+                        .severity_number
+                        .map(SeverityNumber::from)
+                        .unwrap_or(SeverityNumber::Unspecified)
+                        as i32,
+                    severity_text: record.severity_text.unwrap_or_default(),
+                    name: String::new(),
+                    body: record.body.map(Into::into),
+                    attributes: record
+                        .attributes
+                        .map(|attributes| Attributes::from(attributes).0)
+                        .unwrap_or_default(),
+                    dropped_attributes_count: 0,
+                    flags: trace_context
+                        .as_ref()
+                        .and_then(|cx| cx.trace_flags)
+                        .map(u32::from)
+                        .unwrap_or_default(),
+                    trace_id: trace_context
+                        .as_ref()
+                        .map(|cx| cx.trace_id.to_u128().to_be_bytes().to_vec())
+                        .unwrap_or_default(),
+                    span_id: trace_context
+                        .as_ref()
+                        .map(|cx| cx.span_id.to_u64().to_be_bytes().to_vec())
+                        .unwrap_or_default(),
+                }],
+            }],
+        }
+    }
+}
@@ -3,18 +3,20 @@ pub(crate) mod tonic {
     use crate::proto::{
         collector::metrics::v1::ExportMetricsServiceRequest,
         metrics::v1::{
-            metric::Data, AggregationTemporality, DoubleDataPoint, DoubleGauge, DoubleHistogram,
-            DoubleHistogramDataPoint, DoubleSum, InstrumentationLibraryMetrics, IntDataPoint,
-            IntGauge, IntHistogram, IntHistogramDataPoint, IntSum, Metric, ResourceMetrics,
+            metric::Data, AggregationTemporality, DoubleDataPoint, DoubleExemplar, DoubleGauge,
+            DoubleHistogram, DoubleHistogramDataPoint, DoubleSum, InstrumentationLibraryMetrics,
+            IntDataPoint, IntExemplar, IntGauge, IntHistogram, IntHistogramDataPoint, IntSum,
+            Metric, ResourceMetrics,
         },
     };
     use opentelemetry::metrics::{MetricsError, NumberKind};
     use opentelemetry::sdk::export::metrics::{
-        Count, ExportKind, ExportKindFor, Histogram, LastValue, Max, Min, Points, Record, Sum,
+        Count, Exemplar, Exemplars, ExponentialBuckets, ExponentialHistogram, ExportKind,
+        ExportKindFor, Histogram, LastValue, Max, Min, Points, Record, Sum,
     };
     use opentelemetry::sdk::metrics::aggregators::{
-        ArrayAggregator, HistogramAggregator, LastValueAggregator, MinMaxSumCountAggregator,
-        SumAggregator,
+        ArrayAggregator, ExponentialHistogramAggregator, HistogramAggregator, LastValueAggregator,
+        MinMaxSumCountAggregator, SumAggregator,
     };
 
     use crate::proto::common::v1::StringKeyValue;
@@ -42,6 +44,34 @@ pub(crate) mod tonic {
         }
     }
 
+    fn int_exemplar(exemplar: &Exemplar, kind: &NumberKind) -> IntExemplar {
+        IntExemplar {
+            filtered_labels: exemplar
+                .filtered_attributes()
+                .iter()
+                .map(|kv| (&kv.key, &kv.value).into())
+                .collect(),
+            time_unix_nano: to_nanos(exemplar.time()),
+            value: exemplar.value().to_i64(kind),
+            span_id: exemplar.span_id().to_vec(),
+            trace_id: exemplar.trace_id().to_vec(),
+        }
+    }
+
+    fn double_exemplar(exemplar: &Exemplar, kind: &NumberKind) -> DoubleExemplar {
+        DoubleExemplar {
+            filtered_labels: exemplar
+                .filtered_attributes()
+                .iter()
+                .map(|kv| (&kv.key, &kv.value).into())
+                .collect(),
+            time_unix_nano: to_nanos(exemplar.time()),
+            value: exemplar.value().to_f64(kind),
+            span_id: exemplar.span_id().to_vec(),
+            trace_id: exemplar.trace_id().to_vec(),
+        }
+    }
+
     pub(crate) fn record_to_metric(
         record: &Record,
         export_selector: &dyn ExportKindFor,
@@ -125,7 +155,7 @@ pub(crate) mod tonic {
                     })
                 } else if let Some(sum) = aggregator.as_any().downcast_ref::<SumAggregator>() {
                     Some({
-                        let val = sum.sum()?;
+                        let (val, exemplars) = (sum.sum()?, sum.exemplars()?);
                         match kind {
                             NumberKind::U64 | NumberKind::I64 => Data::IntSum(IntSum {
                                 data_points: vec![IntDataPoint {
@@ -133,7 +163,10 @@ pub(crate) mod tonic {
                                     start_time_unix_nano: to_nanos(*record.start_time()),
                                     time_unix_nano: to_nanos(*record.end_time()),
                                     value: val.to_i64(kind),
-                                    exemplars: Vec::default(),
+                                    exemplars: exemplars
+                                        .iter()
+                                        .map(|exemplar| int_exemplar(exemplar, kind))
+                                        .collect(),
                                 }],
                                 aggregation_temporality: temporality as i32,
                                 is_monotonic: descriptor.instrument_kind().monotonic(),
@@ -144,7 +177,10 @@ pub(crate) mod tonic {
                                     start_time_unix_nano: to_nanos(*record.start_time()),
                                     time_unix_nano: to_nanos(*record.end_time()),
                                     value: val.to_f64(kind),
-                                    exemplars: Vec::default(),
+                                    exemplars: exemplars
+                                        .iter()
+                                        .map(|exemplar| double_exemplar(exemplar, kind))
+                                        .collect(),
                                 }],
                                 aggregation_temporality: temporality as i32,
                                 is_monotonic: descriptor.instrument_kind().monotonic(),
@@ -157,6 +193,7 @@ pub(crate) mod tonic {
                     Some({
                         let (sum, count, buckets) =
                             (histogram.sum()?, histogram.count()?, histogram.histogram()?);
+                        let exemplars = histogram.exemplars()?;
                         match kind {
                             NumberKind::I64 | NumberKind::U64 => Data::IntHistogram(IntHistogram {
                                 data_points: vec![IntHistogramDataPoint {
@@ -172,7 +209,10 @@ pub(crate) mod tonic {
                                         .map(|c| c as u64)
                                         .collect(),
                                     explicit_bounds: buckets.boundaries().clone(),
-                                    exemplars: Vec::default(),
+                                    exemplars: exemplars
+                                        .iter()
+                                        .map(|exemplar| int_exemplar(exemplar, kind))
+                                        .collect(),
                                 }],
                                 aggregation_temporality: temporality as i32,
                             }),
@@ -190,6 +230,50 @@ pub(crate) mod tonic {
                                         .map(|c| c as u64)
                                         .collect(),
                                     explicit_bounds: buckets.boundaries().clone(),
+                                    exemplars: exemplars
+                                        .iter()
+                                        .map(|exemplar| double_exemplar(exemplar, kind))
+                                        .collect(),
+                                }],
+                                aggregation_temporality: temporality as i32,
+                            }),
+                        }
+                    })
+                } else if let Some(histogram) = aggregator
+                    .as_any()
+                    .downcast_ref::<ExponentialHistogramAggregator>()
+                {
+                    Some({
+                        let (sum, count) = (histogram.sum()?, histogram.count()?);
+                        let (explicit_bounds, bucket_counts) = explicit_buckets(
+                            histogram.scale()?,
+                            histogram.zero_count()?,
+                            &histogram.positive()?,
+                            &histogram.negative()?,
+                        );
+                        match kind {
+                            NumberKind::I64 | NumberKind::U64 => Data::IntHistogram(IntHistogram {
+                                data_points: vec![IntHistogramDataPoint {
+                                    labels,
+                                    start_time_unix_nano: to_nanos(*record.start_time()),
+                                    time_unix_nano: to_nanos(*record.end_time()),
+                                    count,
+                                    sum: sum.to_i64(kind),
+                                    bucket_counts,
+                                    explicit_bounds,
+                                    exemplars: Vec::default(),
+                                }],
+                                aggregation_temporality: temporality as i32,
+                            }),
+                            NumberKind::F64 => Data::DoubleHistogram(DoubleHistogram {
+                                data_points: vec![DoubleHistogramDataPoint {
+                                    labels,
+                                    start_time_unix_nano: to_nanos(*record.start_time()),
+                                    time_unix_nano: to_nanos(*record.end_time()),
+                                    count,
+                                    sum: sum.to_f64(kind),
+                                    bucket_counts,
+                                    explicit_bounds,
                                     exemplars: Vec::default(),
                                 }],
                                 aggregation_temporality: temporality as i32,
@@ -245,6 +329,42 @@ pub(crate) mod tonic {
         })
     }
 
+    /// The explicit bounds and bucket counts of an exponential histogram.
+    ///
+    /// The proto version in use has no exponential histogram data point, the
+    /// buckets are exported with their exact boundaries instead: the negative
+    /// buckets, a bucket around zero holding the zero count and the positive
+    /// buckets.
+    pub(crate) fn explicit_buckets(
+        scale: i8,
+        zero_count: u64,
+        positive: &ExponentialBuckets,
+        negative: &ExponentialBuckets,
+    ) -> (Vec<f64>, Vec<u64>) {
+        let boundary = |index: i32| ExponentialBuckets::lower_boundary(scale, index);
+        let mut bounds = Vec::new();
+        let mut counts = Vec::new();
+        if !negative.counts().is_empty() {
+            let end = negative.offset() + negative.counts().len() as i32;
+            bounds.push(-boundary(end));
+            counts.push(0);
+            for (index, count) in (negative.offset()..end).zip(negative.counts()).rev() {
+                bounds.push(-boundary(index));
+                counts.push(*count);
+            }
+        }
+        counts.push(zero_count);
+        if !positive.counts().is_empty() {
+            bounds.push(boundary(positive.offset()));
+            for (index, count) in (positive.offset()..).zip(positive.counts()) {
+                bounds.push(boundary(index + 1));
+                counts.push(*count);
+            }
+            counts.push(0);
+        }
+        (bounds, counts)
+    }
+
     // Group metrics with resources and instrumentation libraries with resources first,
     // then instrumentation libraries.
     #[allow(clippy::map_entry)] // caused by https://github.com/rust-lang/rust-clippy/issues/4674
@@ -293,11 +413,16 @@ pub(crate) mod tonic {
             resource_metrics: sink_map
                 .into_iter()
                 .map(|(resource, metric_map)| ResourceMetrics {
+                    schema_url: resource.schema_url().unwrap_or_default().to_string(),
                     resource: Some(resource.into()),
                     instrumentation_library_metrics: metric_map
                         .into_iter()
                         .map(
                             |(instrumentation_library, metrics)| InstrumentationLibraryMetrics {
+                                schema_url: instrumentation_library
+                                    .schema_url
+                                    .unwrap_or_default()
+                                    .to_string(),
                                 instrumentation_library: Some(instrumentation_library.into()),
                                 metrics: metrics
                                     .into_iter()
@@ -346,6 +471,8 @@ pub(crate) mod tonic {
         merge_same_type!(base, other,
             Data::IntGauge => Data::IntSum, Data::IntGauge;
             Data::DoubleGauge => Data::DoubleSum, Data::DoubleGauge;
+            Data::IntSum => Data::IntSum;
+            Data::DoubleSum => Data::DoubleSum;
             Data::DoubleHistogram => Data::DoubleHistogram;
             Data::IntHistogram => Data::IntHistogram;
             Data::DoubleSummary => Data::DoubleSummary
@@ -363,18 +490,23 @@ mod tests {
             IntHistogramDataPoint, IntSum, Metric, ResourceMetrics,
         };
         use crate::transform::common::Attributes;
+        use crate::transform::metrics::tonic::explicit_buckets;
         use crate::transform::{record_to_metric, sink, ResourceWrapper};
         use chrono::prelude::*;
         use opentelemetry::labels::LabelSet;
         use opentelemetry::metrics::{
             Descriptor, InstrumentKind, MetricsError, Number, NumberKind,
         };
-        use opentelemetry::sdk::export::metrics::{record, Aggregator, ExportKindSelector};
+        use opentelemetry::sdk::export::metrics::{
+            record, Aggregator, ExponentialBuckets, ExportKindSelector,
+        };
         use opentelemetry::sdk::metrics::aggregators::{
-            histogram, last_value, min_max_sum_count, SumAggregator,
+            histogram, last_value, min_max_sum_count, ExemplarReservoir, SumAggregator,
         };
+        use opentelemetry::sdk::trace::TracerProvider;
         use opentelemetry::sdk::{InstrumentationLibrary, Resource};
-        use opentelemetry::KeyValue;
+        use opentelemetry::trace::{Span, TraceContextExt, Tracer, TracerProvider as _};
+        use opentelemetry::{Context, KeyValue};
         use std::cmp::Ordering;
         use std::sync::Arc;
 
@@ -451,11 +583,13 @@ mod tests {
                         .into_iter()
                         .map(|(name, data_points)| get_metric_with_name(name, data_points))
                         .collect::<Vec<Metric>>(),
+                    schema_url: String::new(),
                 });
             }
             ResourceMetrics {
                 resource: Some(resource),
                 instrumentation_library_metrics,
+                schema_url: String::new(),
             }
         }
 
@@ -581,6 +715,47 @@ mod tests {
                 assert_eq!(expect, metric);
             }
 
+            // Non-monotonic sums
+            for kind in vec![
+                InstrumentKind::UpDownCounter,
+                InstrumentKind::UpDownSumObserver,
+            ] {
+                let descriptor =
+                    Descriptor::new("test".to_string(), "test", None, kind, NumberKind::I64);
+                let aggregator = SumAggregator::default();
+                aggregator.update(&Number::from(5_i64), &descriptor)?;
+                aggregator.update(&Number::from(-8_i64), &descriptor)?;
+                let wrapped_aggregator: Arc<dyn Aggregator + Send + Sync> = Arc::new(aggregator);
+                let record = record(
+                    &descriptor,
+                    &label_set,
+                    &resource,
+                    Some(&wrapped_aggregator),
+                    start_time.into(),
+                    end_time.into(),
+                );
+                let metric = record_to_metric(&record, &ExportKindSelector::Cumulative)?;
+
+                let expect = Metric {
+                    name: "test".to_string(),
+                    description: "".to_string(),
+                    unit: "".to_string(),
+                    data: Some(Data::IntSum(IntSum {
+                        data_points: vec![IntDataPoint {
+                            labels: str_kv_labels.clone(),
+                            start_time_unix_nano: 1608891000000000000,
+                            time_unix_nano: 1608891030000000000,
+                            value: -3,
+                            exemplars: vec![],
+                        }],
+                        aggregation_temporality: 2,
+                        is_monotonic: false,
+                    })),
+                };
+
+                assert_eq!(expect, metric);
+            }
+
             // Last Value
             {
                 let descriptor = Descriptor::new(
@@ -730,6 +905,99 @@ mod tests {
             Ok(())
         }
 
+        #[test]
+        fn test_sum_exemplars() -> Result<(), MetricsError> {
+            let provider = TracerProvider::builder().build();
+            let tracer = provider.get_tracer("test", None);
+            let span = tracer.start("request");
+            let span_context = span.span_context().clone();
+            let cx = Context::new().with_span(span);
+
+            let descriptor = Descriptor::new(
+                "requests".to_string(),
+                "test",
+                None,
+                InstrumentKind::Counter,
+                NumberKind::U64,
+            );
+            let aggregator =
+                SumAggregator::default().with_exemplar_reservoir(&ExemplarReservoir::FixedSize(1));
+            aggregator.update_with_context(
+                &Number::from(3u64),
+                &descriptor,
+                &cx,
+                &[KeyValue::new("user", "1")],
+            )?;
+            aggregator.update_with_context(
+                &Number::from(4u64),
+                &descriptor,
+                &Context::new(),
+                &[],
+            )?;
+            let wrapped_aggregator: Arc<dyn Aggregator + Send + Sync> = Arc::new(aggregator);
+            let resource = Resource::default();
+            let label_set = LabelSet::from_labels(vec![].into_iter());
+            let record = record(
+                &descriptor,
+                &label_set,
+                &resource,
+                Some(&wrapped_aggregator),
+                Utc::now().into(),
+                Utc::now().into(),
+            );
+            let metric = record_to_metric(&record, &ExportKindSelector::Cumulative)?;
+
+            let data_point = match metric.data {
+                Some(Data::IntSum(IntSum {
+                    mut data_points, ..
+                })) => data_points.remove(0),
+                data => panic!("unexpected data {:?}", data),
+            };
+            assert_eq!(data_point.value, 7);
+            assert_eq!(data_point.exemplars.len(), 1);
+            let exemplar = &data_point.exemplars[0];
+            assert_eq!(exemplar.value, 3);
+            assert_eq!(
+                exemplar.trace_id,
+                span_context.trace_id().to_byte_array().to_vec()
+            );
+            assert_eq!(
+                exemplar.span_id,
+                span_context.span_id().to_byte_array().to_vec()
+            );
+            assert_eq!(
+                exemplar.filtered_labels,
+                vec![StringKeyValue {
+                    key: "user".to_string(),
+                    value: "1".to_string(),
+                }]
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn test_exponential_histogram_buckets() {
+            // scale 0: buckets (1, 2], (2, 4], (4, 8]
+            let (bounds, counts) = explicit_buckets(
+                0,
+                1,
+                &ExponentialBuckets::new(0, vec![1, 0, 3]),
+                &ExponentialBuckets::new(1, vec![2]),
+            );
+            assert_eq!(bounds, vec![-4.0, -2.0, 1.0, 2.0, 4.0, 8.0]);
+            assert_eq!(counts, vec![0, 2, 1, 1, 0, 3, 0]);
+
+            let (bounds, counts) = explicit_buckets(
+                3,
+                4,
+                &ExponentialBuckets::default(),
+                &ExponentialBuckets::default(),
+            );
+            assert!(bounds.is_empty());
+            assert_eq!(counts, vec![4]);
+        }
+
         #[test]
         fn test_sink() {
             let test_data: Vec<(ResourceWrapper, InstrumentationLibrary, Metric)> = vec![
@@ -1,4 +1,6 @@
 mod common;
+#[cfg(all(feature = "tonic", feature = "logs"))]
+mod logs;
 #[cfg(feature = "metrics")]
 mod metrics;
 mod resource;
@@ -10,6 +12,11 @@ pub(crate) use metrics::tonic::record_to_metric;
 pub(crate) use metrics::tonic::sink;
 #[cfg(all(feature = "tonic", feature = "metrics"))]
 pub(crate) use resource::ResourceWrapper;
+#[cfg(feature = "http-proto")]
+pub(crate) use traces::map_chunks;
+pub(crate) use traces::resource_spans;
+#[cfg(all(test, feature = "http-proto"))]
+pub(crate) use traces::tests::batch as test_batch;
 
 #[cfg(all(feature = "tonic", feature = "metrics"))]
 use opentelemetry::sdk::InstrumentationLibrary;
@@ -22,3 +29,19 @@ pub(crate) type CheckpointedMetrics = (
     InstrumentationLibrary,
     crate::proto::metrics::v1::Metric,
 );
+
+/// Serialize trace and span ids as lowercase hex strings, as required by
+/// OTLP/JSON.
+#[cfg(feature = "http-json")]
+pub(crate) fn serialize_hex<T, S>(bytes: T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: AsRef<[u8]>,
+    S: serde::Serializer,
+{
+    let hex: String = bytes
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    serializer.serialize_str(&hex)
+}
@@ -1,4 +1,4 @@
-#[cfg(feature = "tonic")]
+#[cfg(feature = "prost")]
 use crate::proto::{common::v1::KeyValue, resource::v1::Resource};
 use std::cmp::Ordering;
 
@@ -11,6 +11,13 @@ impl From<opentelemetry::sdk::Resource> for ResourceWrapper {
     }
 }
 
+impl ResourceWrapper {
+    #[cfg(all(feature = "tonic", feature = "metrics"))]
+    pub(crate) fn schema_url(&self) -> Option<&str> {
+        self.0.schema_url()
+    }
+}
+
 impl Eq for ResourceWrapper {}
 
 impl Ord for ResourceWrapper {
@@ -25,7 +32,7 @@ impl PartialOrd for ResourceWrapper {
     }
 }
 
-#[cfg(feature = "tonic")]
+#[cfg(feature = "prost")]
 impl From<ResourceWrapper> for Resource {
     fn from(resource: ResourceWrapper) -> Self {
         Resource {
@@ -1,31 +1,32 @@
-#[cfg(feature = "tonic")]
+#[cfg(feature = "prost")]
 use crate::proto::resource::v1::Resource;
 
-#[cfg(feature = "tonic")]
+#[cfg(feature = "prost")]
 use crate::proto::trace::v1::{
     span, status, InstrumentationLibrarySpans, ResourceSpans, Span, Status,
 };
 
-#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+#[cfg(all(feature = "grpc-sys", not(feature = "prost")))]
 use crate::proto::grpcio::resource::Resource;
 
-#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+#[cfg(all(feature = "grpc-sys", not(feature = "prost")))]
 use crate::proto::grpcio::trace::{
     InstrumentationLibrarySpans, ResourceSpans, Span, Span_Event, Span_Link, Span_SpanKind, Status,
-    Status_StatusCode,
+    Status_DeprecatedStatusCode, Status_StatusCode,
 };
 
 use crate::transform::common::{to_nanos, Attributes};
 use opentelemetry::sdk::{self, export::trace::SpanData};
 use opentelemetry::trace::{Link, SpanKind, StatusCode};
+use std::{panic, thread};
 
-#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+#[cfg(all(feature = "grpc-sys", not(feature = "prost")))]
 use protobuf::reflect::ProtobufValue;
 
-#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+#[cfg(all(feature = "grpc-sys", not(feature = "prost")))]
 use protobuf::{RepeatedField, SingularPtrField};
 
-#[cfg(feature = "tonic")]
+#[cfg(feature = "prost")]
 impl From<SpanKind> for span::SpanKind {
     fn from(span_kind: SpanKind) -> Self {
         match span_kind {
@@ -38,7 +39,7 @@ impl From<SpanKind> for span::SpanKind {
     }
 }
 
-#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+#[cfg(all(feature = "grpc-sys", not(feature = "prost")))]
 impl From<SpanKind> for Span_SpanKind {
     fn from(span_kind: SpanKind) -> Self {
         match span_kind {
@@ -51,7 +52,7 @@ impl From<SpanKind> for Span_SpanKind {
     }
 }
 
-#[cfg(feature = "tonic")]
+#[cfg(feature = "prost")]
 impl From<StatusCode> for status::StatusCode {
     fn from(status_code: StatusCode) -> Self {
         match status_code {
@@ -62,7 +63,7 @@ impl From<StatusCode> for status::StatusCode {
     }
 }
 
-#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+#[cfg(all(feature = "grpc-sys", not(feature = "prost")))]
 impl From<StatusCode> for Status_StatusCode {
     fn from(status_code: StatusCode) -> Self {
         match status_code {
@@ -73,7 +74,46 @@ impl From<StatusCode> for Status_StatusCode {
     }
 }
 
-#[cfg(feature = "tonic")]
+/// The OTLP status of a span. The description is only kept for errors, and the
+/// deprecated code is set for receivers of earlier protocol versions.
+#[cfg(feature = "prost")]
+#[allow(deprecated)]
+fn to_status(status_code: StatusCode, message: String) -> Status {
+    let (deprecated_code, message) = match status_code {
+        StatusCode::Error => (status::DeprecatedStatusCode::UnknownError, message),
+        _ => (status::DeprecatedStatusCode::Ok, String::new()),
+    };
+    Status {
+        deprecated_code: deprecated_code.into(),
+        message,
+        code: status::StatusCode::from(status_code).into(),
+    }
+}
+
+/// The OTLP status of a span. The description is only kept for errors, and the
+/// deprecated code is set for receivers of earlier protocol versions.
+#[cfg(all(feature = "grpc-sys", not(feature = "prost")))]
+#[allow(deprecated)]
+fn to_status(status_code: StatusCode, message: String) -> Status {
+    let (deprecated_code, message) = match status_code {
+        StatusCode::Error => (
+            Status_DeprecatedStatusCode::DEPRECATED_STATUS_CODE_UNKNOWN_ERROR,
+            message,
+        ),
+        _ => (
+            Status_DeprecatedStatusCode::DEPRECATED_STATUS_CODE_OK,
+            String::new(),
+        ),
+    };
+    Status {
+        deprecated_code,
+        message,
+        code: Status_StatusCode::from(status_code),
+        ..Default::default()
+    }
+}
+
+#[cfg(feature = "prost")]
 impl From<Link> for span::Link {
     fn from(link: Link) -> Self {
         span::Link {
@@ -96,7 +136,7 @@ impl From<Link> for span::Link {
     }
 }
 
-#[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+#[cfg(all(feature = "grpc-sys", not(feature = "prost")))]
 impl From<Link> for Span_Link {
     fn from(link: Link) -> Self {
         Span_Link {
@@ -120,7 +160,7 @@ impl From<Link> for Span_Link {
     }
 }
 
-fn resource_attributes(resource: &sdk::Resource) -> Attributes {
+pub(crate) fn resource_attributes(resource: &sdk::Resource) -> Attributes {
     resource
         .iter()
         .map(|(k, v)| opentelemetry::KeyValue::new(k.clone(), v.clone()))
@@ -129,7 +169,7 @@ fn resource_attributes(resource: &sdk::Resource) -> Attributes {
 }
 
 impl From<SpanData> for ResourceSpans {
-    #[cfg(feature = "tonic")]
+    #[cfg(feature = "prost")]
     fn from(source_span: SpanData) -> Self {
         let span_kind: span::SpanKind = source_span.span_kind.into();
         ResourceSpans {
@@ -137,8 +177,18 @@ impl From<SpanData> for ResourceSpans {
                 attributes: resource_attributes(&source_span.resource).0,
                 dropped_attributes_count: 0,
             }),
+            schema_url: source_span
+                .resource
+                .schema_url()
+                .unwrap_or_default()
+                .to_string(),
             instrumentation_library_spans: vec![InstrumentationLibrarySpans {
-                instrumentation_library: Default::default(),
+                schema_url: source_span
+                    .instrumentation_lib
+                    .schema_url
+                    .unwrap_or_default()
+                    .to_string(),
+                instrumentation_library: Some(source_span.instrumentation_lib.into()),
                 spans: vec![Span {
                     trace_id: source_span
                         .span_context
@@ -160,7 +210,7 @@ impl From<SpanData> for ResourceSpans {
                             vec![]
                         }
                     },
-                    name: source_span.name,
+                    name: source_span.name.into_owned(),
                     kind: span_kind as i32,
                     start_time_unix_nano: to_nanos(source_span.start_time),
                     end_time_unix_nano: to_nanos(source_span.end_time),
@@ -170,26 +220,28 @@ impl From<SpanData> for ResourceSpans {
                     events: source_span
                         .message_events
                         .into_iter()
-                        .map(|event| span::Event {
-                            time_unix_nano: to_nanos(event.timestamp),
-                            name: event.name.into(),
-                            attributes: Attributes::from(event.attributes).0,
-                            dropped_attributes_count: 0,
+                        .map(|event| {
+                            let attributes = event.attributes_with_severity();
+                            span::Event {
+                                time_unix_nano: to_nanos(event.timestamp),
+                                name: event.name.into(),
+                                attributes: Attributes::from(attributes).0,
+                                dropped_attributes_count: 0,
+                            }
                         })
                         .collect(),
                     dropped_links_count: source_span.links.dropped_count(),
                     links: source_span.links.into_iter().map(Into::into).collect(),
-                    status: Some(Status {
-                        code: status::StatusCode::from(source_span.status_code).into(),
-                        message: source_span.status_message,
-                        ..Default::default()
-                    }),
+                    status: Some(to_status(
+                        source_span.status_code,
+                        source_span.status_message,
+                    )),
                 }],
             }],
         }
     }
 
-    #[cfg(all(feature = "grpc-sys", not(feature = "tonic")))]
+    #[cfg(all(feature = "grpc-sys", not(feature = "prost")))]
     fn from(source_span: SpanData) -> Self {
         ResourceSpans {
             resource: SingularPtrField::from(Some(Resource {
@@ -197,9 +249,21 @@ impl From<SpanData> for ResourceSpans {
                 dropped_attributes_count: 0,
                 ..Default::default()
             })),
+            schema_url: source_span
+                .resource
+                .schema_url()
+                .unwrap_or_default()
+                .to_string(),
             instrumentation_library_spans: RepeatedField::from_vec(vec![
                 InstrumentationLibrarySpans {
-                    instrumentation_library: Default::default(),
+                    schema_url: source_span
+                        .instrumentation_lib
+                        .schema_url
+                        .unwrap_or_default()
+                        .to_string(),
+                    instrumentation_library: SingularPtrField::some(
+                        source_span.instrumentation_lib.into(),
+                    ),
                     spans: RepeatedField::from_vec(vec![Span {
                         trace_id: source_span
                             .span_context
@@ -221,7 +285,7 @@ impl From<SpanData> for ResourceSpans {
                                 vec![]
                             }
                         },
-                        name: source_span.name,
+                        name: source_span.name.into_owned(),
                         kind: source_span.span_kind.into(),
                         start_time_unix_nano: to_nanos(source_span.start_time),
                         end_time_unix_nano: to_nanos(source_span.end_time),
@@ -232,12 +296,15 @@ impl From<SpanData> for ResourceSpans {
                             source_span
                                 .message_events
                                 .into_iter()
-                                .map(|event| Span_Event {
-                                    time_unix_nano: to_nanos(event.timestamp),
-                                    name: event.name.into(),
-                                    attributes: Attributes::from(event.attributes).0,
-                                    dropped_attributes_count: 0,
-                                    ..Default::default()
+                                .map(|event| {
+                                    let attributes = event.attributes_with_severity();
+                                    Span_Event {
+                                        time_unix_nano: to_nanos(event.timestamp),
+                                        name: event.name.into(),
+                                        attributes: Attributes::from(attributes).0,
+                                        dropped_attributes_count: 0,
+                                        ..Default::default()
+                                    }
                                 })
                                 .collect(),
                         ),
@@ -245,11 +312,10 @@ impl From<SpanData> for ResourceSpans {
                         links: RepeatedField::from_vec(
                             source_span.links.into_iter().map(Into::into).collect(),
                         ),
-                        status: SingularPtrField::some(Status {
-                            code: Status_StatusCode::from(source_span.status_code),
-                            message: source_span.status_message,
-                            ..Default::default()
-                        }),
+                        status: SingularPtrField::some(to_status(
+                            source_span.status_code,
+                            source_span.status_message,
+                        )),
                         ..Default::default()
                     }]),
                     ..Default::default()
@@ -259,3 +325,174 @@ impl From<SpanData> for ResourceSpans {
         }
     }
 }
+
+/// The smallest batch split across threads, smaller batches convert faster
+/// than the threads start.
+pub(crate) const PARALLEL_ENCODING_MIN_SPANS: usize = 512;
+
+/// Apply `f` to consecutive chunks of the batch on up to `parallelism`
+/// threads and return the results in the order of the chunks.
+///
+/// The first chunk is handled on the calling thread. Batches smaller than
+/// [`PARALLEL_ENCODING_MIN_SPANS`] are passed to `f` whole without spawning
+/// any threads.
+pub(crate) fn map_chunks<T, F>(mut batch: Vec<SpanData>, parallelism: usize, f: F) -> Vec<T>
+where
+    T: Send + 'static,
+    F: Fn(Vec<SpanData>) -> T + Clone + Send + 'static,
+{
+    // wasm32 targets have no threads to encode on
+    if parallelism <= 1 || batch.len() < PARALLEL_ENCODING_MIN_SPANS || cfg!(target_arch = "wasm32")
+    {
+        return vec![f(batch)];
+    }
+
+    let chunk_size = (batch.len() - 1) / parallelism + 1;
+    // split off the back so the spans are only moved once
+    let mut chunks = Vec::with_capacity(parallelism - 1);
+    while batch.len() > chunk_size {
+        let at = (batch.len() - 1) / chunk_size * chunk_size;
+        chunks.push(batch.split_off(at));
+    }
+    let workers: Vec<_> = chunks
+        .into_iter()
+        .rev()
+        .map(|chunk| {
+            let f = f.clone();
+            thread::Builder::new()
+                .name("opentelemetry-otlp-encoder".to_string())
+                .spawn(move || f(chunk))
+                .expect("failed to spawn encoding thread")
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(workers.len() + 1);
+    results.push(f(batch));
+    for worker in workers {
+        results.push(
+            worker
+                .join()
+                .unwrap_or_else(|err| panic::resume_unwind(err)),
+        );
+    }
+    results
+}
+
+/// Convert a batch to resource spans on up to `parallelism` threads, see
+/// [`map_chunks`].
+pub(crate) fn resource_spans(batch: Vec<SpanData>, parallelism: usize) -> Vec<ResourceSpans> {
+    map_chunks(batch, parallelism, |chunk| {
+        chunk
+            .into_iter()
+            .map(ResourceSpans::from)
+            .collect::<Vec<_>>()
+    })
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+#[cfg(all(test, feature = "prost"))]
+pub(crate) mod tests {
+    use super::*;
+    use opentelemetry::sdk::trace::{EvictedHashMap, EvictedQueue};
+    use opentelemetry::sdk::InstrumentationLibrary;
+    use opentelemetry::trace::{SpanContext, SpanId, TraceId, TraceState};
+    use std::sync::Arc;
+    use std::time::SystemTime;
+
+    /// A batch of `len` spans named after their span ids.
+    pub(crate) fn batch(len: usize) -> Vec<SpanData> {
+        (1..=len as u64)
+            .map(|id| SpanData {
+                span_context: SpanContext::new(
+                    TraceId::from_u128(1),
+                    SpanId::from_u64(id),
+                    0,
+                    false,
+                    TraceState::default(),
+                ),
+                parent_span_id: SpanId::invalid(),
+                span_kind: SpanKind::Internal,
+                name: format!("span-{}", id).into(),
+                start_time: SystemTime::UNIX_EPOCH,
+                end_time: SystemTime::UNIX_EPOCH,
+                attributes: EvictedHashMap::new(1, 0),
+                message_events: EvictedQueue::new(1),
+                links: EvictedQueue::new(1),
+                status_code: StatusCode::Unset,
+                status_message: String::new(),
+                resource: Arc::new(sdk::Resource::default()),
+                instrumentation_lib: InstrumentationLibrary::new("test", None),
+            })
+            .collect()
+    }
+
+    fn span_names(resource_spans: Vec<ResourceSpans>) -> Vec<String> {
+        resource_spans
+            .into_iter()
+            .flat_map(|resource_spans| resource_spans.instrumentation_library_spans)
+            .flat_map(|library_spans| library_spans.spans)
+            .map(|span| span.name)
+            .collect()
+    }
+
+    #[test]
+    fn map_chunks_splits_large_batches() {
+        let lens = |len, parallelism| map_chunks(batch(len), parallelism, |chunk| chunk.len());
+        assert_eq!(lens(PARALLEL_ENCODING_MIN_SPANS - 1, 4), vec![511]);
+        assert_eq!(lens(1000, 1), vec![1000]);
+        assert_eq!(lens(1000, 4), vec![250, 250, 250, 250]);
+        assert_eq!(lens(1001, 3), vec![334, 334, 333]);
+        assert_eq!(
+            lens(600, 16),
+            vec![38; 15].into_iter().chain(Some(30)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn exports_schema_urls() {
+        let mut span = batch(1).remove(0);
+        span.resource = Arc::new(sdk::Resource::from_schema_url(
+            vec![opentelemetry::KeyValue::new("service.name", "test")],
+            "https://opentelemetry.io/schemas/1.4.0",
+        ));
+        span.instrumentation_lib = InstrumentationLibrary::new("test", None)
+            .with_schema_url("https://opentelemetry.io/schemas/1.2.0");
+
+        let resource_spans = ResourceSpans::from(span);
+        assert_eq!(
+            resource_spans.schema_url,
+            "https://opentelemetry.io/schemas/1.4.0"
+        );
+        assert_eq!(
+            resource_spans.instrumentation_library_spans[0].schema_url,
+            "https://opentelemetry.io/schemas/1.2.0"
+        );
+        assert_eq!(ResourceSpans::from(batch(1).remove(0)).schema_url, "");
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn status_description_only_for_errors() {
+        let ok = to_status(StatusCode::Ok, "fine".to_string());
+        assert_eq!(ok.code, status::StatusCode::Ok as i32);
+        assert_eq!(ok.deprecated_code, status::DeprecatedStatusCode::Ok as i32);
+        assert_eq!(ok.message, "");
+
+        let error = to_status(StatusCode::Error, "failed".to_string());
+        assert_eq!(error.code, status::StatusCode::Error as i32);
+        assert_eq!(
+            error.deprecated_code,
+            status::DeprecatedStatusCode::UnknownError as i32
+        );
+        assert_eq!(error.message, "failed");
+    }
+
+    #[test]
+    fn parallel_conversion_keeps_span_order() {
+        let expected = span_names(resource_spans(batch(1000), 1));
+        assert_eq!(expected.len(), 1000);
+        assert_eq!(span_names(resource_spans(batch(1000), 3)), expected);
+    }
+}
@@ -1,74 +1,21 @@
-use futures::StreamExt;
 use opentelemetry::global::shutdown_tracer_provider;
-use opentelemetry::trace::{Span, SpanKind, Tracer};
-use opentelemetry_otlp::proto::collector::trace::v1::{
-    trace_service_server::{TraceService, TraceServiceServer},
-    ExportTraceServiceRequest, ExportTraceServiceResponse,
-};
-use std::{net::SocketAddr, sync::Mutex};
-use tokio::sync::mpsc;
-use tokio_stream::wrappers::TcpListenerStream;
+use opentelemetry::sdk::{self, trace::TracerProvider};
+use opentelemetry::trace::{Span, SpanKind, Tracer, TracerProvider as _};
+use opentelemetry_otlp::testing::{FakeCollector, FakeResponse};
+use opentelemetry_otlp::{ExportStats, ExporterConfig, TraceExporter};
+use std::time::Duration;
 
-struct MockServer {
-    tx: Mutex<mpsc::Sender<ExportTraceServiceRequest>>,
-}
-
-impl MockServer {
-    pub fn new(tx: mpsc::Sender<ExportTraceServiceRequest>) -> Self {
-        Self { tx: Mutex::new(tx) }
-    }
-}
-
-#[tonic::async_trait]
-impl TraceService for MockServer {
-    async fn export(
-        &self,
-        request: tonic::Request<ExportTraceServiceRequest>,
-    ) -> Result<tonic::Response<ExportTraceServiceResponse>, tonic::Status> {
-        println!("Sending request into channel...");
-        self.tx
-            .lock()
-            .unwrap()
-            .try_send(request.into_inner())
-            .expect("Channel full");
-        Ok(tonic::Response::new(ExportTraceServiceResponse {}))
-    }
-}
-
-async fn setup() -> (SocketAddr, mpsc::Receiver<ExportTraceServiceRequest>) {
-    let addr: SocketAddr = "[::1]:0".parse().unwrap();
-    let listener = tokio::net::TcpListener::bind(addr)
-        .await
-        .expect("failed to bind");
-    let addr = listener.local_addr().unwrap();
-    let stream = TcpListenerStream::new(listener).map(|s| {
-        if let Ok(ref s) = s {
-            println!("Got new conn at {}", s.peer_addr().unwrap());
-        }
-        s
-    });
-
-    let (req_tx, req_rx) = mpsc::channel(10);
-    let service = TraceServiceServer::new(MockServer::new(req_tx));
-    tokio::task::spawn(async move {
-        tonic::transport::Server::builder()
-            .add_service(service)
-            .serve_with_incoming(stream)
-            .await
-            .expect("Server failed");
-    });
-    (addr, req_rx)
-}
+const TIMEOUT: Duration = Duration::from_secs(10);
 
-#[tokio::test(flavor = "multi_thread")]
-async fn smoke_tracer() {
+#[test]
+fn smoke_tracer() {
     println!("Starting server setup...");
-    let (addr, mut req_rx) = setup().await;
+    let collector = FakeCollector::start().expect("failed to start collector");
 
     {
         println!("Installing tracer...");
         let tracer = opentelemetry_otlp::new_pipeline()
-            .with_endpoint(format!("http://{}", addr))
+            .with_endpoint(collector.endpoint())
             .install()
             .expect("failed to install");
 
@@ -84,18 +31,69 @@ async fn smoke_tracer() {
     }
 
     println!("Waiting for request...");
-    let req = req_rx.recv().await.expect("missing export request");
-    let first_span = req
-        .resource_spans
-        .get(0)
-        .unwrap()
-        .instrumentation_library_spans
-        .get(0)
-        .unwrap()
-        .spans
-        .get(0)
-        .unwrap();
+    let spans = collector.wait_for_spans(1, TIMEOUT);
+    let first_span = spans.first().unwrap();
     assert_eq!("my-test-span", first_span.name);
-    let first_event = first_span.events.get(0).unwrap();
+    let first_event = first_span.events.first().unwrap();
     assert_eq!("my-test-event", first_event.name);
 }
+
+/// A tracer exporting each span when it ends, the provider must be kept
+/// alive while it is used.
+fn exporting_tracer(config: ExporterConfig) -> (TracerProvider, sdk::trace::Tracer) {
+    let exporter = TraceExporter::new(config).expect("failed to build exporter");
+    let provider = TracerProvider::builder().with_exporter(exporter).build();
+    let tracer = provider.get_tracer("smoke", None);
+    (provider, tracer)
+}
+
+#[test]
+fn collector_responses() {
+    let collector = FakeCollector::start().expect("failed to start collector");
+    let stats = ExportStats::default();
+    let (_provider, tracer) = exporting_tracer(ExporterConfig {
+        endpoint: collector.endpoint(),
+        retry: None,
+        stats: stats.clone(),
+        ..ExporterConfig::default()
+    });
+
+    collector.respond_with(FakeResponse::PartialSuccess {
+        rejected_spans: 1,
+        error_message: "span name is empty".to_string(),
+    });
+    collector.respond_with(FakeResponse::Error(tonic::Status::invalid_argument(
+        "malformed request",
+    )));
+    tracer.in_span("partial", |_cx| {});
+    tracer.in_span("failed", |_cx| {});
+    tracer.in_span("accepted", |_cx| {});
+
+    let names: Vec<_> = collector
+        .wait_for_spans(3, TIMEOUT)
+        .into_iter()
+        .map(|span| span.name)
+        .collect();
+    assert_eq!(names, vec!["partial", "failed", "accepted"]);
+    assert_eq!(stats.rejected_spans(), 1);
+    assert_eq!(stats.rejected_exports(), 1);
+    assert!(stats.last_error().is_some());
+}
+
+#[test]
+fn slow_collector() {
+    let collector = FakeCollector::start().expect("failed to start collector");
+    collector.set_delay(Duration::from_millis(200));
+    let stats = ExportStats::default();
+    let (_provider, tracer) = exporting_tracer(ExporterConfig {
+        endpoint: collector.endpoint(),
+        timeout: Duration::from_millis(50),
+        retry: None,
+        stats: stats.clone(),
+        ..ExporterConfig::default()
+    });
+    tracer.in_span("slow", |_cx| {});
+
+    assert_eq!(collector.wait_for_requests(1, TIMEOUT).len(), 1);
+    assert!(stats.last_error().is_some());
+}
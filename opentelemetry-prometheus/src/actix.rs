@@ -0,0 +1,52 @@
+//! # actix-web Handler
+//!
+//! An [actix-web] handler responding with the metrics of the exporter, which
+//! is passed as the app data of the service.
+//!
+//! ```no_run
+//! # async fn run() -> std::io::Result<()> {
+//! use actix_web::{App, HttpServer};
+//!
+//! let exporter = opentelemetry_prometheus::exporter().init();
+//!
+//! HttpServer::new(move || App::new().configure(opentelemetry_prometheus::actix::configure(exporter.clone())))
+//!     .bind(("0.0.0.0", 9464))?
+//!     .run()
+//!     .await
+//! # }
+//! ```
+//!
+//! [actix-web]: https://docs.rs/actix-web/4
+use crate::{PrometheusExporter, METRICS_PATH};
+use actix_web::{http::header::ACCEPT, web, HttpRequest, HttpResponse};
+use opentelemetry::global;
+
+/// Respond with the metrics of the exporter, in the format asked for by the
+/// `Accept` header of the request.
+pub async fn metrics(exporter: web::Data<PrometheusExporter>, req: HttpRequest) -> HttpResponse {
+    let accept = req
+        .headers()
+        .get(ACCEPT)
+        .and_then(|value| value.to_str().ok());
+    match exporter.scrape(accept) {
+        Ok(scrape) => HttpResponse::Ok()
+            .content_type(scrape.content_type())
+            .body(scrape.into_body()),
+        Err(err) => {
+            global::handle_error(err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Configure a service to serve the metrics of `exporter` on
+/// [`METRICS_PATH`], see [`App::configure`].
+///
+/// [`App::configure`]: actix_web::App::configure
+pub fn configure(exporter: PrometheusExporter) -> impl FnOnce(&mut web::ServiceConfig) {
+    move |config| {
+        config
+            .app_data(web::Data::new(exporter))
+            .route(METRICS_PATH, web::get().to(metrics));
+    }
+}
@@ -0,0 +1,59 @@
+//! # axum Handler
+//!
+//! A [axum] handler responding with the metrics of the exporter, which is
+//! passed as the state of the router.
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), hyper::Error> {
+//! use axum::{routing::get, Router};
+//! use opentelemetry_prometheus::{axum::metrics, METRICS_PATH};
+//!
+//! let exporter = opentelemetry_prometheus::exporter().init();
+//! let app = Router::new()
+//!     .route(METRICS_PATH, get(metrics))
+//!     .with_state(exporter);
+//!
+//! axum::Server::bind(&([0, 0, 0, 0], 9464).into())
+//!     .serve(app.into_make_service())
+//!     .await
+//! # }
+//! ```
+//!
+//! [axum]: https://docs.rs/axum/0.6
+use crate::{PrometheusExporter, METRICS_PATH};
+use axum::{
+    extract::State,
+    http::{
+        header::{ACCEPT, CONTENT_TYPE},
+        HeaderMap, StatusCode,
+    },
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use opentelemetry::global;
+
+/// Respond with the metrics of the exporter, in the format asked for by the
+/// `Accept` header of the request.
+pub async fn metrics(State(exporter): State<PrometheusExporter>, headers: HeaderMap) -> Response {
+    let accept = headers.get(ACCEPT).and_then(|value| value.to_str().ok());
+    match exporter.scrape(accept) {
+        Ok(scrape) => (
+            [(CONTENT_TYPE, scrape.content_type().to_string())],
+            scrape.into_body(),
+        )
+            .into_response(),
+        Err(err) => {
+            global::handle_error(err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// A router serving the metrics of `exporter` on [`METRICS_PATH`], to be
+/// merged into the router of the service.
+pub fn router<S>(exporter: PrometheusExporter) -> Router<S> {
+    Router::new()
+        .route(METRICS_PATH, get(metrics))
+        .with_state(exporter)
+}
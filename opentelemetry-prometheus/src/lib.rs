@@ -39,16 +39,26 @@
 //! //
 //! // # HELP a_counter Counts things
 //! // # TYPE a_counter counter
-//! // a_counter{R="V",key="value"} 100
+//! // a_counter{key="value",otel_scope_name="my-app"} 100
 //! // # HELP a_value_recorder Records values
 //! // # TYPE a_value_recorder histogram
-//! // a_value_recorder_bucket{R="V",key="value",le="0.5"} 0
-//! // a_value_recorder_bucket{R="V",key="value",le="0.9"} 0
-//! // a_value_recorder_bucket{R="V",key="value",le="0.99"} 0
-//! // a_value_recorder_bucket{R="V",key="value",le="+Inf"} 1
-//! // a_value_recorder_sum{R="V",key="value"} 100
-//! // a_value_recorder_count{R="V",key="value"} 1
+//! // a_value_recorder_bucket{key="value",otel_scope_name="my-app",le="0.5"} 0
+//! // a_value_recorder_bucket{key="value",otel_scope_name="my-app",le="0.9"} 0
+//! // a_value_recorder_bucket{key="value",otel_scope_name="my-app",le="0.99"} 0
+//! // a_value_recorder_bucket{key="value",otel_scope_name="my-app",le="+Inf"} 1
+//! // a_value_recorder_sum{key="value",otel_scope_name="my-app"} 100
+//! // a_value_recorder_count{key="value",otel_scope_name="my-app"} 1
+//! // # HELP otel_scope_info Instrumentation Scope metadata
+//! // # TYPE otel_scope_info gauge
+//! // otel_scope_info{otel_scope_name="my-app"} 1
+//! // # HELP target_info Target metadata
+//! // # TYPE target_info gauge
+//! // target_info{R="V"} 1
 //! ```
+//!
+//! The resource is exported once as the labels of the `target_info` metric,
+//! see [`ExporterBuilder::without_target_info`] to add its attributes to the
+//! labels of every series instead.
 #![warn(
     future_incompatible,
     missing_debug_implementations,
@@ -66,32 +76,61 @@
 
 use opentelemetry::global;
 use opentelemetry::sdk::{
-    export::metrics::{CheckpointSet, ExportKindSelector, Histogram, LastValue, Record, Sum},
+    export::metrics::{
+        CheckpointSet, Exemplars, ExportKindSelector, Histogram, LastValue, Record, Sum,
+    },
     metrics::{
         aggregators::{HistogramAggregator, LastValueAggregator, SumAggregator},
         controllers,
         selectors::simple::Selector,
-        PullController,
+        PullController, View,
     },
     Resource,
 };
 use opentelemetry::{
     labels,
-    metrics::{registry::RegistryMeterProvider, MetricsError, NumberKind},
+    metrics::{registry::RegistryMeterProvider, InstrumentKind, MetricsError, NumberKind},
     Key, Value,
 };
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
+#[cfg(feature = "actix-handler")]
+#[cfg_attr(docsrs, doc(cfg(feature = "actix-handler")))]
+pub mod actix;
+#[cfg(feature = "axum-handler")]
+#[cfg_attr(docsrs, doc(cfg(feature = "axum-handler")))]
+pub mod axum;
+mod open_metrics;
 mod sanitize;
+#[cfg(feature = "hyper-server")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hyper-server")))]
+pub mod server;
+#[cfg(feature = "warp-filter")]
+#[cfg_attr(docsrs, doc(cfg(feature = "warp-filter")))]
+pub mod warp;
+
+use open_metrics::{series_key, ExemplarStore, SeriesKey, ValuedExemplar};
+pub use open_metrics::{OpenMetricsEncoder, OPEN_METRICS_FORMAT};
+use prometheus::{Encoder, TextEncoder};
+use sanitize::{metric_name, sanitize};
 
-use sanitize::sanitize;
+/// The path the HTTP handlers serve the metrics on.
+pub const METRICS_PATH: &str = "/metrics";
 
 /// Cache disabled by default.
 const DEFAULT_CACHE_PERIOD: Duration = Duration::from_secs(0);
 
 const EXPORT_KIND_SELECTOR: ExportKindSelector = ExportKindSelector::Cumulative;
 
+const TARGET_INFO_NAME: &str = "target_info";
+const TARGET_INFO_HELP: &str = "Target metadata";
+const SCOPE_INFO_NAME: &str = "otel_scope_info";
+const SCOPE_INFO_HELP: &str = "Instrumentation Scope metadata";
+const SCOPE_NAME_LABEL: &str = "otel_scope_name";
+const SCOPE_VERSION_LABEL: &str = "otel_scope_version";
+
 /// Create a new prometheus exporter builder.
 pub fn exporter() -> ExporterBuilder {
     ExporterBuilder::default()
@@ -122,6 +161,16 @@ pub struct ExporterBuilder {
     ///
     /// If not set a new empty `Registry` is created.
     registry: Option<prometheus::Registry>,
+
+    /// Views customizing the metrics exported for the instruments they select.
+    views: Vec<View>,
+
+    /// Whether the resource attributes are added to the labels of every series
+    /// instead of being exported as a `target_info` metric.
+    disable_target_info: bool,
+
+    /// Whether the instrumentation scope is left out of the exported series.
+    disable_scope_info: bool,
 }
 
 impl ExporterBuilder {
@@ -168,6 +217,32 @@ impl ExporterBuilder {
         }
     }
 
+    /// Register a view customizing the metrics exported for the instruments it
+    /// selects, e.g. to drop high cardinality labels.
+    pub fn with_view(mut self, view: View) -> Self {
+        self.views.push(view);
+        self
+    }
+
+    /// Add the resource attributes to the labels of every series, instead of
+    /// exporting them once as the labels of the `target_info` metric.
+    pub fn without_target_info(self) -> Self {
+        ExporterBuilder {
+            disable_target_info: true,
+            ..self
+        }
+    }
+
+    /// Leave out the `otel_scope_name` and `otel_scope_version` labels of
+    /// every series and the `otel_scope_info` metric, which identify the
+    /// instrumentation scope of the meter recording them.
+    pub fn without_scope_info(self) -> Self {
+        ExporterBuilder {
+            disable_scope_info: true,
+            ..self
+        }
+    }
+
     /// Sets up a complete export pipeline with the recommended setup, using the
     /// recommended selector and standard processor.
     pub fn try_init(self) -> Result<PrometheusExporter, MetricsError> {
@@ -185,15 +260,22 @@ impl ExporterBuilder {
         if let Some(resource) = self.resource {
             controller_builder = controller_builder.with_resource(resource);
         }
+        for view in self.views {
+            controller_builder = controller_builder.with_view(view);
+        }
         let controller = controller_builder.build();
 
         global::set_meter_provider(controller.provider());
 
-        PrometheusExporter::new(
+        PrometheusExporter::with_config(
             registry,
             controller,
             default_summary_quantiles,
             default_histogram_boundaries,
+            CollectorConfig {
+                target_info: !self.disable_target_info,
+                scope_info: !self.disable_scope_info,
+            },
         )
     }
 
@@ -216,6 +298,7 @@ impl ExporterBuilder {
 pub struct PrometheusExporter {
     registry: prometheus::Registry,
     controller: Arc<Mutex<PullController>>,
+    exemplars: Arc<ExemplarStore>,
     default_summary_quantiles: Vec<f64>,
     default_histogram_boundaries: Vec<f64>,
 }
@@ -227,9 +310,26 @@ impl PrometheusExporter {
         controller: PullController,
         default_summary_quantiles: Vec<f64>,
         default_histogram_boundaries: Vec<f64>,
+    ) -> Result<Self, MetricsError> {
+        PrometheusExporter::with_config(
+            registry,
+            controller,
+            default_summary_quantiles,
+            default_histogram_boundaries,
+            CollectorConfig::default(),
+        )
+    }
+
+    fn with_config(
+        registry: prometheus::Registry,
+        controller: PullController,
+        default_summary_quantiles: Vec<f64>,
+        default_histogram_boundaries: Vec<f64>,
+        config: CollectorConfig,
     ) -> Result<Self, MetricsError> {
         let controller = Arc::new(Mutex::new(controller));
-        let collector = Collector::with_controller(controller.clone());
+        let exemplars = Arc::new(ExemplarStore::default());
+        let collector = Collector::with_controller(controller.clone(), exemplars.clone(), config);
         registry
             .register(Box::new(collector))
             .map_err(|e| MetricsError::Other(e.to_string()))?;
@@ -237,6 +337,7 @@ impl PrometheusExporter {
         Ok(PrometheusExporter {
             registry,
             controller,
+            exemplars,
             default_summary_quantiles,
             default_histogram_boundaries,
         })
@@ -247,6 +348,51 @@ impl PrometheusExporter {
         &self.registry
     }
 
+    /// An encoder of the OpenMetrics text format, which includes the
+    /// exemplars of the metrics last gathered from the registry.
+    ///
+    /// Exemplars are only kept for instruments selected by a view with an
+    /// exemplar reservoir, see [`View::with_exemplar_reservoir`].
+    ///
+    /// [`View::with_exemplar_reservoir`]: opentelemetry::sdk::metrics::View::with_exemplar_reservoir
+    pub fn open_metrics_encoder(&self) -> OpenMetricsEncoder {
+        OpenMetricsEncoder::new(self.exemplars.clone())
+    }
+
+    /// Gather and encode the metrics of the registry for a scrape, in the
+    /// OpenMetrics text format if the `Accept` header of the scrape request
+    /// asks for it and in the Prometheus text format otherwise.
+    ///
+    /// The [`Scrape`] is what the `/metrics` handler of a service responds
+    /// with, whatever HTTP framework it uses. Ready-made handlers are in the
+    /// [`server`] (hyper), [`axum`], [`warp`] and [`actix`] modules, behind
+    /// the `hyper-server`, `axum-handler`, `warp-filter` and `actix-handler`
+    /// features.
+    ///
+    /// [`server`]: crate::server
+    /// [`axum`]: crate::axum
+    /// [`warp`]: crate::warp
+    /// [`actix`]: crate::actix
+    pub fn scrape(&self, accept: Option<&str>) -> Result<Scrape, MetricsError> {
+        let metric_families = self.registry.gather();
+        let mut body = Vec::new();
+        let content_type = if accepts_open_metrics(accept) {
+            let encoder = self.open_metrics_encoder();
+            encoder
+                .encode(&metric_families, &mut body)
+                .map_err(|err| MetricsError::Other(err.to_string()))?;
+            encoder.format_type().to_string()
+        } else {
+            let encoder = TextEncoder::new();
+            encoder
+                .encode(&metric_families, &mut body)
+                .map_err(|err| MetricsError::Other(err.to_string()))?;
+            encoder.format_type().to_string()
+        };
+
+        Ok(Scrape { content_type, body })
+    }
+
     /// Get this exporter's provider.
     pub fn provider(&self) -> Result<RegistryMeterProvider, MetricsError> {
         self.controller
@@ -256,14 +402,77 @@ impl PrometheusExporter {
     }
 }
 
+/// The encoded metrics of a scrape, see [`PrometheusExporter::scrape`].
+#[derive(Clone, Debug)]
+pub struct Scrape {
+    content_type: String,
+    body: Vec<u8>,
+}
+
+impl Scrape {
+    /// The value of the `Content-Type` header of the scrape response.
+    pub fn content_type(&self) -> &str {
+        &self.content_type
+    }
+
+    /// The encoded metrics.
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Consume the scrape, returning the encoded metrics.
+    pub fn into_body(self) -> Vec<u8> {
+        self.body
+    }
+}
+
+/// Whether the `Accept` header of a scrape request prefers the OpenMetrics
+/// text format, as sent by Prometheus with exemplar storage enabled.
+fn accepts_open_metrics(accept: Option<&str>) -> bool {
+    match accept.and_then(|accept| accept.split(',').next()) {
+        Some(preferred) => preferred.trim().starts_with("application/openmetrics-text"),
+        None => false,
+    }
+}
+
+/// What the collector exports besides the series of the instruments.
+#[derive(Clone, Copy, Debug)]
+struct CollectorConfig {
+    /// Export the resource as the `target_info` metric instead of adding its
+    /// attributes to the labels of every series.
+    target_info: bool,
+    /// Label every series with its instrumentation scope and export the
+    /// `otel_scope_info` metric.
+    scope_info: bool,
+}
+
+impl Default for CollectorConfig {
+    fn default() -> Self {
+        CollectorConfig {
+            target_info: true,
+            scope_info: true,
+        }
+    }
+}
+
 #[derive(Debug)]
 struct Collector {
     controller: Arc<Mutex<PullController>>,
+    exemplars: Arc<ExemplarStore>,
+    config: CollectorConfig,
 }
 
 impl Collector {
-    fn with_controller(controller: Arc<Mutex<PullController>>) -> Self {
-        Collector { controller }
+    fn with_controller(
+        controller: Arc<Mutex<PullController>>,
+        exemplars: Arc<ExemplarStore>,
+        config: CollectorConfig,
+    ) -> Self {
+        Collector {
+            controller,
+            exemplars,
+            config,
+        }
     }
 }
 
@@ -277,6 +486,9 @@ impl prometheus::core::Collector for Collector {
     fn collect(&self) -> Vec<prometheus::proto::MetricFamily> {
         if let Ok(mut controller) = self.controller.lock() {
             let mut metrics = Vec::new();
+            let mut exemplars = HashMap::new();
+            let mut target_info = None;
+            let mut scopes = BTreeSet::new();
 
             if let Err(err) = controller.collect() {
                 global::handle_error(err);
@@ -289,11 +501,28 @@ impl prometheus::core::Collector for Collector {
                 let instrument_kind = record.descriptor().instrument_kind();
 
                 let desc = get_metric_desc(&record);
-                let labels = get_metric_labels(record);
+                let mut labels = if self.config.target_info {
+                    if target_info.is_none() && !record.resource().is_empty() {
+                        target_info = Some(label_pairs(record.resource().iter()));
+                    }
+                    label_pairs(record.labels().iter())
+                } else {
+                    get_metric_labels(record)
+                };
+                if self.config.scope_info {
+                    let library = record.descriptor().instrumentation_library();
+                    let scope = scope_labels(library.name, library.version);
+                    labels.extend(scope.iter().cloned());
+                    scopes.insert((library.name, library.version));
+                }
 
                 if let Some(hist) = agg.as_any().downcast_ref::<HistogramAggregator>() {
+                    keep_exemplars(&mut exemplars, hist, number_kind, &desc, &labels)?;
                     metrics.push(build_histogram(hist, number_kind, desc, labels)?);
                 } else if let Some(sum) = agg.as_any().downcast_ref::<SumAggregator>() {
+                    if instrument_kind.monotonic() {
+                        keep_exemplars(&mut exemplars, sum, number_kind, &desc, &labels)?;
+                    }
                     let counter = if instrument_kind.monotonic() {
                         build_monotonic_counter(sum, number_kind, desc, labels)?
                     } else {
@@ -309,6 +538,18 @@ impl prometheus::core::Collector for Collector {
             }) {
                 global::handle_error(err);
             }
+            self.exemplars.replace(exemplars);
+
+            if let Some(labels) = target_info {
+                metrics.push(build_info(TARGET_INFO_NAME, TARGET_INFO_HELP, vec![labels]));
+            }
+            if !scopes.is_empty() {
+                let scopes = scopes
+                    .into_iter()
+                    .map(|(name, version)| scope_labels(name, version))
+                    .collect();
+                metrics.push(build_info(SCOPE_INFO_NAME, SCOPE_INFO_HELP, scopes));
+            }
 
             metrics
         } else {
@@ -317,6 +558,26 @@ impl prometheus::core::Collector for Collector {
     }
 }
 
+fn keep_exemplars(
+    exemplars: &mut HashMap<SeriesKey, Vec<ValuedExemplar>>,
+    aggregator: &dyn Exemplars,
+    kind: &NumberKind,
+    desc: &PrometheusMetricDesc,
+    labels: &[prometheus::proto::LabelPair],
+) -> Result<(), MetricsError> {
+    let kept = aggregator.exemplars()?;
+    if !kept.is_empty() {
+        exemplars.insert(
+            series_key(&desc.name, labels),
+            kept.into_iter()
+                .map(|exemplar| (exemplar.value().to_f64(kind), exemplar))
+                .collect(),
+        );
+    }
+
+    Ok(())
+}
+
 fn build_last_value(
     lv: &LastValueAggregator,
     kind: &NumberKind,
@@ -428,10 +689,53 @@ fn build_histogram(
     Ok(mf)
 }
 
-fn build_label_pair(key: &Key, value: &Value) -> prometheus::proto::LabelPair {
+/// A gauge with a value of `1` for each of the label sets, the way Prometheus
+/// exports metadata to join series with.
+fn build_info(
+    name: &str,
+    help: &str,
+    label_sets: Vec<Vec<prometheus::proto::LabelPair>>,
+) -> prometheus::proto::MetricFamily {
+    let metrics = label_sets
+        .into_iter()
+        .map(|labels| {
+            let mut g = prometheus::proto::Gauge::default();
+            g.set_value(1.0);
+
+            let mut m = prometheus::proto::Metric::default();
+            m.set_label(protobuf::RepeatedField::from_vec(labels));
+            m.set_gauge(g);
+            m
+        })
+        .collect();
+
+    let mut mf = prometheus::proto::MetricFamily::default();
+    mf.set_name(name.to_string());
+    mf.set_help(help.to_string());
+    mf.set_field_type(prometheus::proto::MetricType::GAUGE);
+    mf.set_metric(protobuf::RepeatedField::from_vec(metrics));
+
+    mf
+}
+
+fn scope_labels(name: &str, version: Option<&str>) -> Vec<prometheus::proto::LabelPair> {
+    let mut labels = vec![build_label_pair(
+        SCOPE_NAME_LABEL.to_string(),
+        name.to_string(),
+    )];
+    if let Some(version) = version {
+        labels.push(build_label_pair(
+            SCOPE_VERSION_LABEL.to_string(),
+            version.to_string(),
+        ));
+    }
+    labels
+}
+
+fn build_label_pair(name: String, value: String) -> prometheus::proto::LabelPair {
     let mut lp = prometheus::proto::LabelPair::new();
-    lp.set_name(sanitize(key.as_str()));
-    lp.set_value(value.to_string());
+    lp.set_name(name);
+    lp.set_value(value);
 
     lp
 }
@@ -439,8 +743,36 @@ fn build_label_pair(key: &Key, value: &Value) -> prometheus::proto::LabelPair {
 fn get_metric_labels(record: &Record<'_>) -> Vec<prometheus::proto::LabelPair> {
     // Duplicate keys are resolved by taking the record label value over
     // the resource value.
-    let iter = labels::merge_iters(record.labels().iter(), record.resource().iter());
-    iter.map(|(key, value)| build_label_pair(key, value))
+    label_pairs(labels::merge_iters(
+        record.labels().iter(),
+        record.resource().iter(),
+    ))
+}
+
+fn label_pairs<'a, I>(iter: I) -> Vec<prometheus::proto::LabelPair>
+where
+    I: Iterator<Item = (&'a Key, &'a Value)>,
+{
+    // Keys which are distinct before sanitization but not after are merged,
+    // joining their values in the order of the original keys.
+    let mut sanitized: BTreeMap<String, Vec<(&Key, &Value)>> = BTreeMap::new();
+    for (key, value) in iter {
+        sanitized
+            .entry(sanitize(key.as_str()))
+            .or_default()
+            .push((key, value));
+    }
+    sanitized
+        .into_iter()
+        .map(|(name, mut values)| {
+            values.sort_by(|a, b| a.0.cmp(b.0));
+            let value = values
+                .iter()
+                .map(|(_, value)| value.to_string())
+                .collect::<Vec<_>>()
+                .join(";");
+            build_label_pair(name, value)
+        })
         .collect()
 }
 
@@ -451,7 +783,11 @@ struct PrometheusMetricDesc {
 
 fn get_metric_desc(record: &Record<'_>) -> PrometheusMetricDesc {
     let desc = record.descriptor();
-    let name = sanitize(desc.name());
+    let gauge = match desc.instrument_kind() {
+        InstrumentKind::ValueRecorder => false,
+        kind => !kind.monotonic(),
+    };
+    let name = metric_name(desc.name(), desc.unit(), gauge);
     let help = desc
         .description()
         .cloned()
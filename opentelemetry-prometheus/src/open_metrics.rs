@@ -0,0 +1,333 @@
+//! # OpenMetrics Text Encoding
+//!
+//! The Prometheus text format has no room for exemplars, the
+//! [`OpenMetricsEncoder`] encodes the gathered metric families in the
+//! [OpenMetrics] text format instead, with the exemplars the exporter kept for
+//! its counters and histograms.
+//!
+//! [OpenMetrics]: https://github.com/OpenObservability/OpenMetrics/blob/main/specification/OpenMetrics.md
+use opentelemetry::sdk::export::metrics::Exemplar;
+use prometheus::proto::{LabelPair, Metric, MetricFamily, MetricType};
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::UNIX_EPOCH;
+
+/// The content type of the OpenMetrics text format.
+pub const OPEN_METRICS_FORMAT: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// The maximum length of the label names and values of an exemplar.
+const MAX_EXEMPLAR_LABELS_LENGTH: usize = 128;
+
+/// A series by metric name and labels.
+pub(crate) type SeriesKey = (String, Vec<(String, String)>);
+
+/// An exemplar with its value, as the number kind of its instrument is not
+/// known anymore when encoding.
+pub(crate) type ValuedExemplar = (f64, Exemplar);
+
+/// The exemplars of the series collected last, by metric name and labels.
+#[derive(Debug, Default)]
+pub(crate) struct ExemplarStore(Mutex<HashMap<SeriesKey, Vec<ValuedExemplar>>>);
+
+impl ExemplarStore {
+    /// Replace the kept exemplars with the ones of the latest collection.
+    pub(crate) fn replace(&self, exemplars: HashMap<SeriesKey, Vec<ValuedExemplar>>) {
+        if let Ok(mut kept) = self.0.lock() {
+            *kept = exemplars;
+        }
+    }
+
+    fn get(&self, name: &str, labels: &[LabelPair]) -> Vec<ValuedExemplar> {
+        self.0
+            .lock()
+            .ok()
+            .and_then(|kept| kept.get(&series_key(name, labels)).cloned())
+            .unwrap_or_default()
+    }
+}
+
+pub(crate) fn series_key(name: &str, labels: &[LabelPair]) -> SeriesKey {
+    (
+        name.to_string(),
+        labels
+            .iter()
+            .map(|lp| (lp.get_name().to_string(), lp.get_value().to_string()))
+            .collect(),
+    )
+}
+
+/// An encoder of the [OpenMetrics] text format, including exemplars.
+///
+/// Created with [`PrometheusExporter::open_metrics_encoder`], the exemplars
+/// are the ones of the metric families gathered last from the registry of the
+/// exporter.
+///
+/// [OpenMetrics]: https://github.com/OpenObservability/OpenMetrics/blob/main/specification/OpenMetrics.md
+/// [`PrometheusExporter::open_metrics_encoder`]: crate::PrometheusExporter::open_metrics_encoder
+#[derive(Clone, Debug)]
+pub struct OpenMetricsEncoder {
+    exemplars: Arc<ExemplarStore>,
+}
+
+impl OpenMetricsEncoder {
+    pub(crate) fn new(exemplars: Arc<ExemplarStore>) -> Self {
+        OpenMetricsEncoder { exemplars }
+    }
+}
+
+impl prometheus::Encoder for OpenMetricsEncoder {
+    fn encode<W: Write>(
+        &self,
+        metric_families: &[MetricFamily],
+        writer: &mut W,
+    ) -> prometheus::Result<()> {
+        for mf in metric_families {
+            let metric_type = mf.get_field_type();
+            // counter samples carry the `_total` suffix, the family does not
+            let name = match metric_type {
+                MetricType::COUNTER => mf
+                    .get_name()
+                    .strip_suffix("_total")
+                    .unwrap_or_else(|| mf.get_name()),
+                _ => mf.get_name(),
+            };
+            let type_name = match metric_type {
+                MetricType::COUNTER => "counter",
+                MetricType::GAUGE => "gauge",
+                MetricType::HISTOGRAM => "histogram",
+                MetricType::SUMMARY => "summary",
+                MetricType::UNTYPED => "unknown",
+            };
+            writeln!(writer, "# TYPE {} {}", name, type_name)?;
+            if !mf.get_help().is_empty() {
+                writeln!(writer, "# HELP {} {}", name, escape(mf.get_help(), true))?;
+            }
+
+            for m in mf.get_metric() {
+                let exemplars = self.exemplars.get(mf.get_name(), m.get_label());
+                match metric_type {
+                    MetricType::COUNTER => {
+                        let sample = Sample::new(name, "_total", m);
+                        let exemplar = exemplars.iter().max_by_key(|(_, exemplar)| exemplar.time());
+                        sample.write(writer, None, m.get_counter().get_value(), exemplar)?;
+                    }
+                    MetricType::GAUGE => {
+                        Sample::new(name, "", m).write(
+                            writer,
+                            None,
+                            m.get_gauge().get_value(),
+                            None,
+                        )?;
+                    }
+                    MetricType::HISTOGRAM => {
+                        let h = m.get_histogram();
+                        let bucket = Sample::new(name, "_bucket", m);
+                        let mut lower_bound = f64::NEG_INFINITY;
+                        let mut inf_seen = false;
+                        for b in h.get_bucket() {
+                            let upper_bound = b.get_upper_bound();
+                            bucket.write(
+                                writer,
+                                Some(("le", &format_float(upper_bound))),
+                                b.get_cumulative_count() as f64,
+                                bucket_exemplar(&exemplars, lower_bound, upper_bound),
+                            )?;
+                            lower_bound = upper_bound;
+                            inf_seen = upper_bound == f64::INFINITY;
+                        }
+                        if !inf_seen {
+                            bucket.write(
+                                writer,
+                                Some(("le", "+Inf")),
+                                h.get_sample_count() as f64,
+                                bucket_exemplar(&exemplars, lower_bound, f64::INFINITY),
+                            )?;
+                        }
+                        Sample::new(name, "_sum", m).write(
+                            writer,
+                            None,
+                            h.get_sample_sum(),
+                            None,
+                        )?;
+                        Sample::new(name, "_count", m).write(
+                            writer,
+                            None,
+                            h.get_sample_count() as f64,
+                            None,
+                        )?;
+                    }
+                    MetricType::SUMMARY => {
+                        let s = m.get_summary();
+                        let quantile = Sample::new(name, "", m);
+                        for q in s.get_quantile() {
+                            quantile.write(
+                                writer,
+                                Some(("quantile", &format_float(q.get_quantile()))),
+                                q.get_value(),
+                                None,
+                            )?;
+                        }
+                        Sample::new(name, "_sum", m).write(
+                            writer,
+                            None,
+                            s.get_sample_sum(),
+                            None,
+                        )?;
+                        Sample::new(name, "_count", m).write(
+                            writer,
+                            None,
+                            s.get_sample_count() as f64,
+                            None,
+                        )?;
+                    }
+                    MetricType::UNTYPED => {
+                        Sample::new(name, "", m).write(
+                            writer,
+                            None,
+                            m.get_untyped().get_value(),
+                            None,
+                        )?;
+                    }
+                }
+            }
+        }
+        writer.write_all(b"# EOF\n")?;
+
+        Ok(())
+    }
+
+    fn format_type(&self) -> &str {
+        OPEN_METRICS_FORMAT
+    }
+}
+
+/// The latest exemplar with a value in `[lower_bound, upper_bound)`, the
+/// bucket of the histogram aggregator counting the value.
+fn bucket_exemplar(
+    exemplars: &[ValuedExemplar],
+    lower_bound: f64,
+    upper_bound: f64,
+) -> Option<&ValuedExemplar> {
+    exemplars
+        .iter()
+        .filter(|(value, _)| *value >= lower_bound && *value < upper_bound)
+        .max_by_key(|(_, exemplar)| exemplar.time())
+}
+
+struct Sample<'a> {
+    name: &'a str,
+    suffix: &'a str,
+    metric: &'a Metric,
+}
+
+impl<'a> Sample<'a> {
+    fn new(name: &'a str, suffix: &'a str, metric: &'a Metric) -> Self {
+        Sample {
+            name,
+            suffix,
+            metric,
+        }
+    }
+
+    fn write<W: Write>(
+        &self,
+        writer: &mut W,
+        additional_label: Option<(&str, &str)>,
+        value: f64,
+        exemplar: Option<&ValuedExemplar>,
+    ) -> prometheus::Result<()> {
+        write!(writer, "{}{}", self.name, self.suffix)?;
+        let labels = self
+            .metric
+            .get_label()
+            .iter()
+            .map(|lp| (lp.get_name(), lp.get_value()))
+            .chain(additional_label);
+        write_labels(writer, labels)?;
+        write!(writer, " {}", format_float(value))?;
+        if let Some(exemplar) = exemplar {
+            write_exemplar(writer, exemplar)?;
+        }
+        writer.write_all(b"\n")?;
+
+        Ok(())
+    }
+}
+
+fn write_exemplar<W: Write>(
+    writer: &mut W,
+    (value, exemplar): &ValuedExemplar,
+) -> prometheus::Result<()> {
+    let (trace_id, span_id) = (hex(&exemplar.trace_id()), hex(&exemplar.span_id()));
+    let mut labels = vec![
+        ("trace_id", trace_id.as_str()),
+        ("span_id", span_id.as_str()),
+    ];
+    let mut length = "trace_idspan_id".len() + trace_id.len() + span_id.len();
+    let filtered: Vec<(String, String)> = exemplar
+        .filtered_attributes()
+        .iter()
+        .map(|kv| (crate::sanitize(kv.key.as_str()), kv.value.to_string()))
+        .collect();
+    for (key, value) in &filtered {
+        length += key.chars().count() + value.chars().count();
+        if length > MAX_EXEMPLAR_LABELS_LENGTH {
+            break;
+        }
+        labels.push((key, value));
+    }
+
+    writer.write_all(b" # ")?;
+    write_labels(writer, labels.into_iter())?;
+    write!(writer, " {}", format_float(*value))?;
+    if let Ok(since_epoch) = exemplar.time().duration_since(UNIX_EPOCH) {
+        write!(writer, " {}", since_epoch.as_secs_f64())?;
+    }
+
+    Ok(())
+}
+
+fn write_labels<'a, W, I>(writer: &mut W, labels: I) -> prometheus::Result<()>
+where
+    W: Write,
+    I: Iterator<Item = (&'a str, &'a str)>,
+{
+    let mut separator = "{";
+    for (name, value) in labels {
+        write!(writer, "{}{}=\"{}\"", separator, name, escape(value, true))?;
+        separator = ",";
+    }
+    if separator == "," {
+        writer.write_all(b"}")?;
+    }
+
+    Ok(())
+}
+
+fn escape(value: &str, include_double_quote: bool) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '"' if include_double_quote => escaped.push_str("\\\""),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn format_float(value: f64) -> String {
+    if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -18,6 +18,101 @@ pub(crate) fn sanitize<T: AsRef<str>>(raw: T) -> String {
     prefix.chars().chain(escaped).take(100).collect()
 }
 
+/// metric_name returns the sanitized name of a metric with the suffix of its
+/// unit appended, e.g. `_seconds` for durations in `s`, unless the name ends
+/// with it already.
+///
+/// The unit `1` of ratios is only appended to gauges, annotations like
+/// `{requests}` are dropped.
+pub(crate) fn metric_name(name: &str, unit: Option<&str>, gauge: bool) -> String {
+    let mut name = sanitize(name);
+    if let Some(suffix) = unit.and_then(|unit| unit_suffix(unit, gauge)) {
+        if !name.ends_with(&format!("_{}", suffix)) {
+            name.push('_');
+            name.push_str(&suffix);
+        }
+    }
+    name
+}
+
+fn unit_suffix(unit: &str, gauge: bool) -> Option<String> {
+    let unit = unit.trim();
+    if unit.is_empty() || unit.starts_with('{') {
+        return None;
+    }
+    if unit == "1" {
+        return if gauge {
+            Some("ratio".to_string())
+        } else {
+            None
+        };
+    }
+
+    let mut parts = unit.splitn(2, '/');
+    let main = parts.next().unwrap_or_default();
+    let per = parts.next();
+    let main = main_unit(main)
+        .map(str::to_string)
+        .unwrap_or_else(|| sanitize(main));
+    match per {
+        Some(per) => {
+            let per = per_unit(per)
+                .map(str::to_string)
+                .unwrap_or_else(|| sanitize(per));
+            Some(format!("{}_per_{}", main, per))
+        }
+        None => Some(main),
+    }
+}
+
+fn main_unit(unit: &str) -> Option<&'static str> {
+    Some(match unit {
+        // time
+        "d" => "days",
+        "h" => "hours",
+        "min" => "minutes",
+        "s" => "seconds",
+        "ms" => "milliseconds",
+        "us" => "microseconds",
+        "ns" => "nanoseconds",
+        // bytes
+        "By" => "bytes",
+        "KiBy" => "kibibytes",
+        "MiBy" => "mebibytes",
+        "GiBy" => "gibibytes",
+        "TiBy" => "tibibytes",
+        "KBy" => "kilobytes",
+        "MBy" => "megabytes",
+        "GBy" => "gigabytes",
+        "TBy" => "terabytes",
+        // SI
+        "m" => "meters",
+        "V" => "volts",
+        "A" => "amperes",
+        "J" => "joules",
+        "W" => "watts",
+        "g" => "grams",
+        // misc
+        "Cel" => "celsius",
+        "Hz" => "hertz",
+        "%" => "percent",
+        _ => return None,
+    })
+}
+
+fn per_unit(unit: &str) -> Option<&'static str> {
+    Some(match unit {
+        "s" => "second",
+        "m" => "minute",
+        "h" => "hour",
+        "d" => "day",
+        "w" => "week",
+        "mo" => "month",
+        "y" => "year",
+        _ => return None,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -64,4 +159,31 @@ mod tests {
             assert_eq!(sanitize(raw), sanitized, "{} doesn't match", name)
         }
     }
+
+    #[test]
+    fn append_unit_suffixes() {
+        for (name, unit, gauge, expected) in vec![
+            (
+                "http.server.duration",
+                Some("s"),
+                false,
+                "http_server_duration_seconds",
+            ),
+            ("request.size", Some("By"), false, "request_size_bytes"),
+            (
+                "download.rate",
+                Some("MiBy/s"),
+                true,
+                "download_rate_mebibytes_per_second",
+            ),
+            ("heap.used_bytes", Some("By"), true, "heap_used_bytes"),
+            ("cpu.utilization", Some("1"), true, "cpu_utilization_ratio"),
+            ("requests", Some("1"), false, "requests"),
+            ("requests", Some("{requests}"), false, "requests"),
+            ("queue.length", Some("jobs"), true, "queue_length_jobs"),
+            ("requests", None, false, "requests"),
+        ] {
+            assert_eq!(metric_name(name, unit, gauge), expected);
+        }
+    }
 }
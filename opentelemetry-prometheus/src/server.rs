@@ -0,0 +1,97 @@
+//! # Metrics HTTP Server
+//!
+//! A `/metrics` endpoint served with [hyper], so services do not have to
+//! write the glue between the exporter and an HTTP server themselves.
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), hyper::Error> {
+//! let exporter = opentelemetry_prometheus::exporter().init();
+//!
+//! // serve the metrics on http://0.0.0.0:9464/metrics
+//! opentelemetry_prometheus::server::serve(exporter, ([0, 0, 0, 0], 9464).into()).await
+//! # }
+//! ```
+//!
+//! Services already running a hyper server can route requests to [`handle`]
+//! instead.
+//!
+//! ### Other HTTP Frameworks
+//!
+//! Services built with [axum], [warp] or [actix-web] can use the handlers of
+//! the [`axum`], [`warp`] and [`actix`] modules, behind the `axum-handler`,
+//! `warp-filter` and `actix-handler` features. Handlers of other frameworks
+//! respond with the [`Scrape`] of the exporter, passing on the `Accept` header
+//! of the request so that exemplars are sent to Prometheus scrapers asking for
+//! the OpenMetrics text format.
+//!
+//! [hyper]: https://docs.rs/hyper/0.14
+//! [`Scrape`]: crate::Scrape
+//! [axum]: https://docs.rs/axum
+//! [warp]: https://docs.rs/warp
+//! [actix-web]: https://docs.rs/actix-web
+//! [`axum`]: crate::axum
+//! [`warp`]: crate::warp
+//! [`actix`]: crate::actix
+use crate::{PrometheusExporter, METRICS_PATH};
+use hyper::{
+    header::{ACCEPT, ALLOW, CONTENT_TYPE},
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use opentelemetry::global;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+
+/// Serve the metrics of `exporter` on [`METRICS_PATH`] at `addr`, until the
+/// server fails.
+pub async fn serve(exporter: PrometheusExporter, addr: SocketAddr) -> Result<(), hyper::Error> {
+    let make_svc = make_service_fn(move |_conn| {
+        let exporter = exporter.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                let response = handle(&exporter, &req);
+                async move { Ok::<_, Infallible>(response) }
+            }))
+        }
+    });
+
+    Server::try_bind(&addr)?.serve(make_svc).await
+}
+
+/// Respond to a request of a hyper server, with the metrics of `exporter` for
+/// `GET` requests of [`METRICS_PATH`].
+///
+/// Other paths are not found, other methods are not allowed.
+pub fn handle<B>(exporter: &PrometheusExporter, req: &Request<B>) -> Response<Body> {
+    if req.uri().path() != METRICS_PATH {
+        return status(StatusCode::NOT_FOUND);
+    }
+    if req.method() != Method::GET && req.method() != Method::HEAD {
+        let mut response = status(StatusCode::METHOD_NOT_ALLOWED);
+        response
+            .headers_mut()
+            .insert(ALLOW, "GET, HEAD".parse().unwrap());
+        return response;
+    }
+
+    let accept = req
+        .headers()
+        .get(ACCEPT)
+        .and_then(|value| value.to_str().ok());
+    match exporter.scrape(accept) {
+        Ok(scrape) => Response::builder()
+            .header(CONTENT_TYPE, scrape.content_type())
+            .body(Body::from(scrape.into_body()))
+            .unwrap_or_else(|_| status(StatusCode::INTERNAL_SERVER_ERROR)),
+        Err(err) => {
+            global::handle_error(err);
+            status(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+fn status(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
@@ -0,0 +1,53 @@
+//! # warp Filter
+//!
+//! A [warp] filter responding to `GET` requests of [`METRICS_PATH`] with the
+//! metrics of the exporter.
+//!
+//! ```no_run
+//! # async fn run() {
+//! let exporter = opentelemetry_prometheus::exporter().init();
+//!
+//! warp::serve(opentelemetry_prometheus::warp::filter(exporter))
+//!     .run(([0, 0, 0, 0], 9464))
+//!     .await
+//! # }
+//! ```
+//!
+//! [warp]: https://docs.rs/warp/0.3
+use crate::{PrometheusExporter, METRICS_PATH};
+use opentelemetry::global;
+use warp::{
+    http::{header::CONTENT_TYPE, Response, StatusCode},
+    Filter, Rejection,
+};
+
+/// A filter serving the metrics of `exporter` on [`METRICS_PATH`], in the
+/// format asked for by the `Accept` header of the request.
+pub fn filter(
+    exporter: PrometheusExporter,
+) -> impl Filter<Extract = (Response<Vec<u8>>,), Error = Rejection> + Clone {
+    warp::path(METRICS_PATH.trim_start_matches('/'))
+        .and(warp::path::end())
+        .and(warp::get().or(warp::head()).unify())
+        .and(warp::header::optional::<String>("accept"))
+        .map(move |accept: Option<String>| respond(&exporter, accept.as_deref()))
+}
+
+fn respond(exporter: &PrometheusExporter, accept: Option<&str>) -> Response<Vec<u8>> {
+    let response = match exporter.scrape(accept) {
+        Ok(scrape) => Response::builder()
+            .header(CONTENT_TYPE, scrape.content_type())
+            .body(scrape.into_body()),
+        Err(err) => {
+            global::handle_error(err);
+            Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(Vec::new())
+        }
+    };
+    response.unwrap_or_else(|_| {
+        let mut response = Response::new(Vec::new());
+        *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+        response
+    })
+}
@@ -1,9 +1,14 @@
+use opentelemetry::sdk::metrics::{aggregators::ExemplarReservoir, Aggregation, View};
 use opentelemetry::sdk::Resource;
+use opentelemetry::testing::trace::TestSpan;
+use opentelemetry::trace::{
+    SpanContext, SpanId, TraceContextExt, TraceId, TraceState, TRACE_FLAG_SAMPLED,
+};
 use opentelemetry::{
     metrics::{BatchObserverResult, MeterProvider, ObserverResult},
-    KeyValue,
+    Context, KeyValue, Unit,
 };
-use opentelemetry_prometheus::PrometheusExporter;
+use opentelemetry_prometheus::{PrometheusExporter, OPEN_METRICS_FORMAT};
 use prometheus::{Encoder, TextEncoder};
 
 #[test]
@@ -23,7 +28,9 @@ fn free_unused_instruments() {
         counter.add(10.0, &labels);
         counter.add(5.3, &labels);
 
-        expected.push(r#"counter{A="B",C="D",R="V"} 15.3"#);
+        expected.push(r#"counter{A="B",C="D",otel_scope_name="test"} 15.3"#);
+        expected.push(r#"otel_scope_info{otel_scope_name="test"} 1"#);
+        expected.push(r#"target_info{R="V"} 1"#);
     }
     // Standard export
     compare_export(&exporter, expected.clone());
@@ -56,8 +63,10 @@ fn batch() {
         }
     });
 
-    expected.push(r#"uint_observer{A="B",R="V"} 2"#);
-    expected.push(r#"float_observer{A="B",R="V"} 3.1"#);
+    expected.push(r#"uint_observer{A="B",otel_scope_name="test"} 2"#);
+    expected.push(r#"float_observer{A="B",otel_scope_name="test"} 3.1"#);
+    expected.push(r#"otel_scope_info{otel_scope_name="test"} 1"#);
+    expected.push(r#"target_info{R="V"} 1"#);
     compare_export(&exporter, expected);
 }
 
@@ -81,7 +90,7 @@ fn test_add() {
     counter.add(10.0, &labels);
     counter.add(5.3, &labels);
 
-    expected.push(r#"counter{A="B",C="D",R="V"} 15.3"#);
+    expected.push(r#"counter{A="B",C="D",otel_scope_name="test"} 15.3"#);
 
     let cb_labels = labels.clone();
     let _observer = meter
@@ -90,23 +99,26 @@ fn test_add() {
         })
         .init();
 
-    expected.push(r#"intobserver{A="B",C="D",R="V"} 1"#);
+    expected.push(r#"intobserver{A="B",C="D",otel_scope_name="test"} 1"#);
 
     value_recorder.record(-0.6, &labels);
     value_recorder.record(-0.4, &labels);
     value_recorder.record(0.6, &labels);
     value_recorder.record(20.0, &labels);
 
-    expected.push(r#"value_recorder_bucket{A="B",C="D",R="V",le="+Inf"} 4"#);
-    expected.push(r#"value_recorder_bucket{A="B",C="D",R="V",le="-0.5"} 1"#);
-    expected.push(r#"value_recorder_bucket{A="B",C="D",R="V",le="1"} 3"#);
-    expected.push(r#"value_recorder_count{A="B",C="D",R="V"} 4"#);
-    expected.push(r#"value_recorder_sum{A="B",C="D",R="V"} 19.6"#);
+    expected.push(r#"value_recorder_bucket{A="B",C="D",otel_scope_name="test",le="+Inf"} 4"#);
+    expected.push(r#"value_recorder_bucket{A="B",C="D",otel_scope_name="test",le="-0.5"} 1"#);
+    expected.push(r#"value_recorder_bucket{A="B",C="D",otel_scope_name="test",le="1"} 3"#);
+    expected.push(r#"value_recorder_count{A="B",C="D",otel_scope_name="test"} 4"#);
+    expected.push(r#"value_recorder_sum{A="B",C="D",otel_scope_name="test"} 19.6"#);
 
     up_down_counter.add(10.0, &labels);
     up_down_counter.add(-3.2, &labels);
 
-    expected.push(r#"updowncounter{A="B",C="D",R="V"} 6.8"#);
+    expected.push(r#"updowncounter{A="B",C="D",otel_scope_name="test"} 6.8"#);
+
+    expected.push(r#"otel_scope_info{otel_scope_name="test"} 1"#);
+    expected.push(r#"target_info{R="V"} 1"#);
 
     compare_export(&exporter, expected)
 }
@@ -133,15 +145,282 @@ fn test_sanitization() {
     value_recorder.record(20.0, &labels);
 
     let expected = vec![
-        r#"http_server_duration_bucket{http_host="server",http_method="GET",service_name="Test Service",le="+Inf"} 4"#,
-        r#"http_server_duration_bucket{http_host="server",http_method="GET",service_name="Test Service",le="-0.5"} 1"#,
-        r#"http_server_duration_bucket{http_host="server",http_method="GET",service_name="Test Service",le="1"} 3"#,
-        r#"http_server_duration_count{http_host="server",http_method="GET",service_name="Test Service"} 4"#,
-        r#"http_server_duration_sum{http_host="server",http_method="GET",service_name="Test Service"} 19.6"#,
+        r#"http_server_duration_bucket{http_host="server",http_method="GET",otel_scope_name="test",le="+Inf"} 4"#,
+        r#"http_server_duration_bucket{http_host="server",http_method="GET",otel_scope_name="test",le="-0.5"} 1"#,
+        r#"http_server_duration_bucket{http_host="server",http_method="GET",otel_scope_name="test",le="1"} 3"#,
+        r#"http_server_duration_count{http_host="server",http_method="GET",otel_scope_name="test"} 4"#,
+        r#"http_server_duration_sum{http_host="server",http_method="GET",otel_scope_name="test"} 19.6"#,
+        r#"otel_scope_info{otel_scope_name="test"} 1"#,
+        r#"target_info{service_name="Test Service"} 1"#,
+    ];
+    compare_export(&exporter, expected)
+}
+
+#[test]
+fn test_unit_suffixes_and_duplicate_labels() {
+    let exporter = opentelemetry_prometheus::exporter()
+        .with_resource(Resource::new(vec![KeyValue::new("service.name", "svc")]))
+        .without_target_info()
+        .without_scope_info()
+        .init();
+    let meter = exporter.provider().unwrap().meter("test", None);
+
+    let counter = meter
+        .u64_counter("request.size")
+        .with_unit(Unit::new("By"))
+        .init();
+    let labels = vec![
+        KeyValue::new("http.method", "GET"),
+        KeyValue::new("http_method", "get"),
+        KeyValue::new("service_name", "override"),
+    ];
+    counter.add(10, &labels);
+
+    let gauge = meter
+        .f64_up_down_counter("cpu.utilization")
+        .with_unit(Unit::new("1"))
+        .init();
+    gauge.add(0.5, &[]);
+
+    let expected = vec![
+        r#"request_size_bytes{http_method="GET;get",service_name="svc;override"} 10"#,
+        r#"cpu_utilization_ratio{service_name="svc"} 0.5"#,
     ];
     compare_export(&exporter, expected)
 }
 
+#[test]
+fn target_and_scope_info() {
+    let exporter = opentelemetry_prometheus::exporter()
+        .with_resource(Resource::new(vec![KeyValue::new("service.name", "svc")]))
+        .init();
+    let provider = exporter.provider().unwrap();
+    provider
+        .meter("http", Some("0.1.0"))
+        .u64_counter("requests")
+        .init()
+        .add(1, &[]);
+    provider
+        .meter("db", None)
+        .u64_counter("queries")
+        .init()
+        .add(2, &[]);
+
+    let expected = vec![
+        r#"requests{otel_scope_name="http",otel_scope_version="0.1.0"} 1"#,
+        r#"queries{otel_scope_name="db"} 2"#,
+        r#"otel_scope_info{otel_scope_name="db"} 1"#,
+        r#"otel_scope_info{otel_scope_name="http",otel_scope_version="0.1.0"} 1"#,
+        r#"target_info{service_name="svc"} 1"#,
+    ];
+    compare_export(&exporter, expected);
+
+    let exporter = opentelemetry_prometheus::exporter()
+        .with_resource(Resource::new(vec![KeyValue::new("service.name", "svc")]))
+        .without_target_info()
+        .without_scope_info()
+        .init();
+    let meter = exporter.provider().unwrap().meter("http", Some("0.1.0"));
+    meter.u64_counter("requests").init().add(1, &[]);
+
+    compare_export(&exporter, vec![r#"requests{service_name="svc"} 1"#]);
+}
+
+#[test]
+fn open_metrics_exemplars() {
+    let exporter = opentelemetry_prometheus::exporter()
+        .with_resource(Resource::new(vec![KeyValue::new("R", "V")]))
+        .with_view(
+            View::new("requests")
+                .with_aggregation(Aggregation::Sum)
+                .with_exemplar_reservoir(ExemplarReservoir::FixedSize(1)),
+        )
+        .with_view(
+            View::new("latency")
+                .with_attribute_keys(vec!["route"])
+                .with_aggregation(Aggregation::Histogram(vec![10.0]))
+                .with_exemplar_reservoir(ExemplarReservoir::AlignedHistogram),
+        )
+        .init();
+    let meter = exporter.provider().unwrap().meter("test", None);
+    let requests = meter.u64_counter("requests").init();
+    let latency = meter.f64_value_recorder("latency").init();
+
+    latency.record(1.0, &[KeyValue::new("route", "/")]);
+    {
+        let _guard = Context::new()
+            .with_span(TestSpan(SpanContext::new(
+                TraceId::from_u128(1),
+                SpanId::from_u64(2),
+                TRACE_FLAG_SAMPLED,
+                false,
+                TraceState::default(),
+            )))
+            .attach();
+        requests.add(3, &[]);
+        latency.record(
+            12.5,
+            &[KeyValue::new("route", "/"), KeyValue::new("user", "1")],
+        );
+    }
+
+    let mut output = Vec::new();
+    let encoder = exporter.open_metrics_encoder();
+    encoder
+        .encode(&exporter.registry().gather(), &mut output)
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    let exemplar = r#"{trace_id="00000000000000000000000000000001",span_id="0000000000000002""#;
+    let expected = vec![
+        format!(
+            r#"requests_total{{otel_scope_name="test"}} 3 # {}}} 3 "#,
+            exemplar
+        ),
+        r#"latency_bucket{route="/",otel_scope_name="test",le="10"} 1"#.to_string(),
+        format!(
+            r#"latency_bucket{{route="/",otel_scope_name="test",le="+Inf"}} 2 # {},user="1"}} 12.5 "#,
+            exemplar
+        ),
+        r#"latency_count{route="/",otel_scope_name="test"} 2"#.to_string(),
+        r#"target_info{R="V"} 1"#.to_string(),
+    ];
+    for line in expected {
+        assert!(
+            output.lines().any(|exported| exported.starts_with(&line)),
+            "{} not in {}",
+            line,
+            output
+        );
+    }
+    assert!(output.contains("# TYPE requests counter\n"));
+    assert!(output.ends_with("# EOF\n"));
+}
+
+#[test]
+fn scrape_negotiates_format() {
+    let exporter = opentelemetry_prometheus::exporter().init();
+    let meter = exporter.provider().unwrap().meter("test", None);
+    meter.u64_counter("requests").init().add(3, &[]);
+
+    let scrape = exporter.scrape(None).unwrap();
+    assert_eq!(scrape.content_type(), TextEncoder::new().format_type());
+    let body = String::from_utf8(scrape.into_body()).unwrap();
+    assert!(
+        body.contains("\nrequests{otel_scope_name=\"test\"} 3\n"),
+        "{}",
+        body
+    );
+
+    let scrape = exporter
+        .scrape(Some(
+            "application/openmetrics-text;version=1.0.0,text/plain;version=0.0.4;q=0.5",
+        ))
+        .unwrap();
+    assert_eq!(scrape.content_type(), OPEN_METRICS_FORMAT);
+    let body = String::from_utf8(scrape.into_body()).unwrap();
+    assert!(
+        body.contains("\nrequests_total{otel_scope_name=\"test\"} 3\n"),
+        "{}",
+        body
+    );
+    assert!(body.ends_with("# EOF\n"));
+}
+
+#[cfg(feature = "hyper-server")]
+#[tokio::test]
+async fn serve_metrics_path() {
+    use hyper::{Body, Method, Request, StatusCode};
+    use opentelemetry_prometheus::server::handle;
+
+    let exporter = opentelemetry_prometheus::exporter().init();
+    let meter = exporter.provider().unwrap().meter("test", None);
+    meter.u64_counter("requests").init().add(3, &[]);
+    let request = |method, path| {
+        Request::builder()
+            .method(method)
+            .uri(path)
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = handle(&exporter, &request(Method::GET, "/metrics"));
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    assert!(String::from_utf8_lossy(&body).contains("\nrequests{otel_scope_name=\"test\"} 3\n"));
+
+    let response = handle(&exporter, &request(Method::GET, "/"));
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = handle(&exporter, &request(Method::POST, "/metrics"));
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+}
+
+#[cfg(feature = "axum-handler")]
+#[tokio::test]
+async fn axum_metrics_handler() {
+    use axum::extract::State;
+    use axum::http::{header::ACCEPT, HeaderMap, StatusCode};
+    use opentelemetry_prometheus::axum::metrics;
+
+    let exporter = opentelemetry_prometheus::exporter().init();
+    let meter = exporter.provider().unwrap().meter("test", None);
+    meter.u64_counter("requests").init().add(3, &[]);
+    let mut headers = HeaderMap::new();
+    headers.insert(ACCEPT, OPEN_METRICS_FORMAT.parse().unwrap());
+
+    let response = metrics(State(exporter), headers).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], OPEN_METRICS_FORMAT);
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    assert!(String::from_utf8_lossy(&body).ends_with("# EOF\n"));
+}
+
+#[cfg(feature = "warp-filter")]
+#[tokio::test]
+async fn warp_metrics_filter() {
+    use opentelemetry_prometheus::warp::filter;
+
+    let exporter = opentelemetry_prometheus::exporter().init();
+    let meter = exporter.provider().unwrap().meter("test", None);
+    meter.u64_counter("requests").init().add(3, &[]);
+    let filter = filter(exporter);
+
+    let response = warp::test::request().path("/metrics").reply(&filter).await;
+    assert_eq!(response.status(), 200);
+    assert!(String::from_utf8_lossy(response.body())
+        .contains("\nrequests{otel_scope_name=\"test\"} 3\n"));
+
+    let response = warp::test::request().path("/").reply(&filter).await;
+    assert_eq!(response.status(), 404);
+    let response = warp::test::request()
+        .method("POST")
+        .path("/metrics")
+        .reply(&filter)
+        .await;
+    assert_eq!(response.status(), 405);
+}
+
+#[cfg(feature = "actix-handler")]
+#[actix_web::test]
+async fn actix_metrics_handler() {
+    use actix_web::{test, App};
+    use opentelemetry_prometheus::actix::configure;
+
+    let exporter = opentelemetry_prometheus::exporter().init();
+    let meter = exporter.provider().unwrap().meter("test", None);
+    meter.u64_counter("requests").init().add(3, &[]);
+    let app = test::init_service(App::new().configure(configure(exporter))).await;
+
+    let response =
+        test::call_service(&app, test::TestRequest::get().uri("/metrics").to_request()).await;
+    assert!(response.status().is_success());
+    let body = test::read_body(response).await;
+    assert!(String::from_utf8_lossy(&body).contains("\nrequests{otel_scope_name=\"test\"} 3\n"));
+
+    let response = test::call_service(&app, test::TestRequest::get().uri("/").to_request()).await;
+    assert_eq!(response.status(), 404);
+}
+
 fn compare_export(exporter: &PrometheusExporter, mut expected: Vec<&'static str>) {
     let mut output = Vec::new();
     let encoder = TextEncoder::new();
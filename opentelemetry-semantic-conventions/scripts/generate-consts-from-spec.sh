@@ -1,64 +1,31 @@
 #!/bin/bash
-# Semantic conventions are currently defined in Markdown files in the
-# specification repository. This script downloads these files and attempts to
-# find the attributes and their documentation. It then prints Rust code with
-# constants for these attributes to stdout.
+# Regenerates the attribute constants of the trace and resource modules, and
+# the typed span attributes of src/trace/attributes.rs, from the YAML model of
+# a release of the semantic conventions, see
+# https://github.com/open-telemetry/semantic-conventions.
 #
-# This helps you get started but doesn't do all the things. You will need to the
-# following manually:
-# - This prints some attributes multiple times, because it mistakes examples for
-#   attribute definitions. Remove those.
-# - In some cases the description isn't parsed properly. In those cases you will
-#   need to copy it manually.
-# - Hard wrap description text, so it fits the code style of this repo.
-# - Add URLs for links in the documentation.
+# Usage: generate-consts-from-spec.sh <version>, e.g. 1.20.0
+#
+# Only the code below the generated code markers of src/trace.rs,
+# src/resource.rs and src/trace/attributes.rs is replaced. Attributes
+# deprecated by the release are marked with `#[deprecated]`, so review the new
+# warnings of `cargo build` and `cargo test` after regenerating, e.g. of typed
+# attributes still using them. Requires git, python3 with PyYAML and rustfmt.
+
+set -eu
 
-set -e
+if [ $# -ne 1 ]; then
+	echo "Usage: $0 <version>"
+	exit 1
+fi
 
-md_to_rs() {
-	while read line; do
-		# Regex explanation:
-		# Find markdown tables (lines starting with "|") and take the first 2 columns
-		# - The 1st column is the attribute name consisting of a-z0-9._
-		# - The 2nd column is (usually) the type
-		# - The 3rd column is (usually) the description
-		if [[ "$line" =~ ^\|\ \[?\`?([a-z0-9._]+)\`?\]?(:?\([^|]+\))?\ +\|\ ([^|]+)\ +\|\ ([^|]+) ]]; then
-			name="${BASH_REMATCH[1]}"
-			const_name=$(echo "$name" | tr a-z A-Z | sed "s/\\./_/g")
-			doc="${BASH_REMATCH[4]}"
-			echo ""
-			echo "/// $doc"
-			echo "pub const $const_name: Key = Key::from_static_str(\"$name\");"
-		fi
-	done <<<"$1"
-}
+crate_dir="$(cd "$(dirname "$0")/.." && pwd)"
+spec_dir="$(mktemp -d)"
+trap 'rm -rf "$spec_dir"' EXIT
 
-md_url_to_rs() {
-	md_to_rs "$(curl -sSf "https://raw.githubusercontent.com/open-telemetry/opentelemetry-specification/master/specification/$1")"
-}
+git clone --quiet --depth 1 --branch "v$1" \
+	https://github.com/open-telemetry/semantic-conventions.git "$spec_dir"
 
-case "$1" in
-	"resource")
-		md_url_to_rs "resource/semantic_conventions/README.md"
-		md_url_to_rs "resource/semantic_conventions/cloud.md"
-		md_url_to_rs "resource/semantic_conventions/container.md"
-		md_url_to_rs "resource/semantic_conventions/deployment_environment.md"
-		md_url_to_rs "resource/semantic_conventions/faas.md"
-		md_url_to_rs "resource/semantic_conventions/host.md"
-		md_url_to_rs "resource/semantic_conventions/k8s.md"
-		md_url_to_rs "resource/semantic_conventions/os.md"
-		md_url_to_rs "resource/semantic_conventions/process.md"
-		;;
-	"trace")
-		md_url_to_rs "trace/semantic_conventions/database.md"
-		md_url_to_rs "trace/semantic_conventions/exceptions.md"
-		md_url_to_rs "trace/semantic_conventions/faas.md"
-		md_url_to_rs "trace/semantic_conventions/http.md"
-		md_url_to_rs "trace/semantic_conventions/messaging.md"
-		md_url_to_rs "trace/semantic_conventions/rpc.md"
-		md_url_to_rs "trace/semantic_conventions/span-general.md"
-		;;
-	*)
-		echo "Usage: $0 <resource|trace>"
-		;;
-esac
+python3 "$crate_dir/scripts/generate_consts.py" --crate "$crate_dir" "$spec_dir/model"
+rustfmt --edition 2018 "$crate_dir/src/trace.rs" "$crate_dir/src/resource.rs" \
+	"$crate_dir/src/trace/attributes.rs"
@@ -0,0 +1,480 @@
+#!/usr/bin/env python3
+"""Regenerate the attribute constants and the typed span attributes.
+
+Reads the YAML model of the semantic conventions, e.g. the `model` directory
+of https://github.com/open-telemetry/semantic-conventions, and replaces
+everything below the generated code marker of src/trace.rs and
+src/resource.rs with one constant per attribute. The hand written part above
+the marker, i.e. the module docs and imports, is kept.
+
+The structs of src/trace/attributes.rs are generated from the `STRUCTS` below,
+which pick the attributes of each kind of span. Their field types, docs and
+enums are taken from the model, values are checked by the hand written
+validators above the marker of that file.
+
+Attributes used by groups of type `resource`, or by groups in files below a
+`resource` directory, go to the resource module, all others to the trace
+module. Deprecated attributes are marked with `#[deprecated]`, so code still
+using them gets a warning after an update rather than a compile error.
+
+Requires PyYAML. Usually run through generate-consts-from-spec.sh.
+"""
+import argparse
+import os
+import re
+import sys
+import textwrap
+
+import yaml
+
+MARKER = "// The constants below are generated"
+MARKER_COMMENT = (
+    MARKER + " from the semantic conventions with\n"
+    "// scripts/generate-consts-from-spec.sh, edit the script rather than the constants.\n"
+)
+DOC_WIDTH = 76
+
+ATTRIBUTES_MARKER = "// The typed attributes below are generated"
+ATTRIBUTES_MARKER_COMMENT = (
+    ATTRIBUTES_MARKER + " from the semantic conventions with\n"
+    "// scripts/generate-consts-from-spec.sh, edit the script rather than the structs.\n"
+)
+
+# The typed attributes of common kinds of spans. Fields are named after the
+# attributes without `prefix`, or without their namespace for attributes of
+# other namespaces, e.g. `net.peer.name` is the `peer_name` field.
+STRUCTS = [
+    {
+        "name": "HttpServerAttributes",
+        "doc": """The attributes of a span of an HTTP server handling a request.
+
+Set the fields that are known and leave the others at their default.
+
+# Examples
+
+```
+use opentelemetry_semantic_conventions::trace::HttpServerAttributes;
+
+let attributes = HttpServerAttributes {
+    method: "GET".to_string(),
+    target: "/users/42?details=true".to_string(),
+    route: Some("/users/:id".to_string()),
+    status_code: Some(200),
+    ..Default::default()
+};
+assert!(attributes.validate().is_ok());
+let attributes: Vec<_> = attributes.into_iter().collect();
+```""",
+        "prefix": "http",
+        "required": ["http.method", "http.target"],
+        "optional": [
+            "http.route",
+            "http.scheme",
+            "http.host",
+            "http.server_name",
+            "http.flavor",
+            "http.status_code",
+            "http.client_ip",
+            "http.user_agent",
+        ],
+    },
+    {
+        "name": "HttpClientAttributes",
+        "doc": "The attributes of a span of an HTTP client sending a request.",
+        "prefix": "http",
+        "required": ["http.method", "http.url"],
+        "optional": [
+            "http.flavor",
+            "http.status_code",
+            "http.user_agent",
+            "net.peer.name",
+            "net.peer.port",
+        ],
+    },
+    {
+        "name": "GrpcAttributes",
+        "doc": "The attributes of a span of a gRPC call, on the client or the server.",
+        "prefix": "rpc",
+        "fixed": {"rpc.system": "grpc"},
+        "required": ["rpc.service", "rpc.method"],
+        "optional": ["rpc.grpc.status_code", "net.peer.name", "net.peer.port"],
+    },
+    {
+        "name": "DbAttributes",
+        "doc": "The attributes of a span of a database client call.",
+        "prefix": "db",
+        "required": ["db.system"],
+        "optional": [
+            "db.name",
+            "db.statement",
+            "db.operation",
+            "db.user",
+            "net.peer.name",
+            "net.peer.port",
+        ],
+    },
+    {
+        "name": "MessagingAttributes",
+        "doc": "The attributes of a span of sending, receiving or processing a message.",
+        "prefix": "messaging",
+        "required": ["messaging.system", "messaging.destination"],
+        "optional": [
+            "messaging.destination_kind",
+            "messaging.temp_destination",
+            "messaging.operation",
+            "messaging.message_id",
+            "messaging.conversation_id",
+            "messaging.message_payload_size_bytes",
+        ],
+    },
+]
+
+# Rust types of attributes narrower than the model's `int`, e.g. the gRPC
+# status codes, which are an enum of numbers in the model.
+RUST_TYPES = {
+    "http.status_code": "u16",
+    "net.peer.port": "u16",
+    "rpc.grpc.status_code": "i32",
+}
+
+# Field names differing from the attribute names.
+FIELD_NAMES = {
+    "messaging.message_payload_size_bytes": "payload_size_bytes",
+}
+
+# Hand written validators of src/trace/attributes.rs checking the value of an
+# attribute, called with `&str` for strings and by value otherwise.
+VALIDATORS = {
+    "http.method": "validate_method",
+    "http.target": "validate_target",
+    "http.url": "validate_url",
+    "http.route": "validate_route",
+    "http.status_code": "validate_status_code",
+    "rpc.grpc.status_code": "validate_grpc_status_code",
+}
+
+
+def load_groups(model_dir):
+    """Yield the groups of all YAML files below `model_dir` in path order."""
+    for root, dirs, files in os.walk(model_dir):
+        dirs.sort()
+        for name in sorted(files):
+            if not name.endswith((".yaml", ".yml")):
+                continue
+            path = os.path.join(root, name)
+            with open(path) as f:
+                model = yaml.safe_load(f) or {}
+            for group in model.get("groups", []):
+                yield os.path.relpath(path, model_dir), group
+
+
+def collect_attributes(model_dir):
+    """Map the names of the trace and the resource attributes to their model.
+
+    Attributes used by resources, i.e. defined or referenced by a resource
+    group, are resource attributes. All others, and the ones used by spans as
+    well, are trace attributes.
+    """
+    definitions, resource_names, span_names = {}, [], []
+    for path, group in load_groups(model_dir):
+        group_type = group.get("type", "span")
+        if group_type == "resource" or "resource" in path.split(os.sep):
+            used = resource_names
+        elif group_type in ("span", "event"):
+            used = span_names
+        else:
+            used = []
+        prefix = group.get("prefix", "")
+        for attribute in group.get("attributes", []):
+            if "ref" in attribute:
+                used.append(attribute["ref"])
+                continue
+            # templates name a family of attributes, e.g. all request headers
+            if str(attribute.get("type", "")).startswith("template["):
+                continue
+            name = "{}.{}".format(prefix, attribute["id"]) if prefix else attribute["id"]
+            definitions.setdefault(name, attribute)
+            used.append(name)
+
+    resource = {name: definitions[name] for name in definitions if name in resource_names}
+    trace = {
+        name: definitions[name]
+        for name in definitions
+        if name not in resource or name in span_names
+    }
+    return trace, resource
+
+
+def wrap(paragraph, width=DOC_WIDTH):
+    """Wrap a paragraph of markdown, keeping tables, lists and code as is."""
+    lines = paragraph.splitlines()
+    if any(re.match(r"\s*([|*-]|\d+\.|```)|\s{4}", line) for line in lines):
+        return [line.rstrip() for line in lines]
+    text = " ".join(line.strip() for line in lines)
+    # links must not be split
+    return textwrap.wrap(text, width, break_long_words=False, break_on_hyphens=False) or [""]
+
+
+def members_paragraphs(name, attribute_type):
+    custom = attribute_type.get("allow_custom_values", False)
+    table = ["| Value  | Description |", "|---|---|"]
+    for member in attribute_type.get("members", []):
+        brief = " ".join(str(member.get("brief", member["id"])).split())
+        table.append("| `{}` | {} |".format(member["value"], brief))
+    return [
+        "`{}` MUST be one of the following{}:".format(
+            name,
+            " or, if none of the listed values apply, a custom value" if custom else "",
+        ),
+        "\n".join(table),
+    ]
+
+
+def const_name(name):
+    return re.sub(r"[^A-Z0-9]", "_", name.upper())
+
+
+def camel_case(name):
+    """`http.flavor` is `HttpFlavor`, `http_1_0` is `Http1_0`."""
+    result = ""
+    for part in re.split(r"[._]", name):
+        if part[:1].isdigit() and result[-1:].isdigit():
+            result += "_"
+        result += part[:1].upper() + part[1:]
+    return result
+
+
+def doc_comment(text, indent=""):
+    lines = []
+    for paragraph in re.split(r"\n\s*\n", text.strip()):
+        if lines:
+            lines.append("")
+        lines.extend(wrap(paragraph, DOC_WIDTH - len(indent)))
+    return "".join("{}///{}\n".format(indent, " " + line if line else "") for line in lines)
+
+
+def constant(name, attribute):
+    paragraphs = [str(attribute.get("brief", "")).strip()]
+    note = attribute.get("note")
+    if note:
+        paragraphs.extend(p for p in re.split(r"\n\s*\n", str(note).strip()) if p)
+    if isinstance(attribute.get("type"), dict):
+        paragraphs.extend(members_paragraphs(name, attribute["type"]))
+
+    lines = []
+    for paragraph in paragraphs:
+        if lines:
+            lines.append("")
+        lines.extend(wrap(paragraph))
+    docs = "".join("///{}\n".format(" " + line if line else "") for line in lines)
+
+    deprecated = attribute.get("deprecated")
+    if deprecated:
+        note = " ".join(str(deprecated).split()).replace("\\", "\\\\").replace('"', '\\"')
+        docs += '#[deprecated(note = "{}")]\n'.format(note)
+    elif attribute.get("stability") == "deprecated":
+        docs += "#[deprecated]\n"
+
+    return '\n{}pub const {}: Key = Key::from_static_str("{}");\n'.format(
+        docs, const_name(name), name
+    )
+
+
+def regenerate(path, attributes):
+    with open(path) as f:
+        source = f.read()
+    start = source.find(MARKER)
+    if start < 0:
+        sys.exit("{} has no generated code marker `{}`".format(path, MARKER))
+
+    generated = "".join(constant(name, attributes[name]) for name in attributes)
+    with open(path, "w") as f:
+        f.write(source[:start] + MARKER_COMMENT + generated)
+
+
+def brief(attribute):
+    return " ".join(str(attribute.get("brief", "")).split()).rstrip(".")
+
+
+def field(struct, name, attribute, required):
+    """The Rust field of an attribute, with the code to validate and set it."""
+    prefix = struct["prefix"] + "."
+    field_name = FIELD_NAMES.get(name)
+    if field_name is None:
+        field_name = name[len(prefix):] if name.startswith(prefix) else name.split(".", 1)[1]
+        field_name = field_name.replace(".", "_")
+
+    attribute_type = attribute.get("type")
+    if name in RUST_TYPES:
+        rust_type = RUST_TYPES[name]
+    elif isinstance(attribute_type, dict):
+        rust_type = camel_case(name)
+    else:
+        rust_type = {"string": "String", "int": "i64", "double": "f64", "boolean": "bool"}.get(
+            attribute_type
+        )
+        if rust_type is None:
+            sys.exit("{} has unsupported type {}".format(name, attribute_type))
+    if required and rust_type != "String":
+        sys.exit("{} is required but not a string".format(name))
+    if attribute.get("deprecated") or attribute.get("stability") == "deprecated":
+        print("warning: {} uses deprecated {}".format(struct["name"], name), file=sys.stderr)
+
+    key = const_name(name)
+    if rust_type == "String":
+        value = "{}.string({{}})".format(key)
+    elif rust_type == "bool":
+        value = "{}.bool(true)".format(key)
+    elif rust_type == "f64":
+        value = "{}.f64({{}})".format(key)
+    elif rust_type == "i64":
+        value = "{}.i64({{}})".format(key)
+    elif rust_type in ("u8", "u16", "u32", "i8", "i16", "i32"):
+        value = "{}.i64({{}}.into())".format(key)
+    else:
+        value = "{}.string({{}}.as_str())".format(key)
+
+    validator = VALIDATORS.get(name)
+    if rust_type == "bool":
+        declared = "bool"
+        push = "if self.{0} {{\nattributes.push({1});\n}}\n".format(field_name, value)
+        checks = []
+    elif required:
+        declared = rust_type
+        push = None
+        checks = ["validate_required({}, &self.{})?;".format(key, field_name)]
+        if validator:
+            checks.append("{}(&self.{})?;".format(validator, field_name))
+    else:
+        declared = "Option<{}>".format(rust_type)
+        push = "if let Some(value) = self.{} {{\nattributes.push({});\n}}\n".format(
+            field_name, value.format("value")
+        )
+        checks = []
+        if validator:
+            checks.append(
+                "if let Some(value) = {} {{\n{}(value)?;\n}}".format(
+                    "self.{}.as_deref()".format(field_name)
+                    if rust_type == "String"
+                    else "self.{}".format(field_name),
+                    validator,
+                )
+            )
+
+    docs = doc_comment("{}, see [`{}`].".format(brief(attribute), key), "    ")
+    return {
+        "declaration": "{}    pub {}: {},\n".format(docs, field_name, declared),
+        "checks": checks,
+        "value": value.format("self." + field_name) if required else None,
+        "push": push,
+    }
+
+
+def enum(name, attribute):
+    enum_name = camel_case(name)
+    variants, values = "", ""
+    for member in attribute["type"].get("members", []):
+        variant = camel_case(str(member["id"]))
+        variants += doc_comment(str(member.get("brief", member["id"])), "    ")
+        variants += "    {},\n".format(variant)
+        values += '{}::{} => "{}",\n'.format(enum_name, variant, member["value"])
+    return (
+        "\n{docs}#[derive(Clone, Copy, Debug, PartialEq)]\n"
+        "pub enum {name} {{\n{variants}}}\n\n"
+        "impl {name} {{\n"
+        "    /// The value of the attribute.\n"
+        "    pub fn as_str(&self) -> &'static str {{\n"
+        "        match self {{\n{values}}}\n"
+        "    }}\n"
+        "}}\n"
+    ).format(
+        docs=doc_comment("{}, see [`{}`].".format(brief(attribute), const_name(name))),
+        name=enum_name,
+        variants=variants,
+        values=values,
+    )
+
+
+def struct(definition, attributes):
+    def model(name):
+        if name not in attributes:
+            sys.exit("{} uses {}, which is not in the model".format(definition["name"], name))
+        return attributes[name]
+
+    fields = [field(definition, name, model(name), True) for name in definition["required"]]
+    fields += [field(definition, name, model(name), False) for name in definition["optional"]]
+    values = [
+        '{}.string("{}")'.format(const_name(name), value)
+        for name, value in definition.get("fixed", {}).items()
+    ]
+    values += [f["value"] for f in fields if f["value"]]
+    checks = [check for f in fields for check in f["checks"]]
+
+    return (
+        "\n{docs}#[derive(Clone, Debug, Default, PartialEq)]\n"
+        "pub struct {name} {{\n{fields}}}\n\n"
+        "impl {name} {{\n"
+        "    /// Check that the required attributes are set and the values are valid.\n"
+        "    pub fn validate(&self) -> Result<(), AttributeError> {{\n"
+        "{checks}\nOk(())\n"
+        "    }}\n"
+        "}}\n\n"
+        "impl IntoIterator for {name} {{\n"
+        "    type Item = KeyValue;\n"
+        "    type IntoIter = vec::IntoIter<KeyValue>;\n\n"
+        "    fn into_iter(self) -> Self::IntoIter {{\n"
+        "        let mut attributes = vec![{values}];\n"
+        "{pushes}"
+        "        attributes.into_iter()\n"
+        "    }}\n"
+        "}}\n"
+    ).format(
+        docs=doc_comment(definition["doc"]),
+        name=definition["name"],
+        fields="".join(f["declaration"] for f in fields),
+        checks="\n".join(checks),
+        values=", ".join(values),
+        pushes="".join(f["push"] for f in fields if f["push"]),
+    )
+
+
+def regenerate_attributes(path, attributes):
+    with open(path) as f:
+        source = f.read()
+    start = source.find(ATTRIBUTES_MARKER)
+    if start < 0:
+        sys.exit("{} has no generated code marker `{}`".format(path, ATTRIBUTES_MARKER))
+
+    # the enums of all structs go first, each once
+    enums = {}
+    for definition in STRUCTS:
+        for name in definition["required"] + definition["optional"]:
+            attribute = attributes.get(name, {})
+            if name not in RUST_TYPES and isinstance(attribute.get("type"), dict):
+                enums.setdefault(name, enum(name, attribute))
+    structs = "".join(struct(definition, attributes) for definition in STRUCTS)
+
+    with open(path, "w") as f:
+        f.write(source[:start] + ATTRIBUTES_MARKER_COMMENT + "".join(enums.values()) + structs)
+
+
+def main():
+    parser = argparse.ArgumentParser(description=__doc__.splitlines()[0])
+    parser.add_argument("model", help="the model directory of the semantic conventions")
+    parser.add_argument(
+        "--crate",
+        default=os.path.join(os.path.dirname(os.path.abspath(__file__)), os.pardir),
+        help="the directory of the opentelemetry-semantic-conventions crate",
+    )
+    args = parser.parse_args()
+
+    trace, resource = collect_attributes(args.model)
+    if not trace or not resource:
+        sys.exit("no attributes found in {}".format(args.model))
+    regenerate(os.path.join(args.crate, "src", "trace.rs"), trace)
+    regenerate(os.path.join(args.crate, "src", "resource.rs"), resource)
+    regenerate_attributes(os.path.join(args.crate, "src", "trace", "attributes.rs"), trace)
+
+
+if __name__ == "__main__":
+    main()
@@ -21,6 +21,9 @@
 
 use opentelemetry::Key;
 
+// The constants below are generated from the semantic conventions with
+// scripts/generate-consts-from-spec.sh, edit the script rather than the constants.
+
 /// Logical name of the service.
 ///
 /// MUST be the same for all instances of horizontally scaled services.
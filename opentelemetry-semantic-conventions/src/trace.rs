@@ -21,9 +21,44 @@
 //!     ])
 //!     .start(&tracer);
 //! ```
+//!
+//! ## Typed Attributes
+//!
+//! The attributes of common kinds of spans can be assembled from structs
+//! naming the attributes of each kind, which check the required attributes
+//! and their values with `validate`.
+//!
+//! ```rust
+//! use opentelemetry::trace::{SpanKind, Tracer};
+//! use opentelemetry::global;
+//! use opentelemetry_semantic_conventions::trace::HttpServerAttributes;
+//!
+//! let attributes = HttpServerAttributes {
+//!     method: "GET".to_string(),
+//!     target: "/users/42".to_string(),
+//!     route: Some("/users/:id".to_string()),
+//!     status_code: Some(200),
+//!     ..Default::default()
+//! };
+//! debug_assert!(attributes.validate().is_ok());
+//!
+//! let tracer = global::tracer("my-component");
+//! let _span = tracer
+//!     .span_builder("/users/:id")
+//!     .with_kind(SpanKind::Server)
+//!     .with_attributes(attributes.into_iter().collect())
+//!     .start(&tracer);
+//! ```
 
 use opentelemetry::Key;
 
+mod attributes;
+
+pub use attributes::*;
+
+// The constants below are generated from the semantic conventions with
+// scripts/generate-consts-from-spec.sh, edit the script rather than the constants.
+
 /// An identifier for the database management system (DBMS) product being used.
 pub const DB_SYSTEM: Key = Key::from_static_str("db.system");
 
@@ -385,6 +420,11 @@ pub const RPC_SERVICE: Key = Key::from_static_str("rpc.service");
 /// the span name.
 pub const RPC_METHOD: Key = Key::from_static_str("rpc.method");
 
+/// The [numeric status code] of the gRPC request.
+///
+/// [numeric status code]: https://github.com/grpc/grpc/blob/v1.33.2/doc/statuscodes.md
+pub const RPC_GRPC_STATUS_CODE: Key = Key::from_static_str("rpc.grpc.status_code");
+
 /// Like `net.peer.ip` but for the host IP.
 ///
 /// Useful in case of a multi-IP host.
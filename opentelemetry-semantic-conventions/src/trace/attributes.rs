@@ -0,0 +1,620 @@
+use super::*;
+use opentelemetry::{Key, KeyValue};
+use std::error::Error;
+use std::fmt;
+use std::vec;
+
+/// Error returned when attributes violate the semantic conventions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttributeError {
+    key: Key,
+    reason: &'static str,
+}
+
+impl AttributeError {
+    fn new(key: Key, reason: &'static str) -> Self {
+        AttributeError { key, reason }
+    }
+
+    /// The key of the invalid attribute.
+    pub fn key(&self) -> &Key {
+        &self.key
+    }
+}
+
+impl fmt::Display for AttributeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid {} attribute: {}",
+            self.key.as_str(),
+            self.reason
+        )
+    }
+}
+
+impl Error for AttributeError {}
+
+fn validate_required(key: Key, value: &str) -> Result<(), AttributeError> {
+    if value.is_empty() {
+        Err(AttributeError::new(key, "is required"))
+    } else {
+        Ok(())
+    }
+}
+
+fn validate_method(method: &str) -> Result<(), AttributeError> {
+    if method
+        .bytes()
+        .all(|b| b.is_ascii_uppercase() || b == b'-' || b == b'_')
+    {
+        Ok(())
+    } else {
+        Err(AttributeError::new(
+            HTTP_METHOD,
+            "must be an upper case HTTP method",
+        ))
+    }
+}
+
+fn validate_target(target: &str) -> Result<(), AttributeError> {
+    if target.starts_with('/') || target == "*" {
+        Ok(())
+    } else {
+        Err(AttributeError::new(
+            HTTP_TARGET,
+            "must be an absolute path or `*`",
+        ))
+    }
+}
+
+fn validate_url(url: &str) -> Result<(), AttributeError> {
+    if url.contains("://") {
+        Ok(())
+    } else {
+        Err(AttributeError::new(HTTP_URL, "must be an absolute URL"))
+    }
+}
+
+fn validate_route(route: &str) -> Result<(), AttributeError> {
+    if route.starts_with('/') {
+        Ok(())
+    } else {
+        Err(AttributeError::new(HTTP_ROUTE, "must be an absolute path"))
+    }
+}
+
+fn validate_status_code(status_code: u16) -> Result<(), AttributeError> {
+    if (100..=599).contains(&status_code) {
+        Ok(())
+    } else {
+        Err(AttributeError::new(
+            HTTP_STATUS_CODE,
+            "must be an HTTP status code",
+        ))
+    }
+}
+
+fn validate_grpc_status_code(status_code: i32) -> Result<(), AttributeError> {
+    if (0..=16).contains(&status_code) {
+        Ok(())
+    } else {
+        Err(AttributeError::new(
+            RPC_GRPC_STATUS_CODE,
+            "must be a gRPC status code",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn http_server_attributes() {
+        let attributes = HttpServerAttributes {
+            method: "GET".to_string(),
+            target: "/users/42".to_string(),
+            route: Some("/users/:id".to_string()),
+            flavor: Some(HttpFlavor::Http1_1),
+            status_code: Some(200),
+            ..Default::default()
+        };
+        assert_eq!(attributes.validate(), Ok(()));
+        assert_eq!(
+            attributes.into_iter().collect::<Vec<_>>(),
+            vec![
+                KeyValue::new("http.method", "GET"),
+                KeyValue::new("http.target", "/users/42"),
+                KeyValue::new("http.route", "/users/:id"),
+                KeyValue::new("http.flavor", "1.1"),
+                KeyValue::new("http.status_code", 200),
+            ]
+        );
+    }
+
+    #[test]
+    fn reject_invalid_attributes() {
+        let server = HttpServerAttributes {
+            method: "get".to_string(),
+            target: "/".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(server.validate().unwrap_err().key(), &HTTP_METHOD);
+        let client = HttpClientAttributes {
+            method: "POST".to_string(),
+            url: "https://example.com/".to_string(),
+            status_code: Some(1000),
+            ..Default::default()
+        };
+        assert_eq!(
+            client.validate().unwrap_err().to_string(),
+            "invalid http.status_code attribute: must be an HTTP status code"
+        );
+        assert_eq!(
+            GrpcAttributes::default().validate().unwrap_err().key(),
+            &RPC_SERVICE
+        );
+        assert_eq!(
+            DbAttributes::default().validate().unwrap_err().key(),
+            &DB_SYSTEM
+        );
+        let message = MessagingAttributes {
+            system: "kafka".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            message.validate().unwrap_err().key(),
+            &MESSAGING_DESTINATION
+        );
+    }
+
+    #[test]
+    fn messaging_attributes() {
+        let attributes = MessagingAttributes {
+            system: "kafka".to_string(),
+            destination: "orders".to_string(),
+            destination_kind: Some(MessagingDestinationKind::Topic),
+            operation: Some(MessagingOperation::Process),
+            payload_size_bytes: Some(512),
+            ..Default::default()
+        };
+        assert_eq!(attributes.validate(), Ok(()));
+        assert_eq!(
+            attributes.into_iter().collect::<Vec<_>>(),
+            vec![
+                KeyValue::new("messaging.system", "kafka"),
+                KeyValue::new("messaging.destination", "orders"),
+                KeyValue::new("messaging.destination_kind", "topic"),
+                KeyValue::new("messaging.operation", "process"),
+                KeyValue::new("messaging.message_payload_size_bytes", 512),
+            ]
+        );
+    }
+}
+
+// The typed attributes below are generated from the semantic conventions with
+// scripts/generate-consts-from-spec.sh, edit the script rather than the structs.
+
+/// Kind of HTTP protocol used, see [`HTTP_FLAVOR`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HttpFlavor {
+    /// HTTP 1.0
+    Http1_0,
+    /// HTTP 1.1
+    Http1_1,
+    /// HTTP 2
+    Http2_0,
+    /// SPDY protocol.
+    Spdy,
+    /// QUIC protocol.
+    Quic,
+}
+
+impl HttpFlavor {
+    /// The value of the attribute.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HttpFlavor::Http1_0 => "1.0",
+            HttpFlavor::Http1_1 => "1.1",
+            HttpFlavor::Http2_0 => "2.0",
+            HttpFlavor::Spdy => "SPDY",
+            HttpFlavor::Quic => "QUIC",
+        }
+    }
+}
+
+/// The kind of message destination, see [`MESSAGING_DESTINATION_KIND`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MessagingDestinationKind {
+    /// A message sent to a queue
+    Queue,
+    /// A message sent to a topic
+    Topic,
+}
+
+impl MessagingDestinationKind {
+    /// The value of the attribute.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MessagingDestinationKind::Queue => "queue",
+            MessagingDestinationKind::Topic => "topic",
+        }
+    }
+}
+
+/// A string identifying the kind of message consumption as defined in the
+/// [Operation
+/// names](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/trace/semantic_conventions/messaging.md#operation-names)
+/// section above, see [`MESSAGING_OPERATION`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MessagingOperation {
+    /// receive
+    Receive,
+    /// process
+    Process,
+}
+
+impl MessagingOperation {
+    /// The value of the attribute.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MessagingOperation::Receive => "receive",
+            MessagingOperation::Process => "process",
+        }
+    }
+}
+
+/// The attributes of a span of an HTTP server handling a request.
+///
+/// Set the fields that are known and leave the others at their default.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry_semantic_conventions::trace::HttpServerAttributes;
+///
+/// let attributes = HttpServerAttributes {
+///     method: "GET".to_string(),
+///     target: "/users/42?details=true".to_string(),
+///     route: Some("/users/:id".to_string()),
+///     status_code: Some(200),
+///     ..Default::default()
+/// };
+/// assert!(attributes.validate().is_ok());
+/// let attributes: Vec<_> = attributes.into_iter().collect();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HttpServerAttributes {
+    /// HTTP request method, see [`HTTP_METHOD`].
+    pub method: String,
+    /// The full request target as passed in a HTTP request line or equivalent,
+    /// see [`HTTP_TARGET`].
+    pub target: String,
+    /// The matched route (path template), see [`HTTP_ROUTE`].
+    pub route: Option<String>,
+    /// The URI scheme identifying the used protocol, see [`HTTP_SCHEME`].
+    pub scheme: Option<String>,
+    /// The value of the [HTTP host
+    /// header](https://tools.ietf.org/html/rfc7230#section-5.4), see
+    /// [`HTTP_HOST`].
+    pub host: Option<String>,
+    /// The primary server name of the matched virtual host, see
+    /// [`HTTP_SERVER_NAME`].
+    pub server_name: Option<String>,
+    /// Kind of HTTP protocol used, see [`HTTP_FLAVOR`].
+    pub flavor: Option<HttpFlavor>,
+    /// [HTTP response status
+    /// code](https://tools.ietf.org/html/rfc7231#section-6), see
+    /// [`HTTP_STATUS_CODE`].
+    pub status_code: Option<u16>,
+    /// The IP address of the original client behind all proxies, if known (e.g.
+    /// from
+    /// [X-Forwarded-For](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/X-Forwarded-For)),
+    /// see [`HTTP_CLIENT_IP`].
+    pub client_ip: Option<String>,
+    /// Value of the [HTTP
+    /// User-Agent](https://tools.ietf.org/html/rfc7231#section-5.5.3) header
+    /// sent by the client, see [`HTTP_USER_AGENT`].
+    pub user_agent: Option<String>,
+}
+
+impl HttpServerAttributes {
+    /// Check that the required attributes are set and the values are valid.
+    pub fn validate(&self) -> Result<(), AttributeError> {
+        validate_required(HTTP_METHOD, &self.method)?;
+        validate_method(&self.method)?;
+        validate_required(HTTP_TARGET, &self.target)?;
+        validate_target(&self.target)?;
+        if let Some(value) = self.route.as_deref() {
+            validate_route(value)?;
+        }
+        if let Some(value) = self.status_code {
+            validate_status_code(value)?;
+        }
+        Ok(())
+    }
+}
+
+impl IntoIterator for HttpServerAttributes {
+    type Item = KeyValue;
+    type IntoIter = vec::IntoIter<KeyValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let mut attributes = vec![
+            HTTP_METHOD.string(self.method),
+            HTTP_TARGET.string(self.target),
+        ];
+        if let Some(value) = self.route {
+            attributes.push(HTTP_ROUTE.string(value));
+        }
+        if let Some(value) = self.scheme {
+            attributes.push(HTTP_SCHEME.string(value));
+        }
+        if let Some(value) = self.host {
+            attributes.push(HTTP_HOST.string(value));
+        }
+        if let Some(value) = self.server_name {
+            attributes.push(HTTP_SERVER_NAME.string(value));
+        }
+        if let Some(value) = self.flavor {
+            attributes.push(HTTP_FLAVOR.string(value.as_str()));
+        }
+        if let Some(value) = self.status_code {
+            attributes.push(HTTP_STATUS_CODE.i64(value.into()));
+        }
+        if let Some(value) = self.client_ip {
+            attributes.push(HTTP_CLIENT_IP.string(value));
+        }
+        if let Some(value) = self.user_agent {
+            attributes.push(HTTP_USER_AGENT.string(value));
+        }
+        attributes.into_iter()
+    }
+}
+
+/// The attributes of a span of an HTTP client sending a request.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HttpClientAttributes {
+    /// HTTP request method, see [`HTTP_METHOD`].
+    pub method: String,
+    /// Full HTTP request URL in the form
+    /// `scheme://host[:port]/path?query[#fragment]`, see [`HTTP_URL`].
+    pub url: String,
+    /// Kind of HTTP protocol used, see [`HTTP_FLAVOR`].
+    pub flavor: Option<HttpFlavor>,
+    /// [HTTP response status
+    /// code](https://tools.ietf.org/html/rfc7231#section-6), see
+    /// [`HTTP_STATUS_CODE`].
+    pub status_code: Option<u16>,
+    /// Value of the [HTTP
+    /// User-Agent](https://tools.ietf.org/html/rfc7231#section-5.5.3) header
+    /// sent by the client, see [`HTTP_USER_AGENT`].
+    pub user_agent: Option<String>,
+    /// Remote hostname or similar, see [`NET_PEER_NAME`].
+    pub peer_name: Option<String>,
+    /// Remote port number, see [`NET_PEER_PORT`].
+    pub peer_port: Option<u16>,
+}
+
+impl HttpClientAttributes {
+    /// Check that the required attributes are set and the values are valid.
+    pub fn validate(&self) -> Result<(), AttributeError> {
+        validate_required(HTTP_METHOD, &self.method)?;
+        validate_method(&self.method)?;
+        validate_required(HTTP_URL, &self.url)?;
+        validate_url(&self.url)?;
+        if let Some(value) = self.status_code {
+            validate_status_code(value)?;
+        }
+        Ok(())
+    }
+}
+
+impl IntoIterator for HttpClientAttributes {
+    type Item = KeyValue;
+    type IntoIter = vec::IntoIter<KeyValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let mut attributes = vec![HTTP_METHOD.string(self.method), HTTP_URL.string(self.url)];
+        if let Some(value) = self.flavor {
+            attributes.push(HTTP_FLAVOR.string(value.as_str()));
+        }
+        if let Some(value) = self.status_code {
+            attributes.push(HTTP_STATUS_CODE.i64(value.into()));
+        }
+        if let Some(value) = self.user_agent {
+            attributes.push(HTTP_USER_AGENT.string(value));
+        }
+        if let Some(value) = self.peer_name {
+            attributes.push(NET_PEER_NAME.string(value));
+        }
+        if let Some(value) = self.peer_port {
+            attributes.push(NET_PEER_PORT.i64(value.into()));
+        }
+        attributes.into_iter()
+    }
+}
+
+/// The attributes of a span of a gRPC call, on the client or the server.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GrpcAttributes {
+    /// The full name of the service being called, including its package name,
+    /// if applicable, see [`RPC_SERVICE`].
+    pub service: String,
+    /// The name of the method being called, must be equal to the $method part
+    /// in the span name, see [`RPC_METHOD`].
+    pub method: String,
+    /// The [numeric status
+    /// code](https://github.com/grpc/grpc/blob/v1.33.2/doc/statuscodes.md) of
+    /// the gRPC request, see [`RPC_GRPC_STATUS_CODE`].
+    pub grpc_status_code: Option<i32>,
+    /// Remote hostname or similar, see [`NET_PEER_NAME`].
+    pub peer_name: Option<String>,
+    /// Remote port number, see [`NET_PEER_PORT`].
+    pub peer_port: Option<u16>,
+}
+
+impl GrpcAttributes {
+    /// Check that the required attributes are set and the values are valid.
+    pub fn validate(&self) -> Result<(), AttributeError> {
+        validate_required(RPC_SERVICE, &self.service)?;
+        validate_required(RPC_METHOD, &self.method)?;
+        if let Some(value) = self.grpc_status_code {
+            validate_grpc_status_code(value)?;
+        }
+        Ok(())
+    }
+}
+
+impl IntoIterator for GrpcAttributes {
+    type Item = KeyValue;
+    type IntoIter = vec::IntoIter<KeyValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let mut attributes = vec![
+            RPC_SYSTEM.string("grpc"),
+            RPC_SERVICE.string(self.service),
+            RPC_METHOD.string(self.method),
+        ];
+        if let Some(value) = self.grpc_status_code {
+            attributes.push(RPC_GRPC_STATUS_CODE.i64(value.into()));
+        }
+        if let Some(value) = self.peer_name {
+            attributes.push(NET_PEER_NAME.string(value));
+        }
+        if let Some(value) = self.peer_port {
+            attributes.push(NET_PEER_PORT.i64(value.into()));
+        }
+        attributes.into_iter()
+    }
+}
+
+/// The attributes of a span of a database client call.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DbAttributes {
+    /// An identifier for the database management system (DBMS) product being
+    /// used, see [`DB_SYSTEM`].
+    pub system: String,
+    /// If no [tech-specific
+    /// attribute](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/trace/semantic_conventions/database.md#call-level-attributes-for-specific-technologies)
+    /// is defined, this attribute is used to report the name of the database
+    /// being accessed, see [`DB_NAME`].
+    pub name: Option<String>,
+    /// The database statement being executed, see [`DB_STATEMENT`].
+    pub statement: Option<String>,
+    /// The name of the operation being executed, see [`DB_OPERATION`].
+    pub operation: Option<String>,
+    /// Username for accessing the database, see [`DB_USER`].
+    pub user: Option<String>,
+    /// Remote hostname or similar, see [`NET_PEER_NAME`].
+    pub peer_name: Option<String>,
+    /// Remote port number, see [`NET_PEER_PORT`].
+    pub peer_port: Option<u16>,
+}
+
+impl DbAttributes {
+    /// Check that the required attributes are set and the values are valid.
+    pub fn validate(&self) -> Result<(), AttributeError> {
+        validate_required(DB_SYSTEM, &self.system)?;
+        Ok(())
+    }
+}
+
+impl IntoIterator for DbAttributes {
+    type Item = KeyValue;
+    type IntoIter = vec::IntoIter<KeyValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let mut attributes = vec![DB_SYSTEM.string(self.system)];
+        if let Some(value) = self.name {
+            attributes.push(DB_NAME.string(value));
+        }
+        if let Some(value) = self.statement {
+            attributes.push(DB_STATEMENT.string(value));
+        }
+        if let Some(value) = self.operation {
+            attributes.push(DB_OPERATION.string(value));
+        }
+        if let Some(value) = self.user {
+            attributes.push(DB_USER.string(value));
+        }
+        if let Some(value) = self.peer_name {
+            attributes.push(NET_PEER_NAME.string(value));
+        }
+        if let Some(value) = self.peer_port {
+            attributes.push(NET_PEER_PORT.i64(value.into()));
+        }
+        attributes.into_iter()
+    }
+}
+
+/// The attributes of a span of sending, receiving or processing a message.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MessagingAttributes {
+    /// A string identifying the messaging system, see [`MESSAGING_SYSTEM`].
+    pub system: String,
+    /// The message destination name, see [`MESSAGING_DESTINATION`].
+    pub destination: String,
+    /// The kind of message destination, see [`MESSAGING_DESTINATION_KIND`].
+    pub destination_kind: Option<MessagingDestinationKind>,
+    /// A boolean that is true if the message destination is temporary, see
+    /// [`MESSAGING_TEMP_DESTINATION`].
+    pub temp_destination: bool,
+    /// A string identifying the kind of message consumption as defined in the
+    /// [Operation
+    /// names](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/trace/semantic_conventions/messaging.md#operation-names)
+    /// section above, see [`MESSAGING_OPERATION`].
+    pub operation: Option<MessagingOperation>,
+    /// A value used by the messaging system as an identifier for the message,
+    /// represented as a string, see [`MESSAGING_MESSAGE_ID`].
+    pub message_id: Option<String>,
+    /// The [conversation
+    /// ID](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/trace/semantic_conventions/messaging.md#conversations)
+    /// identifying the conversation to which the message belongs, represented
+    /// as a string, see [`MESSAGING_CONVERSATION_ID`].
+    pub conversation_id: Option<String>,
+    /// The (uncompressed) size of the message payload in bytes, see
+    /// [`MESSAGING_MESSAGE_PAYLOAD_SIZE_BYTES`].
+    pub payload_size_bytes: Option<i64>,
+}
+
+impl MessagingAttributes {
+    /// Check that the required attributes are set and the values are valid.
+    pub fn validate(&self) -> Result<(), AttributeError> {
+        validate_required(MESSAGING_SYSTEM, &self.system)?;
+        validate_required(MESSAGING_DESTINATION, &self.destination)?;
+        Ok(())
+    }
+}
+
+impl IntoIterator for MessagingAttributes {
+    type Item = KeyValue;
+    type IntoIter = vec::IntoIter<KeyValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let mut attributes = vec![
+            MESSAGING_SYSTEM.string(self.system),
+            MESSAGING_DESTINATION.string(self.destination),
+        ];
+        if let Some(value) = self.destination_kind {
+            attributes.push(MESSAGING_DESTINATION_KIND.string(value.as_str()));
+        }
+        if self.temp_destination {
+            attributes.push(MESSAGING_TEMP_DESTINATION.bool(true));
+        }
+        if let Some(value) = self.operation {
+            attributes.push(MESSAGING_OPERATION.string(value.as_str()));
+        }
+        if let Some(value) = self.message_id {
+            attributes.push(MESSAGING_MESSAGE_ID.string(value));
+        }
+        if let Some(value) = self.conversation_id {
+            attributes.push(MESSAGING_CONVERSATION_ID.string(value));
+        }
+        if let Some(value) = self.payload_size_bytes {
+            attributes.push(MESSAGING_MESSAGE_PAYLOAD_SIZE_BYTES.i64(value));
+        }
+        attributes.into_iter()
+    }
+}
@@ -2,8 +2,14 @@ mod model;
 mod uploader;
 
 use async_trait::async_trait;
-use http::Uri;
 use model::endpoint::Endpoint;
+use model::span::Span;
+#[cfg(any(
+    feature = "reqwest-blocking-client",
+    feature = "reqwest-client",
+    feature = "surf-client"
+))]
+use opentelemetry::sdk::export::trace::factory::ExporterFactory;
 use opentelemetry::{
     global, sdk,
     sdk::export::{trace, ExportError},
@@ -26,14 +32,121 @@ pub struct Exporter {
 }
 
 impl Exporter {
-    fn new(local_endpoint: Endpoint, client: Box<dyn HttpClient>, collector_endpoint: Uri) -> Self {
+    fn new(local_endpoint: Endpoint, uploader: uploader::Uploader) -> Self {
         Exporter {
             local_endpoint,
-            uploader: uploader::Uploader::new(client, collector_endpoint),
+            uploader,
         }
     }
 }
 
+/// Encoding of the spans sent to Zipkin.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Encoding {
+    /// JSON list of spans in the Zipkin v2 format, the default.
+    Json,
+    /// Proto3 encoded `ListOfSpans`, more compact and cheaper to produce than
+    /// JSON. Requires the `proto` feature.
+    #[cfg(feature = "proto")]
+    Proto3,
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Encoding::Json
+    }
+}
+
+impl Encoding {
+    /// The media type of spans with this encoding.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Encoding::Json => "application/json",
+            #[cfg(feature = "proto")]
+            Encoding::Proto3 => "application/x-protobuf",
+        }
+    }
+
+    fn encode(&self, spans: Vec<Span>) -> Vec<u8> {
+        match self {
+            Encoding::Json => serde_json::to_vec(&spans).unwrap_or_default(),
+            #[cfg(feature = "proto")]
+            Encoding::Proto3 => {
+                use prost::Message;
+
+                let spans = model::proto::ListOfSpans {
+                    spans: spans.into_iter().map(Into::into).collect(),
+                };
+                let mut payload = Vec::with_capacity(spans.encoded_len());
+                spans
+                    .encode(&mut payload)
+                    .expect("vec should grow to fit the spans");
+                payload
+            }
+        }
+    }
+}
+
+/// Sends encoded spans to Zipkin, replacing the HTTP collector.
+///
+/// Zipkin also collects spans from message brokers, e.g. Kafka, RabbitMQ or
+/// ActiveMQ. Implement this trait to publish the spans with the client of the
+/// broker instead of posting them to the collector endpoint.
+///
+/// # Examples
+///
+/// ```ignore
+/// use async_trait::async_trait;
+/// use opentelemetry::sdk::export::trace::ExportResult;
+/// use opentelemetry_zipkin::{Encoding, Transport};
+///
+/// #[derive(Debug)]
+/// struct KafkaTransport {
+///     producer: kafka::Producer,
+/// }
+///
+/// #[async_trait]
+/// impl Transport for KafkaTransport {
+///     async fn send(&self, payload: Vec<u8>, _encoding: Encoding) -> ExportResult {
+///         // Zipkin reads spans from the `zipkin` topic by default
+///         self.producer
+///             .send("zipkin", payload)
+///             .await
+///             .map_err(|err| opentelemetry_zipkin::Error::Other(err.to_string()).into())
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait Transport: std::fmt::Debug + Send + Sync {
+    /// Send a batch of spans, encoded with `encoding`.
+    async fn send(&self, payload: Vec<u8>, encoding: Encoding) -> trace::ExportResult;
+}
+
+/// Register the `zipkin` span exporter with `factory`.
+///
+/// The exporter sends spans to the default collector endpoint with the
+/// default HTTP client of the enabled client feature.
+#[cfg(any(
+    feature = "reqwest-blocking-client",
+    feature = "reqwest-client",
+    feature = "surf-client"
+))]
+pub fn register_exporter(factory: ExporterFactory) -> ExporterFactory {
+    factory.with_exporter("zipkin", || Ok(Box::new(new_pipeline().init_exporter()?)))
+}
+
+/// Create an [`ExporterFactory`] providing the `zipkin` span exporter in
+/// addition to the exporters of the SDK.
+#[cfg(any(
+    feature = "reqwest-blocking-client",
+    feature = "reqwest-client",
+    feature = "surf-client"
+))]
+pub fn exporter_factory() -> ExporterFactory {
+    register_exporter(ExporterFactory::new())
+}
+
 /// Create a new Zipkin exporter pipeline builder.
 pub fn new_pipeline() -> ZipkinPipelineBuilder {
     ZipkinPipelineBuilder::default()
@@ -47,6 +160,8 @@ pub struct ZipkinPipelineBuilder {
     collector_endpoint: String,
     trace_config: Option<sdk::trace::Config>,
     client: Option<Box<dyn HttpClient>>,
+    transport: Option<Box<dyn Transport>>,
+    encoding: Encoding,
 }
 
 impl Default for ZipkinPipelineBuilder {
@@ -77,6 +192,8 @@ impl Default for ZipkinPipelineBuilder {
             service_addr: None,
             collector_endpoint: DEFAULT_COLLECTOR_ENDPOINT.to_string(),
             trace_config: None,
+            transport: None,
+            encoding: Encoding::default(),
         }
     }
 }
@@ -84,30 +201,38 @@ impl Default for ZipkinPipelineBuilder {
 impl ZipkinPipelineBuilder {
     /// Create `ExporterConfig` struct from current `ExporterConfigBuilder`
     pub fn install(mut self) -> Result<sdk::trace::Tracer, TraceError> {
-        if let Some(client) = self.client {
-            let endpoint = Endpoint::new(self.service_name, self.service_addr);
-            let exporter = Exporter::new(
-                endpoint,
-                client,
-                self.collector_endpoint
-                    .parse()
-                    .map_err::<Error, _>(Into::into)?,
-            );
-
-            let mut provider_builder =
-                sdk::trace::TracerProvider::builder().with_exporter(exporter);
-            if let Some(config) = self.trace_config.take() {
-                provider_builder = provider_builder.with_config(config);
-            }
-            let provider = provider_builder.build();
-            let tracer =
-                provider.get_tracer("opentelemetry-zipkin", Some(env!("CARGO_PKG_VERSION")));
-            let _ = global::set_tracer_provider(provider);
+        let trace_config = self.trace_config.take();
+        let exporter = self.init_exporter()?;
 
-            Ok(tracer)
-        } else {
-            Err(Error::NoHttpClient.into())
+        let mut provider_builder = sdk::trace::TracerProvider::builder().with_exporter(exporter);
+        if let Some(config) = trace_config {
+            provider_builder = provider_builder.with_config(config);
         }
+        let provider = provider_builder.build();
+        let tracer = provider.get_tracer("opentelemetry-zipkin", Some(env!("CARGO_PKG_VERSION")));
+        let _ = global::set_tracer_provider(provider);
+
+        Ok(tracer)
+    }
+
+    /// Initialize a new exporter.
+    ///
+    /// This is useful if you are manually constructing a pipeline.
+    pub fn init_exporter(self) -> Result<Exporter, TraceError> {
+        let uploader = if let Some(transport) = self.transport {
+            uploader::Uploader::with_transport(transport, self.encoding)
+        } else if let Some(client) = self.client {
+            let collector_endpoint = self
+                .collector_endpoint
+                .parse()
+                .map_err::<Error, _>(Into::into)?;
+            uploader::Uploader::new(client, collector_endpoint, self.encoding)
+        } else {
+            return Err(Error::NoHttpClient.into());
+        };
+        let endpoint = Endpoint::new(self.service_name, self.service_addr);
+
+        Ok(Exporter::new(endpoint, uploader))
     }
 
     /// Assign the service name under which to group traces.
@@ -134,6 +259,19 @@ impl ZipkinPipelineBuilder {
         self
     }
 
+    /// Assign the encoding of the spans, JSON by default.
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Send spans through a custom transport, e.g. a message broker, instead
+    /// of the HTTP client and collector endpoint.
+    pub fn with_transport<T: Transport + 'static>(mut self, transport: T) -> Self {
+        self.transport = Some(Box::new(transport));
+        self
+    }
+
     /// Assign the SDK trace configuration.
     pub fn with_trace_config(mut self, config: sdk::trace::Config) -> Self {
         self.trace_config = Some(config);
@@ -180,3 +318,13 @@ impl ExportError for Error {
         "zipkin"
     }
 }
+
+#[cfg(all(test, feature = "reqwest-blocking-client"))]
+mod tests {
+    #[test]
+    fn exporter_factory_provides_zipkin() {
+        let factory = crate::exporter_factory();
+        assert_eq!(factory.names(), vec!["stdout", "zipkin"]);
+        assert_eq!(factory.build("zipkin").unwrap().len(), 1);
+    }
+}
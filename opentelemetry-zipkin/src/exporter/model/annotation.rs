@@ -30,6 +30,16 @@ impl From<Event> for Annotation {
     }
 }
 
+#[cfg(feature = "proto")]
+impl From<Annotation> for super::proto::Annotation {
+    fn from(annotation: Annotation) -> Self {
+        super::proto::Annotation {
+            timestamp: annotation.timestamp.unwrap_or_default(),
+            value: annotation.value.unwrap_or_default(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::exporter::model::annotation::Annotation;
@@ -8,6 +8,8 @@ use std::time::{Duration, SystemTime};
 
 pub(crate) mod annotation;
 pub(crate) mod endpoint;
+#[cfg(feature = "proto")]
+pub(crate) mod proto;
 pub(crate) mod span;
 
 use endpoint::Endpoint;
@@ -42,6 +44,7 @@ fn into_zipkin_span_kind(kind: SpanKind) -> Option<span::Kind> {
 /// be ingested into a Zipkin collector.
 pub(crate) fn into_zipkin_span(local_endpoint: Endpoint, span_data: trace::SpanData) -> span::Span {
     // see tests in create/exporter/model/span.rs
+    let duration = span_data.duration();
     let mut user_defined_span_kind = false;
     let mut tags = map_from_kvs(
         span_data
@@ -80,7 +83,7 @@ pub(crate) fn into_zipkin_span(local_endpoint: Endpoint, span_data: trace::SpanD
         .trace_id(span_data.span_context.trace_id().to_hex())
         .parent_id(span_data.parent_span_id.to_hex())
         .id(span_data.span_context.span_id().to_hex())
-        .name(span_data.name)
+        .name(span_data.name.into_owned())
         .kind(if user_defined_span_kind {
             None
         } else {
@@ -93,13 +96,7 @@ pub(crate) fn into_zipkin_span(local_endpoint: Endpoint, span_data: trace::SpanD
                 .unwrap_or_else(|_| Duration::from_secs(0))
                 .as_micros() as u64,
         )
-        .duration(
-            span_data
-                .end_time
-                .duration_since(span_data.start_time)
-                .unwrap_or_else(|_| Duration::from_secs(0))
-                .as_micros() as u64,
-        )
+        .duration(duration.as_micros() as u64)
         .local_endpoint(local_endpoint)
         .annotations(
             span_data
@@ -0,0 +1,98 @@
+//! Zipkin proto3 model, see [zipkin.proto].
+//!
+//! [zipkin.proto]: https://github.com/openzipkin/zipkin-api/blob/master/zipkin.proto
+use std::collections::HashMap;
+
+/// `zipkin.proto3.ListOfSpans`, the body of a proto3 encoded request.
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct ListOfSpans {
+    #[prost(message, repeated, tag = "1")]
+    pub(crate) spans: Vec<Span>,
+}
+
+/// `zipkin.proto3.Span`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct Span {
+    #[prost(bytes, tag = "1")]
+    pub(crate) trace_id: Vec<u8>,
+    #[prost(bytes, tag = "2")]
+    pub(crate) parent_id: Vec<u8>,
+    #[prost(bytes, tag = "3")]
+    pub(crate) id: Vec<u8>,
+    #[prost(enumeration = "Kind", tag = "4")]
+    pub(crate) kind: i32,
+    #[prost(string, tag = "5")]
+    pub(crate) name: String,
+    #[prost(fixed64, tag = "6")]
+    pub(crate) timestamp: u64,
+    #[prost(uint64, tag = "7")]
+    pub(crate) duration: u64,
+    #[prost(message, optional, tag = "8")]
+    pub(crate) local_endpoint: Option<Endpoint>,
+    #[prost(message, optional, tag = "9")]
+    pub(crate) remote_endpoint: Option<Endpoint>,
+    #[prost(message, repeated, tag = "10")]
+    pub(crate) annotations: Vec<Annotation>,
+    #[prost(map = "string, string", tag = "11")]
+    pub(crate) tags: HashMap<String, String>,
+    #[prost(bool, tag = "12")]
+    pub(crate) debug: bool,
+    #[prost(bool, tag = "13")]
+    pub(crate) shared: bool,
+}
+
+/// `zipkin.proto3.Span.Kind`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub(crate) enum Kind {
+    Unspecified = 0,
+    Client = 1,
+    Server = 2,
+    Producer = 3,
+    Consumer = 4,
+}
+
+/// `zipkin.proto3.Endpoint`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct Endpoint {
+    #[prost(string, tag = "1")]
+    pub(crate) service_name: String,
+    #[prost(bytes, tag = "2")]
+    pub(crate) ipv4: Vec<u8>,
+    #[prost(bytes, tag = "3")]
+    pub(crate) ipv6: Vec<u8>,
+    #[prost(int32, tag = "4")]
+    pub(crate) port: i32,
+}
+
+/// `zipkin.proto3.Annotation`
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct Annotation {
+    #[prost(fixed64, tag = "1")]
+    pub(crate) timestamp: u64,
+    #[prost(string, tag = "2")]
+    pub(crate) value: String,
+}
+
+/// Decode the lower hex ids of the JSON model, ids are sent as raw bytes.
+pub(crate) fn id_bytes(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .filter_map(|i| hex.get(i..i + 2))
+        .filter_map(|byte| u8::from_str_radix(byte, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_hex_ids() {
+        assert_eq!(
+            id_bytes("00f067aa0ba902b7"),
+            vec![0, 0xf0, 0x67, 0xaa, 0x0b, 0xa9, 0x02, 0xb7]
+        );
+        assert_eq!(id_bytes(""), Vec::<u8>::new());
+    }
+}
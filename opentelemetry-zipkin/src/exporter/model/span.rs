@@ -53,6 +53,44 @@ pub(crate) struct Span {
     shared: bool,
 }
 
+#[cfg(feature = "proto")]
+impl From<Span> for super::proto::Span {
+    fn from(span: Span) -> Self {
+        use super::proto;
+
+        let id_bytes = |id: Option<String>| id.map(|id| proto::id_bytes(&id)).unwrap_or_default();
+        let kind = match span.kind {
+            Some(Kind::Client) => proto::Kind::Client,
+            Some(Kind::Server) => proto::Kind::Server,
+            Some(Kind::Producer) => proto::Kind::Producer,
+            Some(Kind::Consumer) => proto::Kind::Consumer,
+            None => proto::Kind::Unspecified,
+        };
+
+        proto::Span {
+            trace_id: id_bytes(span.trace_id),
+            // root spans have an all zero parent id
+            parent_id: id_bytes(span.parent_id.filter(|id| id.bytes().any(|b| b != b'0'))),
+            id: id_bytes(span.id),
+            kind: kind as i32,
+            name: span.name.unwrap_or_default(),
+            timestamp: span.timestamp.unwrap_or_default(),
+            duration: span.duration.unwrap_or_default(),
+            local_endpoint: span.local_endpoint.map(Into::into),
+            remote_endpoint: span.remote_endpoint.map(Into::into),
+            annotations: span
+                .annotations
+                .unwrap_or_default()
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            tags: span.tags.unwrap_or_default(),
+            debug: span.debug,
+            shared: span.shared,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::exporter::model::annotation::Annotation;
@@ -114,6 +152,45 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "proto")]
+    #[test]
+    fn test_proto_conversion() {
+        use crate::exporter::model::proto;
+        use prost::Message;
+
+        let span: proto::Span = Span::builder()
+            .trace_id("4e441824ec2b6a44ffdc9bb9a6453df3".to_owned())
+            .parent_id("0000000000000000".to_owned())
+            .id("efdc9cd9a1849df3".to_owned())
+            .kind(Some(Kind::Client))
+            .name("main".to_owned())
+            .local_endpoint(
+                Endpoint::builder()
+                    .ipv4(Ipv4Addr::new(192, 168, 0, 1))
+                    .port(8080)
+                    .build(),
+            )
+            .build()
+            .into();
+
+        assert_eq!(span.trace_id.len(), 16);
+        assert_eq!(span.trace_id[0], 0x4e);
+        assert!(span.parent_id.is_empty(), "root spans have no parent id");
+        assert_eq!(span.id.len(), 8);
+        assert_eq!(span.kind, proto::Kind::Client as i32);
+        let local_endpoint = span.local_endpoint.clone().unwrap();
+        assert_eq!(local_endpoint.ipv4, vec![192, 168, 0, 1]);
+        assert_eq!(local_endpoint.port, 8080);
+
+        let list = proto::ListOfSpans { spans: vec![span] };
+        let mut payload = Vec::new();
+        list.encode(&mut payload).unwrap();
+        assert_eq!(
+            proto::ListOfSpans::decode(payload.as_slice()).unwrap(),
+            list
+        );
+    }
+
     fn test_json_serialization(span: Span, desired: &str) {
         let result = serde_json::to_string(&span).unwrap();
         assert_eq!(result, desired.to_owned());
@@ -170,7 +247,7 @@ mod tests {
                 ),
                 parent_span_id: SpanId::from_u64(1),
                 span_kind: SpanKind::Client,
-                name: "".to_string(),
+                name: "".into(),
                 start_time: SystemTime::now(),
                 end_time: SystemTime::now(),
                 attributes: EvictedHashMap::new(20, 20),
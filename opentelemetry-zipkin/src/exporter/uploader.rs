@@ -1,6 +1,6 @@
 //! # Zipkin Span Exporter
 use crate::exporter::model::span::Span;
-use crate::exporter::Error;
+use crate::exporter::{Encoding, Error, Transport};
 use http::{header::CONTENT_TYPE, Method, Request, Uri};
 use opentelemetry::sdk::export::trace::ExportResult;
 use opentelemetry_http::HttpClient;
@@ -8,15 +8,29 @@ use std::fmt::Debug;
 
 #[derive(Debug)]
 pub(crate) enum Uploader {
-    Http(JsonV2Client),
+    Http(HttpUploader),
+    Transport(TransportUploader),
 }
 
 impl Uploader {
     /// Create a new http uploader
-    pub(crate) fn new(client: Box<dyn HttpClient>, collector_endpoint: Uri) -> Self {
-        Uploader::Http(JsonV2Client {
+    pub(crate) fn new(
+        client: Box<dyn HttpClient>,
+        collector_endpoint: Uri,
+        encoding: Encoding,
+    ) -> Self {
+        Uploader::Http(HttpUploader {
             client,
             collector_endpoint,
+            encoding,
+        })
+    }
+
+    /// Create an uploader sending spans through a custom transport
+    pub(crate) fn with_transport(transport: Box<dyn Transport>, encoding: Encoding) -> Self {
+        Uploader::Transport(TransportUploader {
+            transport,
+            encoding,
         })
     }
 
@@ -24,24 +38,71 @@ impl Uploader {
     pub(crate) async fn upload(&self, spans: Vec<Span>) -> ExportResult {
         match self {
             Uploader::Http(client) => client.upload(spans).await,
+            Uploader::Transport(uploader) => {
+                let payload = uploader.encoding.encode(spans);
+                uploader.transport.send(payload, uploader.encoding).await
+            }
         }
     }
 }
 
 #[derive(Debug)]
-pub(crate) struct JsonV2Client {
+pub(crate) struct HttpUploader {
     client: Box<dyn HttpClient>,
     collector_endpoint: Uri,
+    encoding: Encoding,
 }
 
-impl JsonV2Client {
+impl HttpUploader {
     async fn upload(&self, spans: Vec<Span>) -> ExportResult {
         let req = Request::builder()
             .method(Method::POST)
             .uri(self.collector_endpoint.clone())
-            .header(CONTENT_TYPE, "application/json")
-            .body(serde_json::to_vec(&spans).unwrap_or_default())
+            .header(CONTENT_TYPE, self.encoding.content_type())
+            .body(self.encoding.encode(spans))
             .map_err::<Error, _>(Into::into)?;
-        self.client.send(req).await
+        self.client.send(req).await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct TransportUploader {
+    transport: Box<dyn Transport>,
+    encoding: Encoding,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Default)]
+    struct RecordingTransport(Arc<Mutex<Vec<(Vec<u8>, Encoding)>>>);
+
+    #[async_trait]
+    impl Transport for RecordingTransport {
+        async fn send(&self, payload: Vec<u8>, encoding: Encoding) -> ExportResult {
+            self.0.lock().unwrap().push((payload, encoding));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn upload_through_transport() {
+        let transport = RecordingTransport::default();
+        let sent = transport.0.clone();
+        let uploader = Uploader::with_transport(Box::new(transport), Encoding::Json);
+
+        futures::executor::block_on(uploader.upload(vec![Span::builder().build()])).unwrap();
+
+        assert_eq!(
+            *sent.lock().unwrap(),
+            vec![(
+                b"[{\"debug\":false,\"shared\":false}]".to_vec(),
+                Encoding::Json
+            )]
+        );
     }
 }
@@ -71,6 +71,21 @@
 //!
 //! [`HttpClient`]: https://docs.rs/opentelemetry/0.10/opentelemetry/exporter/trace/trait.HttpClient.html
 //!
+//! ## Encoding and Transport
+//!
+//! Spans are sent as JSON by default. With the `proto` feature enabled, the
+//! more compact proto3 encoding can be selected with
+//! [`ZipkinPipelineBuilder::with_encoding`]. To publish spans to a message
+//! broker such as Kafka instead of the HTTP collector, pass an implementation
+//! of the [`Transport`] trait to [`ZipkinPipelineBuilder::with_transport`].
+//!
+//! ```ignore
+//! let tracer = opentelemetry_zipkin::new_pipeline()
+//!     .with_encoding(opentelemetry_zipkin::Encoding::Proto3)
+//!     .with_transport(KafkaTransport::new("kafka:9092"))
+//!     .install()?;
+//! ```
+//!
 //! ## Kitchen Sink Full Configuration
 //!
 //! Example showing how to override all configuration options. See the
@@ -80,7 +95,7 @@
 //! ```no_run
 //! use opentelemetry::{KeyValue, trace::Tracer};
 //! use opentelemetry::sdk::{trace::{self, IdGenerator, Sampler}, Resource};
-//! use opentelemetry::sdk::export::trace::ExportResult;
+//! use opentelemetry::trace::TraceError;
 //! use opentelemetry::global;
 //! use opentelemetry_http::HttpClient;
 //! use async_trait::async_trait;
@@ -94,11 +109,12 @@
 //!
 //! #[async_trait]
 //! impl HttpClient for IsahcClient {
-//!   async fn send(&self, request: http::Request<Vec<u8>>) -> ExportResult {
+//!   async fn send(&self, request: http::Request<Vec<u8>>) -> Result<http::Response<Vec<u8>>, TraceError> {
 //!     let result = self.0.send_async(request).await.map_err(|err| opentelemetry_zipkin::Error::Other(err.to_string()))?;
 //!
 //!     if result.status().is_success() {
-//!       Ok(())
+//!       // the response body is not needed to export spans
+//!       Ok(result.map(|_body| Vec::new()))
 //!     } else {
 //!       Err(opentelemetry_zipkin::Error::Other(result.status().to_string()).into())
 //!     }
@@ -141,6 +157,7 @@
 //!   client (enabled by default).
 //! * `reqwest-client`: Export spans using the reqwest non-blocking http client.
 //! * `surf-client`: Export spans using the surf non-blocking http client.
+//! * `proto`: Support the proto3 [`Encoding`] of spans.
 //!
 //! ## Supported Rust Versions
 //!
@@ -176,5 +193,11 @@ extern crate typed_builder;
 mod exporter;
 mod propagator;
 
-pub use exporter::{new_pipeline, Error, Exporter, ZipkinPipelineBuilder};
+#[cfg(any(
+    feature = "reqwest-blocking-client",
+    feature = "reqwest-client",
+    feature = "surf-client"
+))]
+pub use exporter::{exporter_factory, register_exporter};
+pub use exporter::{new_pipeline, Encoding, Error, Exporter, Transport, ZipkinPipelineBuilder};
 pub use propagator::{B3Encoding, Propagator};
@@ -0,0 +1,90 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use opentelemetry::{
+    global, runtime,
+    sdk::{
+        export::trace::SpanData,
+        trace::{self as sdktrace, EvictedHashMap, EvictedQueue, SpanProcessor},
+        InstrumentationLibrary, Resource,
+    },
+    trace::{NoopSpanExporter, SpanContext, SpanId, SpanKind, StatusCode, TraceId, TraceState},
+};
+use std::sync::Arc;
+use std::thread;
+use std::time::SystemTime;
+
+const THREADS: usize = 4;
+const SPANS_PER_THREAD: usize = 10_000;
+
+fn criterion_benchmark(c: &mut Criterion) {
+    // spans dropped by full queues are reported as errors
+    let _ = global::set_error_handler(|_| {});
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(THREADS)
+        .enable_all()
+        .build()
+        .unwrap();
+    let _guard = runtime.enter();
+
+    let mut group = c.benchmark_group("BatchSpanProcessor");
+    group.throughput(Throughput::Elements((THREADS * SPANS_PER_THREAD) as u64));
+    for shards in &[1, 2, 4, 8] {
+        let processor =
+            sdktrace::BatchSpanProcessor::builder(NoopSpanExporter::new(), runtime::Tokio)
+                .with_max_queue_size(THREADS * SPANS_PER_THREAD)
+                .with_max_export_batch_size(2048)
+                .build_sharded(*shards);
+        let processor = Arc::new(processor);
+
+        group.bench_with_input(
+            BenchmarkId::new("end-spans-sharded", shards),
+            &processor,
+            |b, processor| b.iter(|| end_spans_concurrently(processor)),
+        );
+    }
+    group.finish();
+}
+
+/// End spans from several threads and wait until the processor exported them.
+fn end_spans_concurrently(processor: &Arc<sdktrace::ShardedSpanProcessor>) {
+    let threads: Vec<_> = (0..THREADS)
+        .map(|thread| {
+            let processor = processor.clone();
+            thread::spawn(move || {
+                for i in 0..SPANS_PER_THREAD {
+                    processor.on_end(Arc::new(span_data((thread * SPANS_PER_THREAD + i) as u128)));
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    processor.force_flush().unwrap();
+}
+
+fn span_data(trace_id: u128) -> SpanData {
+    SpanData {
+        span_context: SpanContext::new(
+            TraceId::from_u128(trace_id),
+            SpanId::from_u64(1),
+            0,
+            false,
+            TraceState::default(),
+        ),
+        parent_span_id: SpanId::from_u64(0),
+        span_kind: SpanKind::Internal,
+        name: "foo".into(),
+        start_time: SystemTime::now(),
+        end_time: SystemTime::now(),
+        attributes: EvictedHashMap::new(32, 0),
+        message_events: EvictedQueue::new(0),
+        links: EvictedQueue::new(0),
+        status_code: StatusCode::Unset,
+        status_message: String::new(),
+        resource: Arc::new(Resource::default()),
+        instrumentation_lib: InstrumentationLibrary::new("batch-bench", None),
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,42 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use opentelemetry::Context;
+
+#[derive(Debug, PartialEq)]
+struct ValueA(u64);
+
+#[derive(Debug, PartialEq)]
+struct ValueB(&'static str);
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Context");
+    group.bench_function("current", |b| b.iter(Context::current));
+    group.bench_function("with-value", |b| {
+        let cx = Context::new().with_value(ValueA(1));
+        b.iter(|| cx.with_value(ValueB("b")))
+    });
+    group.bench_function("get-value", |b| {
+        let cx = Context::new().with_value(ValueA(1)).with_value(ValueB("b"));
+        b.iter(|| cx.get::<ValueA>().is_some())
+    });
+    group.bench_function("attach-detach", |b| {
+        let cx = Context::new().with_value(ValueA(1));
+        b.iter(|| drop(cx.clone().attach()))
+    });
+    group.bench_function("attach-detach-nested-4", |b| {
+        let cx = Context::new().with_value(ValueA(1));
+        b.iter(|| {
+            let _a = cx.clone().attach();
+            let _b = cx.clone().attach();
+            let _c = cx.clone().attach();
+            let _d = cx.clone().attach();
+        })
+    });
+    group.bench_function("current-with-value-attach", |b| {
+        let _outer = Context::new().with_value(ValueA(1)).attach();
+        b.iter(|| drop(Context::current_with_value(ValueB("b")).attach()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,107 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use opentelemetry::{
+    baggage::BaggageExt,
+    propagation::TextMapPropagator,
+    sdk::propagation::{BaggagePropagator, TextMapCompositePropagator, TraceContextPropagator},
+    trace::{
+        NoopTracer, SpanContext, SpanId, TraceContextExt, TraceId, Tracer, TRACE_FLAG_SAMPLED,
+    },
+    Context, KeyValue,
+};
+use std::collections::HashMap;
+
+// fixed fixtures, so results are comparable across runs
+const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+const TRACESTATE: &str = "congo=t61rcWkgMzE,rojo=00f067aa0ba902b7";
+const BAGGAGE: &str = "user.id=1234,tenant=acme;tier=gold,request.origin=mobile%20app";
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let cx = context();
+    let headers = headers();
+
+    propagator_benchmark_group(
+        c,
+        "TraceContextPropagator",
+        TraceContextPropagator::new(),
+        &cx,
+        &headers,
+    );
+    propagator_benchmark_group(
+        c,
+        "BaggagePropagator",
+        BaggagePropagator::new(),
+        &cx,
+        &headers,
+    );
+    propagator_benchmark_group(
+        c,
+        "TextMapCompositePropagator",
+        TextMapCompositePropagator::new(vec![
+            Box::new(TraceContextPropagator::new()),
+            Box::new(BaggagePropagator::new()),
+        ]),
+        &cx,
+        &headers,
+    );
+}
+
+fn propagator_benchmark_group<P: TextMapPropagator>(
+    c: &mut Criterion,
+    name: &str,
+    propagator: P,
+    cx: &Context,
+    headers: &HashMap<String, String>,
+) {
+    let mut injected = HashMap::new();
+    propagator.inject_context(cx, &mut injected);
+    assert!(!injected.is_empty(), "{} injected nothing", name);
+
+    let mut group = c.benchmark_group(name);
+    group.bench_function("inject", |b| {
+        b.iter(|| {
+            let mut injector = HashMap::new();
+            propagator.inject_context(cx, &mut injector);
+            injector
+        })
+    });
+    group.bench_function("extract", |b| {
+        b.iter(|| propagator.extract_with_context(&Context::new(), headers))
+    });
+    group.bench_function("extract-empty", |b| {
+        let empty = HashMap::new();
+        b.iter(|| propagator.extract_with_context(&Context::new(), &empty))
+    });
+    group.finish();
+}
+
+/// A context with an active span and baggage, the no-op tracer keeps the fixed
+/// span context of its parent.
+fn context() -> Context {
+    let parent = Context::new().with_remote_span_context(SpanContext::new(
+        TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736),
+        SpanId::from_u64(0x00f0_67aa_0ba9_02b7),
+        TRACE_FLAG_SAMPLED,
+        true,
+        TRACESTATE.parse().unwrap(),
+    ));
+    let span = NoopTracer::new().start_with_context("foo", parent);
+    Context::new().with_span(span).with_baggage(vec![
+        KeyValue::new("user.id", "1234"),
+        KeyValue::new("tenant", "acme"),
+        KeyValue::new("request.origin", "mobile app"),
+    ])
+}
+
+fn headers() -> HashMap<String, String> {
+    let mut headers = HashMap::new();
+    headers.insert("traceparent".to_string(), TRACEPARENT.to_string());
+    headers.insert("tracestate".to_string(), TRACESTATE.to_string());
+    headers.insert("baggage".to_string(), BAGGAGE.to_string());
+    // unrelated headers, as found on real requests
+    headers.insert("content-type".to_string(), "application/json".to_string());
+    headers.insert("user-agent".to_string(), "bench/1.0".to_string());
+    headers
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
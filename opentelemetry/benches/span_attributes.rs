@@ -0,0 +1,127 @@
+//! Heap allocations of recording span attributes, counted by a global
+//! allocator. The counts are exact, so they are printed rather than sampled
+//! with criterion, see the `trace` benchmark for the timings.
+use criterion::black_box;
+use opentelemetry::{
+    sdk::{export::trace::SpanData, trace as sdktrace},
+    trace::{Span, TraceResult, Tracer, TracerProvider},
+    Context, Key, KeyValue,
+};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const ITERATIONS: usize = 10_000;
+
+const KEYS: [Key; 16] = [
+    Key::from_static_str("key1"),
+    Key::from_static_str("key2"),
+    Key::from_static_str("key3"),
+    Key::from_static_str("key4"),
+    Key::from_static_str("key5"),
+    Key::from_static_str("key6"),
+    Key::from_static_str("key7"),
+    Key::from_static_str("key8"),
+    Key::from_static_str("key9"),
+    Key::from_static_str("key10"),
+    Key::from_static_str("key11"),
+    Key::from_static_str("key12"),
+    Key::from_static_str("key13"),
+    Key::from_static_str("key14"),
+    Key::from_static_str("key15"),
+    Key::from_static_str("key16"),
+];
+
+/// Drops the ended spans, so they are recorded but not exported.
+#[derive(Debug)]
+struct DropProcessor;
+
+impl sdktrace::SpanProcessor for DropProcessor {
+    fn on_start(&self, _span: &sdktrace::Span, _cx: &Context) {}
+
+    fn on_end(&self, span: Arc<SpanData>) {
+        black_box(span);
+    }
+
+    fn force_flush(&self) -> TraceResult<()> {
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> TraceResult<()> {
+        Ok(())
+    }
+}
+
+/// Print the average number of allocations of `f`.
+fn count_allocations<F: FnMut()>(name: &str, mut f: F) {
+    // warm up, e.g. thread locals allocate on first use
+    f();
+    let start = ALLOCATIONS.load(Ordering::Relaxed);
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - start;
+    println!(
+        "{:<32} {:>6.2} allocs/iter",
+        name,
+        allocations as f64 / ITERATIONS as f64
+    );
+}
+
+fn main() {
+    for n in &[1, 4, 8, 16] {
+        count_allocations(&format!("EvictedHashMap/insert {}", n), || {
+            let mut map = sdktrace::EvictedHashMap::new(128, 0);
+            for (idx, key) in KEYS.iter().enumerate().take(*n) {
+                map.insert(KeyValue::new(key.clone(), idx as i64));
+            }
+            black_box(map);
+        });
+    }
+
+    // tracers don't keep their provider alive
+    let provider = sdktrace::TracerProvider::builder()
+        .with_config(sdktrace::config().with_default_sampler(sdktrace::Sampler::AlwaysOn))
+        .with_span_processor(DropProcessor)
+        .build();
+    let pooled_provider = sdktrace::TracerProvider::builder()
+        .with_config(sdktrace::config().with_default_sampler(sdktrace::Sampler::AlwaysOn))
+        .with_span_processor(DropProcessor)
+        .with_span_pool(16)
+        .build();
+    for (name, provider) in &[("start-end-span", &provider), ("pooled", &pooled_provider)] {
+        let tracer = provider.get_tracer("always-sample", None);
+        for n in &[0, 4, 8, 16] {
+            count_allocations(&format!("{}/{} attrs", name, n), || {
+                let span = tracer.start("foo");
+                for (idx, key) in KEYS.iter().enumerate().take(*n) {
+                    span.set_attribute(KeyValue::new(key.clone(), idx as i64));
+                }
+                span.end();
+            });
+        }
+    }
+}
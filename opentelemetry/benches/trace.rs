@@ -63,6 +63,35 @@ fn criterion_benchmark(c: &mut Criterion) {
         span.set_attribute(Key::new("key15").f64(123.456));
         span.end();
     });
+
+    let mut group = c.benchmark_group("start-end-span-owned-string-attrs");
+    group.bench_function("plain", |b| {
+        let tracer = sdktrace::TracerProvider::builder()
+            .with_config(sdktrace::config().with_default_sampler(sdktrace::Sampler::AlwaysOn))
+            .build()
+            .get_tracer("plain", None);
+        b.iter(|| set_owned_string_attrs(&tracer));
+    });
+    group.bench_function("interned", |b| {
+        let tracer = sdktrace::TracerProvider::builder()
+            .with_config(
+                sdktrace::config()
+                    .with_default_sampler(sdktrace::Sampler::AlwaysOn)
+                    .with_string_interner(sdktrace::StringInterner::new(16)),
+            )
+            .build()
+            .get_tracer("interned", None);
+        b.iter(|| set_owned_string_attrs(&tracer));
+    });
+    group.finish();
+}
+
+fn set_owned_string_attrs(tracer: &sdktrace::Tracer) {
+    let span = tracer.start("foo");
+    span.set_attribute(Key::new("http.method").string("GET".to_string()));
+    span.set_attribute(Key::new("http.flavor").string("1.1".to_string()));
+    span.set_attribute(Key::new("peer.service").string("backend".to_string()));
+    span.end();
 }
 
 const MAP_KEYS: [Key; 20] = [
@@ -59,6 +59,12 @@ const MAX_KEY_VALUE_PAIRS: usize = 180;
 const MAX_BYTES_FOR_ONE_PAIR: usize = 4096;
 const MAX_LEN_OF_ALL_PAIRS: usize = 8192;
 
+/// Maximum length of the encoded `baggage` header propagated by the
+/// [W3C Baggage] specification.
+///
+/// [W3C Baggage]: https://w3c.github.io/baggage/#limits
+pub const MAX_ENCODED_LEN: usize = 8192;
+
 /// A set of name-value pairs describing user-defined properties.
 ///
 /// ### Baggage Names
@@ -129,6 +135,71 @@ impl Baggage {
         self.inner.get(&key.into())
     }
 
+    /// Returns the string value associated with a given name.
+    ///
+    /// Values which are not strings are not converted, use [`get`] for those.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use opentelemetry::baggage::Baggage;
+    ///
+    /// let mut cc = Baggage::new();
+    /// let _ = cc.insert("user", "alice");
+    ///
+    /// assert_eq!(cc.get_str("user"), Some("alice"));
+    /// ```
+    ///
+    /// [`get`]: Baggage::get
+    pub fn get_str<T: Into<Key>>(&self, key: T) -> Option<&str> {
+        match self.get(key) {
+            Some(Value::String(value)) => Some(value.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Returns the boolean value associated with a given name, parsing string
+    /// values like the ones extracted from a `baggage` header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use opentelemetry::baggage::Baggage;
+    ///
+    /// let mut cc = Baggage::new();
+    /// let _ = cc.insert("synthetic", "true");
+    ///
+    /// assert_eq!(cc.get_bool("synthetic"), Some(true));
+    /// ```
+    pub fn get_bool<T: Into<Key>>(&self, key: T) -> Option<bool> {
+        match self.get(key) {
+            Some(Value::Bool(value)) => Some(*value),
+            Some(Value::String(value)) => value.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the integer value associated with a given name, parsing string
+    /// values like the ones extracted from a `baggage` header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use opentelemetry::baggage::Baggage;
+    ///
+    /// let mut cc = Baggage::new();
+    /// let _ = cc.insert("retries", "3");
+    ///
+    /// assert_eq!(cc.get_i64("retries"), Some(3));
+    /// ```
+    pub fn get_i64<T: Into<Key>>(&self, key: T) -> Option<i64> {
+        match self.get(key) {
+            Some(Value::I64(value)) => Some(*value),
+            Some(Value::String(value)) => value.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
     /// Inserts a name-value pair into the baggage.
     ///
     /// If the name was not present, [`None`] is returned. If the name was present,
@@ -187,10 +258,50 @@ impl Baggage {
         }
     }
 
+    /// Sets the value of a name, keeping the metadata of an existing entry.
+    ///
+    /// Unlike [`insert`], which resets the metadata of the entry, the value
+    /// can be updated without knowing the metadata it was received with. The
+    /// old value is returned if the name was present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use opentelemetry::{baggage::{Baggage, BaggageMetadata}, Value};
+    ///
+    /// let mut cc = Baggage::new();
+    /// let _ = cc.insert_with_metadata("my-name", "my-value", "test");
+    /// let _ = cc.set_value("my-name", "new-value");
+    ///
+    /// assert_eq!(cc.get_with_metadata("my-name"), Some(&(Value::from("new-value"), BaggageMetadata::from("test"))))
+    /// ```
+    ///
+    /// [`insert`]: Baggage::insert
+    pub fn set_value<K, V>(&mut self, key: K, value: V) -> Option<Value>
+    where
+        K: Into<Key>,
+        V: Into<Value>,
+    {
+        let key = key.into();
+        let metadata = self
+            .inner
+            .get(&key)
+            .map(|(_, metadata)| metadata.clone())
+            .unwrap_or_default();
+        self.insert_with_metadata(key, value, metadata)
+            .map(|pair| pair.0)
+    }
+
     /// Removes a name from the baggage, returning the value
     /// corresponding to the name if the pair was previously in the map.
     pub fn remove<K: Into<Key>>(&mut self, key: K) -> Option<(Value, BaggageMetadata)> {
-        self.inner.remove(&key.into())
+        let key = key.into();
+        let removed = self.inner.remove(&key);
+        if let Some((value, metadata)) = &removed {
+            self.kv_content_len -=
+                key.as_str().len() + value.as_str().len() + metadata.as_str().len();
+        }
+        removed
     }
 
     /// Returns the number of attributes for this baggage
@@ -208,6 +319,77 @@ impl Baggage {
         self.into_iter()
     }
 
+    /// Returns the length in bytes of this baggage once encoded as a W3C
+    /// `baggage` header value.
+    ///
+    /// Names and values are percent-encoded, so the encoded length can
+    /// exceed the limits checked on insertion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use opentelemetry::baggage::Baggage;
+    ///
+    /// let mut baggage = Baggage::new();
+    /// let _ = baggage.insert("user", "a b");
+    /// let _ = baggage.insert_with_metadata("tenant", "x", "internal");
+    ///
+    /// // "tenant=x;internal,user=a%20b"
+    /// assert_eq!(baggage.encoded_len(), 28);
+    /// ```
+    pub fn encoded_len(&self) -> usize {
+        let entries = self
+            .inner
+            .iter()
+            .map(|(key, (value, metadata))| encoded_entry_len(key, value, metadata))
+            .sum::<usize>();
+        // entries are separated by commas
+        entries + self.inner.len().saturating_sub(1)
+    }
+
+    /// Gets an iterator over the entries that do not fit into a header of
+    /// `max_len` encoded bytes.
+    ///
+    /// Entries are considered in name order and kept as long as they fit, so
+    /// removing the returned entries yields a baggage whose
+    /// [`encoded_len`] is at most `max_len`. This lets callers decide what to
+    /// drop or shorten before injecting the baggage, see [`MAX_ENCODED_LEN`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use opentelemetry::baggage::{Baggage, MAX_ENCODED_LEN};
+    ///
+    /// let mut baggage = Baggage::new();
+    /// let _ = baggage.insert("a", "1");
+    /// let _ = baggage.insert("b", "2");
+    ///
+    /// assert_eq!(baggage.over_limit(MAX_ENCODED_LEN).count(), 0);
+    ///
+    /// let over_limit: Vec<_> = baggage.over_limit(4).map(|(key, _)| key.as_str()).collect();
+    /// assert_eq!(over_limit, vec!["b"]);
+    /// ```
+    ///
+    /// [`encoded_len`]: Baggage::encoded_len
+    pub fn over_limit(&self, max_len: usize) -> OverLimit<'_> {
+        let mut entries = self.inner.iter().collect::<Vec<_>>();
+        entries.sort_by_key(|(key, _)| *key);
+
+        let mut len = 0;
+        let mut over_limit = Vec::new();
+        for (key, entry) in entries {
+            let separator = if len == 0 { 0 } else { 1 };
+            let entry_len = separator + encoded_entry_len(key, &entry.0, &entry.1);
+            if len + entry_len <= max_len {
+                len += entry_len;
+            } else {
+                over_limit.push((key, entry));
+            }
+        }
+
+        OverLimit(over_limit.into_iter())
+    }
+
     /// Determine whether the key value pair exceed one of the [limits](https://w3c.github.io/baggage/#limits).
     /// If not, update the total length of key values
     fn insertable(&mut self, key: &Key, value: &Value, metadata: &BaggageMetadata) -> bool {
@@ -263,6 +445,31 @@ fn key_value_metadata_bytes_size(key: &str, value: &str, metadata: &str) -> usiz
     key.bytes().len() + value.bytes().len() + metadata.bytes().len()
 }
 
+/// Get the number of bytes of one percent-encoded `name=value;metadata` entry
+fn encoded_entry_len(key: &Key, value: &Value, metadata: &BaggageMetadata) -> usize {
+    let metadata = metadata.as_str().trim();
+    let metadata_len = if metadata.is_empty() {
+        0
+    } else {
+        1 + metadata.len()
+    };
+    percent_encoded_len(key.as_str().trim())
+        + 1
+        + percent_encoded_len(value.as_str().trim())
+        + metadata_len
+}
+
+/// Get the length of `s` once control characters, non-ASCII bytes and the
+/// baggage delimiters are percent-encoded
+fn percent_encoded_len(s: &str) -> usize {
+    s.bytes()
+        .map(|b| match b {
+            0..=0x1f | 0x7f..=0xff | b' ' | b'"' | b';' | b',' | b'=' => 3,
+            _ => 1,
+        })
+        .sum()
+}
+
 /// An iterator over the entries of a [`Baggage`].
 #[derive(Debug)]
 pub struct Iter<'a>(hash_map::Iter<'a, Key, (Value, BaggageMetadata)>);
@@ -275,6 +482,20 @@ impl<'a> Iterator for Iter<'a> {
     }
 }
 
+/// An iterator over the entries of a [`Baggage`] exceeding an encoded length.
+///
+/// Created by [`Baggage::over_limit`].
+#[derive(Debug)]
+pub struct OverLimit<'a>(std::vec::IntoIter<(&'a Key, &'a (Value, BaggageMetadata))>);
+
+impl<'a> Iterator for OverLimit<'a> {
+    type Item = (&'a Key, &'a (Value, BaggageMetadata));
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
 impl<'a> IntoIterator for &'a Baggage {
     type Item = (&'a Key, &'a (Value, BaggageMetadata));
     type IntoIter = Iter<'a>;
@@ -367,6 +588,33 @@ pub trait BaggageExt {
     /// ```
     fn with_cleared_baggage(&self) -> Self;
 
+    /// Returns a clone of the given context with the value of a single name
+    /// set.
+    ///
+    /// The metadata of the entry is replaced with the given one, or kept if
+    /// it is `None`, so that entries received with metadata can be updated
+    /// without losing it. The metadata of the other entries is kept as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use opentelemetry::{baggage::{BaggageExt, BaggageMetadata, KeyValueMetadata}, Context, Value};
+    ///
+    /// let cx = Context::current_with_baggage(vec![KeyValueMetadata::new("tenant", "a", "internal")]);
+    /// let cx = cx.with_baggage_entry("tenant", "b", None);
+    ///
+    /// assert_eq!(
+    ///     cx.baggage().get_with_metadata("tenant"),
+    ///     Some(&(Value::from("b"), BaggageMetadata::from("internal"))),
+    /// )
+    /// ```
+    fn with_baggage_entry<K: Into<Key>, V: Into<Value>>(
+        &self,
+        key: K,
+        value: V,
+        metadata: Option<BaggageMetadata>,
+    ) -> Self;
+
     /// Returns a reference to this context's baggage, or the default
     /// empty baggage if none has been set.
     fn baggage(&self) -> &Baggage;
@@ -399,6 +647,31 @@ impl BaggageExt for Context {
         self.with_value(Baggage::new())
     }
 
+    fn with_baggage_entry<K: Into<Key>, V: Into<Value>>(
+        &self,
+        key: K,
+        value: V,
+        metadata: Option<BaggageMetadata>,
+    ) -> Self {
+        let mut baggage: Baggage = self
+            .baggage()
+            .iter()
+            .map(|(key, (value, metadata))| {
+                KeyValueMetadata::new(key.clone(), value.clone(), metadata.clone())
+            })
+            .collect();
+        match metadata {
+            Some(metadata) => {
+                baggage.insert_with_metadata(key, value, metadata);
+            }
+            None => {
+                baggage.set_value(key, value);
+            }
+        }
+
+        self.with_value(baggage)
+    }
+
     fn baggage(&self) -> &Baggage {
         self.get::<Baggage>().unwrap_or(&DEFAULT_BAGGAGE)
     }
@@ -475,6 +748,92 @@ mod tests {
     use super::*;
     use std::iter::FromIterator;
 
+    #[test]
+    fn over_limit_keeps_encoded_len_within_max() {
+        let baggage = Baggage::from_iter(
+            (0..20).map(|i| KeyValue::new(format!("key{:02}", i), "a value, encoded")),
+        );
+        let max_len = baggage.encoded_len() / 2;
+
+        let mut trimmed = Baggage::from_iter(
+            baggage
+                .iter()
+                .map(|(key, (value, _))| KeyValue::new(key.clone(), value.clone())),
+        );
+        let over_limit = baggage.over_limit(max_len).collect::<Vec<_>>();
+        assert!(!over_limit.is_empty());
+        for (key, _) in over_limit {
+            trimmed.remove(key.clone());
+        }
+        assert!(trimmed.encoded_len() <= max_len);
+        assert!(
+            trimmed.get("key00").is_some(),
+            "keeps entries in name order"
+        );
+    }
+
+    #[test]
+    fn typed_getters() {
+        let mut baggage = Baggage::new();
+        baggage.insert("str", "value");
+        baggage.insert("bool", " true ");
+        baggage.insert("int", "-12");
+        baggage.insert("typed_int", 7);
+        baggage.insert("not_int", "1.5");
+
+        assert_eq!(baggage.get_str("str"), Some("value"));
+        assert_eq!(baggage.get_str("typed_int"), None);
+        assert_eq!(baggage.get_bool("bool"), Some(true));
+        assert_eq!(baggage.get_bool("str"), None);
+        assert_eq!(baggage.get_i64("int"), Some(-12));
+        assert_eq!(baggage.get_i64("typed_int"), Some(7));
+        assert_eq!(baggage.get_i64("not_int"), None);
+        assert_eq!(baggage.get_i64("missing"), None);
+    }
+
+    #[test]
+    fn mutation_keeps_metadata() {
+        let cx = Context::new().with_baggage(vec![
+            KeyValueMetadata::new("tenant", "a", "internal"),
+            KeyValueMetadata::new("user", "1", "pii"),
+        ]);
+
+        let updated = cx
+            .with_baggage_entry("tenant", "b", None)
+            .with_baggage_entry("user", "2", Some("".into()))
+            .with_baggage_entry("new", "value", None);
+        let baggage = updated.baggage();
+        assert_eq!(
+            baggage.get_with_metadata("tenant"),
+            Some(&(Value::from("b"), BaggageMetadata::from("internal")))
+        );
+        assert_eq!(
+            baggage.get_with_metadata("user"),
+            Some(&(Value::from("2"), BaggageMetadata::default()))
+        );
+        assert_eq!(
+            baggage.get_with_metadata("new"),
+            Some(&(Value::from("value"), BaggageMetadata::default()))
+        );
+        assert_eq!(cx.baggage().get_str("tenant"), Some("a"));
+    }
+
+    #[test]
+    fn remove_frees_length() {
+        let mut baggage = Baggage::new();
+        let value = "v".repeat(3000);
+        baggage.insert("a", value.clone());
+        baggage.remove("a");
+        baggage.insert("b", value.clone());
+        baggage.remove("b");
+        baggage.insert("c", value);
+        assert_eq!(
+            baggage.len(),
+            1,
+            "removed entries do not count to the limit"
+        );
+    }
+
     #[test]
     fn insert_non_ascii_key() {
         let mut baggage = Baggage::new();
@@ -1,14 +1,29 @@
-use std::any::{Any, TypeId};
+#[cfg(not(feature = "std"))]
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+use core::any::{Any, TypeId};
+use core::fmt;
+#[cfg(feature = "std")]
+use core::marker::PhantomData;
+#[cfg(feature = "std")]
 use std::cell::RefCell;
+#[cfg(feature = "std")]
 use std::collections::HashMap;
-use std::fmt;
+#[cfg(feature = "std")]
 use std::hash::{BuildHasherDefault, Hasher};
-use std::marker::PhantomData;
-use std::sync::Arc;
 
+#[cfg(feature = "std")]
+type Entries = HashMap<TypeId, Arc<dyn Any + Sync + Send>, BuildHasherDefault<IdHasher>>;
+#[cfg(not(feature = "std"))]
+type Entries = BTreeMap<TypeId, Arc<dyn Any + Sync + Send>>;
+
+#[cfg(feature = "std")]
 thread_local! {
     static CURRENT_CONTEXT: RefCell<Context> = RefCell::new(Context::default());
     static DEFAULT_CONTEXT: Context = Context::default();
+    static ATTACHED_CONTEXTS: RefCell<AttachedContexts> = RefCell::new(AttachedContexts::default());
 }
 
 /// An execution-scoped collection of values.
@@ -35,8 +50,11 @@ thread_local! {
 /// by dropping the returned [`ContextGuard`]. Context can be nested, and will
 /// restore their parent outer context when detached on drop. To access the
 /// values of the context, a snapshot can be created via the [`Context::current`]
-/// method.
+/// method. Without the `std` feature there are no thread locals to hold the
+/// current context, contexts are then passed explicitly, e.g. to
+/// [`Tracer::start_with_context`].
 ///
+/// [`Tracer::start_with_context`]: crate::trace::Tracer::start_with_context()
 /// [`Context::current`]: Context::current()
 /// [`get`]: Context::get()
 /// [`with_value`]: Context::with_value()
@@ -45,6 +63,8 @@ thread_local! {
 /// # Examples
 ///
 /// ```
+/// # #[cfg(feature = "std")]
+/// # {
 /// use opentelemetry::Context;
 ///
 /// // Application-specific `a` and `b` values
@@ -72,10 +92,11 @@ thread_local! {
 /// let current = Context::current();
 /// assert_eq!(current.get::<ValueA>(), Some(&ValueA("a")));
 /// assert_eq!(current.get::<ValueB>(), None);
+/// # }
 /// ```
 #[derive(Clone, Default)]
 pub struct Context {
-    entries: HashMap<TypeId, Arc<dyn Any + Sync + Send>, BuildHasherDefault<IdHasher>>,
+    entries: Entries,
 }
 
 impl Context {
@@ -89,6 +110,8 @@ impl Context {
         Context::default()
     }
 
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
     /// Returns an immutable snapshot of the current thread's context.
     ///
     /// # Examples
@@ -110,6 +133,14 @@ impl Context {
         get_current(|cx| cx.clone())
     }
 
+    #[cfg(feature = "std")]
+    /// Applies a function to the current thread's context, without cloning it.
+    pub(crate) fn map_current<T, F: FnMut(&Context) -> T>(f: F) -> T {
+        get_current(f)
+    }
+
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
     /// Returns a clone of the current thread's context with the given value.
     ///
     /// This is a more efficient form of `Context::current().with_value(value)`
@@ -208,6 +239,8 @@ impl Context {
         new_context
     }
 
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
     /// Replaces the current context on this thread with this context.
     ///
     /// Dropping the returned [`ContextGuard`] will reset the current context to the
@@ -283,12 +316,17 @@ impl Context {
     /// assert_eq!(Context::current().get::<ValueA>(), None);
     /// ```
     pub fn attach(self) -> ContextGuard {
-        let previous_cx = CURRENT_CONTEXT
+        let id = CURRENT_CONTEXT
             .try_with(|current| current.replace(self))
-            .ok();
+            .ok()
+            .and_then(|previous_cx| {
+                ATTACHED_CONTEXTS
+                    .try_with(|attached| attached.borrow_mut().push(previous_cx))
+                    .ok()
+            });
 
         ContextGuard {
-            previous_cx,
+            id,
             _marker: PhantomData,
         }
     }
@@ -302,22 +340,95 @@ impl fmt::Debug for Context {
     }
 }
 
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 /// A guard that resets the current context to the prior context when dropped.
+///
+/// Guards are expected to be dropped in the reverse order they were created
+/// in. A guard dropped while guards created after it are still alive, e.g.
+/// when a guard is held across an `.await`, leaves the current context as it
+/// is and makes the next guard restore its prior context instead, so that the
+/// current context is correct again once all of them are dropped. Such misuse
+/// is reported with [`global::handle_error`] in debug builds.
+///
+/// [`global::handle_error`]: crate::global::handle_error
 #[allow(missing_debug_implementations)]
 pub struct ContextGuard {
-    previous_cx: Option<Context>,
+    id: Option<u64>,
     // ensure this type is !Send as it relies on thread locals
     _marker: PhantomData<*const ()>,
 }
 
+#[cfg(feature = "std")]
 impl Drop for ContextGuard {
     fn drop(&mut self) {
-        if let Some(previous_cx) = self.previous_cx.take() {
-            let _ = CURRENT_CONTEXT.try_with(|current| current.replace(previous_cx));
+        let id = match self.id.take() {
+            Some(id) => id,
+            None => return,
+        };
+        let detached = ATTACHED_CONTEXTS
+            .try_with(|attached| attached.borrow_mut().detach(id))
+            .ok()
+            .flatten();
+        match detached {
+            Some(Detached::InOrder(previous_cx)) => {
+                let _ = CURRENT_CONTEXT.try_with(|current| current.replace(previous_cx));
+            }
+            Some(Detached::OutOfOrder) => {
+                #[cfg(debug_assertions)]
+                crate::global::handle_error(crate::global::Error::Other(
+                    "context guard dropped before guards attached after it, the current context is restored once they are dropped".into(),
+                ));
+            }
+            None => {}
         }
     }
 }
 
+#[cfg(feature = "std")]
+/// The contexts replaced by the attached contexts of this thread, by the id
+/// of the guard restoring them.
+#[derive(Default)]
+struct AttachedContexts {
+    next_id: u64,
+    previous: Vec<(u64, Context)>,
+}
+
+#[cfg(feature = "std")]
+enum Detached {
+    /// The guard was the last attached one, the context to restore.
+    InOrder(Context),
+    /// Guards attached after the guard are still alive.
+    OutOfOrder,
+}
+
+#[cfg(feature = "std")]
+impl AttachedContexts {
+    fn push(&mut self, previous_cx: Context) -> u64 {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        self.previous.push((id, previous_cx));
+        id
+    }
+
+    fn detach(&mut self, id: u64) -> Option<Detached> {
+        let position = self
+            .previous
+            .iter()
+            .rposition(|(attached, _)| *attached == id)?;
+        let (_, previous_cx) = self.previous.remove(position);
+        match self.previous.get_mut(position) {
+            // the next guard restores the context this guard would have
+            Some((_, next_previous_cx)) => {
+                *next_previous_cx = previous_cx;
+                Some(Detached::OutOfOrder)
+            }
+            None => Some(Detached::InOrder(previous_cx)),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
 /// Executes a closure with a reference to this thread's current context.
 ///
 /// Note: This function will panic if you attempt to attach another context
@@ -328,12 +439,14 @@ fn get_current<F: FnMut(&Context) -> T, T>(mut f: F) -> T {
         .unwrap_or_else(|_| DEFAULT_CONTEXT.with(|cx| f(&*cx)))
 }
 
+#[cfg(feature = "std")]
 /// With TypeIds as keys, there's no need to hash them. They are already hashes
 /// themselves, coming from the compiler. The IdHasher holds the u64 of
 /// the TypeId, and then returns it, instead of doing any bit fiddling.
 #[derive(Clone, Default, Debug)]
 struct IdHasher(u64);
 
+#[cfg(feature = "std")]
 impl Hasher for IdHasher {
     fn write(&mut self, _: &[u8]) {
         unreachable!("TypeId calls write_u64");
@@ -350,7 +463,7 @@ impl Hasher for IdHasher {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
 
@@ -380,4 +493,21 @@ mod tests {
         assert_eq!(current.get(), Some(&ValueA("a")));
         assert_eq!(current.get::<ValueB>(), None);
     }
+
+    #[test]
+    fn out_of_order_guards() {
+        #[derive(Debug, PartialEq)]
+        struct ValueA(&'static str);
+
+        let outer_guard = Context::new().with_value(ValueA("a")).attach();
+        let inner_guard = Context::new().with_value(ValueA("b")).attach();
+
+        // the inner context stays current until its guard is dropped
+        drop(outer_guard);
+        assert_eq!(Context::current().get(), Some(&ValueA("b")));
+
+        // which restores the context before the outer one
+        drop(inner_guard);
+        assert_eq!(Context::current().get::<ValueA>(), None);
+    }
 }
@@ -1,8 +1,8 @@
 //! OpenTelemetry shared core date types
+use alloc::{borrow::Cow, format, string::String, vec::Vec};
+use core::fmt;
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
-use std::borrow::Cow;
-use std::fmt;
 
 /// Key used for metric `LabelSet`s and trace `Span` attributes.
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
@@ -11,8 +11,11 @@ pub struct Key(Cow<'static, str>);
 
 impl Key {
     /// Create a new `Key`.
+    ///
+    /// Owned names of common semantic convention attributes, e.g. `http.method`,
+    /// are replaced by their static name, so cloning the key does not allocate.
     pub fn new<S: Into<Cow<'static, str>>>(value: S) -> Self {
-        Key(value.into())
+        Key(intern(value.into()))
     }
 
     /// Create a new const `Key`.
@@ -76,7 +79,68 @@ impl From<&'static str> for Key {
 impl From<String> for Key {
     /// Convert a `String` to a `Key`.
     fn from(string: String) -> Self {
-        Key(Cow::from(string))
+        Key(intern(Cow::Owned(string)))
+    }
+}
+
+/// Names of common semantic convention attributes, sorted for binary search.
+const INTERNED_KEYS: &[&str] = &[
+    "code.function",
+    "code.namespace",
+    "db.name",
+    "db.operation",
+    "db.statement",
+    "db.system",
+    "db.user",
+    "enduser.id",
+    "error",
+    "event.name",
+    "exception.message",
+    "exception.stacktrace",
+    "exception.type",
+    "http.client_ip",
+    "http.flavor",
+    "http.host",
+    "http.method",
+    "http.route",
+    "http.scheme",
+    "http.server_name",
+    "http.status_code",
+    "http.target",
+    "http.url",
+    "http.user_agent",
+    "messaging.destination",
+    "messaging.system",
+    "net.host.name",
+    "net.host.port",
+    "net.peer.ip",
+    "net.peer.name",
+    "net.peer.port",
+    "net.transport",
+    "otel.status_code",
+    "peer.service",
+    "rpc.method",
+    "rpc.service",
+    "rpc.system",
+    "service.name",
+    "service.namespace",
+    "service.version",
+    "span.kind",
+    "telemetry.sdk.language",
+    "telemetry.sdk.name",
+    "telemetry.sdk.version",
+    "thread.id",
+    "thread.name",
+];
+
+/// Replace an owned name in `INTERNED_KEYS` by the static one.
+fn intern(name: Cow<'static, str>) -> Cow<'static, str> {
+    match name {
+        Cow::Owned(ref owned) => match INTERNED_KEYS.binary_search(&owned.as_str()) {
+            Ok(idx) => Cow::Borrowed(INTERNED_KEYS[idx]),
+            Err(_) => name,
+        },
+        borrowed => borrowed,
     }
 }
 
@@ -261,6 +325,98 @@ impl KeyValue {
     }
 }
 
+/// The normalized severity of log records and span events, following the [severity numbers]
+/// of the data model.
+///
+/// Severities of the same range, e.g. `Info` to `Info4`, allow sources with
+/// more fine grained levels to keep their order.
+///
+/// [severity numbers]: https://github.com/open-telemetry/opentelemetry-specification/blob/main/specification/logs/data-model.md#field-severitynumber
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// TRACE
+    Trace = 1,
+    /// TRACE2
+    Trace2 = 2,
+    /// TRACE3
+    Trace3 = 3,
+    /// TRACE4
+    Trace4 = 4,
+    /// DEBUG
+    Debug = 5,
+    /// DEBUG2
+    Debug2 = 6,
+    /// DEBUG3
+    Debug3 = 7,
+    /// DEBUG4
+    Debug4 = 8,
+    /// INFO
+    Info = 9,
+    /// INFO2
+    Info2 = 10,
+    /// INFO3
+    Info3 = 11,
+    /// INFO4
+    Info4 = 12,
+    /// WARN
+    Warn = 13,
+    /// WARN2
+    Warn2 = 14,
+    /// WARN3
+    Warn3 = 15,
+    /// WARN4
+    Warn4 = 16,
+    /// ERROR
+    Error = 17,
+    /// ERROR2
+    Error2 = 18,
+    /// ERROR3
+    Error3 = 19,
+    /// ERROR4
+    Error4 = 20,
+    /// FATAL
+    Fatal = 21,
+    /// FATAL2
+    Fatal2 = 22,
+    /// FATAL3
+    Fatal3 = 23,
+    /// FATAL4
+    Fatal4 = 24,
+}
+
+impl Severity {
+    /// The short name of the severity, e.g. `INFO2`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Trace => "TRACE",
+            Severity::Trace2 => "TRACE2",
+            Severity::Trace3 => "TRACE3",
+            Severity::Trace4 => "TRACE4",
+            Severity::Debug => "DEBUG",
+            Severity::Debug2 => "DEBUG2",
+            Severity::Debug3 => "DEBUG3",
+            Severity::Debug4 => "DEBUG4",
+            Severity::Info => "INFO",
+            Severity::Info2 => "INFO2",
+            Severity::Info3 => "INFO3",
+            Severity::Info4 => "INFO4",
+            Severity::Warn => "WARN",
+            Severity::Warn2 => "WARN2",
+            Severity::Warn3 => "WARN3",
+            Severity::Warn4 => "WARN4",
+            Severity::Error => "ERROR",
+            Severity::Error2 => "ERROR2",
+            Severity::Error3 => "ERROR3",
+            Severity::Error4 => "ERROR4",
+            Severity::Fatal => "FATAL",
+            Severity::Fatal2 => "FATAL2",
+            Severity::Fatal3 => "FATAL3",
+            Severity::Fatal4 => "FATAL4",
+        }
+    }
+}
+
 /// Units denote underlying data units tracked by `Meter`s.
 #[derive(Clone, Default, Debug, PartialEq, Hash)]
 pub struct Unit(String);
@@ -283,3 +439,25 @@ impl AsRef<str> for Unit {
         self.0.as_ref()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interned_keys_are_sorted() {
+        assert!(INTERNED_KEYS.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn owned_common_keys_are_interned() {
+        let key = Key::new(String::from("http.method"));
+        assert!(matches!(key.0, Cow::Borrowed("http.method")));
+        let key = Key::from(String::from("net.peer.port"));
+        assert!(matches!(key.0, Cow::Borrowed("net.peer.port")));
+
+        let key = Key::from(String::from("my.attribute"));
+        assert!(matches!(key.0, Cow::Owned(_)));
+        assert_eq!(key.as_str(), "my.attribute");
+    }
+}
@@ -0,0 +1,39 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether the SDKs record telemetry, see [`set_telemetry_enabled`].
+static TELEMETRY_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Switch recording of spans and metrics on or off at runtime.
+///
+/// While disabled the SDK tracers start non-recording spans that only
+/// propagate their parent's context, and metric measurements and observer
+/// callbacks are skipped. This allows shedding the cost of telemetry, e.g.
+/// during an incident, without restarting or reconfiguring the pipelines.
+/// Telemetry is enabled by default.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature="trace")]
+/// # {
+/// use opentelemetry::{global, sdk::trace::TracerProvider};
+/// use opentelemetry::trace::{Span, Tracer, TracerProvider as _};
+///
+/// let provider = TracerProvider::default();
+/// let tracer = provider.get_tracer("example", None);
+///
+/// global::set_telemetry_enabled(false);
+/// assert!(!tracer.start("shed").is_recording());
+///
+/// global::set_telemetry_enabled(true);
+/// assert!(tracer.start("recorded").is_recording());
+/// # }
+/// ```
+pub fn set_telemetry_enabled(enabled: bool) {
+    TELEMETRY_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns `false` if telemetry was switched off with [`set_telemetry_enabled`].
+pub fn is_telemetry_enabled() -> bool {
+    TELEMETRY_ENABLED.load(Ordering::Relaxed)
+}
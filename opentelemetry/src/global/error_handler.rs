@@ -1,55 +1,271 @@
-use std::sync::PoisonError;
-use std::sync::RwLock;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Mutex, PoisonError, RwLock};
+use std::time::{Duration, SystemTime};
 
+#[cfg(feature = "logs")]
+use crate::logs::LogError;
 #[cfg(feature = "metrics")]
 use crate::metrics::MetricsError;
+use crate::sdk::resource::ResourceError;
 #[cfg(feature = "trace")]
 use crate::trace::TraceError;
 
 lazy_static::lazy_static! {
     /// The global error handler.
-    static ref GLOBAL_ERROR_HANDLER: RwLock<Option<ErrorHandler>> = RwLock::new(None);
+    static ref GLOBAL_ERROR_HANDLER: RwLock<Option<Box<dyn ErrorHandler>>> = RwLock::new(None);
+    /// The handler used until a global error handler is set.
+    static ref DEFAULT_ERROR_HANDLER: RateLimitedErrorHandler = RateLimitedErrorHandler::default();
 }
 
 /// Wrapper for error from both tracing and metrics part of open telemetry.
 #[derive(thiserror::Error, Debug)]
 #[non_exhaustive]
 pub enum Error {
+    /// Errors of the trace API and SDK.
     #[cfg(feature = "trace")]
     #[cfg_attr(docsrs, doc(cfg(feature = "trace")))]
     #[error(transparent)]
     Trace(#[from] TraceError),
+    /// Errors of the metrics API and SDK.
     #[cfg(feature = "metrics")]
     #[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
     #[error(transparent)]
     Metric(#[from] MetricsError),
+    /// Errors of the logs API and SDK.
+    #[cfg(feature = "logs")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "logs")))]
+    #[error(transparent)]
+    Log(#[from] LogError),
+    /// Failures to inject or extract a context.
+    #[error("{0}")]
+    Propagation(String),
+    /// Other errors.
     #[error("{0}")]
     Other(String),
 }
 
+impl Error {
+    /// The category of the error.
+    ///
+    /// Failed and timed out exports of all signals are categorized as
+    /// [`ErrorCategory::Export`], as they usually share a cause like an
+    /// unavailable collector.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            #[cfg(feature = "trace")]
+            Error::Trace(TraceError::ExportFailed(_))
+            | Error::Trace(TraceError::ExportTimedOut(_)) => ErrorCategory::Export,
+            #[cfg(feature = "trace")]
+            Error::Trace(_) => ErrorCategory::Trace,
+            #[cfg(feature = "metrics")]
+            Error::Metric(MetricsError::ExportErr(_)) => ErrorCategory::Export,
+            #[cfg(feature = "metrics")]
+            Error::Metric(_) => ErrorCategory::Metrics,
+            #[cfg(feature = "logs")]
+            Error::Log(LogError::ExportFailed(_)) | Error::Log(LogError::ExportTimedOut(_)) => {
+                ErrorCategory::Export
+            }
+            #[cfg(feature = "logs")]
+            Error::Log(_) => ErrorCategory::Logs,
+            Error::Propagation(_) => ErrorCategory::Propagation,
+            Error::Other(_) => ErrorCategory::Other,
+        }
+    }
+}
+
 impl<T> From<PoisonError<T>> for Error {
     fn from(err: PoisonError<T>) -> Self {
         Error::Other(err.to_string())
     }
 }
 
-struct ErrorHandler(Box<dyn Fn(Error) + Send + Sync>);
+impl From<ResourceError> for Error {
+    fn from(err: ResourceError) -> Self {
+        Error::Other(err.to_string())
+    }
+}
+
+/// The category of an [`Error`], see [`Error::category`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorCategory {
+    /// Errors of the trace API and SDK.
+    Trace,
+    /// Errors of the metrics API and SDK.
+    Metrics,
+    /// Errors of the logs API and SDK.
+    Logs,
+    /// Failures to inject or extract a context.
+    Propagation,
+    /// Failed or timed out exports of any signal.
+    Export,
+    /// Errors not covered by the other categories.
+    Other,
+}
+
+impl ErrorCategory {
+    fn name(&self) -> &'static str {
+        match self {
+            ErrorCategory::Trace => "trace",
+            ErrorCategory::Metrics => "metrics",
+            ErrorCategory::Logs => "logs",
+            ErrorCategory::Propagation => "propagation",
+            ErrorCategory::Export => "export",
+            ErrorCategory::Other => "other",
+        }
+    }
+}
+
+/// Handles the errors reported with [`handle_error`], registered with
+/// [`register_error_handler`].
+pub trait ErrorHandler: Send + Sync + 'static {
+    /// Handle an error of the given category.
+    fn handle(&self, category: ErrorCategory, err: Error);
+}
+
+/// A handler set with [`set_error_handler`], ignoring the category.
+struct FnErrorHandler<F>(F);
+
+impl<F> ErrorHandler for FnErrorHandler<F>
+where
+    F: Fn(Error) + Send + Sync + 'static,
+{
+    fn handle(&self, _category: ErrorCategory, err: Error) {
+        (self.0)(err)
+    }
+}
+
+/// An [`ErrorHandler`] writing errors to stderr, at most a number of errors
+/// per category and interval.
+///
+/// Errors over the limit are counted and reported as a single line with the
+/// next error of the category after the interval, so an unavailable collector
+/// does not flood stderr with the same export failure. This is the handler
+/// used until another one is set.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::global::{self, RateLimitedErrorHandler};
+/// use std::time::Duration;
+///
+/// // write at most 5 errors of each category per minute
+/// let handler = RateLimitedErrorHandler::new(5, Duration::from_secs(60));
+/// global::register_error_handler(handler).expect("error handler lock poisoned");
+/// ```
+pub struct RateLimitedErrorHandler {
+    max_per_interval: u32,
+    interval: Duration,
+    windows: Mutex<HashMap<ErrorCategory, Window>>,
+}
+
+struct Window {
+    start: SystemTime,
+    handled: u32,
+    suppressed: u64,
+}
+
+impl RateLimitedErrorHandler {
+    /// Create a new handler writing at most `max_per_interval` errors of each
+    /// category per `interval`.
+    pub fn new(max_per_interval: u32, interval: Duration) -> Self {
+        RateLimitedErrorHandler {
+            max_per_interval,
+            interval,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The lines to write for an error of `category` happening at `now`.
+    fn lines(&self, now: SystemTime, category: ErrorCategory, err: Error) -> Vec<String> {
+        let mut windows = match self.windows.lock() {
+            Ok(windows) => windows,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let window = windows.entry(category).or_insert(Window {
+            start: now,
+            handled: 0,
+            suppressed: 0,
+        });
+
+        let mut lines = Vec::new();
+        // the system time is used as `Instant` is not available on wasm32 targets
+        let elapsed = now.duration_since(window.start).unwrap_or_default();
+        if elapsed >= self.interval {
+            if window.suppressed > 0 {
+                lines.push(format!(
+                    "OpenTelemetry suppressed {} {} errors in the last {:?}",
+                    window.suppressed,
+                    category.name(),
+                    elapsed
+                ));
+            }
+            *window = Window {
+                start: now,
+                handled: 0,
+                suppressed: 0,
+            };
+        }
+
+        if window.handled < self.max_per_interval {
+            window.handled += 1;
+            lines.push(error_line(err));
+        } else {
+            window.suppressed += 1;
+        }
+        lines
+    }
+}
+
+impl Default for RateLimitedErrorHandler {
+    /// At most 10 errors of each category per minute.
+    fn default() -> Self {
+        RateLimitedErrorHandler::new(10, Duration::from_secs(60))
+    }
+}
+
+impl fmt::Debug for RateLimitedErrorHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimitedErrorHandler")
+            .field("max_per_interval", &self.max_per_interval)
+            .field("interval", &self.interval)
+            .finish()
+    }
+}
+
+impl ErrorHandler for RateLimitedErrorHandler {
+    fn handle(&self, category: ErrorCategory, err: Error) {
+        for line in self.lines(crate::time::now(), category, err) {
+            eprintln!("{}", line);
+        }
+    }
+}
+
+fn error_line(err: Error) -> String {
+    match err {
+        #[cfg(feature = "metrics")]
+        Error::Metric(err) => format!("OpenTelemetry metrics error occurred {:?}", err),
+        #[cfg(feature = "trace")]
+        Error::Trace(err) => format!("OpenTelemetry trace error occurred {:?}", err),
+        #[cfg(feature = "logs")]
+        Error::Log(err) => format!("OpenTelemetry log error occurred {:?}", err),
+        Error::Propagation(err_msg) => {
+            format!("OpenTelemetry propagation error occurred {}", err_msg)
+        }
+        Error::Other(err_msg) => format!("OpenTelemetry error occurred {}", err_msg),
+    }
+}
 
 /// Handle error using the globally configured error handler.
 ///
-/// Writes to stderr if unset.
+/// Writes to stderr with a [`RateLimitedErrorHandler`] if unset.
 pub fn handle_error<T: Into<Error>>(err: T) {
+    let err = err.into();
+    let category = err.category();
     match GLOBAL_ERROR_HANDLER.read() {
-        Ok(handler) if handler.is_some() => (handler.as_ref().unwrap().0)(err.into()),
-        _ => match err.into() {
-            #[cfg(feature = "metrics")]
-            #[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
-            Error::Metric(err) => eprintln!("OpenTelemetry metrics error occurred {:?}", err),
-            #[cfg(feature = "trace")]
-            #[cfg_attr(docsrs, doc(cfg(feature = "trace")))]
-            Error::Trace(err) => eprintln!("OpenTelemetry trace error occurred {:?}", err),
-            Error::Other(err_msg) => eprintln!("OpenTelemetry error occurred {}", err_msg),
-        },
+        Ok(handler) if handler.is_some() => handler.as_ref().unwrap().handle(category, err),
+        _ => DEFAULT_ERROR_HANDLER.handle(category, err),
     }
 }
 
@@ -58,8 +274,71 @@ pub fn set_error_handler<F>(f: F) -> std::result::Result<(), Error>
 where
     F: Fn(Error) + Send + Sync + 'static,
 {
+    register_error_handler(FnErrorHandler(f))
+}
+
+/// Register a global [`ErrorHandler`], receiving the errors along with their
+/// category.
+pub fn register_error_handler<H: ErrorHandler>(handler: H) -> std::result::Result<(), Error> {
     GLOBAL_ERROR_HANDLER
         .write()
-        .map(|mut handler| *handler = Some(ErrorHandler(Box::new(f))))
+        .map(|mut global_handler| *global_handler = Some(Box::new(handler)))
         .map_err(Into::into)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limited_handler_suppresses_errors_over_limit() {
+        let handler = RateLimitedErrorHandler::new(2, Duration::from_secs(10));
+        let start = SystemTime::now();
+        let error = || Error::Other("unavailable".into());
+
+        for _ in 0..2 {
+            assert_eq!(
+                handler.lines(start, ErrorCategory::Other, error()),
+                vec!["OpenTelemetry error occurred unavailable".to_string()]
+            );
+        }
+        for _ in 0..3 {
+            assert!(handler
+                .lines(start, ErrorCategory::Other, error())
+                .is_empty());
+        }
+        // categories are limited separately
+        assert_eq!(
+            handler
+                .lines(
+                    start,
+                    ErrorCategory::Propagation,
+                    Error::Propagation("bad header".into())
+                )
+                .len(),
+            1
+        );
+
+        let later = start + Duration::from_secs(10);
+        assert_eq!(
+            handler.lines(later, ErrorCategory::Other, error()),
+            vec![
+                "OpenTelemetry suppressed 3 other errors in the last 10s".to_string(),
+                "OpenTelemetry error occurred unavailable".to_string(),
+            ]
+        );
+    }
+
+    #[cfg(feature = "trace")]
+    #[test]
+    fn export_errors_are_categorized_across_signals() {
+        let timed_out = Error::from(TraceError::ExportTimedOut(Duration::from_secs(1)));
+        assert_eq!(timed_out.category(), ErrorCategory::Export);
+        let other = Error::from(TraceError::from("invalid span"));
+        assert_eq!(other.category(), ErrorCategory::Trace);
+        assert_eq!(
+            Error::Propagation("bad header".into()).category(),
+            ErrorCategory::Propagation
+        );
+    }
+}
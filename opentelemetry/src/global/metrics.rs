@@ -1,4 +1,6 @@
+use crate::global;
 use crate::metrics::{self, Meter, MeterProvider};
+use std::mem;
 use std::sync::{Arc, RwLock};
 
 lazy_static::lazy_static! {
@@ -17,6 +19,14 @@ impl MeterProvider for GlobalMeterProvider {
     fn meter(&self, name: &'static str, version: Option<&'static str>) -> Meter {
         self.provider.meter(name, version)
     }
+
+    fn force_flush(&self) -> metrics::Result<()> {
+        self.provider.force_flush()
+    }
+
+    fn shutdown(&self) -> metrics::Result<()> {
+        self.provider.shutdown()
+    }
 }
 
 impl GlobalMeterProvider {
@@ -52,6 +62,25 @@ pub fn meter_provider() -> GlobalMeterProvider {
         .clone()
 }
 
+/// Shut down the current meter provider, running a final collection and
+/// export of the measurements recorded so far, and reset the global meter
+/// provider to a no-op one.
+///
+/// Call this before the process exits, measurements of the last collection
+/// interval are lost otherwise.
+pub fn shutdown_meter_provider() {
+    let meter_provider = mem::replace(
+        &mut *GLOBAL_METER_PROVIDER
+            .write()
+            .expect("GLOBAL_METER_PROVIDER RwLock poisoned"),
+        GlobalMeterProvider::new(metrics::noop::NoopMeterProvider::new()),
+    );
+
+    if let Err(err) = meter_provider.shutdown() {
+        global::handle_error(err);
+    }
+}
+
 /// Creates a named [`Meter`] via the configured [`GlobalMeterProvider`].
 ///
 /// If the name is an empty string, the provider will use a default name.
@@ -109,6 +109,9 @@
 //! // in main or other app start
 //! init_meter();
 //! do_something_instrumented();
+//!
+//! // export the measurements of the last interval before the app exits
+//! global::shutdown_meter_provider();
 //! # }
 //! ```
 //!
@@ -136,6 +139,7 @@
 //! [`MeterProvider`]: crate::metrics::MeterProvider
 //! [`set_meter_provider`]: crate::global::set_meter_provider
 
+mod enabled;
 mod error_handler;
 #[cfg(feature = "metrics")]
 mod metrics;
@@ -144,11 +148,16 @@ mod propagation;
 #[cfg(feature = "trace")]
 mod trace;
 
-pub use error_handler::{handle_error, set_error_handler};
+pub use enabled::{is_telemetry_enabled, set_telemetry_enabled};
+pub use error_handler::{
+    handle_error, register_error_handler, set_error_handler, Error, ErrorCategory, ErrorHandler,
+    RateLimitedErrorHandler,
+};
 #[cfg(feature = "metrics")]
 #[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
 pub use metrics::{
-    meter, meter_provider, meter_with_version, set_meter_provider, GlobalMeterProvider,
+    meter, meter_provider, meter_with_version, set_meter_provider, shutdown_meter_provider,
+    GlobalMeterProvider,
 };
 #[cfg(feature = "trace")]
 #[cfg_attr(docsrs, doc(cfg(feature = "trace")))]
@@ -156,6 +165,7 @@ pub use propagation::{get_text_map_propagator, set_text_map_propagator};
 #[cfg(feature = "trace")]
 #[cfg_attr(docsrs, doc(cfg(feature = "trace")))]
 pub use trace::{
-    set_tracer_provider, shutdown_tracer_provider, tracer, tracer_provider, tracer_with_version,
-    BoxedSpan, BoxedTracer, GenericTracer, GenericTracerProvider, GlobalTracerProvider,
+    set_tracer_provider, set_tracer_provider_with_grace_period, shutdown_tracer_provider, tracer,
+    tracer_provider, tracer_with_version, BoxedSpan, BoxedTracer, GenericTracer,
+    GenericTracerProvider, GlobalTracerProvider,
 };
@@ -1,9 +1,13 @@
+use crate::global;
 use crate::trace::NoopTracerProvider;
-use crate::{trace, trace::TracerProvider, Context, KeyValue};
+use crate::{trace, trace::TracerProvider, Context, KeyValue, Severity, Value};
+use std::borrow::Cow;
 use std::fmt;
 use std::mem;
 use std::sync::{Arc, RwLock};
-use std::time::SystemTime;
+#[cfg(not(target_arch = "wasm32"))]
+use std::thread;
+use std::time::{Duration, SystemTime};
 
 /// Wraps the [`BoxedTracer`]'s [`Span`] so it can be used generically by
 /// applications without knowing the underlying type.
@@ -29,6 +33,19 @@ impl trace::Span for BoxedSpan {
         self.0.add_event_with_timestamp(name, timestamp, attributes)
     }
 
+    /// Records events with a severity and a structured body in the context of
+    /// a given `Span`.
+    fn add_event_with_severity(
+        &self,
+        name: String,
+        severity: Severity,
+        body: Option<Value>,
+        attributes: Vec<KeyValue>,
+    ) {
+        self.0
+            .add_event_with_severity(name, severity, body, attributes)
+    }
+
     /// Returns the `SpanContext` for the given `Span`.
     fn span_context(&self) -> &trace::SpanContext {
         self.0.span_context()
@@ -92,15 +109,21 @@ impl trace::Tracer for BoxedTracer {
     /// trace. A span is said to be a _root span_ if it does not have a parent. Each
     /// trace includes a single root span, which is the shared ancestor of all other
     /// spans in the trace.
-    fn start_with_context(&self, name: &str, cx: Context) -> Self::Span {
-        BoxedSpan(self.0.start_with_context_boxed(name, cx))
+    fn start_with_context<T>(&self, name: T, cx: Context) -> Self::Span
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        BoxedSpan(self.0.start_with_context_boxed(name.into(), cx))
     }
 
     /// Creates a span builder
     ///
     /// An ergonomic way for attributes to be configured before the `Span` is started.
-    fn span_builder(&self, name: &str) -> trace::SpanBuilder {
-        trace::SpanBuilder::from_name(name.to_string())
+    fn span_builder<T>(&self, name: T) -> trace::SpanBuilder
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        trace::SpanBuilder::from_name(name)
     }
 
     /// Create a span from a `SpanBuilder`
@@ -119,7 +142,7 @@ pub trait GenericTracer: fmt::Debug + 'static {
 
     /// Returns a trait object so the underlying implementation can be swapped
     /// out at runtime.
-    fn start_with_context_boxed(&self, name: &str, cx: Context) -> Box<DynSpan>;
+    fn start_with_context_boxed(&self, name: Cow<'static, str>, cx: Context) -> Box<DynSpan>;
 
     /// Returns a trait object so the underlying implementation can be swapped
     /// out at runtime.
@@ -138,7 +161,7 @@ where
 
     /// Returns a trait object so the underlying implementation can be swapped
     /// out at runtime.
-    fn start_with_context_boxed(&self, name: &str, cx: Context) -> Box<DynSpan> {
+    fn start_with_context_boxed(&self, name: Cow<'static, str>, cx: Context) -> Box<DynSpan> {
         Box::new(self.start_with_context(name, cx))
     }
 
@@ -161,6 +184,9 @@ pub trait GenericTracerProvider: fmt::Debug + 'static {
         name: &'static str,
         version: Option<&'static str>,
     ) -> Box<dyn GenericTracer + Send + Sync>;
+
+    /// Force flush the underlying `TracerProvider`.
+    fn force_flush_boxed(&self) -> Vec<trace::TraceResult<()>>;
 }
 
 impl<S, T, P> GenericTracerProvider for P
@@ -177,6 +203,11 @@ where
     ) -> Box<dyn GenericTracer + Send + Sync> {
         Box::new(self.get_tracer(name, version))
     }
+
+    /// Force flush the provider
+    fn force_flush_boxed(&self) -> Vec<trace::TraceResult<()>> {
+        self.force_flush()
+    }
 }
 
 /// Represents the globally configured [`TracerProvider`] instance for this
@@ -187,6 +218,7 @@ where
 #[derive(Clone, Debug)]
 pub struct GlobalTracerProvider {
     provider: Arc<dyn GenericTracerProvider + Send + Sync>,
+    noop: bool,
 }
 
 impl GlobalTracerProvider {
@@ -199,6 +231,16 @@ impl GlobalTracerProvider {
     {
         GlobalTracerProvider {
             provider: Arc::new(provider),
+            noop: false,
+        }
+    }
+
+    /// The provider installed before any [`set_tracer_provider`] call and after
+    /// [`shutdown_tracer_provider`], it has nothing to flush when replaced.
+    fn noop() -> Self {
+        GlobalTracerProvider {
+            provider: Arc::new(NoopTracerProvider::new()),
+            noop: true,
         }
     }
 }
@@ -210,11 +252,19 @@ impl trace::TracerProvider for GlobalTracerProvider {
     fn get_tracer(&self, name: &'static str, version: Option<&'static str>) -> Self::Tracer {
         BoxedTracer(self.provider.get_tracer_boxed(name, version))
     }
+
+    /// Force flush the global provider.
+    fn force_flush(&self) -> Vec<trace::TraceResult<()>> {
+        self.provider.force_flush_boxed()
+    }
 }
 
+/// How long a replaced provider is kept alive by [`set_tracer_provider`].
+const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
 lazy_static::lazy_static! {
     /// The global `Tracer` provider singleton.
-    static ref GLOBAL_TRACER_PROVIDER: RwLock<GlobalTracerProvider> = RwLock::new(GlobalTracerProvider::new(trace::NoopTracerProvider::new()));
+    static ref GLOBAL_TRACER_PROVIDER: RwLock<GlobalTracerProvider> = RwLock::new(GlobalTracerProvider::noop());
 }
 
 /// Returns an instance of the currently configured global [`TracerProvider`] through
@@ -252,6 +302,14 @@ pub fn tracer_with_version(name: &'static str, version: &'static str) -> BoxedTr
 
 /// Sets the given [`TracerProvider`] instance as the current global provider.
 ///
+/// The previous provider is kept alive for a grace period of 5 seconds, so
+/// tracers already handed out keep recording and exporting the spans in
+/// flight. It is flushed and dropped afterwards on a background thread,
+/// shutting down its span processors unless the returned provider is still
+/// held. The no-op provider installed before the first call, or after
+/// [`shutdown_tracer_provider`], is not retired. See
+/// [`set_tracer_provider_with_grace_period`] to configure the grace period.
+///
 /// [`TracerProvider`]: crate::trace::TracerProvider
 #[must_use]
 pub fn set_tracer_provider<P, T, S>(new_provider: P) -> GlobalTracerProvider
@@ -260,13 +318,75 @@ where
     T: trace::Tracer<Span = S> + Send + Sync,
     P: trace::TracerProvider<Tracer = T> + Send + Sync,
 {
-    let mut tracer_provider = GLOBAL_TRACER_PROVIDER
-        .write()
-        .expect("GLOBAL_TRACER_PROVIDER RwLock poisoned");
-    mem::replace(
-        &mut *tracer_provider,
-        GlobalTracerProvider::new(new_provider),
-    )
+    set_tracer_provider_with_grace_period(new_provider, DEFAULT_GRACE_PERIOD)
+}
+
+/// Sets the given [`TracerProvider`] instance as the current global provider,
+/// keeping the previous provider alive for `grace_period`.
+///
+/// Spans of tracers from the previous provider ending within the grace period
+/// are exported by the previous provider, spans ending afterwards may be lost
+/// once it has shut down. A zero grace period drops the previous provider
+/// right away if the returned provider is not held. On `wasm32` targets the
+/// previous provider is flushed right away instead of after the grace period.
+///
+/// [`TracerProvider`]: crate::trace::TracerProvider
+#[must_use]
+pub fn set_tracer_provider_with_grace_period<P, T, S>(
+    new_provider: P,
+    grace_period: Duration,
+) -> GlobalTracerProvider
+where
+    S: trace::Span + Send + Sync,
+    T: trace::Tracer<Span = S> + Send + Sync,
+    P: trace::TracerProvider<Tracer = T> + Send + Sync,
+{
+    let previous = {
+        let mut tracer_provider = GLOBAL_TRACER_PROVIDER
+            .write()
+            .expect("GLOBAL_TRACER_PROVIDER RwLock poisoned");
+        mem::replace(
+            &mut *tracer_provider,
+            GlobalTracerProvider::new(new_provider),
+        )
+    };
+    if grace_period > Duration::from_secs(0) && !previous.noop {
+        retire_after(previous.clone(), grace_period);
+    }
+
+    previous
+}
+
+/// Flush and drop a replaced `provider` once `grace_period` elapsed.
+#[cfg(not(target_arch = "wasm32"))]
+fn retire_after(provider: GlobalTracerProvider, grace_period: Duration) {
+    let spawned = thread::Builder::new()
+        .name("opentelemetry-tracer-provider-retirement".to_string())
+        .spawn(move || {
+            thread::sleep(grace_period);
+            for result in provider.force_flush() {
+                if let Err(err) = result {
+                    global::handle_error(err);
+                }
+            }
+        });
+    if let Err(err) = spawned {
+        global::handle_error(global::Error::Other(format!(
+            "failed to spawn the thread retiring the previous tracer provider: {}",
+            err
+        )));
+    }
+}
+
+/// Flush a replaced `provider` right away, `wasm32` targets have no threads
+/// to wait for the grace period on.
+#[cfg(target_arch = "wasm32")]
+fn retire_after(provider: GlobalTracerProvider, _grace_period: Duration) {
+    for result in provider.force_flush() {
+        if let Err(err) = result {
+            global::handle_error(err);
+        }
+    }
 }
 
 /// Shut down the current tracer provider. This will invoke the shutdown method on all span processors.
@@ -276,10 +396,7 @@ pub fn shutdown_tracer_provider() {
         .write()
         .expect("GLOBAL_TRACER_PROVIDER RwLock poisoned");
 
-    let _ = mem::replace(
-        &mut *tracer_provider,
-        GlobalTracerProvider::new(NoopTracerProvider::new()),
-    );
+    let _ = mem::replace(&mut *tracer_provider, GlobalTracerProvider::noop());
 }
 
 #[cfg(test)]
@@ -373,6 +490,12 @@ mod tests {
         }
     }
 
+    #[test]
+    fn noop_provider_is_not_retired() {
+        assert!(GlobalTracerProvider::noop().noop);
+        assert!(!GlobalTracerProvider::new(NoopTracerProvider::new()).noop);
+    }
+
     #[test]
     #[ignore]
     fn test_set_tracer_provider() {
@@ -437,6 +560,36 @@ mod tests {
         assert!(second_resp.contains("thread 2"));
     }
 
+    #[test]
+    #[ignore]
+    #[cfg(feature = "testing")]
+    fn test_replaced_provider_exports_spans_in_flight() {
+        use crate::sdk::testing::trace::InMemorySpanExporter;
+        use crate::trace::{Span, Tracer};
+
+        let exporter = InMemorySpanExporter::new();
+        let _ = set_tracer_provider_with_grace_period(
+            crate::sdk::trace::TracerProvider::builder()
+                .with_in_memory_exporter(&exporter)
+                .build(),
+            Duration::from_millis(100),
+        );
+        let tracer = tracer("swap");
+        let span = tracer.start("in flight");
+
+        let _ = set_tracer_provider_with_grace_period(
+            TestTracerProvider::new("new one"),
+            Duration::from_millis(100),
+        );
+        span.end();
+        assert_eq!(exporter.get_finished_spans().unwrap().len(), 1);
+
+        // the previous provider is dropped after the grace period
+        sleep(Duration::from_millis(500));
+        tracer.start("after grace period").end();
+        assert_eq!(exporter.get_finished_spans().unwrap().len(), 1);
+    }
+
     cfg_tokio! {
 
         fn build_tracer_provider(batch_processor: bool, assert_writer: AssertWriter) -> crate::sdk::trace::TracerProvider {
@@ -516,19 +669,22 @@ mod tests {
         assert!(assert_writer.len() > 0);
     }
 
-    // Test if the single thread tokio runtime could exit successfully when not force flushing spans
+    // Test if the single thread tokio runtime could exit successfully when not force flushing spans.
+    // With `rt-tokio` enabled as well `with_exporter` spawns the worker onto the current runtime.
     #[tokio::test]
     #[ignore]
-    #[cfg(feature = "rt-tokio-current-thread")]
+    #[cfg(all(feature = "rt-tokio-current-thread", not(feature = "rt-tokio")))]
     async fn test_set_provider_single_thread_tokio() {
         let assert_writer = test_set_provider_in_tokio().await;
         assert_eq!(assert_writer.len(), 0)
     }
 
     // Test if the single thread tokio runtime could exit successfully when force flushing spans.
+    // With `rt-tokio` enabled as well `with_exporter` spawns the worker onto the current runtime,
+    // which the shutdown blocks forever.
     #[tokio::test]
     #[ignore]
-    #[cfg(feature = "rt-tokio-current-thread")]
+    #[cfg(all(feature = "rt-tokio-current-thread", not(feature = "rt-tokio")))]
     async fn test_set_provider_single_thread_tokio_shutdown() {
         let assert_writer = test_set_provider_in_tokio().await;
         shutdown_tracer_provider();
@@ -39,8 +39,17 @@
 //!
 //! The following core crate feature flags are available:
 //!
-//! * `trace`: Includes the trace API and SDK (enabled by default).
+//! * `std`: Includes everything relying on the standard library (enabled by
+//!   default), see [no_std support](#no_std-support).
+//! * `trace`: Includes the trace SDK (enabled by default), the trace API is
+//!   always available.
 //! * `metrics`: Includes the unstable metrics API and SDK.
+//! * `logs`: Includes the unstable logs API and SDK, log records are correlated
+//!   with spans so it enables `trace` as well.
+//! * `log-bridge`: Ships the records of the [log] crate as OpenTelemetry logs,
+//!   see [`logs::install_log_bridge`].
+//! * `tracing-layer`: Ships the events of [tracing] as OpenTelemetry logs, see
+//!   [`logs::log_layer`].
 //! * `serialize`: Adds [serde] serializers for common types.
 //!
 //! Support for recording and exporting telemetry asynchronously can be added
@@ -49,10 +58,40 @@
 //! * `rt-tokio`: Spawn telemetry tasks using [tokio]'s multi-thread runtime.
 //! * `rt-tokio-current-thread`: Spawn telemetry tasks on a separate runtime so that the main runtime won't be blocked.
 //! * `rt-async-std`: Spawn telemetry tasks using [async-std]'s runtime.
+//! * `rt-wasm-bindgen`: Spawn telemetry tasks on the event loop of the JavaScript environment of
+//!   `wasm32` targets.
 //!
 //! [tokio]: https://crates.io/crates/tokio
 //! [async-std]: https://crates.io/crates/async-std
 //! [serde]: https://crates.io/crates/serde
+//! [log]: https://crates.io/crates/log
+//! [tracing]: https://crates.io/crates/tracing
+//!
+//! ## no_std support
+//!
+//! Without the `std` feature the crate is `no_std` and only needs `alloc`, so
+//! embedded targets can create spans and forward them over their own
+//! transport. It then contains [`KeyValue`] and the other attribute types,
+//! [`Context`], the trace API with the [`trace::TracerProvider`],
+//! [`trace::Tracer`] and [`trace::Span`] traits and the
+//! [`propagation::TextMapPropagator`], [`propagation::Injector`] and
+//! [`propagation::Extractor`] traits. There are no thread locals and no
+//! system clock without `std`, so:
+//!
+//! * there is no current context, contexts are passed explicitly, e.g. to
+//!   [`trace::Tracer::start_with_context`], and [`Context::attach`],
+//!   [`trace::Tracer::in_span`] and [`trace::mark_span_as_active`] need `std`,
+//! * spans and events are timestamped by their implementation, the
+//!   [`trace::Span::add_event`] and [`trace::Span::end`] methods have no
+//!   default implementation and timestamps are [`time::SystemTime`]s since
+//!   the Unix epoch.
+//!
+//! The globals, the SDK and the `trace` and `metrics` features need `std`.
+//!
+//! ```toml
+//! [dependencies]
+//! opentelemetry = { version = "*", default-features = false }
+//! ```
 //!
 //! ## Working with runtimes
 //!
@@ -72,6 +111,15 @@
 //! task with other tasks in the same runtime. Thus, users should enable `rt-tokio-current-thread` feature
 //! to ask the background task be scheduled on a different runtime on a different thread.
 //!
+//! ### WebAssembly
+//!
+//! The API and SDK compile for `wasm32-unknown-unknown`, with ids generated by the crypto API and
+//! timestamps read from `Date` of the JavaScript environment. Spans are exported in the background
+//! with the [`runtime::WasmBindgen`] runtime of the `rt-wasm-bindgen` feature. Flushing and shutting
+//! down processors doesn't wait for the exports there, as waiting would block the only thread. The
+//! [`opentelemetry-http`] crate provides an `HttpClient` sending requests with `fetch` behind its
+//! `fetch` feature.
+//!
 //! ## Related Crates
 //!
 //! In addition to `opentelemetry`, the [`open-telemetry/opentelemetry-rust`]
@@ -171,51 +219,58 @@
     html_logo_url = "https://raw.githubusercontent.com/open-telemetry/opentelemetry-rust/main/assets/logo.svg"
 )]
 #![cfg_attr(test, deny(warnings))]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 pub mod global;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 pub mod sdk;
 
 #[cfg(feature = "testing")]
 #[allow(missing_docs)]
 pub mod testing;
 
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 pub mod baggage;
 
 mod context;
 
-pub use context::{Context, ContextGuard};
+pub use context::Context;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use context::ContextGuard;
 
 mod core;
 
-pub use crate::core::{Array, Key, KeyValue, Unit, Value};
+pub use crate::core::{Array, Key, KeyValue, Severity, Unit, Value};
 
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 pub mod util;
 
 #[cfg(feature = "metrics")]
 #[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
 pub mod labels;
 
+#[cfg(feature = "logs")]
+#[cfg_attr(docsrs, doc(cfg(feature = "logs")))]
+pub mod logs;
+
 #[cfg(feature = "metrics")]
 #[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
 pub mod metrics;
 
 pub mod propagation;
 
-#[cfg(feature = "trace")]
-#[cfg_attr(docsrs, doc(cfg(feature = "trace")))]
-pub mod trace;
-
-#[cfg(any(feature = "metrics", feature = "trace"))]
-pub(crate) mod time {
-    use std::time::SystemTime;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod runtime;
 
-    #[cfg(not(target_arch = "wasm32"))]
-    pub(crate) fn now() -> SystemTime {
-        SystemTime::now()
-    }
+pub mod trace;
 
-    #[cfg(target_arch = "wasm32")]
-    pub(crate) fn now() -> SystemTime {
-        SystemTime::UNIX_EPOCH + std::time::Duration::from_millis(js_sys::Date::now() as u64)
-    }
-}
+pub mod time;
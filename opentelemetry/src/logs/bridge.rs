@@ -0,0 +1,302 @@
+//! # Bridge from the `log` crate
+//!
+//! Applications and libraries logging with the [`log`] crate ship their logs
+//! through OpenTelemetry once the [`LogBridge`] is installed as the logger of
+//! the `log` facade.
+//!
+//! ```no_run
+//! # #[cfg(feature = "log-bridge")]
+//! # {
+//! use opentelemetry::sdk::logs::LoggerProvider;
+//!
+//! fn main() -> Result<(), log::SetLoggerError> {
+//!     let provider = LoggerProvider::builder().build();
+//!     opentelemetry::logs::install_log_bridge(&provider)?;
+//!
+//!     log::info!("user logged in");
+//!
+//!     Ok(())
+//! }
+//! # }
+//! ```
+//!
+//! Verbose logs can be kept for sampled traces only with a
+//! [`SampledTraceFilter`], so they are available for the requests that are
+//! traced without flooding the logs with the details of every request.
+//!
+//! [`log`]: https://docs.rs/log/0.4
+use crate::logs::{LogRecord, Logger, LoggerProvider, Severity};
+use crate::trace::TraceContextExt;
+use crate::{Context, KeyValue};
+
+/// The name of the instrumentation library of the records bridged from `log`.
+const BRIDGE_NAME: &str = "opentelemetry-log-bridge";
+
+/// Install a [`LogBridge`] emitting to a logger of `provider` as the logger of
+/// the `log` facade.
+///
+/// The maximum level of the facade is raised to the level enabled at compile
+/// time, as records are filtered by the log processors of the provider.
+///
+/// Fails if a logger has been installed already.
+pub fn install_log_bridge<P>(provider: &P) -> Result<(), log::SetLoggerError>
+where
+    P: LoggerProvider,
+    P::Logger: 'static,
+{
+    let logger = provider.get_logger(BRIDGE_NAME, Some(env!("CARGO_PKG_VERSION")));
+    log::set_boxed_logger(Box::new(LogBridge::new(logger)))?;
+    log::set_max_level(log::STATIC_MAX_LEVEL);
+
+    Ok(())
+}
+
+/// A [`log::Log`] implementation converting the records of the `log` crate to
+/// [`LogRecord`]s, emitted to a [`Logger`].
+///
+/// The record is correlated with the span active in the current context. Its
+/// target, module and source location are kept as attributes.
+#[derive(Debug)]
+pub struct LogBridge<L> {
+    logger: L,
+}
+
+impl<L: Logger> LogBridge<L> {
+    /// Create a new bridge emitting to `logger`.
+    pub fn new(logger: L) -> Self {
+        LogBridge { logger }
+    }
+}
+
+impl<L: Logger> log::Log for LogBridge<L> {
+    fn enabled(&self, _metadata: &log::Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        let mut builder = LogRecord::builder()
+            .with_timestamp(crate::time::now())
+            .with_context(&Context::current())
+            .with_severity_text(record.level().to_string())
+            .with_severity_number(severity(record.level()))
+            .with_body(record.args().to_string().into())
+            .with_attribute(KeyValue::new("log.target", record.target().to_string()));
+        if let Some(module_path) = record.module_path() {
+            builder =
+                builder.with_attribute(KeyValue::new("code.namespace", module_path.to_string()));
+        }
+        if let Some(file) = record.file() {
+            builder = builder.with_attribute(KeyValue::new("code.filepath", file.to_string()));
+        }
+        if let Some(line) = record.line() {
+            builder = builder.with_attribute(KeyValue::new("code.lineno", i64::from(line)));
+        }
+
+        self.logger.emit(builder.build());
+    }
+
+    fn flush(&self) {}
+}
+
+/// A [`log::Log`] implementation passing the records more verbose than a
+/// level on to the logger it wraps only while the current context belongs to
+/// a sampled trace, see [`TraceContextExt::is_current_span_sampled`].
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::logs::{LogBridge, NoopLoggerProvider, LoggerProvider, SampledTraceFilter};
+///
+/// let bridge = LogBridge::new(NoopLoggerProvider::new().get_logger("my-app", None));
+/// // debug and trace logs are kept for sampled traces only
+/// let logger = SampledTraceFilter::new(bridge, log::LevelFilter::Info);
+/// ```
+#[derive(Debug)]
+pub struct SampledTraceFilter<L> {
+    logger: L,
+    max_unsampled_level: log::LevelFilter,
+}
+
+impl<L: log::Log> SampledTraceFilter<L> {
+    /// Create a filter passing records up to `max_unsampled_level` on to
+    /// `logger` always, and more verbose ones in sampled traces only.
+    pub fn new(logger: L, max_unsampled_level: log::LevelFilter) -> Self {
+        SampledTraceFilter {
+            logger,
+            max_unsampled_level,
+        }
+    }
+}
+
+impl<L: log::Log> log::Log for SampledTraceFilter<L> {
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        (metadata.level() <= self.max_unsampled_level || Context::is_current_span_sampled())
+            && self.logger.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        if self.enabled(record.metadata()) {
+            self.logger.log(record)
+        }
+    }
+
+    fn flush(&self) {
+        self.logger.flush()
+    }
+}
+
+/// The severity of a `log` level, the levels of the `log` crate map to the
+/// first severity of their range.
+fn severity(level: log::Level) -> Severity {
+    match level {
+        log::Level::Error => Severity::Error,
+        log::Level::Warn => Severity::Warn,
+        log::Level::Info => Severity::Info,
+        log::Level::Debug => Severity::Debug,
+        log::Level::Trace => Severity::Trace,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logs::TraceContext;
+    use crate::testing::trace::TestSpan;
+    use crate::trace::{
+        SpanContext, SpanId, TraceId, TraceState, TRACE_FLAG_NOT_SAMPLED, TRACE_FLAG_SAMPLED,
+    };
+    use crate::Value;
+    use log::Log;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Debug, Default)]
+    struct RecordingLogger(Arc<Mutex<Vec<LogRecord>>>);
+
+    impl Logger for RecordingLogger {
+        fn emit(&self, record: LogRecord) {
+            self.0.lock().unwrap().push(record);
+        }
+    }
+
+    #[test]
+    fn converts_records() {
+        let logger = RecordingLogger::default();
+        let bridge = LogBridge::new(logger.clone());
+
+        let span_context = SpanContext::new(
+            TraceId::from_u128(1),
+            SpanId::from_u64(2),
+            0,
+            false,
+            TraceState::default(),
+        );
+        {
+            let _guard = Context::new()
+                .with_span(TestSpan(span_context.clone()))
+                .attach();
+            bridge.log(
+                &log::Record::builder()
+                    .args(format_args!("user {} logged in", 42))
+                    .level(log::Level::Warn)
+                    .target("auth")
+                    .module_path_static(Some("app::auth"))
+                    .file_static(Some("src/auth.rs"))
+                    .line(Some(7))
+                    .build(),
+            );
+        }
+        bridge.log(
+            &log::Record::builder()
+                .args(format_args!("outside of a span"))
+                .level(log::Level::Trace)
+                .target("app")
+                .build(),
+        );
+
+        let records = logger.0.lock().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records[0].trace_context,
+            Some(TraceContext::from(&span_context))
+        );
+        assert!(records[0].timestamp.is_some());
+        assert_eq!(records[0].severity_number, Some(Severity::Warn));
+        assert_eq!(records[0].severity_text.as_deref(), Some("WARN"));
+        assert_eq!(records[0].body, Some(Value::from("user 42 logged in")));
+        assert_eq!(
+            records[0].attributes,
+            Some(vec![
+                KeyValue::new("log.target", "auth"),
+                KeyValue::new("code.namespace", "app::auth"),
+                KeyValue::new("code.filepath", "src/auth.rs"),
+                KeyValue::new("code.lineno", 7),
+            ])
+        );
+
+        assert_eq!(records[1].trace_context, None);
+        assert_eq!(records[1].severity_number, Some(Severity::Trace));
+        assert_eq!(
+            records[1].attributes,
+            Some(vec![KeyValue::new("log.target", "app")])
+        );
+    }
+
+    #[test]
+    fn passes_verbose_records_of_sampled_traces() {
+        let logger = RecordingLogger::default();
+        let filter =
+            SampledTraceFilter::new(LogBridge::new(logger.clone()), log::LevelFilter::Info);
+        let log = |level, message| {
+            filter.log(
+                &log::Record::builder()
+                    .args(format_args!("{}", message))
+                    .level(level)
+                    .build(),
+            )
+        };
+        let span_context = |trace_flags| {
+            SpanContext::new(
+                TraceId::from_u128(1),
+                SpanId::from_u64(2),
+                trace_flags,
+                true,
+                TraceState::default(),
+            )
+        };
+
+        log(log::Level::Info, "info");
+        log(log::Level::Debug, "debug without a trace");
+        {
+            let _guard = Context::new()
+                .with_remote_span_context(span_context(TRACE_FLAG_SAMPLED))
+                .attach();
+            log(log::Level::Debug, "debug of a sampled remote parent");
+            let _guard = Context::current()
+                .with_span(TestSpan(span_context(TRACE_FLAG_NOT_SAMPLED)))
+                .attach();
+            log(log::Level::Trace, "trace of an unsampled span");
+            log(log::Level::Warn, "warn of an unsampled span");
+        }
+        let _guard = Context::new()
+            .with_span(TestSpan(span_context(TRACE_FLAG_SAMPLED)))
+            .attach();
+        log(log::Level::Trace, "trace of a sampled span");
+
+        let bodies: Vec<_> = logger
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|record| record.body.clone().unwrap())
+            .collect();
+        assert_eq!(
+            bodies,
+            vec![
+                Value::from("info"),
+                Value::from("debug of a sampled remote parent"),
+                Value::from("warn of an unsampled span"),
+                Value::from("trace of a sampled span"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,22 @@
+use crate::logs::LogRecord;
+use std::fmt;
+
+/// Emits [`LogRecord`]s on behalf of an instrumentation library, typically
+/// the appender bridging a logging framework to OpenTelemetry.
+pub trait Logger: fmt::Debug + Send + Sync {
+    /// Emit a log record.
+    ///
+    /// Records without a trace context are correlated with the span active
+    /// in the current context, if any.
+    fn emit(&self, record: LogRecord);
+}
+
+/// An interface to create [`Logger`] instances.
+pub trait LoggerProvider: fmt::Debug + 'static {
+    /// The `Logger` type that this `LoggerProvider` will return.
+    type Logger: Logger;
+
+    /// Creates a named logger instance of `Self::Logger`.
+    /// If the name is an empty string then provider uses default name.
+    fn get_logger(&self, name: &'static str, version: Option<&'static str>) -> Self::Logger;
+}
@@ -0,0 +1,111 @@
+//! # OpenTelemetry Logs API
+//!
+//! The logs API bridges existing logging libraries to OpenTelemetry. A
+//! [`LoggerProvider`] creates named [`Logger`] instances, which emit
+//! [`LogRecord`]s, e.g. from the appender of a logging framework. Log records
+//! emitted while a span is active are correlated with the span's trace
+//! context, so logs can be found from the traces they happened in.
+//!
+//! The API is a no-op unless an SDK like the [`LoggerProvider`] of the
+//! [`sdk::logs`] module is installed.
+//!
+//! ```
+//! use opentelemetry::logs::{LogRecord, Logger, LoggerProvider, NoopLoggerProvider, Severity};
+//! use opentelemetry::KeyValue;
+//!
+//! let logger = NoopLoggerProvider::new().get_logger("my-appender", None);
+//!
+//! logger.emit(
+//!     LogRecord::builder()
+//!         .with_severity_number(Severity::Info)
+//!         .with_body("user logged in".into())
+//!         .with_attributes(vec![KeyValue::new("user.id", 42)])
+//!         .build(),
+//! );
+//! ```
+//!
+//! [`sdk::logs`]: crate::sdk::logs
+//! [`LoggerProvider`]: crate::sdk::logs::LoggerProvider
+use crate::sdk::export::ExportError;
+use futures::channel::{mpsc::TrySendError, oneshot::Canceled};
+use std::time::Duration;
+use thiserror::Error;
+
+#[cfg(feature = "log-bridge")]
+#[cfg_attr(docsrs, doc(cfg(feature = "log-bridge")))]
+mod bridge;
+mod logger;
+mod noop;
+mod record;
+#[cfg(feature = "tracing-layer")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tracing-layer")))]
+mod tracing_layer;
+
+pub use crate::Severity;
+#[cfg(feature = "log-bridge")]
+#[cfg_attr(docsrs, doc(cfg(feature = "log-bridge")))]
+pub use bridge::{install_log_bridge, LogBridge, SampledTraceFilter};
+pub use logger::{Logger, LoggerProvider};
+pub use noop::{NoopLogger, NoopLoggerProvider};
+pub use record::{LogRecord, LogRecordBuilder, TraceContext};
+#[cfg(feature = "tracing-layer")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tracing-layer")))]
+pub use tracing_layer::{log_layer, LogLayer};
+
+/// Describe the result of operations in log SDK.
+pub type LogResult<T> = Result<T, LogError>;
+
+/// Errors returned by the log API.
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum LogError {
+    /// Export failed with the error returned by the exporter
+    #[error("Exporter {} failed with {0}", .0.exporter_name())]
+    ExportFailed(Box<dyn ExportError>),
+
+    /// Export failed to finish after certain period and processor stopped the export.
+    #[error("Exporting timed out after {} seconds", .0.as_secs())]
+    ExportTimedOut(Duration),
+
+    /// Other errors propagated from log SDK that weren't covered above
+    #[error(transparent)]
+    Other(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
+}
+
+impl<T> From<T> for LogError
+where
+    T: ExportError,
+{
+    fn from(err: T) -> Self {
+        LogError::ExportFailed(Box::new(err))
+    }
+}
+
+impl<T> From<TrySendError<T>> for LogError {
+    fn from(err: TrySendError<T>) -> Self {
+        LogError::Other(Box::new(err.into_send_error()))
+    }
+}
+
+impl From<Canceled> for LogError {
+    fn from(err: Canceled) -> Self {
+        LogError::Other(Box::new(err))
+    }
+}
+
+impl From<String> for LogError {
+    fn from(err_msg: String) -> Self {
+        LogError::Other(Box::new(Custom(err_msg)))
+    }
+}
+
+impl From<&'static str> for LogError {
+    fn from(err_msg: &'static str) -> Self {
+        LogError::Other(Box::new(Custom(err_msg.into())))
+    }
+}
+
+/// Wrap type for string
+#[derive(Error, Debug)]
+#[error("{0}")]
+struct Custom(String);
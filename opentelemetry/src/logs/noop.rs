@@ -0,0 +1,48 @@
+//! # No-op OpenTelemetry Logs Implementation
+//!
+//! This implementation is returned as the global logger provider if no
+//! `LoggerProvider` has been set. It is also useful for testing purposes as
+//! it is intended to have minimal resource utilization and runtime impact.
+use crate::logs::{LogRecord, Logger, LoggerProvider};
+
+/// A no-op instance of a `LoggerProvider`.
+#[derive(Clone, Debug, Default)]
+pub struct NoopLoggerProvider {
+    _private: (),
+}
+
+impl NoopLoggerProvider {
+    /// Create a new no-op logger provider.
+    pub fn new() -> Self {
+        NoopLoggerProvider { _private: () }
+    }
+}
+
+impl LoggerProvider for NoopLoggerProvider {
+    type Logger = NoopLogger;
+
+    /// Returns a new `NoopLogger` instance.
+    fn get_logger(&self, _name: &'static str, _version: Option<&'static str>) -> Self::Logger {
+        NoopLogger::new()
+    }
+}
+
+/// A no-op instance of a `Logger`, dropping all records.
+#[derive(Clone, Debug, Default)]
+pub struct NoopLogger {
+    _private: (),
+}
+
+impl NoopLogger {
+    /// Create a new no-op logger.
+    pub fn new() -> Self {
+        NoopLogger { _private: () }
+    }
+}
+
+impl Logger for NoopLogger {
+    /// Ignores the record.
+    fn emit(&self, _record: LogRecord) {
+        // Ignored
+    }
+}
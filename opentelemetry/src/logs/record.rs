@@ -0,0 +1,203 @@
+use crate::trace::{SpanContext, SpanId, TraceContextExt, TraceId};
+use crate::{Context, KeyValue, Severity, Value};
+use std::time::SystemTime;
+
+/// A log record, as emitted by a [`Logger`].
+///
+/// [`Logger`]: crate::logs::Logger
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct LogRecord {
+    /// Time when the event occurred.
+    pub timestamp: Option<SystemTime>,
+
+    /// Time when the event was observed by the collection system, set by the
+    /// SDK when emitting records without one.
+    pub observed_timestamp: Option<SystemTime>,
+
+    /// The trace context of the span the event occurred in.
+    pub trace_context: Option<TraceContext>,
+
+    /// The severity as known by the source, e.g. `WARN` or `warning`.
+    pub severity_text: Option<String>,
+
+    /// The normalized severity.
+    pub severity_number: Option<Severity>,
+
+    /// The body of the record, e.g. a human readable message.
+    pub body: Option<Value>,
+
+    /// Additional information about the event.
+    pub attributes: Option<Vec<KeyValue>>,
+}
+
+impl LogRecord {
+    /// Create a [`LogRecordBuilder`] to create a new log record.
+    pub fn builder() -> LogRecordBuilder {
+        LogRecordBuilder::default()
+    }
+}
+
+/// The trace context a log record is correlated with.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceContext {
+    /// The trace id of the span.
+    pub trace_id: TraceId,
+    /// The span id of the span.
+    pub span_id: SpanId,
+    /// The trace flags of the span.
+    pub trace_flags: Option<u8>,
+}
+
+impl From<&SpanContext> for TraceContext {
+    fn from(span_context: &SpanContext) -> Self {
+        TraceContext {
+            trace_id: span_context.trace_id(),
+            span_id: span_context.span_id(),
+            trace_flags: Some(span_context.trace_flags()),
+        }
+    }
+}
+
+/// Builder for [`LogRecord`].
+#[derive(Clone, Debug, Default)]
+pub struct LogRecordBuilder {
+    record: LogRecord,
+}
+
+impl LogRecordBuilder {
+    /// Assign the time the event occurred.
+    pub fn with_timestamp(self, timestamp: SystemTime) -> Self {
+        LogRecordBuilder {
+            record: LogRecord {
+                timestamp: Some(timestamp),
+                ..self.record
+            },
+        }
+    }
+
+    /// Assign the time the event was observed.
+    pub fn with_observed_timestamp(self, observed_timestamp: SystemTime) -> Self {
+        LogRecordBuilder {
+            record: LogRecord {
+                observed_timestamp: Some(observed_timestamp),
+                ..self.record
+            },
+        }
+    }
+
+    /// Correlate the record with a span.
+    pub fn with_span_context(self, span_context: &SpanContext) -> Self {
+        LogRecordBuilder {
+            record: LogRecord {
+                trace_context: Some(span_context.into()),
+                ..self.record
+            },
+        }
+    }
+
+    /// Correlate the record with the active span of `cx`, if any.
+    pub fn with_context(self, cx: &Context) -> Self {
+        if cx.has_active_span() {
+            self.with_span_context(cx.span().span_context())
+        } else {
+            self
+        }
+    }
+
+    /// Assign the severity as known by the source.
+    pub fn with_severity_text<T: Into<String>>(self, severity_text: T) -> Self {
+        LogRecordBuilder {
+            record: LogRecord {
+                severity_text: Some(severity_text.into()),
+                ..self.record
+            },
+        }
+    }
+
+    /// Assign the normalized severity.
+    pub fn with_severity_number(self, severity_number: Severity) -> Self {
+        LogRecordBuilder {
+            record: LogRecord {
+                severity_number: Some(severity_number),
+                ..self.record
+            },
+        }
+    }
+
+    /// Assign the body.
+    pub fn with_body(self, body: Value) -> Self {
+        LogRecordBuilder {
+            record: LogRecord {
+                body: Some(body),
+                ..self.record
+            },
+        }
+    }
+
+    /// Assign the attributes, replacing the ones assigned before.
+    pub fn with_attributes(self, attributes: Vec<KeyValue>) -> Self {
+        LogRecordBuilder {
+            record: LogRecord {
+                attributes: Some(attributes),
+                ..self.record
+            },
+        }
+    }
+
+    /// Add an attribute.
+    pub fn with_attribute(mut self, attribute: KeyValue) -> Self {
+        self.record
+            .attributes
+            .get_or_insert_with(Vec::new)
+            .push(attribute);
+        self
+    }
+
+    /// Build the record.
+    pub fn build(self) -> LogRecord {
+        self.record
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::trace::TestSpan;
+    use crate::trace::{TraceState, TRACE_FLAG_SAMPLED};
+
+    #[test]
+    fn builder_correlates_with_active_span() {
+        let span_context = SpanContext::new(
+            TraceId::from_u128(1),
+            SpanId::from_u64(2),
+            TRACE_FLAG_SAMPLED,
+            false,
+            TraceState::default(),
+        );
+        let cx = Context::new().with_span(TestSpan(span_context));
+
+        let record = LogRecord::builder()
+            .with_context(&cx)
+            .with_severity_number(Severity::Warn)
+            .with_attribute(KeyValue::new("a", 1))
+            .with_attribute(KeyValue::new("b", 2))
+            .build();
+        assert_eq!(
+            record.trace_context,
+            Some(TraceContext {
+                trace_id: TraceId::from_u128(1),
+                span_id: SpanId::from_u64(2),
+                trace_flags: Some(TRACE_FLAG_SAMPLED),
+            })
+        );
+        assert_eq!(record.severity_number.map(|s| s.name()), Some("WARN"));
+        assert_eq!(
+            record.attributes.map(|attributes| attributes.len()),
+            Some(2)
+        );
+
+        let record = LogRecord::builder().with_context(&Context::new()).build();
+        assert_eq!(record.trace_context, None);
+    }
+}
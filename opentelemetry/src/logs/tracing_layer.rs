@@ -0,0 +1,211 @@
+//! # Layer for the `tracing` ecosystem
+//!
+//! The [`LogLayer`] is a [`tracing-subscriber`] layer emitting the events of
+//! [`tracing`] as OpenTelemetry log records, so they are shipped next to the
+//! spans of the application.
+//!
+//! ```no_run
+//! # #[cfg(feature = "tracing-layer")]
+//! # {
+//! use opentelemetry::sdk::logs::LoggerProvider;
+//! use tracing_subscriber::layer::SubscriberExt;
+//!
+//! let provider = LoggerProvider::builder().build();
+//! let subscriber = tracing_subscriber::registry()
+//!     .with(opentelemetry::logs::log_layer(&provider));
+//! tracing::subscriber::set_global_default(subscriber).expect("a subscriber is set already");
+//!
+//! tracing::info!(user_id = 42, "user logged in");
+//! # }
+//! ```
+//!
+//! [`tracing-subscriber`]: https://docs.rs/tracing-subscriber/0.2
+//! [`tracing`]: https://docs.rs/tracing/0.1
+use crate::logs::{LogRecord, LogRecordBuilder, Logger, LoggerProvider, Severity};
+use crate::{Context, KeyValue, Value};
+use std::convert::TryFrom;
+use std::fmt;
+use tracing_core::{field::Field, field::Visit, Event, Level, Subscriber};
+use tracing_subscriber::layer;
+
+/// The name of the instrumentation library of the records emitted by the layer.
+const LAYER_NAME: &str = "opentelemetry-tracing-layer";
+
+/// The field of tracing events holding the formatted message.
+const MESSAGE_FIELD: &str = "message";
+
+/// Create a [`LogLayer`] emitting to a logger of `provider`.
+pub fn log_layer<P: LoggerProvider>(provider: &P) -> LogLayer<P::Logger> {
+    LogLayer::new(provider.get_logger(LAYER_NAME, Some(env!("CARGO_PKG_VERSION"))))
+}
+
+/// A [`tracing-subscriber`] layer converting the events of `tracing` to
+/// [`LogRecord`]s, emitted to a [`Logger`].
+///
+/// The message of an event becomes the body of the record, its other fields
+/// are kept as attributes with their types. The record is correlated with the
+/// OpenTelemetry span active in the current context.
+///
+/// [`tracing-subscriber`]: https://docs.rs/tracing-subscriber/0.2
+#[derive(Debug)]
+pub struct LogLayer<L> {
+    logger: L,
+}
+
+impl<L: Logger> LogLayer<L> {
+    /// Create a new layer emitting to `logger`.
+    pub fn new(logger: L) -> Self {
+        LogLayer { logger }
+    }
+}
+
+impl<S, L> layer::Layer<S> for LogLayer<L>
+where
+    S: Subscriber,
+    L: Logger + 'static,
+{
+    fn on_event(&self, event: &Event<'_>, _ctx: layer::Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = EventVisitor {
+            builder: LogRecord::builder()
+                .with_timestamp(crate::time::now())
+                .with_context(&Context::current())
+                .with_severity_text(metadata.level().to_string())
+                .with_severity_number(severity(metadata.level()))
+                .with_attribute(KeyValue::new("log.target", metadata.target().to_string())),
+        };
+        if let Some(module_path) = metadata.module_path() {
+            visitor.attribute("code.namespace", module_path.to_string());
+        }
+        if let Some(file) = metadata.file() {
+            visitor.attribute("code.filepath", file.to_string());
+        }
+        if let Some(line) = metadata.line() {
+            visitor.attribute("code.lineno", i64::from(line));
+        }
+        event.record(&mut visitor);
+
+        self.logger.emit(visitor.builder.build());
+    }
+}
+
+/// The severity of a `tracing` level, the levels of `tracing` map to the first
+/// severity of their range.
+fn severity(level: &Level) -> Severity {
+    match *level {
+        Level::ERROR => Severity::Error,
+        Level::WARN => Severity::Warn,
+        Level::INFO => Severity::Info,
+        Level::DEBUG => Severity::Debug,
+        _ => Severity::Trace,
+    }
+}
+
+struct EventVisitor {
+    builder: LogRecordBuilder,
+}
+
+impl EventVisitor {
+    fn attribute<T: Into<Value>>(&mut self, key: &'static str, value: T) {
+        let builder = std::mem::take(&mut self.builder);
+        self.builder = builder.with_attribute(KeyValue::new(key, value));
+    }
+
+    fn field<T: Into<Value>>(&mut self, field: &Field, value: T) {
+        let builder = std::mem::take(&mut self.builder);
+        self.builder = if field.name() == MESSAGE_FIELD {
+            builder.with_body(value.into())
+        } else {
+            builder.with_attribute(KeyValue::new(field.name(), value))
+        };
+    }
+}
+
+impl Visit for EventVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.field(field, value)
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.field(field, value)
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        match i64::try_from(value) {
+            Ok(value) => self.field(field, value),
+            Err(_) => self.field(field, value.to_string()),
+        }
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.field(field, value)
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.field(field, value.to_string())
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.field(field, format!("{:?}", value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logs::TraceContext;
+    use crate::testing::trace::TestSpan;
+    use crate::trace::{SpanContext, SpanId, TraceContextExt, TraceId, TraceState};
+    use std::sync::{Arc, Mutex};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[derive(Clone, Debug, Default)]
+    struct RecordingLogger(Arc<Mutex<Vec<LogRecord>>>);
+
+    impl Logger for RecordingLogger {
+        fn emit(&self, record: LogRecord) {
+            self.0.lock().unwrap().push(record);
+        }
+    }
+
+    #[test]
+    fn converts_events() {
+        let logger = RecordingLogger::default();
+        let subscriber = tracing_subscriber::registry().with(LogLayer::new(logger.clone()));
+
+        let span_context = SpanContext::new(
+            TraceId::from_u128(1),
+            SpanId::from_u64(2),
+            0,
+            false,
+            TraceState::default(),
+        );
+        tracing::subscriber::with_default(subscriber, || {
+            let _guard = Context::new()
+                .with_span(TestSpan(span_context.clone()))
+                .attach();
+            tracing::warn!(target: "auth", user_id = 42, admin = false, "user {} logged in", "ann");
+        });
+
+        let records = logger.0.lock().unwrap();
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(
+            record.trace_context,
+            Some(TraceContext::from(&span_context))
+        );
+        assert!(record.timestamp.is_some());
+        assert_eq!(record.severity_number, Some(Severity::Warn));
+        assert_eq!(record.severity_text.as_deref(), Some("WARN"));
+        assert_eq!(record.body, Some(Value::from("user ann logged in")));
+
+        let attributes = record.attributes.as_ref().unwrap();
+        assert_eq!(attributes[0], KeyValue::new("log.target", "auth"));
+        assert!(attributes.contains(&KeyValue::new(
+            "code.namespace",
+            "opentelemetry::logs::tracing_layer::tests"
+        )));
+        assert!(attributes.contains(&KeyValue::new("user_id", 42)));
+        assert!(attributes.contains(&KeyValue::new("admin", false)));
+    }
+}
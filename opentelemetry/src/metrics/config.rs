@@ -15,10 +15,7 @@ impl InstrumentConfig {
         InstrumentConfig {
             description: None,
             unit: None,
-            instrumentation_library: InstrumentationLibrary {
-                name: instrumentation_name,
-                version: None,
-            },
+            instrumentation_library: InstrumentationLibrary::new(instrumentation_name, None),
         }
     }
 
@@ -30,10 +27,10 @@ impl InstrumentConfig {
         InstrumentConfig {
             description: None,
             unit: None,
-            instrumentation_library: InstrumentationLibrary {
-                name: instrumentation_name,
-                version: instrumentation_version,
-            },
+            instrumentation_library: InstrumentationLibrary::new(
+                instrumentation_name,
+                instrumentation_version,
+            ),
         }
     }
 
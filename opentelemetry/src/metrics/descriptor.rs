@@ -11,6 +11,7 @@ pub struct Descriptor {
     instrument_kind: InstrumentKind,
     number_kind: NumberKind,
     pub(crate) config: InstrumentConfig,
+    histogram_boundaries: Option<Vec<f64>>,
     attribute_hash: u64,
 }
 
@@ -23,14 +24,7 @@ impl Descriptor {
         instrument_kind: InstrumentKind,
         number_kind: NumberKind,
     ) -> Self {
-        let mut hasher = FnvHasher::default();
-        name.hash(&mut hasher);
-        instrumentation_name.hash(&mut hasher);
-        instrumentation_version.hash(&mut hasher);
-        instrument_kind.hash(&mut hasher);
-        number_kind.hash(&mut hasher);
-
-        Descriptor {
+        let mut descriptor = Descriptor {
             name,
             instrument_kind,
             number_kind,
@@ -38,8 +32,34 @@ impl Descriptor {
                 instrumentation_name,
                 instrumentation_version,
             ),
-            attribute_hash: hasher.finish(),
-        }
+            histogram_boundaries: None,
+            attribute_hash: 0,
+        };
+        descriptor.attribute_hash = descriptor.compute_attribute_hash();
+        descriptor
+    }
+
+    fn compute_attribute_hash(&self) -> u64 {
+        let mut hasher = FnvHasher::default();
+        self.name.hash(&mut hasher);
+        self.instrumentation_name().hash(&mut hasher);
+        self.instrumentation_version().hash(&mut hasher);
+        self.instrument_kind.hash(&mut hasher);
+        self.number_kind.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Rename the instrument, e.g. as configured by a view.
+    pub(crate) fn with_name(mut self, name: String) -> Self {
+        self.name = name;
+        self.attribute_hash = self.compute_attribute_hash();
+        self
+    }
+
+    /// Set the schema url of the instrumentation library.
+    pub(crate) fn with_schema_url(mut self, schema_url: Option<&'static str>) -> Self {
+        self.config.instrumentation_library.schema_url = schema_url;
+        self
     }
 
     /// The metric instrument's name.
@@ -73,6 +93,18 @@ impl Descriptor {
         self.config.unit.as_ref().map(|unit| unit.as_ref())
     }
 
+    /// Bucket boundaries recommended by the instrumentation for histograms of
+    /// this instrument.
+    pub fn histogram_boundaries(&self) -> Option<&[f64]> {
+        self.histogram_boundaries.as_deref()
+    }
+
+    /// Recommend the bucket `boundaries` of histograms aggregating this
+    /// instrument, instead of the default boundaries of the SDK.
+    pub fn set_histogram_boundaries(&mut self, boundaries: Vec<f64>) {
+        self.histogram_boundaries = Some(boundaries);
+    }
+
     /// The name of the library that provided instrumentation for this instrument.
     pub fn instrumentation_name(&self) -> &'static str {
         self.config.instrumentation_name()
@@ -23,6 +23,22 @@ pub trait MeterProvider: fmt::Debug {
         instrumentation_name: &'static str,
         instrumentation_version: Option<&'static str>,
     ) -> Meter;
+
+    /// Collect and export the measurements recorded so far, waiting for the
+    /// export to finish.
+    ///
+    /// Only has an effect for providers exporting in the background.
+    fn force_flush(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Stop exporting in the background, after a final collection and export
+    /// of the measurements recorded so far.
+    ///
+    /// Measurements recorded after the shutdown are not exported anymore.
+    fn shutdown(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// Meter is the OpenTelemetry metric API, based on a sdk-defined `MeterCore`
@@ -60,8 +76,18 @@ impl Meter {
         }
     }
 
+    /// Set the schema url of the semantic conventions used by the
+    /// instrumentation library.
+    ///
+    /// Instruments created by this meter report the schema url as part of
+    /// their instrumentation library.
+    pub fn with_schema_url(mut self, schema_url: &'static str) -> Self {
+        self.instrumentation_library.schema_url = Some(schema_url);
+        self
+    }
+
     pub(crate) fn instrumentation_library(&self) -> InstrumentationLibrary {
-        self.instrumentation_library
+        self.instrumentation_library.clone()
     }
 
     /// Creates a new integer `CounterBuilder` for `u64` values with the given name.
@@ -233,6 +259,136 @@ impl Meter {
         )
     }
 
+    /// Creates a new `ObservableCounter` builder for `u64` values with the given
+    /// name, observed by the callback set with `with_callback` or registered
+    /// with [`register_callback`].
+    ///
+    /// [`register_callback`]: Meter::register_callback()
+    pub fn u64_observable_counter<T>(&self, name: T) -> SumObserverBuilder<'_, u64>
+    where
+        T: Into<String>,
+    {
+        SumObserverBuilder::new(self, name.into(), None, NumberKind::U64)
+    }
+
+    /// Creates a new `ObservableCounter` builder for `f64` values with the given
+    /// name, observed by the callback set with `with_callback` or registered
+    /// with [`register_callback`].
+    ///
+    /// [`register_callback`]: Meter::register_callback()
+    pub fn f64_observable_counter<T>(&self, name: T) -> SumObserverBuilder<'_, f64>
+    where
+        T: Into<String>,
+    {
+        SumObserverBuilder::new(self, name.into(), None, NumberKind::F64)
+    }
+
+    /// Creates a new `ObservableUpDownCounter` builder for `i64` values with the
+    /// given name, observed by the callback set with `with_callback` or
+    /// registered with [`register_callback`].
+    ///
+    /// [`register_callback`]: Meter::register_callback()
+    pub fn i64_observable_up_down_counter<T>(&self, name: T) -> UpDownSumObserverBuilder<'_, i64>
+    where
+        T: Into<String>,
+    {
+        UpDownSumObserverBuilder::new(self, name.into(), None, NumberKind::I64)
+    }
+
+    /// Creates a new `ObservableUpDownCounter` builder for `f64` values with the
+    /// given name, observed by the callback set with `with_callback` or
+    /// registered with [`register_callback`].
+    ///
+    /// [`register_callback`]: Meter::register_callback()
+    pub fn f64_observable_up_down_counter<T>(&self, name: T) -> UpDownSumObserverBuilder<'_, f64>
+    where
+        T: Into<String>,
+    {
+        UpDownSumObserverBuilder::new(self, name.into(), None, NumberKind::F64)
+    }
+
+    /// Creates a new `ObservableGauge` builder for `u64` values with the given
+    /// name, observed by the callback set with `with_callback` or registered
+    /// with [`register_callback`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use opentelemetry::{global, KeyValue};
+    ///
+    /// let meter = global::meter("test");
+    /// let _rss = meter
+    ///     .u64_observable_gauge("process.memory.rss")
+    ///     .with_callback(|observer| observer.observe(42 * 1024 * 1024, &[]))
+    ///     .init();
+    /// ```
+    ///
+    /// [`register_callback`]: Meter::register_callback()
+    pub fn u64_observable_gauge<T>(&self, name: T) -> ValueObserverBuilder<'_, u64>
+    where
+        T: Into<String>,
+    {
+        ValueObserverBuilder::new(self, name.into(), None, NumberKind::U64)
+    }
+
+    /// Creates a new `ObservableGauge` builder for `i64` values with the given
+    /// name, observed by the callback set with `with_callback` or registered
+    /// with [`register_callback`].
+    ///
+    /// [`register_callback`]: Meter::register_callback()
+    pub fn i64_observable_gauge<T>(&self, name: T) -> ValueObserverBuilder<'_, i64>
+    where
+        T: Into<String>,
+    {
+        ValueObserverBuilder::new(self, name.into(), None, NumberKind::I64)
+    }
+
+    /// Creates a new `ObservableGauge` builder for `f64` values with the given
+    /// name, observed by the callback set with `with_callback` or registered
+    /// with [`register_callback`].
+    ///
+    /// [`register_callback`]: Meter::register_callback()
+    pub fn f64_observable_gauge<T>(&self, name: T) -> ValueObserverBuilder<'_, f64>
+    where
+        T: Into<String>,
+    {
+        ValueObserverBuilder::new(self, name.into(), None, NumberKind::F64)
+    }
+
+    /// Register a callback observing several asynchronous instruments, run on
+    /// each collection.
+    ///
+    /// The observations passed together to [`BatchObserverResult::observe`]
+    /// are recorded atomically, so related values like the length and the
+    /// capacity of a queue are always collected from the same moment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use opentelemetry::{global, metrics::BatchObserverResult, KeyValue};
+    ///
+    /// # fn init_observer() -> opentelemetry::metrics::Result<()> {
+    /// let meter = global::meter("test");
+    /// let length = meter.i64_observable_up_down_counter("queue.length").try_init()?;
+    /// let capacity = meter.i64_observable_up_down_counter("queue.capacity").try_init()?;
+    ///
+    /// meter.register_callback(move |result: BatchObserverResult| {
+    ///     result.observe(
+    ///         &[KeyValue::new("queue", "jobs")],
+    ///         &[length.observation(3), capacity.observation(64)],
+    ///     );
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn register_callback<F>(&self, callback: F) -> Result<()>
+    where
+        F: Fn(BatchObserverResult) + Send + Sync + 'static,
+    {
+        self.core
+            .new_batch_observer(AsyncRunner::Batch(Box::new(callback)))
+    }
+
     /// Creates a new `BatchObserver` that supports making batches of observations
     /// for multiple instruments or returns an error if instrument initialization
     /// fails.
@@ -28,8 +28,9 @@ pub use kind::InstrumentKind;
 pub use meter::{Meter, MeterProvider};
 pub use number::{AtomicNumber, Number, NumberKind};
 pub use observer::{
-    BatchObserver, SumObserver, SumObserverBuilder, UpDownSumObserver, UpDownSumObserverBuilder,
-    ValueObserver, ValueObserverBuilder,
+    BatchObserver, ObservableCounter, ObservableGauge, ObservableUpDownCounter, SumObserver,
+    SumObserverBuilder, UpDownSumObserver, UpDownSumObserverBuilder, ValueObserver,
+    ValueObserverBuilder,
 };
 pub use sync_instrument::Measurement;
 pub use up_down_counter::{BoundUpDownCounter, UpDownCounter, UpDownCounterBuilder};
@@ -1,6 +1,6 @@
 use crate::metrics::{
     sdk_api, AsyncRunner, Descriptor, InstrumentKind, Meter, Number, NumberKind, Observation,
-    Result,
+    ObserverResult, Result,
 };
 use crate::Unit;
 use std::sync::Arc;
@@ -116,7 +116,8 @@ impl<'a, T> SumObserverBuilder<'a, T> {
                 meter.instrumentation_library().version,
                 InstrumentKind::SumObserver,
                 number_kind,
-            ),
+            )
+            .with_schema_url(meter.instrumentation_library().schema_url),
             runner,
             _marker: std::marker::PhantomData,
         }
@@ -209,7 +210,8 @@ impl<'a, T> UpDownSumObserverBuilder<'a, T> {
                 meter.instrumentation_library().version,
                 InstrumentKind::UpDownSumObserver,
                 number_kind,
-            ),
+            )
+            .with_schema_url(meter.instrumentation_library().schema_url),
             runner,
             _marker: std::marker::PhantomData,
         }
@@ -301,7 +303,8 @@ impl<'a, T> ValueObserverBuilder<'a, T> {
                 meter.instrumentation_library().version,
                 InstrumentKind::ValueObserver,
                 number_kind,
-            ),
+            )
+            .with_schema_url(meter.instrumentation_library().schema_url),
             runner,
             _marker: std::marker::PhantomData,
         }
@@ -348,3 +351,41 @@ impl<'a, T> ValueObserverBuilder<'a, T> {
         }
     }
 }
+
+/// An asynchronous instrument reporting a monotonic sum, e.g. the CPU time
+/// used by the process.
+pub type ObservableCounter<T> = SumObserver<T>;
+
+/// An asynchronous instrument reporting a non-monotonic sum, e.g. the number
+/// of items in a queue.
+pub type ObservableUpDownCounter<T> = UpDownSumObserver<T>;
+
+/// An asynchronous instrument reporting the current value of a measurement,
+/// e.g. the resident set size of the process.
+pub type ObservableGauge<T> = ValueObserver<T>;
+
+macro_rules! with_callback {
+    ($builder:ident, $number:ty, $runner:ident) => {
+        impl<'a> $builder<'a, $number> {
+            /// Set the callback observing this instrument.
+            ///
+            /// The callback is run on each collection, it replaces any callback
+            /// set before.
+            pub fn with_callback<F>(mut self, callback: F) -> Self
+            where
+                F: Fn(ObserverResult<$number>) + Send + Sync + 'static,
+            {
+                self.runner = Some(AsyncRunner::$runner(Box::new(callback)));
+                self
+            }
+        }
+    };
+}
+
+with_callback!(SumObserverBuilder, u64, U64);
+with_callback!(SumObserverBuilder, f64, F64);
+with_callback!(UpDownSumObserverBuilder, i64, I64);
+with_callback!(UpDownSumObserverBuilder, f64, F64);
+with_callback!(ValueObserverBuilder, u64, U64);
+with_callback!(ValueObserverBuilder, i64, I64);
+with_callback!(ValueObserverBuilder, f64, F64);
@@ -23,6 +23,14 @@ impl MeterProvider for RegistryMeterProvider {
     fn meter(&self, name: &'static str, version: Option<&'static str>) -> Meter {
         Meter::new(name, version, self.0.clone())
     }
+
+    fn force_flush(&self) -> Result<()> {
+        self.0.force_flush()
+    }
+
+    fn shutdown(&self) -> Result<()> {
+        self.0.shutdown()
+    }
 }
 
 #[derive(Debug)]
@@ -100,6 +108,14 @@ impl MeterCore for UniqueInstrumentMeterCore {
     fn new_batch_observer(&self, runner: AsyncRunner) -> Result<()> {
         self.inner.new_batch_observer(runner)
     }
+
+    fn force_flush(&self) -> Result<()> {
+        self.inner.force_flush()
+    }
+
+    fn shutdown(&self) -> Result<()> {
+        self.inner.shutdown()
+    }
 }
 
 fn check_sync_uniqueness(
@@ -29,6 +29,18 @@ pub trait MeterCore: fmt::Debug {
 
     /// Register a batch observer
     fn new_batch_observer(&self, runner: AsyncRunner) -> Result<()>;
+
+    /// Collect and export the measurements recorded so far, for
+    /// implementations exporting them in the background.
+    fn force_flush(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Stop exporting in the background, after a final collection and export,
+    /// for implementations exporting in the background.
+    fn shutdown(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// A common interface for synchronous and asynchronous instruments.
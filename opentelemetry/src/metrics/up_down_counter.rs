@@ -73,7 +73,8 @@ impl<'a, T> UpDownCounterBuilder<'a, T> {
                 meter.instrumentation_library().version,
                 InstrumentKind::UpDownCounter,
                 number_kind,
-            ),
+            )
+            .with_schema_url(meter.instrumentation_library().schema_url),
             _marker: marker::PhantomData,
         }
     }
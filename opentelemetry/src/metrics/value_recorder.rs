@@ -74,7 +74,8 @@ impl<'a, T> ValueRecorderBuilder<'a, T> {
                 meter.instrumentation_library().version,
                 InstrumentKind::ValueRecorder,
                 number_kind,
-            ),
+            )
+            .with_schema_url(meter.instrumentation_library().schema_url),
             _marker: marker::PhantomData,
         }
     }
@@ -91,6 +92,16 @@ impl<'a, T> ValueRecorderBuilder<'a, T> {
         self
     }
 
+    /// Set the bucket boundaries of histograms aggregating this
+    /// `ValueRecorder`.
+    ///
+    /// The boundaries are a recommendation, they are only used if the SDK
+    /// aggregates the recorder into a histogram and no view overrides them.
+    pub fn with_histogram_boundaries(mut self, boundaries: Vec<f64>) -> Self {
+        self.descriptor.set_histogram_boundaries(boundaries);
+        self
+    }
+
     /// Creates a new value recorder.
     pub fn try_init(self) -> Result<ValueRecorder<T>> {
         let instrument = self.meter.new_sync_instrument(self.descriptor)?;
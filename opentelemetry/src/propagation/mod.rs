@@ -161,6 +161,8 @@
 //!
 //! The Keys function returns a vector of the propagation keys.
 //!
+use alloc::{string::String, vec::Vec};
+#[cfg(feature = "std")]
 use std::collections::HashMap;
 
 pub mod text_map_propagator;
@@ -182,6 +184,7 @@ pub trait Extractor {
     fn keys(&self) -> Vec<&str>;
 }
 
+#[cfg(feature = "std")]
 impl<S: std::hash::BuildHasher> Injector for HashMap<String, String, S> {
     /// Set a key and value in the HashMap.
     fn set(&mut self, key: &str, value: String) {
@@ -189,6 +192,7 @@ impl<S: std::hash::BuildHasher> Injector for HashMap<String, String, S> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<S: std::hash::BuildHasher> Extractor for HashMap<String, String, S> {
     /// Get a value for a key from the HashMap.
     fn get(&self, key: &str) -> Option<&str> {
@@ -201,7 +205,7 @@ impl<S: std::hash::BuildHasher> Extractor for HashMap<String, String, S> {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
     use std::collections::HashMap;
@@ -6,12 +6,15 @@ use crate::{
     propagation::{Extractor, Injector},
     Context,
 };
-use std::fmt::Debug;
-use std::slice;
+use alloc::string::String;
+use core::fmt::Debug;
+use core::slice;
 
 /// Methods to inject and extract a value as text into injectors and extractors that travel
 /// in-band across process boundaries.
 pub trait TextMapPropagator: Debug {
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
     /// Properly encodes the values of the current [`Context`] and injects them into
     /// the [`Injector`].
     ///
@@ -28,6 +31,8 @@ pub trait TextMapPropagator: Debug {
     /// [`Injector`]: crate::propagation::Injector
     fn inject_context(&self, cx: &Context, injector: &mut dyn Injector);
 
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
     /// Retrieves encoded data using the provided [`Extractor`]. If no data for this
     /// format was retrieved OR if the retrieved data is invalid, then the current
     /// [`Context`] is returned.
@@ -0,0 +1,223 @@
+//! # Async Runtimes
+//!
+//! The batch processors export on a background task, which needs an async
+//! runtime to spawn it and to wait for timers. The [`Runtime`] trait is the
+//! abstraction over those runtimes, with implementations for [Tokio] and
+//! [async-std] behind the `rt-tokio`, `rt-tokio-current-thread` and
+//! `rt-async-std` features, and for the event loop of browsers and other
+//! JavaScript environments behind the `rt-wasm-bindgen` feature.
+//!
+//! ```
+//! # #[cfg(all(feature = "trace", feature = "rt-tokio"))]
+//! # {
+//! use opentelemetry::{runtime, sdk::trace as sdktrace, trace as apitrace};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let batch = sdktrace::BatchSpanProcessor::builder(
+//!         apitrace::NoopSpanExporter::new(),
+//!         runtime::Tokio,
+//!     )
+//!     .build();
+//!     # drop(batch)
+//! }
+//! # }
+//! ```
+//!
+//! [Tokio]: https://crates.io/crates/tokio
+//! [async-std]: https://crates.io/crates/async-std
+#[cfg(feature = "rt-wasm-bindgen")]
+use futures::{channel::oneshot, stream::BoxStream, FutureExt, StreamExt};
+use futures::{future::BoxFuture, Stream};
+use std::{future::Future, time::Duration};
+
+/// An async runtime the batch processors spawn their workers on.
+///
+/// Further primitives, like timeouts or channels, can be added as provided
+/// methods without breaking the builders taking a runtime.
+pub trait Runtime: Clone + Send + Sync + 'static {
+    /// A stream yielding an item every interval, the items are ignored.
+    type Interval: Stream + Send;
+
+    /// A future resolving after a delay, its output is ignored.
+    type Delay: Future + Send;
+
+    /// Create a stream yielding an item every `duration`.
+    fn interval(&self, duration: Duration) -> Self::Interval;
+
+    /// Spawn a task running `future` to completion.
+    ///
+    /// Processors block the calling thread while waiting for their worker to
+    /// flush or shut down, so the task has to keep running while the thread
+    /// spawning it is blocked.
+    fn spawn(&self, future: BoxFuture<'static, ()>);
+
+    /// Create a future resolving after `duration`.
+    fn delay(&self, duration: Duration) -> Self::Delay;
+}
+
+/// The multi threaded [Tokio] runtime the caller runs on.
+///
+/// [Tokio]: https://crates.io/crates/tokio
+#[cfg(feature = "rt-tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rt-tokio")))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Tokio;
+
+#[cfg(feature = "rt-tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rt-tokio")))]
+impl Runtime for Tokio {
+    type Interval = tokio_stream::wrappers::IntervalStream;
+    type Delay = tokio::time::Sleep;
+
+    fn interval(&self, duration: Duration) -> Self::Interval {
+        crate::util::tokio_interval_stream(duration)
+    }
+
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        tokio::spawn(future);
+    }
+
+    fn delay(&self, duration: Duration) -> Self::Delay {
+        tokio::time::sleep(duration)
+    }
+}
+
+/// A current thread [Tokio] runtime on a dedicated background thread.
+///
+/// Use this runtime when the application runs on a current thread runtime,
+/// e.g. `#[tokio::main(flavor = "current_thread")]` or `#[tokio::test]`. Its
+/// single thread is blocked while a processor waits for its worker to flush or
+/// shut down, so a worker spawned on it with [`Tokio`] never runs and flushing
+/// deadlocks.
+///
+/// Every task spawned from outside gets a thread running its own current
+/// thread runtime until the task completes. Tasks spawned from such a task,
+/// like offloaded exports, run on the same runtime instead of starting another
+/// thread.
+///
+/// [Tokio]: https://crates.io/crates/tokio
+#[cfg(feature = "rt-tokio-current-thread")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rt-tokio-current-thread")))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TokioCurrentThread;
+
+#[cfg(feature = "rt-tokio-current-thread")]
+thread_local! {
+    /// Whether the current thread is a background thread of [`TokioCurrentThread`].
+    // const initializers need a newer compiler than the supported one
+    #[allow(clippy::missing_const_for_thread_local)]
+    static ON_BACKGROUND_THREAD: std::cell::Cell<bool> = std::cell::Cell::new(false);
+}
+
+#[cfg(feature = "rt-tokio-current-thread")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rt-tokio-current-thread")))]
+impl Runtime for TokioCurrentThread {
+    type Interval = tokio_stream::wrappers::IntervalStream;
+    type Delay = tokio::time::Sleep;
+
+    fn interval(&self, duration: Duration) -> Self::Interval {
+        crate::util::tokio_interval_stream(duration)
+    }
+
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        if ON_BACKGROUND_THREAD.with(|background| background.get()) {
+            tokio::spawn(future);
+            return;
+        }
+
+        std::thread::Builder::new()
+            .name("opentelemetry-background-worker".to_string())
+            .spawn(move || {
+                ON_BACKGROUND_THREAD.with(|background| background.set(true));
+                let rt = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .expect("failed to create the tokio runtime of the background thread");
+                rt.block_on(future);
+            })
+            .expect("failed to spawn the background thread");
+    }
+
+    fn delay(&self, duration: Duration) -> Self::Delay {
+        tokio::time::sleep(duration)
+    }
+}
+
+/// The [async-std] runtime.
+///
+/// [async-std]: https://crates.io/crates/async-std
+#[cfg(feature = "rt-async-std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rt-async-std")))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AsyncStd;
+
+#[cfg(feature = "rt-async-std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rt-async-std")))]
+impl Runtime for AsyncStd {
+    type Interval = async_std::stream::Interval;
+    type Delay = BoxFuture<'static, ()>;
+
+    fn interval(&self, duration: Duration) -> Self::Interval {
+        async_std::stream::interval(duration)
+    }
+
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        async_std::task::spawn(future);
+    }
+
+    fn delay(&self, duration: Duration) -> Self::Delay {
+        Box::pin(async_std::task::sleep(duration))
+    }
+}
+
+/// The event loop of the JavaScript environment running a `wasm32` module,
+/// e.g. a browser, driven through [wasm-bindgen].
+///
+/// Tasks run on the event loop of the current thread and timers are set with
+/// `setTimeout`, so this runtime only works on `wasm32` targets. Flushing or
+/// shutting down a batch processor on `wasm32` targets doesn't wait for the
+/// exports, as blocking the only thread would keep them from ever running.
+///
+/// [wasm-bindgen]: https://crates.io/crates/wasm-bindgen
+#[cfg(feature = "rt-wasm-bindgen")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rt-wasm-bindgen")))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WasmBindgen;
+
+#[cfg(feature = "rt-wasm-bindgen")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = setTimeout)]
+    fn set_timeout(handler: &wasm_bindgen::JsValue, timeout: i32) -> wasm_bindgen::JsValue;
+}
+
+#[cfg(feature = "rt-wasm-bindgen")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rt-wasm-bindgen")))]
+impl Runtime for WasmBindgen {
+    type Interval = BoxStream<'static, ()>;
+    type Delay = oneshot::Receiver<()>;
+
+    fn interval(&self, duration: Duration) -> Self::Interval {
+        futures::stream::unfold(*self, move |runtime| {
+            runtime.delay(duration).map(move |_| Some(((), runtime)))
+        })
+        .boxed()
+    }
+
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        wasm_bindgen_futures::spawn_local(future);
+    }
+
+    fn delay(&self, duration: Duration) -> Self::Delay {
+        // JS timers don't hold on to Rust values, so the delay is a channel
+        // the timer callback completes. The callback is freed once called.
+        let (sender, receiver) = oneshot::channel();
+        let callback = wasm_bindgen::closure::Closure::once_into_js(move || {
+            let _ = sender.send(());
+        });
+        let timeout = duration.as_millis().min(i32::MAX as u128) as i32;
+        set_timeout(&callback, timeout);
+        receiver
+    }
+}
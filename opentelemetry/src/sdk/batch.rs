@@ -0,0 +1,365 @@
+//! # Batching
+//!
+//! The machinery shared by the batch processors of the signals, which buffer
+//! items on a worker task and export them in batches on a schedule.
+use crate::runtime::Runtime;
+use crossbeam_queue::SegQueue;
+use futures::{
+    future::Either,
+    pin_mut,
+    task::{waker, ArcWake},
+    Future, Stream,
+};
+use pin_project::pin_project;
+use std::env;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{self, Poll};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The value of the first of the environment variables `names` which is set
+/// and parses as a `T`.
+pub(crate) fn env_var<T: FromStr>(names: &[&str]) -> Option<T> {
+    names
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .next()
+        .and_then(|value| T::from_str(&value).ok())
+}
+
+/// A lock-free multi-producer queue holding at most `capacity` items.
+///
+/// Producers reserve a slot by incrementing the length before pushing, so
+/// they never wait for each other or for the consumer. Unlike a ring buffer
+/// the queue only allocates as it fills up, a large capacity costs nothing
+/// until it is used.
+#[derive(Debug)]
+pub(crate) struct BoundedQueue<T> {
+    items: SegQueue<T>,
+    len: AtomicUsize,
+    capacity: usize,
+}
+
+impl<T> BoundedQueue<T> {
+    pub(crate) fn new(capacity: usize) -> Self {
+        BoundedQueue {
+            items: SegQueue::new(),
+            len: AtomicUsize::new(0),
+            capacity,
+        }
+    }
+
+    /// The number of queued items.
+    pub(crate) fn len(&self) -> usize {
+        self.len.load(Ordering::Acquire).min(self.capacity)
+    }
+
+    /// The maximum number of queued items.
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Push an item, handing it back if the queue is full.
+    pub(crate) fn push(&self, item: T) -> Result<(), T> {
+        if self.len.fetch_add(1, Ordering::AcqRel) >= self.capacity {
+            self.len.fetch_sub(1, Ordering::AcqRel);
+            return Err(item);
+        }
+        self.items.push(item);
+        Ok(())
+    }
+
+    /// Take the oldest item, if any.
+    pub(crate) fn pop(&self) -> Option<T> {
+        let item = self.items.pop()?;
+        self.len.fetch_sub(1, Ordering::AcqRel);
+        Some(item)
+    }
+
+    /// Take the items queued so far, oldest first.
+    ///
+    /// Items pushed while draining are left for the next drain, so busy
+    /// producers can't keep the consumer draining forever.
+    pub(crate) fn drain(&self) -> Vec<T> {
+        let queued = self.items.len();
+        let mut items = Vec::with_capacity(queued);
+        for _ in 0..queued {
+            match self.items.pop() {
+                Some(item) => items.push(item),
+                None => break,
+            }
+        }
+        self.len.fetch_sub(items.len(), Ordering::AcqRel);
+        items
+    }
+}
+
+/// Split buffered items into batches of at most `max_export_batch_size`
+/// items, keeping their order.
+pub(crate) fn split_batches<T>(items: &mut Vec<T>, max_export_batch_size: usize) -> Vec<Vec<T>> {
+    let max_export_batch_size = max_export_batch_size.max(1);
+    let mut batches = Vec::with_capacity(items.len() / max_export_batch_size + 1);
+    let mut drained = items.drain(..);
+    loop {
+        let batch: Vec<T> = drained.by_ref().take(max_export_batch_size).collect();
+        if batch.is_empty() {
+            break;
+        }
+        batches.push(batch);
+    }
+    batches
+}
+
+/// Run `future` until it completes or `time_out` elapses on `runtime`,
+/// returning `None` on time out.
+pub(crate) async fn with_timeout<F, R>(
+    future: F,
+    time_out: Duration,
+    runtime: &R,
+) -> Option<F::Output>
+where
+    F: Future,
+    R: Runtime,
+{
+    let timeout = runtime.delay(time_out);
+    pin_mut!(future);
+    pin_mut!(timeout);
+    match futures::future::select(future, timeout).await {
+        Either::Left((output, _)) => Some(output),
+        Either::Right((_, _)) => None,
+    }
+}
+
+/// Run `future` to completion on the current thread, returning `None` if
+/// `time_out` elapses first.
+///
+/// This is [`with_timeout`] for workers running on their own thread, without
+/// a runtime providing timers.
+pub(crate) fn block_on_with_timeout<F: Future>(future: F, time_out: Duration) -> Option<F::Output> {
+    struct ThreadWaker(thread::Thread);
+
+    impl ArcWake for ThreadWaker {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.unpark();
+        }
+    }
+
+    let deadline = Instant::now() + time_out;
+    let waker = waker(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = task::Context::from_waker(&waker);
+    pin_mut!(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return Some(output);
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return None;
+        }
+        // woken early by the waker or spuriously, either way the future is polled again
+        thread::park_timeout(deadline - now);
+    }
+}
+
+/// Stream adapter yielding a single item for all items of the inner stream
+/// which are ready at once.
+///
+/// Interval streams catch up on ticks missed while the worker was busy, e.g.
+/// during an export slower than the scheduled delay. Without coalescing each
+/// missed tick would trigger its own flush right after the previous one.
+#[pin_project]
+#[derive(Debug)]
+pub(crate) struct Coalesce<S> {
+    #[pin]
+    inner: S,
+    terminated: bool,
+}
+
+impl<S> Coalesce<S> {
+    pub(crate) fn new(inner: S) -> Self {
+        Coalesce {
+            inner,
+            terminated: false,
+        }
+    }
+}
+
+impl<S: Stream> Stream for Coalesce<S> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        if *this.terminated {
+            return Poll::Ready(None);
+        }
+
+        let mut latest = match this.inner.as_mut().poll_next(cx) {
+            Poll::Ready(Some(item)) => item,
+            Poll::Ready(None) => {
+                *this.terminated = true;
+                return Poll::Ready(None);
+            }
+            Poll::Pending => return Poll::Pending,
+        };
+        // skip items which queued up behind the first one
+        loop {
+            match this.inner.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => latest = item,
+                Poll::Ready(None) => {
+                    *this.terminated = true;
+                    break;
+                }
+                Poll::Pending => break,
+            }
+        }
+
+        Poll::Ready(Some(latest))
+    }
+}
+
+/// A delay shared with a running worker, which picks up changes the next
+/// time it reads the delay.
+#[derive(Debug)]
+pub(crate) struct SharedDelay {
+    nanos: AtomicU64,
+}
+
+impl SharedDelay {
+    pub(crate) fn new(delay: Duration) -> Self {
+        SharedDelay {
+            nanos: AtomicU64::new(delay.as_nanos() as u64),
+        }
+    }
+
+    pub(crate) fn get(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::Relaxed))
+    }
+
+    pub(crate) fn set(&self, delay: Duration) {
+        self.nanos.store(delay.as_nanos() as u64, Ordering::Relaxed)
+    }
+}
+
+/// Stream adapter restarting the inner stream of ticks with the stream
+/// `restart` creates for a new delay once a shared delay changed.
+///
+/// The change applies from the next tick on, which is still yielded at the
+/// time it was scheduled with the previous delay.
+#[pin_project]
+pub(crate) struct Reschedule<S, F> {
+    #[pin]
+    inner: S,
+    delay: Duration,
+    shared: Arc<SharedDelay>,
+    restart: F,
+}
+
+impl<S, F> Reschedule<S, F> {
+    /// Reschedule `inner`, which ticks with `delay`.
+    pub(crate) fn new(inner: S, delay: Duration, shared: Arc<SharedDelay>, restart: F) -> Self {
+        Reschedule {
+            inner,
+            delay,
+            shared,
+            restart,
+        }
+    }
+}
+
+impl<S, F> Stream for Reschedule<S, F>
+where
+    S: Stream,
+    F: FnMut(Duration) -> S,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        let item = match this.inner.as_mut().poll_next(cx) {
+            Poll::Ready(item) => item,
+            Poll::Pending => return Poll::Pending,
+        };
+        let delay = this.shared.get();
+        if delay != *this.delay {
+            *this.delay = delay;
+            this.inner.set((this.restart)(delay));
+        }
+        Poll::Ready(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounded_queue_rejects_items_when_full() {
+        let queue = BoundedQueue::new(2);
+        assert_eq!(queue.push(1), Ok(()));
+        assert_eq!(queue.push(2), Ok(()));
+        assert_eq!(queue.push(3), Err(3));
+
+        assert_eq!(queue.drain(), vec![1, 2]);
+        assert_eq!(queue.push(4), Ok(()));
+        assert_eq!(queue.drain(), vec![4]);
+        assert!(queue.drain().is_empty());
+    }
+
+    #[test]
+    fn bounded_queue_keeps_concurrent_pushes_within_capacity() {
+        let queue = Arc::new(BoundedQueue::new(1000));
+        let producers: Vec<_> = (0..4)
+            .map(|producer| {
+                let queue = queue.clone();
+                thread::spawn(move || {
+                    (0..500)
+                        .filter(|item| queue.push(producer * 500 + item).is_ok())
+                        .count()
+                })
+            })
+            .collect();
+        let pushed: usize = producers
+            .into_iter()
+            .map(|producer| producer.join().unwrap())
+            .sum();
+
+        assert_eq!(pushed, 1000);
+        assert_eq!(queue.drain().len(), 1000);
+    }
+
+    #[test]
+    fn split_batches_keeps_order() {
+        let mut items = vec![1, 2, 3, 4, 5];
+        assert_eq!(
+            split_batches(&mut items, 2),
+            vec![vec![1, 2], vec![3, 4], vec![5]]
+        );
+        assert!(items.is_empty());
+
+        let mut items = vec![1, 2];
+        assert_eq!(split_batches(&mut items, 0), vec![vec![1], vec![2]]);
+    }
+
+    #[test]
+    fn block_on_with_timeout_waits_for_wake_ups() {
+        let (sender, receiver) = futures::channel::oneshot::channel();
+        let sending = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            sender.send(42).unwrap();
+        });
+        assert_eq!(
+            block_on_with_timeout(receiver, Duration::from_secs(5)),
+            Some(Ok(42))
+        );
+        sending.join().unwrap();
+
+        let pending = futures::future::pending::<()>();
+        assert_eq!(
+            block_on_with_timeout(pending, Duration::from_millis(10)),
+            None
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! # Clocks
+//!
+//! The SDK reads the timestamps of spans, events and measurements from a
+//! [`Clock`] instead of the system time, so that tests and replay tooling can
+//! control time. The [`SystemClock`] is used by default.
+use std::fmt;
+use std::time::{Instant, SystemTime};
+
+/// A source of timestamps.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "trace")]
+/// # {
+/// use opentelemetry::sdk::{trace, Clock};
+/// use std::time::{Duration, SystemTime};
+///
+/// /// A clock stopped at one second past the epoch.
+/// #[derive(Debug)]
+/// struct FixedClock;
+///
+/// impl Clock for FixedClock {
+///     fn now(&self) -> SystemTime {
+///         SystemTime::UNIX_EPOCH + Duration::from_secs(1)
+///     }
+/// }
+///
+/// let config = trace::config().with_clock(FixedClock);
+/// # }
+/// ```
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// The current time.
+    fn now(&self) -> SystemTime;
+
+    /// A monotonic reading of the current time, for measuring durations.
+    ///
+    /// Spans started and ended with a clock providing monotonic readings end
+    /// at their start time plus the elapsed monotonic time, so that changes of
+    /// [`now`] while they run don't distort their durations. Clocks without
+    /// monotonic readings return `None`, the default, and spans end at [`now`].
+    ///
+    /// [`now`]: Clock::now
+    fn instant(&self) -> Option<Instant> {
+        None
+    }
+}
+
+/// The system time, which may jump when the system clock is adjusted.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        crate::time::now()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn instant(&self) -> Option<Instant> {
+        Some(Instant::now())
+    }
+}
+
+/// The system time at creation advanced by a monotonic clock.
+///
+/// Timestamps read from this clock never go backwards, so the durations of
+/// spans are never negative, even when the system clock is adjusted while they
+/// run. In exchange the timestamps drift from the system time by the
+/// adjustments made since the clock was created.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Copy, Debug)]
+pub struct MonotonicClock {
+    anchor: SystemTime,
+    start: Instant,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl MonotonicClock {
+    /// Create a clock starting at the current system time.
+    pub fn new() -> Self {
+        MonotonicClock {
+            anchor: SystemTime::now(),
+            start: Instant::now(),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for MonotonicClock {
+    fn default() -> Self {
+        MonotonicClock::new()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Clock for MonotonicClock {
+    fn now(&self) -> SystemTime {
+        self.anchor + self.start.elapsed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn monotonic_clock_does_not_go_backwards() {
+        let clock = MonotonicClock::new();
+        let mut previous = clock.now();
+        for _ in 0..100 {
+            let now = clock.now();
+            assert!(now >= previous);
+            previous = now;
+        }
+    }
+}
@@ -8,10 +8,15 @@ use std::env;
 use std::time::Duration;
 
 static OTEL_RESOURCE_ATTRIBUTES: &str = "OTEL_RESOURCE_ATTRIBUTES";
+static OTEL_SERVICE_NAME: &str = "OTEL_SERVICE_NAME";
 
 /// Resource detector implements ResourceDetector and is used to extract
 /// general SDK configuration from environment.
 ///
+/// Attributes are read from `OTEL_RESOURCE_ATTRIBUTES`, a comma separated list
+/// of `key=value` pairs. `OTEL_SERVICE_NAME` sets `service.name` and takes
+/// priority over a `service.name` given in `OTEL_RESOURCE_ATTRIBUTES`.
+///
 /// See
 /// [semantic conventions](https://github.com/open-telemetry/opentelemetry-specification/tree/master/specification/resource/semantic_conventions#telemetry-sdk)
 /// for details.
@@ -22,10 +27,10 @@ pub struct EnvResourceDetector {
 
 impl ResourceDetector for EnvResourceDetector {
     fn detect(&self, _timeout: Duration) -> Resource {
-        match env::var(OTEL_RESOURCE_ATTRIBUTES) {
-            Ok(s) if !s.is_empty() => construct_otel_resources(s),
-            Ok(_) | Err(_) => Resource::new(vec![]), // return empty resource
-        }
+        from_env_values(
+            env::var(OTEL_SERVICE_NAME).ok(),
+            env::var(OTEL_RESOURCE_ATTRIBUTES).ok(),
+        )
     }
 }
 
@@ -42,6 +47,23 @@ impl Default for EnvResourceDetector {
     }
 }
 
+/// Construct a resource from the values of `OTEL_SERVICE_NAME` and
+/// `OTEL_RESOURCE_ATTRIBUTES`, empty values are ignored.
+fn from_env_values(service_name: Option<String>, attributes: Option<String>) -> Resource {
+    let service_name = match service_name {
+        Some(name) if !name.trim().is_empty() => {
+            Resource::new(vec![KeyValue::new("service.name", name.trim().to_owned())])
+        }
+        _ => Resource::empty(),
+    };
+    let attributes = match attributes {
+        Some(s) if !s.is_empty() => construct_otel_resources(s),
+        _ => Resource::empty(),
+    };
+
+    service_name.merge(&attributes)
+}
+
 /// Extract key value pairs and construct a resource from resources string like
 /// key1=value1,key2=value2,...
 fn construct_otel_resources(s: String) -> Resource {
@@ -59,7 +81,7 @@ fn construct_otel_resources(s: String) -> Resource {
 
 #[cfg(test)]
 mod tests {
-    use crate::sdk::env::OTEL_RESOURCE_ATTRIBUTES;
+    use crate::sdk::env::{from_env_values, OTEL_RESOURCE_ATTRIBUTES};
     use crate::sdk::resource::{Resource, ResourceDetector};
     use crate::sdk::EnvResourceDetector;
     use crate::{Key, KeyValue};
@@ -89,4 +111,26 @@ mod tests {
         let resource = detector.detect(time::Duration::from_secs(5));
         assert!(resource.is_empty());
     }
+
+    #[test]
+    fn test_service_name_precedence() {
+        let attributes = Some("service.name=from-attributes,k=v".to_string());
+
+        let resource = from_env_values(Some("from-env".to_string()), attributes.clone());
+        assert_eq!(
+            resource,
+            Resource::new(vec![
+                KeyValue::new("service.name", "from-env"),
+                KeyValue::new("k", "v"),
+            ])
+        );
+
+        let resource = from_env_values(Some(" ".to_string()), attributes);
+        assert_eq!(
+            resource.get(Key::new("service.name")),
+            Some("from-attributes".into())
+        );
+
+        assert!(from_env_values(None, Some(String::new())).is_empty());
+    }
 }
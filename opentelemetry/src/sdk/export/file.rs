@@ -0,0 +1,516 @@
+//! # File Exporter
+//!
+//! The file exporter appends spans and metrics to a file, one OTLP/JSON
+//! `ExportTraceServiceRequest` or `ExportMetricsServiceRequest` per line, so
+//! the file can be shipped by log agents or read by the file receivers of
+//! collectors.
+//!
+//! The file is rotated once it would exceed a maximum size or after a
+//! rotation interval. The current file is renamed to `<file>.<unix millis>`
+//! and a new file is started. With the `gzip` feature rotated files can be
+//! compressed to `<file>.<unix millis>.gz`. Only a maximum number of rotated
+//! files is kept if configured, the oldest are removed first.
+//!
+//! Rotation happens in the export call that writes the next line, so it
+//! delays that export by the time needed to rename, and possibly compress,
+//! the file.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # #[cfg(feature = "trace")]
+//! # {
+//! use opentelemetry::sdk::{export::file, trace::TracerProvider};
+//! use std::time::Duration;
+//!
+//! let exporter = file::new_exporter("/var/log/app/spans.jsonl")
+//!     .with_max_file_size(64 * 1024 * 1024)
+//!     .with_rotation_interval(Duration::from_secs(3600))
+//!     .with_max_rotated_files(24)
+//!     .build()
+//!     .expect("failed to open span file");
+//! let provider = TracerProvider::builder()
+//!     .with_simple_exporter(exporter)
+//!     .build();
+//! # }
+//! ```
+#[cfg(feature = "metrics")]
+use crate::metrics::{Descriptor, MetricsError};
+#[cfg(feature = "metrics")]
+use crate::sdk::export::metrics::{
+    self, CheckpointSet, ExportKind, ExportKindFor, ExportKindSelector,
+};
+#[cfg(feature = "trace")]
+use crate::sdk::export::trace::{self, ExportResult, SpanData, SpanExporter};
+#[cfg(feature = "trace")]
+use crate::{global, sdk::export::ExportError, trace::TraceError};
+#[cfg(feature = "trace")]
+use async_trait::async_trait;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Create a builder for a file exporter writing to `path`.
+pub fn new_exporter<P: Into<PathBuf>>(path: P) -> FileExporterBuilder {
+    FileExporterBuilder {
+        path: path.into(),
+        rotation: Rotation::default(),
+        #[cfg(feature = "metrics")]
+        export_selector: ExportKindSelector::Cumulative,
+    }
+}
+
+/// Builder of [`FileExporter`]s.
+#[derive(Debug)]
+pub struct FileExporterBuilder {
+    path: PathBuf,
+    rotation: Rotation,
+    #[cfg(feature = "metrics")]
+    export_selector: ExportKindSelector,
+}
+
+impl FileExporterBuilder {
+    /// Rotate the file before it would exceed `max_file_size` bytes. A single
+    /// export larger than the maximum is still written to its own file.
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.rotation.max_file_size = Some(max_file_size);
+        self
+    }
+
+    /// Rotate the file once it was written to for `rotation_interval`.
+    pub fn with_rotation_interval(mut self, rotation_interval: Duration) -> Self {
+        self.rotation.interval = Some(rotation_interval);
+        self
+    }
+
+    /// Keep at most `max_rotated_files` rotated files, removing the oldest.
+    /// By default all rotated files are kept.
+    pub fn with_max_rotated_files(mut self, max_rotated_files: usize) -> Self {
+        self.rotation.max_rotated_files = Some(max_rotated_files);
+        self
+    }
+
+    /// Compress rotated files with gzip.
+    #[cfg(feature = "gzip")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gzip")))]
+    pub fn with_gzip(mut self, gzip: bool) -> Self {
+        self.rotation.gzip = gzip;
+        self
+    }
+
+    /// Set the export kind of metrics, cumulative by default.
+    #[cfg(feature = "metrics")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+    pub fn with_export_kind(mut self, export_selector: ExportKindSelector) -> Self {
+        self.export_selector = export_selector;
+        self
+    }
+
+    /// Open the file, appending to it if it exists.
+    pub fn build(self) -> io::Result<FileExporter> {
+        Ok(FileExporter {
+            writer: Mutex::new(RotatingFile::open(self.path, self.rotation)?),
+            #[cfg(feature = "metrics")]
+            export_selector: self.export_selector,
+        })
+    }
+}
+
+/// Exporter writing spans and metrics as OTLP/JSON lines to a file.
+///
+/// Spans are exported as a [`SpanExporter`] and metrics as a metrics
+/// [`Exporter`], e.g. with a push controller.
+///
+/// [`SpanExporter`]: crate::sdk::export::trace::SpanExporter
+/// [`Exporter`]: crate::sdk::export::metrics::Exporter
+#[derive(Debug)]
+pub struct FileExporter {
+    writer: Mutex<RotatingFile>,
+    #[cfg(feature = "metrics")]
+    export_selector: ExportKindSelector,
+}
+
+impl FileExporter {
+    /// The path of the file currently written to.
+    pub fn path(&self) -> PathBuf {
+        match self.writer.lock() {
+            Ok(writer) => writer.path.clone(),
+            Err(poisoned) => poisoned.into_inner().path.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "trace")]
+#[async_trait]
+impl SpanExporter for FileExporter {
+    async fn export(&mut self, batch: Vec<SpanData>) -> ExportResult {
+        if batch.is_empty() {
+            return Ok(());
+        }
+        let line = trace::json::spans_to_otlp_json(&batch);
+        let writer = self.writer.get_mut().map_err(|err| err.to_string())?;
+        writer.write_line(line).map_err(Error::from)?;
+        Ok(())
+    }
+
+    fn shutdown(&mut self) {
+        if let Ok(writer) = self.writer.get_mut() {
+            if let Err(err) = writer.file.flush() {
+                global::handle_error(TraceError::from(Error(err)));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl metrics::Exporter for FileExporter {
+    fn export(&self, checkpoint_set: &mut dyn CheckpointSet) -> crate::metrics::Result<()> {
+        if let Some(line) = metrics::json::metrics_to_otlp_json(checkpoint_set, self)? {
+            self.writer
+                .lock()?
+                .write_line(line)
+                .map_err(|err| MetricsError::Other(err.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl ExportKindFor for FileExporter {
+    fn export_kind_for(&self, descriptor: &Descriptor) -> ExportKind {
+        self.export_selector.export_kind_for(descriptor)
+    }
+}
+
+/// File exporter's error
+#[cfg(feature = "trace")]
+#[derive(thiserror::Error, Debug)]
+#[error(transparent)]
+struct Error(#[from] io::Error);
+
+#[cfg(feature = "trace")]
+impl ExportError for Error {
+    fn exporter_name(&self) -> &'static str {
+        "file"
+    }
+}
+
+#[derive(Debug, Default)]
+struct Rotation {
+    max_file_size: Option<u64>,
+    interval: Option<Duration>,
+    max_rotated_files: Option<usize>,
+    #[cfg(feature = "gzip")]
+    gzip: bool,
+}
+
+/// A file appended to line by line, rotated according to its [`Rotation`].
+#[derive(Debug)]
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    opened_at: Instant,
+    rotation: Rotation,
+}
+
+impl RotatingFile {
+    fn open(path: PathBuf, rotation: Rotation) -> io::Result<Self> {
+        let file = open_append(&path)?;
+        let size = file.metadata()?.len();
+        Ok(RotatingFile {
+            path,
+            file,
+            size,
+            opened_at: Instant::now(),
+            rotation,
+        })
+    }
+
+    fn write_line(&mut self, mut line: String) -> io::Result<()> {
+        line.push('\n');
+        let len = line.len() as u64;
+        if self.size > 0 && self.should_rotate(len) {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.size += len;
+        Ok(())
+    }
+
+    fn should_rotate(&self, len: u64) -> bool {
+        let too_large = self
+            .rotation
+            .max_file_size
+            .map(|max| self.size + len > max)
+            .unwrap_or(false);
+        let expired = self
+            .rotation
+            .interval
+            .map(|interval| self.opened_at.elapsed() >= interval)
+            .unwrap_or(false);
+        too_large || expired
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let mut millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis())
+            .unwrap_or(0);
+        // keep the names of files rotated within the same millisecond unique
+        // and ordered
+        let rotated = loop {
+            let rotated = self.rotated_path(millis);
+            if !rotated.exists() && !gz_path(&rotated).exists() {
+                break rotated;
+            }
+            millis += 1;
+        };
+        fs::rename(&self.path, &rotated)?;
+        self.file = open_append(&self.path)?;
+        self.size = 0;
+        self.opened_at = Instant::now();
+
+        #[cfg(feature = "gzip")]
+        {
+            if self.rotation.gzip {
+                compress(&rotated)?;
+            }
+        }
+        if let Some(max_rotated_files) = self.rotation.max_rotated_files {
+            self.remove_rotated_files(max_rotated_files)?;
+        }
+        Ok(())
+    }
+
+    fn rotated_path(&self, millis: u128) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{}", millis));
+        path.into()
+    }
+
+    /// Remove the oldest rotated files until at most `max` are left.
+    fn remove_rotated_files(&self, max: usize) -> io::Result<()> {
+        let dir = match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let prefix = match self.path.file_name().and_then(|name| name.to_str()) {
+            Some(name) => format!("{}.", name),
+            None => return Ok(()),
+        };
+        let mut rotated = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let millis = name
+                .to_str()
+                .and_then(|name| name.strip_prefix(prefix.as_str()))
+                .map(|suffix| suffix.trim_end_matches(".gz"))
+                .and_then(|millis| millis.parse::<u128>().ok());
+            if let Some(millis) = millis {
+                rotated.push((millis, entry.path()));
+            }
+        }
+        if rotated.len() > max {
+            rotated.sort();
+            let excess = rotated.len() - max;
+            for (_, path) in rotated.into_iter().take(excess) {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+fn gz_path(path: &Path) -> PathBuf {
+    let mut gz_path = path.to_path_buf().into_os_string();
+    gz_path.push(".gz");
+    gz_path.into()
+}
+
+/// Replace a rotated file by its gzip compressed version.
+#[cfg(feature = "gzip")]
+fn compress(path: &Path) -> io::Result<()> {
+    let mut input = File::open(path)?;
+    let output = File::create(gz_path(path))?;
+    let mut encoder = flate2::write::GzEncoder::new(output, flate2::Compression::default());
+    io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?.sync_all()?;
+    fs::remove_file(path)
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::sdk::export::metrics::{record, Aggregator};
+    use crate::sdk::metrics::aggregators;
+    use crate::sdk::Resource;
+    use crate::testing::trace::new_test_export_span_data;
+    use crate::{
+        labels::LabelSet,
+        metrics::{InstrumentKind, Number, NumberKind},
+        KeyValue,
+    };
+    use std::sync::Arc;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "otel-file-exporter-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn rotated_files(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .filter(|name| name != "spans.jsonl")
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn rotate_by_size() {
+        let dir = test_dir("size");
+        let mut exporter = new_exporter(dir.join("spans.jsonl"))
+            .with_max_file_size(10)
+            .with_max_rotated_files(2)
+            .build()
+            .unwrap();
+        for _ in 0..4 {
+            futures::executor::block_on(exporter.export(vec![new_test_export_span_data()]))
+                .unwrap();
+        }
+
+        let current = fs::read_to_string(exporter.path()).unwrap();
+        assert_eq!(current.lines().count(), 1);
+        assert!(current.starts_with("{\"resourceSpans\":["));
+        // the two oldest rotated files were removed
+        let rotated = rotated_files(&dir);
+        assert_eq!(rotated.len(), 2);
+        for name in rotated {
+            assert!(name.starts_with("spans.jsonl."));
+            let rotated = fs::read_to_string(dir.join(name)).unwrap();
+            assert_eq!(rotated.lines().count(), 1);
+        }
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rotate_by_interval() {
+        let dir = test_dir("interval");
+        let path = dir.join("spans.jsonl");
+        fs::write(&path, "existing\n").unwrap();
+        let mut exporter = new_exporter(&path)
+            .with_rotation_interval(Duration::from_secs(0))
+            .build()
+            .unwrap();
+        futures::executor::block_on(exporter.export(vec![new_test_export_span_data()])).unwrap();
+
+        let rotated = rotated_files(&dir);
+        assert_eq!(rotated.len(), 1);
+        assert_eq!(
+            fs::read_to_string(dir.join(&rotated[0])).unwrap(),
+            "existing\n"
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn compress_rotated_files() {
+        use std::io::Read;
+
+        let dir = test_dir("gzip");
+        let path = dir.join("spans.jsonl");
+        fs::write(&path, "existing\n").unwrap();
+        let mut exporter = new_exporter(&path)
+            .with_max_file_size(1)
+            .with_gzip(true)
+            .build()
+            .unwrap();
+        futures::executor::block_on(exporter.export(vec![new_test_export_span_data()])).unwrap();
+
+        let rotated = rotated_files(&dir);
+        assert_eq!(rotated.len(), 1);
+        assert!(rotated[0].ends_with(".gz"));
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(File::open(dir.join(&rotated[0])).unwrap())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, "existing\n");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[derive(Debug)]
+    struct TestCheckpointSet(Arc<dyn Aggregator + Send + Sync>);
+
+    impl CheckpointSet for TestCheckpointSet {
+        fn try_for_each(
+            &mut self,
+            _export_selector: &dyn ExportKindFor,
+            f: &mut dyn FnMut(&metrics::Record<'_>) -> crate::metrics::Result<()>,
+        ) -> crate::metrics::Result<()> {
+            let descriptor = Descriptor::new(
+                "requests".to_string(),
+                "server",
+                None,
+                InstrumentKind::Counter,
+                NumberKind::U64,
+            );
+            let labels = LabelSet::from_labels(vec![KeyValue::new("method", "GET")]);
+            let resource = Resource::new(vec![KeyValue::new("service.name", "users")]);
+            f(&record(
+                &descriptor,
+                &labels,
+                &resource,
+                Some(&self.0),
+                UNIX_EPOCH + Duration::from_nanos(1_000),
+                UNIX_EPOCH + Duration::from_nanos(2_000),
+            ))
+        }
+    }
+
+    #[test]
+    fn export_metrics() {
+        let dir = test_dir("metrics");
+        let exporter = new_exporter(dir.join("metrics.jsonl")).build().unwrap();
+        let sum = Arc::new(aggregators::sum());
+        let descriptor = Descriptor::new(
+            "requests".to_string(),
+            "server",
+            None,
+            InstrumentKind::Counter,
+            NumberKind::U64,
+        );
+        sum.update(&Number::from(3u64), &descriptor).unwrap();
+        metrics::Exporter::export(&exporter, &mut TestCheckpointSet(sum)).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(exporter.path()).unwrap(),
+            concat!(
+                r#"{"resourceMetrics":[{"resource":{"attributes":[{"key":"service.name","value":{"stringValue":"users"}}]},"#,
+                r#""instrumentationLibraryMetrics":[{"instrumentationLibrary":{"name":"server"},"metrics":["#,
+                r#"{"name":"requests","intSum":{"dataPoints":[{"labels":[{"key":"method","value":"GET"}],"#,
+                r#""startTimeUnixNano":"1000","timeUnixNano":"2000","value":"3"}],"#,
+                r#""aggregationTemporality":2,"isMonotonic":true}}]}]}]}"#,
+                "\n"
+            )
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,140 @@
+//! # JSON Encoding Helpers
+//!
+//! Building blocks of the JSON encodings of the exporters, written without a
+//! JSON library as only a handful of types need to be encoded. The `otlp`
+//! helpers follow the [OTLP/JSON] mapping of the OTLP protobuf messages.
+//!
+//! [OTLP/JSON]: https://github.com/open-telemetry/opentelemetry-specification/blob/main/specification/protocol/otlp.md#json-protobuf-encoding
+use crate::sdk::InstrumentationLibrary;
+use crate::{Array, Key, Value};
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Nanoseconds since the unix epoch, times before the epoch are 0.
+pub(crate) fn unix_nanos(time: SystemTime) -> u128 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or(0)
+}
+
+/// Write an OTLP `InstrumentationLibrary` message.
+pub(crate) fn write_otlp_library(out: &mut String, library: &InstrumentationLibrary) {
+    out.push_str("{\"name\":");
+    write_str(out, library.name);
+    if let Some(version) = library.version {
+        out.push_str(",\"version\":");
+        write_str(out, version);
+    }
+    if !library.attributes.is_empty() {
+        out.push_str(",\"attributes\":");
+        write_otlp_attributes(
+            out,
+            library.attributes.iter().map(|kv| (&kv.key, &kv.value)),
+        );
+    }
+    out.push('}');
+}
+
+/// Write attributes as a list of OTLP `KeyValue` messages.
+pub(crate) fn write_otlp_attributes<'a>(
+    out: &mut String,
+    attributes: impl Iterator<Item = (&'a Key, &'a Value)>,
+) {
+    out.push('[');
+    for (i, (key, value)) in attributes.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str("{\"key\":");
+        write_str(out, key.as_str());
+        out.push_str(",\"value\":");
+        write_otlp_value(out, value);
+        out.push('}');
+    }
+    out.push(']');
+}
+
+pub(crate) fn write_otlp_value(out: &mut String, value: &Value) {
+    match value {
+        Value::Bool(v) => {
+            let _ = write!(out, "{{\"boolValue\":{}}}", v);
+        }
+        Value::I64(v) => {
+            let _ = write!(out, "{{\"intValue\":\"{}\"}}", v);
+        }
+        Value::F64(v) => {
+            out.push_str("{\"doubleValue\":");
+            write_f64(out, *v);
+            out.push('}');
+        }
+        Value::String(v) => {
+            out.push_str("{\"stringValue\":");
+            write_str(out, v);
+            out.push('}');
+        }
+        Value::Array(array) => {
+            out.push_str("{\"arrayValue\":{\"values\":[");
+            match array {
+                Array::Bool(values) => write_list(out, values, |out, v| {
+                    write_otlp_value(out, &Value::Bool(*v))
+                }),
+                Array::I64(values) => {
+                    write_list(out, values, |out, v| write_otlp_value(out, &Value::I64(*v)))
+                }
+                Array::F64(values) => {
+                    write_list(out, values, |out, v| write_otlp_value(out, &Value::F64(*v)))
+                }
+                Array::String(values) => write_list(out, values, |out, v| {
+                    out.push_str("{\"stringValue\":");
+                    write_str(out, v);
+                    out.push('}');
+                }),
+            }
+            out.push_str("]}}");
+        }
+    }
+}
+
+pub(crate) fn write_list<T>(out: &mut String, values: &[T], write: impl Fn(&mut String, &T)) {
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write(out, value);
+    }
+}
+
+/// Write a float, JSON has no representation of NaN and infinities so they
+/// are written as strings as in the OTLP/JSON mapping.
+pub(crate) fn write_f64(out: &mut String, value: f64) {
+    if value.is_nan() {
+        out.push_str("\"NaN\"");
+    } else if value.is_infinite() {
+        out.push_str(if value > 0.0 {
+            "\"Infinity\""
+        } else {
+            "\"-Infinity\""
+        });
+    } else {
+        let _ = write!(out, "{:?}", value);
+    }
+}
+
+/// Write a JSON string literal.
+pub(crate) fn write_str(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
@@ -0,0 +1,40 @@
+//! Log exporters
+use crate::logs::{LogError, LogRecord};
+use crate::sdk::{InstrumentationLibrary, Resource};
+use async_trait::async_trait;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Describes the result of an export.
+pub type ExportResult = Result<(), LogError>;
+
+/// `LogExporter` defines the interface that protocol-specific exporters must
+/// implement so that they can be plugged into OpenTelemetry SDK and support
+/// sending of log records.
+#[async_trait]
+pub trait LogExporter: Send + Debug {
+    /// Exports a batch of log records.
+    ///
+    /// This function will never be called concurrently for the same exporter
+    /// instance. It can be called again only after the current call returns.
+    ///
+    /// This function must not block indefinitely, there must be a reasonable
+    /// upper limit after which the call must time out with an error result.
+    async fn export(&mut self, batch: Vec<LogData>) -> ExportResult;
+
+    /// Shuts down the exporter. Called when SDK is shut down. This is an
+    /// opportunity for exporter to do any cleanup required.
+    fn shutdown(&mut self) {}
+}
+
+/// `LogData` associates a [`LogRecord`] with the resource and the
+/// instrumentation library of the logger emitting it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogData {
+    /// The log record
+    pub record: LogRecord,
+    /// Resource of the logger provider the record was emitted with
+    pub resource: Arc<Resource>,
+    /// Instrumentation library of the logger emitting the record
+    pub instrumentation_lib: InstrumentationLibrary,
+}
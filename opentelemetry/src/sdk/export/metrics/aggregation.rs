@@ -1,5 +1,6 @@
 //! Metrics SDK Aggregator export API
 use crate::metrics::{Number, Result};
+use crate::KeyValue;
 use std::time::SystemTime;
 
 /// Sum returns an aggregated sum.
@@ -84,6 +85,118 @@ pub trait Histogram: Sum + Count {
     fn histogram(&self) -> Result<Buckets>;
 }
 
+/// Buckets of one sign of an exponential histogram.
+///
+/// The bucket with index `offset + i` counts the values whose absolute value
+/// lies in `(base^(offset + i), base^(offset + i + 1)]`, where the base is
+/// `2^(2^-scale)` for the scale of the histogram.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExponentialBuckets {
+    offset: i32,
+    counts: Vec<u64>,
+}
+
+impl ExponentialBuckets {
+    /// Create new buckets, the first count is the one of bucket `offset`
+    pub fn new(offset: i32, counts: Vec<u64>) -> Self {
+        ExponentialBuckets { offset, counts }
+    }
+
+    /// Index of the first bucket
+    pub fn offset(&self) -> i32 {
+        self.offset
+    }
+
+    /// Counts of consecutive buckets, starting at `offset`
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// The lower boundary of the bucket `index` of a histogram with `scale`,
+    /// i.e. `base^index`.
+    pub fn lower_boundary(scale: i8, index: i32) -> f64 {
+        (f64::from(index) * 2f64.powi(-i32::from(scale))).exp2()
+    }
+}
+
+/// ExponentialHistogram returns the count of events in buckets with
+/// exponentially growing boundaries, chosen to fit the recorded values.
+pub trait ExponentialHistogram: Sum + Count {
+    /// The scale of the buckets, larger scales have narrower buckets
+    fn scale(&self) -> Result<i8>;
+
+    /// The count of events equal to zero
+    fn zero_count(&self) -> Result<u64>;
+
+    /// Buckets of the positive events
+    fn positive(&self) -> Result<ExponentialBuckets>;
+
+    /// Buckets of the negative events, by absolute value
+    fn negative(&self) -> Result<ExponentialBuckets>;
+}
+
+/// A measurement recorded while a sampled span was active, linking the
+/// aggregated metric to the trace it was recorded in.
+#[derive(Clone, Debug)]
+pub struct Exemplar {
+    value: Number,
+    time: SystemTime,
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+    filtered_attributes: Vec<KeyValue>,
+}
+
+impl Exemplar {
+    /// Create a new exemplar
+    pub fn new(
+        value: Number,
+        time: SystemTime,
+        trace_id: [u8; 16],
+        span_id: [u8; 8],
+        filtered_attributes: Vec<KeyValue>,
+    ) -> Self {
+        Exemplar {
+            value,
+            time,
+            trace_id,
+            span_id,
+            filtered_attributes,
+        }
+    }
+
+    /// The measured value
+    pub fn value(&self) -> &Number {
+        &self.value
+    }
+
+    /// The time of the measurement
+    pub fn time(&self) -> SystemTime {
+        self.time
+    }
+
+    /// The id of the trace of the span active during the measurement
+    pub fn trace_id(&self) -> [u8; 16] {
+        self.trace_id
+    }
+
+    /// The id of the span active during the measurement
+    pub fn span_id(&self) -> [u8; 8] {
+        self.span_id
+    }
+
+    /// The attributes of the measurement that are not part of the labels of
+    /// the aggregated metric, e.g. because a view dropped them.
+    pub fn filtered_attributes(&self) -> &[KeyValue] {
+        &self.filtered_attributes
+    }
+}
+
+/// Exemplars returns a sample of the measurements that were aggregated.
+pub trait Exemplars {
+    /// The exemplars of the currently aggregated metrics
+    fn exemplars(&self) -> Result<Vec<Exemplar>>;
+}
+
 /// MinMaxSumCount supports the Min, Max, Sum, and Count interfaces.
 pub trait MinMaxSumCount: Min + Max + Sum + Count {}
 
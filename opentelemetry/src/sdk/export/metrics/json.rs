@@ -0,0 +1,271 @@
+//! # OTLP/JSON Metrics Encoding
+//!
+//! Encodes checkpoint sets following the [OTLP/JSON] mapping of the
+//! `ExportMetricsServiceRequest` protobuf message. Aggregations are mapped to
+//! OTLP metrics the same way as in the OTLP exporter: sums to sums, last
+//! values and arrays to gauges and histograms and min-max-sum-counts to
+//! histograms.
+//!
+//! [OTLP/JSON]: https://github.com/open-telemetry/opentelemetry-specification/blob/main/specification/protocol/otlp.md#json-protobuf-encoding
+use crate::metrics::{MetricsError, Number, NumberKind, Result};
+use crate::sdk::export::json::{
+    unix_nanos, write_f64, write_list, write_otlp_attributes, write_otlp_library, write_str,
+};
+use crate::sdk::export::metrics::{
+    CheckpointSet, Count, ExportKind, ExportKindFor, Histogram, LastValue, Max, Min, Points,
+    Record, Sum,
+};
+use crate::sdk::metrics::aggregators::{
+    ArrayAggregator, HistogramAggregator, LastValueAggregator, MinMaxSumCountAggregator,
+    SumAggregator,
+};
+use crate::sdk::{InstrumentationLibrary, Resource};
+use std::fmt::Write;
+use std::time::SystemTime;
+
+/// Encoded metrics of a resource, grouped by instrumentation library.
+type ResourceMetrics = (Resource, Vec<LibraryMetrics>);
+type LibraryMetrics = (InstrumentationLibrary, Vec<String>);
+
+/// Encode a checkpoint set as a single line OTLP/JSON
+/// `ExportMetricsServiceRequest`, without the trailing newline.
+///
+/// Returns `None` if the checkpoint set contains no metrics.
+pub(crate) fn metrics_to_otlp_json(
+    checkpoint_set: &mut dyn CheckpointSet,
+    export_selector: &dyn ExportKindFor,
+) -> Result<Option<String>> {
+    let mut groups: Vec<ResourceMetrics> = Vec::new();
+    checkpoint_set.try_for_each(export_selector, &mut |record| {
+        let metric = match write_otlp_metric(record, export_selector)? {
+            Some(metric) => metric,
+            None => return Ok(()),
+        };
+        let libraries = match groups
+            .iter_mut()
+            .find(|(resource, _)| resource == record.resource())
+        {
+            Some((_, libraries)) => libraries,
+            None => {
+                groups.push((record.resource().clone(), Vec::new()));
+                &mut groups.last_mut().unwrap().1
+            }
+        };
+        let library = record.descriptor().instrumentation_library();
+        match libraries.iter_mut().find(|(lib, _)| lib == library) {
+            Some((_, metrics)) => metrics.push(metric),
+            None => libraries.push((library.clone(), vec![metric])),
+        }
+        Ok(())
+    })?;
+    if groups.is_empty() {
+        return Ok(None);
+    }
+
+    let mut out = String::with_capacity(256);
+    out.push_str("{\"resourceMetrics\":[");
+    for (i, (resource, libraries)) in groups.into_iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str("{\"resource\":{\"attributes\":");
+        write_otlp_attributes(&mut out, resource.iter());
+        out.push_str("},\"instrumentationLibraryMetrics\":[");
+        for (j, (library, metrics)) in libraries.into_iter().enumerate() {
+            if j > 0 {
+                out.push(',');
+            }
+            out.push_str("{\"instrumentationLibrary\":");
+            write_otlp_library(&mut out, &library);
+            out.push_str(",\"metrics\":[");
+            out.push_str(&metrics.join(","));
+            out.push(']');
+            if let Some(schema_url) = library.schema_url {
+                out.push_str(",\"schemaUrl\":");
+                write_str(&mut out, schema_url);
+            }
+            out.push('}');
+        }
+        out.push_str("]}");
+    }
+    out.push_str("]}");
+    Ok(Some(out))
+}
+
+/// Encode the OTLP `Metric` message of a record, `None` if its aggregation
+/// has no OTLP representation.
+fn write_otlp_metric(
+    record: &Record<'_>,
+    export_selector: &dyn ExportKindFor,
+) -> Result<Option<String>> {
+    let descriptor = record.descriptor();
+    let aggregator = record.aggregator().ok_or(MetricsError::NoDataCollected)?;
+    let kind = descriptor.number_kind();
+    let is_int = *kind != NumberKind::F64;
+    let temporality = match export_selector.export_kind_for(descriptor) {
+        ExportKind::Delta => 1,
+        ExportKind::Cumulative => 2,
+    };
+    let start_time = *record.start_time();
+    let end_time = *record.end_time();
+
+    let mut data = String::new();
+    let aggregator = aggregator.as_any();
+    if let Some(array) = aggregator.downcast_ref::<ArrayAggregator>() {
+        let points = array.points()?;
+        data.push_str(if is_int {
+            "\"intGauge\""
+        } else {
+            "\"doubleGauge\""
+        });
+        data.push_str(":{\"dataPoints\":[");
+        write_list(&mut data, &points, |out, point| {
+            write_number_point(out, record, start_time, end_time, point, kind)
+        });
+        data.push_str("]}");
+    } else if let Some(last_value) = aggregator.downcast_ref::<LastValueAggregator>() {
+        let (value, sample_time) = last_value.last_value()?;
+        data.push_str(if is_int {
+            "\"intGauge\""
+        } else {
+            "\"doubleGauge\""
+        });
+        data.push_str(":{\"dataPoints\":[");
+        write_number_point(&mut data, record, start_time, sample_time, &value, kind);
+        data.push_str("]}");
+    } else if let Some(sum) = aggregator.downcast_ref::<SumAggregator>() {
+        let value = sum.sum()?;
+        data.push_str(if is_int {
+            "\"intSum\""
+        } else {
+            "\"doubleSum\""
+        });
+        data.push_str(":{\"dataPoints\":[");
+        write_number_point(&mut data, record, start_time, end_time, &value, kind);
+        let _ = write!(
+            data,
+            "],\"aggregationTemporality\":{},\"isMonotonic\":{}}}",
+            temporality,
+            descriptor.instrument_kind().monotonic()
+        );
+    } else if let Some(histogram) = aggregator.downcast_ref::<HistogramAggregator>() {
+        let buckets = histogram.histogram()?;
+        let counts: Vec<u64> = buckets.counts().iter().map(|c| *c as u64).collect();
+        write_otlp_histogram(
+            &mut data,
+            record,
+            (histogram.count()?, &histogram.sum()?),
+            &counts,
+            buckets.boundaries(),
+            temporality,
+        );
+    } else if let Some(mmsc) = aggregator.downcast_ref::<MinMaxSumCountAggregator>() {
+        // min and max are sent as the counts of two buckets, as in the OTLP
+        // exporter
+        let counts = [mmsc.min()?.to_u64(kind), mmsc.max()?.to_u64(kind)];
+        write_otlp_histogram(
+            &mut data,
+            record,
+            (mmsc.count()?, &mmsc.sum()?),
+            &counts,
+            &[0.0, 100.0],
+            temporality,
+        );
+    } else {
+        return Ok(None);
+    }
+
+    let mut out = String::with_capacity(128 + data.len());
+    out.push_str("{\"name\":");
+    write_str(&mut out, descriptor.name());
+    if let Some(description) = descriptor.description().filter(|d| !d.is_empty()) {
+        out.push_str(",\"description\":");
+        write_str(&mut out, description);
+    }
+    if let Some(unit) = descriptor.unit().filter(|unit| !unit.is_empty()) {
+        out.push_str(",\"unit\":");
+        write_str(&mut out, unit);
+    }
+    out.push(',');
+    out.push_str(&data);
+    out.push('}');
+    Ok(Some(out))
+}
+
+fn write_otlp_histogram(
+    out: &mut String,
+    record: &Record<'_>,
+    (count, sum): (u64, &Number),
+    counts: &[u64],
+    bounds: &[f64],
+    temporality: i32,
+) {
+    let kind = record.descriptor().number_kind();
+    out.push_str(if *kind != NumberKind::F64 {
+        "\"intHistogram\""
+    } else {
+        "\"doubleHistogram\""
+    });
+    out.push_str(":{\"dataPoints\":[");
+    write_point_header(out, record, *record.start_time(), *record.end_time());
+    // fixed64 fields are strings in OTLP/JSON
+    let _ = write!(out, ",\"count\":\"{}\",\"sum\":", count);
+    write_number(out, sum, kind);
+    out.push_str(",\"bucketCounts\":[");
+    write_list(out, counts, |out, count| {
+        let _ = write!(out, "\"{}\"", count);
+    });
+    out.push_str("],\"explicitBounds\":[");
+    write_list(out, bounds, |out, bound| write_f64(out, *bound));
+    let _ = write!(out, "]}}],\"aggregationTemporality\":{}}}", temporality);
+}
+
+fn write_number_point(
+    out: &mut String,
+    record: &Record<'_>,
+    start_time: SystemTime,
+    time: SystemTime,
+    value: &Number,
+    kind: &NumberKind,
+) {
+    write_point_header(out, record, start_time, time);
+    out.push_str(",\"value\":");
+    write_number(out, value, kind);
+    out.push('}');
+}
+
+/// Write the opening of a data point with its labels and times.
+fn write_point_header(
+    out: &mut String,
+    record: &Record<'_>,
+    start_time: SystemTime,
+    time: SystemTime,
+) {
+    out.push_str("{\"labels\":[");
+    for (i, (key, value)) in record.labels().iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str("{\"key\":");
+        write_str(out, key.as_str());
+        out.push_str(",\"value\":");
+        write_str(out, &value.as_str());
+        out.push('}');
+    }
+    let _ = write!(
+        out,
+        "],\"startTimeUnixNano\":\"{}\",\"timeUnixNano\":\"{}\"",
+        unix_nanos(start_time),
+        unix_nanos(time)
+    );
+}
+
+/// Write a number, int64 values are strings in OTLP/JSON.
+fn write_number(out: &mut String, value: &Number, kind: &NumberKind) {
+    match kind {
+        NumberKind::F64 => write_f64(out, value.to_f64(kind)),
+        _ => {
+            let _ = write!(out, "\"{}\"", value.to_i64(kind));
+        }
+    }
+}
@@ -1,8 +1,9 @@
 //! Metrics Export
-use crate::sdk::resource::Resource;
+use crate::sdk::{resource::Resource, Clock};
 use crate::{
     labels,
     metrics::{Descriptor, InstrumentKind, Number, Result},
+    Context, KeyValue,
 };
 use std::any::Any;
 use std::fmt;
@@ -10,11 +11,12 @@ use std::sync::Arc;
 use std::time::SystemTime;
 
 mod aggregation;
+pub(crate) mod json;
 pub mod stdout;
 
 pub use aggregation::{
-    Buckets, Count, Distribution, Histogram, LastValue, Max, Min, MinMaxSumCount, Points, Quantile,
-    Sum,
+    Buckets, Count, Distribution, Exemplar, Exemplars, ExponentialBuckets, ExponentialHistogram,
+    Histogram, LastValue, Max, Min, MinMaxSumCount, Points, Quantile, Sum,
 };
 pub use stdout::stdout;
 
@@ -120,6 +122,41 @@ pub trait Aggregator: fmt::Debug {
     /// `SpanContext`.
     fn update(&self, number: &Number, descriptor: &Descriptor) -> Result<()>;
 
+    /// Update with a measurement recorded in the context `cx`, which may be
+    /// kept as an [`Exemplar`] of the aggregation if a sampled span is active.
+    ///
+    /// `filtered_attributes` are the attributes of the measurement that are
+    /// not part of the labels of the record being updated. Aggregators not
+    /// keeping exemplars only `update` with the measured value.
+    fn update_with_context(
+        &self,
+        number: &Number,
+        descriptor: &Descriptor,
+        _cx: &Context,
+        _filtered_attributes: &[KeyValue],
+    ) -> Result<()> {
+        self.update(number, descriptor)
+    }
+
+    /// Update with a measurement recorded in the context `cx`, like
+    /// [`update_with_context`], reading timestamps from `clock`.
+    ///
+    /// The accumulator updates its aggregators with this method, passing its
+    /// clock. Aggregators keeping timestamps, like the time of the last value
+    /// or of exemplars, read them from `clock` instead of the system time.
+    ///
+    /// [`update_with_context`]: Aggregator::update_with_context
+    fn update_with_clock(
+        &self,
+        number: &Number,
+        descriptor: &Descriptor,
+        cx: &Context,
+        filtered_attributes: &[KeyValue],
+        _clock: &dyn Clock,
+    ) -> Result<()> {
+        self.update_with_context(number, descriptor, cx, filtered_attributes)
+    }
+
     /// This method is called during collection to finish one period of aggregation
     /// by atomically saving the currently-updating state into the argument
     /// Aggregator.
@@ -431,3 +468,55 @@ impl ExportKindFor for ExportKindSelector {
         }
     }
 }
+
+/// A selector returning the export kind configured for the kind of an
+/// instrument, e.g. delta sums for backends only accepting deltas while
+/// other instruments are exported cumulatively.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::metrics::InstrumentKind;
+/// use opentelemetry::sdk::export::metrics::{ExportKind, InstrumentKindSelector};
+///
+/// let selector = InstrumentKindSelector::new(ExportKind::Cumulative)
+///     .with_export_kind(InstrumentKind::Counter, ExportKind::Delta)
+///     .with_export_kind(InstrumentKind::SumObserver, ExportKind::Delta);
+/// ```
+#[derive(Debug, Clone)]
+pub struct InstrumentKindSelector {
+    default: ExportKind,
+    export_kinds: Vec<(InstrumentKind, ExportKind)>,
+}
+
+impl InstrumentKindSelector {
+    /// Create a selector returning `default` for all instrument kinds.
+    pub fn new(default: ExportKind) -> Self {
+        InstrumentKindSelector {
+            default,
+            export_kinds: Vec::new(),
+        }
+    }
+
+    /// Export instruments of kind `instrument_kind` with `export_kind`.
+    pub fn with_export_kind(
+        mut self,
+        instrument_kind: InstrumentKind,
+        export_kind: ExportKind,
+    ) -> Self {
+        self.export_kinds
+            .retain(|(kind, _)| *kind != instrument_kind);
+        self.export_kinds.push((instrument_kind, export_kind));
+        self
+    }
+}
+
+impl ExportKindFor for InstrumentKindSelector {
+    fn export_kind_for(&self, descriptor: &Descriptor) -> ExportKind {
+        self.export_kinds
+            .iter()
+            .find(|(kind, _)| kind == descriptor.instrument_kind())
+            .map(|(_, export_kind)| export_kind.clone())
+            .unwrap_or_else(|| self.default.clone())
+    }
+}
@@ -1,14 +1,34 @@
-//! Stdout Metrics Exporter
+//! # Stdout Metrics Exporter
+//!
+//! Prints the checkpoints collected by a push controller, to check the wiring
+//! of instruments while developing. By default the export batches are debug
+//! printed, see [`Format`] to print them as aligned tables meant to be read by
+//! humans, or as OTLP/JSON lines to be processed by tools like `jq`.
+//!
+//! ```no_run
+//! # #[cfg(feature = "rt-tokio")]
+//! # fn main() -> opentelemetry::metrics::Result<()> {
+//! use opentelemetry::sdk::export::metrics::stdout;
+//! use opentelemetry::util::tokio_interval_stream;
+//!
+//! let controller = stdout(tokio::spawn, tokio_interval_stream)
+//!     .with_format(stdout::Format::Table)
+//!     .try_init()?;
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "rt-tokio"))]
+//! # fn main() {}
+//! ```
 use crate::global;
 use crate::sdk::{
     export::metrics::{
-        CheckpointSet, Count, ExportKind, ExportKindFor, ExportKindSelector, Exporter, LastValue,
-        Max, Min, Quantile, Sum,
+        json, CheckpointSet, Count, ExportKind, ExportKindFor, ExportKindSelector, Exporter,
+        LastValue, Max, Min, Quantile, Sum,
     },
     metrics::{
         aggregators::{
-            ArrayAggregator, HistogramAggregator, LastValueAggregator, MinMaxSumCountAggregator,
-            SumAggregator,
+            ArrayAggregator, ExponentialHistogramAggregator, HistogramAggregator,
+            LastValueAggregator, MinMaxSumCountAggregator, SumAggregator,
         },
         controllers::{self, PushController, PushControllerWorker},
         selectors::simple,
@@ -24,6 +44,7 @@ use futures::Stream;
 #[cfg(feature = "serialize")]
 use serde::{Serialize, Serializer};
 use std::fmt;
+use std::fmt::Write as _;
 use std::io;
 use std::iter;
 use std::sync::Mutex;
@@ -39,6 +60,26 @@ where
     StdoutExporterBuilder::<io::Stdout, S, I>::builder(spawn, interval)
 }
 
+/// Output format of the stdout exporter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Format {
+    /// The debug printed [`ExportBatch`], pretty printed if enabled, or the
+    /// output of the formatter if one is set.
+    Debug,
+    /// A table with a row per record and aligned columns for the instrument
+    /// name, the labels and the aggregated values.
+    Table,
+    /// One OTLP/JSON `ExportMetricsServiceRequest` per export and line.
+    OtlpJson,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Format::Debug
+    }
+}
+
 ///
 #[derive(Debug)]
 pub struct StdoutExporter<W> {
@@ -59,6 +100,8 @@ pub struct StdoutExporter<W> {
     label_encoder: Box<dyn Encoder + Send + Sync>,
     /// An optional user-defined function to format a given export batch.
     formatter: Option<Formatter>,
+    /// The output format.
+    format: Format,
 }
 
 /// A collection of exported lines
@@ -123,7 +166,18 @@ where
     W: fmt::Debug + io::Write,
 {
     fn export(&self, checkpoint_set: &mut dyn CheckpointSet) -> Result<()> {
+        if self.format == Format::OtlpJson {
+            return match json::metrics_to_otlp_json(checkpoint_set, self)? {
+                Some(mut line) => {
+                    line.push('\n');
+                    self.write(&line)
+                }
+                None => Ok(()),
+            };
+        }
+
         let mut batch = ExportBatch::default();
+        let mut rows = Vec::new();
         if !self.do_not_print_time {
             batch.timestamp = Some(crate::time::now());
         }
@@ -173,11 +227,23 @@ where
             }
 
             if let Some(histogram) = agg.as_any().downcast_ref::<HistogramAggregator>() {
+                expose.min = Some(ExportNumeric(histogram.min()?.to_debug(kind)));
+                expose.max = Some(ExportNumeric(histogram.max()?.to_debug(kind)));
                 expose.sum = Some(ExportNumeric(histogram.sum()?.to_debug(kind)));
                 expose.count = histogram.count()?;
                 // TODO expose buckets
             }
 
+            if let Some(histogram) = agg
+                .as_any()
+                .downcast_ref::<ExponentialHistogramAggregator>()
+            {
+                expose.min = Some(ExportNumeric(histogram.min()?.to_debug(kind)));
+                expose.max = Some(ExportNumeric(histogram.max()?.to_debug(kind)));
+                expose.sum = Some(ExportNumeric(histogram.sum()?.to_debug(kind)));
+                expose.count = histogram.count()?;
+            }
+
             if let Some(mmsc) = agg.as_any().downcast_ref::<MinMaxSumCountAggregator>() {
                 expose.min = Some(ExportNumeric(mmsc.min()?.to_debug(kind)));
                 expose.max = Some(ExportNumeric(mmsc.max()?.to_debug(kind)));
@@ -189,32 +255,26 @@ where
                 expose.sum = Some(ExportNumeric(sum.sum()?.to_debug(kind)));
             }
 
-            let mut encoded_labels = String::new();
-            let iter = record.labels().iter();
-            if let (0, _) = iter.size_hint() {
-                encoded_labels = record.labels().encoded(Some(self.label_encoder.as_ref()));
+            let encoded_labels = record.labels().encoded(Some(self.label_encoder.as_ref()));
+            let all_labels = [encoded_resource, encoded_inst_labels, encoded_labels]
+                .iter()
+                .filter(|encoded| !encoded.is_empty())
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(",");
+
+            if self.format == Format::Table {
+                rows.push([desc.name().to_string(), all_labels, expose.values()]);
+                return Ok(());
             }
 
             let mut sb = String::new();
 
             sb.push_str(desc.name());
 
-            if !encoded_labels.is_empty()
-                || !encoded_resource.is_empty()
-                || !encoded_inst_labels.is_empty()
-            {
+            if !all_labels.is_empty() {
                 sb.push('{');
-                sb.push_str(&encoded_resource);
-                if !encoded_inst_labels.is_empty() && !encoded_resource.is_empty() {
-                    sb.push(',');
-                }
-                sb.push_str(&encoded_inst_labels);
-                if !encoded_labels.is_empty()
-                    && (!encoded_inst_labels.is_empty() || !encoded_resource.is_empty())
-                {
-                    sb.push(',');
-                }
-                sb.push_str(&encoded_labels);
+                sb.push_str(&all_labels);
                 sb.push('}');
             }
 
@@ -224,17 +284,84 @@ where
             Ok(())
         })?;
 
+        let formatted = match &self.formatter {
+            _ if self.format == Format::Table => format_table(rows),
+            Some(formatter) => formatter.0(batch)?,
+            None if self.pretty_print => format!("{:#?}\n", batch),
+            None => format!("{:?}\n", batch),
+        };
+        self.write(&formatted)
+    }
+}
+
+impl<W> StdoutExporter<W>
+where
+    W: io::Write,
+{
+    fn write(&self, formatted: &str) -> Result<()> {
         self.writer.lock().map_err(From::from).and_then(|mut w| {
-            let formatted = match &self.formatter {
-                Some(formatter) => formatter.0(batch)?,
-                None => format!("{:?}\n", batch),
-            };
             w.write_all(formatted.as_bytes())
                 .map_err(|e| MetricsError::Other(e.to_string()))
         })
     }
 }
 
+impl ExportLine {
+    /// The aggregated values of the line, e.g. `count=2 sum=3 min=1 max=2`.
+    fn values(&self) -> String {
+        let mut values = String::new();
+        if let Some(last_value) = &self.last_value {
+            let _ = write!(values, " last={:?}", last_value);
+        }
+        if self.min.is_some() {
+            let _ = write!(values, " count={}", self.count);
+        }
+        if let Some(sum) = &self.sum {
+            let _ = write!(values, " sum={:?}", sum);
+        }
+        if let Some(min) = &self.min {
+            let _ = write!(values, " min={:?}", min);
+        }
+        if let Some(max) = &self.max {
+            let _ = write!(values, " max={:?}", max);
+        }
+        for quantile in self.quantiles.iter().flatten() {
+            let _ = write!(values, " p{}={:?}", quantile.q * 100.0, quantile.v);
+        }
+        values.trim_start().to_string()
+    }
+}
+
+/// Format the rows of a table, padding all but the last column to the width
+/// of their widest cell.
+fn format_table(rows: Vec<[String; 3]>) -> String {
+    let header = [
+        "INSTRUMENT".to_string(),
+        "LABELS".to_string(),
+        "VALUES".to_string(),
+    ];
+    let mut widths = [0; 3];
+    for row in iter::once(&header).chain(rows.iter()) {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut table = String::new();
+    for row in iter::once(header).chain(rows) {
+        let _ = writeln!(
+            table,
+            "{:<name$}  {:<labels$}  {}",
+            row[0],
+            row[1],
+            row[2],
+            name = widths[0],
+            labels = widths[1]
+        );
+    }
+    table
+}
+
 impl<W> ExportKindFor for StdoutExporter<W>
 where
     W: fmt::Debug + io::Write,
@@ -264,6 +391,7 @@ pub struct StdoutExporterBuilder<W, S, I> {
     label_encoder: Option<Box<dyn Encoder + Send + Sync>>,
     period: Option<Duration>,
     formatter: Option<Formatter>,
+    format: Format,
 }
 
 impl<W, S, SO, I, IS, ISI> StdoutExporterBuilder<W, S, I>
@@ -284,6 +412,7 @@ where
             label_encoder: None,
             period: None,
             formatter: None,
+            format: Format::default(),
         }
     }
     /// Set the writer that this exporter will use.
@@ -298,6 +427,7 @@ where
             label_encoder: self.label_encoder,
             period: self.period,
             formatter: self.formatter,
+            format: self.format,
         }
     }
 
@@ -344,7 +474,13 @@ where
         }
     }
 
-    /// Set a formatter for serializing export batch data
+    /// Set the output format, debug printed export batches by default.
+    pub fn with_format(self, format: Format) -> Self {
+        StdoutExporterBuilder { format, ..self }
+    }
+
+    /// Set a formatter for serializing export batch data, used by the
+    /// [`Format::Debug`] output format.
     pub fn with_formatter<T>(self, formatter: T) -> Self
     where
         T: Fn(ExportBatch) -> Result<String> + Send + Sync + 'static,
@@ -395,7 +531,118 @@ where
                 quantiles: self.quantiles.unwrap_or_else(|| vec![0.5, 0.9, 0.99]),
                 label_encoder: self.label_encoder.unwrap_or_else(default_encoder),
                 formatter: self.formatter,
+                format: self.format,
             },
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::labels::DefaultLabelEncoder;
+    use crate::metrics::{InstrumentKind, Number, NumberKind};
+    use crate::sdk::export::metrics::{record, Aggregator};
+    use crate::sdk::metrics::aggregators;
+    use crate::sdk::Resource;
+    use std::sync::Arc;
+    use std::time::UNIX_EPOCH;
+
+    #[derive(Debug)]
+    struct TestCheckpointSet(Vec<(Descriptor, Arc<dyn Aggregator + Send + Sync>)>);
+
+    impl TestCheckpointSet {
+        fn new() -> Self {
+            let requests = Descriptor::new(
+                "requests".to_string(),
+                "server",
+                None,
+                InstrumentKind::Counter,
+                NumberKind::U64,
+            );
+            let sum = Arc::new(aggregators::sum());
+            sum.update(&Number::from(3u64), &requests).unwrap();
+
+            let latency = Descriptor::new(
+                "request.latency".to_string(),
+                "server",
+                None,
+                InstrumentKind::ValueRecorder,
+                NumberKind::F64,
+            );
+            let mmsc = Arc::new(aggregators::min_max_sum_count(&latency));
+            mmsc.update(&Number::from(0.5), &latency).unwrap();
+            mmsc.update(&Number::from(1.5), &latency).unwrap();
+
+            TestCheckpointSet(vec![(requests, sum), (latency, mmsc)])
+        }
+    }
+
+    impl CheckpointSet for TestCheckpointSet {
+        fn try_for_each(
+            &mut self,
+            _export_selector: &dyn ExportKindFor,
+            f: &mut dyn FnMut(&crate::sdk::export::metrics::Record<'_>) -> Result<()>,
+        ) -> Result<()> {
+            let labels = LabelSet::from_labels(vec![KeyValue::new("method", "GET")]);
+            let resource = Resource::new(vec![KeyValue::new("service.name", "users")]);
+            for (descriptor, aggregator) in &self.0 {
+                f(&record(
+                    descriptor,
+                    &labels,
+                    &resource,
+                    Some(aggregator),
+                    UNIX_EPOCH + Duration::from_nanos(1_000),
+                    UNIX_EPOCH + Duration::from_nanos(2_000),
+                ))?;
+            }
+            Ok(())
+        }
+    }
+
+    fn exporter(format: Format) -> StdoutExporter<Vec<u8>> {
+        StdoutExporter {
+            writer: Mutex::new(Vec::new()),
+            pretty_print: false,
+            do_not_print_time: true,
+            quantiles: vec![0.5],
+            label_encoder: Box::new(DefaultLabelEncoder),
+            formatter: None,
+            format,
+        }
+    }
+
+    fn output(exporter: StdoutExporter<Vec<u8>>) -> String {
+        String::from_utf8(exporter.writer.into_inner().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn export_table() {
+        let exporter = exporter(Format::Table);
+        exporter.export(&mut TestCheckpointSet::new()).unwrap();
+
+        assert_eq!(
+            output(exporter),
+            concat!(
+                "INSTRUMENT       LABELS                                                     VALUES\n",
+                "requests         service.name=users,instrumentation.name=server,method=GET  sum=3\n",
+                "request.latency  service.name=users,instrumentation.name=server,method=GET  count=2 sum=2.0 min=0.5 max=1.5\n",
+            )
+        );
+    }
+
+    #[test]
+    fn export_otlp_json() {
+        let exporter = exporter(Format::OtlpJson);
+        exporter.export(&mut TestCheckpointSet::new()).unwrap();
+        exporter.export(&mut TestCheckpointSet(Vec::new())).unwrap();
+
+        let output = output(exporter);
+        assert_eq!(output.lines().count(), 1);
+        assert!(output.starts_with(
+            r#"{"resourceMetrics":[{"resource":{"attributes":[{"key":"service.name""#
+        ));
+        assert!(output.contains(r#"{"name":"requests","intSum":"#));
+        assert!(output.contains(r#"{"name":"request.latency","#));
+    }
+}
@@ -1,4 +1,12 @@
 //! Metrics Export
+#[cfg(any(feature = "metrics", feature = "trace"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "metrics", feature = "trace"))))]
+pub mod file;
+#[cfg(any(feature = "metrics", feature = "trace"))]
+pub(crate) mod json;
+#[cfg(feature = "logs")]
+#[cfg_attr(docsrs, doc(cfg(feature = "logs")))]
+pub mod logs;
 #[cfg(feature = "metrics")]
 #[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
 pub mod metrics;
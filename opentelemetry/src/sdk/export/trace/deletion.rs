@@ -0,0 +1,291 @@
+//! # Subject Deletion
+//!
+//! Data protection regulations like the GDPR require deleting the personal
+//! data of a subject on request, which includes telemetry still in flight.
+//! [`SubjectDeletion`] is an [`ExportHook`] dropping or anonymizing the spans
+//! referring to deleted subjects before they are exported. This covers the
+//! spans still queued in span processors when a deletion is requested, as
+//! well as spans recorded afterwards until the deletion expires.
+//!
+//! Subjects are identified by string attribute values of spans and their
+//! events, e.g. a user id or an email address. Only exact matches are found,
+//! identifiers embedded in longer values like URLs are not.
+//!
+//! # Examples
+//!
+//! ```
+//! use opentelemetry::sdk::export::trace::{
+//!     deletion::SubjectDeletion, layer::ExporterLayer, stdout,
+//! };
+//! use opentelemetry::sdk::trace::TracerProvider;
+//! use std::time::Duration;
+//!
+//! let deletion = SubjectDeletion::new(Duration::from_secs(3600)).with_keys(vec!["enduser.id"]);
+//! let exporter = ExporterLayer::new(stdout::Exporter::new(std::io::stdout(), false))
+//!     .with_hook(deletion.clone());
+//! let provider = TracerProvider::builder()
+//!     .with_simple_exporter(exporter)
+//!     .build();
+//!
+//! // on a deletion request
+//! deletion.delete_subjects(vec!["user-1234"]);
+//! ```
+//!
+//! [`ExportHook`]: crate::sdk::export::trace::layer::ExportHook
+use crate::sdk::export::trace::{layer::ExportHook, ExportResult, SpanData};
+use crate::trace::TraceError;
+use crate::{Array, Key, KeyValue, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// Value exported in place of the identifier of a deleted subject.
+pub const DELETED_SUBJECT: &str = "DELETED_SUBJECT";
+
+/// How spans referring to a deleted subject are handled.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DeletionAction {
+    /// Spans are not exported.
+    Drop,
+    /// Identifiers are replaced by [`DELETED_SUBJECT`], the remaining span is
+    /// exported.
+    Anonymize,
+}
+
+/// Drops or anonymizes spans referring to deleted subjects, see the [module
+/// level documentation].
+///
+/// Clones share the deleted subjects, so a clone can be registered on the
+/// exporter while the original receives deletion requests.
+///
+/// [module level documentation]: self
+#[derive(Clone, Debug)]
+pub struct SubjectDeletion {
+    ttl: Duration,
+    action: DeletionAction,
+    keys: Vec<Key>,
+    subjects: Arc<Mutex<HashMap<String, SystemTime>>>,
+}
+
+impl SubjectDeletion {
+    /// Create a hook dropping spans referring to subjects deleted within the
+    /// last `ttl`.
+    ///
+    /// The `ttl` should exceed the time spans of a request can take to be
+    /// exported, including retries and the time in batch queues.
+    pub fn new(ttl: Duration) -> Self {
+        SubjectDeletion {
+            ttl,
+            action: DeletionAction::Drop,
+            keys: Vec::new(),
+            subjects: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Specify how spans referring to a deleted subject are handled, spans are
+    /// dropped by default.
+    pub fn with_action(mut self, action: DeletionAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// Only match identifiers in attributes with one of the given keys,
+    /// instead of all attributes.
+    pub fn with_keys<I, K>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        K: Into<Key>,
+    {
+        self.keys.extend(keys.into_iter().map(Into::into));
+        self
+    }
+
+    /// Delete the subjects with the given identifiers from spans exported
+    /// until the ttl elapsed.
+    ///
+    /// Requesting the deletion of a subject again extends its ttl.
+    pub fn delete_subjects<I, T>(&self, identifiers: I)
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        let expires_at = crate::time::now() + self.ttl;
+        if let Ok(mut subjects) = self.subjects.lock() {
+            subjects.extend(
+                identifiers
+                    .into_iter()
+                    .map(|identifier| (identifier.into(), expires_at)),
+            );
+        }
+    }
+
+    fn matches(&self, key: &Key) -> bool {
+        self.keys.is_empty() || self.keys.contains(key)
+    }
+}
+
+impl ExportHook for SubjectDeletion {
+    fn before_export(&self, batch: &mut Vec<SpanData>) -> ExportResult {
+        // spans are never exported unchecked, failing the export is the only
+        // option if the subjects are unavailable
+        let mut subjects = self
+            .subjects
+            .lock()
+            .map_err(|err| TraceError::from(err.to_string()))?;
+        let now = crate::time::now();
+        subjects.retain(|_, expires_at| *expires_at > now);
+        if subjects.is_empty() {
+            return Ok(());
+        }
+
+        match self.action {
+            DeletionAction::Drop => batch.retain(|span| {
+                let mut attributes = span.attributes.iter().chain(
+                    span.message_events
+                        .iter()
+                        .flat_map(|event| event.attributes.iter().map(|kv| (&kv.key, &kv.value))),
+                );
+                !attributes
+                    .any(|(key, value)| self.matches(key) && anonymize(value, &subjects).is_some())
+            }),
+            DeletionAction::Anonymize => {
+                for span in batch.iter_mut() {
+                    let replaced = span
+                        .attributes
+                        .iter()
+                        .filter(|(key, _)| self.matches(key))
+                        .filter_map(|(key, value)| {
+                            anonymize(value, &subjects)
+                                .map(|value| KeyValue::new(key.clone(), value))
+                        })
+                        .collect::<Vec<_>>();
+                    for kv in replaced {
+                        span.attributes.insert(kv);
+                    }
+                    for kv in span
+                        .message_events
+                        .iter_mut()
+                        .flat_map(|event| event.attributes.iter_mut())
+                        .filter(|kv| self.matches(&kv.key))
+                    {
+                        if let Some(value) = anonymize(&kv.value, &subjects) {
+                            kv.value = value;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The anonymized `value`, `None` if it contains no deleted subject.
+fn anonymize(value: &Value, subjects: &HashMap<String, SystemTime>) -> Option<Value> {
+    match value {
+        Value::String(s) if subjects.contains_key(s.as_ref()) => Some(DELETED_SUBJECT.into()),
+        Value::Array(Array::String(values))
+            if values.iter().any(|s| subjects.contains_key(s.as_ref())) =>
+        {
+            Some(Value::Array(Array::String(
+                values
+                    .iter()
+                    .map(|s| {
+                        if subjects.contains_key(s.as_ref()) {
+                            DELETED_SUBJECT.into()
+                        } else {
+                            s.clone()
+                        }
+                    })
+                    .collect(),
+            )))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::testing::trace::new_test_export_span_data;
+    use crate::trace::Event;
+
+    fn strings(values: Vec<&'static str>) -> Value {
+        Value::Array(Array::String(values.into_iter().map(Into::into).collect()))
+    }
+
+    fn span(user: &'static str) -> SpanData {
+        let mut span = new_test_export_span_data();
+        span.attributes.insert(KeyValue::new("enduser.id", user));
+        span.attributes.insert(KeyValue::new("http.method", "GET"));
+        span.message_events.push_back(Event::with_name("login"));
+        span.message_events.push_back(Event::new(
+            "lookup",
+            crate::time::now(),
+            vec![KeyValue::new("recipients", strings(vec![user, "user-2"]))],
+        ));
+        span
+    }
+
+    #[test]
+    fn drop_spans_of_deleted_subjects() {
+        let deletion = SubjectDeletion::new(Duration::from_secs(60));
+        let mut batch = vec![span("user-1"), span("user-3")];
+        assert!(deletion.before_export(&mut batch).is_ok());
+        assert_eq!(batch.len(), 2);
+
+        deletion.clone().delete_subjects(vec!["user-1"]);
+        assert!(deletion.before_export(&mut batch).is_ok());
+        assert_eq!(batch.len(), 1);
+        assert_eq!(
+            batch[0].attributes.get(&Key::new("enduser.id")),
+            Some(&Value::from("user-3"))
+        );
+
+        // user-2 only appears in event attributes
+        deletion.delete_subjects(vec!["user-2"]);
+        assert!(deletion.before_export(&mut batch).is_ok());
+        assert!(batch.is_empty());
+    }
+
+    #[test]
+    fn anonymize_matching_keys() {
+        let deletion = SubjectDeletion::new(Duration::from_secs(60))
+            .with_action(DeletionAction::Anonymize)
+            .with_keys(vec!["enduser.id"]);
+        deletion.delete_subjects(vec!["user-1", "user-2"]);
+
+        let mut batch = vec![span("user-1")];
+        assert!(deletion.before_export(&mut batch).is_ok());
+        assert_eq!(
+            batch[0].attributes.get(&Key::new("enduser.id")),
+            Some(&Value::from(DELETED_SUBJECT))
+        );
+        assert_eq!(
+            batch[0].message_events.iter().nth(1).unwrap().attributes,
+            vec![KeyValue::new(
+                "recipients",
+                strings(vec!["user-1", "user-2"])
+            )]
+        );
+
+        let deletion = deletion.with_keys(vec!["recipients"]);
+        assert!(deletion.before_export(&mut batch).is_ok());
+        assert_eq!(
+            batch[0].message_events.iter().nth(1).unwrap().attributes,
+            vec![KeyValue::new(
+                "recipients",
+                strings(vec![DELETED_SUBJECT, DELETED_SUBJECT])
+            )]
+        );
+    }
+
+    #[test]
+    fn expire_deletions() {
+        let deletion = SubjectDeletion::new(Duration::from_secs(0));
+        deletion.delete_subjects(vec!["user-1"]);
+        let mut batch = vec![span("user-1")];
+        assert!(deletion.before_export(&mut batch).is_ok());
+        assert_eq!(batch.len(), 1);
+        assert!(deletion.subjects.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,191 @@
+//! # Exporter Factory
+//!
+//! Creates the span exporters selected by the `OTEL_TRACES_EXPORTER`
+//! environment variable, a comma separated list of exporter names such as
+//! `otlp`, `jaeger`, `zipkin`, `stdout` or `none`. Without the variable the
+//! `otlp` exporter is selected, as defined by the [specification].
+//!
+//! This crate provides the `stdout` exporter, `none` selects no exporter.
+//! The exporters of other crates are registered by name with a constructor,
+//! so only the exporters of the crates an application depends on are
+//! available. The `opentelemetry-otlp`, `opentelemetry-jaeger` and
+//! `opentelemetry-zipkin` crates provide an `exporter_factory` with their
+//! exporter registered, and a `register_exporter` function to add it to
+//! another factory.
+//!
+//! # Examples
+//!
+//! ```text
+//! use opentelemetry::sdk::{export::trace::factory::ExporterFactory, trace::TracerProvider};
+//!
+//! // creates the otlp exporter selected by default, or jaeger or stdout
+//! let factory = opentelemetry_jaeger::register_exporter(opentelemetry_otlp::exporter_factory());
+//!
+//! let provider = TracerProvider::builder()
+//!     .with_exporters_from_env(&factory)
+//!     .expect("invalid OTEL_TRACES_EXPORTER")
+//!     .build();
+//! ```
+//!
+//! Exporters can also be registered with a custom constructor:
+//!
+//! ```no_run
+//! use opentelemetry::sdk::{export::trace::factory::ExporterFactory, trace::TracerProvider};
+//! use opentelemetry::trace::NoopSpanExporter;
+//!
+//! let factory = ExporterFactory::new()
+//!     .with_exporter("noop", || Ok(Box::new(NoopSpanExporter::new())));
+//!
+//! let provider = TracerProvider::builder()
+//!     .with_exporters_from_env(&factory)
+//!     .expect("invalid OTEL_TRACES_EXPORTER")
+//!     .build();
+//! ```
+//!
+//! [specification]: https://github.com/open-telemetry/opentelemetry-specification/blob/main/specification/sdk-environment-variables.md#exporter-selection
+use crate::sdk::export::trace::{stdout, SpanExporter};
+use crate::trace::{TraceError, TraceResult};
+use std::env;
+use std::fmt;
+
+/// Name of the environment variable selecting the span exporters.
+pub const OTEL_TRACES_EXPORTER: &str = "OTEL_TRACES_EXPORTER";
+
+/// Exporter selected if `OTEL_TRACES_EXPORTER` is not set.
+const OTEL_TRACES_EXPORTER_DEFAULT: &str = "otlp";
+
+/// Selects no exporter.
+const NONE: &str = "none";
+
+type Constructor = Box<dyn Fn() -> TraceResult<Box<dyn SpanExporter>> + Send + Sync>;
+
+/// Creates span exporters by name, see the [module level documentation].
+///
+/// [module level documentation]: self
+pub struct ExporterFactory {
+    constructors: Vec<(String, Constructor)>,
+}
+
+impl fmt::Debug for ExporterFactory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExporterFactory")
+            .field("exporters", &self.names())
+            .finish()
+    }
+}
+
+impl Default for ExporterFactory {
+    fn default() -> Self {
+        ExporterFactory {
+            constructors: Vec::new(),
+        }
+        .with_exporter("stdout", || {
+            Ok(Box::new(stdout::Exporter::new(std::io::stdout(), false)))
+        })
+    }
+}
+
+impl ExporterFactory {
+    /// Create a factory providing the exporters of this crate.
+    pub fn new() -> Self {
+        ExporterFactory::default()
+    }
+
+    /// Register the constructor of the exporter named `name`, replacing the
+    /// constructor registered with the same name before.
+    ///
+    /// Names are case insensitive.
+    pub fn with_exporter<F>(mut self, name: &str, constructor: F) -> Self
+    where
+        F: Fn() -> TraceResult<Box<dyn SpanExporter>> + Send + Sync + 'static,
+    {
+        let name = name.trim().to_ascii_lowercase();
+        self.constructors
+            .retain(|(registered, _)| *registered != name);
+        self.constructors.push((name, Box::new(constructor)));
+        self
+    }
+
+    /// The names of the registered exporters.
+    pub fn names(&self) -> Vec<&str> {
+        self.constructors
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// Create the exporters selected by `OTEL_TRACES_EXPORTER`.
+    pub fn build_from_env(&self) -> TraceResult<Vec<Box<dyn SpanExporter>>> {
+        let selected = env::var(OTEL_TRACES_EXPORTER)
+            .ok()
+            .filter(|names| !names.trim().is_empty())
+            .unwrap_or_else(|| OTEL_TRACES_EXPORTER_DEFAULT.to_string());
+        self.build(&selected)
+    }
+
+    /// Create the exporters named in the comma separated list `names`.
+    ///
+    /// Fails if a name was not registered or an exporter could not be
+    /// created.
+    pub fn build(&self, names: &str) -> TraceResult<Vec<Box<dyn SpanExporter>>> {
+        let mut exporters = Vec::new();
+        for name in names
+            .split(',')
+            .map(|name| name.trim().to_ascii_lowercase())
+        {
+            if name.is_empty() || name == NONE {
+                continue;
+            }
+            let constructor = self
+                .constructors
+                .iter()
+                .find(|(registered, _)| *registered == name)
+                .map(|(_, constructor)| constructor)
+                .ok_or_else(|| {
+                    TraceError::from(format!(
+                        "unknown span exporter \"{}\" in {}, expected one of {:?}, \
+                         exporter crates register theirs with `register_exporter`",
+                        name,
+                        OTEL_TRACES_EXPORTER,
+                        self.names()
+                    ))
+                })?;
+            exporters.push(constructor()?);
+        }
+        Ok(exporters)
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::trace::NoopSpanExporter;
+
+    fn factory() -> ExporterFactory {
+        ExporterFactory::new()
+            .with_exporter("OTLP", || Ok(Box::new(NoopSpanExporter::new())))
+            .with_exporter("jaeger", || Err("agent unreachable".into()))
+    }
+
+    #[test]
+    fn build_selected_exporters() {
+        assert_eq!(factory().names(), vec!["stdout", "otlp", "jaeger"]);
+        assert_eq!(factory().build("otlp, Stdout").unwrap().len(), 2);
+        assert!(factory().build("none").unwrap().is_empty());
+
+        let err = factory().build("otlp,zipkin").unwrap_err();
+        assert!(err.to_string().contains("unknown span exporter \"zipkin\""));
+        assert!(factory().build("jaeger").is_err());
+    }
+
+    #[test]
+    fn select_otlp_by_default() {
+        env::remove_var(OTEL_TRACES_EXPORTER);
+        assert_eq!(factory().build_from_env().unwrap().len(), 1);
+        assert!(ExporterFactory::new().build_from_env().is_err());
+
+        env::set_var(OTEL_TRACES_EXPORTER, "none");
+        assert!(factory().build_from_env().unwrap().is_empty());
+        env::remove_var(OTEL_TRACES_EXPORTER);
+    }
+}
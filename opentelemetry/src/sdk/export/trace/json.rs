@@ -0,0 +1,416 @@
+//! # JSON Span Encodings
+//!
+//! Machine-parseable encodings of [`SpanData`], written without a JSON
+//! library as only a handful of types need to be encoded.
+//!
+//! The newline-delimited encoding writes one object per span with the fields
+//! of [`SpanData`]. The OTLP encoding follows the [OTLP/JSON] mapping of the
+//! `ExportTraceServiceRequest` protobuf message, so its output can be read by
+//! file-based collectors.
+//!
+//! [OTLP/JSON]: https://github.com/open-telemetry/opentelemetry-specification/blob/main/specification/protocol/otlp.md#json-protobuf-encoding
+use crate::sdk::export::json::{
+    unix_nanos, write_f64, write_list, write_otlp_attributes, write_otlp_library, write_str,
+};
+use crate::sdk::export::trace::SpanData;
+use crate::sdk::{InstrumentationLibrary, Resource};
+use crate::trace::{SpanId, SpanKind};
+use crate::{Array, Key, Value};
+use std::fmt::Write;
+
+/// Encode a span as a single line JSON object, without the trailing newline.
+pub(crate) fn span_to_json(span: &SpanData) -> String {
+    let mut out = String::with_capacity(512);
+    out.push_str("{\"trace_id\":");
+    write_str(&mut out, &span.span_context.trace_id().to_hex());
+    out.push_str(",\"span_id\":");
+    write_str(&mut out, &span.span_context.span_id().to_hex());
+    if span.parent_span_id != SpanId::invalid() {
+        out.push_str(",\"parent_span_id\":");
+        write_str(&mut out, &span.parent_span_id.to_hex());
+    }
+    let trace_state = span.span_context.trace_state().header();
+    if !trace_state.is_empty() {
+        out.push_str(",\"trace_state\":");
+        write_str(&mut out, &trace_state);
+    }
+    out.push_str(",\"name\":");
+    write_str(&mut out, &span.name);
+    let _ = write!(
+        out,
+        ",\"span_kind\":\"{}\",\"start_time_unix_nano\":{},\"end_time_unix_nano\":{}",
+        span.span_kind,
+        unix_nanos(span.start_time),
+        unix_nanos(span.end_time)
+    );
+    out.push_str(",\"attributes\":");
+    write_attribute_map(&mut out, span.attributes.iter());
+    if span.attributes.dropped_count() > 0 {
+        let _ = write!(
+            out,
+            ",\"dropped_attributes_count\":{}",
+            span.attributes.dropped_count()
+        );
+    }
+
+    out.push_str(",\"events\":[");
+    for (i, event) in span.message_events.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str("{\"name\":");
+        write_str(&mut out, &event.name);
+        let _ = write!(out, ",\"time_unix_nano\":{}", unix_nanos(event.timestamp));
+        out.push_str(",\"attributes\":");
+        write_attribute_map(
+            &mut out,
+            event.attributes.iter().map(|kv| (&kv.key, &kv.value)),
+        );
+        out.push('}');
+    }
+    out.push_str("],\"links\":[");
+    for (i, link) in span.links.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str("{\"trace_id\":");
+        write_str(&mut out, &link.span_context().trace_id().to_hex());
+        out.push_str(",\"span_id\":");
+        write_str(&mut out, &link.span_context().span_id().to_hex());
+        out.push_str(",\"attributes\":");
+        write_attribute_map(
+            &mut out,
+            link.attributes().iter().map(|kv| (&kv.key, &kv.value)),
+        );
+        out.push('}');
+    }
+
+    out.push_str("],\"status_code\":");
+    write_str(&mut out, span.status_code.as_str());
+    if !span.status_message.is_empty() {
+        out.push_str(",\"status_message\":");
+        write_str(&mut out, &span.status_message);
+    }
+    out.push_str(",\"resource\":");
+    write_attribute_map(&mut out, span.resource.iter());
+    out.push_str(",\"instrumentation_library\":{\"name\":");
+    write_str(&mut out, span.instrumentation_lib.name);
+    if let Some(version) = span.instrumentation_lib.version {
+        out.push_str(",\"version\":");
+        write_str(&mut out, version);
+    }
+    out.push_str("}}");
+    out
+}
+
+/// Spans of a resource, grouped by instrumentation library.
+type ResourceSpans<'a> = (&'a Resource, Vec<LibrarySpans<'a>>);
+type LibrarySpans<'a> = (&'a InstrumentationLibrary, Vec<&'a SpanData>);
+
+/// Encode spans as a single line OTLP/JSON `ExportTraceServiceRequest`,
+/// without the trailing newline.
+///
+/// Spans are grouped by resource and instrumentation library, keeping the
+/// order in which the groups first appear.
+pub(crate) fn spans_to_otlp_json(spans: &[SpanData]) -> String {
+    let mut groups: Vec<ResourceSpans<'_>> = Vec::new();
+    for span in spans {
+        let libraries = match groups
+            .iter_mut()
+            .find(|(resource, _)| **resource == *span.resource)
+        {
+            Some((_, libraries)) => libraries,
+            None => {
+                groups.push((&span.resource, Vec::new()));
+                &mut groups.last_mut().unwrap().1
+            }
+        };
+        match libraries
+            .iter_mut()
+            .find(|(library, _)| **library == span.instrumentation_lib)
+        {
+            Some((_, spans)) => spans.push(span),
+            None => libraries.push((&span.instrumentation_lib, vec![span])),
+        }
+    }
+
+    let mut out = String::with_capacity(512 * spans.len() + 64);
+    out.push_str("{\"resourceSpans\":[");
+    for (i, (resource, libraries)) in groups.into_iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str("{\"resource\":{\"attributes\":");
+        write_otlp_attributes(&mut out, resource.iter());
+        out.push_str("},\"instrumentationLibrarySpans\":[");
+        for (j, (library, spans)) in libraries.into_iter().enumerate() {
+            if j > 0 {
+                out.push(',');
+            }
+            out.push_str("{\"instrumentationLibrary\":");
+            write_otlp_library(&mut out, library);
+            out.push_str(",\"spans\":[");
+            for (k, span) in spans.into_iter().enumerate() {
+                if k > 0 {
+                    out.push(',');
+                }
+                write_otlp_span(&mut out, span);
+            }
+            out.push(']');
+            if let Some(schema_url) = library.schema_url {
+                out.push_str(",\"schemaUrl\":");
+                write_str(&mut out, schema_url);
+            }
+            out.push('}');
+        }
+        out.push_str("]}");
+    }
+    out.push_str("]}");
+    out
+}
+
+fn write_otlp_span(out: &mut String, span: &SpanData) {
+    out.push_str("{\"traceId\":");
+    write_str(out, &span.span_context.trace_id().to_hex());
+    out.push_str(",\"spanId\":");
+    write_str(out, &span.span_context.span_id().to_hex());
+    let trace_state = span.span_context.trace_state().header();
+    if !trace_state.is_empty() {
+        out.push_str(",\"traceState\":");
+        write_str(out, &trace_state);
+    }
+    if span.parent_span_id != SpanId::invalid() {
+        out.push_str(",\"parentSpanId\":");
+        write_str(out, &span.parent_span_id.to_hex());
+    }
+    out.push_str(",\"name\":");
+    write_str(out, &span.name);
+    // int64 fields are strings in OTLP/JSON
+    let _ = write!(
+        out,
+        ",\"kind\":{},\"startTimeUnixNano\":\"{}\",\"endTimeUnixNano\":\"{}\"",
+        otlp_span_kind(&span.span_kind),
+        unix_nanos(span.start_time),
+        unix_nanos(span.end_time)
+    );
+    out.push_str(",\"attributes\":");
+    write_otlp_attributes(out, span.attributes.iter());
+    if span.attributes.dropped_count() > 0 {
+        let _ = write!(
+            out,
+            ",\"droppedAttributesCount\":{}",
+            span.attributes.dropped_count()
+        );
+    }
+
+    out.push_str(",\"events\":[");
+    for (i, event) in span.message_events.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        let _ = write!(
+            out,
+            "{{\"timeUnixNano\":\"{}\",\"name\":",
+            unix_nanos(event.timestamp)
+        );
+        write_str(out, &event.name);
+        out.push_str(",\"attributes\":");
+        write_otlp_attributes(out, event.attributes.iter().map(|kv| (&kv.key, &kv.value)));
+        out.push('}');
+    }
+    if span.message_events.dropped_count() > 0 {
+        let _ = write!(
+            out,
+            "],\"droppedEventsCount\":{}",
+            span.message_events.dropped_count()
+        );
+    } else {
+        out.push(']');
+    }
+
+    out.push_str(",\"links\":[");
+    for (i, link) in span.links.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str("{\"traceId\":");
+        write_str(out, &link.span_context().trace_id().to_hex());
+        out.push_str(",\"spanId\":");
+        write_str(out, &link.span_context().span_id().to_hex());
+        out.push_str(",\"attributes\":");
+        write_otlp_attributes(out, link.attributes().iter().map(|kv| (&kv.key, &kv.value)));
+        out.push('}');
+    }
+    if span.links.dropped_count() > 0 {
+        let _ = write!(
+            out,
+            "],\"droppedLinksCount\":{}",
+            span.links.dropped_count()
+        );
+    } else {
+        out.push(']');
+    }
+
+    let _ = write!(out, ",\"status\":{{\"code\":{}", span.status_code as i32);
+    if !span.status_message.is_empty() {
+        out.push_str(",\"message\":");
+        write_str(out, &span.status_message);
+    }
+    out.push_str("}}");
+}
+
+fn otlp_span_kind(kind: &SpanKind) -> i32 {
+    match kind {
+        SpanKind::Internal => 1,
+        SpanKind::Server => 2,
+        SpanKind::Client => 3,
+        SpanKind::Producer => 4,
+        SpanKind::Consumer => 5,
+    }
+}
+
+/// Write attributes as an object of plain JSON values.
+fn write_attribute_map<'a>(
+    out: &mut String,
+    attributes: impl Iterator<Item = (&'a Key, &'a Value)>,
+) {
+    out.push('{');
+    for (i, (key, value)) in attributes.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_str(out, key.as_str());
+        out.push(':');
+        write_value(out, value);
+    }
+    out.push('}');
+}
+
+fn write_value(out: &mut String, value: &Value) {
+    match value {
+        Value::Bool(v) => {
+            let _ = write!(out, "{}", v);
+        }
+        Value::I64(v) => {
+            let _ = write!(out, "{}", v);
+        }
+        Value::F64(v) => write_f64(out, *v),
+        Value::String(v) => write_str(out, v),
+        Value::Array(array) => {
+            out.push('[');
+            match array {
+                Array::Bool(values) => write_list(out, values, |out, v| {
+                    let _ = write!(out, "{}", v);
+                }),
+                Array::I64(values) => write_list(out, values, |out, v| {
+                    let _ = write!(out, "{}", v);
+                }),
+                Array::F64(values) => write_list(out, values, |out, v| write_f64(out, *v)),
+                Array::String(values) => write_list(out, values, |out, v| write_str(out, v)),
+            }
+            out.push(']');
+        }
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::sdk::trace::{EvictedHashMap, EvictedQueue};
+    use crate::trace::{Event, SpanContext, StatusCode, TraceId, TraceState};
+    use crate::KeyValue;
+    use std::sync::Arc;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn span_data() -> SpanData {
+        let mut attributes = EvictedHashMap::new(32, 1);
+        attributes.insert(KeyValue::new("http.method", "GET"));
+        let mut message_events = EvictedQueue::new(32);
+        message_events.push_back(Event::new(
+            "exception",
+            UNIX_EPOCH + Duration::from_nanos(1_500),
+            vec![KeyValue::new("message", "line\n\"quoted\"")],
+        ));
+        SpanData {
+            span_context: SpanContext::new(
+                TraceId::from_u128(1),
+                SpanId::from_u64(2),
+                1,
+                false,
+                TraceState::default(),
+            ),
+            parent_span_id: SpanId::invalid(),
+            span_kind: SpanKind::Server,
+            name: "GET /users".into(),
+            start_time: UNIX_EPOCH + Duration::from_nanos(1_000),
+            end_time: UNIX_EPOCH + Duration::from_nanos(2_000),
+            attributes,
+            message_events,
+            links: EvictedQueue::new(32),
+            status_code: StatusCode::Error,
+            status_message: "failed".to_string(),
+            resource: Arc::new(Resource::new(vec![KeyValue::new("service.name", "users")])),
+            instrumentation_lib: InstrumentationLibrary::new("server", Some("1.0")),
+        }
+    }
+
+    #[test]
+    fn encode_span_as_json_line() {
+        assert_eq!(
+            span_to_json(&span_data()),
+            concat!(
+                r#"{"trace_id":"00000000000000000000000000000001","span_id":"0000000000000002","#,
+                r#""name":"GET /users","span_kind":"server","start_time_unix_nano":1000,"#,
+                r#""end_time_unix_nano":2000,"attributes":{"http.method":"GET"},"#,
+                r#""events":[{"name":"exception","time_unix_nano":1500,"#,
+                r#""attributes":{"message":"line\n\"quoted\""}}],"links":[],"#,
+                r#""status_code":"ERROR","status_message":"failed","#,
+                r#""resource":{"service.name":"users"},"#,
+                r#""instrumentation_library":{"name":"server","version":"1.0"}}"#,
+            )
+        );
+    }
+
+    #[test]
+    fn encode_spans_as_otlp_json() {
+        let first = span_data();
+        let mut second = span_data();
+        second.instrumentation_lib = InstrumentationLibrary::new("client", None)
+            .with_attributes(vec![KeyValue::new("client.pool", "default")]);
+        second.span_kind = SpanKind::Client;
+        second.parent_span_id = SpanId::from_u64(2);
+        let mut third = span_data();
+        third.attributes = EvictedHashMap::new(32, 1);
+        third.attributes.insert(KeyValue::new(
+            "values",
+            Value::Array(Array::F64(vec![1.5, f64::NAN])),
+        ));
+        third.message_events = EvictedQueue::new(32);
+        third.status_code = StatusCode::Unset;
+        third.status_message = String::new();
+
+        let encoded = spans_to_otlp_json(&[first, second, third]);
+        assert_eq!(
+            encoded,
+            concat!(
+                r#"{"resourceSpans":[{"resource":{"attributes":[{"key":"service.name","value":{"stringValue":"users"}}]},"#,
+                r#""instrumentationLibrarySpans":[{"instrumentationLibrary":{"name":"server","version":"1.0"},"spans":["#,
+                r#"{"traceId":"00000000000000000000000000000001","spanId":"0000000000000002","name":"GET /users","#,
+                r#""kind":2,"startTimeUnixNano":"1000","endTimeUnixNano":"2000","#,
+                r#""attributes":[{"key":"http.method","value":{"stringValue":"GET"}}],"#,
+                r#""events":[{"timeUnixNano":"1500","name":"exception","attributes":[{"key":"message","value":{"stringValue":"line\n\"quoted\""}}]}],"#,
+                r#""links":[],"status":{"code":2,"message":"failed"}},"#,
+                r#"{"traceId":"00000000000000000000000000000001","spanId":"0000000000000002","name":"GET /users","#,
+                r#""kind":2,"startTimeUnixNano":"1000","endTimeUnixNano":"2000","#,
+                r#""attributes":[{"key":"values","value":{"arrayValue":{"values":[{"doubleValue":1.5},{"doubleValue":"NaN"}]}}}],"#,
+                r#""events":[],"links":[],"status":{"code":0}}]},"#,
+                r#"{"instrumentationLibrary":{"name":"client","attributes":[{"key":"client.pool","value":{"stringValue":"default"}}]},"spans":["#,
+                r#"{"traceId":"00000000000000000000000000000001","spanId":"0000000000000002","parentSpanId":"0000000000000002","#,
+                r#""name":"GET /users","kind":3,"startTimeUnixNano":"1000","endTimeUnixNano":"2000","#,
+                r#""attributes":[{"key":"http.method","value":{"stringValue":"GET"}}],"#,
+                r#""events":[{"timeUnixNano":"1500","name":"exception","attributes":[{"key":"message","value":{"stringValue":"line\n\"quoted\""}}]}],"#,
+                r#""links":[],"status":{"code":2,"message":"failed"}}]}]}]}"#,
+            )
+        );
+    }
+}
@@ -0,0 +1,203 @@
+//! # Exporter Layer
+//!
+//! Wraps any [`SpanExporter`] with hooks running around each export, the
+//! single place to add concerns shared by all exporters, e.g. recording
+//! batch sizes and export durations, logging failed requests or injecting
+//! failures to test how an application copes with an unavailable backend.
+//!
+//! Credentials and other request headers are specific to the protocol of an
+//! exporter and remain configured on the exporter itself.
+//!
+//! # Examples
+//!
+//! ```
+//! use opentelemetry::sdk::export::trace::{
+//!     layer::{ExportHook, ExporterLayer},
+//!     stdout, ExportResult,
+//! };
+//! use opentelemetry::sdk::trace::TracerProvider;
+//! use std::time::Duration;
+//!
+//! #[derive(Debug)]
+//! struct LogFailures;
+//!
+//! impl ExportHook for LogFailures {
+//!     fn after_export(&self, spans: usize, _duration: Duration, result: &ExportResult) {
+//!         if let Err(err) = result {
+//!             eprintln!("failed to export {} spans: {}", spans, err);
+//!         }
+//!     }
+//! }
+//!
+//! let exporter = ExporterLayer::new(stdout::Exporter::new(std::io::stdout(), false))
+//!     .with_hook(LogFailures);
+//! let provider = TracerProvider::builder()
+//!     .with_simple_exporter(exporter)
+//!     .build();
+//! ```
+use crate::sdk::export::trace::{ExportResult, SpanData, SpanExporter};
+use async_trait::async_trait;
+use std::fmt::Debug;
+use std::time::Duration;
+
+/// Hook observing or altering the exports of an [`ExporterLayer`].
+pub trait ExportHook: Send + Sync + Debug {
+    /// Called before the batch is passed to the inner exporter.
+    ///
+    /// The batch can be altered, e.g. to drop spans. Returning an error skips
+    /// the export and the remaining `before_export` hooks, the error is the
+    /// result of the export.
+    fn before_export(&self, _batch: &mut Vec<SpanData>) -> ExportResult {
+        Ok(())
+    }
+
+    /// Called with the number of exported spans, the time the export took and
+    /// its result, also if the export was skipped by a `before_export` hook.
+    fn after_export(&self, _spans: usize, _duration: Duration, _result: &ExportResult) {}
+}
+
+/// A [`SpanExporter`] delegating to an inner exporter and running
+/// [`ExportHook`]s around each export.
+///
+/// `before_export` hooks run in the order they were added and
+/// `after_export` hooks in the reverse order, so the first hook added wraps
+/// all others.
+#[derive(Debug)]
+pub struct ExporterLayer<E> {
+    inner: E,
+    hooks: Vec<Box<dyn ExportHook>>,
+}
+
+impl<E: SpanExporter> ExporterLayer<E> {
+    /// Wrap `inner` without hooks.
+    pub fn new(inner: E) -> Self {
+        ExporterLayer {
+            inner,
+            hooks: Vec::new(),
+        }
+    }
+
+    /// Add a hook running around each export.
+    pub fn with_hook<H: ExportHook + 'static>(mut self, hook: H) -> Self {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    /// The wrapped exporter.
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+
+    /// Unwrap the inner exporter, dropping the hooks.
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+}
+
+#[async_trait]
+impl<E: SpanExporter> SpanExporter for ExporterLayer<E> {
+    async fn export(&mut self, mut batch: Vec<SpanData>) -> ExportResult {
+        let start = crate::time::now();
+        let (spans, result) = match self
+            .hooks
+            .iter()
+            .try_for_each(|hook| hook.before_export(&mut batch))
+        {
+            Ok(()) => {
+                let spans = batch.len();
+                (spans, self.inner.export(batch).await)
+            }
+            Err(err) => (batch.len(), Err(err)),
+        };
+        let duration = crate::time::now().duration_since(start).unwrap_or_default();
+        for hook in self.hooks.iter().rev() {
+            hook.after_export(spans, duration, &result);
+        }
+        result
+    }
+
+    fn shutdown(&mut self) {
+        self.inner.shutdown()
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::sdk::testing::trace::InMemorySpanExporter;
+    use crate::testing::trace::new_test_export_span_data;
+    use crate::trace::TraceError;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug)]
+    struct Recorder {
+        name: &'static str,
+        calls: Arc<Mutex<Vec<String>>>,
+        fail: bool,
+    }
+
+    impl ExportHook for Recorder {
+        fn before_export(&self, batch: &mut Vec<SpanData>) -> ExportResult {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("before {} {}", self.name, batch.len()));
+            if self.fail {
+                return Err(TraceError::from("injected failure"));
+            }
+            batch.truncate(1);
+            Ok(())
+        }
+
+        fn after_export(&self, spans: usize, _duration: Duration, result: &ExportResult) {
+            self.calls.lock().unwrap().push(format!(
+                "after {} {} {}",
+                self.name,
+                spans,
+                result.is_ok()
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn run_hooks_around_export() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name, fail| Recorder {
+            name,
+            calls: calls.clone(),
+            fail,
+        };
+        let spans = InMemorySpanExporter::new();
+        let mut exporter = ExporterLayer::new(spans.clone())
+            .with_hook(recorder("outer", false))
+            .with_hook(recorder("inner", false));
+
+        let batch = vec![new_test_export_span_data(), new_test_export_span_data()];
+        assert!(exporter.export(batch.clone()).await.is_ok());
+        assert_eq!(spans.get_finished_spans().unwrap().len(), 1);
+        assert_eq!(
+            calls.lock().unwrap().drain(..).collect::<Vec<_>>(),
+            vec![
+                "before outer 2",
+                "before inner 1",
+                "after inner 1 true",
+                "after outer 1 true"
+            ]
+        );
+
+        let mut exporter = exporter.with_hook(recorder("failing", true));
+        assert!(exporter.export(batch).await.is_err());
+        assert_eq!(spans.get_finished_spans().unwrap().len(), 1);
+        assert_eq!(
+            calls.lock().unwrap().drain(..).collect::<Vec<_>>(),
+            vec![
+                "before outer 2",
+                "before inner 1",
+                "before failing 1",
+                "after failing 1 false",
+                "after inner 1 false",
+                "after outer 1 false"
+            ]
+        );
+    }
+}
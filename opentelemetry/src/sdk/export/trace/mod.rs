@@ -1,7 +1,8 @@
 //! Trace exporters
+use std::borrow::Cow;
 use std::fmt::Debug;
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 use async_trait::async_trait;
 #[cfg(feature = "serialize")]
@@ -12,6 +13,10 @@ use crate::{
     trace::{Event, Link, SpanContext, SpanId, SpanKind, StatusCode, TraceError},
 };
 
+pub mod deletion;
+pub mod factory;
+pub(crate) mod json;
+pub mod layer;
 pub mod stdout;
 
 /// Describes the result of an export.
@@ -54,6 +59,17 @@ pub trait SpanExporter: Send + Debug {
     fn shutdown(&mut self) {}
 }
 
+#[async_trait]
+impl SpanExporter for Box<dyn SpanExporter> {
+    async fn export(&mut self, batch: Vec<SpanData>) -> ExportResult {
+        self.as_mut().export(batch).await
+    }
+
+    fn shutdown(&mut self) {
+        self.as_mut().shutdown()
+    }
+}
+
 /// `SpanData` contains all the information collected by a `Span` and can be used
 /// by exporters as a standard input.
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
@@ -66,7 +82,7 @@ pub struct SpanData {
     /// Span kind
     pub span_kind: SpanKind,
     /// Span name
-    pub name: String,
+    pub name: Cow<'static, str>,
     /// Span start time
     pub start_time: SystemTime,
     /// Span end time
@@ -88,6 +104,31 @@ pub struct SpanData {
     pub instrumentation_lib: sdk::InstrumentationLibrary,
 }
 
+impl SpanData {
+    /// The duration of the span, zero if it ended before its start time.
+    ///
+    /// Spans timed by the sdk end at their start time plus the time measured
+    /// by a monotonic clock, so their duration isn't affected by adjustments
+    /// of the system time while they run. Exporters should use this duration
+    /// rather than computing their own from the start and end times.
+    pub fn duration(&self) -> Duration {
+        self.end_time
+            .duration_since(self.start_time)
+            .unwrap_or_default()
+    }
+
+    /// A builder of spans with fixed ids and times, for tests of exporters.
+    ///
+    /// See [`SpanDataBuilder`] for the defaults.
+    ///
+    /// [`SpanDataBuilder`]: crate::sdk::testing::trace::SpanDataBuilder
+    #[cfg(feature = "testing")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+    pub fn builder() -> sdk::testing::trace::SpanDataBuilder {
+        sdk::testing::trace::SpanDataBuilder::default()
+    }
+}
+
 #[cfg(feature = "serialize")]
 #[cfg(test)]
 mod tests {
@@ -111,7 +152,7 @@ mod tests {
 
         let parent_span_id = 1;
         let span_kind = SpanKind::Client;
-        let name = "foo/bar baz 人?!".to_string();
+        let name = "foo/bar baz 人?!".into();
         let start_time = crate::time::now();
         let end_time = crate::time::now();
 
@@ -3,6 +3,11 @@
 //! The stdout [`SpanExporter`] writes debug printed [`Span`]s to its configured
 //! [`Write`] instance. By default it will write to [`Stdout`].
 //!
+//! The debug output is meant to be read by humans. Spans can be written as
+//! newline-delimited JSON instead to be processed by tools like `jq`, or in
+//! the OTLP/JSON encoding to be read by file-based collectors, see
+//! [`Format`].
+//!
 //! [`SpanExporter`]: super::SpanExporter
 //! [`Span`]: crate::trace::Span
 //! [`Write`]: std::io::Write
@@ -27,22 +32,75 @@
 //!     shutdown_tracer_provider(); // sending remaining spans
 //! }
 //! ```
+//!
+//! Writing one JSON object per span:
+//!
+//! ```no_run
+//! use opentelemetry::sdk::export::trace::stdout;
+//!
+//! let tracer = stdout::new_pipeline()
+//!     .with_format(stdout::Format::Json)
+//!     .with_flush_policy(stdout::FlushPolicy::EverySpan)
+//!     .install();
+//! ```
 use crate::{
     global, sdk,
     sdk::export::{
-        trace::{ExportResult, SpanData, SpanExporter},
+        trace::{json, ExportResult, SpanData, SpanExporter},
         ExportError,
     },
-    trace::TracerProvider,
+    trace::{TraceError, TracerProvider},
 };
 use async_trait::async_trait;
 use std::fmt::Debug;
 use std::io::{stdout, Stdout, Write};
 
+/// Output format of the stdout exporter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Format {
+    /// Debug printed spans, pretty printed if enabled.
+    Debug,
+    /// One JSON object per span and line, with the fields of [`SpanData`].
+    Json,
+    /// One OTLP/JSON `ExportTraceServiceRequest` per exported batch and line.
+    OtlpJson,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Format::Debug
+    }
+}
+
+/// When the exporter flushes its writer.
+///
+/// The writer is always flushed when the exporter is shut down.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum FlushPolicy {
+    /// Leave flushing to the writer, e.g. [`Stdout`] flushes every line.
+    ///
+    /// [`Stdout`]: std::io::Stdout
+    Never,
+    /// Flush after every exported batch.
+    EveryBatch,
+    /// Flush after every span written.
+    EverySpan,
+}
+
+impl Default for FlushPolicy {
+    fn default() -> Self {
+        FlushPolicy::EveryBatch
+    }
+}
+
 /// Pipeline builder
 #[derive(Debug)]
 pub struct PipelineBuilder<W: Write> {
     pretty_print: bool,
+    format: Format,
+    flush_policy: FlushPolicy,
     trace_config: Option<sdk::trace::Config>,
     writer: W,
 }
@@ -57,6 +115,8 @@ impl Default for PipelineBuilder<Stdout> {
     fn default() -> Self {
         Self {
             pretty_print: false,
+            format: Format::default(),
+            flush_policy: FlushPolicy::default(),
             trace_config: None,
             writer: stdout(),
         }
@@ -70,6 +130,18 @@ impl<W: Write> PipelineBuilder<W> {
         self
     }
 
+    /// Specify the output format, debug printed spans by default.
+    pub fn with_format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Specify when the writer is flushed, after every batch by default.
+    pub fn with_flush_policy(mut self, flush_policy: FlushPolicy) -> Self {
+        self.flush_policy = flush_policy;
+        self
+    }
+
     /// Assign the SDK trace configuration.
     pub fn with_trace_config(mut self, config: sdk::trace::Config) -> Self {
         self.trace_config = Some(config);
@@ -80,6 +152,8 @@ impl<W: Write> PipelineBuilder<W> {
     pub fn with_writer<T: Write>(self, writer: T) -> PipelineBuilder<T> {
         PipelineBuilder {
             pretty_print: self.pretty_print,
+            format: self.format,
+            flush_policy: self.flush_policy,
             trace_config: self.trace_config,
             writer,
         }
@@ -92,7 +166,9 @@ where
 {
     /// Install the stdout exporter pipeline with the recommended defaults.
     pub fn install(mut self) -> sdk::trace::Tracer {
-        let exporter = Exporter::new(self.writer, self.pretty_print);
+        let exporter = Exporter::new(self.writer, self.pretty_print)
+            .with_format(self.format)
+            .with_flush_policy(self.flush_policy);
 
         let mut provider_builder = sdk::trace::TracerProvider::builder().with_exporter(exporter);
         if let Some(config) = self.trace_config.take() {
@@ -115,6 +191,8 @@ where
 pub struct Exporter<W: Write> {
     writer: W,
     pretty_print: bool,
+    format: Format,
+    flush_policy: FlushPolicy,
 }
 
 impl<W: Write> Exporter<W> {
@@ -123,8 +201,31 @@ impl<W: Write> Exporter<W> {
         Self {
             writer,
             pretty_print,
+            format: Format::default(),
+            flush_policy: FlushPolicy::default(),
         }
     }
+
+    /// Specify the output format.
+    pub fn with_format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Specify when the writer is flushed.
+    pub fn with_flush_policy(mut self, flush_policy: FlushPolicy) -> Self {
+        self.flush_policy = flush_policy;
+        self
+    }
+
+    fn write_line(&mut self, mut line: String) -> Result<(), Error> {
+        line.push('\n');
+        self.writer.write_all(line.as_bytes())?;
+        if self.flush_policy == FlushPolicy::EverySpan {
+            self.writer.flush()?;
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -134,20 +235,40 @@ where
 {
     /// Export spans to stdout
     async fn export(&mut self, batch: Vec<SpanData>) -> ExportResult {
-        for span in batch {
-            if self.pretty_print {
-                self.writer
-                    .write_all(format!("{:#?}\n", span).as_bytes())
-                    .map_err::<Error, _>(Into::into)?;
-            } else {
-                self.writer
-                    .write_all(format!("{:?}\n", span).as_bytes())
-                    .map_err::<Error, _>(Into::into)?;
+        match self.format {
+            Format::Debug if self.pretty_print => {
+                for span in batch {
+                    self.write_line(format!("{:#?}", span))?;
+                }
+            }
+            Format::Debug => {
+                for span in batch {
+                    self.write_line(format!("{:?}", span))?;
+                }
+            }
+            Format::Json => {
+                for span in batch {
+                    self.write_line(json::span_to_json(&span))?;
+                }
+            }
+            Format::OtlpJson => {
+                if !batch.is_empty() {
+                    self.write_line(json::spans_to_otlp_json(&batch))?;
+                }
             }
         }
 
+        if self.flush_policy == FlushPolicy::EveryBatch {
+            self.writer.flush().map_err::<Error, _>(Into::into)?;
+        }
         Ok(())
     }
+
+    fn shutdown(&mut self) {
+        if let Err(err) = self.writer.flush() {
+            global::handle_error(TraceError::from(Error(err)));
+        }
+    }
 }
 
 /// Stdout exporter's error
@@ -2,24 +2,70 @@
 //! See `OTEPS-0083` for details.
 //!
 //! [OTEPS-0083](https://github.com/open-telemetry/oteps/blob/master/text/0083-component.md)
+use crate::KeyValue;
+use std::hash::{Hash, Hasher};
 
 /// InstrumentationLibrary contains information about instrumentation library.
 ///
 /// See `Instrumentation Libraries` for more information.
 ///
 /// [`Instrumentation Libraries`](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/overview.md#instrumentation-libraries)
-#[derive(Debug, Default, Hash, Copy, Clone, PartialEq, Eq)]
+/// Libraries are identified by their name, version and schema url, their
+/// attributes are not part of their identity.
+#[derive(Debug, Default, Clone)]
 #[non_exhaustive]
 pub struct InstrumentationLibrary {
     /// instrumentation library name, cannot be empty
     pub name: &'static str,
     /// instrumentation library version, can be empty
     pub version: Option<&'static str>,
+    /// schema url of the semantic conventions used by the instrumentation
+    /// library, can be empty
+    pub schema_url: Option<&'static str>,
+    /// attributes of the instrumentation library, exported along with the
+    /// telemetry of the library
+    pub attributes: Vec<KeyValue>,
 }
 
 impl InstrumentationLibrary {
     /// Create an InstrumentationLibrary from name and version.
     pub fn new(name: &'static str, version: Option<&'static str>) -> InstrumentationLibrary {
-        InstrumentationLibrary { name, version }
+        InstrumentationLibrary {
+            name,
+            version,
+            schema_url: None,
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Set the schema url of the semantic conventions used by the
+    /// instrumentation library.
+    pub fn with_schema_url(mut self, schema_url: &'static str) -> Self {
+        self.schema_url = Some(schema_url);
+        self
+    }
+
+    /// Set the attributes of the instrumentation library.
+    pub fn with_attributes<T: IntoIterator<Item = KeyValue>>(mut self, attributes: T) -> Self {
+        self.attributes = attributes.into_iter().collect();
+        self
+    }
+}
+
+impl PartialEq for InstrumentationLibrary {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.version == other.version
+            && self.schema_url == other.schema_url
+    }
+}
+
+impl Eq for InstrumentationLibrary {}
+
+impl Hash for InstrumentationLibrary {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.version.hash(state);
+        self.schema_url.hash(state);
     }
 }
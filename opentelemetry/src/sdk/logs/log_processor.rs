@@ -0,0 +1,502 @@
+//! # OpenTelemetry Log Processor Interface
+//!
+//! Log processors receive the records emitted by the loggers of a
+//! [`LoggerProvider`], in the order they were registered, and pass them on to
+//! exporters, either one by one with the [`SimpleLogProcessor`] or in batches
+//! with the [`BatchLogProcessor`].
+//!
+//! [`LoggerProvider`]: crate::sdk::logs::LoggerProvider
+use crate::global;
+use crate::logs::{LogError, LogResult};
+use crate::runtime::Runtime;
+use crate::sdk::batch::{env_var, split_batches, with_timeout, Coalesce};
+use crate::sdk::export::logs::{ExportResult, LogData, LogExporter};
+use futures::{channel::mpsc, channel::oneshot, executor, StreamExt};
+use std::fmt;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Delay interval between two consecutive exports.
+const OTEL_BLRP_SCHEDULE_DELAY: &str = "OTEL_BLRP_SCHEDULE_DELAY";
+/// Default delay interval between two consecutive exports.
+const OTEL_BLRP_SCHEDULE_DELAY_DEFAULT: u64 = 1_000;
+/// Maximum queue size
+const OTEL_BLRP_MAX_QUEUE_SIZE: &str = "OTEL_BLRP_MAX_QUEUE_SIZE";
+/// Default maximum queue size
+const OTEL_BLRP_MAX_QUEUE_SIZE_DEFAULT: usize = 2_048;
+/// Maximum batch size, must be less than or equal to OTEL_BLRP_MAX_QUEUE_SIZE
+const OTEL_BLRP_MAX_EXPORT_BATCH_SIZE: &str = "OTEL_BLRP_MAX_EXPORT_BATCH_SIZE";
+/// Default maximum batch size
+const OTEL_BLRP_MAX_EXPORT_BATCH_SIZE_DEFAULT: usize = 512;
+/// Maximum allowed time to export data.
+const OTEL_BLRP_EXPORT_TIMEOUT: &str = "OTEL_BLRP_EXPORT_TIMEOUT";
+/// Default maximum allowed time to export data.
+const OTEL_BLRP_EXPORT_TIMEOUT_DEFAULT: u64 = 30_000;
+
+/// `LogProcessor` is an interface which allows hooks for emitted log records.
+pub trait LogProcessor: Send + Sync + fmt::Debug {
+    /// Called when a log record is emitted. This method is called
+    /// synchronously on the thread that emitted the record, therefore it
+    /// should not block.
+    fn emit(&self, data: LogData);
+    /// Force the log records lying in the cache to be exported.
+    fn force_flush(&self) -> LogResult<()>;
+    /// Shuts down the processor. Called when SDK is shut down. This is an
+    /// opportunity for processors to do any cleanup required.
+    fn shutdown(&mut self) -> LogResult<()>;
+}
+
+/// A [`LogProcessor`] that exports synchronously when records are emitted.
+///
+/// The simple processor blocks the emitting thread for every export, consider
+/// the [`BatchLogProcessor`] outside of tests and debugging.
+#[derive(Debug)]
+pub struct SimpleLogProcessor {
+    exporter: Mutex<Box<dyn LogExporter>>,
+}
+
+impl SimpleLogProcessor {
+    pub(crate) fn new(exporter: Box<dyn LogExporter>) -> Self {
+        SimpleLogProcessor {
+            exporter: Mutex::new(exporter),
+        }
+    }
+}
+
+impl LogProcessor for SimpleLogProcessor {
+    fn emit(&self, data: LogData) {
+        let result = self
+            .exporter
+            .lock()
+            .map_err(|_| LogError::from("simple log processor mutex poisoned"))
+            .and_then(|mut exporter| executor::block_on(exporter.export(vec![data])));
+
+        if let Err(err) = result {
+            global::handle_error(err);
+        }
+    }
+
+    fn force_flush(&self) -> LogResult<()> {
+        // Ignored since all records are exported as they are emitted.
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> LogResult<()> {
+        match self.exporter.lock() {
+            Ok(mut exporter) => {
+                exporter.shutdown();
+                Ok(())
+            }
+            Err(_) => Err(LogError::from(
+                "When shutting down the SimpleLogProcessor, the exporter's lock has been poisoned",
+            )),
+        }
+    }
+}
+
+/// A [`LogProcessor`] that asynchronously buffers log records and exports
+/// them in batches, every scheduled delay or once a batch is full.
+///
+/// Records emitted while the queue is full are dropped and reported to the
+/// global error handler.
+pub struct BatchLogProcessor {
+    message_sender: Mutex<mpsc::Sender<BatchMessage>>,
+}
+
+impl fmt::Debug for BatchLogProcessor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BatchLogProcessor")
+            .field("message_sender", &self.message_sender)
+            .finish()
+    }
+}
+
+impl LogProcessor for BatchLogProcessor {
+    fn emit(&self, data: LogData) {
+        let result = self
+            .message_sender
+            .lock()
+            .map_err(|_| LogError::from("batch log processor mutex poisoned"))
+            .and_then(|mut sender| {
+                sender
+                    .try_send(BatchMessage::ExportLog(Box::new(data)))
+                    .map_err(LogError::from)
+            });
+
+        if let Err(err) = result {
+            global::handle_error(err);
+        }
+    }
+
+    fn force_flush(&self) -> LogResult<()> {
+        let (res_sender, res_receiver) = oneshot::channel();
+        self.message_sender
+            .lock()
+            .map_err(|_| LogError::from("batch log processor mutex poisoned"))?
+            .try_send(BatchMessage::Flush(Some(res_sender)))?;
+
+        wait_for_result(res_receiver)
+    }
+
+    fn shutdown(&mut self) -> LogResult<()> {
+        let (res_sender, res_receiver) = oneshot::channel();
+        self.message_sender
+            .lock()
+            .map_err(|_| LogError::from("batch log processor mutex poisoned"))?
+            .try_send(BatchMessage::Shutdown(res_sender))?;
+
+        wait_for_result(res_receiver)
+    }
+}
+
+/// Wait for the result of the exports triggered by a flush or shutdown.
+#[cfg(not(target_arch = "wasm32"))]
+fn wait_for_result(result: oneshot::Receiver<ExportResult>) -> LogResult<()> {
+    executor::block_on(result)?
+}
+
+/// The worker shares the only thread of `wasm32` targets, so the exports
+/// can't be waited for. Failed exports are reported by the worker instead.
+#[cfg(target_arch = "wasm32")]
+fn wait_for_result(result: oneshot::Receiver<ExportResult>) -> LogResult<()> {
+    drop(result);
+    Ok(())
+}
+
+/// Send the result of a flush or shutdown to the processor, reporting a failed
+/// export if it no longer waits for it.
+fn send_result(channel: oneshot::Sender<ExportResult>, result: ExportResult) {
+    if let Err(Err(err)) = channel.send(result) {
+        global::handle_error(err);
+    }
+}
+
+/// Messages sent to the worker of a batch log processor.
+#[derive(Debug)]
+enum BatchMessage {
+    /// Buffer a log record, exporting the batch once it is full.
+    ExportLog(Box<LogData>),
+    /// Export all buffered log records, scheduled without a result channel.
+    Flush(Option<oneshot::Sender<ExportResult>>),
+    /// Export all buffered log records and shut the exporter down.
+    Shutdown(oneshot::Sender<ExportResult>),
+}
+
+impl BatchLogProcessor {
+    pub(crate) fn new<R: Runtime>(
+        mut exporter: Box<dyn LogExporter>,
+        runtime: R,
+        config: BatchConfig,
+    ) -> Self {
+        let (message_sender, message_receiver) = mpsc::channel(config.max_queue_size);
+        let worker_runtime = runtime.clone();
+
+        runtime.spawn(Box::pin(async move {
+            // The interval is created on the worker, as some runtimes need to be
+            // running in the thread creating their timers.
+            let ticker = Coalesce::new(worker_runtime.interval(config.scheduled_delay))
+                .map(|_| BatchMessage::Flush(None));
+            let mut logs = Vec::new();
+            let mut messages = Box::pin(futures::stream::select(message_receiver, ticker));
+
+            while let Some(message) = messages.next().await {
+                match message {
+                    BatchMessage::ExportLog(log) => {
+                        logs.push(*log);
+                        if logs.len() >= config.max_export_batch_size {
+                            let result = export_with_timeout(
+                                config.max_export_timeout,
+                                exporter.as_mut(),
+                                &worker_runtime,
+                                logs.split_off(0),
+                            )
+                            .await;
+                            if let Err(err) = result {
+                                global::handle_error(err);
+                            }
+                        }
+                    }
+                    BatchMessage::Flush(res_channel) => {
+                        let result =
+                            export_batches(&config, exporter.as_mut(), &worker_runtime, &mut logs)
+                                .await;
+                        match res_channel {
+                            Some(channel) => send_result(channel, result),
+                            None => {
+                                if let Err(err) = result {
+                                    global::handle_error(err);
+                                }
+                            }
+                        }
+                    }
+                    BatchMessage::Shutdown(channel) => {
+                        let result =
+                            export_batches(&config, exporter.as_mut(), &worker_runtime, &mut logs)
+                                .await;
+                        exporter.shutdown();
+                        send_result(channel, result);
+                        break;
+                    }
+                }
+            }
+        }));
+
+        BatchLogProcessor {
+            message_sender: Mutex::new(message_sender),
+        }
+    }
+
+    /// Create a new batch processor builder
+    pub fn builder<E, R>(exporter: E, runtime: R) -> BatchLogProcessorBuilder<E, R>
+    where
+        E: LogExporter,
+        R: Runtime,
+    {
+        BatchLogProcessorBuilder {
+            exporter,
+            runtime,
+            config: BatchConfig::default(),
+        }
+    }
+}
+
+/// Export all buffered log records in batches of the maximum export batch
+/// size, returning the error of the last failed batch.
+async fn export_batches<R: Runtime>(
+    config: &BatchConfig,
+    exporter: &mut dyn LogExporter,
+    runtime: &R,
+    logs: &mut Vec<LogData>,
+) -> ExportResult {
+    let mut result = Ok(());
+    for batch in split_batches(logs, config.max_export_batch_size) {
+        if let Err(err) =
+            export_with_timeout(config.max_export_timeout, exporter, runtime, batch).await
+        {
+            if let Err(previous) = result {
+                global::handle_error(previous);
+            }
+            result = Err(err);
+        }
+    }
+    result
+}
+
+async fn export_with_timeout<R: Runtime>(
+    time_out: Duration,
+    exporter: &mut dyn LogExporter,
+    runtime: &R,
+    batch: Vec<LogData>,
+) -> ExportResult {
+    if batch.is_empty() {
+        return Ok(());
+    }
+
+    with_timeout(exporter.export(batch), time_out, runtime)
+        .await
+        .unwrap_or(Err(LogError::ExportTimedOut(time_out)))
+}
+
+/// Batch log processor configuration
+#[derive(Clone, Debug)]
+pub struct BatchConfig {
+    /// The maximum queue size to buffer log records for delayed processing.
+    /// If the queue gets full it drops the records. The default value of is
+    /// 2048.
+    max_queue_size: usize,
+
+    /// The delay interval between two consecutive exports. The default value
+    /// is 1 second.
+    scheduled_delay: Duration,
+
+    /// The maximum number of log records to export in a single batch. The
+    /// default value is 512.
+    max_export_batch_size: usize,
+
+    /// The maximum duration to export a batch of data. The default value is
+    /// 30 seconds.
+    max_export_timeout: Duration,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        let mut config = BatchConfig {
+            max_queue_size: OTEL_BLRP_MAX_QUEUE_SIZE_DEFAULT,
+            scheduled_delay: Duration::from_millis(OTEL_BLRP_SCHEDULE_DELAY_DEFAULT),
+            max_export_batch_size: OTEL_BLRP_MAX_EXPORT_BATCH_SIZE_DEFAULT,
+            max_export_timeout: Duration::from_millis(OTEL_BLRP_EXPORT_TIMEOUT_DEFAULT),
+        };
+
+        if let Some(max_queue_size) = env_var(&[OTEL_BLRP_MAX_QUEUE_SIZE]) {
+            config.max_queue_size = max_queue_size;
+        }
+
+        if let Some(scheduled_delay) = env_var(&[OTEL_BLRP_SCHEDULE_DELAY]) {
+            config.scheduled_delay = Duration::from_millis(scheduled_delay);
+        }
+
+        if let Some(max_export_batch_size) = env_var(&[OTEL_BLRP_MAX_EXPORT_BATCH_SIZE]) {
+            config.max_export_batch_size = max_export_batch_size;
+        }
+
+        // max export batch size must be less or equal to max queue size.
+        if config.max_export_batch_size > config.max_queue_size {
+            config.max_export_batch_size = config.max_queue_size;
+        }
+
+        if let Some(max_export_timeout) = env_var(&[OTEL_BLRP_EXPORT_TIMEOUT]) {
+            config.max_export_timeout = Duration::from_millis(max_export_timeout);
+        }
+
+        config
+    }
+}
+
+/// A builder for creating [`BatchLogProcessor`] instances.
+#[derive(Debug)]
+pub struct BatchLogProcessorBuilder<E, R> {
+    exporter: E,
+    runtime: R,
+    config: BatchConfig,
+}
+
+impl<E, R> BatchLogProcessorBuilder<E, R>
+where
+    E: LogExporter + 'static,
+    R: Runtime,
+{
+    /// Set max queue size for batches
+    pub fn with_max_queue_size(self, size: usize) -> Self {
+        let mut config = self.config;
+        config.max_queue_size = size;
+
+        BatchLogProcessorBuilder { config, ..self }
+    }
+
+    /// Set scheduled delay for batches
+    pub fn with_scheduled_delay(self, delay: Duration) -> Self {
+        let mut config = self.config;
+        config.scheduled_delay = delay;
+
+        BatchLogProcessorBuilder { config, ..self }
+    }
+
+    /// Set max timeout for exporting.
+    pub fn with_max_timeout(self, timeout: Duration) -> Self {
+        let mut config = self.config;
+        config.max_export_timeout = timeout;
+
+        BatchLogProcessorBuilder { config, ..self }
+    }
+
+    /// Set max export size for batches, should always less than or equals to
+    /// max queue size.
+    ///
+    /// If input is larger than max queue size, will lower it to be equal to
+    /// max queue size
+    pub fn with_max_export_batch_size(self, size: usize) -> Self {
+        let mut config = self.config;
+        config.max_export_batch_size = size.min(config.max_queue_size);
+
+        BatchLogProcessorBuilder { config, ..self }
+    }
+
+    /// Build a batch processor
+    pub fn build(self) -> BatchLogProcessor {
+        BatchLogProcessor::new(Box::new(self.exporter), self.runtime, self.config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logs::LogRecord;
+    use crate::sdk::{InstrumentationLibrary, Resource};
+    use crate::util::tokio_interval_stream;
+    use async_trait::async_trait;
+    use futures::future::BoxFuture;
+    use std::sync::Arc;
+
+    #[derive(Debug, Default)]
+    struct BatchesExporter {
+        batches: Arc<Mutex<Vec<usize>>>,
+        shut_down: Arc<Mutex<bool>>,
+    }
+
+    #[async_trait]
+    impl LogExporter for BatchesExporter {
+        async fn export(&mut self, batch: Vec<LogData>) -> ExportResult {
+            self.batches.lock().unwrap().push(batch.len());
+            Ok(())
+        }
+
+        fn shutdown(&mut self) {
+            *self.shut_down.lock().unwrap() = true;
+        }
+    }
+
+    /// Runs tasks on the blocking pool, as the processor blocks the test thread
+    /// while flushing.
+    #[derive(Clone, Debug)]
+    struct SpawnBlocking;
+
+    impl Runtime for SpawnBlocking {
+        type Interval = tokio_stream::wrappers::IntervalStream;
+        type Delay = tokio::time::Sleep;
+
+        fn interval(&self, duration: Duration) -> Self::Interval {
+            tokio_interval_stream(duration)
+        }
+
+        fn spawn(&self, future: BoxFuture<'static, ()>) {
+            tokio::task::spawn_blocking(|| executor::block_on(future));
+        }
+
+        fn delay(&self, duration: Duration) -> Self::Delay {
+            tokio::time::sleep(duration)
+        }
+    }
+
+    fn log_data() -> LogData {
+        LogData {
+            record: LogRecord::builder().with_body("test".into()).build(),
+            resource: Arc::new(Resource::empty()),
+            instrumentation_lib: InstrumentationLibrary::new("test", None),
+        }
+    }
+
+    #[tokio::test]
+    async fn batch_log_processor_exports_full_batches_and_flushes_the_rest() {
+        let exporter = BatchesExporter::default();
+        let (batches, shut_down) = (exporter.batches.clone(), exporter.shut_down.clone());
+        let mut processor = BatchLogProcessor::builder(exporter, SpawnBlocking)
+            // only export via full batches and force_flush
+            .with_scheduled_delay(Duration::from_secs(60 * 60 * 24))
+            .with_max_export_batch_size(2)
+            .build();
+        tokio::time::sleep(Duration::from_millis(100)).await; // skip the first tick
+
+        for _ in 0..5 {
+            processor.emit(log_data());
+        }
+        assert!(processor.force_flush().is_ok());
+        assert_eq!(*batches.lock().unwrap(), vec![2, 2, 1]);
+
+        assert!(processor.shutdown().is_ok());
+        assert!(*shut_down.lock().unwrap());
+    }
+
+    #[test]
+    fn simple_log_processor_exports_every_record() {
+        let exporter = BatchesExporter::default();
+        let (batches, shut_down) = (exporter.batches.clone(), exporter.shut_down.clone());
+        let mut processor = SimpleLogProcessor::new(Box::new(exporter));
+
+        processor.emit(log_data());
+        processor.emit(log_data());
+        assert_eq!(*batches.lock().unwrap(), vec![1, 1]);
+
+        assert!(processor.shutdown().is_ok());
+        assert!(*shut_down.lock().unwrap());
+    }
+}
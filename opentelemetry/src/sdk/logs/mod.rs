@@ -0,0 +1,41 @@
+//! # OpenTelemetry Logs SDK
+//!
+//! The logs SDK implements the [logs API] with a [`LoggerProvider`], whose
+//! loggers pass the emitted records with the provider's resource to its
+//! [`LogProcessor`]s, and those on to [`LogExporter`]s.
+//!
+//! ```
+//! use opentelemetry::logs::{LogRecord, Logger, LoggerProvider as _, Severity};
+//! use opentelemetry::sdk::{logs::LoggerProvider, Resource};
+//! use opentelemetry::KeyValue;
+//! # use opentelemetry::sdk::export::logs::{ExportResult, LogData, LogExporter};
+//! # #[derive(Debug)]
+//! # struct MyExporter;
+//! # #[async_trait::async_trait]
+//! # impl LogExporter for MyExporter {
+//! #     async fn export(&mut self, _batch: Vec<LogData>) -> ExportResult { Ok(()) }
+//! # }
+//!
+//! let provider = LoggerProvider::builder()
+//!     .with_simple_exporter(MyExporter)
+//!     .with_resource(Resource::new(vec![KeyValue::new("service.name", "users")]))
+//!     .build();
+//!
+//! let logger = provider.get_logger("my-appender", None);
+//! logger.emit(
+//!     LogRecord::builder()
+//!         .with_severity_number(Severity::Error)
+//!         .with_body("connection refused".into())
+//!         .build(),
+//! );
+//! ```
+//!
+//! [logs API]: crate::logs
+//! [`LogExporter`]: crate::sdk::export::logs::LogExporter
+mod log_processor;
+mod provider;
+
+pub use log_processor::{
+    BatchConfig, BatchLogProcessor, BatchLogProcessorBuilder, LogProcessor, SimpleLogProcessor,
+};
+pub use provider::{Builder, Logger, LoggerProvider};
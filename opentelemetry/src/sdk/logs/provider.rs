@@ -0,0 +1,313 @@
+//! # Logger Provider SDK
+//!
+//! New [`Logger`] instances are created through a [`LoggerProvider`], which
+//! holds the log processors and the resource shared by all of its loggers.
+//! Log processors are shut down once the last clone of the provider is
+//! dropped.
+use crate::{
+    global,
+    logs::{LogRecord, LogResult, TraceContext},
+    sdk::{
+        self,
+        export::logs::{LogData, LogExporter},
+        logs::{BatchLogProcessor, LogProcessor, SimpleLogProcessor},
+    },
+    trace::TraceContextExt,
+    Context,
+};
+use std::sync::{Arc, Weak};
+
+/// Default logger name if empty string is provided.
+const DEFAULT_COMPONENT_NAME: &str = "rust.opentelemetry.io/sdk/logger";
+
+/// LoggerProvider inner type
+#[derive(Debug)]
+pub(crate) struct LoggerProviderInner {
+    processors: Vec<Box<dyn LogProcessor>>,
+    resource: Arc<sdk::Resource>,
+}
+
+impl Drop for LoggerProviderInner {
+    fn drop(&mut self) {
+        for processor in &mut self.processors {
+            if let Err(err) = processor.shutdown() {
+                global::handle_error(err);
+            }
+        }
+    }
+}
+
+/// Creator of named [`Logger`] instances.
+#[derive(Clone, Debug)]
+pub struct LoggerProvider {
+    inner: Arc<LoggerProviderInner>,
+}
+
+impl Default for LoggerProvider {
+    fn default() -> Self {
+        LoggerProvider::builder().build()
+    }
+}
+
+impl LoggerProvider {
+    /// Create a new `LoggerProvider` builder.
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Log processors associated with this provider
+    pub fn log_processors(&self) -> &Vec<Box<dyn LogProcessor>> {
+        &self.inner.processors
+    }
+
+    /// The resource log records are associated with.
+    pub fn resource(&self) -> Arc<sdk::Resource> {
+        self.inner.resource.clone()
+    }
+
+    /// Create a logger for an instrumentation library following the semantic
+    /// conventions described by `schema_url`.
+    pub fn versioned_logger(
+        &self,
+        name: &'static str,
+        version: Option<&'static str>,
+        schema_url: Option<&'static str>,
+    ) -> Logger {
+        // Use default value if name is invalid empty string
+        let component_name = if name.is_empty() {
+            DEFAULT_COMPONENT_NAME
+        } else {
+            name
+        };
+        let mut instrumentation_lib = sdk::InstrumentationLibrary::new(component_name, version);
+        instrumentation_lib.schema_url = schema_url;
+
+        Logger {
+            instrumentation_lib,
+            provider: Arc::downgrade(&self.inner),
+        }
+    }
+
+    /// Force flush all remaining log records of the log processors, returning
+    /// the result of each processor.
+    pub fn force_flush(&self) -> Vec<LogResult<()>> {
+        self.log_processors()
+            .iter()
+            .map(|processor| processor.force_flush())
+            .collect()
+    }
+}
+
+impl crate::logs::LoggerProvider for LoggerProvider {
+    /// This implementation of `LoggerProvider` produces `Logger` instances.
+    type Logger = Logger;
+
+    fn get_logger(&self, name: &'static str, version: Option<&'static str>) -> Self::Logger {
+        self.versioned_logger(name, version, None)
+    }
+}
+
+/// Builder for provider attributes.
+#[derive(Default, Debug)]
+pub struct Builder {
+    processors: Vec<Box<dyn LogProcessor>>,
+    resource: Option<sdk::Resource>,
+}
+
+impl Builder {
+    /// The `LogExporter` that this provider should export to as soon as log
+    /// records are emitted.
+    pub fn with_simple_exporter<T: LogExporter + 'static>(self, exporter: T) -> Self {
+        self.with_log_processor(SimpleLogProcessor::new(Box::new(exporter)))
+    }
+
+    /// The `BatchLogProcessor` that this provider should use.
+    pub fn with_batch_exporter(self, processor: BatchLogProcessor) -> Self {
+        self.with_log_processor(processor)
+    }
+
+    /// Add a configured `LogExporter`, exporting in batches.
+    #[cfg(feature = "rt-tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rt-tokio")))]
+    pub fn with_exporter<T: LogExporter + 'static>(self, exporter: T) -> Self {
+        let batch = BatchLogProcessor::builder(exporter, crate::runtime::Tokio);
+        self.with_batch_exporter(batch.build())
+    }
+
+    /// Add a configured `LogExporter`, exporting in batches.
+    #[cfg(all(feature = "rt-tokio-current-thread", not(feature = "rt-tokio")))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rt-tokio-current-thread")))]
+    pub fn with_exporter<T: LogExporter + 'static>(self, exporter: T) -> Self {
+        let batch = BatchLogProcessor::builder(exporter, crate::runtime::TokioCurrentThread);
+        self.with_batch_exporter(batch.build())
+    }
+
+    /// Add a configured `LogExporter`, exporting in batches.
+    #[cfg(all(
+        feature = "rt-async-std",
+        not(feature = "rt-tokio"),
+        not(feature = "rt-tokio-current-thread")
+    ))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rt-async-std")))]
+    pub fn with_exporter<T: LogExporter + 'static>(self, exporter: T) -> Self {
+        let batch = BatchLogProcessor::builder(exporter, crate::runtime::AsyncStd);
+        self.with_batch_exporter(batch.build())
+    }
+
+    /// Add a `LogProcessor`, called after the ones added before.
+    pub fn with_log_processor<T: LogProcessor + 'static>(self, processor: T) -> Self {
+        let mut processors = self.processors;
+        processors.push(Box::new(processor));
+
+        Builder { processors, ..self }
+    }
+
+    /// The resource log records are associated with.
+    pub fn with_resource(self, resource: sdk::Resource) -> Self {
+        Builder {
+            resource: Some(resource),
+            ..self
+        }
+    }
+
+    /// Create a new provider from this configuration.
+    pub fn build(self) -> LoggerProvider {
+        LoggerProvider {
+            inner: Arc::new(LoggerProviderInner {
+                processors: self.processors,
+                resource: Arc::new(self.resource.unwrap_or_default()),
+            }),
+        }
+    }
+}
+
+/// A [`Logger`] passing the log records it emits to the log processors of
+/// its provider.
+///
+/// [`Logger`]: crate::logs::Logger
+#[derive(Debug)]
+pub struct Logger {
+    instrumentation_lib: sdk::InstrumentationLibrary,
+    provider: Weak<LoggerProviderInner>,
+}
+
+impl Logger {
+    /// The instrumentation library of this logger.
+    pub fn instrumentation_library(&self) -> &sdk::InstrumentationLibrary {
+        &self.instrumentation_lib
+    }
+}
+
+impl crate::logs::Logger for Logger {
+    /// Emit the record to the processors of the provider, unless the provider
+    /// was dropped already.
+    fn emit(&self, mut record: LogRecord) {
+        let provider = match self.provider.upgrade() {
+            Some(provider) => provider,
+            None => return,
+        };
+
+        if record.trace_context.is_none() {
+            let cx = Context::current();
+            if cx.has_active_span() {
+                record.trace_context = Some(TraceContext::from(cx.span().span_context()));
+            }
+        }
+        if record.observed_timestamp.is_none() {
+            record.observed_timestamp = Some(crate::time::now());
+        }
+
+        let data = LogData {
+            record,
+            resource: provider.resource.clone(),
+            instrumentation_lib: self.instrumentation_lib.clone(),
+        };
+        if let Some((last, others)) = provider.processors.split_last() {
+            for processor in others {
+                processor.emit(data.clone());
+            }
+            last.emit(data);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logs::{Logger as _, LoggerProvider as _, Severity};
+    use crate::sdk::export::logs::ExportResult;
+    use crate::testing::trace::TestSpan;
+    use crate::trace::{SpanContext, SpanId, TraceId, TraceState, TRACE_FLAG_SAMPLED};
+    use crate::KeyValue;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    #[derive(Clone, Debug, Default)]
+    struct InMemoryExporter(Arc<Mutex<Vec<LogData>>>);
+
+    #[async_trait]
+    impl LogExporter for InMemoryExporter {
+        async fn export(&mut self, batch: Vec<LogData>) -> ExportResult {
+            self.0.lock().unwrap().extend(batch);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn emit_with_resource_and_active_span() {
+        let exporter = InMemoryExporter::default();
+        let provider = LoggerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .with_resource(sdk::Resource::new(vec![KeyValue::new(
+                "service.name",
+                "users",
+            )]))
+            .build();
+        let logger = provider.get_logger("appender", Some("1.0"));
+
+        let span_context = SpanContext::new(
+            TraceId::from_u128(1),
+            SpanId::from_u64(2),
+            TRACE_FLAG_SAMPLED,
+            false,
+            TraceState::default(),
+        );
+        {
+            let _guard = Context::new()
+                .with_span(TestSpan(span_context.clone()))
+                .attach();
+            logger.emit(
+                LogRecord::builder()
+                    .with_severity_number(Severity::Info)
+                    .with_body("in span".into())
+                    .build(),
+            );
+        }
+        logger.emit(LogRecord::builder().with_body("no span".into()).build());
+
+        let exported = exporter.0.lock().unwrap();
+        assert_eq!(exported.len(), 2);
+        assert_eq!(
+            exported[0].record.trace_context,
+            Some(TraceContext::from(&span_context))
+        );
+        assert!(exported[0].record.observed_timestamp.is_some());
+        assert_eq!(exported[0].resource, provider.resource());
+        assert_eq!(exported[0].instrumentation_lib.name, "appender");
+        assert_eq!(exported[0].instrumentation_lib.version, Some("1.0"));
+        assert_eq!(exported[1].record.trace_context, None);
+    }
+
+    #[test]
+    fn emit_after_provider_dropped() {
+        let exporter = InMemoryExporter::default();
+        let provider = LoggerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let logger = provider.get_logger("appender", None);
+        drop(provider);
+
+        logger.emit(LogRecord::builder().build());
+        assert!(exporter.0.lock().unwrap().is_empty());
+    }
+}
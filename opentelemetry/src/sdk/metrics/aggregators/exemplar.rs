@@ -0,0 +1,235 @@
+use crate::metrics::Number;
+use crate::sdk::{export::metrics::Exemplar, Clock};
+use crate::{Context, KeyValue};
+use rand::{seq::index, Rng};
+use std::mem;
+
+/// Selects the measurements kept as exemplars by an aggregator.
+///
+/// Only measurements recorded while a sampled span is active are kept.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExemplarReservoir {
+    /// Keep a uniform random sample of at most the given number of
+    /// measurements.
+    FixedSize(usize),
+    /// Keep the last measurement of each bucket of a histogram. Aggregators
+    /// without buckets keep the last measurement.
+    AlignedHistogram,
+}
+
+/// The exemplars kept by an aggregator during one collection interval.
+#[derive(Debug)]
+pub(crate) struct Reservoir {
+    config: ExemplarReservoir,
+    slots: Vec<Option<Exemplar>>,
+    /// The number of measurements offered, sampled or not.
+    seen: u64,
+}
+
+impl Reservoir {
+    /// Create an empty reservoir for an aggregator with `buckets` buckets.
+    pub(crate) fn new(config: &ExemplarReservoir, buckets: usize) -> Self {
+        let size = match config {
+            ExemplarReservoir::FixedSize(size) => *size,
+            ExemplarReservoir::AlignedHistogram => buckets,
+        };
+        Reservoir {
+            config: config.clone(),
+            slots: vec![None; size],
+            seen: 0,
+        }
+    }
+
+    /// Offer a measurement falling in the bucket `bucket` of the aggregator,
+    /// timestamped with `clock` if kept.
+    pub(crate) fn offer(
+        &mut self,
+        number: &Number,
+        bucket: usize,
+        cx: &Context,
+        filtered_attributes: &[KeyValue],
+        clock: &dyn Clock,
+    ) {
+        let (trace_id, span_id) = match sampled_span(cx) {
+            Some(ids) => ids,
+            None => return,
+        };
+        let slot = match self.config {
+            ExemplarReservoir::FixedSize(size) => {
+                self.seen += 1;
+                if self.seen <= size as u64 {
+                    self.seen as usize - 1
+                } else {
+                    rand::thread_rng().gen_range(0..self.seen) as usize
+                }
+            }
+            ExemplarReservoir::AlignedHistogram => bucket,
+        };
+        if let Some(slot) = self.slots.get_mut(slot) {
+            *slot = Some(Exemplar::new(
+                number.clone(),
+                clock.now(),
+                trace_id,
+                span_id,
+                filtered_attributes.to_vec(),
+            ));
+        }
+    }
+
+    /// The exemplars kept so far.
+    pub(crate) fn exemplars(&self) -> Vec<Exemplar> {
+        self.slots.iter().flatten().cloned().collect()
+    }
+
+    /// Move the kept exemplars into `other`, emptying this reservoir.
+    pub(crate) fn move_into(&mut self, other: &mut Reservoir) {
+        let empty = Reservoir::new(&self.config, self.slots.len());
+        *other = mem::replace(self, empty);
+    }
+
+    /// Combine the exemplars of `other` into this reservoir.
+    pub(crate) fn merge(&mut self, other: &Reservoir) {
+        match self.config {
+            ExemplarReservoir::FixedSize(size) => {
+                let mut exemplars = self.exemplars();
+                exemplars.extend(other.exemplars());
+                if exemplars.len() > size {
+                    let mut rng = rand::thread_rng();
+                    let sampled = index::sample(&mut rng, exemplars.len(), size);
+                    exemplars = sampled.iter().map(|i| exemplars[i].clone()).collect();
+                }
+                let mut slots: Vec<_> = exemplars.into_iter().map(Some).collect();
+                slots.resize(size, None);
+                self.slots = slots;
+                self.seen += other.seen;
+            }
+            ExemplarReservoir::AlignedHistogram => {
+                for (slot, other) in self.slots.iter_mut().zip(other.slots.iter()) {
+                    match (&slot, other) {
+                        (Some(kept), Some(other)) if kept.time() < other.time() => {
+                            *slot = Some(other.clone())
+                        }
+                        (None, Some(other)) => *slot = Some(other.clone()),
+                        _ => (),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The trace and span ids of the span active in `cx`, if it is sampled.
+#[cfg(feature = "trace")]
+fn sampled_span(cx: &Context) -> Option<([u8; 16], [u8; 8])> {
+    use crate::trace::TraceContextExt;
+
+    let span_context = cx.span().span_context();
+    if span_context.is_valid() && span_context.is_sampled() {
+        Some((
+            span_context.trace_id().to_byte_array(),
+            span_context.span_id().to_byte_array(),
+        ))
+    } else {
+        None
+    }
+}
+
+#[cfg(not(feature = "trace"))]
+fn sampled_span(_cx: &Context) -> Option<([u8; 16], [u8; 8])> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdk::SystemClock;
+    use crate::testing::trace::TestSpan;
+    use crate::trace::{
+        SpanContext, SpanId, TraceContextExt, TraceId, TraceState, TRACE_FLAG_NOT_SAMPLED,
+        TRACE_FLAG_SAMPLED,
+    };
+
+    fn context(span_id: u64, trace_flags: u8) -> Context {
+        Context::new().with_span(TestSpan(SpanContext::new(
+            TraceId::from_u128(1),
+            SpanId::from_u64(span_id),
+            trace_flags,
+            false,
+            TraceState::default(),
+        )))
+    }
+
+    fn values(reservoir: &Reservoir) -> Vec<u64> {
+        reservoir
+            .exemplars()
+            .iter()
+            .map(|exemplar| exemplar.value().to_u64(&crate::metrics::NumberKind::U64))
+            .collect()
+    }
+
+    #[test]
+    fn keep_measurements_of_sampled_spans() {
+        let mut reservoir = Reservoir::new(&ExemplarReservoir::FixedSize(4), 1);
+        let attributes = [KeyValue::new("user", "1")];
+        reservoir.offer(&1u64.into(), 0, &Context::new(), &attributes, &SystemClock);
+        reservoir.offer(
+            &2u64.into(),
+            0,
+            &context(2, TRACE_FLAG_NOT_SAMPLED),
+            &attributes,
+            &SystemClock,
+        );
+        reservoir.offer(
+            &3u64.into(),
+            0,
+            &context(3, TRACE_FLAG_SAMPLED),
+            &attributes,
+            &SystemClock,
+        );
+
+        let exemplars = reservoir.exemplars();
+        assert_eq!(exemplars.len(), 1);
+        assert_eq!(
+            exemplars[0].trace_id(),
+            TraceId::from_u128(1).to_byte_array()
+        );
+        assert_eq!(exemplars[0].span_id(), SpanId::from_u64(3).to_byte_array());
+        assert_eq!(exemplars[0].filtered_attributes(), &attributes);
+    }
+
+    #[test]
+    fn sample_fixed_size() {
+        let cx = context(1, TRACE_FLAG_SAMPLED);
+        let mut reservoir = Reservoir::new(&ExemplarReservoir::FixedSize(3), 1);
+        for value in 0..100u64 {
+            reservoir.offer(&value.into(), 0, &cx, &[], &SystemClock);
+        }
+        assert_eq!(reservoir.exemplars().len(), 3);
+
+        let mut other = Reservoir::new(&ExemplarReservoir::FixedSize(3), 1);
+        other.offer(&100u64.into(), 0, &cx, &[], &SystemClock);
+        reservoir.merge(&other);
+        assert_eq!(reservoir.exemplars().len(), 3);
+        assert_eq!(reservoir.seen, 101);
+
+        let mut checkpoint = Reservoir::new(&ExemplarReservoir::FixedSize(3), 1);
+        reservoir.move_into(&mut checkpoint);
+        assert!(reservoir.exemplars().is_empty());
+        assert_eq!(checkpoint.exemplars().len(), 3);
+    }
+
+    #[test]
+    fn keep_last_measurement_of_buckets() {
+        let cx = context(1, TRACE_FLAG_SAMPLED);
+        let mut reservoir = Reservoir::new(&ExemplarReservoir::AlignedHistogram, 3);
+        reservoir.offer(&1u64.into(), 0, &cx, &[], &SystemClock);
+        reservoir.offer(&2u64.into(), 0, &cx, &[], &SystemClock);
+        reservoir.offer(&20u64.into(), 2, &cx, &[], &SystemClock);
+        assert_eq!(values(&reservoir), vec![2, 20]);
+
+        let mut other = Reservoir::new(&ExemplarReservoir::AlignedHistogram, 3);
+        other.offer(&10u64.into(), 1, &cx, &[], &SystemClock);
+        reservoir.merge(&other);
+        assert_eq!(values(&reservoir), vec![2, 10, 20]);
+    }
+}
@@ -0,0 +1,484 @@
+use crate::metrics::{AtomicNumber, Descriptor, MetricsError, Number, NumberKind, Result};
+use crate::sdk::export::metrics::{
+    Aggregator, Count, ExponentialBuckets, ExponentialHistogram, Max, Min, Sum,
+};
+use std::any::Any;
+use std::cmp::Ordering;
+use std::sync::{Arc, Mutex};
+
+/// Smallest scale, at which all finite `f64` values map to 3 buckets.
+const MIN_SCALE: i8 = -10;
+/// Largest scale, at which all finite `f64` values map to `i32` indexes.
+const MAX_SCALE: i8 = 20;
+
+const SIGNIFICAND_WIDTH: u32 = 52;
+const SIGNIFICAND_MASK: u64 = (1 << SIGNIFICAND_WIDTH) - 1;
+const EXPONENT_MASK: u64 = 0x7ff << SIGNIFICAND_WIDTH;
+const EXPONENT_BIAS: i32 = 1023;
+
+/// Create a new exponential histogram for the given descriptor
+pub fn exponential_histogram(
+    descriptor: &Descriptor,
+    config: &ExponentialHistogramConfig,
+) -> ExponentialHistogramAggregator {
+    let kind = descriptor.number_kind().clone();
+    ExponentialHistogramAggregator {
+        inner: Mutex::new(State::empty(config.max_scale, &kind)),
+        config: config.clone(),
+        kind,
+    }
+}
+
+/// Exponential histogram configuration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExponentialHistogramConfig {
+    max_size: u32,
+    max_scale: i8,
+}
+
+impl ExponentialHistogramConfig {
+    /// Create a new config for histograms with at most `max_size` positive
+    /// and `max_size` negative buckets, starting at `max_scale`.
+    ///
+    /// The size is at least 2 and the scale between -10 and 20.
+    pub fn new(max_size: u32, max_scale: i8) -> Self {
+        let max_scale = max_scale.min(MAX_SCALE);
+        ExponentialHistogramConfig {
+            max_size: max_size.max(2),
+            max_scale: max_scale.max(MIN_SCALE),
+        }
+    }
+}
+
+impl Default for ExponentialHistogramConfig {
+    /// 160 buckets starting at scale 20, as recommended by the specification.
+    fn default() -> Self {
+        ExponentialHistogramConfig::new(160, MAX_SCALE)
+    }
+}
+
+/// This aggregator counts events in buckets whose boundaries grow
+/// exponentially, by a factor of `2^(2^-scale)`. The scale starts at the
+/// configured maximum and is lowered whenever the recorded values need more
+/// buckets than configured, so the buckets adapt to the range of the values
+/// without choosing boundaries upfront. It also calculates the sum, count,
+/// min and max of all events.
+#[derive(Debug)]
+pub struct ExponentialHistogramAggregator {
+    inner: Mutex<State>,
+    config: ExponentialHistogramConfig,
+    kind: NumberKind,
+}
+
+#[derive(Clone, Debug)]
+struct State {
+    scale: i8,
+    count: u64,
+    zero_count: u64,
+    sum: AtomicNumber,
+    min: AtomicNumber,
+    max: AtomicNumber,
+    positive: Store,
+    negative: Store,
+}
+
+impl State {
+    fn empty(scale: i8, kind: &NumberKind) -> Self {
+        State {
+            scale,
+            count: 0,
+            zero_count: 0,
+            sum: kind.zero().to_atomic(),
+            min: kind.max().to_atomic(),
+            max: kind.min().to_atomic(),
+            positive: Store::default(),
+            negative: Store::default(),
+        }
+    }
+
+    /// Lower the scale by `change`, merging neighbouring buckets.
+    fn downscale(&mut self, change: i8) {
+        if change > 0 {
+            self.positive.downscale(change);
+            self.negative.downscale(change);
+            self.scale -= change;
+        }
+    }
+}
+
+/// Counts of consecutive buckets starting at `offset`.
+#[derive(Clone, Debug, Default)]
+struct Store {
+    offset: i32,
+    counts: Vec<u64>,
+}
+
+impl Store {
+    /// The lowest and highest index with a count, if any.
+    fn range(&self) -> Option<(i32, i32)> {
+        if self.counts.is_empty() {
+            None
+        } else {
+            Some((self.offset, self.offset + self.counts.len() as i32 - 1))
+        }
+    }
+
+    fn increment(&mut self, index: i32, count: u64) {
+        match self.range() {
+            None => {
+                self.offset = index;
+                self.counts.push(count);
+                return;
+            }
+            Some((low, _)) if index < low => {
+                let prepended = (low - index) as usize;
+                self.counts.splice(0..0, vec![0; prepended]);
+                self.offset = index;
+            }
+            Some((_, high)) if index > high => {
+                let new_len = (index - self.offset + 1) as usize;
+                self.counts.resize(new_len, 0);
+            }
+            _ => (),
+        }
+        self.counts[(index - self.offset) as usize] += count;
+    }
+
+    fn downscale(&mut self, change: i8) {
+        if let Some((low, high)) = self.range() {
+            let offset = low >> change;
+            let mut counts = vec![0; ((high >> change) - offset + 1) as usize];
+            for (index, count) in (low..=high).zip(self.counts.iter()) {
+                counts[((index >> change) - offset) as usize] += count;
+            }
+            self.offset = offset;
+            self.counts = counts;
+        }
+    }
+
+    fn to_buckets(&self) -> ExponentialBuckets {
+        ExponentialBuckets::new(self.offset, self.counts.clone())
+    }
+}
+
+/// The scale change needed to fit the indexes `low..=high` in `max_size`
+/// buckets.
+fn scale_change(low: i32, high: i32, max_size: u32) -> i8 {
+    let mut change = 0;
+    while i64::from(high >> change) - i64::from(low >> change) + 1 > i64::from(max_size) {
+        change += 1;
+    }
+    change
+}
+
+/// The range covering both ranges, if any.
+fn union(a: Option<(i32, i32)>, b: Option<(i32, i32)>) -> Option<(i32, i32)> {
+    match (a, b) {
+        (Some((a_low, a_high)), Some((b_low, b_high))) => {
+            Some((a_low.min(b_low), a_high.max(b_high)))
+        }
+        (range, None) | (None, range) => range,
+    }
+}
+
+/// The index of the bucket of `value` at `scale`, for positive finite values.
+///
+/// Values equal to a power of the base are in the bucket they close, as
+/// buckets include their upper boundary.
+fn map_to_index(value: f64, scale: i8) -> i32 {
+    let bits = value.to_bits();
+    let biased_exponent = ((bits & EXPONENT_MASK) >> SIGNIFICAND_WIDTH) as i32;
+    // subnormal values have no implicit leading bit, use the logarithm
+    if biased_exponent != 0 {
+        let exponent = biased_exponent - EXPONENT_BIAS;
+        let power_of_two = bits & SIGNIFICAND_MASK == 0;
+        if scale <= 0 {
+            let exponent = if power_of_two { exponent - 1 } else { exponent };
+            return exponent >> -scale;
+        } else if power_of_two {
+            return (exponent << scale) - 1;
+        }
+    }
+    (value.log2() * 2f64.powi(i32::from(scale))).ceil() as i32 - 1
+}
+
+impl ExponentialHistogramAggregator {
+    fn state(&self) -> Result<std::sync::MutexGuard<'_, State>> {
+        self.inner.lock().map_err(From::from)
+    }
+}
+
+impl Sum for ExponentialHistogramAggregator {
+    fn sum(&self) -> Result<Number> {
+        self.state().map(|state| state.sum.load())
+    }
+}
+
+impl Count for ExponentialHistogramAggregator {
+    fn count(&self) -> Result<u64> {
+        self.state().map(|state| state.count)
+    }
+}
+
+impl Min for ExponentialHistogramAggregator {
+    fn min(&self) -> Result<Number> {
+        self.state().map(|state| {
+            if state.count == 0 {
+                self.kind.zero()
+            } else {
+                state.min.load()
+            }
+        })
+    }
+}
+
+impl Max for ExponentialHistogramAggregator {
+    fn max(&self) -> Result<Number> {
+        self.state().map(|state| {
+            if state.count == 0 {
+                self.kind.zero()
+            } else {
+                state.max.load()
+            }
+        })
+    }
+}
+
+impl ExponentialHistogram for ExponentialHistogramAggregator {
+    fn scale(&self) -> Result<i8> {
+        self.state().map(|state| state.scale)
+    }
+
+    fn zero_count(&self) -> Result<u64> {
+        self.state().map(|state| state.zero_count)
+    }
+
+    fn positive(&self) -> Result<ExponentialBuckets> {
+        self.state().map(|state| state.positive.to_buckets())
+    }
+
+    fn negative(&self) -> Result<ExponentialBuckets> {
+        self.state().map(|state| state.negative.to_buckets())
+    }
+}
+
+impl Aggregator for ExponentialHistogramAggregator {
+    fn update(&self, number: &Number, descriptor: &Descriptor) -> Result<()> {
+        let kind = descriptor.number_kind();
+        let value = number.to_f64(kind);
+        if !value.is_finite() {
+            return Ok(());
+        }
+
+        let mut state = self.state()?;
+        state.count += 1;
+        state.sum.fetch_add(kind, number);
+        if number.partial_cmp(kind, &state.min.load()) == Some(Ordering::Less) {
+            state.min.store(number);
+        }
+        if number.partial_cmp(kind, &state.max.load()) == Some(Ordering::Greater) {
+            state.max.store(number);
+        }
+        if value == 0.0 {
+            state.zero_count += 1;
+            return Ok(());
+        }
+
+        let mut index = map_to_index(value.abs(), state.scale);
+        let store = if value > 0.0 {
+            &state.positive
+        } else {
+            &state.negative
+        };
+        if let Some((low, high)) = union(store.range(), Some((index, index))) {
+            let change = scale_change(low, high, self.config.max_size);
+            state.downscale(change);
+            index >>= change;
+        }
+        if value > 0.0 {
+            state.positive.increment(index, 1);
+        } else {
+            state.negative.increment(index, 1);
+        }
+        Ok(())
+    }
+
+    fn synchronized_move(
+        &self,
+        other: &Arc<dyn Aggregator + Send + Sync>,
+        _descriptor: &Descriptor,
+    ) -> Result<()> {
+        if let Some(other) = other.as_any().downcast_ref::<Self>() {
+            let mut state = self.state()?;
+            let empty = State::empty(self.config.max_scale, &self.kind);
+            *other.state()? = std::mem::replace(&mut *state, empty);
+            Ok(())
+        } else {
+            Err(MetricsError::InconsistentAggregator(format!(
+                "Expected {:?}, got: {:?}",
+                self, other
+            )))
+        }
+    }
+
+    fn merge(&self, other: &(dyn Aggregator + Send + Sync), descriptor: &Descriptor) -> Result<()> {
+        if let Some(other) = other.as_any().downcast_ref::<Self>() {
+            let mut state = self.state()?;
+            let mut other = other.state()?.clone();
+            if other.count == 0 {
+                return Ok(());
+            }
+
+            // bring both to the same scale, low enough for the merged buckets
+            // to fit
+            let scale = state.scale.min(other.scale);
+            let (change, other_change) = (state.scale - scale, other.scale - scale);
+            state.downscale(change);
+            other.downscale(other_change);
+            let change = [
+                union(state.positive.range(), other.positive.range()),
+                union(state.negative.range(), other.negative.range()),
+            ]
+            .iter()
+            .flatten()
+            .map(|&(low, high)| scale_change(low, high, self.config.max_size))
+            .max()
+            .unwrap_or(0);
+            state.downscale(change);
+            other.downscale(change);
+
+            for (index, count) in (other.positive.offset..).zip(other.positive.counts) {
+                state.positive.increment(index, count);
+            }
+            for (index, count) in (other.negative.offset..).zip(other.negative.counts) {
+                state.negative.increment(index, count);
+            }
+            let kind = descriptor.number_kind();
+            state.count += other.count;
+            state.zero_count += other.zero_count;
+            state.sum.fetch_add(kind, &other.sum.load());
+            let (other_min, other_max) = (other.min.load(), other.max.load());
+            if other_min.partial_cmp(kind, &state.min.load()) == Some(Ordering::Less) {
+                state.min.store(&other_min);
+            }
+            if other_max.partial_cmp(kind, &state.max.load()) == Some(Ordering::Greater) {
+                state.max.store(&other_max);
+            }
+            Ok(())
+        } else {
+            Err(MetricsError::InconsistentAggregator(format!(
+                "Expected {:?}, got: {:?}",
+                self, other
+            )))
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::InstrumentKind;
+
+    fn descriptor(kind: NumberKind) -> Descriptor {
+        Descriptor::new(
+            "latency".to_string(),
+            "test",
+            None,
+            InstrumentKind::ValueRecorder,
+            kind,
+        )
+    }
+
+    fn record(aggregator: &ExponentialHistogramAggregator, values: &[f64]) {
+        let descriptor = descriptor(NumberKind::F64);
+        for value in values {
+            aggregator.update(&(*value).into(), &descriptor).unwrap();
+        }
+    }
+
+    #[test]
+    fn map_values_to_indexes() {
+        // buckets include their upper boundary
+        assert_eq!(map_to_index(1.0, 0), -1);
+        assert_eq!(map_to_index(1.5, 0), 0);
+        assert_eq!(map_to_index(2.0, 0), 0);
+        assert_eq!(map_to_index(2.5, 0), 1);
+        assert_eq!(map_to_index(4.0, -1), 0);
+        assert_eq!(map_to_index(5.0, -1), 1);
+        assert_eq!(map_to_index(0.25, 1), -5);
+        assert_eq!(map_to_index(1.5, 1), 1);
+        assert_eq!(map_to_index(2.0, 1), 1);
+        assert_eq!(map_to_index(f64::MAX, MAX_SCALE), (1024 << MAX_SCALE) - 1);
+        assert_eq!(map_to_index(f64::MAX, MIN_SCALE), 0);
+        assert!(map_to_index(f64::MIN_POSITIVE / 2.0, MAX_SCALE) < map_to_index(1e-300, 20));
+
+        for &scale in &[-3, 0, 3, 10, MAX_SCALE] {
+            for &value in &[0.001, 0.3, 1.7, 42.0, 1e6] {
+                let index = map_to_index(value, scale);
+                assert!(ExponentialBuckets::lower_boundary(scale, index) < value * (1.0 + 1e-9));
+                assert!(
+                    ExponentialBuckets::lower_boundary(scale, index + 1) >= value * (1.0 - 1e-9)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn downscale_to_fit_values() {
+        let aggregator = exponential_histogram(
+            &descriptor(NumberKind::F64),
+            &ExponentialHistogramConfig::new(4, 20),
+        );
+        record(&aggregator, &[1.5, 0.0, -3.0]);
+        assert_eq!(aggregator.scale().unwrap(), 20);
+        assert_eq!(aggregator.zero_count().unwrap(), 1);
+
+        // 1.5 and 8.0 need 5 buckets at scale 1, but only 3 at scale 0:
+        // (1, 2], (2, 4] and (4, 8]
+        record(&aggregator, &[8.0, 2.0]);
+        assert_eq!(aggregator.scale().unwrap(), 0);
+        assert_eq!(
+            aggregator.positive().unwrap(),
+            ExponentialBuckets::new(0, vec![2, 0, 1])
+        );
+
+        assert_eq!(aggregator.count().unwrap(), 5);
+        assert_eq!(aggregator.sum().unwrap().to_f64(&NumberKind::F64), 8.5);
+        assert_eq!(aggregator.min().unwrap().to_f64(&NumberKind::F64), -3.0);
+        assert_eq!(aggregator.max().unwrap().to_f64(&NumberKind::F64), 8.0);
+        assert_eq!(aggregator.negative().unwrap().counts(), &[1]);
+    }
+
+    #[test]
+    fn merge_at_common_scale() {
+        let config = ExponentialHistogramConfig::new(4, 20);
+        let descriptor = descriptor(NumberKind::F64);
+        let aggregator = exponential_histogram(&descriptor, &config);
+        let checkpoint: Arc<dyn Aggregator + Send + Sync> =
+            Arc::new(exponential_histogram(&descriptor, &config));
+
+        record(&aggregator, &[1.0, 100.0]);
+        aggregator
+            .synchronized_move(&checkpoint, &descriptor)
+            .unwrap();
+        assert_eq!(aggregator.count().unwrap(), 0);
+        assert_eq!(aggregator.scale().unwrap(), 20);
+
+        record(&aggregator, &[3.0, 0.0]);
+        aggregator.merge(checkpoint.as_ref(), &descriptor).unwrap();
+        assert_eq!(aggregator.count().unwrap(), 4);
+        assert_eq!(aggregator.zero_count().unwrap(), 1);
+        let (scale, positive) = (aggregator.scale().unwrap(), aggregator.positive().unwrap());
+        assert_eq!(positive.counts().iter().sum::<u64>(), 3);
+        assert!(positive.counts().len() <= 4);
+        for &value in &[1.0, 3.0, 100.0] {
+            let index = map_to_index(value, scale) - positive.offset();
+            assert!(positive.counts()[index as usize] > 0);
+        }
+        assert_eq!(aggregator.max().unwrap().to_f64(&NumberKind::F64), 100.0);
+    }
+}
@@ -1,36 +1,87 @@
 use crate::metrics::{AtomicNumber, Descriptor, MetricsError, Number, NumberKind, Result};
-use crate::sdk::export::metrics::{Buckets, Count, Histogram, Sum};
+use crate::sdk::export::metrics::{Buckets, Count, Exemplar, Exemplars, Histogram, Max, Min, Sum};
+use crate::sdk::metrics::aggregators::exemplar::{ExemplarReservoir, Reservoir};
 use crate::sdk::metrics::export::metrics::Aggregator;
+use crate::sdk::{Clock, SystemClock};
+use crate::{Context, KeyValue};
+use std::cmp::Ordering;
 use std::mem;
 use std::sync::{Arc, RwLock};
 
 /// Create a new histogram for the given descriptor with the given boundaries
+///
+/// The boundaries are sorted, `NaN` and duplicate boundaries are ignored.
 pub fn histogram(desc: &Descriptor, boundaries: &[f64]) -> HistogramAggregator {
-    let mut sorted_boundaries = boundaries.to_owned();
+    let mut sorted_boundaries: Vec<f64> = boundaries
+        .iter()
+        .copied()
+        .filter(|boundary| !boundary.is_nan())
+        .collect();
     sorted_boundaries.sort_by(|a, b| a.partial_cmp(&b).unwrap());
-    let state = State::empty(&sorted_boundaries);
+    sorted_boundaries.dedup();
+    let state = State::empty(&sorted_boundaries, desc.number_kind());
 
     HistogramAggregator {
         inner: RwLock::new(Inner {
             boundaries: sorted_boundaries,
             kind: desc.number_kind().clone(),
             state,
+            exemplars: None,
         }),
     }
 }
 
 /// This aggregator observes events and counts them in pre-determined buckets. It
-/// also calculates the sum and count of all events.
+/// also calculates the sum, count, min and max of all events.
 #[derive(Debug)]
 pub struct HistogramAggregator {
     inner: RwLock<Inner>,
 }
 
+impl HistogramAggregator {
+    /// Keep the measurements selected by `reservoir` as exemplars of the
+    /// histogram.
+    pub fn with_exemplar_reservoir(mut self, reservoir: &ExemplarReservoir) -> Self {
+        if let Ok(inner) = self.inner.get_mut() {
+            inner.exemplars = Some(Reservoir::new(reservoir, inner.boundaries.len() + 1));
+        }
+        self
+    }
+}
+
 #[derive(Debug)]
 struct Inner {
     boundaries: Vec<f64>,
     kind: NumberKind,
     state: State,
+    exemplars: Option<Reservoir>,
+}
+
+impl Inner {
+    /// Record `number` in the state, returns the index of its bucket.
+    fn record(&mut self, number: &Number, kind: &NumberKind) -> usize {
+        let as_float = number.to_f64(kind);
+
+        let mut bucket_id = self.boundaries.len();
+        for (idx, boundary) in self.boundaries.iter().enumerate() {
+            if as_float < *boundary {
+                bucket_id = idx;
+                break;
+            }
+        }
+
+        self.state.count.fetch_add(&NumberKind::U64, &1u64.into());
+        self.state.sum.fetch_add(kind, number);
+        self.state.bucket_counts[bucket_id] += 1.0;
+        if number.partial_cmp(kind, &self.state.min.load()) == Some(Ordering::Less) {
+            self.state.min.store(number);
+        }
+        if number.partial_cmp(kind, &self.state.max.load()) == Some(Ordering::Greater) {
+            self.state.max.store(number);
+        }
+
+        bucket_id
+    }
 }
 
 #[derive(Debug)]
@@ -38,16 +89,24 @@ struct State {
     bucket_counts: Vec<f64>,
     count: AtomicNumber,
     sum: AtomicNumber,
+    min: AtomicNumber,
+    max: AtomicNumber,
 }
 
 impl State {
-    fn empty(boundaries: &[f64]) -> Self {
+    fn empty(boundaries: &[f64], kind: &NumberKind) -> Self {
         State {
             bucket_counts: vec![0.0; boundaries.len() + 1],
             count: NumberKind::U64.zero().to_atomic(),
-            sum: NumberKind::U64.zero().to_atomic(),
+            sum: kind.zero().to_atomic(),
+            min: kind.max().to_atomic(),
+            max: kind.min().to_atomic(),
         }
     }
+
+    fn is_empty(&self) -> bool {
+        self.count.load().to_u64(&NumberKind::U64) == 0
+    }
 }
 
 impl Sum for HistogramAggregator {
@@ -68,6 +127,30 @@ impl Count for HistogramAggregator {
     }
 }
 
+impl Min for HistogramAggregator {
+    fn min(&self) -> Result<Number> {
+        self.inner.read().map_err(From::from).map(|inner| {
+            if inner.state.is_empty() {
+                inner.kind.zero()
+            } else {
+                inner.state.min.load()
+            }
+        })
+    }
+}
+
+impl Max for HistogramAggregator {
+    fn max(&self) -> Result<Number> {
+        self.inner.read().map_err(From::from).map(|inner| {
+            if inner.state.is_empty() {
+                inner.kind.zero()
+            } else {
+                inner.state.max.load()
+            }
+        })
+    }
+}
+
 impl Histogram for HistogramAggregator {
     fn histogram(&self) -> Result<Buckets> {
         self.inner
@@ -77,23 +160,48 @@ impl Histogram for HistogramAggregator {
     }
 }
 
+impl Exemplars for HistogramAggregator {
+    fn exemplars(&self) -> Result<Vec<Exemplar>> {
+        self.inner.read().map_err(From::from).map(|inner| {
+            inner
+                .exemplars
+                .as_ref()
+                .map(Reservoir::exemplars)
+                .unwrap_or_default()
+        })
+    }
+}
+
 impl Aggregator for HistogramAggregator {
     fn update(&self, number: &Number, descriptor: &Descriptor) -> Result<()> {
         self.inner.write().map_err(From::from).map(|mut inner| {
-            let kind = descriptor.number_kind();
-            let as_float = number.to_f64(kind);
-
-            let mut bucket_id = inner.boundaries.len();
-            for (idx, boundary) in inner.boundaries.iter().enumerate() {
-                if as_float < *boundary {
-                    bucket_id = idx;
-                    break;
-                }
-            }
+            inner.record(number, descriptor.number_kind());
+        })
+    }
+
+    fn update_with_context(
+        &self,
+        number: &Number,
+        descriptor: &Descriptor,
+        cx: &Context,
+        filtered_attributes: &[KeyValue],
+    ) -> Result<()> {
+        self.update_with_clock(number, descriptor, cx, filtered_attributes, &SystemClock)
+    }
 
-            inner.state.count.fetch_add(&NumberKind::U64, &1u64.into());
-            inner.state.sum.fetch_add(kind, number);
-            inner.state.bucket_counts[bucket_id] += 1.0;
+    fn update_with_clock(
+        &self,
+        number: &Number,
+        descriptor: &Descriptor,
+        cx: &Context,
+        filtered_attributes: &[KeyValue],
+        clock: &dyn Clock,
+    ) -> Result<()> {
+        self.inner.write().map_err(From::from).map(|mut inner| {
+            let bucket_id = inner.record(number, descriptor.number_kind());
+            if let Some(reservoir) = &mut inner.exemplars {
+                reservoir.offer(number, bucket_id, cx, filtered_attributes, clock);
+            }
         })
     }
 
@@ -108,8 +216,13 @@ impl Aggregator for HistogramAggregator {
                 .map_err(From::from)
                 .and_then(|mut inner| {
                     other.inner.write().map_err(From::from).map(|mut other| {
-                        let empty = State::empty(&inner.boundaries);
-                        other.state = mem::replace(&mut inner.state, empty)
+                        let empty = State::empty(&inner.boundaries, &inner.kind);
+                        other.state = mem::replace(&mut inner.state, empty);
+                        if let (Some(reservoir), Some(other)) =
+                            (&mut inner.exemplars, &mut other.exemplars)
+                        {
+                            reservoir.move_into(other);
+                        }
                     })
                 })
         } else {
@@ -139,6 +252,25 @@ impl Aggregator for HistogramAggregator {
                         for idx in 0..inner.state.bucket_counts.len() {
                             inner.state.bucket_counts[idx] += other.state.bucket_counts[idx];
                         }
+
+                        let kind = desc.number_kind();
+                        let (other_min, other_max) =
+                            (other.state.min.load(), other.state.max.load());
+                        if other_min.partial_cmp(kind, &inner.state.min.load())
+                            == Some(Ordering::Less)
+                        {
+                            inner.state.min.store(&other_min);
+                        }
+                        if other_max.partial_cmp(kind, &inner.state.max.load())
+                            == Some(Ordering::Greater)
+                        {
+                            inner.state.max.store(&other_max);
+                        }
+                        if let (Some(reservoir), Some(other)) =
+                            (&mut inner.exemplars, &other.exemplars)
+                        {
+                            reservoir.merge(other);
+                        }
                     })
                 })
         } else {
@@ -153,3 +285,85 @@ impl Aggregator for HistogramAggregator {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::InstrumentKind;
+    use crate::sdk::export::metrics::AggregatorSelector;
+    use crate::sdk::metrics::selectors::simple::Selector;
+
+    fn descriptor(kind: NumberKind) -> Descriptor {
+        Descriptor::new(
+            "latency".to_string(),
+            "test",
+            None,
+            InstrumentKind::ValueRecorder,
+            kind,
+        )
+    }
+
+    fn record(aggregator: &HistogramAggregator, descriptor: &Descriptor, values: &[f64]) {
+        for value in values {
+            aggregator.update(&(*value).into(), descriptor).unwrap();
+        }
+    }
+
+    #[test]
+    fn count_in_buckets() {
+        let descriptor = descriptor(NumberKind::F64);
+        let aggregator = histogram(&descriptor, &[5.0, 1.0, f64::NAN, 1.0]);
+        assert_eq!(aggregator.min().unwrap().to_f64(&NumberKind::F64), 0.0);
+        assert_eq!(aggregator.max().unwrap().to_f64(&NumberKind::F64), 0.0);
+
+        record(&aggregator, &descriptor, &[0.5, 1.0, 2.0, 7.5, 3.0]);
+        let buckets = aggregator.histogram().unwrap();
+        assert_eq!(buckets.boundaries(), &vec![1.0, 5.0]);
+        assert_eq!(buckets.counts(), &vec![1.0, 3.0, 1.0]);
+        assert_eq!(aggregator.count().unwrap(), 5);
+        assert_eq!(aggregator.sum().unwrap().to_f64(&NumberKind::F64), 14.0);
+        assert_eq!(aggregator.min().unwrap().to_f64(&NumberKind::F64), 0.5);
+        assert_eq!(aggregator.max().unwrap().to_f64(&NumberKind::F64), 7.5);
+    }
+
+    #[test]
+    fn merge_checkpoints() {
+        let descriptor = descriptor(NumberKind::F64);
+        let aggregator = histogram(&descriptor, &[1.0]);
+        let checkpoint: Arc<dyn Aggregator + Send + Sync> =
+            Arc::new(histogram(&descriptor, &[1.0]));
+        record(&aggregator, &descriptor, &[2.0, 3.0]);
+        aggregator
+            .synchronized_move(&checkpoint, &descriptor)
+            .unwrap();
+        assert_eq!(aggregator.count().unwrap(), 0);
+
+        record(&aggregator, &descriptor, &[0.5]);
+        aggregator.merge(checkpoint.as_ref(), &descriptor).unwrap();
+        assert_eq!(aggregator.histogram().unwrap().counts(), &vec![1.0, 2.0]);
+        assert_eq!(aggregator.count().unwrap(), 3);
+        assert_eq!(aggregator.min().unwrap().to_f64(&NumberKind::F64), 0.5);
+        assert_eq!(aggregator.max().unwrap().to_f64(&NumberKind::F64), 3.0);
+    }
+
+    #[test]
+    fn select_instrument_boundaries() {
+        let selector = Selector::Histogram(vec![10.0, 100.0]);
+        let mut descriptor = descriptor(NumberKind::U64);
+        let boundaries = |descriptor: &Descriptor| {
+            let aggregator = selector.aggregator_for(descriptor).unwrap();
+            aggregator
+                .as_any()
+                .downcast_ref::<HistogramAggregator>()
+                .unwrap()
+                .histogram()
+                .unwrap()
+                .boundaries()
+                .clone()
+        };
+        assert_eq!(boundaries(&descriptor), vec![10.0, 100.0]);
+
+        descriptor.set_histogram_boundaries(vec![1.0, 2.0, 4.0]);
+        assert_eq!(boundaries(&descriptor), vec![1.0, 2.0, 4.0]);
+    }
+}
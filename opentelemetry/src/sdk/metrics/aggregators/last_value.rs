@@ -1,5 +1,9 @@
 use crate::metrics::{Descriptor, MetricsError, Number, Result};
-use crate::sdk::export::metrics::{Aggregator, LastValue};
+use crate::sdk::{
+    export::metrics::{Aggregator, LastValue},
+    Clock, SystemClock,
+};
+use crate::{Context, KeyValue};
 use std::any::Any;
 use std::sync::{Arc, Mutex};
 use std::time::SystemTime;
@@ -18,11 +22,21 @@ pub struct LastValueAggregator {
 }
 
 impl Aggregator for LastValueAggregator {
-    fn update(&self, number: &Number, _descriptor: &Descriptor) -> Result<()> {
+    fn update(&self, number: &Number, descriptor: &Descriptor) -> Result<()> {
+        self.update_with_clock(number, descriptor, &Context::new(), &[], &SystemClock)
+    }
+    fn update_with_clock(
+        &self,
+        number: &Number,
+        _descriptor: &Descriptor,
+        _cx: &Context,
+        _filtered_attributes: &[KeyValue],
+        clock: &dyn Clock,
+    ) -> Result<()> {
         self.inner.lock().map_err(Into::into).map(|mut inner| {
             inner.state = Some(LastValueData {
                 value: number.clone(),
-                timestamp: crate::time::now(),
+                timestamp: clock.now(),
             });
         })
     }
@@ -3,6 +3,8 @@ use crate::metrics::{Descriptor, InstrumentKind, MetricsError, Number, NumberKin
 
 mod array;
 mod ddsketch;
+mod exemplar;
+mod exponential_histogram;
 mod histogram;
 mod last_value;
 mod min_max_sum_count;
@@ -10,6 +12,10 @@ mod sum;
 
 pub use array::{array, ArrayAggregator};
 pub use ddsketch::{ddsketch, DDSKetchAggregator, DDSketchConfig};
+pub use exemplar::ExemplarReservoir;
+pub use exponential_histogram::{
+    exponential_histogram, ExponentialHistogramAggregator, ExponentialHistogramConfig,
+};
 pub use histogram::{histogram, HistogramAggregator};
 pub use last_value::{last_value, LastValueAggregator};
 pub use min_max_sum_count::{min_max_sum_count, MinMaxSumCountAggregator};
@@ -1,7 +1,10 @@
 use crate::metrics::{AtomicNumber, Descriptor, MetricsError, Number, Result};
-use crate::sdk::export::metrics::{Aggregator, Subtractor, Sum};
+use crate::sdk::export::metrics::{Aggregator, Exemplar, Exemplars, Subtractor, Sum};
+use crate::sdk::metrics::aggregators::exemplar::{ExemplarReservoir, Reservoir};
+use crate::sdk::{Clock, SystemClock};
+use crate::{Context, KeyValue};
 use std::any::Any;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 /// Create a new sum aggregator.
 pub fn sum() -> SumAggregator {
@@ -12,6 +15,15 @@ pub fn sum() -> SumAggregator {
 #[derive(Debug, Default)]
 pub struct SumAggregator {
     value: AtomicNumber,
+    exemplars: Option<Mutex<Reservoir>>,
+}
+
+impl SumAggregator {
+    /// Keep the measurements selected by `reservoir` as exemplars of the sum.
+    pub fn with_exemplar_reservoir(mut self, reservoir: &ExemplarReservoir) -> Self {
+        self.exemplars = Some(Mutex::new(Reservoir::new(reservoir, 1)));
+        self
+    }
 }
 
 impl Sum for SumAggregator {
@@ -20,6 +32,15 @@ impl Sum for SumAggregator {
     }
 }
 
+impl Exemplars for SumAggregator {
+    fn exemplars(&self) -> Result<Vec<Exemplar>> {
+        match &self.exemplars {
+            Some(reservoir) => Ok(reservoir.lock()?.exemplars()),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
 impl Subtractor for SumAggregator {
     fn subtract(
         &self,
@@ -34,7 +55,7 @@ impl Subtractor for SumAggregator {
             (Some(op), Some(res)) => {
                 res.value.store(&self.value.load());
                 res.value
-                    .fetch_add(descriptor.number_kind(), &op.value.load());
+                    .fetch_sub(descriptor.number_kind(), &op.value.load());
                 Ok(())
             }
             _ => Err(MetricsError::InconsistentAggregator(format!(
@@ -50,6 +71,31 @@ impl Aggregator for SumAggregator {
         self.value.fetch_add(descriptor.number_kind(), number);
         Ok(())
     }
+    fn update_with_context(
+        &self,
+        number: &Number,
+        descriptor: &Descriptor,
+        cx: &Context,
+        filtered_attributes: &[KeyValue],
+    ) -> Result<()> {
+        self.update_with_clock(number, descriptor, cx, filtered_attributes, &SystemClock)
+    }
+    fn update_with_clock(
+        &self,
+        number: &Number,
+        descriptor: &Descriptor,
+        cx: &Context,
+        filtered_attributes: &[KeyValue],
+        clock: &dyn Clock,
+    ) -> Result<()> {
+        self.value.fetch_add(descriptor.number_kind(), number);
+        if let Some(reservoir) = &self.exemplars {
+            reservoir
+                .lock()?
+                .offer(number, 0, cx, filtered_attributes, clock);
+        }
+        Ok(())
+    }
     fn synchronized_move(
         &self,
         other: &Arc<dyn Aggregator + Send + Sync>,
@@ -59,6 +105,9 @@ impl Aggregator for SumAggregator {
             let kind = descriptor.number_kind();
             other.value.store(&self.value.load());
             self.value.store(&kind.zero());
+            if let (Some(reservoir), Some(other)) = (&self.exemplars, &other.exemplars) {
+                reservoir.lock()?.move_into(&mut *other.lock()?);
+            }
             Ok(())
         } else {
             Err(MetricsError::InconsistentAggregator(format!(
@@ -70,7 +119,10 @@ impl Aggregator for SumAggregator {
     fn merge(&self, other: &(dyn Aggregator + Send + Sync), descriptor: &Descriptor) -> Result<()> {
         if let Some(other_sum) = other.as_any().downcast_ref::<SumAggregator>() {
             self.value
-                .fetch_add(descriptor.number_kind(), &other_sum.value.load())
+                .fetch_add(descriptor.number_kind(), &other_sum.value.load());
+            if let (Some(reservoir), Some(other)) = (&self.exemplars, &other_sum.exemplars) {
+                reservoir.lock()?.merge(&*other.lock()?);
+            }
         }
 
         Ok(())
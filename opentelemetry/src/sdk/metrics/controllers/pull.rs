@@ -4,9 +4,10 @@ use crate::sdk::{
     metrics::{
         accumulator,
         processors::{self, BasicProcessor},
-        Accumulator,
+        view::{ViewAggregatorSelector, Views},
+        Accumulator, View,
     },
-    Resource,
+    Clock, Resource,
 };
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
@@ -99,6 +100,15 @@ pub struct PullControllerBuilder {
     /// `true`, `CheckpointSet::try_for_each` will visit metrics that were not
     /// updated in the most recent interval. Default true.
     memory: bool,
+
+    /// Views customizing the metrics exported for the instruments they select.
+    views: Vec<View>,
+
+    /// The maximum number of attribute sets recorded per instrument.
+    cardinality_limit: Option<usize>,
+
+    /// The clock the timestamps of measurements and collections are read from.
+    clock: Option<Arc<dyn Clock>>,
 }
 
 impl PullControllerBuilder {
@@ -113,6 +123,9 @@ impl PullControllerBuilder {
             resource: None,
             cache_period: None,
             memory: true,
+            views: Vec::new(),
+            cardinality_limit: None,
+            clock: None,
         }
     }
 
@@ -139,17 +152,61 @@ impl PullControllerBuilder {
         PullControllerBuilder { memory, ..self }
     }
 
+    /// Register a view customizing the metrics exported for the instruments
+    /// it selects.
+    pub fn with_view(mut self, view: View) -> Self {
+        self.views.push(view);
+        self
+    }
+
+    /// Set the maximum number of attribute sets recorded per instrument, see
+    /// [`AccumulatorBuilder::with_cardinality_limit`].
+    ///
+    /// [`AccumulatorBuilder::with_cardinality_limit`]: crate::sdk::metrics::AccumulatorBuilder::with_cardinality_limit
+    pub fn with_cardinality_limit(self, limit: usize) -> Self {
+        PullControllerBuilder {
+            cardinality_limit: Some(limit),
+            ..self
+        }
+    }
+
+    /// Read the timestamps of measurements and collection intervals from
+    /// `clock`, the [`SystemClock`] by default.
+    ///
+    /// [`SystemClock`]: crate::sdk::SystemClock
+    pub fn with_clock<T: Clock + 'static>(self, clock: T) -> Self {
+        PullControllerBuilder {
+            clock: Some(Arc::new(clock)),
+            ..self
+        }
+    }
+
     /// Build a new `PullController` from the current configuration.
     pub fn build(self) -> PullController {
-        let processor = Arc::new(processors::basic(
-            self.aggregator_selector,
+        let views = Arc::new(Views::new(self.views));
+        let mut processor = processors::basic(
+            Box::new(ViewAggregatorSelector::new(
+                views.clone(),
+                self.aggregator_selector,
+            )),
             self.export_selector,
             self.memory,
-        ));
+        );
+        if let Some(clock) = &self.clock {
+            processor = processor.with_clock(clock.clone());
+        }
+        let processor = Arc::new(processor);
 
-        let accumulator = accumulator(processor.clone())
+        let mut accumulator = accumulator(processor.clone())
             .with_resource(self.resource.unwrap_or_default())
-            .build();
+            .with_views(views);
+        if let Some(limit) = self.cardinality_limit {
+            accumulator = accumulator.with_cardinality_limit(limit);
+        }
+        if let Some(clock) = self.clock {
+            accumulator = accumulator.with_clock(clock);
+        }
+        let accumulator = accumulator.build();
         let provider = registry::meter_provider(Arc::new(accumulator.clone()));
 
         PullController {
@@ -161,3 +218,233 @@ impl PullControllerBuilder {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{BatchObserverResult, MeterProvider, NumberKind};
+    use crate::sdk::export::metrics::{ExportKindSelector, LastValue, Sum};
+    use crate::sdk::metrics::{aggregators, selectors};
+    use crate::KeyValue;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    #[test]
+    fn observe_with_callbacks_on_collect() {
+        let mut controller = pull(
+            Box::new(selectors::simple::Selector::Exact),
+            Box::new(ExportKindSelector::Cumulative),
+        )
+        .with_cache_period(Duration::from_secs(0))
+        .build();
+        let meter = controller.provider().meter("test", None);
+        let collections = Arc::new(AtomicU64::new(0));
+
+        let gauge_collections = collections.clone();
+        let _rss = meter
+            .u64_observable_gauge("rss")
+            .with_callback(move |observer| {
+                let n = gauge_collections.fetch_add(1, Ordering::SeqCst) + 1;
+                observer.observe(n * 100, &[]);
+            })
+            .init();
+        let length = meter.i64_observable_up_down_counter("length").init();
+        let capacity = meter.i64_observable_up_down_counter("capacity").init();
+        meter
+            .register_callback(move |result: BatchObserverResult| {
+                result.observe(
+                    &[KeyValue::new("queue", "jobs")],
+                    &[length.observation(3), capacity.observation(8)],
+                )
+            })
+            .unwrap();
+
+        let exported = |controller: &mut PullController| {
+            controller.collect().unwrap();
+            let mut exported = Vec::new();
+            controller
+                .try_for_each(&ExportKindSelector::Cumulative, &mut |record| {
+                    let aggregator = record.aggregator().unwrap().as_any();
+                    let value = match aggregator.downcast_ref::<aggregators::SumAggregator>() {
+                        Some(sum) => sum.sum()?.to_i64(&NumberKind::I64),
+                        None => aggregator
+                            .downcast_ref::<aggregators::LastValueAggregator>()
+                            .unwrap()
+                            .last_value()?
+                            .0
+                            .to_i64(&NumberKind::U64),
+                    };
+                    exported.push((record.descriptor().name().to_string(), value));
+                    Ok(())
+                })
+                .unwrap();
+            exported.sort();
+            exported
+        };
+
+        let expected = |rss| {
+            vec![
+                ("capacity".to_string(), 8),
+                ("length".to_string(), 3),
+                ("rss".to_string(), rss),
+            ]
+        };
+        assert_eq!(exported(&mut controller), expected(100));
+        assert_eq!(exported(&mut controller), expected(200));
+        assert_eq!(collections.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn sum_negative_increments_of_up_down_counters() {
+        let mut controller = pull(
+            Box::new(selectors::simple::Selector::Exact),
+            Box::new(ExportKindSelector::Cumulative),
+        )
+        .with_cache_period(Duration::from_secs(0))
+        .build();
+        let meter = controller.provider().meter("test", None);
+        let in_flight = meter.i64_up_down_counter("in_flight").init();
+        in_flight.add(5, &[]);
+        in_flight.add(-3, &[]);
+        let pool = meter.f64_up_down_counter("pool").init();
+        pool.add(-1.5, &[]);
+        // monotonic counters still reject negative increments
+        let requests = meter.f64_counter("requests").init();
+        requests.add(2.0, &[]);
+        requests.add(-1.0, &[]);
+        controller.collect().unwrap();
+
+        let mut exported = Vec::new();
+        controller
+            .try_for_each(&ExportKindSelector::Cumulative, &mut |record| {
+                let descriptor = record.descriptor();
+                let sum = record
+                    .aggregator()
+                    .and_then(|agg| agg.as_any().downcast_ref::<aggregators::SumAggregator>())
+                    .unwrap()
+                    .sum()?;
+                exported.push((
+                    descriptor.name().to_string(),
+                    sum.to_f64(descriptor.number_kind()),
+                    descriptor.instrument_kind().monotonic(),
+                ));
+                Ok(())
+            })
+            .unwrap();
+        exported.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            exported,
+            vec![
+                ("in_flight".to_string(), 2.0, false),
+                ("pool".to_string(), -1.5, false),
+                ("requests".to_string(), 2.0, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn record_new_attribute_sets_beyond_cardinality_limit_in_overflow_set() {
+        let mut controller = pull(
+            Box::new(selectors::simple::Selector::Exact),
+            Box::new(ExportKindSelector::Cumulative),
+        )
+        .with_cache_period(Duration::from_secs(0))
+        .with_cardinality_limit(3)
+        .with_view(View::new("errors").with_cardinality_limit(2))
+        .build();
+        let meter = controller.provider().meter("test", None);
+        let requests = meter.u64_counter("requests").init();
+        let errors = meter.u64_counter("errors").init();
+        for (i, route) in ["a", "b", "c", "d", "e"].iter().enumerate() {
+            requests.add(i as u64 + 1, &[KeyValue::new("route", *route)]);
+            errors.add(i as u64 + 1, &[KeyValue::new("route", *route)]);
+        }
+        // attribute sets recorded before the limit was reached are kept
+        requests.add(10, &[KeyValue::new("route", "a")]);
+        controller.collect().unwrap();
+
+        let mut exported = Vec::new();
+        controller
+            .try_for_each(&ExportKindSelector::Cumulative, &mut |record| {
+                let sum = record
+                    .aggregator()
+                    .and_then(|agg| agg.as_any().downcast_ref::<aggregators::SumAggregator>())
+                    .unwrap()
+                    .sum()?;
+                exported.push((
+                    record.descriptor().name().to_string(),
+                    record
+                        .labels()
+                        .iter()
+                        .map(|(key, value)| format!("{}={}", key.as_str(), value))
+                        .collect::<Vec<_>>()
+                        .join(","),
+                    sum.to_u64(&NumberKind::U64),
+                ));
+                Ok(())
+            })
+            .unwrap();
+        exported.sort();
+        assert_eq!(
+            exported,
+            vec![
+                (
+                    "errors".to_string(),
+                    "otel.metric.overflow=true".to_string(),
+                    14
+                ),
+                ("errors".to_string(), "route=a".to_string(), 1),
+                (
+                    "requests".to_string(),
+                    "otel.metric.overflow=true".to_string(),
+                    12
+                ),
+                ("requests".to_string(), "route=a".to_string(), 11),
+                ("requests".to_string(), "route=b".to_string(), 2),
+            ]
+        );
+    }
+
+    #[derive(Debug)]
+    struct FixedClock(SystemTime);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> SystemTime {
+            self.0
+        }
+    }
+
+    #[test]
+    fn read_timestamps_from_the_clock() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(42);
+        let mut controller = pull(
+            Box::new(selectors::simple::Selector::Exact),
+            Box::new(ExportKindSelector::Cumulative),
+        )
+        .with_cache_period(Duration::from_secs(0))
+        .with_clock(FixedClock(now))
+        .build();
+        let meter = controller.provider().meter("test", None);
+        let _rss = meter
+            .u64_observable_gauge("rss")
+            .with_callback(|observer| observer.observe(100, &[]))
+            .init();
+        controller.collect().unwrap();
+
+        let mut timestamps = Vec::new();
+        controller
+            .try_for_each(&ExportKindSelector::Cumulative, &mut |record| {
+                let (_, timestamp) = record
+                    .aggregator()
+                    .and_then(|agg| {
+                        agg.as_any()
+                            .downcast_ref::<aggregators::LastValueAggregator>()
+                    })
+                    .unwrap()
+                    .last_value()?;
+                timestamps.push((*record.start_time(), *record.end_time(), timestamp));
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(timestamps, vec![(now, now, now)]);
+    }
+}
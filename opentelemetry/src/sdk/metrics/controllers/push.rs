@@ -1,16 +1,26 @@
 use crate::global;
-use crate::metrics::registry;
+use crate::metrics::{
+    registry,
+    sdk_api::{AsyncInstrumentCore, MeterCore, SyncInstrumentCore},
+    AsyncRunner, Descriptor, Measurement, MetricsError, Result,
+};
 use crate::sdk::{
     export::metrics::{AggregatorSelector, Checkpointer, ExportKindFor, Exporter},
     metrics::{
         self,
         processors::{self, BasicProcessor},
-        Accumulator,
+        view::{ViewAggregatorSelector, Views},
+        Accumulator, View,
     },
-    Resource,
+    Clock, Resource,
+};
+use crate::{Context, KeyValue};
+use futures::{
+    channel::{mpsc, oneshot},
+    task, Future, Stream, StreamExt,
 };
-use futures::{channel::mpsc, task, Future, Stream, StreamExt};
 use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time;
 
@@ -18,6 +28,9 @@ lazy_static::lazy_static! {
     static ref DEFAULT_PUSH_PERIOD: time::Duration = time::Duration::from_secs(10);
 }
 
+/// The default maximum of forced exports waiting for the worker.
+const DEFAULT_MAX_OUTSTANDING_EXPORTS: usize = 4;
+
 /// Create a new `PushControllerBuilder`.
 pub fn push<AS, ES, E, SP, SO, I, IO>(
     aggregator_selector: AS,
@@ -43,43 +56,174 @@ where
         stateful: None,
         period: None,
         timeout: None,
+        views: Vec::new(),
+        cardinality_limit: None,
+        max_outstanding_exports: None,
+        clock: None,
     }
 }
 
 /// Organizes a periodic push of metric data.
+///
+/// Collections are aligned to the ticks of the interval stream. A tick is
+/// skipped if the previous collection and export is still running when it is
+/// due, so a slow exporter delays no later collection and exports never pile
+/// up.
 #[derive(Debug)]
 pub struct PushController {
-    message_sender: Mutex<mpsc::Sender<PushMessage>>,
+    handle: Arc<PushHandle>,
     provider: registry::RegistryMeterProvider,
 }
 
 #[derive(Debug)]
 enum PushMessage {
-    Tick,
-    Shutdown,
+    Flush(oneshot::Sender<Result<()>>),
+    Shutdown(Option<oneshot::Sender<Result<()>>>),
+}
+
+/// The sending side of the messages to the worker of a push controller.
+#[derive(Debug)]
+struct PushHandle {
+    message_sender: Mutex<mpsc::Sender<PushMessage>>,
+    /// The number of forced exports requested and not finished yet.
+    outstanding_exports: Arc<AtomicUsize>,
+    max_outstanding_exports: usize,
+}
+
+impl PushHandle {
+    fn force_flush(&self) -> Result<()> {
+        let outstanding = self.outstanding_exports.fetch_add(1, Ordering::SeqCst);
+        if outstanding >= self.max_outstanding_exports {
+            self.outstanding_exports.fetch_sub(1, Ordering::SeqCst);
+            return Err(MetricsError::Other(format!(
+                "force flush rejected, {} exports of the push controller are outstanding",
+                outstanding
+            )));
+        }
+
+        match self.request_export(PushMessage::Flush) {
+            Ok(export) => wait_for_export(export),
+            Err(err) => {
+                self.outstanding_exports.fetch_sub(1, Ordering::SeqCst);
+                Err(err)
+            }
+        }
+    }
+
+    fn shutdown(&self) -> Result<()> {
+        self.request_export(|ch| PushMessage::Shutdown(Some(ch)))
+            .and_then(wait_for_export)
+    }
+
+    /// Send a message requesting an export to the worker, returns the
+    /// receiver of the result of the export.
+    fn request_export<F>(&self, message: F) -> Result<oneshot::Receiver<Result<()>>>
+    where
+        F: FnOnce(oneshot::Sender<Result<()>>) -> PushMessage,
+    {
+        let (res_sender, res_receiver) = oneshot::channel();
+        self.message_sender
+            .lock()
+            .map_err(MetricsError::from)
+            .and_then(|mut sender| {
+                sender
+                    .try_send(message(res_sender))
+                    .map_err(|err| MetricsError::Other(err.to_string()))
+            })?;
+
+        Ok(res_receiver)
+    }
+}
+
+fn wait_for_export(export: oneshot::Receiver<Result<()>>) -> Result<()> {
+    futures::executor::block_on(export).unwrap_or_else(|_| {
+        Err(MetricsError::Other(
+            "push controller worker stopped before the export finished".into(),
+        ))
+    })
+}
+
+/// Meter core of the provider of a push controller, forcing exports through
+/// the controller's worker.
+#[derive(Debug)]
+struct PushMeterCore {
+    accumulator: Accumulator,
+    handle: Arc<PushHandle>,
+}
+
+impl MeterCore for PushMeterCore {
+    fn record_batch_with_context(
+        &self,
+        cx: &Context,
+        labels: &[KeyValue],
+        measurements: Vec<Measurement>,
+    ) {
+        self.accumulator
+            .record_batch_with_context(cx, labels, measurements)
+    }
+
+    fn new_sync_instrument(&self, descriptor: Descriptor) -> Result<Arc<dyn SyncInstrumentCore>> {
+        self.accumulator.new_sync_instrument(descriptor)
+    }
+
+    fn new_async_instrument(
+        &self,
+        descriptor: Descriptor,
+        runner: Option<AsyncRunner>,
+    ) -> Result<Arc<dyn AsyncInstrumentCore>> {
+        self.accumulator.new_async_instrument(descriptor, runner)
+    }
+
+    fn new_batch_observer(&self, runner: AsyncRunner) -> Result<()> {
+        self.accumulator.new_batch_observer(runner)
+    }
+
+    fn force_flush(&self) -> Result<()> {
+        self.handle.force_flush()
+    }
+
+    fn shutdown(&self) -> Result<()> {
+        self.handle.shutdown()
+    }
 }
 
 /// The future which executes push controller work periodically. Can be run on a
 /// passed in executor.
 #[allow(missing_debug_implementations)]
 pub struct PushControllerWorker {
-    messages: Pin<Box<dyn Stream<Item = PushMessage> + Send>>,
+    messages: mpsc::Receiver<PushMessage>,
+    ticker: Pin<Box<dyn Stream<Item = ()> + Send>>,
     accumulator: Accumulator,
     processor: Arc<BasicProcessor>,
     exporter: Box<dyn Exporter + Send + Sync>,
+    outstanding_exports: Arc<AtomicUsize>,
+    /// Whether the final export of a requested shutdown is done.
+    shut_down: bool,
     _timeout: time::Duration,
 }
 
 impl PushControllerWorker {
-    fn on_tick(&mut self) {
+    fn on_tick(&mut self) -> Result<()> {
         // TODO handle timeout
-        if let Err(err) = self.processor.lock().and_then(|mut checkpointer| {
+        self.processor.lock().and_then(|mut checkpointer| {
             checkpointer.start_collection();
             self.accumulator.0.collect(&mut checkpointer);
             checkpointer.finish_collection()?;
             self.exporter.export(checkpointer.checkpoint_set())
-        }) {
-            global::handle_error(err)
+        })
+    }
+
+    /// Skip the ticks which were due while the last export was running.
+    fn skip_overlapping_ticks(&mut self, cx: &mut task::Context<'_>) {
+        let mut skipped = 0;
+        while let task::Poll::Ready(Some(_)) = self.ticker.poll_next_unpin(cx) {
+            skipped += 1;
+        }
+        if skipped > 0 {
+            global::handle_error(MetricsError::Other(format!(
+                "skipped {} metric collections overlapping a slow export",
+                skipped
+            )));
         }
     }
 }
@@ -88,13 +232,39 @@ impl Future for PushControllerWorker {
     type Output = ();
     fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> task::Poll<Self::Output> {
         loop {
-            match futures::ready!(self.messages.poll_next_unpin(cx)) {
-                // Span batch interval time reached, export current spans.
-                Some(PushMessage::Tick) => self.on_tick(),
-                // Stream has terminated or processor is shutdown, return to finish execution.
-                None | Some(PushMessage::Shutdown) => {
+            match self.messages.poll_next_unpin(cx) {
+                // A force flush has been invoked, export and reply with the result.
+                task::Poll::Ready(Some(PushMessage::Flush(ch))) => {
+                    let result = self.on_tick();
+                    self.outstanding_exports.fetch_sub(1, Ordering::SeqCst);
+                    let _ = ch.send(result);
+                    self.skip_overlapping_ticks(cx);
+                    continue;
+                }
+                // Controller is shutdown, export one last time and reply with the result.
+                task::Poll::Ready(Some(PushMessage::Shutdown(Some(ch)))) => {
+                    let result = self.on_tick();
+                    self.shut_down = true;
+                    let _ = ch.send(result);
                     return task::Poll::Ready(());
                 }
+                // Controller is dropped, return to finish execution and export on drop.
+                task::Poll::Ready(None) | task::Poll::Ready(Some(PushMessage::Shutdown(None))) => {
+                    return task::Poll::Ready(());
+                }
+                task::Poll::Pending => {}
+            }
+
+            match self.ticker.poll_next_unpin(cx) {
+                // Push interval time reached, export current metrics.
+                task::Poll::Ready(Some(_)) => {
+                    if let Err(err) = self.on_tick() {
+                        global::handle_error(err);
+                    }
+                    self.skip_overlapping_ticks(cx);
+                }
+                // Without ticks exports are only forced, wait for messages.
+                task::Poll::Ready(None) | task::Poll::Pending => return task::Poll::Pending,
             }
         }
     }
@@ -102,8 +272,13 @@ impl Future for PushControllerWorker {
 
 impl Drop for PushControllerWorker {
     fn drop(&mut self) {
+        if self.shut_down {
+            return;
+        }
         // Try to push data one last time
-        self.on_tick()
+        if let Err(err) = self.on_tick() {
+            global::handle_error(err);
+        }
     }
 }
 
@@ -112,12 +287,30 @@ impl PushController {
     pub fn provider(&self) -> registry::RegistryMeterProvider {
         self.provider.clone()
     }
+
+    /// Collect and export the measurements recorded so far, waiting for the
+    /// export to finish.
+    ///
+    /// Forced exports are queued behind the running one, at most
+    /// [`PushControllerBuilder::with_max_outstanding_exports`] at once, further
+    /// calls fail until some of them finished.
+    pub fn force_flush(&self) -> Result<()> {
+        self.handle.force_flush()
+    }
+
+    /// Stop the controller after a final collection and export, waiting for
+    /// the export to finish.
+    ///
+    /// The controller is stopped without waiting when it is dropped.
+    pub fn shutdown(&self) -> Result<()> {
+        self.handle.shutdown()
+    }
 }
 
 impl Drop for PushController {
     fn drop(&mut self) {
-        if let Ok(mut sender) = self.message_sender.lock() {
-            let _ = sender.try_send(PushMessage::Shutdown);
+        if let Ok(mut sender) = self.handle.message_sender.lock() {
+            let _ = sender.try_send(PushMessage::Shutdown(None));
         }
     }
 }
@@ -134,6 +327,10 @@ pub struct PushControllerBuilder<S, I> {
     stateful: Option<bool>,
     period: Option<time::Duration>,
     timeout: Option<time::Duration>,
+    views: Vec<View>,
+    cardinality_limit: Option<usize>,
+    max_outstanding_exports: Option<usize>,
+    clock: Option<Arc<dyn Clock>>,
 }
 
 impl<S, SO, I, IS, ISI> PushControllerBuilder<S, I>
@@ -166,33 +363,230 @@ where
         }
     }
 
+    /// Register a view customizing the metrics exported for the instruments
+    /// it selects.
+    pub fn with_view(mut self, view: View) -> Self {
+        self.views.push(view);
+        self
+    }
+
+    /// Set the maximum number of attribute sets recorded per instrument, see
+    /// [`AccumulatorBuilder::with_cardinality_limit`].
+    ///
+    /// [`AccumulatorBuilder::with_cardinality_limit`]: crate::sdk::metrics::AccumulatorBuilder::with_cardinality_limit
+    pub fn with_cardinality_limit(self, limit: usize) -> Self {
+        PushControllerBuilder {
+            cardinality_limit: Some(limit),
+            ..self
+        }
+    }
+
+    /// Set the maximum number of forced exports waiting for the worker of the
+    /// controller, 4 by default.
+    ///
+    /// Force flushes beyond the limit fail right away instead of queuing up
+    /// behind a slow exporter.
+    pub fn with_max_outstanding_exports(self, max: usize) -> Self {
+        PushControllerBuilder {
+            max_outstanding_exports: Some(max),
+            ..self
+        }
+    }
+
+    /// Read the timestamps of measurements and collection intervals from
+    /// `clock`, the [`SystemClock`] by default.
+    ///
+    /// [`SystemClock`]: crate::sdk::SystemClock
+    pub fn with_clock<T: Clock + 'static>(self, clock: T) -> Self {
+        PushControllerBuilder {
+            clock: Some(Arc::new(clock)),
+            ..self
+        }
+    }
+
     /// Build a new `PushController` with this configuration.
     pub fn build(self) -> PushController {
-        let processor = processors::basic(self.aggregator_selector, self.export_selector, false);
+        let views = Arc::new(Views::new(self.views));
+        let aggregator_selector =
+            ViewAggregatorSelector::new(views.clone(), self.aggregator_selector);
+        let mut processor =
+            processors::basic(Box::new(aggregator_selector), self.export_selector, false);
+        if let Some(clock) = &self.clock {
+            processor = processor.with_clock(clock.clone());
+        }
         let processor = Arc::new(processor);
-        let mut accumulator = metrics::accumulator(processor.clone());
+        let mut accumulator = metrics::accumulator(processor.clone()).with_views(views);
 
         if let Some(resource) = self.resource {
             accumulator = accumulator.with_resource(resource);
         }
+        if let Some(limit) = self.cardinality_limit {
+            accumulator = accumulator.with_cardinality_limit(limit);
+        }
+        if let Some(clock) = self.clock {
+            accumulator = accumulator.with_clock(clock);
+        }
         let accumulator = accumulator.build();
-        let provider = registry::meter_provider(Arc::new(accumulator.clone()));
 
         let (message_sender, message_receiver) = mpsc::channel(256);
-        let ticker =
-            (self.interval)(self.period.unwrap_or(*DEFAULT_PUSH_PERIOD)).map(|_| PushMessage::Tick);
+        let outstanding_exports = Arc::new(AtomicUsize::new(0));
+        let handle = Arc::new(PushHandle {
+            message_sender: Mutex::new(message_sender),
+            outstanding_exports: outstanding_exports.clone(),
+            max_outstanding_exports: self
+                .max_outstanding_exports
+                .unwrap_or(DEFAULT_MAX_OUTSTANDING_EXPORTS),
+        });
+        let provider = registry::meter_provider(Arc::new(PushMeterCore {
+            accumulator: accumulator.clone(),
+            handle: handle.clone(),
+        }));
+        let ticker = (self.interval)(self.period.unwrap_or(*DEFAULT_PUSH_PERIOD)).map(|_| ());
 
         (self.spawn)(PushControllerWorker {
-            messages: Box::pin(futures::stream::select(message_receiver, ticker)),
+            messages: message_receiver,
+            ticker: Box::pin(ticker.fuse()),
             accumulator,
             processor,
             exporter: self.exporter,
+            outstanding_exports,
+            shut_down: false,
             _timeout: self.timeout.unwrap_or(*DEFAULT_PUSH_PERIOD),
         });
 
-        PushController {
-            message_sender: Mutex::new(message_sender),
-            provider,
+        PushController { handle, provider }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::global::GlobalMeterProvider;
+    use crate::metrics::MeterProvider;
+    use crate::sdk::export::metrics::{CheckpointSet, ExportKind, ExportKindSelector};
+    use crate::sdk::metrics::selectors;
+    use std::sync::mpsc as std_mpsc;
+    use std::thread;
+
+    #[derive(Debug)]
+    struct CountingExporter {
+        exports: Arc<AtomicUsize>,
+        started: Mutex<std_mpsc::Sender<()>>,
+        // exports block until released
+        release: Mutex<std_mpsc::Receiver<()>>,
+    }
+
+    impl ExportKindFor for CountingExporter {
+        fn export_kind_for(&self, descriptor: &Descriptor) -> ExportKind {
+            ExportKindSelector::Cumulative.export_kind_for(descriptor)
+        }
+    }
+
+    impl Exporter for CountingExporter {
+        fn export(&self, _checkpoint_set: &mut dyn CheckpointSet) -> Result<()> {
+            let _ = self.started.lock().unwrap().send(());
+            let _ = self.release.lock().unwrap().recv();
+            self.exports.fetch_add(1, Ordering::SeqCst);
+            Ok(())
         }
     }
+
+    fn spawn_worker(worker: PushControllerWorker) -> thread::JoinHandle<()> {
+        thread::spawn(move || futures::executor::block_on(worker))
+    }
+
+    #[test]
+    fn skip_ticks_overlapping_export() {
+        let exports = Arc::new(AtomicUsize::new(0));
+        let (started_sender, started) = std_mpsc::channel();
+        let (release, release_receiver) = std_mpsc::channel();
+        let exporter = CountingExporter {
+            exports: exports.clone(),
+            started: Mutex::new(started_sender),
+            release: Mutex::new(release_receiver),
+        };
+        // ticks missed during a slow export are all ready at once
+        let controller = push(
+            selectors::simple::Selector::Exact,
+            ExportKindSelector::Cumulative,
+            exporter,
+            spawn_worker,
+            |_| futures::stream::iter(vec![(); 5]).chain(futures::stream::pending()),
+        )
+        .build();
+
+        started.recv().unwrap();
+        release.send(()).unwrap();
+        // the forced export is the second one, the other four ticks are skipped
+        release.send(()).unwrap();
+        controller.provider().force_flush().unwrap();
+        assert_eq!(exports.load(Ordering::SeqCst), 2);
+        release.send(()).unwrap();
+    }
+
+    #[test]
+    fn reject_force_flush_beyond_max_outstanding_exports() {
+        let exports = Arc::new(AtomicUsize::new(0));
+        let (started_sender, started) = std_mpsc::channel();
+        let (release, release_receiver) = std_mpsc::channel();
+        let exporter = CountingExporter {
+            exports: exports.clone(),
+            started: Mutex::new(started_sender),
+            release: Mutex::new(release_receiver),
+        };
+        let controller = Arc::new(
+            push(
+                selectors::simple::Selector::Exact,
+                ExportKindSelector::Cumulative,
+                exporter,
+                spawn_worker,
+                |_| futures::stream::pending::<()>(),
+            )
+            .with_max_outstanding_exports(1)
+            .build(),
+        );
+
+        let flushing = controller.clone();
+        let flush = thread::spawn(move || flushing.force_flush());
+        started.recv().unwrap();
+        assert!(controller.force_flush().is_err());
+
+        release.send(()).unwrap();
+        assert!(flush.join().unwrap().is_ok());
+        assert_eq!(exports.load(Ordering::SeqCst), 1);
+        // the drop of the worker exports one last time
+        release.send(()).unwrap();
+    }
+
+    #[test]
+    fn shutdown_exports_one_last_time() {
+        let exports = Arc::new(AtomicUsize::new(0));
+        let (started_sender, _started) = std_mpsc::channel();
+        let (release, release_receiver) = std_mpsc::channel();
+        let exporter = CountingExporter {
+            exports: exports.clone(),
+            started: Mutex::new(started_sender),
+            release: Mutex::new(release_receiver),
+        };
+        let controller = push(
+            selectors::simple::Selector::Exact,
+            ExportKindSelector::Cumulative,
+            exporter,
+            spawn_worker,
+            |_| futures::stream::pending::<()>(),
+        )
+        .build();
+        let provider = GlobalMeterProvider::new(controller.provider());
+        let counter = provider.meter("test", None).u64_counter("c").init();
+        counter.add(1, &[]);
+        release.send(()).unwrap();
+        release.send(()).unwrap();
+
+        assert!(provider.shutdown().is_ok());
+        assert_eq!(exports.load(Ordering::SeqCst), 1);
+        assert!(provider.force_flush().is_err());
+        assert!(controller.shutdown().is_err());
+        drop(controller);
+        assert_eq!(exports.load(Ordering::SeqCst), 1);
+    }
 }
@@ -1,8 +1,9 @@
 //! # OpenTelemetry Metrics SDK
 use crate::global;
 use crate::metrics::{
-    sdk_api::{self, InstrumentCore as _, SyncBoundInstrumentCore as _},
-    AsyncRunner, AtomicNumber, Descriptor, Measurement, Number, NumberKind, Observation, Result,
+    sdk_api::{self, InstrumentCore as _},
+    AsyncRunner, AtomicNumber, Descriptor, Measurement, MetricsError, Number, NumberKind,
+    Observation, Result,
 };
 use crate::sdk::{
     export::{
@@ -10,6 +11,7 @@ use crate::sdk::{
         metrics::{Aggregator, LockedProcessor, Processor},
     },
     resource::Resource,
+    Clock, SystemClock,
 };
 use crate::{
     labels::{hash_labels, LabelSet},
@@ -20,20 +22,34 @@ use std::any::Any;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
+use std::sync::atomic::{self, AtomicU64, AtomicUsize};
 use std::sync::{Arc, Mutex};
+use view::{AttributeFilter, Views};
 
 pub mod aggregators;
 pub mod controllers;
 pub mod processors;
 pub mod selectors;
+pub mod view;
 
 pub use controllers::{PullController, PushController, PushControllerWorker};
+pub use view::{Aggregation, View};
+
+/// Default maximum number of attribute sets recorded per instrument.
+const DEFAULT_CARDINALITY_LIMIT: usize = 2_000;
+
+/// Key of the attribute set recording the measurements of an instrument
+/// exceeding its cardinality limit.
+const OVERFLOW_KEY: &str = "otel.metric.overflow";
 
 /// Creates a new accumulator builder
 pub fn accumulator(processor: Arc<dyn Processor + Send + Sync>) -> AccumulatorBuilder {
     AccumulatorBuilder {
         processor,
         resource: None,
+        views: None,
+        cardinality_limit: None,
+        clock: None,
     }
 }
 
@@ -42,6 +58,9 @@ pub fn accumulator(processor: Arc<dyn Processor + Send + Sync>) -> AccumulatorBu
 pub struct AccumulatorBuilder {
     processor: Arc<dyn Processor + Send + Sync>,
     resource: Option<Resource>,
+    views: Option<Arc<Views>>,
+    cardinality_limit: Option<usize>,
+    clock: Option<Arc<dyn Clock>>,
 }
 
 impl AccumulatorBuilder {
@@ -53,11 +72,47 @@ impl AccumulatorBuilder {
         }
     }
 
+    /// The views applied to the instruments of this accumulator, their
+    /// aggregations must be selected by the processor's aggregator selector.
+    pub(crate) fn with_views(self, views: Arc<Views>) -> Self {
+        AccumulatorBuilder {
+            views: Some(views),
+            ..self
+        }
+    }
+
+    /// The maximum number of attribute sets recorded per instrument, 2000 by
+    /// default.
+    ///
+    /// Once an instrument reaches the limit, measurements with new attribute
+    /// sets are recorded with the single attribute `otel.metric.overflow=true`
+    /// instead, and the number of such measurements is reported to the global
+    /// error handler on collection. The overflow attribute set counts towards
+    /// the limit.
+    pub fn with_cardinality_limit(self, limit: usize) -> Self {
+        AccumulatorBuilder {
+            cardinality_limit: Some(limit),
+            ..self
+        }
+    }
+
+    /// The clock the timestamps of measurements are read from, the
+    /// [`SystemClock`] by default.
+    pub fn with_clock(self, clock: Arc<dyn Clock>) -> Self {
+        AccumulatorBuilder {
+            clock: Some(clock),
+            ..self
+        }
+    }
+
     /// Create a new accumulator from this configuration
     pub fn build(self) -> Accumulator {
         Accumulator(Arc::new(AccumulatorCore::new(
             self.processor,
             self.resource.unwrap_or_default(),
+            self.views.unwrap_or_default(),
+            self.cardinality_limit.unwrap_or(DEFAULT_CARDINALITY_LIMIT),
+            self.clock.unwrap_or_else(|| Arc::new(SystemClock)),
         )))
     }
 }
@@ -129,16 +184,32 @@ struct AccumulatorCore {
     processor: Arc<dyn Processor + Send + Sync>,
     /// The resource applied to all records in this Accumulator.
     resource: Resource,
+    /// The views applied to new instruments.
+    views: Arc<Views>,
+    /// The maximum number of attribute sets of instruments without a limit
+    /// set by a view.
+    cardinality_limit: usize,
+    /// The clock the timestamps of measurements are read from.
+    clock: Arc<dyn Clock>,
 }
 
 impl AccumulatorCore {
-    fn new(processor: Arc<dyn Processor + Send + Sync>, resource: Resource) -> Self {
+    fn new(
+        processor: Arc<dyn Processor + Send + Sync>,
+        resource: Resource,
+        views: Arc<Views>,
+        cardinality_limit: usize,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
         AccumulatorCore {
             current: dashmap::DashMap::new(),
             async_instruments: Mutex::new(AsyncInstrumentState::default()),
             current_epoch: NumberKind::U64.zero().to_atomic(),
             processor,
             resource,
+            views,
+            cardinality_limit,
+            clock,
         }
     }
 
@@ -181,7 +252,9 @@ impl AccumulatorCore {
             .map_or(0, |async_instruments| {
                 let mut async_collected = 0;
 
-                async_instruments.run();
+                if global::is_telemetry_enabled() {
+                    async_instruments.run();
+                }
 
                 for instrument in &async_instruments.instruments {
                     if let Some(a) = instrument.as_any().downcast_ref::<AsyncInstrument>() {
@@ -200,6 +273,9 @@ impl AccumulatorCore {
             let mods = &value.update_count.load();
             let coll = &value.collected_count.load();
 
+            if value.overflow {
+                value.instrument.instrument.report_overflow();
+            }
             if mods.partial_cmp(&NumberKind::U64, coll) != Some(Ordering::Equal) {
                 // Updates happened in this interval,
                 // checkpoint and continue.
@@ -215,6 +291,11 @@ impl AccumulatorCore {
                     if mods.partial_cmp(&NumberKind::U64, coll) != Some(Ordering::Equal) {
                         checkpointed += self.checkpoint_record(value, locked_processor);
                     }
+                    value
+                        .instrument
+                        .instrument
+                        .series
+                        .fetch_sub(1, atomic::Ordering::Relaxed);
                     return false;
                 }
             };
@@ -293,6 +374,7 @@ impl AccumulatorCore {
             if recorders.as_ref().map_or(false, |map| map.is_empty()) {
                 *recorders = None;
             }
+            instrument.instrument.report_overflow();
 
             checkpointed
         })
@@ -306,6 +388,35 @@ struct SyncInstrument {
 
 impl SyncInstrument {
     fn acquire_handle(&self, labels: &[KeyValue]) -> Arc<Record> {
+        let labels = match &self.instrument.attribute_filter {
+            Some(filter) => filter.filter(labels),
+            None => labels.into(),
+        };
+        let map_key = self.map_key(&labels);
+        let current = &self.instrument.meter.0.current;
+        if let Some(existing_record) = current.get(&map_key) {
+            return existing_record.value().clone();
+        }
+
+        if !self
+            .instrument
+            .admits_new_series(self.instrument.series.load(atomic::Ordering::Relaxed))
+        {
+            self.instrument
+                .overflowed
+                .fetch_add(1, atomic::Ordering::Relaxed);
+            let overflow = overflow_labels();
+            let overflow_key = self.map_key(&overflow);
+            if let Some(overflow_record) = current.get(&overflow_key) {
+                return overflow_record.value().clone();
+            }
+            return self.insert_record(overflow_key, &overflow, true);
+        }
+
+        self.insert_record(map_key, &labels, false)
+    }
+
+    fn map_key(&self, labels: &[KeyValue]) -> MapKey {
         let mut hasher = FnvHasher::default();
         self.instrument
             .descriptor
@@ -314,37 +425,56 @@ impl SyncInstrument {
 
         hash_labels(&mut hasher, labels.iter().map(|kv| (&kv.key, &kv.value)));
 
-        let map_key = MapKey {
+        MapKey {
             instrument_hash: hasher.finish(),
-        };
-        let current = &self.instrument.meter.0.current;
-        if let Some(existing_record) = current.get(&map_key) {
-            return existing_record.value().clone();
         }
+    }
 
-        let record = Arc::new(Record {
-            update_count: NumberKind::U64.zero().to_atomic(),
-            collected_count: NumberKind::U64.zero().to_atomic(),
-            labels: LabelSet::from_labels(labels.iter().cloned()),
-            instrument: self.clone(),
-            current: self
-                .instrument
-                .meter
-                .0
-                .processor
-                .aggregation_selector()
-                .aggregator_for(&self.instrument.descriptor),
-            checkpoint: self
-                .instrument
-                .meter
-                .0
-                .processor
-                .aggregation_selector()
-                .aggregator_for(&self.instrument.descriptor),
-        });
-        current.insert(map_key, record.clone());
+    fn insert_record(&self, map_key: MapKey, labels: &[KeyValue], overflow: bool) -> Arc<Record> {
+        self.instrument
+            .meter
+            .0
+            .current
+            .entry(map_key)
+            .or_insert_with(|| {
+                self.instrument
+                    .series
+                    .fetch_add(1, atomic::Ordering::Relaxed);
+                Arc::new(Record {
+                    update_count: NumberKind::U64.zero().to_atomic(),
+                    collected_count: NumberKind::U64.zero().to_atomic(),
+                    labels: LabelSet::from_labels(labels.iter().cloned()),
+                    instrument: self.clone(),
+                    overflow,
+                    current: self
+                        .instrument
+                        .meter
+                        .0
+                        .processor
+                        .aggregation_selector()
+                        .aggregator_for(&self.instrument.descriptor),
+                    checkpoint: self
+                        .instrument
+                        .meter
+                        .0
+                        .processor
+                        .aggregation_selector()
+                        .aggregator_for(&self.instrument.descriptor),
+                })
+            })
+            .value()
+            .clone()
+    }
+}
 
-        record
+impl SyncInstrument {
+    /// Record `number` measured with `labels` in the context `cx`.
+    fn record_in_context(&self, number: &Number, labels: &[KeyValue], cx: &Context) {
+        let handle = self.acquire_handle(labels);
+        match &self.instrument.attribute_filter {
+            Some(filter) => handle.record_in_context(number, cx, &filter.dropped(labels)),
+            None => handle.record_in_context(number, cx, &[]),
+        }
     }
 }
 
@@ -359,8 +489,10 @@ impl sdk_api::SyncInstrumentCore for SyncInstrument {
         self.acquire_handle(labels)
     }
     fn record_one(&self, number: Number, labels: &'_ [KeyValue]) {
-        let handle = self.acquire_handle(labels);
-        handle.record_one(number)
+        if !global::is_telemetry_enabled() {
+            return;
+        }
+        Context::map_current(|cx| self.record_in_context(&number, labels, cx))
     }
     fn as_any(&self) -> &dyn Any {
         self
@@ -385,8 +517,22 @@ impl AsyncInstrument {
         if let Err(err) = aggregators::range_test(number, &self.instrument.descriptor) {
             global::handle_error(err);
         }
+        let filtered;
+        let labels = match &self.instrument.attribute_filter {
+            Some(filter) => {
+                filtered = filter.filter_set(labels);
+                &filtered
+            }
+            None => labels,
+        };
         if let Some(recorder) = self.get_recorder(labels) {
-            if let Err(err) = recorder.update(number, &self.instrument.descriptor) {
+            if let Err(err) = recorder.update_with_clock(
+                number,
+                &self.instrument.descriptor,
+                &Context::new(),
+                &[],
+                self.instrument.meter.0.clock.as_ref(),
+            ) {
                 global::handle_error(err)
             }
         }
@@ -394,9 +540,21 @@ impl AsyncInstrument {
 
     fn get_recorder(&self, labels: &LabelSet) -> Option<Arc<dyn Aggregator + Send + Sync>> {
         self.recorders.lock().map_or(None, |mut recorders| {
-            let mut hasher = FnvHasher::default();
-            hash_labels(&mut hasher, labels.into_iter());
-            let label_hash = hasher.finish();
+            let mut labels = labels;
+            let mut label_hash = hash_label_set(labels);
+            let series = recorders.as_ref().map_or(0, HashMap::len);
+            let known = recorders
+                .as_ref()
+                .map_or(false, |rec| rec.contains_key(&label_hash));
+            let overflow;
+            if !known && !self.instrument.admits_new_series(series) {
+                self.instrument
+                    .overflowed
+                    .fetch_add(1, atomic::Ordering::Relaxed);
+                overflow = LabelSet::from_labels(overflow_labels().iter().cloned());
+                labels = &overflow;
+                label_hash = hash_label_set(labels);
+            }
             if let Some(recorder) = recorders.as_mut().and_then(|rec| rec.get_mut(&label_hash)) {
                 let current_epoch = self
                     .instrument
@@ -485,6 +643,10 @@ struct Record {
     /// TODO: look at perf here.
     labels: LabelSet,
 
+    /// Whether this record holds the measurements of new attribute sets beyond
+    /// the cardinality limit of the instrument.
+    overflow: bool,
+
     /// The corresponding instrument.
     instrument: SyncInstrument,
 
@@ -494,14 +656,22 @@ struct Record {
     checkpoint: Option<Arc<dyn Aggregator + Send + Sync>>,
 }
 
-impl sdk_api::SyncBoundInstrumentCore for Record {
-    fn record_one<'a>(&self, number: Number) {
+impl Record {
+    /// Record `number` in the context `cx`, measured with the labels of this
+    /// record and the `filtered_attributes` dropped from them.
+    fn record_in_context(&self, number: &Number, cx: &Context, filtered_attributes: &[KeyValue]) {
         // check if the instrument is disabled according to the AggregatorSelector.
         if let Some(recorder) = &self.current {
-            if let Err(err) =
-                aggregators::range_test(&number, &self.instrument.instrument.descriptor)
-                    .and_then(|_| recorder.update(&number, &self.instrument.instrument.descriptor))
-            {
+            let descriptor = &self.instrument.instrument.descriptor;
+            if let Err(err) = aggregators::range_test(number, descriptor).and_then(|_| {
+                recorder.update_with_clock(
+                    number,
+                    descriptor,
+                    cx,
+                    filtered_attributes,
+                    self.instrument.instrument.meter.0.clock.as_ref(),
+                )
+            }) {
                 global::handle_error(err);
                 return;
             }
@@ -513,10 +683,76 @@ impl sdk_api::SyncBoundInstrumentCore for Record {
     }
 }
 
+impl sdk_api::SyncBoundInstrumentCore for Record {
+    fn record_one<'a>(&self, number: Number) {
+        if !global::is_telemetry_enabled() {
+            return;
+        }
+        Context::map_current(|cx| self.record_in_context(&number, cx, &[]))
+    }
+}
+
 #[derive(Debug)]
 struct Instrument {
     descriptor: Descriptor,
     meter: Accumulator,
+    /// The attributes kept by the view applied to this instrument.
+    attribute_filter: Option<AttributeFilter>,
+    /// The maximum number of attribute sets recorded, including the overflow
+    /// attribute set.
+    cardinality_limit: usize,
+    /// The number of attribute sets of the records of this instrument.
+    series: AtomicUsize,
+    /// The number of measurements recorded in the overflow attribute set since
+    /// the last collection.
+    overflowed: AtomicU64,
+}
+
+impl Instrument {
+    fn new(descriptor: Descriptor, meter: &Accumulator) -> Self {
+        let (descriptor, attribute_filter, cardinality_limit) = meter.0.views.apply(descriptor);
+        Instrument {
+            descriptor,
+            meter: meter.clone(),
+            attribute_filter,
+            cardinality_limit: cardinality_limit.unwrap_or(meter.0.cardinality_limit),
+            series: AtomicUsize::new(0),
+            overflowed: AtomicU64::new(0),
+        }
+    }
+
+    /// Whether a new attribute set can be recorded while `series` are, the
+    /// last one left is taken by the overflow attribute set.
+    fn admits_new_series(&self, series: usize) -> bool {
+        series + 1 < self.cardinality_limit
+    }
+
+    /// Report the measurements recorded in the overflow attribute set since
+    /// the last report.
+    fn report_overflow(&self) {
+        let overflowed = self.overflowed.swap(0, atomic::Ordering::Relaxed);
+        if overflowed > 0 {
+            global::handle_error(MetricsError::Other(format!(
+                "instrument {} exceeded its cardinality limit of {} attribute sets, \
+                 {} measurements with new attribute sets were recorded with {}=true",
+                self.descriptor.name(),
+                self.cardinality_limit,
+                overflowed,
+                OVERFLOW_KEY
+            )));
+        }
+    }
+}
+
+/// The attribute set recording measurements beyond the cardinality limit.
+fn overflow_labels() -> [KeyValue; 1] {
+    [KeyValue::new(OVERFLOW_KEY, true)]
+}
+
+fn hash_label_set(labels: &LabelSet) -> u64 {
+    let mut hasher = FnvHasher::default();
+    hash_labels(&mut hasher, labels.into_iter());
+    hasher.finish()
 }
 
 impl sdk_api::InstrumentCore for Instrument {
@@ -531,28 +767,26 @@ impl sdk_api::MeterCore for Accumulator {
         descriptor: Descriptor,
     ) -> Result<Arc<dyn sdk_api::SyncInstrumentCore>> {
         Ok(Arc::new(SyncInstrument {
-            instrument: Arc::new(Instrument {
-                descriptor,
-                meter: self.clone(),
-            }),
+            instrument: Arc::new(Instrument::new(descriptor, self)),
         }))
     }
 
     fn record_batch_with_context(
         &self,
-        _cx: &Context,
+        cx: &Context,
         labels: &[KeyValue],
         measurements: Vec<Measurement>,
     ) {
-        for measure in measurements.into_iter() {
+        if !global::is_telemetry_enabled() {
+            return;
+        }
+        for measure in measurements.iter() {
             if let Some(instrument) = measure
                 .instrument()
                 .as_any()
                 .downcast_ref::<SyncInstrument>()
             {
-                let handle = instrument.acquire_handle(labels);
-
-                handle.record_one(measure.into_number());
+                instrument.record_in_context(measure.number(), labels, cx);
             }
         }
     }
@@ -563,10 +797,7 @@ impl sdk_api::MeterCore for Accumulator {
         runner: Option<AsyncRunner>,
     ) -> Result<Arc<dyn sdk_api::AsyncInstrumentCore>> {
         let instrument = Arc::new(AsyncInstrument {
-            instrument: Arc::new(Instrument {
-                descriptor,
-                meter: self.clone(),
-            }),
+            instrument: Arc::new(Instrument::new(descriptor, self)),
             recorders: Arc::new(Mutex::new(None)),
         });
 
@@ -579,3 +810,85 @@ impl sdk_api::MeterCore for Accumulator {
         self.0.register_runner(runner)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::metrics::{MeterProvider, NumberKind};
+    use crate::sdk::export::metrics::{CheckpointSet, ExportKindSelector, Sum};
+    use crate::sdk::metrics::{aggregators, controllers, selectors};
+    use crate::{global, KeyValue};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn skips_measurements_while_telemetry_disabled() {
+        let mut controller = controllers::pull(
+            Box::new(selectors::simple::Selector::Inexpensive),
+            Box::new(ExportKindSelector::Delta),
+        )
+        .with_cache_period(Duration::from_secs(0))
+        .build();
+        let meter = controller.provider().meter("telemetry-enabled", None);
+        let counter = meter.u64_counter("counter").init();
+        let bound = counter.bind(&[]);
+        let batched = meter.u64_counter("batched").init();
+        let observer_runs = Arc::new(AtomicUsize::new(0));
+        let runs = observer_runs.clone();
+        let _observer = meter
+            .u64_sum_observer("observed", move |result| {
+                runs.fetch_add(1, Ordering::SeqCst);
+                result.observe(1, &[]);
+            })
+            .init();
+        let labels = [KeyValue::new("a", "b")];
+
+        let mut sums = || {
+            controller.collect().unwrap();
+            let mut sums = Vec::new();
+            controller
+                .try_for_each(&ExportKindSelector::Delta, &mut |record| {
+                    let sum = record
+                        .aggregator()
+                        .unwrap()
+                        .as_any()
+                        .downcast_ref::<aggregators::SumAggregator>()
+                        .unwrap()
+                        .sum()?
+                        .to_u64(&NumberKind::U64);
+                    sums.push((record.descriptor().name().to_string(), sum));
+                    Ok(())
+                })
+                .unwrap();
+            sums.retain(|(_, sum)| *sum > 0);
+            sums.sort();
+            sums
+        };
+
+        // record and collect while disabled, keeping the window other tests
+        // running in parallel could observe as short as possible
+        global::set_telemetry_enabled(false);
+        counter.add(1, &labels);
+        bound.add(1);
+        meter.record_batch(&labels, vec![batched.measurement(1)]);
+        let disabled = sums();
+        global::set_telemetry_enabled(true);
+
+        assert_eq!(disabled, Vec::new());
+        assert_eq!(observer_runs.load(Ordering::SeqCst), 0);
+
+        counter.add(1, &labels);
+        bound.add(1);
+        meter.record_batch(&labels, vec![batched.measurement(1)]);
+        assert_eq!(
+            sums(),
+            vec![
+                ("batched".to_string(), 1),
+                ("counter".to_string(), 1),
+                ("counter".to_string(), 1),
+                ("observed".to_string(), 1),
+            ]
+        );
+        assert_eq!(observer_runs.load(Ordering::SeqCst), 1);
+    }
+}
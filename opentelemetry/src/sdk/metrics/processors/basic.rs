@@ -4,7 +4,7 @@ use crate::sdk::{
         ExportKind, ExportKindFor, LockedProcessor, Processor, Record, Subtractor,
     },
     metrics::aggregators::SumAggregator,
-    Resource,
+    Clock, Resource, SystemClock,
 };
 use crate::{
     labels::{hash_labels, LabelSet},
@@ -26,6 +26,7 @@ pub fn basic(
         aggregator_selector,
         export_selector,
         state: Mutex::new(BasicProcessorState::with_memory(memory)),
+        clock: Arc::new(SystemClock),
     }
 }
 
@@ -35,9 +36,23 @@ pub struct BasicProcessor {
     aggregator_selector: Box<dyn AggregatorSelector + Send + Sync>,
     export_selector: Box<dyn ExportKindFor + Send + Sync>,
     state: Mutex<BasicProcessorState>,
+    clock: Arc<dyn Clock>,
 }
 
 impl BasicProcessor {
+    /// Read the timestamps of the collection intervals from `clock`, the
+    /// [`SystemClock`] by default.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        if let Ok(state) = self.state.get_mut() {
+            let now = clock.now();
+            state.process_start = now;
+            state.interval_start = now;
+            state.interval_end = now;
+        }
+        self.clock = clock;
+        self
+    }
+
     /// Lock this processor to return a mutable locked processor
     pub fn lock(&self) -> Result<BasicLockedProcessor<'_>> {
         self.state
@@ -188,7 +203,7 @@ impl Checkpointer for BasicLockedProcessor<'_> {
     }
 
     fn finish_collection(&mut self) -> Result<()> {
-        self.state.interval_end = crate::time::now();
+        self.state.interval_end = self.parent.clock.now();
         if self.state.started_collection != self.state.finished_collection.wrapping_add(1) {
             return Err(MetricsError::InconsistentState);
         }
@@ -410,3 +425,76 @@ struct StateValue {
     /// the last cumulative value.
     cumulative: Option<Arc<dyn Aggregator + Send + Sync>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{InstrumentKind, Number, NumberKind};
+    use crate::sdk::export::metrics::{accumulation, InstrumentKindSelector, Sum};
+    use crate::sdk::metrics::{aggregators, selectors};
+    use crate::KeyValue;
+
+    #[test]
+    fn export_kind_per_instrument_kind() -> Result<()> {
+        let export_selector = InstrumentKindSelector::new(ExportKind::Cumulative)
+            .with_export_kind(InstrumentKind::Counter, ExportKind::Delta)
+            .with_export_kind(InstrumentKind::SumObserver, ExportKind::Delta);
+        let processor = basic(
+            Box::new(selectors::simple::Selector::Inexpensive),
+            Box::new(export_selector.clone()),
+            false,
+        );
+        let descriptor = |name: &str, kind| {
+            Descriptor::new(name.to_string(), "test", None, kind, NumberKind::I64)
+        };
+        let counter = descriptor("counter", InstrumentKind::Counter);
+        let up_down_counter = descriptor("up_down_counter", InstrumentKind::UpDownCounter);
+        let sum_observer = descriptor("sum_observer", InstrumentKind::SumObserver);
+        let labels = LabelSet::from_labels(vec![KeyValue::new("key", "value")]);
+        let resource = Resource::empty();
+
+        let collect = |values: [i64; 3]| -> Result<Vec<(String, i64)>> {
+            let mut locked = processor.lock()?;
+            locked.start_collection();
+            for (descriptor, value) in [&counter, &up_down_counter, &sum_observer]
+                .iter()
+                .zip(values.iter())
+            {
+                let aggregator: Arc<dyn Aggregator + Send + Sync> = Arc::new(aggregators::sum());
+                aggregator.update(&Number::from(*value), descriptor)?;
+                locked.process(accumulation(descriptor, &labels, &resource, &aggregator))?;
+            }
+            locked.finish_collection()?;
+
+            let mut sums = Vec::new();
+            locked
+                .checkpoint_set()
+                .try_for_each(&export_selector, &mut |record| {
+                    let sum = record
+                        .aggregator()
+                        .and_then(|agg| agg.as_any().downcast_ref::<SumAggregator>())
+                        .ok_or(MetricsError::NoDataCollected)?
+                        .sum()?;
+                    sums.push((
+                        record.descriptor().name().to_string(),
+                        sum.to_i64(&NumberKind::I64),
+                    ));
+                    Ok(())
+                })?;
+            sums.sort();
+            Ok(sums)
+        };
+
+        let expected = |values: [i64; 3]| {
+            vec![
+                ("counter".to_string(), values[0]),
+                ("sum_observer".to_string(), values[2]),
+                ("up_down_counter".to_string(), values[1]),
+            ]
+        };
+        assert_eq!(collect([5, 2, 10])?, expected([5, 2, 10]));
+        // counter and sum observer deltas, cumulative up down counter
+        assert_eq!(collect([3, 4, 15])?, expected([3, 6, 5]));
+        Ok(())
+    }
+}
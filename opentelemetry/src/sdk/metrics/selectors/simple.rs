@@ -23,7 +23,16 @@ pub enum Selector {
     /// A simple aggregation selector that uses sum, and histogram aggregators
     /// for metrics. This selector uses more memory than `Inexpensive` because
     /// it uses a counter per bucket.
+    ///
+    /// The given bucket boundaries are used for instruments without
+    /// boundaries of their own, see [`Descriptor::histogram_boundaries`].
+    ///
+    /// [`Descriptor::histogram_boundaries`]: crate::metrics::Descriptor::histogram_boundaries
     Histogram(Vec<f64>),
+    /// A simple aggregation selector that uses sum, and exponential histogram
+    /// aggregators for metrics. The buckets of the histograms adapt to the
+    /// recorded values, no boundaries need to be chosen upfront.
+    ExponentialHistogram(aggregators::ExponentialHistogramConfig),
 }
 
 impl AggregatorSelector for Selector {
@@ -52,10 +61,18 @@ impl AggregatorSelector for Selector {
             Selector::Histogram(boundaries) => match descriptor.instrument_kind() {
                 InstrumentKind::ValueObserver => Some(Arc::new(aggregators::last_value())),
                 InstrumentKind::ValueRecorder => {
+                    let boundaries = descriptor.histogram_boundaries().unwrap_or(boundaries);
                     Some(Arc::new(aggregators::histogram(descriptor, boundaries)))
                 }
                 _ => Some(Arc::new(aggregators::sum())),
             },
+            Selector::ExponentialHistogram(config) => match descriptor.instrument_kind() {
+                InstrumentKind::ValueObserver => Some(Arc::new(aggregators::last_value())),
+                InstrumentKind::ValueRecorder => Some(Arc::new(
+                    aggregators::exponential_histogram(descriptor, config),
+                )),
+                _ => Some(Arc::new(aggregators::sum())),
+            },
         }
     }
 }
@@ -0,0 +1,564 @@
+//! # Views
+//!
+//! Views customize the metrics exported for instruments, without changing the
+//! instrumentation recording them. This allows e.g. dropping high cardinality
+//! attributes recorded by the instrumentation of a dependency, or exporting
+//! its instruments under the names expected by a backend.
+//!
+//! A view selects instruments by name, instrumentation library and instrument
+//! kind, and overrides the name and description of the exported metric, the
+//! aggregation and the attributes kept. Views are registered on the
+//! controller providing the meters, the first view selecting an instrument is
+//! applied to it. Instruments selected by no view are exported unchanged.
+//!
+//! # Examples
+//!
+//! ```
+//! use opentelemetry::sdk::{
+//!     export::metrics::ExportKindSelector,
+//!     metrics::{controllers, selectors, Aggregation, View},
+//! };
+//!
+//! let controller = controllers::pull(
+//!     Box::new(selectors::simple::Selector::Inexpensive),
+//!     Box::new(ExportKindSelector::Cumulative),
+//! )
+//! // keep only the route of the latency of requests
+//! .with_view(
+//!     View::new("http.server.duration")
+//!         .with_attribute_keys(vec!["http.route"])
+//!         .with_aggregation(Aggregation::Histogram(vec![0.1, 0.5, 1.0, 5.0])),
+//! )
+//! // drop the metrics of a chatty dependency
+//! .with_view(
+//!     View::new("*")
+//!         .with_instrumentation_library("chatty_dep")
+//!         .with_aggregation(Aggregation::Drop),
+//! )
+//! .build();
+//! ```
+use crate::global;
+use crate::labels::LabelSet;
+use crate::metrics::{Descriptor, InstrumentKind, MetricsError};
+use crate::sdk::export::metrics::{Aggregator, AggregatorSelector};
+use crate::sdk::metrics::aggregators;
+use crate::{Key, KeyValue};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// The aggregation of the measurements of an instrument selected by a view.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Aggregation {
+    /// Measurements are dropped, the instrument is not exported.
+    Drop,
+    /// Measurements are summed up.
+    Sum,
+    /// The last measurement is exported.
+    LastValue,
+    /// The minimum, maximum, sum and count of the measurements are exported.
+    MinMaxSumCount,
+    /// Measurements are counted in buckets with the given boundaries.
+    Histogram(Vec<f64>),
+    /// Measurements are counted in buckets with exponentially growing
+    /// boundaries, adapting to the range of the measurements.
+    ExponentialHistogram(aggregators::ExponentialHistogramConfig),
+    /// All measurements are exported.
+    Exact,
+}
+
+impl Aggregation {
+    fn aggregator_for(
+        &self,
+        descriptor: &Descriptor,
+        exemplars: Option<&aggregators::ExemplarReservoir>,
+    ) -> Option<Arc<dyn Aggregator + Send + Sync>> {
+        match self {
+            Aggregation::Drop => None,
+            Aggregation::Sum => match exemplars {
+                Some(reservoir) => Some(Arc::new(
+                    aggregators::sum().with_exemplar_reservoir(reservoir),
+                )),
+                None => Some(Arc::new(aggregators::sum())),
+            },
+            Aggregation::LastValue => Some(Arc::new(aggregators::last_value())),
+            Aggregation::MinMaxSumCount => {
+                Some(Arc::new(aggregators::min_max_sum_count(descriptor)))
+            }
+            Aggregation::Histogram(boundaries) => {
+                let histogram = aggregators::histogram(descriptor, boundaries);
+                match exemplars {
+                    Some(reservoir) => Some(Arc::new(histogram.with_exemplar_reservoir(reservoir))),
+                    None => Some(Arc::new(histogram)),
+                }
+            }
+            Aggregation::ExponentialHistogram(config) => Some(Arc::new(
+                aggregators::exponential_histogram(descriptor, config),
+            )),
+            Aggregation::Exact => Some(Arc::new(aggregators::array())),
+        }
+    }
+}
+
+/// Selects instruments and customizes the metrics exported for them, see the
+/// [module level documentation].
+///
+/// [module level documentation]: self
+#[derive(Clone, Debug)]
+pub struct View {
+    instrument_name: String,
+    instrumentation_library: Option<String>,
+    instrument_kind: Option<InstrumentKind>,
+    name: Option<String>,
+    description: Option<String>,
+    aggregation: Option<Aggregation>,
+    exemplar_reservoir: Option<aggregators::ExemplarReservoir>,
+    attribute_keys: Option<Vec<Key>>,
+    cardinality_limit: Option<usize>,
+}
+
+impl View {
+    /// Create a view selecting the instruments named `instrument_name`.
+    ///
+    /// The name may contain the wildcards `*`, matching any sequence of
+    /// characters, and `?`, matching any single character.
+    pub fn new<T: Into<String>>(instrument_name: T) -> Self {
+        View {
+            instrument_name: instrument_name.into(),
+            instrumentation_library: None,
+            instrument_kind: None,
+            name: None,
+            description: None,
+            aggregation: None,
+            exemplar_reservoir: None,
+            attribute_keys: None,
+            cardinality_limit: None,
+        }
+    }
+
+    /// Only select instruments of the instrumentation library `name`.
+    pub fn with_instrumentation_library<T: Into<String>>(mut self, name: T) -> Self {
+        self.instrumentation_library = Some(name.into());
+        self
+    }
+
+    /// Only select instruments of kind `kind`.
+    pub fn with_instrument_kind(mut self, kind: InstrumentKind) -> Self {
+        self.instrument_kind = Some(kind);
+        self
+    }
+
+    /// Export the selected instrument under `name`.
+    ///
+    /// Ignored if the view can select several instruments, i.e. if the
+    /// instrument name contains wildcards.
+    pub fn with_name<T: Into<String>>(mut self, name: T) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Export the selected instruments with `description`.
+    pub fn with_description<T: Into<String>>(mut self, description: T) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Aggregate the measurements of the selected instruments with
+    /// `aggregation`, instead of the aggregation of the aggregator selector.
+    pub fn with_aggregation(mut self, aggregation: Aggregation) -> Self {
+        self.aggregation = Some(aggregation);
+        self
+    }
+
+    /// Keep the measurements selected by `reservoir` as exemplars of the
+    /// metrics of the selected instruments.
+    ///
+    /// Only the `Sum` and `Histogram` aggregations of the view keep exemplars,
+    /// the view must select one of them.
+    pub fn with_exemplar_reservoir(mut self, reservoir: aggregators::ExemplarReservoir) -> Self {
+        self.exemplar_reservoir = Some(reservoir);
+        self
+    }
+
+    /// Only keep the attributes with the given keys, measurements differing
+    /// in other attributes are aggregated together.
+    pub fn with_attribute_keys<I, K>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        K: Into<Key>,
+    {
+        self.attribute_keys = Some(keys.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Record at most `limit` attribute sets for each selected instrument,
+    /// instead of the limit configured on the controller.
+    ///
+    /// Measurements with new attribute sets beyond the limit are recorded with
+    /// the single attribute `otel.metric.overflow=true`.
+    pub fn with_cardinality_limit(mut self, limit: usize) -> Self {
+        self.cardinality_limit = Some(limit);
+        self
+    }
+
+    fn selects(&self, descriptor: &Descriptor) -> bool {
+        let library_selected = match &self.instrumentation_library {
+            Some(name) => name == descriptor.instrumentation_name(),
+            None => true,
+        };
+        let kind_selected = match &self.instrument_kind {
+            Some(kind) => kind == descriptor.instrument_kind(),
+            None => true,
+        };
+        library_selected
+            && kind_selected
+            && wildcard_match(
+                self.instrument_name.as_bytes(),
+                descriptor.name().as_bytes(),
+            )
+    }
+
+    fn has_wildcard(&self) -> bool {
+        self.instrument_name.contains(&['*', '?'][..])
+    }
+}
+
+/// Whether `name` matches `pattern` containing `*` and `?` wildcards.
+fn wildcard_match(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.split_first(), name.split_first()) {
+        (None, None) => true,
+        (Some((b'*', rest)), _) => {
+            wildcard_match(rest, name) || (!name.is_empty() && wildcard_match(pattern, &name[1..]))
+        }
+        (Some((b'?', rest)), Some((_, name_rest))) => wildcard_match(rest, name_rest),
+        (Some((p, rest)), Some((n, name_rest))) => p == n && wildcard_match(rest, name_rest),
+        _ => false,
+    }
+}
+
+/// Attribute keys kept by the view applied to an instrument.
+#[derive(Clone, Debug)]
+pub(crate) struct AttributeFilter(Arc<[Key]>);
+
+impl AttributeFilter {
+    pub(crate) fn filter<'a>(&self, labels: &'a [KeyValue]) -> Cow<'a, [KeyValue]> {
+        if labels.iter().all(|kv| self.0.contains(&kv.key)) {
+            return Cow::Borrowed(labels);
+        }
+        Cow::Owned(
+            labels
+                .iter()
+                .filter(|kv| self.0.contains(&kv.key))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// The attributes of `labels` that are not kept.
+    pub(crate) fn dropped(&self, labels: &[KeyValue]) -> Vec<KeyValue> {
+        labels
+            .iter()
+            .filter(|kv| !self.0.contains(&kv.key))
+            .cloned()
+            .collect()
+    }
+
+    pub(crate) fn filter_set(&self, labels: &LabelSet) -> LabelSet {
+        LabelSet::from_labels(
+            labels
+                .iter()
+                .filter(|(key, _)| self.0.contains(key))
+                .map(|(key, value)| KeyValue::new(key.clone(), value.clone())),
+        )
+    }
+}
+
+/// The views registered on a controller, with the aggregations they selected
+/// for the instruments created so far.
+#[derive(Debug, Default)]
+pub(crate) struct Views {
+    views: Vec<View>,
+    /// Aggregations and exemplar reservoirs by the attribute hash of the
+    /// exported descriptors.
+    aggregations: RwLock<HashMap<u64, (Aggregation, Option<aggregators::ExemplarReservoir>)>>,
+}
+
+impl Views {
+    pub(crate) fn new(views: Vec<View>) -> Self {
+        Views {
+            views,
+            aggregations: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Apply the first view selecting the instrument of `descriptor`, returns
+    /// the descriptor of the exported metric, the attributes to keep and the
+    /// cardinality limit.
+    pub(crate) fn apply(
+        &self,
+        descriptor: Descriptor,
+    ) -> (Descriptor, Option<AttributeFilter>, Option<usize>) {
+        let view = match self.views.iter().find(|view| view.selects(&descriptor)) {
+            Some(view) => view,
+            None => return (descriptor, None, None),
+        };
+
+        let mut descriptor = match &view.name {
+            Some(name) if view.has_wildcard() => {
+                global::handle_error(MetricsError::Other(format!(
+                    "view selecting instruments {} cannot rename them to {}",
+                    view.instrument_name, name
+                )));
+                descriptor
+            }
+            Some(name) => descriptor.with_name(name.clone()),
+            None => descriptor,
+        };
+        if let Some(description) = &view.description {
+            descriptor.set_description(description.clone());
+        }
+        match (&view.aggregation, &view.exemplar_reservoir) {
+            (Some(aggregation), reservoir) => {
+                if let Ok(mut aggregations) = self.aggregations.write() {
+                    aggregations
+                        .entry(descriptor.attribute_hash())
+                        .or_insert_with(|| (aggregation.clone(), reservoir.clone()));
+                }
+            }
+            (None, Some(_)) => global::handle_error(MetricsError::Other(format!(
+                "view selecting instruments {} needs an aggregation to keep exemplars",
+                view.instrument_name
+            ))),
+            (None, None) => (),
+        }
+        let filter = view
+            .attribute_keys
+            .as_ref()
+            .map(|keys| AttributeFilter(keys.clone().into()));
+
+        (descriptor, filter, view.cardinality_limit)
+    }
+}
+
+/// Selects the aggregators of the views registered on a controller, falling
+/// back to the configured aggregator selector.
+#[derive(Debug)]
+pub(crate) struct ViewAggregatorSelector {
+    views: Arc<Views>,
+    selector: Box<dyn AggregatorSelector + Send + Sync>,
+}
+
+impl ViewAggregatorSelector {
+    pub(crate) fn new(
+        views: Arc<Views>,
+        selector: Box<dyn AggregatorSelector + Send + Sync>,
+    ) -> Self {
+        ViewAggregatorSelector { views, selector }
+    }
+}
+
+impl AggregatorSelector for ViewAggregatorSelector {
+    fn aggregator_for(&self, descriptor: &Descriptor) -> Option<Arc<dyn Aggregator + Send + Sync>> {
+        let aggregation = self
+            .views
+            .aggregations
+            .read()
+            .ok()
+            .and_then(|aggregations| aggregations.get(&descriptor.attribute_hash()).cloned());
+        match aggregation {
+            Some((aggregation, reservoir)) => {
+                aggregation.aggregator_for(descriptor, reservoir.as_ref())
+            }
+            None => self.selector.aggregator_for(descriptor),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{MeterProvider, NumberKind};
+    use crate::sdk::export::metrics::{CheckpointSet, ExportKindSelector, Sum};
+    use crate::sdk::metrics::{controllers, selectors};
+    use std::time::Duration;
+
+    #[test]
+    fn match_wildcards() {
+        for (pattern, name, expected) in vec![
+            ("http.server.duration", "http.server.duration", true),
+            ("http.server.duration", "http.server.durations", false),
+            ("http.*", "http.server.duration", true),
+            ("http.*", "http.", true),
+            ("http.*", "rpc.server.duration", false),
+            ("*.duration", "http.server.duration", true),
+            ("*", "", true),
+            ("http.?erver.*", "http.server.duration", true),
+            ("http.?erver.*", "http.erver.duration", false),
+        ] {
+            assert_eq!(
+                wildcard_match(pattern.as_bytes(), name.as_bytes()),
+                expected,
+                "{} matching {}",
+                pattern,
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn apply_first_selecting_view() {
+        let views = Views::new(vec![
+            View::new("requests")
+                .with_instrumentation_library("server")
+                .with_name("http.server.requests")
+                .with_description("served requests")
+                .with_attribute_keys(vec!["route"]),
+            View::new("*")
+                .with_instrument_kind(InstrumentKind::ValueRecorder)
+                .with_aggregation(Aggregation::Drop),
+        ]);
+        let descriptor = |name: &str, library, kind| {
+            Descriptor::new(name.to_string(), library, None, kind, NumberKind::U64)
+        };
+
+        let (requests, filter, _) =
+            views.apply(descriptor("requests", "server", InstrumentKind::Counter));
+        assert_eq!(requests.name(), "http.server.requests");
+        assert_eq!(
+            requests.description().map(String::as_str),
+            Some("served requests")
+        );
+        let labels = [KeyValue::new("route", "/"), KeyValue::new("user", "1")];
+        assert_eq!(
+            filter.unwrap().filter(&labels).as_ref(),
+            &[KeyValue::new("route", "/")]
+        );
+
+        let (client_requests, filter, _) =
+            views.apply(descriptor("requests", "client", InstrumentKind::Counter));
+        assert_eq!(client_requests.name(), "requests");
+        assert!(filter.is_none());
+
+        let (duration, _, _) = views.apply(descriptor(
+            "duration",
+            "server",
+            InstrumentKind::ValueRecorder,
+        ));
+        let selector = ViewAggregatorSelector::new(
+            Arc::new(views),
+            Box::new(crate::sdk::metrics::selectors::simple::Selector::Inexpensive),
+        );
+        assert!(selector.aggregator_for(&duration).is_none());
+        assert!(selector.aggregator_for(&requests).is_some());
+    }
+
+    #[test]
+    fn export_instruments_through_views() {
+        let mut controller = controllers::pull(
+            Box::new(selectors::simple::Selector::Exact),
+            Box::new(ExportKindSelector::Cumulative),
+        )
+        .with_cache_period(Duration::from_secs(0))
+        .with_view(
+            View::new("requests")
+                .with_name("http.server.requests")
+                .with_attribute_keys(vec!["route"]),
+        )
+        .with_view(View::new("duration").with_aggregation(Aggregation::Drop))
+        .build();
+        let meter = controller.provider().meter("server", None);
+        let requests = meter.u64_counter("requests").init();
+        requests.add(
+            1,
+            &[KeyValue::new("route", "/"), KeyValue::new("user", "1")],
+        );
+        requests.add(
+            2,
+            &[KeyValue::new("route", "/"), KeyValue::new("user", "2")],
+        );
+        let duration = meter.i64_value_recorder("duration").init();
+        duration.record(10, &[]);
+        controller.collect().unwrap();
+
+        let mut exported = Vec::new();
+        controller
+            .try_for_each(&ExportKindSelector::Cumulative, &mut |record| {
+                let sum = record
+                    .aggregator()
+                    .and_then(|agg| agg.as_any().downcast_ref::<aggregators::SumAggregator>())
+                    .map(Sum::sum)
+                    .transpose()?;
+                exported.push((
+                    record.descriptor().name().to_string(),
+                    record.labels().iter().count(),
+                    sum.map(|sum| sum.to_u64(&NumberKind::U64)),
+                ));
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(
+            exported,
+            vec![("http.server.requests".to_string(), 1, Some(3))]
+        );
+    }
+
+    #[test]
+    fn keep_exemplars_of_sampled_measurements() {
+        use crate::sdk::export::metrics::Exemplars;
+        use crate::testing::trace::TestSpan;
+        use crate::trace::{
+            SpanContext, SpanId, TraceContextExt, TraceId, TraceState, TRACE_FLAG_SAMPLED,
+        };
+        use crate::Context;
+
+        let mut controller = controllers::pull(
+            Box::new(selectors::simple::Selector::Exact),
+            Box::new(ExportKindSelector::Cumulative),
+        )
+        .with_cache_period(Duration::from_secs(0))
+        .with_view(
+            View::new("duration")
+                .with_attribute_keys(vec!["route"])
+                .with_aggregation(Aggregation::Histogram(vec![10.0]))
+                .with_exemplar_reservoir(aggregators::ExemplarReservoir::AlignedHistogram),
+        )
+        .build();
+        let meter = controller.provider().meter("server", None);
+        let duration = meter.u64_value_recorder("duration").init();
+        let labels = [KeyValue::new("route", "/"), KeyValue::new("user", "1")];
+        duration.record(1, &labels);
+        {
+            let _guard = Context::new()
+                .with_span(TestSpan(SpanContext::new(
+                    TraceId::from_u128(1),
+                    SpanId::from_u64(2),
+                    TRACE_FLAG_SAMPLED,
+                    false,
+                    TraceState::default(),
+                )))
+                .attach();
+            duration.record(20, &labels);
+        }
+        controller.collect().unwrap();
+
+        let mut exemplars = Vec::new();
+        controller
+            .try_for_each(&ExportKindSelector::Cumulative, &mut |record| {
+                if let Some(histogram) = record.aggregator().and_then(|agg| {
+                    agg.as_any()
+                        .downcast_ref::<aggregators::HistogramAggregator>()
+                }) {
+                    exemplars.extend(histogram.exemplars()?);
+                }
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(exemplars.len(), 1);
+        assert_eq!(exemplars[0].value().to_u64(&NumberKind::U64), 20);
+        assert_eq!(exemplars[0].span_id(), SpanId::from_u64(2).to_byte_array());
+        assert_eq!(
+            exemplars[0].filtered_attributes(),
+            &[KeyValue::new("user", "1")]
+        );
+    }
+}
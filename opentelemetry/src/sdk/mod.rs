@@ -6,20 +6,35 @@
 //! facilitates the delivery of telemetry data to storage systems
 //! through `Exporter`s. These can be configured on `Tracer` and
 //! `Meter` creation.
+#[cfg(feature = "trace")]
+pub(crate) mod batch;
+pub mod clock;
 pub mod env;
 pub mod export;
 pub mod instrumentation;
+#[cfg(feature = "logs")]
+#[cfg_attr(docsrs, doc(cfg(feature = "logs")))]
+pub mod logs;
 #[cfg(feature = "metrics")]
 #[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
 pub mod metrics;
 #[cfg(feature = "trace")]
 #[cfg_attr(docsrs, doc(cfg(feature = "trace")))]
+pub mod presets;
+#[cfg(feature = "trace")]
+#[cfg_attr(docsrs, doc(cfg(feature = "trace")))]
 pub mod propagation;
 pub mod resource;
+#[cfg(feature = "testing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub mod testing;
 #[cfg(feature = "trace")]
 #[cfg_attr(docsrs, doc(cfg(feature = "trace")))]
 pub mod trace;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub use clock::MonotonicClock;
+pub use clock::{Clock, SystemClock};
 pub use env::EnvResourceDetector;
 pub use instrumentation::InstrumentationLibrary;
 pub use resource::Resource;
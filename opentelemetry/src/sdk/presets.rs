@@ -0,0 +1,112 @@
+//! # Tracer Provider Presets
+//!
+//! Preconfigured [`TracerProvider`] builders wiring the SDK components for
+//! common setups in one line. Each preset returns a [`Builder`], so anything
+//! it configures can still be replaced, and serves as a known-good baseline
+//! to compare a custom configuration against.
+//!
+//! * [`development`] prints every span to stdout as soon as it ends.
+//! * [`production`] exports spans in batches and samples traces by ratio,
+//!   configured by the standard environment variables.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use opentelemetry::sdk::presets;
+//! use opentelemetry::trace::{Tracer, TracerProvider as _};
+//!
+//! let provider = presets::development().build();
+//! provider.get_tracer("example", None).in_span("work", |_cx| {});
+//! ```
+//!
+//! [`TracerProvider`]: crate::sdk::trace::TracerProvider
+use crate::sdk::export::trace::{stdout, SpanExporter};
+use crate::sdk::trace::{config, Builder, Sampler, TracerProvider};
+use std::env;
+use std::str::FromStr;
+
+/// Ratio of traces sampled by the [`production`] preset.
+const OTEL_TRACES_SAMPLER_ARG: &str = "OTEL_TRACES_SAMPLER_ARG";
+/// Default ratio of traces sampled, as defined by the [specification].
+///
+/// [specification]: https://github.com/open-telemetry/opentelemetry-specification/blob/main/specification/sdk-environment-variables.md#general-sdk-configuration
+const OTEL_TRACES_SAMPLER_ARG_DEFAULT: f64 = 1.0;
+
+/// Maximum number of events, attributes and links per span recorded by the
+/// [`development`] preset.
+const DEVELOPMENT_SPAN_LIMIT: u32 = 32;
+
+/// Provider builder for local development.
+///
+/// * Spans are pretty printed to stdout by a simple span processor, as soon
+///   as they end.
+/// * All traces are sampled.
+/// * At most 32 events, attributes and links are recorded per span, so
+///   instrumentation recording too much shows up early.
+pub fn development() -> Builder {
+    TracerProvider::builder()
+        .with_simple_exporter(stdout::Exporter::new(std::io::stdout(), true))
+        .with_config(
+            config()
+                .with_default_sampler(Sampler::AlwaysOn)
+                .with_max_events_per_span(DEVELOPMENT_SPAN_LIMIT)
+                .with_max_attributes_per_span(DEVELOPMENT_SPAN_LIMIT)
+                .with_max_links_per_span(DEVELOPMENT_SPAN_LIMIT),
+        )
+}
+
+/// Provider builder for production deployments exporting to `exporter`.
+///
+/// * Spans are exported in batches by the batch span processor of the
+///   enabled runtime, configured by the `OTEL_BSP_*` environment variables.
+///   Without a runtime feature spans are exported as soon as they end.
+/// * Root spans are sampled with the ratio set by `OTEL_TRACES_SAMPLER_ARG`,
+///   all traces by default. Child spans follow the decision of their parent.
+/// * Span limits and the resource are read from the environment.
+pub fn production<E: SpanExporter + 'static>(exporter: E) -> Builder {
+    let ratio = env::var(OTEL_TRACES_SAMPLER_ARG)
+        .ok()
+        .and_then(|ratio| f64::from_str(ratio.trim()).ok())
+        .unwrap_or(OTEL_TRACES_SAMPLER_ARG_DEFAULT);
+
+    TracerProvider::builder()
+        .with_exporter(exporter)
+        .with_config(config().with_default_sampler(Sampler::ParentBased(Box::new(
+            Sampler::TraceIdRatioBased(ratio),
+        ))))
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::sdk::testing::trace::InMemorySpanExporter;
+
+    #[test]
+    fn development_preset() {
+        let provider = development().build();
+        let config = provider.config();
+        assert_eq!(format!("{:?}", config.default_sampler), "AlwaysOn");
+        assert_eq!(config.max_events_per_span, DEVELOPMENT_SPAN_LIMIT);
+        assert_eq!(config.max_attributes_per_span, DEVELOPMENT_SPAN_LIMIT);
+        assert_eq!(config.max_links_per_span, DEVELOPMENT_SPAN_LIMIT);
+        assert_eq!(provider.span_processors().len(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn production_preset() {
+        env::set_var(OTEL_TRACES_SAMPLER_ARG, "0.25");
+        let provider = production(InMemorySpanExporter::new()).build();
+        assert_eq!(
+            format!("{:?}", provider.config().default_sampler),
+            "ParentBased(TraceIdRatioBased(0.25))"
+        );
+
+        env::set_var(OTEL_TRACES_SAMPLER_ARG, "not a ratio");
+        let provider = production(InMemorySpanExporter::new()).build();
+        assert_eq!(
+            format!("{:?}", provider.config().default_sampler),
+            "ParentBased(TraceIdRatioBased(1.0))"
+        );
+        env::remove_var(OTEL_TRACES_SAMPLER_ARG);
+    }
+}
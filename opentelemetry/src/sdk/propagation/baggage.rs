@@ -300,6 +300,7 @@ mod tests {
             let cx = Context::current_with_baggage(kvm);
             propagator.inject_context(&cx, &mut injector);
             let header_value = injector.get(BAGGAGE_HEADER).unwrap();
+            assert_eq!(cx.baggage().encoded_len(), header_value.len());
             assert_eq!(header_parts.join(",").len(), header_value.len(),);
             for header_part in &header_parts {
                 assert!(header_value.contains(header_part),)
@@ -332,6 +333,7 @@ mod tests {
             let cx = Context::current_with_baggage(kvm);
             propagator.inject_context(&cx, &mut injector);
             let header_value = injector.get(BAGGAGE_HEADER).unwrap();
+            assert_eq!(cx.baggage().encoded_len(), header_value.len());
 
             assert_eq!(header_parts.join(",").len(), header_value.len());
             for header_part in &header_parts {
@@ -0,0 +1,84 @@
+//! ContainerResourceDetector
+//!
+//! Implementation of `ResourceDetector` to extract a `Resource` describing the
+//! container the process is running in.
+use crate::sdk::{resource::ResourceDetector, Resource};
+use crate::KeyValue;
+use std::fs;
+use std::time::Duration;
+
+/// The cgroup file of the current process.
+const CGROUP_PATH: &str = "/proc/self/cgroup";
+/// Length of a hex encoded container id.
+const CONTAINER_ID_LEN: usize = 64;
+
+/// Resource detector that reads the `container.id` from the cgroup of the
+/// current process.
+///
+/// This works for docker, containerd and cri-o runtimes on linux. An empty
+/// resource is returned if the process does not run in a container.
+#[derive(Debug, Default)]
+pub struct ContainerResourceDetector {
+    _private: (),
+}
+
+impl ContainerResourceDetector {
+    /// Create `ContainerResourceDetector` instance.
+    pub fn new() -> Self {
+        ContainerResourceDetector { _private: () }
+    }
+}
+
+impl ResourceDetector for ContainerResourceDetector {
+    fn detect(&self, _timeout: Duration) -> Resource {
+        match fs::read_to_string(CGROUP_PATH)
+            .ok()
+            .and_then(|cgroup| container_id(&cgroup))
+        {
+            Some(id) => Resource::new(vec![KeyValue::new("container.id", id)]),
+            None => Resource::empty(),
+        }
+    }
+}
+
+/// Extract the container id from the contents of a cgroup file.
+///
+/// Each line looks like `hierarchy-ID:controller-list:cgroup-path`, where the
+/// last path segment contains the container id, optionally wrapped in a
+/// runtime specific prefix and suffix such as `docker-<id>.scope`.
+fn container_id(cgroup: &str) -> Option<String> {
+    cgroup.lines().find_map(|line| {
+        let segment = line.rsplit('/').next()?.trim();
+        let segment = segment.strip_suffix(".scope").unwrap_or(segment);
+        let id = segment.rsplit('-').next()?;
+        if id.len() == CONTAINER_ID_LEN && id.bytes().all(|b| b.is_ascii_hexdigit()) {
+            Some(id.to_string())
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_container_id() {
+        let id = "a4d00c9dd675d67f866c786181419e1b44832d4696780152e61afd44a3e02856";
+        let docker = format!("13:name=systemd:/docker/{}\n12:pids:/docker/{}", id, id);
+        assert_eq!(container_id(&docker), Some(id.to_string()));
+
+        let systemd = format!(
+            "1:name=systemd:/system.slice/docker-{}.scope\n0::/system.slice/docker-{}.scope",
+            id, id
+        );
+        assert_eq!(container_id(&systemd), Some(id.to_string()));
+
+        let crio = format!("0::/kubepods/besteffort/pod123/crio-{}.scope", id);
+        assert_eq!(container_id(&crio), Some(id.to_string()));
+
+        assert_eq!(container_id("0::/user.slice/user-1000.slice"), None);
+        assert_eq!(container_id(""), None);
+    }
+}
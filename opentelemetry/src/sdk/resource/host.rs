@@ -0,0 +1,93 @@
+//! HostResourceDetector
+//!
+//! Implementation of `ResourceDetector` to extract a `Resource` describing the
+//! host the process is running on.
+use crate::sdk::{resource::ResourceDetector, Resource};
+use crate::KeyValue;
+use std::env;
+use std::fs;
+use std::time::Duration;
+
+/// Files which may contain the host name on unix like systems, in order of
+/// preference.
+const HOSTNAME_FILES: [&str; 2] = ["/proc/sys/kernel/hostname", "/etc/hostname"];
+
+/// Resource detector that collects the host name, the host architecture and
+/// the operating system type.
+///
+/// Populates `host.name`, `host.arch` and `os.type` following the
+/// [semantic conventions](https://github.com/open-telemetry/opentelemetry-specification/tree/master/specification/resource/semantic_conventions).
+/// The host name is omitted if it cannot be determined.
+#[derive(Debug, Default)]
+pub struct HostResourceDetector {
+    _private: (),
+}
+
+impl HostResourceDetector {
+    /// Create `HostResourceDetector` instance.
+    pub fn new() -> Self {
+        HostResourceDetector { _private: () }
+    }
+}
+
+impl ResourceDetector for HostResourceDetector {
+    fn detect(&self, _timeout: Duration) -> Resource {
+        let mut attributes = vec![
+            KeyValue::new("host.arch", host_arch(env::consts::ARCH)),
+            KeyValue::new("os.type", os_type(env::consts::OS)),
+        ];
+        if let Some(host_name) = host_name() {
+            attributes.push(KeyValue::new("host.name", host_name));
+        }
+
+        Resource::new(attributes)
+    }
+}
+
+fn host_name() -> Option<String> {
+    HOSTNAME_FILES
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .chain(env::var("HOSTNAME").ok())
+        .chain(env::var("COMPUTERNAME").ok())
+        .map(|name| name.trim().to_string())
+        .find(|name| !name.is_empty())
+}
+
+/// Map rust target architecture names to the `host.arch` well known values.
+fn host_arch(arch: &'static str) -> &'static str {
+    match arch {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        "arm" => "arm32",
+        "powerpc64" => "ppc64",
+        other => other,
+    }
+}
+
+/// Map rust target os names to the `os.type` well known values.
+fn os_type(os: &'static str) -> &'static str {
+    match os {
+        "macos" => "darwin",
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Key;
+
+    #[test]
+    fn test_host_arch_and_os_type() {
+        assert_eq!(host_arch("x86_64"), "amd64");
+        assert_eq!(host_arch("aarch64"), "arm64");
+        assert_eq!(host_arch("s390x"), "s390x");
+        assert_eq!(os_type("macos"), "darwin");
+        assert_eq!(os_type("linux"), "linux");
+
+        let resource = HostResourceDetector::new().detect(Duration::from_secs(0));
+        assert!(resource.get(Key::new("host.arch")).is_some());
+        assert!(resource.get(Key::new("os.type")).is_some());
+    }
+}
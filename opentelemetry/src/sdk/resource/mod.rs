@@ -0,0 +1,701 @@
+//! # Resource
+//!
+//! A `Resource` is an immutable representation of the entity producing telemetry. For example, a
+//! process producing telemetry that is running in a container on Kubernetes has a Pod name, it is
+//! in a namespace, and possibly is part of a Deployment which also has a name. All three of these
+//! attributes can be included in the `Resource`.
+//!
+//! The primary purpose of resources as a first-class concept in the SDK is decoupling of discovery
+//! of resource information from exporters. This allows for independent development and easy
+//! customization for users that need to integrate with closed source environments. When used with
+//! distributed tracing, a resource can be associated with the [`TracerProvider`] when it is created.
+//! That association cannot be changed later. When associated with a `TracerProvider`, all `Span`s
+//! produced by any `Tracer` from the provider are associated with this `Resource`.
+//!
+//! Resources can be assembled from built-in or custom [`ResourceDetector`]s with a
+//! [`ResourceBuilder`]:
+//!
+//! ```
+//! use opentelemetry::sdk::resource::{
+//!     ContainerResourceDetector, HostResourceDetector, ProcessResourceDetector, Resource,
+//! };
+//! use opentelemetry::KeyValue;
+//!
+//! let resource = Resource::builder()
+//!     .with_attributes(vec![KeyValue::new("service.name", "my-service")])
+//!     .with_detector(HostResourceDetector::new())
+//!     .with_detector(ProcessResourceDetector::new())
+//!     .with_detector(ContainerResourceDetector::new())
+//!     .build();
+//! ```
+//!
+//! [`TracerProvider`]: crate::trace::TracerProvider
+mod container;
+mod host;
+mod process;
+
+pub use container::ContainerResourceDetector;
+pub use host::HostResourceDetector;
+pub use process::ProcessResourceDetector;
+
+#[cfg(feature = "metrics")]
+use crate::labels;
+use crate::runtime::Runtime;
+use crate::sdk::EnvResourceDetector;
+use crate::{global, Key, KeyValue, Value};
+use futures::future::{self, BoxFuture};
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{btree_map, btree_map::Entry, BTreeMap};
+use std::fmt;
+use std::time::Duration;
+
+/// Describes an entity about which identifying information and metadata is exposed.
+///
+/// Items are sorted by their key, and are only overwritten if the value is an empty string.
+///
+/// A resource may declare the [schema URL] of the semantic conventions its
+/// attributes follow.
+///
+/// [schema URL]: https://github.com/open-telemetry/opentelemetry-specification/blob/main/specification/schemas/overview.md
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Resource {
+    attrs: BTreeMap<Key, Value>,
+    schema_url: Option<Cow<'static, str>>,
+}
+
+/// Errors returned when combining resources.
+#[derive(thiserror::Error, Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum ResourceError {
+    /// Both resources declare a schema URL and they differ.
+    #[error("cannot merge resources with different schema URLs {0} and {1}")]
+    SchemaUrlConflict(String, String),
+}
+
+lazy_static::lazy_static! {
+    /// Resource detected from the environment, computed once so that every
+    /// signal using the default resource reports the same attributes.
+    static ref DEFAULT_RESOURCE: Resource = Resource::from_detectors(
+        Duration::from_secs(0),
+        vec![Box::new(EnvResourceDetector::new())],
+    );
+}
+
+/// The default resource is read from the `OTEL_SERVICE_NAME` and
+/// `OTEL_RESOURCE_ATTRIBUTES` environment variables.
+///
+/// The environment is read the first time a default resource is created, e.g.
+/// by the default trace config or a metrics controller without a configured
+/// resource. Later calls return the same resource, so traces and metrics
+/// report an identical `service.name`.
+impl Default for Resource {
+    fn default() -> Self {
+        DEFAULT_RESOURCE.clone()
+    }
+}
+
+impl Resource {
+    /// Creates an empty resource.
+    pub fn empty() -> Self {
+        Self {
+            attrs: Default::default(),
+            schema_url: None,
+        }
+    }
+
+    /// Create a new `Resource` from key value pairs.
+    ///
+    /// Values are de-duplicated by key, and the first key-value pair with a non-empty string value
+    /// will be retained
+    pub fn new<T: IntoIterator<Item = KeyValue>>(kvs: T) -> Self {
+        let mut resource = Resource::empty();
+
+        for kv in kvs.into_iter() {
+            resource.insert(kv);
+        }
+
+        resource
+    }
+
+    /// Create a new `Resource` from key value pairs following the semantic
+    /// conventions described by `schema_url`.
+    ///
+    /// Values are de-duplicated as in [`Resource::new`].
+    pub fn from_schema_url<KV, S>(kvs: KV, schema_url: S) -> Self
+    where
+        KV: IntoIterator<Item = KeyValue>,
+        S: Into<Cow<'static, str>>,
+    {
+        let mut resource = Resource::new(kvs);
+        resource.schema_url = Some(schema_url.into());
+        resource
+    }
+
+    /// Create a new [`ResourceBuilder`] to assemble a `Resource` from
+    /// attributes and resource detectors.
+    pub fn builder() -> ResourceBuilder {
+        ResourceBuilder::default()
+    }
+
+    /// Create a new `Resource` from resource detectors.
+    ///
+    /// timeout will be applied to each detector.
+    pub fn from_detectors(timeout: Duration, detectors: Vec<Box<dyn ResourceDetector>>) -> Self {
+        let mut resource = Resource::empty();
+        for detector in detectors {
+            // using extend instead of merge to avoid clone.
+            resource.extend(detector.detect(timeout));
+        }
+
+        resource
+    }
+
+    /// Create a new `Resource` from asynchronous resource detectors.
+    ///
+    /// All detectors run concurrently and share the same deadline: each one is
+    /// given `timeout` to resolve, and detection as a whole completes once
+    /// `timeout` elapses on the given [`Runtime`], even if a detector ignores
+    /// it. The resource then only contains the attributes of the detectors
+    /// that resolved in time. As with [`Resource::from_detectors`], values from
+    /// earlier detectors have priority over later ones regardless of which
+    /// detector resolves first.
+    ///
+    /// [`Runtime`]: crate::runtime::Runtime
+    pub async fn from_detectors_async<R: Runtime>(
+        timeout: Duration,
+        detectors: Vec<Box<dyn AsyncResourceDetector>>,
+        runtime: R,
+    ) -> Self {
+        let mut detected: Vec<Option<Resource>> = detectors.iter().map(|_| None).collect();
+        {
+            let detection = future::join_all(detectors.iter().zip(detected.iter_mut()).map(
+                |(detector, slot)| async move {
+                    *slot = Some(detector.detect(timeout).await);
+                },
+            ));
+            let deadline = runtime.delay(timeout);
+            futures::pin_mut!(detection, deadline);
+            let _ = future::select(detection, deadline).await;
+        }
+
+        let mut resource = Resource::empty();
+        for detected_res in detected.into_iter().flatten() {
+            resource.extend(detected_res);
+        }
+
+        resource
+    }
+
+    /// Create a new `Resource` by combining two resources.
+    ///
+    /// Keys from this resource have priority over keys from the merged resource.
+    ///
+    /// If both resources declare different schema URLs the conflict is
+    /// reported to the global error handler and the schema URL of this
+    /// resource is kept, use [`Resource::try_merge`] to reject the merge
+    /// instead.
+    pub fn merge(&self, other: &Self) -> Self {
+        match self.try_merge(other) {
+            Ok(resource) => resource,
+            Err(err) => {
+                global::handle_error(err);
+                let mut resource = self.merge_attributes(other);
+                resource.schema_url = self.schema_url.clone();
+                resource
+            }
+        }
+    }
+
+    /// Create a new `Resource` by combining two resources, failing if they
+    /// declare different schema URLs.
+    ///
+    /// Keys from this resource have priority over keys from the merged
+    /// resource. The result uses whichever schema URL is set, if any.
+    pub fn try_merge(&self, other: &Self) -> Result<Self, ResourceError> {
+        let schema_url = merge_schema_url(&self.schema_url, &other.schema_url)?;
+        let mut resource = self.merge_attributes(other);
+        resource.schema_url = schema_url;
+        Ok(resource)
+    }
+
+    fn merge_attributes(&self, other: &Self) -> Self {
+        if self.attrs.is_empty() {
+            return other.clone();
+        }
+        if other.attrs.is_empty() {
+            return self.clone();
+        }
+
+        let mut resource = Resource::empty();
+
+        // attrs from self must be added first so they have priority
+        for (k, v) in self.attrs.iter() {
+            resource.insert(KeyValue {
+                key: k.clone(),
+                value: v.clone(),
+            });
+        }
+        for (k, v) in other.attrs.iter() {
+            resource.insert(KeyValue {
+                key: k.clone(),
+                value: v.clone(),
+            });
+        }
+
+        resource
+    }
+
+    /// Returns the schema URL of the semantic conventions used by this
+    /// resource, if any.
+    pub fn schema_url(&self) -> Option<&str> {
+        self.schema_url.as_deref()
+    }
+
+    /// Returns the number of attributes for this resource
+    pub fn len(&self) -> usize {
+        self.attrs.len()
+    }
+
+    /// Returns `true` if the resource contains no attributes.
+    pub fn is_empty(&self) -> bool {
+        self.attrs.is_empty()
+    }
+
+    /// Returns the value of the attribute with the given key, if present.
+    pub fn get(&self, key: Key) -> Option<Value> {
+        self.attrs.get(&key).cloned()
+    }
+
+    /// Gets an iterator over the attributes of this resource, sorted by key.
+    pub fn iter(&self) -> Iter<'_> {
+        self.into_iter()
+    }
+
+    /// Encoded labels
+    #[cfg(feature = "metrics")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+    pub fn encoded(&self, encoder: &dyn labels::Encoder) -> String {
+        encoder.encode(&mut self.into_iter())
+    }
+
+    /// Add the attributes and schema URL of a lower priority resource.
+    ///
+    /// Schema URL conflicts are reported and the current schema URL is kept.
+    fn extend(&mut self, other: Resource) {
+        match merge_schema_url(&self.schema_url, &other.schema_url) {
+            Ok(schema_url) => self.schema_url = schema_url,
+            Err(err) => global::handle_error(err),
+        }
+        for (key, value) in other.attrs.into_iter() {
+            self.insert(KeyValue::new(key, value));
+        }
+    }
+
+    /// Insert a key-value pair into a `Resource`
+    fn insert(&mut self, item: KeyValue) {
+        match self.attrs.entry(item.key) {
+            Entry::Occupied(mut existing_item) => {
+                if let Value::String(s) = existing_item.get() {
+                    if s.is_empty() {
+                        existing_item.insert(item.value);
+                    }
+                }
+            }
+            Entry::Vacant(v) => {
+                v.insert(item.value);
+            }
+        }
+    }
+}
+
+/// Schema URL of two merged resources, see the [resource merge specification].
+///
+/// [resource merge specification]: https://github.com/open-telemetry/opentelemetry-specification/blob/main/specification/resource/sdk.md#merge
+fn merge_schema_url(
+    schema_url: &Option<Cow<'static, str>>,
+    other: &Option<Cow<'static, str>>,
+) -> Result<Option<Cow<'static, str>>, ResourceError> {
+    match (schema_url, other) {
+        (Some(a), Some(b)) if a != b => Err(ResourceError::SchemaUrlConflict(
+            a.to_string(),
+            b.to_string(),
+        )),
+        (Some(a), _) => Ok(Some(a.clone())),
+        (None, b) => Ok(b.clone()),
+    }
+}
+
+/// An owned iterator over the entries of a `Resource`.
+#[derive(Debug)]
+pub struct IntoIter(btree_map::IntoIter<Key, Value>);
+
+impl Iterator for IntoIter {
+    type Item = (Key, Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+impl IntoIterator for Resource {
+    type Item = (Key, Value);
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self.attrs.into_iter())
+    }
+}
+
+/// An iterator over the entries of a `Resource`.
+#[derive(Debug)]
+pub struct Iter<'a>(btree_map::Iter<'a, Key, Value>);
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a Key, &'a Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+impl<'a> IntoIterator for &'a Resource {
+    type Item = (&'a Key, &'a Value);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Iter(self.attrs.iter())
+    }
+}
+
+/// ResourceDetector detects OpenTelemetry resource information
+///
+/// Implementations of this trait can be passed to
+/// the `Resource::from_detectors` function to generate a Resource from the merged information.
+pub trait ResourceDetector {
+    /// detect returns an initialized Resource based on gathered information.
+    ///
+    /// timeout is used in case the detection operation takes too much time.
+    ///
+    /// If source information to construct a Resource is inaccessible, an empty Resource should be returned
+    ///
+    /// If source information to construct a Resource is invalid, for example,
+    /// missing required values. an empty Resource should be returned.
+    fn detect(&self, timeout: Duration) -> Resource;
+}
+
+/// AsyncResourceDetector detects OpenTelemetry resource information asynchronously
+///
+/// Detectors that need to perform I/O, such as querying a cloud provider's
+/// metadata endpoint, should implement this trait rather than block in
+/// [`ResourceDetector::detect`]. Implementations of this trait can be passed to
+/// the `Resource::from_detectors_async` function to run them concurrently.
+pub trait AsyncResourceDetector: Send + Sync {
+    /// detect resolves to an initialized Resource based on gathered information.
+    ///
+    /// timeout is the maximum time the detection should take, implementations
+    /// should resolve to an empty Resource once it has elapsed.
+    ///
+    /// If source information to construct a Resource is inaccessible or
+    /// invalid, an empty Resource should be returned.
+    fn detect(&self, timeout: Duration) -> BoxFuture<'_, Resource>;
+}
+
+/// Builder for a [`Resource`] assembled from attributes and [`ResourceDetector`]s.
+///
+/// Values are taken with the following precedence, highest first:
+///
+/// 1. attributes added with [`ResourceBuilder::with_attributes`]
+/// 2. `OTEL_SERVICE_NAME`
+/// 3. `OTEL_RESOURCE_ATTRIBUTES`
+/// 4. detectors, in the order they are added
+pub struct ResourceBuilder {
+    resource: Resource,
+    detectors: Vec<Box<dyn ResourceDetector>>,
+    timeout: Duration,
+}
+
+impl fmt::Debug for ResourceBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResourceBuilder")
+            .field("resource", &self.resource)
+            .field("detectors", &self.detectors.len())
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+impl Default for ResourceBuilder {
+    fn default() -> Self {
+        ResourceBuilder {
+            resource: Resource::empty(),
+            detectors: Vec::new(),
+            timeout: Duration::from_secs(0),
+        }
+    }
+}
+
+impl ResourceBuilder {
+    /// Add attributes to the resource.
+    ///
+    /// Attributes added with this method take priority over detected ones.
+    pub fn with_attributes<T: IntoIterator<Item = KeyValue>>(mut self, kvs: T) -> Self {
+        for kv in kvs.into_iter() {
+            self.resource.insert(kv);
+        }
+        self
+    }
+
+    /// Set the schema URL of the semantic conventions used by the attributes.
+    ///
+    /// Detected resources declaring a different schema URL are reported to
+    /// the global error handler and keep this schema URL.
+    pub fn with_schema_url<S: Into<Cow<'static, str>>>(mut self, schema_url: S) -> Self {
+        self.resource.schema_url = Some(schema_url.into());
+        self
+    }
+
+    /// Add a resource detector to run when building the resource.
+    pub fn with_detector<D: ResourceDetector + 'static>(mut self, detector: D) -> Self {
+        self.detectors.push(Box::new(detector));
+        self
+    }
+
+    /// Set the timeout applied to each detector.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Run the detectors and build the resource.
+    pub fn build(self) -> Resource {
+        let env = EnvResourceDetector::new().detect(self.timeout);
+        self.resource
+            .merge(&env)
+            .merge(&Resource::from_detectors(self.timeout, self.detectors))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "rt-tokio")]
+    use crate::runtime;
+    use crate::sdk::EnvResourceDetector;
+    use std::collections::BTreeMap;
+    use std::{env, time};
+
+    #[test]
+    fn new_resource() {
+        let args_with_dupe_keys = vec![KeyValue::new("a", ""), KeyValue::new("a", "final")];
+
+        let mut expected_attrs = BTreeMap::new();
+        expected_attrs.insert(Key::new("a"), Value::from("final"));
+
+        assert_eq!(
+            Resource::new(args_with_dupe_keys),
+            Resource {
+                attrs: expected_attrs,
+                schema_url: None,
+            }
+        );
+    }
+
+    #[test]
+    fn merge_resource() {
+        let resource_a = Resource::new(vec![KeyValue::new("a", ""), KeyValue::new("b", "b-value")]);
+
+        let resource_b = Resource::new(vec![
+            KeyValue::new("a", "final"),
+            KeyValue::new("c", "c-value"),
+        ]);
+
+        let mut expected_attrs = BTreeMap::new();
+        expected_attrs.insert(Key::new("a"), Value::from("final"));
+        expected_attrs.insert(Key::new("b"), Value::from("b-value"));
+        expected_attrs.insert(Key::new("c"), Value::from("c-value"));
+
+        assert_eq!(
+            resource_a.merge(&resource_b),
+            Resource {
+                attrs: expected_attrs,
+                schema_url: None,
+            }
+        );
+    }
+
+    #[test]
+    fn merge_resource_schema_url() {
+        let with_schema = |url: &'static str| Resource::from_schema_url(vec![], url);
+        let without_schema = Resource::new(vec![KeyValue::new("a", "a-value")]);
+
+        let merged = with_schema("https://opentelemetry.io/schemas/1.0.0")
+            .try_merge(&without_schema)
+            .unwrap();
+        assert_eq!(
+            merged.schema_url(),
+            Some("https://opentelemetry.io/schemas/1.0.0")
+        );
+        assert_eq!(merged.get(Key::new("a")), Some(Value::from("a-value")));
+
+        let merged = without_schema
+            .try_merge(&with_schema("https://opentelemetry.io/schemas/1.0.0"))
+            .unwrap();
+        assert_eq!(
+            merged.schema_url(),
+            Some("https://opentelemetry.io/schemas/1.0.0")
+        );
+
+        assert!(with_schema("https://opentelemetry.io/schemas/1.0.0")
+            .try_merge(&with_schema("https://opentelemetry.io/schemas/1.0.0"))
+            .is_ok());
+        assert_eq!(
+            with_schema("https://opentelemetry.io/schemas/1.0.0")
+                .try_merge(&with_schema("https://opentelemetry.io/schemas/1.1.0")),
+            Err(ResourceError::SchemaUrlConflict(
+                "https://opentelemetry.io/schemas/1.0.0".to_string(),
+                "https://opentelemetry.io/schemas/1.1.0".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn resource_builder() {
+        struct StaticDetector(Vec<KeyValue>);
+
+        impl ResourceDetector for StaticDetector {
+            fn detect(&self, _timeout: Duration) -> Resource {
+                Resource::new(self.0.clone())
+            }
+        }
+
+        let resource = Resource::builder()
+            .with_attributes(vec![KeyValue::new("a", "user")])
+            .with_detector(StaticDetector(vec![
+                KeyValue::new("a", "first"),
+                KeyValue::new("b", "first"),
+            ]))
+            .with_detector(StaticDetector(vec![
+                KeyValue::new("b", "second"),
+                KeyValue::new("c", "second"),
+            ]))
+            .build();
+
+        // other tests may set resource attributes in the environment concurrently
+        assert_eq!(resource.get(Key::new("a")), Some(Value::from("user")));
+        assert_eq!(resource.get(Key::new("b")), Some(Value::from("first")));
+        assert_eq!(resource.get(Key::new("c")), Some(Value::from("second")));
+    }
+
+    #[cfg(feature = "rt-tokio")]
+    #[tokio::test]
+    async fn detect_resource_async() {
+        use futures::channel::oneshot;
+        use std::sync::Mutex;
+
+        struct ChainedDetector {
+            attrs: Vec<KeyValue>,
+            wait: Mutex<Option<oneshot::Receiver<()>>>,
+            notify: Mutex<Option<oneshot::Sender<()>>>,
+        }
+
+        impl AsyncResourceDetector for ChainedDetector {
+            fn detect(&self, _timeout: Duration) -> BoxFuture<'_, Resource> {
+                let wait = self.wait.lock().unwrap().take();
+                let notify = self.notify.lock().unwrap().take();
+                Box::pin(async move {
+                    if let Some(notify) = notify {
+                        let _ = notify.send(());
+                    }
+                    if let Some(wait) = wait {
+                        let _ = wait.await;
+                    }
+                    Resource::new(self.attrs.clone())
+                })
+            }
+        }
+
+        // the first detector only resolves once the second one has been polled,
+        // so detection would never complete if detectors ran one after another
+        let (tx, rx) = oneshot::channel();
+        let detectors: Vec<Box<dyn AsyncResourceDetector>> = vec![
+            Box::new(ChainedDetector {
+                attrs: vec![KeyValue::new("a", "first"), KeyValue::new("b", "first")],
+                wait: Mutex::new(Some(rx)),
+                notify: Mutex::new(None),
+            }),
+            Box::new(ChainedDetector {
+                attrs: vec![KeyValue::new("b", "second"), KeyValue::new("c", "second")],
+                wait: Mutex::new(None),
+                notify: Mutex::new(Some(tx)),
+            }),
+        ];
+
+        let resource =
+            Resource::from_detectors_async(Duration::from_secs(1), detectors, runtime::Tokio).await;
+
+        assert_eq!(
+            resource,
+            Resource::new(vec![
+                KeyValue::new("a", "first"),
+                KeyValue::new("b", "first"),
+                KeyValue::new("c", "second"),
+            ])
+        );
+    }
+
+    #[cfg(feature = "rt-tokio")]
+    #[tokio::test]
+    async fn detect_resource_async_deadline() {
+        struct PendingDetector;
+
+        impl AsyncResourceDetector for PendingDetector {
+            fn detect(&self, _timeout: Duration) -> BoxFuture<'_, Resource> {
+                // ignores the timeout and never resolves
+                Box::pin(future::pending())
+            }
+        }
+
+        struct ReadyDetector;
+
+        impl AsyncResourceDetector for ReadyDetector {
+            fn detect(&self, _timeout: Duration) -> BoxFuture<'_, Resource> {
+                Box::pin(future::ready(Resource::new(vec![KeyValue::new(
+                    "a", "ready",
+                )])))
+            }
+        }
+
+        let detectors: Vec<Box<dyn AsyncResourceDetector>> =
+            vec![Box::new(PendingDetector), Box::new(ReadyDetector)];
+        let resource = tokio::time::timeout(
+            Duration::from_secs(5),
+            Resource::from_detectors_async(Duration::from_millis(10), detectors, runtime::Tokio),
+        )
+        .await
+        .expect("detection should complete at the deadline");
+
+        assert_eq!(resource, Resource::new(vec![KeyValue::new("a", "ready")]));
+    }
+
+    #[test]
+    fn detect_resource() {
+        env::set_var("OTEL_RESOURCE_ATTRIBUTES", "key=value, k = v , a= x, a=z");
+        env::set_var("irrelevant".to_uppercase(), "20200810");
+
+        let detector = EnvResourceDetector::new();
+        let resource =
+            Resource::from_detectors(time::Duration::from_secs(5), vec![Box::new(detector)]);
+        assert_eq!(
+            resource,
+            Resource::new(vec![
+                KeyValue::new(Key::new("key".to_string()), "value"),
+                KeyValue::new(Key::new("k".to_string()), "v"),
+                KeyValue::new(Key::new("a".to_string()), "x"),
+                KeyValue::new(Key::new("a".to_string()), "z")
+            ])
+        )
+    }
+}
@@ -0,0 +1,69 @@
+//! ProcessResourceDetector
+//!
+//! Implementation of `ResourceDetector` to extract a `Resource` describing the
+//! running process.
+use crate::sdk::{resource::ResourceDetector, Resource};
+use crate::KeyValue;
+use std::env;
+use std::process;
+use std::time::Duration;
+
+/// Resource detector that collects information about the current process.
+///
+/// Populates `process.pid`, `process.executable.name`,
+/// `process.executable.path`, `process.runtime.name` and
+/// `process.runtime.version` following the
+/// [semantic conventions](https://github.com/open-telemetry/opentelemetry-specification/tree/master/specification/resource/semantic_conventions).
+/// The runtime version is the version of the compiler that built this crate.
+#[derive(Debug, Default)]
+pub struct ProcessResourceDetector {
+    _private: (),
+}
+
+impl ProcessResourceDetector {
+    /// Create `ProcessResourceDetector` instance.
+    pub fn new() -> Self {
+        ProcessResourceDetector { _private: () }
+    }
+}
+
+impl ResourceDetector for ProcessResourceDetector {
+    fn detect(&self, _timeout: Duration) -> Resource {
+        let mut attributes = vec![
+            KeyValue::new("process.pid", process::id() as i64),
+            KeyValue::new("process.runtime.name", "rustc"),
+            KeyValue::new("process.runtime.version", env!("OTEL_RUSTC_VERSION")),
+        ];
+        if let Ok(executable) = env::current_exe() {
+            if let Some(name) = executable.file_name() {
+                attributes.push(KeyValue::new(
+                    "process.executable.name",
+                    name.to_string_lossy().into_owned(),
+                ));
+            }
+            attributes.push(KeyValue::new(
+                "process.executable.path",
+                executable.to_string_lossy().into_owned(),
+            ));
+        }
+
+        Resource::new(attributes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Key, Value};
+
+    #[test]
+    fn test_process_detector() {
+        let resource = ProcessResourceDetector::new().detect(Duration::from_secs(0));
+        assert_eq!(
+            resource.get(Key::new("process.pid")),
+            Some(Value::I64(process::id() as i64))
+        );
+        assert!(resource.get(Key::new("process.executable.name")).is_some());
+        assert!(resource.get(Key::new("process.runtime.version")).is_some());
+    }
+}
@@ -0,0 +1,433 @@
+//! # Golden Files
+//!
+//! Regression tests comparing output, e.g. the spans handed to an exporter
+//! or the payloads it sends, with a file checked in next to the tests.
+//!
+//! Spans are compared in a canonical JSON form written by
+//! [`spans_to_canonical_json`]: pretty printed, with the keys of objects and
+//! attributes in order, so the form doesn't depend on the iteration order of
+//! the attribute maps and changes show up as small diffs.
+//!
+//! Golden files are written instead of compared when the `OTEL_UPDATE_GOLDEN`
+//! environment variable is set, so they can be created and updated by running
+//! the tests once with it:
+//!
+//! ```sh
+//! OTEL_UPDATE_GOLDEN=1 cargo test
+//! ```
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use opentelemetry::sdk::export::trace::SpanData;
+//! use opentelemetry::sdk::testing::golden::assert_spans_match_golden;
+//! use opentelemetry::KeyValue;
+//!
+//! let span = SpanData::builder()
+//!     .with_name("GET /users")
+//!     .with_attributes(vec![KeyValue::new("http.method", "GET")])
+//!     .build();
+//!
+//! assert_spans_match_golden(
+//!     concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/get_users.json"),
+//!     &[span],
+//! );
+//! ```
+use crate::sdk::export::json::{unix_nanos, write_f64, write_str};
+use crate::sdk::export::trace::SpanData;
+use crate::trace::SpanId;
+use crate::{Array, Key, KeyValue, Value};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// The environment variable which, when set, makes the assertions write the
+/// golden files instead of comparing with them.
+pub const UPDATE_GOLDEN_ENV: &str = "OTEL_UPDATE_GOLDEN";
+
+/// The number of unchanged lines shown around the changes in a diff.
+const DIFF_CONTEXT: usize = 3;
+
+/// Encode spans as a pretty printed JSON array, with the keys of objects and
+/// attributes in order and a trailing newline.
+///
+/// All fields of [`SpanData`] are encoded, timestamps as nanoseconds since the
+/// unix epoch and attribute values as JSON values of their type.
+pub fn spans_to_canonical_json(spans: &[SpanData]) -> String {
+    let mut out = String::with_capacity(1024 * spans.len() + 3);
+    let spans = Json::Array(spans.iter().map(canonical_span).collect());
+    spans.write(&mut out, 0);
+    out.push('\n');
+    out
+}
+
+/// Assert that `spans` match the golden file at `path` in their canonical
+/// JSON form, see [`spans_to_canonical_json`].
+///
+/// # Panics
+///
+/// Panics with a diff if they don't match, or if the file can't be read.
+pub fn assert_spans_match_golden<P: AsRef<Path>>(path: P, spans: &[SpanData]) {
+    assert_golden(path, &spans_to_canonical_json(spans))
+}
+
+/// Assert that `actual` matches the contents of the golden file at `path`.
+///
+/// The file is written instead, creating missing directories, when the
+/// `OTEL_UPDATE_GOLDEN` environment variable is set.
+///
+/// # Panics
+///
+/// Panics with a diff if the contents don't match, or if the file can't be
+/// read or written.
+pub fn assert_golden<P: AsRef<Path>>(path: P, actual: &str) {
+    let path = path.as_ref();
+    if std::env::var_os(UPDATE_GOLDEN_ENV).is_some() {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).unwrap_or_else(|err| {
+                panic!("failed to create {}: {}", dir.display(), err);
+            });
+        }
+        fs::write(path, actual).unwrap_or_else(|err| {
+            panic!("failed to write {}: {}", path.display(), err);
+        });
+        return;
+    }
+
+    let expected = fs::read_to_string(path).unwrap_or_else(|err| {
+        panic!(
+            "failed to read {}: {}, set {} to write it",
+            path.display(),
+            err,
+            UPDATE_GOLDEN_ENV
+        );
+    });
+    if expected != actual {
+        panic!(
+            "output doesn't match {}, set {} to update it\n{}",
+            path.display(),
+            UPDATE_GOLDEN_ENV,
+            diff(&expected, actual)
+        );
+    }
+}
+
+/// A line diff of `expected` and `actual`, lines only in `expected` start
+/// with `-`, lines only in `actual` with `+`.
+fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<_> = expected.lines().collect();
+    let actual: Vec<_> = actual.lines().collect();
+
+    // lengths of the longest common subsequences of the suffixes
+    let mut lengths = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            lengths[i][j] = if expected[i] == actual[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            lines.push((' ', expected[i]));
+            i += 1;
+            j += 1;
+        } else if j < actual.len() && (i == expected.len() || lengths[i][j + 1] > lengths[i + 1][j])
+        {
+            lines.push(('+', actual[j]));
+            j += 1;
+        } else {
+            lines.push(('-', expected[i]));
+            i += 1;
+        }
+    }
+
+    let changed: Vec<_> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, (tag, _))| *tag != ' ')
+        .map(|(index, _)| index)
+        .collect();
+    let shown = |index: usize| {
+        changed
+            .iter()
+            .any(|changed| index + DIFF_CONTEXT >= *changed && index <= changed + DIFF_CONTEXT)
+    };
+    let mut out = String::new();
+    let mut skipped = false;
+    for (index, (tag, line)) in lines.iter().enumerate() {
+        if shown(index) {
+            let _ = writeln!(out, "{} {}", tag, line);
+            skipped = false;
+        } else if !skipped {
+            out.push_str("  ...\n");
+            skipped = true;
+        }
+    }
+    if expected.len() == actual.len() && changed.is_empty() {
+        // only the line endings differ
+        out.push_str("  (differences in line endings)\n");
+    }
+    out
+}
+
+/// A JSON value with the keys of objects in order.
+enum Json<'a> {
+    /// An encoded string, number or boolean.
+    Scalar(String),
+    Array(Vec<Json<'a>>),
+    Object(BTreeMap<&'a str, Json<'a>>),
+}
+
+impl Json<'_> {
+    fn string(value: &str) -> Self {
+        let mut out = String::with_capacity(value.len() + 2);
+        write_str(&mut out, value);
+        Json::Scalar(out)
+    }
+
+    fn number<T: ToString>(value: T) -> Self {
+        Json::Scalar(value.to_string())
+    }
+
+    fn write(&self, out: &mut String, indent: usize) {
+        match self {
+            Json::Scalar(value) => out.push_str(value),
+            Json::Array(values) if values.is_empty() => out.push_str("[]"),
+            Json::Array(values) => {
+                out.push('[');
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    newline(out, indent + 1);
+                    value.write(out, indent + 1);
+                }
+                newline(out, indent);
+                out.push(']');
+            }
+            Json::Object(fields) if fields.is_empty() => out.push_str("{}"),
+            Json::Object(fields) => {
+                out.push('{');
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    newline(out, indent + 1);
+                    write_str(out, key);
+                    out.push_str(": ");
+                    value.write(out, indent + 1);
+                }
+                newline(out, indent);
+                out.push('}');
+            }
+        }
+    }
+}
+
+fn newline(out: &mut String, indent: usize) {
+    out.push('\n');
+    for _ in 0..indent {
+        out.push_str("  ");
+    }
+}
+
+fn canonical_span(span: &SpanData) -> Json<'_> {
+    let context = &span.span_context;
+    let mut fields = BTreeMap::new();
+    fields.insert("trace_id", Json::string(&context.trace_id().to_hex()));
+    fields.insert("span_id", Json::string(&context.span_id().to_hex()));
+    fields.insert("trace_flags", Json::number(context.trace_flags()));
+    fields.insert("is_remote", Json::number(context.is_remote()));
+    fields.insert("trace_state", Json::string(&context.trace_state().header()));
+    if span.parent_span_id != SpanId::invalid() {
+        fields.insert(
+            "parent_span_id",
+            Json::string(&span.parent_span_id.to_hex()),
+        );
+    }
+    fields.insert("name", Json::string(&span.name));
+    fields.insert("span_kind", Json::string(&span.span_kind.to_string()));
+    fields.insert(
+        "start_time_unix_nano",
+        Json::number(unix_nanos(span.start_time)),
+    );
+    fields.insert(
+        "end_time_unix_nano",
+        Json::number(unix_nanos(span.end_time)),
+    );
+    fields.insert("attributes", canonical_attributes(span.attributes.iter()));
+    fields.insert(
+        "dropped_attributes_count",
+        Json::number(span.attributes.dropped_count()),
+    );
+    fields.insert(
+        "events",
+        Json::Array(
+            span.message_events
+                .iter()
+                .map(|event| {
+                    let mut fields = BTreeMap::new();
+                    fields.insert("name", Json::string(&event.name));
+                    fields.insert("time_unix_nano", Json::number(unix_nanos(event.timestamp)));
+                    fields.insert("attributes", canonical_key_values(&event.attributes));
+                    if let Some(severity) = event.severity {
+                        fields.insert("severity", Json::string(severity.name()));
+                    }
+                    if let Some(body) = &event.body {
+                        fields.insert("body", canonical_value(body));
+                    }
+                    Json::Object(fields)
+                })
+                .collect(),
+        ),
+    );
+    fields.insert(
+        "dropped_events_count",
+        Json::number(span.message_events.dropped_count()),
+    );
+    fields.insert(
+        "links",
+        Json::Array(
+            span.links
+                .iter()
+                .map(|link| {
+                    let context = link.span_context();
+                    let mut fields = BTreeMap::new();
+                    fields.insert("trace_id", Json::string(&context.trace_id().to_hex()));
+                    fields.insert("span_id", Json::string(&context.span_id().to_hex()));
+                    fields.insert("trace_state", Json::string(&context.trace_state().header()));
+                    fields.insert("attributes", canonical_key_values(link.attributes()));
+                    Json::Object(fields)
+                })
+                .collect(),
+        ),
+    );
+    fields.insert(
+        "dropped_links_count",
+        Json::number(span.links.dropped_count()),
+    );
+    fields.insert("status_code", Json::string(span.status_code.as_str()));
+    fields.insert("status_message", Json::string(&span.status_message));
+    fields.insert("resource", canonical_attributes(span.resource.iter()));
+
+    let library = &span.instrumentation_lib;
+    let mut library_fields = BTreeMap::new();
+    library_fields.insert("name", Json::string(library.name));
+    if let Some(version) = library.version {
+        library_fields.insert("version", Json::string(version));
+    }
+    if let Some(schema_url) = library.schema_url {
+        library_fields.insert("schema_url", Json::string(schema_url));
+    }
+    if !library.attributes.is_empty() {
+        library_fields.insert("attributes", canonical_key_values(&library.attributes));
+    }
+    fields.insert("instrumentation_library", Json::Object(library_fields));
+    Json::Object(fields)
+}
+
+fn canonical_key_values(attributes: &[KeyValue]) -> Json<'_> {
+    canonical_attributes(attributes.iter().map(|kv| (&kv.key, &kv.value)))
+}
+
+fn canonical_attributes<'a>(attributes: impl Iterator<Item = (&'a Key, &'a Value)>) -> Json<'a> {
+    Json::Object(
+        attributes
+            .map(|(key, value)| (key.as_str(), canonical_value(value)))
+            .collect(),
+    )
+}
+
+fn canonical_value(value: &Value) -> Json<'static> {
+    let mut out = String::new();
+    match value {
+        Value::Bool(v) => out.push_str(&v.to_string()),
+        Value::I64(v) => out.push_str(&v.to_string()),
+        Value::F64(v) => write_f64(&mut out, *v),
+        Value::String(v) => write_str(&mut out, v),
+        Value::Array(array) => {
+            return Json::Array(match array {
+                Array::Bool(values) => values.iter().map(Json::number).collect(),
+                Array::I64(values) => values.iter().map(Json::number).collect(),
+                Array::F64(values) => values
+                    .iter()
+                    .map(|v| canonical_value(&Value::F64(*v)))
+                    .collect(),
+                Array::String(values) => values.iter().map(|v| Json::string(v)).collect(),
+            })
+        }
+    }
+    Json::Scalar(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdk::{InstrumentationLibrary, Resource};
+    use crate::trace::{Event, Link, SpanContext, SpanKind, StatusCode, TraceId, TraceState};
+    use crate::Severity;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn spans_match_golden_file() {
+        let mut event = Event::new(
+            "exception",
+            UNIX_EPOCH + Duration::from_millis(1500),
+            vec![KeyValue::new("exception.message", "\"quoted\"")],
+        );
+        event.severity = Some(Severity::Error);
+        let span = SpanData::builder()
+            .with_trace_id(TraceId::from_u128(0xabcd))
+            .with_parent_span_id(SpanId::from_u64(7))
+            .with_name("GET /users")
+            .with_kind(SpanKind::Server)
+            // inserted out of order, written in order
+            .with_attributes(vec![
+                KeyValue::new("http.status_code", 500),
+                KeyValue::new("http.method", "GET"),
+                KeyValue::new("retry", true),
+                KeyValue::new("ratio", 0.5),
+                KeyValue::new(
+                    "tags",
+                    Value::Array(Array::String(vec!["a".into(), "b".into()])),
+                ),
+            ])
+            .with_event(event)
+            .with_link(Link::new(
+                SpanContext::new(
+                    TraceId::from_u128(2),
+                    SpanId::from_u64(3),
+                    0,
+                    true,
+                    TraceState::default(),
+                ),
+                vec![KeyValue::new("link", 1)],
+            ))
+            .with_status(StatusCode::Error, "internal error")
+            .with_resource(Resource::new(vec![KeyValue::new("service.name", "users")]))
+            .with_instrumentation_library(InstrumentationLibrary::new("server", Some("1.0")))
+            .build();
+
+        assert_spans_match_golden(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/spans.json"),
+            &[span, SpanData::builder().build()],
+        );
+    }
+
+    #[test]
+    fn diff_shows_changes_with_context() {
+        let expected = "a\nb\nc\nd\ne\nf\ng\nh\ni\n";
+        let actual = "a\nb\nc\nd\ne\nF\ng\nh\ni\nj\n";
+        assert_eq!(
+            diff(expected, actual),
+            "  ...\n  c\n  d\n  e\n- f\n+ F\n  g\n  h\n  i\n+ j\n"
+        );
+    }
+}
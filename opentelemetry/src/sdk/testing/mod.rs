@@ -0,0 +1,8 @@
+//! # SDK Testing Utilities
+//!
+//! Helpers for asserting on the telemetry produced by instrumented code in
+//! tests, for driving processors deterministically and for regression tests
+//! of exporters against golden files, available with the `testing` feature.
+pub mod golden;
+pub mod runtime;
+pub mod trace;
@@ -0,0 +1,411 @@
+//! # Manual Runtime
+//!
+//! A [`Runtime`] whose time only advances when the test advances it, so tests
+//! of batch processors don't depend on real sleeps.
+//!
+//! # Examples
+//!
+//! ```
+//! use opentelemetry::sdk::testing::{runtime::ManualRuntime, trace::InMemorySpanExporter};
+//! use opentelemetry::sdk::trace::{BatchSpanProcessor, TracerProvider};
+//! use opentelemetry::trace::{Tracer, TracerProvider as _};
+//! use std::time::Duration;
+//!
+//! let runtime = ManualRuntime::new();
+//! let exporter = InMemorySpanExporter::default();
+//! let processor = BatchSpanProcessor::builder(exporter.clone(), runtime.clone())
+//!     .with_scheduled_delay(Duration::from_secs(5))
+//!     .build();
+//! let provider = TracerProvider::builder()
+//!     .with_span_processor(processor)
+//!     .build();
+//!
+//! provider.get_tracer("tests", None).in_span("work", |_cx| {});
+//! runtime.wait_until_idle();
+//! assert_eq!(runtime.scheduled(), vec![Duration::from_secs(5)]);
+//!
+//! runtime.advance(Duration::from_secs(5));
+//! assert_eq!(exporter.get_finished_spans().unwrap().len(), 1);
+//! ```
+use crate::runtime::Runtime;
+use futures::{
+    future::BoxFuture,
+    task::{waker, ArcWake},
+    Future, Stream,
+};
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long [`ManualRuntime::wait_until_idle`] waits for tasks to settle.
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A [`Runtime`] with timers advanced by hand.
+///
+/// Delays resolve and intervals tick only once [`advance`] moved the time of
+/// the runtime past them. Intervals tick at the end of every period, their
+/// first tick is one period after their creation. Tasks run on threads of
+/// their own, so they keep running while the test thread is blocked, e.g. in
+/// `force_flush`.
+///
+/// Clones share the same time and tasks.
+///
+/// [`advance`]: ManualRuntime::advance
+#[derive(Clone, Debug, Default)]
+pub struct ManualRuntime {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    state: Mutex<State>,
+    settled: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// The time advanced since the creation of the runtime.
+    elapsed: Duration,
+    timers: HashMap<u64, Timer>,
+    next_timer_id: u64,
+    /// The number of tasks which are running or were woken.
+    running: usize,
+}
+
+#[derive(Debug)]
+struct Timer {
+    deadline: Duration,
+    waker: Option<Waker>,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn task_started(&self) {
+        self.lock().running += 1;
+    }
+
+    fn task_settled(&self) {
+        self.lock().running -= 1;
+        self.settled.notify_all();
+    }
+}
+
+impl ManualRuntime {
+    /// Create a runtime at time zero without tasks.
+    pub fn new() -> Self {
+        ManualRuntime::default()
+    }
+
+    /// The time advanced since the creation of the runtime.
+    pub fn elapsed(&self) -> Duration {
+        self.shared.lock().elapsed
+    }
+
+    /// The times, relative to the creation of the runtime, the pending delays
+    /// resolve and the next ticks of the intervals are due, in order.
+    ///
+    /// Batch processors waiting for their next scheduled export show up here
+    /// with the time of that export.
+    pub fn scheduled(&self) -> Vec<Duration> {
+        let mut scheduled: Vec<_> = self
+            .shared
+            .lock()
+            .timers
+            .values()
+            .map(|timer| timer.deadline)
+            .collect();
+        scheduled.sort();
+        scheduled
+    }
+
+    /// Advance the time of the runtime by `duration`.
+    ///
+    /// Timers due on the way are fired in order, waiting for the tasks to
+    /// settle after each, so that e.g. an interval ticks once per period. The
+    /// tasks have settled when this returns.
+    pub fn advance(&self, duration: Duration) {
+        self.wait_until_idle();
+        let target = self.shared.lock().elapsed + duration;
+        loop {
+            let wakers = {
+                let mut state = self.shared.lock();
+                let elapsed = state.elapsed;
+                let next = state
+                    .timers
+                    .values()
+                    .map(|timer| timer.deadline)
+                    .filter(|deadline| *deadline > elapsed && *deadline <= target)
+                    .min();
+                state.elapsed = next.unwrap_or(target);
+                let now = state.elapsed;
+                state
+                    .timers
+                    .values_mut()
+                    .filter(|timer| timer.deadline <= now)
+                    .filter_map(|timer| timer.waker.take())
+                    .collect::<Vec<_>>()
+            };
+            // woken without holding the lock, as waking a task takes it
+            for waker in wakers {
+                waker.wake();
+            }
+            self.wait_until_idle();
+            if self.shared.lock().elapsed >= target {
+                return;
+            }
+        }
+    }
+
+    /// Wait until all tasks are waiting for a timer, a message or another
+    /// event, or have completed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tasks don't settle within 10 seconds, e.g. because one
+    /// of them panicked or is blocked on real time.
+    pub fn wait_until_idle(&self) {
+        let deadline = Instant::now() + SETTLE_TIMEOUT;
+        let mut state = self.shared.lock();
+        while state.running > 0 {
+            let now = Instant::now();
+            if now >= deadline {
+                panic!(
+                    "{} tasks of the manual runtime did not settle within {:?}",
+                    state.running, SETTLE_TIMEOUT
+                );
+            }
+            state = self
+                .shared
+                .settled
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+    }
+
+    fn register_timer(&self, duration: Duration) -> u64 {
+        let mut state = self.shared.lock();
+        let id = state.next_timer_id;
+        state.next_timer_id += 1;
+        let deadline = state.elapsed + duration;
+        state.timers.insert(
+            id,
+            Timer {
+                deadline,
+                waker: None,
+            },
+        );
+        id
+    }
+}
+
+impl Runtime for ManualRuntime {
+    type Interval = Interval;
+    type Delay = Delay;
+
+    fn interval(&self, duration: Duration) -> Self::Interval {
+        Interval {
+            id: self.register_timer(duration),
+            period: duration,
+            runtime: self.clone(),
+        }
+    }
+
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        let task = Arc::new(Task {
+            state: Mutex::new(TaskState::Running),
+            woken: Condvar::new(),
+            shared: self.shared.clone(),
+        });
+        self.shared.task_started();
+        thread::Builder::new()
+            .name("opentelemetry-manual-runtime".to_string())
+            .spawn(move || task.run(future))
+            .expect("failed to spawn a thread of the manual runtime");
+    }
+
+    fn delay(&self, duration: Duration) -> Self::Delay {
+        Delay {
+            id: self.register_timer(duration),
+            runtime: self.clone(),
+        }
+    }
+}
+
+/// A future resolving once the time of a [`ManualRuntime`] passed its
+/// deadline.
+#[derive(Debug)]
+pub struct Delay {
+    id: u64,
+    runtime: ManualRuntime,
+}
+
+impl Future for Delay {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.runtime.shared.lock();
+        let elapsed = state.elapsed;
+        match state.timers.get_mut(&self.id) {
+            Some(timer) if timer.deadline > elapsed => {
+                timer.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+            _ => {
+                state.timers.remove(&self.id);
+                Poll::Ready(())
+            }
+        }
+    }
+}
+
+impl Drop for Delay {
+    fn drop(&mut self) {
+        self.runtime.shared.lock().timers.remove(&self.id);
+    }
+}
+
+/// A stream ticking every period of the time of a [`ManualRuntime`].
+#[derive(Debug)]
+pub struct Interval {
+    id: u64,
+    period: Duration,
+    runtime: ManualRuntime,
+}
+
+impl Stream for Interval {
+    type Item = ();
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<()>> {
+        let period = self.period;
+        let mut state = self.runtime.shared.lock();
+        let elapsed = state.elapsed;
+        match state.timers.get_mut(&self.id) {
+            Some(timer) if timer.deadline > elapsed => {
+                timer.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+            Some(timer) => {
+                timer.deadline += period;
+                Poll::Ready(Some(()))
+            }
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+impl Drop for Interval {
+    fn drop(&mut self) {
+        self.runtime.shared.lock().timers.remove(&self.id);
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum TaskState {
+    /// Waiting to be woken.
+    Idle,
+    /// Woken while idle, about to be polled.
+    Scheduled,
+    Running,
+    /// Woken while running, polled again right away.
+    Notified,
+}
+
+/// A task of a [`ManualRuntime`], counted as running unless idle.
+struct Task {
+    state: Mutex<TaskState>,
+    woken: Condvar,
+    shared: Arc<Shared>,
+}
+
+impl Task {
+    fn lock(&self) -> MutexGuard<'_, TaskState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn run(self: Arc<Self>, mut future: BoxFuture<'static, ()>) {
+        let waker = waker(self.clone());
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            if future.as_mut().poll(&mut cx).is_ready() {
+                // dropped before settling, so its timers are gone once idle
+                drop(future);
+                self.shared.task_settled();
+                return;
+            }
+            let mut state = self.lock();
+            if *state == TaskState::Notified {
+                *state = TaskState::Running;
+                continue;
+            }
+            *state = TaskState::Idle;
+            self.shared.task_settled();
+            while *state != TaskState::Scheduled {
+                state = self
+                    .woken
+                    .wait(state)
+                    .unwrap_or_else(PoisonError::into_inner);
+            }
+            *state = TaskState::Running;
+        }
+    }
+}
+
+impl ArcWake for Task {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        let mut state = arc_self.lock();
+        match *state {
+            TaskState::Idle => {
+                *state = TaskState::Scheduled;
+                arc_self.shared.task_started();
+                arc_self.woken.notify_one();
+            }
+            TaskState::Running => *state = TaskState::Notified,
+            TaskState::Scheduled | TaskState::Notified => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use std::sync::mpsc;
+
+    #[test]
+    fn timers_fire_when_time_is_advanced() {
+        let runtime = ManualRuntime::new();
+        let (sender, receiver) = mpsc::channel();
+        let delay = runtime.delay(Duration::from_secs(3));
+        let ticks = runtime.interval(Duration::from_secs(2));
+        runtime.spawn(Box::pin(async move {
+            let mut ticks = ticks.take(2);
+            while ticks.next().await.is_some() {
+                sender.send("tick").unwrap();
+            }
+            delay.await;
+            sender.send("delay").unwrap();
+        }));
+
+        runtime.wait_until_idle();
+        assert_eq!(
+            runtime.scheduled(),
+            vec![Duration::from_secs(2), Duration::from_secs(3)]
+        );
+        runtime.advance(Duration::from_secs(1));
+        assert!(receiver.try_recv().is_err());
+
+        runtime.advance(Duration::from_secs(3));
+        assert_eq!(runtime.elapsed(), Duration::from_secs(4));
+        assert_eq!(receiver.try_recv(), Ok("tick"));
+        assert_eq!(receiver.try_recv(), Ok("tick"));
+        assert_eq!(receiver.try_recv(), Ok("delay"));
+        assert!(runtime.scheduled().is_empty());
+    }
+}
@@ -0,0 +1,289 @@
+//! # Span Testing Utilities
+//!
+//! An exporter collecting finished spans in memory so tests can assert on
+//! them, and a builder of spans with fixed ids and times for tests of
+//! exporters.
+//!
+//! # Examples
+//!
+//! ```
+//! use opentelemetry::sdk::{testing::trace::InMemorySpanExporter, trace::TracerProvider};
+//! use opentelemetry::trace::{Tracer, TracerProvider as _};
+//!
+//! let exporter = InMemorySpanExporter::default();
+//! let provider = TracerProvider::builder()
+//!     .with_in_memory_exporter(&exporter)
+//!     .build();
+//!
+//! provider.get_tracer("tests", None).in_span("work", |_cx| {});
+//!
+//! let spans = exporter.get_finished_spans().unwrap();
+//! assert_eq!(spans.len(), 1);
+//! assert_eq!(spans[0].name, "work");
+//! ```
+use crate::sdk::export::trace::{ExportResult, SpanData, SpanExporter};
+use crate::sdk::trace::{Config, EvictedHashMap, EvictedQueue};
+use crate::sdk::{InstrumentationLibrary, Resource};
+use crate::trace::{
+    Event, Link, SpanContext, SpanId, SpanKind, StatusCode, TraceError, TraceId, TraceResult,
+    TraceState, TRACE_FLAG_SAMPLED,
+};
+use crate::KeyValue;
+use async_trait::async_trait;
+use std::borrow::Cow;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A [`SpanExporter`] keeping the exported spans in memory.
+///
+/// Clones share the same spans, so a clone can be handed to the tracer
+/// provider while the original is kept to inspect the spans. Spans are kept
+/// when the exporter is shut down, so they can still be inspected after the
+/// provider was dropped.
+#[derive(Clone, Debug, Default)]
+pub struct InMemorySpanExporter {
+    spans: Arc<Mutex<Vec<SpanData>>>,
+}
+
+impl InMemorySpanExporter {
+    /// Create an exporter without spans.
+    pub fn new() -> Self {
+        InMemorySpanExporter::default()
+    }
+
+    /// The spans exported so far, in the order they were exported.
+    pub fn get_finished_spans(&self) -> TraceResult<Vec<SpanData>> {
+        self.spans
+            .lock()
+            .map(|spans| spans.clone())
+            .map_err(|err| TraceError::from(err.to_string()))
+    }
+
+    /// Remove the exported spans.
+    pub fn clear(&self) {
+        if let Ok(mut spans) = self.spans.lock() {
+            spans.clear();
+        }
+    }
+}
+
+#[async_trait]
+impl SpanExporter for InMemorySpanExporter {
+    async fn export(&mut self, batch: Vec<SpanData>) -> ExportResult {
+        self.spans
+            .lock()
+            .map(|mut spans| spans.extend(batch))
+            .map_err(|err| TraceError::from(err.to_string()))
+    }
+}
+
+/// A builder of [`SpanData`], created with [`SpanData::builder`].
+///
+/// Spans are built with fixed values unless set otherwise, so the output of
+/// exporters stays the same between test runs:
+///
+/// * trace id `1`, span id `1`, sampled, without a parent
+/// * the name `span` and the kind [`SpanKind::Internal`]
+/// * started one second after the unix epoch, ended one second later
+/// * no attributes, events or links and the status [`StatusCode::Unset`]
+/// * an empty resource and the instrumentation library `test`
+///
+/// Attributes, events and links are limited as by the default [`Config`].
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::sdk::export::trace::SpanData;
+/// use opentelemetry::trace::{SpanKind, StatusCode};
+/// use opentelemetry::KeyValue;
+///
+/// let span = SpanData::builder()
+///     .with_name("GET /users")
+///     .with_kind(SpanKind::Server)
+///     .with_attributes(vec![KeyValue::new("http.status_code", 500)])
+///     .with_status(StatusCode::Error, "internal error")
+///     .build();
+///
+/// assert_eq!(span.name, "GET /users");
+/// assert_eq!(span.attributes.len(), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SpanDataBuilder {
+    span: SpanData,
+}
+
+impl Default for SpanDataBuilder {
+    fn default() -> Self {
+        let config = Config::default();
+        let start_time = UNIX_EPOCH + Duration::from_secs(1);
+        SpanDataBuilder {
+            span: SpanData {
+                span_context: SpanContext::new(
+                    TraceId::from_u128(1),
+                    SpanId::from_u64(1),
+                    TRACE_FLAG_SAMPLED,
+                    false,
+                    TraceState::default(),
+                ),
+                parent_span_id: SpanId::invalid(),
+                span_kind: SpanKind::Internal,
+                name: "span".into(),
+                start_time,
+                end_time: start_time + Duration::from_secs(1),
+                attributes: EvictedHashMap::new(config.max_attributes_per_span, 0),
+                message_events: EvictedQueue::new(config.max_events_per_span),
+                links: EvictedQueue::new(config.max_links_per_span),
+                status_code: StatusCode::Unset,
+                status_message: String::new(),
+                resource: Arc::new(Resource::empty()),
+                instrumentation_lib: InstrumentationLibrary::new("test", None),
+            },
+        }
+    }
+}
+
+impl SpanDataBuilder {
+    /// Set the context of the span, e.g. to test the trace state or flags.
+    pub fn with_span_context(mut self, span_context: SpanContext) -> Self {
+        self.span.span_context = span_context;
+        self
+    }
+
+    /// Set the trace id, keeping the rest of the context.
+    pub fn with_trace_id(mut self, trace_id: TraceId) -> Self {
+        let context = &self.span.span_context;
+        self.span.span_context = SpanContext::new(
+            trace_id,
+            context.span_id(),
+            context.trace_flags(),
+            context.is_remote(),
+            context.trace_state().clone(),
+        );
+        self
+    }
+
+    /// Set the span id, keeping the rest of the context.
+    pub fn with_span_id(mut self, span_id: SpanId) -> Self {
+        let context = &self.span.span_context;
+        self.span.span_context = SpanContext::new(
+            context.trace_id(),
+            span_id,
+            context.trace_flags(),
+            context.is_remote(),
+            context.trace_state().clone(),
+        );
+        self
+    }
+
+    /// Set the id of the parent span.
+    pub fn with_parent_span_id(mut self, parent_span_id: SpanId) -> Self {
+        self.span.parent_span_id = parent_span_id;
+        self
+    }
+
+    /// Set the name of the span.
+    pub fn with_name<T: Into<Cow<'static, str>>>(mut self, name: T) -> Self {
+        self.span.name = name.into();
+        self
+    }
+
+    /// Set the kind of the span.
+    pub fn with_kind(mut self, kind: SpanKind) -> Self {
+        self.span.span_kind = kind;
+        self
+    }
+
+    /// Set the start time, keeping the duration of the span.
+    pub fn with_start_time(mut self, start_time: SystemTime) -> Self {
+        let duration = self.span.duration();
+        self.span.start_time = start_time;
+        self.span.end_time = start_time + duration;
+        self
+    }
+
+    /// Set the end time of the span.
+    pub fn with_end_time(mut self, end_time: SystemTime) -> Self {
+        self.span.end_time = end_time;
+        self
+    }
+
+    /// Add attributes to the span, replacing attributes with the same keys.
+    pub fn with_attributes<T: IntoIterator<Item = KeyValue>>(mut self, attributes: T) -> Self {
+        for attribute in attributes {
+            self.span.attributes.insert(attribute);
+        }
+        self
+    }
+
+    /// Add an event to the span.
+    pub fn with_event(mut self, event: Event) -> Self {
+        self.span.message_events.append_vec(&mut vec![event]);
+        self
+    }
+
+    /// Add a link to the span.
+    pub fn with_link(mut self, link: Link) -> Self {
+        self.span.links.append_vec(&mut vec![link]);
+        self
+    }
+
+    /// Set the status of the span.
+    pub fn with_status<T: Into<String>>(mut self, code: StatusCode, message: T) -> Self {
+        self.span.status_code = code;
+        self.span.status_message = message.into();
+        self
+    }
+
+    /// Set the resource the span was produced by.
+    pub fn with_resource(mut self, resource: Resource) -> Self {
+        self.span.resource = Arc::new(resource);
+        self
+    }
+
+    /// Set the instrumentation library the span was produced by.
+    pub fn with_instrumentation_library(mut self, library: InstrumentationLibrary) -> Self {
+        self.span.instrumentation_lib = library;
+        self
+    }
+
+    /// Build the span.
+    pub fn build(self) -> SpanData {
+        self.span
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdk::trace::TracerProvider;
+    use crate::trace::{Span, Tracer, TracerProvider as _};
+
+    #[test]
+    fn collect_finished_spans() {
+        let exporter = InMemorySpanExporter::new();
+        let provider = TracerProvider::builder()
+            .with_in_memory_exporter(&exporter)
+            .build();
+        let tracer = provider.get_tracer("tests", None);
+
+        let span = tracer.start("unfinished");
+        tracer.in_span("first", |_cx| {});
+        tracer.in_span("second", |_cx| {});
+        let names =
+            |spans: Vec<SpanData>| spans.into_iter().map(|span| span.name).collect::<Vec<_>>();
+        assert_eq!(
+            names(exporter.get_finished_spans().unwrap()),
+            vec!["first", "second"]
+        );
+
+        exporter.clear();
+        assert!(exporter.get_finished_spans().unwrap().is_empty());
+
+        span.end();
+        drop(provider);
+        assert_eq!(
+            names(exporter.get_finished_spans().unwrap()),
+            vec!["unfinished"]
+        );
+    }
+}
@@ -0,0 +1,194 @@
+//! # Attribute Cardinality Guard
+//!
+//! Attributes whose values are unique per request, e.g. a user id recorded
+//! where a route was intended, multiply the number of series and indexes
+//! backends have to maintain. Such mistakes are usually only noticed once the
+//! backend starts rejecting data.
+//!
+//! A [`CardinalityGuard`] counts the distinct values recorded for each
+//! attribute key within a time window. Once a key exceeds the configured
+//! number of values, further new values are replaced by
+//! [`HIGH_CARDINALITY`] and the key is reported to the global error handler.
+use crate::trace::TraceError;
+use crate::{global, Key, Value};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+/// Value recorded in place of values of a high cardinality attribute.
+pub const HIGH_CARDINALITY: &str = "HIGH_CARDINALITY";
+
+/// Default duration after which distinct values are counted from zero again.
+const DEFAULT_WINDOW: Duration = Duration::from_secs(60);
+/// Default maximum number of attribute keys tracked in a window.
+const DEFAULT_MAX_KEYS: usize = 1_024;
+
+/// Tracks the distinct values of span attributes and replaces values of keys
+/// exceeding a threshold.
+///
+/// Values are counted per key over a window of recent spans, by default one
+/// minute. The memory used is bounded by the number of tracked keys times the
+/// threshold, keys beyond the tracked limit are passed through unchecked.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::sdk::trace::{config, CardinalityGuard};
+/// use std::time::Duration;
+///
+/// let guard = CardinalityGuard::new(100).with_window(Duration::from_secs(300));
+/// let config = config().with_cardinality_guard(guard);
+/// ```
+pub struct CardinalityGuard {
+    max_values_per_key: usize,
+    max_keys: usize,
+    window: Duration,
+    state: Mutex<GuardState>,
+}
+
+struct GuardState {
+    window_start: SystemTime,
+    values: HashMap<Key, HashSet<u64>>,
+    reported: HashSet<Key>,
+}
+
+impl fmt::Debug for CardinalityGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CardinalityGuard")
+            .field("max_values_per_key", &self.max_values_per_key)
+            .field("max_keys", &self.max_keys)
+            .field("window", &self.window)
+            .finish()
+    }
+}
+
+impl CardinalityGuard {
+    /// Create a guard allowing at most `max_values_per_key` distinct values
+    /// per attribute key within a window.
+    pub fn new(max_values_per_key: usize) -> Self {
+        CardinalityGuard {
+            max_values_per_key,
+            max_keys: DEFAULT_MAX_KEYS,
+            window: DEFAULT_WINDOW,
+            state: Mutex::new(GuardState {
+                window_start: crate::time::now(),
+                values: HashMap::new(),
+                reported: HashSet::new(),
+            }),
+        }
+    }
+
+    /// Set the duration after which distinct values are counted from zero
+    /// again.
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Set the maximum number of attribute keys tracked within a window.
+    pub fn with_max_keys(mut self, max_keys: usize) -> Self {
+        self.max_keys = max_keys;
+        self
+    }
+
+    /// Returns the value to record for `key`, either `value` or
+    /// [`HIGH_CARDINALITY`] if the key has too many distinct values.
+    pub fn check(&self, key: &Key, value: Value) -> Value {
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(_) => return value,
+        };
+
+        let now = crate::time::now();
+        let expired = match now.duration_since(state.window_start) {
+            Ok(elapsed) => elapsed >= self.window,
+            Err(_) => false,
+        };
+        if expired {
+            state.window_start = now;
+            state.values.clear();
+        }
+
+        if !state.values.contains_key(key) && state.values.len() >= self.max_keys {
+            return value;
+        }
+
+        let mut hasher = DefaultHasher::new();
+        value.as_str().hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let values = state.values.entry(key.clone()).or_insert_with(HashSet::new);
+        if values.contains(&hash) {
+            return value;
+        }
+        if values.len() < self.max_values_per_key {
+            values.insert(hash);
+            return value;
+        }
+
+        if state.reported.insert(key.clone()) {
+            global::handle_error(TraceError::from(format!(
+                "attribute {} exceeded {} distinct values, recording {} instead",
+                key.as_str(),
+                self.max_values_per_key,
+                HIGH_CARDINALITY
+            )));
+        }
+
+        Value::from(HIGH_CARDINALITY)
+    }
+
+    /// Returns the keys that exceeded the threshold so far.
+    pub fn high_cardinality_keys(&self) -> Vec<Key> {
+        self.state
+            .lock()
+            .map(|state| state.reported.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_values_beyond_threshold() {
+        let guard = CardinalityGuard::new(2);
+        let key = Key::new("user.id");
+
+        assert_eq!(guard.check(&key, Value::from("a")), Value::from("a"));
+        assert_eq!(guard.check(&key, Value::from("b")), Value::from("b"));
+        assert_eq!(
+            guard.check(&key, Value::from("c")),
+            Value::from(HIGH_CARDINALITY)
+        );
+        // values seen before the threshold was reached are still recorded
+        assert_eq!(guard.check(&key, Value::from("a")), Value::from("a"));
+        assert_eq!(
+            guard.check(&Key::new("http.method"), Value::from("c")),
+            Value::from("c")
+        );
+        assert_eq!(guard.high_cardinality_keys(), vec![key]);
+    }
+
+    #[test]
+    fn counts_values_per_window() {
+        let guard = CardinalityGuard::new(1).with_window(Duration::from_secs(0));
+        let key = Key::new("user.id");
+
+        assert_eq!(guard.check(&key, Value::from("a")), Value::from("a"));
+        assert_eq!(guard.check(&key, Value::from("b")), Value::from("b"));
+    }
+
+    #[test]
+    fn untracked_keys_pass_through() {
+        let guard = CardinalityGuard::new(1).with_max_keys(1);
+
+        assert_eq!(guard.check(&Key::new("a"), Value::I64(1)), Value::I64(1));
+        assert_eq!(guard.check(&Key::new("b"), Value::I64(1)), Value::I64(1));
+        assert_eq!(guard.check(&Key::new("b"), Value::I64(2)), Value::I64(2));
+    }
+}
@@ -2,10 +2,17 @@
 //!
 //! Configuration represents the global tracing configuration, overrides
 //! can be set for the default OpenTelemetry limits and Sampler.
-use crate::{sdk, sdk::trace::Sampler, trace::IdGenerator};
+use crate::{
+    sdk,
+    sdk::trace::{CardinalityGuard, Sampler, StringInterner},
+    sdk::{Clock, SystemClock},
+    trace::IdGenerator,
+};
+use std::collections::HashSet;
 use std::env;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Default trace configuration
 pub fn config() -> Config {
@@ -19,6 +26,8 @@ pub struct Config {
     pub default_sampler: Box<dyn sdk::trace::ShouldSample>,
     /// The id generator that the sdk should use
     pub id_generator: Box<dyn IdGenerator>,
+    /// The clock the timestamps of spans and events are read from.
+    pub clock: Arc<dyn Clock>,
     /// The max events that can be added to a `Span`.
     pub max_events_per_span: u32,
     /// The max attributes that can be added to a `Span`.
@@ -27,6 +36,15 @@ pub struct Config {
     pub max_links_per_span: u32,
     /// Contains attributes representing an entity that produces telemetry.
     pub resource: Arc<sdk::Resource>,
+    /// Optional pool used to intern string attribute values.
+    pub string_interner: Option<Arc<StringInterner>>,
+    /// Optional guard replacing values of high cardinality attributes.
+    pub cardinality_guard: Option<Arc<CardinalityGuard>>,
+    /// Names of the instrumentation libraries whose spans are not recorded.
+    pub disabled_libraries: HashSet<String>,
+    /// Optional logger emitting span events with a severity as log records.
+    #[cfg(feature = "logs")]
+    pub event_logger: Option<Arc<dyn crate::logs::Logger>>,
 }
 
 impl Config {
@@ -45,6 +63,18 @@ impl Config {
         self
     }
 
+    /// Specify the clock the timestamps of spans and events are read from,
+    /// the [`SystemClock`] by default.
+    ///
+    /// Timestamps given explicitly, e.g. with [`SpanBuilder::with_start_time`],
+    /// are kept as they are.
+    ///
+    /// [`SpanBuilder::with_start_time`]: crate::trace::SpanBuilder::with_start_time
+    pub fn with_clock<T: Clock + 'static>(mut self, clock: T) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
     /// Specify the number of events to be recorded per span.
     pub fn with_max_events_per_span(mut self, max_events: u32) -> Self {
         self.max_events_per_span = max_events;
@@ -68,6 +98,62 @@ impl Config {
         self.resource = Arc::new(resource);
         self
     }
+
+    /// Specify the interner used to share repeated string attribute values
+    /// between spans.
+    pub fn with_string_interner(mut self, interner: StringInterner) -> Self {
+        self.string_interner = Some(Arc::new(interner));
+        self
+    }
+
+    /// Specify the guard used to detect attributes with too many distinct
+    /// values.
+    pub fn with_cardinality_guard(mut self, guard: CardinalityGuard) -> Self {
+        self.cardinality_guard = Some(Arc::new(guard));
+        self
+    }
+
+    /// Specify instrumentation libraries whose spans should not be recorded.
+    ///
+    /// Tracers obtained with one of the given names only create non-recording
+    /// spans, which still propagate the context of their parent. This mutes
+    /// chatty instrumentation of dependencies without changing their code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use opentelemetry::sdk::trace::config;
+    ///
+    /// let config = config().with_disabled_libraries(vec!["noisy_dep"]);
+    /// ```
+    pub fn with_disabled_libraries<I, T>(mut self, libraries: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.disabled_libraries
+            .extend(libraries.into_iter().map(Into::into));
+        self
+    }
+
+    /// Specify a logger emitting the span events added with a severity as log
+    /// records, correlated with their span.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use opentelemetry::logs::LoggerProvider as _;
+    /// use opentelemetry::sdk::{logs::LoggerProvider, trace::config};
+    ///
+    /// let logger_provider = LoggerProvider::builder().build();
+    /// let config = config().with_event_logger(logger_provider.get_logger("span-events", None));
+    /// ```
+    #[cfg(feature = "logs")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "logs")))]
+    pub fn with_event_logger<L: crate::logs::Logger + 'static>(mut self, logger: L) -> Self {
+        self.event_logger = Some(Arc::new(logger));
+        self
+    }
 }
 
 impl Default for Config {
@@ -76,10 +162,16 @@ impl Default for Config {
         let mut config = Config {
             default_sampler: Box::new(Sampler::ParentBased(Box::new(Sampler::AlwaysOn))),
             id_generator: Box::new(sdk::trace::IdGenerator::default()),
+            clock: Arc::new(SystemClock),
             max_events_per_span: 128,
             max_attributes_per_span: 128,
             max_links_per_span: 128,
             resource: Arc::new(sdk::Resource::default()),
+            string_interner: None,
+            cardinality_guard: None,
+            disabled_libraries: HashSet::new(),
+            #[cfg(feature = "logs")]
+            event_logger: None,
         };
 
         if let Some(max_attributes_per_span) = env::var("OTEL_SPAN_ATTRIBUTE_COUNT_LIMIT")
@@ -106,3 +198,67 @@ impl Default for Config {
         config
     }
 }
+
+/// Changes to the configuration of a running provider, see
+/// [`TracerProvider::update_config`].
+///
+/// Settings which are not specified are left as they are.
+///
+/// [`TracerProvider::update_config`]: crate::sdk::trace::TracerProvider::update_config
+#[derive(Debug, Default)]
+pub struct ConfigUpdate {
+    pub(crate) default_sampler: Option<Arc<dyn sdk::trace::ShouldSample>>,
+    pub(crate) max_events_per_span: Option<u32>,
+    pub(crate) max_attributes_per_span: Option<u32>,
+    pub(crate) max_links_per_span: Option<u32>,
+    scheduled_delay: Option<Duration>,
+}
+
+impl ConfigUpdate {
+    /// Create an update leaving all settings as they are.
+    pub fn new() -> Self {
+        ConfigUpdate::default()
+    }
+
+    /// Replace the sampler of spans started from now on.
+    pub fn with_default_sampler<T: sdk::trace::ShouldSample + 'static>(
+        mut self,
+        sampler: T,
+    ) -> Self {
+        self.default_sampler = Some(Arc::new(sampler));
+        self
+    }
+
+    /// Change the number of events recorded per span started from now on.
+    pub fn with_max_events_per_span(mut self, max_events: u32) -> Self {
+        self.max_events_per_span = Some(max_events);
+        self
+    }
+
+    /// Change the number of attributes recorded per span started from now on.
+    pub fn with_max_attributes_per_span(mut self, max_attributes: u32) -> Self {
+        self.max_attributes_per_span = Some(max_attributes);
+        self
+    }
+
+    /// Change the number of links recorded per span started from now on.
+    pub fn with_max_links_per_span(mut self, max_links: u32) -> Self {
+        self.max_links_per_span = Some(max_links);
+        self
+    }
+
+    /// Change the delay between two consecutive exports of batch processors.
+    ///
+    /// The new delay applies from the next scheduled export on, spans
+    /// buffered so far are kept.
+    pub fn with_scheduled_delay(mut self, delay: Duration) -> Self {
+        self.scheduled_delay = Some(delay);
+        self
+    }
+
+    /// The new delay between two consecutive exports of batch processors,
+    /// if it changes.
+    pub fn scheduled_delay(&self) -> Option<Duration> {
+        self.scheduled_delay
+    }
+}
@@ -0,0 +1,314 @@
+//! # Self-diagnostics
+//!
+//! A snapshot of the health of a tracer provider, to find out why spans don't
+//! arrive without attaching a debugger: whether exports succeed and how long
+//! they take, whether spans are dropped because processor queues are full,
+//! which sampler is active and which resource spans are reported with.
+//!
+//! See [`TracerProvider::diagnostics`].
+//!
+//! [`TracerProvider::diagnostics`]: crate::sdk::trace::TracerProvider::diagnostics
+use crate::sdk::export::trace::{ExportResult, SpanData, SpanExporter};
+use async_trait::async_trait;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// A snapshot of the health of a tracer provider.
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Diagnostics {
+    /// The default sampler of the provider, as formatted by `Debug`.
+    pub sampler: String,
+    /// The attributes of the resource new spans are associated with.
+    pub resource: BTreeMap<String, String>,
+    /// The span processors of the provider, in the order they are called.
+    pub processors: Vec<ProcessorDiagnostics>,
+}
+
+/// A snapshot of the state of a span processor.
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProcessorDiagnostics {
+    /// The name of the processor.
+    pub name: String,
+    /// Whether the processor panicked too often and no longer receives spans.
+    pub quarantined: bool,
+    /// The queue of processors buffering spans before exporting them.
+    pub queue: Option<QueueDiagnostics>,
+    /// The exporter of processors exporting spans.
+    pub exporter: Option<ExporterDiagnostics>,
+    /// The processors spans are passed on to, e.g. by a sharded processor.
+    pub processors: Vec<ProcessorDiagnostics>,
+}
+
+impl ProcessorDiagnostics {
+    /// Create diagnostics of a processor reporting its name only.
+    pub fn new<T: Into<String>>(name: T) -> Self {
+        ProcessorDiagnostics {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// A snapshot of the queue of a span processor.
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueueDiagnostics {
+    /// The number of spans waiting to be exported.
+    pub len: usize,
+    /// The maximum number of spans the queue holds.
+    pub capacity: usize,
+    /// The number of spans dropped so far, e.g. because the queue was full.
+    pub dropped: usize,
+}
+
+/// The exports of a span processor so far.
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExporterDiagnostics {
+    /// The number of exports, including failed ones.
+    pub exports: u64,
+    /// The number of failed exports.
+    pub failures: u64,
+    /// When the last successful export finished.
+    pub last_success: Option<SystemTime>,
+    /// When the last failed export finished.
+    pub last_failure: Option<SystemTime>,
+    /// The error of the last failed export.
+    pub last_error: Option<String>,
+    /// How long the last export took.
+    pub last_latency: Option<Duration>,
+}
+
+/// Records the exports of a [`RecordingExporter`].
+#[derive(Debug, Default)]
+pub(crate) struct ExportStats {
+    exports: Mutex<ExporterDiagnostics>,
+}
+
+impl ExportStats {
+    pub(crate) fn snapshot(&self) -> ExporterDiagnostics {
+        self.exports
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    fn record(&self, start: SystemTime, error: Option<String>) {
+        let end = crate::time::now();
+        let mut exports = self.exports.lock().unwrap_or_else(PoisonError::into_inner);
+        exports.exports += 1;
+        exports.last_latency = Some(end.duration_since(start).unwrap_or_default());
+        match error {
+            Some(error) => {
+                exports.failures += 1;
+                exports.last_failure = Some(end);
+                exports.last_error = Some(error);
+            }
+            None => exports.last_success = Some(end),
+        }
+    }
+}
+
+/// An exporter recording the outcome and latency of each export of the
+/// exporter it wraps.
+#[derive(Debug)]
+pub(crate) struct RecordingExporter {
+    exporter: Box<dyn SpanExporter>,
+    stats: Arc<ExportStats>,
+}
+
+impl RecordingExporter {
+    pub(crate) fn new(exporter: Box<dyn SpanExporter>) -> Self {
+        RecordingExporter {
+            exporter,
+            stats: Arc::default(),
+        }
+    }
+
+    /// The stats of the exporter, which stay available while it is moved
+    /// around by a batch worker.
+    pub(crate) fn stats(&self) -> Arc<ExportStats> {
+        self.stats.clone()
+    }
+}
+
+#[async_trait]
+impl SpanExporter for RecordingExporter {
+    async fn export(&mut self, batch: Vec<SpanData>) -> ExportResult {
+        let mut export = PendingExport {
+            stats: &self.stats,
+            start: crate::time::now(),
+            finished: false,
+        };
+        let result = self.exporter.export(batch).await;
+        export.finished = true;
+        export
+            .stats
+            .record(export.start, result.as_ref().err().map(ToString::to_string));
+        result
+    }
+
+    fn shutdown(&mut self) {
+        self.exporter.shutdown()
+    }
+}
+
+/// An export in progress, recorded as failed if it is dropped before it
+/// finished, i.e. if it panicked or timed out.
+struct PendingExport<'a> {
+    stats: &'a ExportStats,
+    start: SystemTime,
+    finished: bool,
+}
+
+impl Drop for PendingExport<'_> {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        let error = if thread::panicking() {
+            "the export panicked"
+        } else {
+            "the export was cancelled before it finished, e.g. because it timed out"
+        };
+        self.stats.record(self.start, Some(error.to_string()));
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::sdk::trace::{
+        BatchConfig, BatchSpanProcessor, Config, Sampler, ShardedSpanProcessor, TracerProvider,
+    };
+    use crate::sdk::Resource;
+    use crate::testing::trace::{new_test_export_span_data, new_test_exporter};
+    use crate::trace::{Span as _, TraceError, Tracer as _, TracerProvider as _};
+    use crate::KeyValue;
+    use futures::{executor, future};
+
+    #[derive(Debug)]
+    struct FailingExporter;
+
+    #[async_trait]
+    impl SpanExporter for FailingExporter {
+        async fn export(&mut self, _batch: Vec<SpanData>) -> ExportResult {
+            Err(TraceError::from("connection refused"))
+        }
+    }
+
+    #[derive(Debug)]
+    struct PendingExporter;
+
+    #[async_trait]
+    impl SpanExporter for PendingExporter {
+        async fn export(&mut self, _batch: Vec<SpanData>) -> ExportResult {
+            future::pending().await
+        }
+    }
+
+    #[test]
+    fn records_exports() {
+        let (exporter, _rx_export, _rx_shutdown) = new_test_exporter();
+        let mut exporter = RecordingExporter::new(Box::new(exporter));
+        let stats = exporter.stats();
+        executor::block_on(exporter.export(vec![new_test_export_span_data()])).unwrap();
+
+        let exports = stats.snapshot();
+        assert_eq!((exports.exports, exports.failures), (1, 0));
+        assert!(exports.last_success.is_some());
+        assert!(exports.last_latency.is_some());
+        assert_eq!(exports.last_failure, None);
+
+        let mut exporter = RecordingExporter::new(Box::new(FailingExporter));
+        let stats = exporter.stats();
+        assert!(executor::block_on(exporter.export(vec![])).is_err());
+        // cancelled exports are failures as well
+        let mut pending = RecordingExporter {
+            exporter: Box::new(PendingExporter),
+            stats: stats.clone(),
+        };
+        let export = pending.export(vec![]);
+        let raced = executor::block_on(future::select(export, future::ready(())));
+        assert!(matches!(raced, future::Either::Right(_)));
+        drop(raced);
+
+        let exports = stats.snapshot();
+        assert_eq!((exports.exports, exports.failures), (2, 2));
+        assert_eq!(exports.last_success, None);
+        assert!(exports
+            .last_error
+            .unwrap()
+            .starts_with("the export was cancelled"));
+    }
+
+    #[test]
+    fn provider_diagnostics() {
+        let (exporter, _rx_export, _rx_shutdown) = new_test_exporter();
+        let (batch_exporter, _rx_batch_export, _rx_batch_shutdown) = new_test_exporter();
+        let config = BatchConfig::default()
+            .with_max_queue_size(1)
+            .with_scheduled_delay(Duration::from_secs(3600));
+        let provider = TracerProvider::builder()
+            .with_config(
+                Config::default()
+                    .with_default_sampler(Sampler::AlwaysOn)
+                    .with_resource(Resource::new(vec![KeyValue::new("service.name", "test")])),
+            )
+            .with_simple_exporter(exporter)
+            .with_batch_exporter(BatchSpanProcessor::with_own_thread(batch_exporter, config))
+            .with_span_processor(ShardedSpanProcessor::new(vec![
+                BatchSpanProcessor::with_own_thread(FailingExporter, BatchConfig::default()),
+            ]))
+            .build();
+        let tracer = provider.get_tracer("diagnostics", None);
+        tracer.start("first").end();
+        tracer.start("second").end();
+
+        let diagnostics = provider.diagnostics();
+        assert_eq!(diagnostics.sampler, "AlwaysOn");
+        assert_eq!(
+            diagnostics.resource.get("service.name").map(String::as_str),
+            Some("test")
+        );
+        let names: Vec<_> = diagnostics
+            .processors
+            .iter()
+            .map(|processor| processor.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "SimpleSpanProcessor",
+                "BatchSpanProcessor",
+                "ShardedSpanProcessor"
+            ]
+        );
+
+        let simple = &diagnostics.processors[0];
+        assert_eq!(simple.queue, None);
+        assert_eq!(simple.exporter.as_ref().map(|e| e.exports), Some(2));
+
+        let batch = &diagnostics.processors[1];
+        assert_eq!(
+            batch.queue,
+            Some(QueueDiagnostics {
+                len: 1,
+                capacity: 1,
+                dropped: 1,
+            })
+        );
+        assert_eq!(batch.exporter, Some(ExporterDiagnostics::default()));
+
+        let sharded = &diagnostics.processors[2];
+        assert_eq!(sharded.processors.len(), 1);
+        assert_eq!(sharded.processors[0].name, "BatchSpanProcessor");
+        assert!(sharded.processors[0].exporter.is_some());
+    }
+}
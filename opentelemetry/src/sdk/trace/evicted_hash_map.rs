@@ -1,70 +1,99 @@
 //! # Evicted Map
 
+use crate::sdk::trace::pool::Recycler;
 use crate::{Key, KeyValue, Value};
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
-use std::collections::hash_map::Entry;
-use std::collections::{HashMap, LinkedList};
+use smallvec::SmallVec;
+use std::mem;
 
-/// A hash map with a capped number of attributes that retains the most
-/// recently set entries.
+/// The number of attributes stored inline, without allocating. Most spans
+/// have fewer attributes, so they are looked up by a linear scan.
+const INLINE_ATTRIBUTES: usize = 8;
+
+/// The storage of the entries of an `EvictedHashMap`.
+pub(crate) type AttributeBuffer = SmallVec<[KeyValue; INLINE_ATTRIBUTES]>;
+
+/// A map with a capped number of attributes that retains the most recently
+/// set entries.
+///
+/// Entries are kept in the order they were last set. The first few entries
+/// are stored inline, so recording the attributes of a typical span doesn't
+/// allocate.
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
 #[derive(Clone, Debug, PartialEq)]
 pub struct EvictedHashMap {
-    map: HashMap<Key, Value>,
-    evict_list: LinkedList<Key>,
+    entries: AttributeBuffer,
     max_len: u32,
     dropped_count: u32,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    recycler: Recycler<AttributeBuffer>,
 }
 
 impl EvictedHashMap {
     /// Create a new `EvictedHashMap` with a given max length and capacity.
     pub fn new(max_len: u32, capacity: usize) -> Self {
         EvictedHashMap {
-            map: HashMap::with_capacity(capacity),
-            evict_list: LinkedList::new(),
+            entries: SmallVec::with_capacity(capacity),
+            max_len,
+            dropped_count: 0,
+            recycler: Recycler::default(),
+        }
+    }
+
+    /// Create a map storing its entries in a buffer taken from `recycler` if
+    /// one is available, which the buffer is handed back to once the map is
+    /// dropped or consumed.
+    pub(crate) fn with_recycler(
+        max_len: u32,
+        capacity: usize,
+        recycler: Recycler<AttributeBuffer>,
+    ) -> Self {
+        let entries = recycler
+            .take()
+            .unwrap_or_else(|| SmallVec::with_capacity(capacity));
+        EvictedHashMap {
+            entries,
             max_len,
             dropped_count: 0,
+            recycler,
         }
     }
 
     /// Inserts a key-value pair into the map.
+    ///
+    /// An existing entry with the same key is replaced and becomes the most
+    /// recently set entry. The least recently set entry is evicted if the
+    /// map is full.
     pub fn insert(&mut self, item: KeyValue) {
-        let KeyValue { key, value } = item;
-        let mut already_exists = false;
-        // Check for existing item
-        match self.map.entry(key.clone()) {
-            Entry::Occupied(mut occupied) => {
-                occupied.insert(value);
-                already_exists = true;
+        if let Some(idx) = self.entries.iter().position(|entry| entry.key == item.key) {
+            if idx + 1 == self.entries.len() {
+                self.entries[idx] = item;
+            } else {
+                self.entries.remove(idx);
+                self.entries.push(item);
             }
-            Entry::Vacant(entry) => {
-                entry.insert(value);
-            }
-        }
-
-        if already_exists {
-            self.move_key_to_front(key);
-        } else {
-            // Add new item
-            self.evict_list.push_front(key);
+            return;
         }
 
-        // Verify size not exceeded
-        if self.evict_list.len() as u32 > self.max_len {
-            self.remove_oldest();
+        if self.entries.len() as u32 >= self.max_len {
             self.dropped_count += 1;
+            if self.entries.is_empty() {
+                return;
+            }
+            self.entries.remove(0);
         }
+        self.entries.push(item);
     }
 
     /// Returns the number of elements in the map.
     pub fn len(&self) -> usize {
-        self.map.len()
+        self.entries.len()
     }
 
     /// Returns `true` if the map is empty.
     pub fn is_empty(&self) -> bool {
-        self.map.is_empty()
+        self.entries.is_empty()
     }
 
     /// Returns the dropped attribute count
@@ -72,52 +101,64 @@ impl EvictedHashMap {
         self.dropped_count
     }
 
-    /// Returns a front-to-back iterator.
+    /// Returns an iterator from the least to the most recently set entry.
     pub fn iter(&self) -> Iter<'_> {
-        Iter(self.map.iter())
+        Iter(self.entries.iter())
     }
 
     /// Returns a reference to the value corresponding to the key if it exists
     pub fn get(&self, key: &Key) -> Option<&Value> {
-        self.map.get(key)
-    }
-
-    fn move_key_to_front(&mut self, key: Key) {
-        if self.evict_list.is_empty() {
-            // If empty, push front
-            self.evict_list.push_front(key);
-        } else if self.evict_list.front() == Some(&key) {
-            // Already the front, ignore
-        } else {
-            // Else split linked lists around key and combine
-            let key_idx = self
-                .evict_list
-                .iter()
-                .position(|k| k == &key)
-                .expect("key must exist in evicted hash map, this is a bug");
-            let mut tail = self.evict_list.split_off(key_idx);
-            let item = tail.pop_front().unwrap();
-            self.evict_list.push_front(item);
-            self.evict_list.append(&mut tail);
-        }
+        self.entries
+            .iter()
+            .find(|entry| &entry.key == key)
+            .map(|entry| &entry.value)
     }
 
-    fn remove_oldest(&mut self) {
-        if let Some(oldest_item) = self.evict_list.pop_back() {
-            self.map.remove(&oldest_item);
-        }
+    #[cfg(test)]
+    pub(crate) fn capacity(&self) -> usize {
+        self.entries.capacity()
+    }
+}
+
+impl Drop for EvictedHashMap {
+    fn drop(&mut self) {
+        recycle(&mut self.entries, &self.recycler);
+    }
+}
+
+/// Hand a buffer back to the recycler, unless it is stored inline and so
+/// costs no allocation anyway.
+fn recycle(entries: &mut AttributeBuffer, recycler: &Recycler<AttributeBuffer>) {
+    if recycler.is_enabled() && entries.spilled() {
+        let mut entries = mem::take(entries);
+        entries.clear();
+        recycler.give(entries);
     }
 }
 
 /// An owned iterator over the entries of a `EvictedHashMap`.
 #[derive(Debug)]
-pub struct IntoIter(std::collections::hash_map::IntoIter<Key, Value>);
+pub struct IntoIter {
+    // reversed, so entries are taken from the back and the buffer is kept
+    entries: AttributeBuffer,
+    recycler: Recycler<AttributeBuffer>,
+}
 
 impl Iterator for IntoIter {
     type Item = (Key, Value);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.0.next()
+        self.entries.pop().map(|entry| (entry.key, entry.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.entries.len(), Some(self.entries.len()))
+    }
+}
+
+impl Drop for IntoIter {
+    fn drop(&mut self) {
+        recycle(&mut self.entries, &self.recycler);
     }
 }
 
@@ -125,8 +166,13 @@ impl IntoIterator for EvictedHashMap {
     type Item = (Key, Value);
     type IntoIter = IntoIter;
 
-    fn into_iter(self) -> Self::IntoIter {
-        IntoIter(self.map.into_iter())
+    fn into_iter(mut self) -> Self::IntoIter {
+        let mut entries = mem::take(&mut self.entries);
+        entries.reverse();
+        IntoIter {
+            entries,
+            recycler: mem::take(&mut self.recycler),
+        }
     }
 }
 
@@ -135,19 +181,23 @@ impl<'a> IntoIterator for &'a EvictedHashMap {
     type IntoIter = Iter<'a>;
 
     fn into_iter(self) -> Self::IntoIter {
-        Iter(self.map.iter())
+        self.iter()
     }
 }
 
 /// An iterator over the entries of an `EvictedHashMap`.
 #[derive(Debug)]
-pub struct Iter<'a>(std::collections::hash_map::Iter<'a, Key, Value>);
+pub struct Iter<'a>(std::slice::Iter<'a, KeyValue>);
 
 impl<'a> Iterator for Iter<'a> {
     type Item = (&'a Key, &'a Value);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.0.next()
+        self.0.next().map(|entry| (&entry.key, &entry.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
     }
 }
 
@@ -168,10 +218,43 @@ mod tests {
         assert_eq!(map.dropped_count, 1);
         assert_eq!(map.len(), max_len as usize);
         assert_eq!(
-            map.map.keys().cloned().collect::<HashSet<_>>(),
+            map.iter()
+                .map(|(key, _)| key.clone())
+                .collect::<HashSet<_>>(),
             (1..=max_len)
                 .map(|i| Key::new(i.to_string()))
                 .collect::<HashSet<_>>()
         );
     }
+
+    #[test]
+    fn insert_keeps_entries_in_order_of_last_set() {
+        let mut map = EvictedHashMap::new(3, 0);
+        map.insert(KeyValue::new("a", 1));
+        map.insert(KeyValue::new("b", 2));
+        map.insert(KeyValue::new("c", 3));
+        map.insert(KeyValue::new("a", 4));
+        // "b" is the least recently set entry now
+        map.insert(KeyValue::new("d", 5));
+
+        assert_eq!(map.dropped_count(), 1);
+        assert_eq!(map.get(&Key::new("b")), None);
+        assert_eq!(map.get(&Key::new("a")), Some(&Value::I64(4)));
+        assert_eq!(
+            map.into_iter().collect::<Vec<_>>(),
+            vec![
+                (Key::new("c"), Value::I64(3)),
+                (Key::new("a"), Value::I64(4)),
+                (Key::new("d"), Value::I64(5)),
+            ]
+        );
+    }
+
+    #[test]
+    fn zero_max_len_drops_all_entries() {
+        let mut map = EvictedHashMap::new(0, 0);
+        map.insert(KeyValue::new("a", 1));
+        assert!(map.is_empty());
+        assert_eq!(map.dropped_count(), 1);
+    }
 }
@@ -1,8 +1,10 @@
 //! # Evicted Queue
 
+use crate::sdk::trace::pool::Recycler;
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::mem;
 
 /// This queue maintains an ordered list of elements, and a count of
 /// dropped elements. Elements are removed from the queue in a first
@@ -13,6 +15,8 @@ pub struct EvictedQueue<T> {
     queue: Option<VecDeque<T>>,
     max_len: u32,
     dropped_count: u32,
+    #[cfg_attr(feature = "serialize", serde(skip, default = "Recycler::default"))]
+    recycler: Recycler<VecDeque<T>>,
 }
 
 impl<T> EvictedQueue<T> {
@@ -22,13 +26,23 @@ impl<T> EvictedQueue<T> {
             queue: None,
             max_len,
             dropped_count: 0,
+            recycler: Recycler::default(),
         }
     }
 
+    /// Take the buffer of the queue from `recycler` if one is available, and
+    /// hand it back once the queue is dropped or consumed.
+    pub(crate) fn set_recycler(&mut self, recycler: Recycler<VecDeque<T>>) {
+        self.recycler = recycler;
+    }
+
     /// Push a new element to the back of the queue, dropping and
     /// recording dropped count if over capacity.
     pub(crate) fn push_back(&mut self, value: T) {
-        let queue = self.queue.get_or_insert_with(Default::default);
+        let recycler = &self.recycler;
+        let queue = self
+            .queue
+            .get_or_insert_with(|| recycler.take().unwrap_or_default());
         if queue.len() as u32 == self.max_len {
             queue.pop_front();
             self.dropped_count += 1;
@@ -51,6 +65,11 @@ impl<T> EvictedQueue<T> {
         Iter(self.queue.as_ref().map(|queue| queue.iter()))
     }
 
+    /// Returns a front-to-back iterator returning mutable references.
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.queue.iter_mut().flat_map(|queue| queue.iter_mut())
+    }
+
     /// Returns the number of elements in the `EvictedQueue`.
     pub fn len(&self) -> usize {
         self.queue.as_ref().map_or(0, |queue| queue.len())
@@ -60,17 +79,52 @@ impl<T> EvictedQueue<T> {
     pub fn dropped_count(&self) -> u32 {
         self.dropped_count
     }
+
+    #[cfg(test)]
+    pub(crate) fn capacity(&self) -> usize {
+        self.queue.as_ref().map_or(0, |queue| queue.capacity())
+    }
+}
+
+impl<T> Drop for EvictedQueue<T> {
+    fn drop(&mut self) {
+        recycle(self.queue.take(), &self.recycler);
+    }
+}
+
+/// Hand an allocated buffer back to the recycler.
+fn recycle<T>(queue: Option<VecDeque<T>>, recycler: &Recycler<VecDeque<T>>) {
+    if let Some(mut queue) = queue {
+        if recycler.is_enabled() && queue.capacity() > 0 {
+            queue.clear();
+            recycler.give(queue);
+        }
+    }
 }
 
 /// An owned iterator over the entries of a `EvictedQueue`.
 #[derive(Debug)]
-pub struct IntoIter<T>(Option<std::collections::vec_deque::IntoIter<T>>);
+pub struct IntoIter<T> {
+    queue: Option<VecDeque<T>>,
+    recycler: Recycler<VecDeque<T>>,
+}
 
 impl<T> Iterator for IntoIter<T> {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.0.as_mut().and_then(|iter| iter.next())
+        self.queue.as_mut().and_then(|queue| queue.pop_front())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.queue.as_ref().map_or(0, |queue| queue.len());
+        (len, Some(len))
+    }
+}
+
+impl<T> Drop for IntoIter<T> {
+    fn drop(&mut self) {
+        recycle(self.queue.take(), &self.recycler);
     }
 }
 
@@ -78,8 +132,11 @@ impl<T> IntoIterator for EvictedQueue<T> {
     type Item = T;
     type IntoIter = IntoIter<T>;
 
-    fn into_iter(self) -> Self::IntoIter {
-        IntoIter(self.queue.map(|queue| queue.into_iter()))
+    fn into_iter(mut self) -> Self::IntoIter {
+        IntoIter {
+            queue: self.queue.take(),
+            recycler: mem::take(&mut self.recycler),
+        }
     }
 }
 
@@ -118,7 +175,7 @@ mod tests {
         assert_eq!(queue.dropped_count, 1);
         assert_eq!(queue.len(), capacity as usize);
         assert_eq!(
-            queue.queue.unwrap(),
+            queue.queue.take().unwrap(),
             (1..=capacity).collect::<VecDeque<_>>()
         );
     }
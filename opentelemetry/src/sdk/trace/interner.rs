@@ -0,0 +1,184 @@
+//! # String Interner
+//!
+//! Many span attribute values are drawn from a small set of strings, e.g.
+//! HTTP methods, status strings or peer service names. Recording them as
+//! owned `String`s means every span (and every clone of its data handed to a
+//! span processor) re-allocates the same bytes.
+//!
+//! A [`StringInterner`] keeps a bounded pool of such values. Once a value has
+//! been interned, further lookups return a `&'static str` so the resulting
+//! [`Value`] can be cloned without allocating.
+use crate::Value;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::RwLock;
+
+/// Default maximum number of distinct values retained by an interner.
+const DEFAULT_MAX_ENTRIES: usize = 1_024;
+/// Default maximum length (in bytes) of a value eligible for interning.
+const DEFAULT_MAX_VALUE_LEN: usize = 128;
+
+/// A bounded pool of interned string attribute values.
+///
+/// The pool only grows until it holds `max_entries` values, after which
+/// unknown values are passed through untouched. Interned strings are never
+/// released, so the memory retained by the pool is bounded by
+/// `max_entries * max_value_len` bytes for the lifetime of the process. Only
+/// values that repeat across spans (low cardinality) benefit from interning.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::sdk::trace::{config, StringInterner};
+///
+/// let interner = StringInterner::new(256);
+/// // `value` returns a borrowed value once "GET" has been interned
+/// let method = interner.value("GET");
+///
+/// let config = config().with_string_interner(interner);
+/// ```
+pub struct StringInterner {
+    pool: RwLock<HashSet<&'static str>>,
+    max_entries: usize,
+    max_value_len: usize,
+}
+
+impl fmt::Debug for StringInterner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StringInterner")
+            .field("len", &self.len())
+            .field("max_entries", &self.max_entries)
+            .field("max_value_len", &self.max_value_len)
+            .finish()
+    }
+}
+
+impl Default for StringInterner {
+    fn default() -> Self {
+        StringInterner::new(DEFAULT_MAX_ENTRIES)
+    }
+}
+
+impl StringInterner {
+    /// Create a new interner retaining at most `max_entries` distinct values.
+    pub fn new(max_entries: usize) -> Self {
+        StringInterner {
+            pool: RwLock::new(HashSet::new()),
+            max_entries,
+            max_value_len: DEFAULT_MAX_VALUE_LEN,
+        }
+    }
+
+    /// Set the maximum length in bytes of values eligible for interning.
+    ///
+    /// Longer values are unlikely to repeat and are never interned.
+    pub fn with_max_value_len(mut self, max_value_len: usize) -> Self {
+        self.max_value_len = max_value_len;
+        self
+    }
+
+    /// Returns the interned copy of `s`, interning it if the pool has room.
+    ///
+    /// Returns `None` if `s` is too long or the pool is full.
+    pub fn intern(&self, s: &str) -> Option<&'static str> {
+        if s.len() > self.max_value_len {
+            return None;
+        }
+
+        if let Ok(pool) = self.pool.read() {
+            if let Some(interned) = pool.get(s) {
+                return Some(interned);
+            }
+            if pool.len() >= self.max_entries {
+                return None;
+            }
+        }
+
+        let mut pool = self.pool.write().ok()?;
+        // another thread may have interned the value while we waited for the lock
+        if let Some(interned) = pool.get(s) {
+            return Some(interned);
+        }
+        if pool.len() >= self.max_entries {
+            return None;
+        }
+        let interned: &'static str = Box::leak(s.to_owned().into_boxed_str());
+        pool.insert(interned);
+
+        Some(interned)
+    }
+
+    /// Create a string `Value` for `s`, borrowing the interned copy if there is one.
+    ///
+    /// This avoids allocating entirely for values that are already interned.
+    pub fn value(&self, s: &str) -> Value {
+        match self.intern(s) {
+            Some(interned) => Value::String(Cow::Borrowed(interned)),
+            None => Value::String(Cow::Owned(s.to_owned())),
+        }
+    }
+
+    /// Replace owned string values with their interned copy.
+    ///
+    /// Non-string and already borrowed values are returned unchanged.
+    pub fn intern_value(&self, value: Value) -> Value {
+        match value {
+            Value::String(Cow::Owned(s)) => match self.intern(&s) {
+                Some(interned) => Value::String(Cow::Borrowed(interned)),
+                None => Value::String(Cow::Owned(s)),
+            },
+            other => other,
+        }
+    }
+
+    /// Returns the number of interned values.
+    pub fn len(&self) -> usize {
+        self.pool.read().map(|pool| pool.len()).unwrap_or(0)
+    }
+
+    /// Returns `true` if no values have been interned.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intern_returns_same_str() {
+        let interner = StringInterner::new(4);
+        let owned = String::from("GET");
+        let first = interner.intern("GET").unwrap();
+        let second = interner.intern(&owned).unwrap();
+        assert!(std::ptr::eq(first, second));
+        assert_eq!(interner.len(), 1);
+    }
+
+    #[test]
+    fn intern_is_bounded() {
+        let interner = StringInterner::new(2).with_max_value_len(4);
+        assert!(interner.intern("a").is_some());
+        assert!(interner.intern("b").is_some());
+        assert!(interner.intern("c").is_none());
+        assert!(interner.intern("a").is_some());
+        assert!(StringInterner::new(2)
+            .with_max_value_len(4)
+            .intern("too long")
+            .is_none());
+        assert_eq!(interner.len(), 2);
+    }
+
+    #[test]
+    fn intern_value_borrows() {
+        let interner = StringInterner::new(2);
+        match interner.intern_value(Value::from("POST".to_string())) {
+            Value::String(Cow::Borrowed(s)) => assert_eq!(s, "POST"),
+            other => panic!("expected borrowed value, got {:?}", other),
+        }
+        assert_eq!(interner.intern_value(Value::I64(1)), Value::I64(1));
+        assert_eq!(interner.value("POST"), Value::from("POST"));
+    }
+}
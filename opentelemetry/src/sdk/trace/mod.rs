@@ -6,24 +6,38 @@
 //! * The `Span` struct with is a mutable object storing information about the
 //! current operation execution.
 //! * The `TracerProvider` struct which configures and produces `Tracer`s.
+mod cardinality;
 mod config;
+mod diagnostics;
 mod evicted_hash_map;
 mod evicted_queue;
 mod id_generator;
+mod interner;
+mod pool;
 mod provider;
 mod sampler;
 mod span;
+#[cfg(feature = "metrics")]
+#[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+pub mod span_metrics;
 mod span_processor;
 mod tracer;
 
-pub use config::{config, Config};
+pub use cardinality::{CardinalityGuard, HIGH_CARDINALITY};
+pub use config::{config, Config, ConfigUpdate};
+pub use diagnostics::{Diagnostics, ExporterDiagnostics, ProcessorDiagnostics, QueueDiagnostics};
 pub use evicted_hash_map::EvictedHashMap;
 pub use evicted_queue::EvictedQueue;
 pub use id_generator::{aws::XrayIdGenerator, IdGenerator};
+pub use interner::StringInterner;
 pub use provider::{Builder, TracerProvider};
 pub use sampler::{Sampler, SamplingDecision, SamplingResult, ShouldSample};
 pub use span::Span;
+#[cfg(feature = "metrics")]
+#[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+pub use span_metrics::SpanMetricsProcessor;
 pub use span_processor::{
-    BatchConfig, BatchSpanProcessor, BatchSpanProcessorBuilder, SimpleSpanProcessor, SpanProcessor,
+    BaggageSpanProcessor, BatchConfig, BatchSpanProcessor, BatchSpanProcessorBuilder,
+    MirroringSpanProcessor, ShardedSpanProcessor, SimpleSpanProcessor, SpanProcessor,
 };
 pub use tracer::Tracer;
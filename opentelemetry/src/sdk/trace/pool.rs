@@ -0,0 +1,170 @@
+//! # Span Pool
+//!
+//! Recording a span with more attributes than fit inline, or with events or
+//! links, allocates buffers that are freed again once the span is exported.
+//! At high span rates this churn keeps the allocator busy.
+//!
+//! A [`SpanPool`] keeps the buffers of ended spans for reuse by the spans
+//! started after them. Buffers are handed back when the attributes, events
+//! or links of a span are dropped or consumed, e.g. by an exporter iterating
+//! over them, so recycling works with any exporter. Pooling is enabled on
+//! the provider with [`Builder::with_span_pool`].
+//!
+//! [`Builder::with_span_pool`]: crate::sdk::trace::Builder::with_span_pool
+use crate::sdk::batch::BoundedQueue;
+use crate::sdk::trace::evicted_hash_map::AttributeBuffer;
+use crate::trace::{Event, Link};
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::Arc;
+
+/// The buffers of ended spans, shared by the tracers of a provider.
+#[derive(Clone, Debug)]
+pub(crate) struct SpanPool {
+    pub(crate) attributes: Recycler<AttributeBuffer>,
+    pub(crate) events: Recycler<VecDeque<Event>>,
+    pub(crate) links: Recycler<VecDeque<Link>>,
+}
+
+impl SpanPool {
+    /// Create a pool keeping up to `size` buffers of each kind.
+    pub(crate) fn new(size: usize) -> Self {
+        SpanPool {
+            attributes: Recycler::new(size),
+            events: Recycler::new(size),
+            links: Recycler::new(size),
+        }
+    }
+}
+
+/// A bounded, lock-free store of empty buffers of one kind.
+///
+/// Containers holding a recycler hand their buffer back to it when they are
+/// dropped. The default recycler of containers created outside of a pool
+/// keeps nothing. Recyclers don't take part in the comparison of containers.
+pub(crate) struct Recycler<B>(Option<Arc<BoundedQueue<B>>>);
+
+impl<B> Recycler<B> {
+    fn new(size: usize) -> Self {
+        Recycler(Some(Arc::new(BoundedQueue::new(size))))
+    }
+
+    /// Take a buffer returned by an earlier container.
+    pub(crate) fn take(&self) -> Option<B> {
+        self.0.as_ref().and_then(|buffers| buffers.pop())
+    }
+
+    /// Keep an emptied buffer for reuse, dropping it if the pool is full.
+    pub(crate) fn give(&self, buffer: B) {
+        if let Some(buffers) = &self.0 {
+            let _ = buffers.push(buffer);
+        }
+    }
+
+    /// Whether buffers given to this recycler are kept.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.0.is_some()
+    }
+}
+
+impl<B> Default for Recycler<B> {
+    fn default() -> Self {
+        Recycler(None)
+    }
+}
+
+impl<B> Clone for Recycler<B> {
+    fn clone(&self) -> Self {
+        Recycler(self.0.clone())
+    }
+}
+
+impl<B> fmt::Debug for Recycler<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Recycler").field(&self.is_enabled()).finish()
+    }
+}
+
+impl<B> PartialEq for Recycler<B> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SpanPool;
+    use crate::sdk::trace::{EvictedHashMap, EvictedQueue, TracerProvider};
+    use crate::trace::{Event, Span, Tracer, TracerProvider as _};
+    use crate::KeyValue;
+    use std::time::SystemTime;
+
+    #[test]
+    fn spans_reuse_buffers_of_ended_spans() {
+        let provider = TracerProvider::builder().with_span_pool(4).build();
+        let tracer = provider.get_tracer("test", None);
+
+        let span = tracer.start("first");
+        for i in 0..32 {
+            span.set_attribute(KeyValue::new(format!("key{}", i), i as i64));
+        }
+        span.end();
+
+        let span = tracer.start("second");
+        let capacity = span.with_data(|data| data.attributes.capacity());
+        assert!(capacity.unwrap() >= 32);
+    }
+
+    #[test]
+    fn dropped_attributes_are_recycled() {
+        let pool = SpanPool::new(4);
+        let mut attributes = EvictedHashMap::with_recycler(128, 0, pool.attributes.clone());
+        for i in 0..32 {
+            attributes.insert(KeyValue::new(format!("key{}", i), i as i64));
+        }
+        drop(attributes);
+
+        let recycled = EvictedHashMap::with_recycler(128, 0, pool.attributes.clone());
+        assert!(recycled.is_empty());
+        assert!(recycled.capacity() >= 32);
+        // the only buffer is in use
+        let fresh = EvictedHashMap::with_recycler(128, 0, pool.attributes.clone());
+        assert!(fresh.capacity() < 32);
+    }
+
+    #[test]
+    fn consumed_attributes_are_recycled() {
+        let pool = SpanPool::new(4);
+        let mut attributes = EvictedHashMap::with_recycler(128, 0, pool.attributes.clone());
+        for i in 0..32 {
+            attributes.insert(KeyValue::new(format!("key{}", i), i as i64));
+        }
+        let keys: Vec<_> = attributes.into_iter().map(|(key, _)| key).collect();
+        assert_eq!(keys.first().map(|key| key.as_str()), Some("key0"));
+        assert_eq!(keys.len(), 32);
+
+        let recycled = EvictedHashMap::with_recycler(128, 0, pool.attributes.clone());
+        assert!(recycled.capacity() >= 32);
+    }
+
+    #[test]
+    fn events_are_recycled() {
+        let pool = SpanPool::new(4);
+        let mut events = EvictedQueue::new(128);
+        events.set_recycler(pool.events.clone());
+        for i in 0..16 {
+            events.push_back(Event::new(
+                format!("event{}", i),
+                SystemTime::now(),
+                Vec::new(),
+            ));
+        }
+        assert_eq!(events.into_iter().count(), 16);
+
+        let mut recycled = EvictedQueue::new(128);
+        recycled.set_recycler(pool.events.clone());
+        recycled.push_back(Event::with_name("event"));
+        assert_eq!(recycled.len(), 1);
+        assert!(recycled.capacity() >= 16);
+    }
+}
@@ -8,23 +8,37 @@
 //! propagators) are provided by the `TracerProvider`. `Tracer` instances do
 //! not duplicate this data to avoid that different `Tracer` instances
 //! of the `TracerProvider` have different versions of these data.
+//!
+//! ## Resource Updates
+//!
+//! Attributes only known after startup can be added to the provider's
+//! resource with [`TracerProvider::update_resource`]. Each span keeps the
+//! resource that was current when it started, so updates never change spans
+//! that are already in flight.
+//!
+//! ## Configuration Updates
+//!
+//! The sampler, the span limits and the schedule of batch processors can be
+//! changed while the provider runs with [`TracerProvider::update_config`],
+//! e.g. from a config file watcher, without losing buffered spans.
+//!
+//! ## Panic Isolation
+//!
+//! Span processors are called from application code starting and ending
+//! spans. A panicking processor is reported to the global error handler
+//! instead of unwinding into the application, and is no longer called after
+//! panicking repeatedly. Panics of exporters are reported as failed exports.
 use crate::{
     global,
-    sdk::{self, export::trace::SpanExporter, trace::SpanProcessor},
+    sdk::{
+        self,
+        export::trace::SpanExporter,
+        trace::{pool::SpanPool, span_processor::IsolatedSpanProcessor, SpanProcessor},
+    },
+    trace::TraceResult,
 };
-#[cfg(all(
-    feature = "rt-tokio-current-thread",
-    not(feature = "rt-tokio"),
-    not(feature = "rt-async-std")
-))]
-use futures::future::BoxFuture;
-use std::sync::Arc;
-#[cfg(all(
-    feature = "rt-tokio-current-thread",
-    not(feature = "rt-tokio"),
-    not(feature = "rt-async-std")
-))]
-use std::thread;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 
 /// Default tracer name if empty string is provided.
 const DEFAULT_COMPONENT_NAME: &str = "rust.opentelemetry.io/sdk/tracer";
@@ -34,6 +48,9 @@ const DEFAULT_COMPONENT_NAME: &str = "rust.opentelemetry.io/sdk/tracer";
 pub(crate) struct TracerProviderInner {
     processors: Vec<Box<dyn SpanProcessor>>,
     config: sdk::trace::Config,
+    live_config: RwLock<Arc<LiveConfig>>,
+    tracers: RwLock<HashMap<sdk::InstrumentationLibrary, sdk::trace::Tracer>>,
+    span_pool: Option<SpanPool>,
 }
 
 impl Drop for TracerProviderInner {
@@ -46,6 +63,29 @@ impl Drop for TracerProviderInner {
     }
 }
 
+/// The settings of a provider which can change while it runs.
+#[derive(Clone, Debug)]
+pub(crate) struct LiveConfig {
+    pub(crate) resource: Arc<sdk::Resource>,
+    /// Replaces the default sampler of the config once updated.
+    pub(crate) sampler: Option<Arc<dyn sdk::trace::ShouldSample>>,
+    pub(crate) max_events_per_span: u32,
+    pub(crate) max_attributes_per_span: u32,
+    pub(crate) max_links_per_span: u32,
+}
+
+impl LiveConfig {
+    fn new(config: &sdk::trace::Config) -> Self {
+        LiveConfig {
+            resource: config.resource.clone(),
+            sampler: None,
+            max_events_per_span: config.max_events_per_span,
+            max_attributes_per_span: config.max_attributes_per_span,
+            max_links_per_span: config.max_links_per_span,
+        }
+    }
+}
+
 /// Creator and registry of named `Tracer` instances.
 #[derive(Clone, Debug)]
 pub struct TracerProvider {
@@ -74,10 +114,174 @@ impl TracerProvider {
         &self.inner.processors
     }
 
+    /// The pool recycling the buffers of ended spans, if enabled.
+    pub(crate) fn span_pool(&self) -> Option<&SpanPool> {
+        self.inner.span_pool.as_ref()
+    }
+
     /// Config associated with this tracer
+    ///
+    /// The config is the one the provider was built with, use
+    /// [`TracerProvider::resource`] for the resource including updates. The
+    /// changes of [`TracerProvider::update_config`] are not reflected either.
     pub fn config(&self) -> &sdk::trace::Config {
         &self.inner.config
     }
+
+    /// The settings new spans are started with.
+    pub(crate) fn live_config(&self) -> Arc<LiveConfig> {
+        match self.inner.live_config.read() {
+            Ok(live_config) => live_config.clone(),
+            Err(err) => err.into_inner().clone(),
+        }
+    }
+
+    /// Replace the settings new spans are started with.
+    fn update_live_config<F: FnOnce(&mut LiveConfig)>(&self, f: F) {
+        let mut live_config = match self.inner.live_config.write() {
+            Ok(live_config) => live_config,
+            Err(err) => err.into_inner(),
+        };
+        let mut updated = LiveConfig::clone(&live_config);
+        f(&mut updated);
+        *live_config = Arc::new(updated);
+    }
+
+    /// The resource new spans are associated with.
+    pub fn resource(&self) -> Arc<sdk::Resource> {
+        self.live_config().resource.clone()
+    }
+
+    /// Add attributes to the resource of spans started from now on.
+    ///
+    /// Attributes in `delta` take priority over the current resource. Spans
+    /// that have already started keep the resource they were started with, so
+    /// all spans of a batch are exported with a consistent resource.
+    pub fn update_resource(&self, delta: sdk::Resource) {
+        self.update_live_config(|live_config| {
+            live_config.resource = Arc::new(delta.merge(&live_config.resource));
+        });
+    }
+
+    /// Change the sampler, span limits or batch schedule while the provider
+    /// runs, e.g. when a config file changes.
+    ///
+    /// The sampler and span limits apply to spans started from now on, spans
+    /// that have already started keep their limits. The update is passed on
+    /// to the span processors, see [`SpanProcessor::update_config`], batch
+    /// processors apply a new scheduled delay from their next scheduled export
+    /// on, keeping the spans buffered so far.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use opentelemetry::sdk::trace::{ConfigUpdate, Sampler, TracerProvider};
+    /// use std::time::Duration;
+    ///
+    /// let provider = TracerProvider::builder().build();
+    ///
+    /// // sample a tenth of the traces and export every 10 seconds from now on
+    /// provider.update_config(
+    ///     ConfigUpdate::new()
+    ///         .with_default_sampler(Sampler::TraceIdRatioBased(0.1))
+    ///         .with_scheduled_delay(Duration::from_secs(10)),
+    /// );
+    /// ```
+    pub fn update_config(&self, update: sdk::trace::ConfigUpdate) {
+        self.update_live_config(|live_config| {
+            if let Some(sampler) = &update.default_sampler {
+                live_config.sampler = Some(sampler.clone());
+            }
+            if let Some(max_events) = update.max_events_per_span {
+                live_config.max_events_per_span = max_events;
+            }
+            if let Some(max_attributes) = update.max_attributes_per_span {
+                live_config.max_attributes_per_span = max_attributes;
+            }
+            if let Some(max_links) = update.max_links_per_span {
+                live_config.max_links_per_span = max_links;
+            }
+        });
+        for processor in &self.inner.processors {
+            processor.update_config(&update);
+        }
+    }
+
+    /// A snapshot of the health of the provider, e.g. to find out why spans
+    /// don't arrive at the backend.
+    ///
+    /// Reports the default sampler, the current resource and the state of
+    /// each span processor, see [`SpanProcessor::diagnostics`]. The built-in
+    /// processors report the outcome and latency of their exports, batch
+    /// processors the length of their queue and the spans dropped so far.
+    ///
+    /// With the `serialize` feature the snapshot can be serialized, e.g. to
+    /// serve it from a health endpoint.
+    pub fn diagnostics(&self) -> sdk::trace::Diagnostics {
+        sdk::trace::Diagnostics {
+            sampler: match &self.live_config().sampler {
+                Some(sampler) => format!("{:?}", sampler),
+                None => format!("{:?}", self.inner.config.default_sampler),
+            },
+            resource: self
+                .resource()
+                .iter()
+                .map(|(key, value)| (key.as_str().to_string(), value.to_string()))
+                .collect(),
+            processors: self
+                .inner
+                .processors
+                .iter()
+                .map(|processor| processor.diagnostics())
+                .collect(),
+        }
+    }
+
+    /// Create a tracer for an instrumentation library following the
+    /// semantic conventions described by `schema_url`.
+    ///
+    /// Spans created by the tracer report the schema url as part of their
+    /// instrumentation library.
+    pub fn versioned_tracer(
+        &self,
+        name: &'static str,
+        version: Option<&'static str>,
+        schema_url: Option<&'static str>,
+    ) -> sdk::trace::Tracer {
+        let mut library = sdk::InstrumentationLibrary::new(name, version);
+        library.schema_url = schema_url;
+
+        self.library_tracer(library)
+    }
+
+    /// Create a tracer for an instrumentation library, including its
+    /// attributes.
+    ///
+    /// Tracers are cached by the name, version and schema url of their
+    /// library, so repeated lookups return the same tracer without allocating.
+    /// The attributes of the library the tracer was first created with are
+    /// kept.
+    pub fn library_tracer(&self, mut library: sdk::InstrumentationLibrary) -> sdk::trace::Tracer {
+        // Use default value if name is invalid empty string
+        if library.name.is_empty() {
+            library.name = DEFAULT_COMPONENT_NAME;
+        }
+
+        if let Ok(tracers) = self.inner.tracers.read() {
+            if let Some(tracer) = tracers.get(&library) {
+                return tracer.clone();
+            }
+        }
+
+        let mut tracers = match self.inner.tracers.write() {
+            Ok(tracers) => tracers,
+            Err(err) => err.into_inner(),
+        };
+        tracers
+            .entry(library.clone())
+            .or_insert_with(|| sdk::trace::Tracer::new(library, Arc::downgrade(&self.inner)))
+            .clone()
+    }
 }
 
 impl crate::trace::TracerProvider for TracerProvider {
@@ -86,15 +290,15 @@ impl crate::trace::TracerProvider for TracerProvider {
 
     /// Find or create `Tracer` instance by name.
     fn get_tracer(&self, name: &'static str, version: Option<&'static str>) -> Self::Tracer {
-        // Use default value if name is invalid empty string
-        let component_name = if name.is_empty() {
-            DEFAULT_COMPONENT_NAME
-        } else {
-            name
-        };
-        let instrumentation_lib = sdk::InstrumentationLibrary::new(component_name, version);
+        self.versioned_tracer(name, version, None)
+    }
 
-        sdk::trace::Tracer::new(instrumentation_lib, Arc::downgrade(&self.inner))
+    /// Force flush all the span processors of the provider.
+    fn force_flush(&self) -> Vec<TraceResult<()>> {
+        self.span_processors()
+            .iter()
+            .map(|processor| processor.force_flush())
+            .collect()
     }
 }
 
@@ -103,6 +307,7 @@ impl crate::trace::TracerProvider for TracerProvider {
 pub struct Builder {
     processors: Vec<Box<dyn SpanProcessor>>,
     config: sdk::trace::Config,
+    span_pool: Option<SpanPool>,
 }
 
 impl Builder {
@@ -116,6 +321,19 @@ impl Builder {
         Builder { processors, ..self }
     }
 
+    /// Export spans to an [`InMemorySpanExporter`] as soon as they end, to
+    /// assert on them in tests.
+    ///
+    /// [`InMemorySpanExporter`]: crate::sdk::testing::trace::InMemorySpanExporter
+    #[cfg(feature = "testing")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+    pub fn with_in_memory_exporter(
+        self,
+        exporter: &sdk::testing::trace::InMemorySpanExporter,
+    ) -> Self {
+        self.with_simple_exporter(exporter.clone())
+    }
+
     /// The `BatchProcessor` that this provider should use.
     pub fn with_batch_exporter(self, processor: sdk::trace::BatchSpanProcessor) -> Self {
         let mut processors = self.processors;
@@ -128,12 +346,7 @@ impl Builder {
     #[cfg(feature = "rt-tokio")]
     #[cfg_attr(docsrs, doc(cfg(feature = "rt-tokio")))]
     pub fn with_exporter<T: SpanExporter + 'static>(self, exporter: T) -> Self {
-        let batch = sdk::trace::BatchSpanProcessor::builder(
-            exporter,
-            tokio::spawn,
-            tokio::time::sleep,
-            crate::util::tokio_interval_stream,
-        );
+        let batch = sdk::trace::BatchSpanProcessor::builder(exporter, crate::runtime::Tokio);
         self.with_batch_exporter(batch.build())
     }
 
@@ -145,27 +358,10 @@ impl Builder {
     ))]
     #[cfg_attr(docsrs, doc(cfg(feature = "rt-tokio-current-thread")))]
     pub fn with_exporter<T: SpanExporter + 'static>(self, exporter: T) -> Self {
-        // We cannot force push tracing in current thread tokio scheduler because
-        // we rely on BatchSpanProcessor to export spans in a background task, meanwhile we need to
-        // block the shutdown function so that the runtime will not finish the blocked task and
-        // kill any remaining tasks. But there is only one thread to run task, so it's a deadlock
-        //
-        // Thus, we spawn the background task in a separate thread.
-        let spawn = |box_future: BoxFuture<'static, ()>| {
-            thread::spawn(move || {
-                let rt = tokio::runtime::Builder::new_current_thread()
-                    .enable_all()
-                    .build()
-                    .unwrap();
-                rt.block_on(box_future);
-            });
-        };
-        let batch = sdk::trace::BatchSpanProcessor::builder(
-            exporter,
-            spawn,
-            tokio::time::sleep,
-            crate::util::tokio_interval_stream,
-        );
+        // Flushing and shutting down block the only thread of a current thread
+        // runtime, so the worker runs on a background thread with its own runtime.
+        let batch =
+            sdk::trace::BatchSpanProcessor::builder(exporter, crate::runtime::TokioCurrentThread);
         self.with_batch_exporter(batch.build())
     }
 
@@ -177,17 +373,28 @@ impl Builder {
     ))]
     #[cfg_attr(docsrs, doc(cfg(feature = "rt-async-std")))]
     pub fn with_exporter<T: SpanExporter + 'static>(self, exporter: T) -> Self {
-        let batch = sdk::trace::BatchSpanProcessor::builder(
-            exporter,
-            async_std::task::spawn,
-            async_std::task::sleep,
-            async_std::stream::interval,
-        );
+        let batch = sdk::trace::BatchSpanProcessor::builder(exporter, crate::runtime::AsyncStd);
         self.with_batch_exporter(batch.build())
     }
 
     /// Add a configured `SpanExporter`
     #[cfg(all(
+        feature = "rt-wasm-bindgen",
+        target_arch = "wasm32",
+        not(feature = "rt-async-std"),
+        not(feature = "rt-tokio"),
+        not(feature = "rt-tokio-current-thread")
+    ))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rt-wasm-bindgen")))]
+    pub fn with_exporter<T: SpanExporter + 'static>(self, exporter: T) -> Self {
+        // a simple processor would block the only thread on the export
+        let batch = sdk::trace::BatchSpanProcessor::builder(exporter, crate::runtime::WasmBindgen);
+        self.with_batch_exporter(batch.build())
+    }
+
+    /// Add a configured `SpanExporter`
+    #[cfg(all(
+        not(all(feature = "rt-wasm-bindgen", target_arch = "wasm32")),
         not(feature = "rt-async-std"),
         not(feature = "rt-tokio"),
         not(feature = "rt-tokio-current-thread")
@@ -196,6 +403,20 @@ impl Builder {
         self.with_simple_exporter(exporter)
     }
 
+    /// Add the exporters selected by the `OTEL_TRACES_EXPORTER` environment
+    /// variable, created by `factory`.
+    ///
+    /// Fails if an exporter is unknown or could not be created.
+    pub fn with_exporters_from_env(
+        self,
+        factory: &sdk::export::trace::factory::ExporterFactory,
+    ) -> TraceResult<Self> {
+        Ok(factory
+            .build_from_env()?
+            .into_iter()
+            .fold(self, |builder, exporter| builder.with_exporter(exporter)))
+    }
+
     /// The `SpanProcessor` that this provider should use.
     pub fn with_span_processor<T: SpanProcessor + 'static>(self, processor: T) -> Self {
         let mut processors = self.processors;
@@ -209,13 +430,249 @@ impl Builder {
         Builder { config, ..self }
     }
 
+    /// Recycle the attribute, event and link buffers of ended spans, keeping
+    /// up to `size` buffers of each kind.
+    ///
+    /// Buffers are handed back when the data of a span is dropped or
+    /// consumed after export, and reused by the spans started after it. This
+    /// saves allocations at high span rates, at the cost of holding on to the
+    /// memory of the pooled buffers. Attributes stored inline are not pooled.
+    pub fn with_span_pool(self, size: usize) -> Self {
+        Builder {
+            span_pool: Some(SpanPool::new(size)),
+            ..self
+        }
+    }
+
     /// Create a new provider from this configuration.
     pub fn build(self) -> TracerProvider {
         TracerProvider {
             inner: Arc::new(TracerProviderInner {
-                processors: self.processors,
+                processors: self
+                    .processors
+                    .into_iter()
+                    .map(|processor| {
+                        Box::new(IsolatedSpanProcessor::new(processor)) as Box<dyn SpanProcessor>
+                    })
+                    .collect(),
+                live_config: RwLock::new(Arc::new(LiveConfig::new(&self.config))),
                 config: self.config,
+                tracers: RwLock::new(HashMap::new()),
+                span_pool: self.span_pool,
             }),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::sdk::{
+        self,
+        export::trace::SpanData,
+        trace::{SamplingDecision, SamplingResult, SpanProcessor},
+    };
+    use crate::trace::{Span, Tracer, TracerProvider};
+    use crate::{Context, Key, KeyValue, Value};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Default)]
+    struct CollectingProcessor(Arc<Mutex<Vec<Arc<SpanData>>>>);
+
+    impl SpanProcessor for CollectingProcessor {
+        fn on_start(&self, _span: &sdk::trace::Span, _cx: &Context) {}
+
+        fn on_end(&self, span: Arc<SpanData>) {
+            self.0.lock().unwrap().push(span);
+        }
+
+        fn force_flush(&self) -> crate::trace::TraceResult<()> {
+            Ok(())
+        }
+
+        fn shutdown(&mut self) -> crate::trace::TraceResult<()> {
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct PanickingProcessor(Arc<Mutex<usize>>);
+
+    impl SpanProcessor for PanickingProcessor {
+        fn on_start(&self, _span: &sdk::trace::Span, _cx: &Context) {}
+
+        fn on_end(&self, _span: Arc<SpanData>) {
+            *self.0.lock().unwrap() += 1;
+            panic!("on_end");
+        }
+
+        fn force_flush(&self) -> crate::trace::TraceResult<()> {
+            panic!("force_flush")
+        }
+
+        fn shutdown(&mut self) -> crate::trace::TraceResult<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn processors_share_span_data() {
+        let first = Arc::new(Mutex::new(Vec::new()));
+        let second = Arc::new(Mutex::new(Vec::new()));
+        let provider = sdk::trace::TracerProvider::builder()
+            .with_span_processor(CollectingProcessor(first.clone()))
+            .with_span_processor(CollectingProcessor(second.clone()))
+            .build();
+
+        provider.get_tracer("test", None).start("span").end();
+
+        let (first, second) = (first.lock().unwrap(), second.lock().unwrap());
+        assert!(Arc::ptr_eq(&first[0], &second[0]));
+        assert_eq!(Arc::strong_count(&first[0]), 2);
+    }
+
+    #[test]
+    fn panicking_processors_are_isolated() {
+        let calls = Arc::new(Mutex::new(0));
+        let spans = Arc::new(Mutex::new(Vec::new()));
+        let provider = sdk::trace::TracerProvider::builder()
+            .with_span_processor(PanickingProcessor(calls.clone()))
+            .with_span_processor(CollectingProcessor(spans.clone()))
+            .build();
+        let tracer = provider.get_tracer("test", None);
+
+        for _ in 0..5 {
+            tracer.start("span").end();
+        }
+
+        assert_eq!(spans.lock().unwrap().len(), 5);
+        // the processor is quarantined after its third panic
+        assert_eq!(*calls.lock().unwrap(), 3);
+        assert!(provider.span_processors()[0].force_flush().is_err());
+        assert!(provider.span_processors()[1].force_flush().is_ok());
+    }
+
+    #[test]
+    fn tracers_are_cached_by_library() {
+        let spans = Arc::new(Mutex::new(Vec::new()));
+        let provider = sdk::trace::TracerProvider::builder()
+            .with_span_processor(CollectingProcessor(spans.clone()))
+            .build();
+
+        let library = sdk::InstrumentationLibrary::new("db", Some("1.0"))
+            .with_attributes(vec![KeyValue::new("db.system", "postgresql")]);
+        let tracer = provider.library_tracer(library);
+        let cached = provider.get_tracer("db", Some("1.0"));
+        assert!(std::ptr::eq(
+            tracer.instrumentation_library(),
+            cached.instrumentation_library()
+        ));
+        let other = provider.get_tracer("db", Some("2.0"));
+        assert!(!std::ptr::eq(
+            tracer.instrumentation_library(),
+            other.instrumentation_library()
+        ));
+
+        cached.start("query").end();
+        let spans = spans.lock().unwrap();
+        assert_eq!(
+            spans[0].instrumentation_lib.attributes,
+            vec![KeyValue::new("db.system", "postgresql")]
+        );
+    }
+
+    #[test]
+    fn update_resource_applies_to_new_spans() {
+        let spans = Arc::new(Mutex::new(Vec::new()));
+        let provider = sdk::trace::TracerProvider::builder()
+            .with_span_processor(CollectingProcessor(spans.clone()))
+            .with_config(sdk::trace::config().with_resource(sdk::Resource::new(vec![
+                KeyValue::new("service.name", "test"),
+                KeyValue::new("k8s.pod.name", ""),
+            ])))
+            .build();
+        let tracer = provider.get_tracer("test", None);
+
+        let in_flight = tracer.start("in_flight");
+        provider.update_resource(sdk::Resource::new(vec![
+            KeyValue::new("k8s.pod.name", "pod-1"),
+            KeyValue::new("feature.flag", true),
+        ]));
+        tracer.start("after_update").end();
+        in_flight.end();
+
+        let spans = spans.lock().unwrap();
+        let resource = |name: &str| {
+            spans
+                .iter()
+                .find(|span| span.name == name)
+                .map(|span| span.resource.clone())
+                .unwrap()
+        };
+        assert_eq!(
+            resource("in_flight").get(Key::new("k8s.pod.name")),
+            Some(Value::from(""))
+        );
+        assert_eq!(resource("in_flight").get(Key::new("feature.flag")), None);
+
+        let updated = resource("after_update");
+        assert_eq!(
+            updated.get(Key::new("service.name")),
+            Some(Value::from("test"))
+        );
+        assert_eq!(
+            updated.get(Key::new("k8s.pod.name")),
+            Some(Value::from("pod-1"))
+        );
+        assert_eq!(
+            updated.get(Key::new("feature.flag")),
+            Some(Value::from(true))
+        );
+        assert_eq!(provider.resource(), updated);
+    }
+
+    #[test]
+    fn update_config_applies_to_new_spans() {
+        let spans = Arc::new(Mutex::new(Vec::new()));
+        let provider = sdk::trace::TracerProvider::builder()
+            .with_span_processor(CollectingProcessor(spans.clone()))
+            .with_config(
+                sdk::trace::config()
+                    .with_default_sampler(sdk::trace::Sampler::AlwaysOff)
+                    .with_max_attributes_per_span(1),
+            )
+            .build();
+        let tracer = provider.get_tracer("test", None);
+        let attributes = vec![KeyValue::new("a", 1), KeyValue::new("b", 2)];
+
+        tracer.start("dropped").end();
+        let in_flight = tracer
+            .span_builder("in_flight")
+            .with_sampling_result(SamplingResult {
+                decision: SamplingDecision::RecordAndSample,
+                attributes: vec![],
+                trace_state: Default::default(),
+            })
+            .with_attributes(attributes.clone())
+            .start(&tracer);
+        provider.update_config(
+            sdk::trace::ConfigUpdate::new()
+                .with_default_sampler(sdk::trace::Sampler::AlwaysOn)
+                .with_max_attributes_per_span(2),
+        );
+        tracer
+            .span_builder("after_update")
+            .with_attributes(attributes)
+            .start(&tracer)
+            .end();
+        in_flight.end();
+
+        let spans = spans.lock().unwrap();
+        let names: Vec<_> = spans.iter().map(|span| span.name.as_ref()).collect();
+        assert_eq!(names, vec!["after_update", "in_flight"]);
+        assert_eq!(spans[0].attributes.len(), 2);
+        assert_eq!(spans[1].attributes.len(), 1);
+        assert_eq!(provider.diagnostics().sampler, "AlwaysOn");
+        // the config is the one the provider was built with
+        assert_eq!(provider.config().max_attributes_per_span, 1);
+    }
+}
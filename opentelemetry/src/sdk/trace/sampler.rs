@@ -130,18 +130,10 @@ impl ShouldSample for Sampler {
             }
             // Probabilistically sample the trace.
             Sampler::TraceIdRatioBased(prob) => {
-                if *prob >= 1.0 {
+                if trace_id_ratio_selects(trace_id, *prob) {
                     SamplingDecision::RecordAndSample
                 } else {
-                    let prob_upper_bound = (prob.max(0.0) * (1u64 << 63) as f64) as u64;
-                    // The trace_id is already randomly generated, so we don't need a new one here
-                    let rnd_from_trace_id = (trace_id.to_u128() as u64) >> 1;
-
-                    if rnd_from_trace_id < prob_upper_bound {
-                        SamplingDecision::RecordAndSample
-                    } else {
-                        SamplingDecision::Drop
-                    }
+                    SamplingDecision::Drop
                 }
             }
         };
@@ -159,6 +151,21 @@ impl ShouldSample for Sampler {
     }
 }
 
+/// Returns whether the trace falls within the given ratio of all traces.
+///
+/// The decision only depends on the trace id, so every participant selects
+/// the same traces for the same ratio.
+pub(crate) fn trace_id_ratio_selects(trace_id: TraceId, ratio: f64) -> bool {
+    if ratio >= 1.0 {
+        return true;
+    }
+    let prob_upper_bound = (ratio.max(0.0) * (1u64 << 63) as f64) as u64;
+    // The trace_id is already randomly generated, so we don't need a new one here
+    let rnd_from_trace_id = (trace_id.to_u128() as u64) >> 1;
+
+    rnd_from_trace_id < prob_upper_bound
+}
+
 #[cfg(all(test, feature = "testing", feature = "trace"))]
 mod tests {
     use super::*;
@@ -9,9 +9,10 @@
 //! is possible to change its name, set its `Attributes`, and add `Links` and `Events`.
 //! These cannot be changed after the `Span`'s end time has been set.
 use crate::trace::{Event, SpanContext, SpanId, SpanKind, StatusCode};
-use crate::{sdk, trace, KeyValue};
+use crate::{sdk, trace, KeyValue, Severity, Value};
+use std::borrow::Cow;
 use std::sync::{Arc, Mutex};
-use std::time::SystemTime;
+use std::time::{Instant, SystemTime};
 
 /// Single operation within a trace.
 #[derive(Clone, Debug)]
@@ -34,9 +35,12 @@ pub(crate) struct SpanData {
     /// Span kind
     pub(crate) span_kind: SpanKind,
     /// Span name
-    pub(crate) name: String,
+    pub(crate) name: Cow<'static, str>,
     /// Span start time
     pub(crate) start_time: SystemTime,
+    /// Monotonic reading of the clock at the start time, if the span was
+    /// started with a clock providing them.
+    pub(crate) start_instant: Option<Instant>,
     /// Span end time
     pub(crate) end_time: SystemTime,
     /// Span attributes
@@ -69,7 +73,7 @@ impl Span {
     }
 
     /// Operate on a mutable reference to span data
-    fn with_data<T, F>(&self, f: F) -> Option<T>
+    pub(crate) fn with_data<T, F>(&self, f: F) -> Option<T>
     where
         F: FnOnce(&mut SpanData) -> T,
     {
@@ -80,9 +84,59 @@ impl Span {
                 .and_then(|mut span_data| span_data.as_mut().map(f))
         })
     }
+
+    /// A snapshot of the data of a span that has not ended yet, e.g. for span
+    /// processors keeping track of running spans in `on_start`.
+    ///
+    /// Returns `None` once the span ended or if it is not recording. The data
+    /// is cloned, so this is not meant to be called on every span.
+    pub fn exported_data(&self) -> Option<sdk::export::trace::SpanData> {
+        self.with_data(|data| {
+            build_export_data(
+                data.clone(),
+                self.inner.span_context.clone(),
+                &self.inner.tracer,
+            )
+        })
+    }
+
+    /// Emit an event with a severity to the event logger of the provider, if
+    /// any.
+    #[cfg(feature = "logs")]
+    fn emit_event_log(&self, event: &Event) {
+        let provider = match self.inner.tracer.provider() {
+            Some(provider) => provider,
+            None => return,
+        };
+        let logger = match &provider.config().event_logger {
+            Some(logger) => logger,
+            None => return,
+        };
+
+        let mut builder = crate::logs::LogRecord::builder()
+            .with_timestamp(event.timestamp)
+            .with_span_context(&self.inner.span_context)
+            .with_attributes(event.attributes.clone())
+            .with_attribute(KeyValue::new("event.name", event.name.clone()));
+        if let Some(severity) = event.severity {
+            builder = builder
+                .with_severity_number(severity)
+                .with_severity_text(severity.name());
+        }
+        if let Some(body) = &event.body {
+            builder = builder.with_body(body.clone());
+        }
+        logger.emit(builder.build());
+    }
 }
 
 impl crate::trace::Span for Span {
+    /// Records events at the current time of the clock of the provider in the
+    /// context of a given `Span`.
+    fn add_event(&self, name: String, attributes: Vec<KeyValue>) {
+        self.add_event_with_timestamp(name, self.inner.now(), attributes)
+    }
+
     /// Records events at a specific time in the context of a given `Span`.
     ///
     /// Note that the OpenTelemetry project documents certain ["standard event names and
@@ -100,6 +154,23 @@ impl crate::trace::Span for Span {
         });
     }
 
+    /// Records events with a severity and a structured body, emitting them as
+    /// log records as well if the provider is configured with an event logger.
+    fn add_event_with_severity(
+        &self,
+        name: String,
+        severity: Severity,
+        body: Option<Value>,
+        attributes: Vec<KeyValue>,
+    ) {
+        let mut event = Event::new(name, self.inner.now(), attributes);
+        event.severity = Some(severity);
+        event.body = body;
+        #[cfg(feature = "logs")]
+        self.emit_event_log(&event);
+        self.with_data(|data| data.message_events.push_back(event));
+    }
+
     /// Returns the `SpanContext` for the given `Span`.
     fn span_context(&self) -> &SpanContext {
         &self.inner.span_context
@@ -122,19 +193,34 @@ impl crate::trace::Span for Span {
     /// Note that the OpenTelemetry project documents certain ["standard
     /// attributes"](https://github.com/open-telemetry/opentelemetry-specification/tree/v0.5.0/specification/trace/semantic_conventions/README.md)
     /// that have prescribed semantic meanings.
-    fn set_attribute(&self, attribute: KeyValue) {
+    fn set_attribute(&self, mut attribute: KeyValue) {
         self.with_data(|data| {
+            if let Some(provider) = self.inner.tracer.provider() {
+                if let Some(guard) = &provider.config().cardinality_guard {
+                    attribute.value = guard.check(&attribute.key, attribute.value);
+                }
+                if let Some(interner) = &provider.config().string_interner {
+                    attribute.value = interner.intern_value(attribute.value);
+                }
+            }
             data.attributes.insert(attribute);
         });
     }
 
     /// Sets the status of the `Span`. If used, this will override the default `Span`
-    /// status, which is `Unset`. `message` MUST be ignored when the status is `OK` or `Unset`
+    /// status, which is `Unset`. `message` MUST be ignored when the status is `Ok` or `Unset`
+    ///
+    /// Setting `Unset` is ignored, and so is any status set after `Ok`.
     fn set_status(&self, code: StatusCode, message: String) {
         self.with_data(|data| {
-            if code == StatusCode::Error {
-                data.status_message = message;
+            if code == StatusCode::Unset || data.status_code == StatusCode::Ok {
+                return;
             }
+            data.status_message = if code == StatusCode::Error {
+                message
+            } else {
+                String::new()
+            };
             data.status_code = code;
         });
     }
@@ -142,10 +228,16 @@ impl crate::trace::Span for Span {
     /// Updates the `Span`'s name.
     fn update_name(&self, new_name: String) {
         self.with_data(|data| {
-            data.name = new_name;
+            data.name = new_name.into();
         });
     }
 
+    /// Finishes the span at the current time of the clock of the provider.
+    fn end(&self) {
+        let timestamp = self.with_data(|data| self.inner.now_since_start(data));
+        self.inner.ensure_ended_and_exported(timestamp);
+    }
+
     /// Finishes the span with given timestamp.
     fn end_with_timestamp(&self, timestamp: SystemTime) {
         self.inner.ensure_ended_and_exported(Some(timestamp));
@@ -153,6 +245,28 @@ impl crate::trace::Span for Span {
 }
 
 impl SpanInner {
+    /// The current time of the clock of the provider, or the system time once
+    /// the provider is gone.
+    fn now(&self) -> SystemTime {
+        self.tracer
+            .provider()
+            .map_or_else(crate::time::now, |provider| provider.config().clock.now())
+    }
+
+    /// The current time of the clock of the provider for a span started with
+    /// `data`, measured with monotonic readings since its start if possible.
+    fn now_since_start(&self, data: &SpanData) -> SystemTime {
+        let provider = match self.tracer.provider() {
+            Some(provider) => provider,
+            None => return crate::time::now(),
+        };
+        let clock = &provider.config().clock;
+        match (data.start_instant, clock.instant()) {
+            (Some(start), Some(now)) => data.start_time + now.saturating_duration_since(start),
+            _ => clock.now(),
+        }
+    }
+
     fn ensure_ended_and_exported(&self, timestamp: Option<SystemTime>) {
         if let Some(data) = &self.data {
             if let Ok(mut span_data) = data.lock().map(|mut data| data.take()) {
@@ -161,28 +275,28 @@ impl SpanInner {
                     if let Some(timestamp) = timestamp {
                         span_data.end_time = timestamp;
                     } else if span_data.end_time == span_data.start_time {
-                        span_data.end_time = crate::time::now();
+                        span_data.end_time = self.now_since_start(span_data);
                     }
                 }
 
-                // Notify each span processor that the span has ended
-                if let Some(provider) = self.tracer.provider() {
+                // Notify each span processor that the span has ended, sharing the data
+                if let (Some(provider), Some(span_data)) = (self.tracer.provider(), span_data) {
+                    let mut span_data = Some(Arc::new(build_export_data(
+                        span_data,
+                        self.span_context.clone(),
+                        &self.tracer,
+                    )));
                     let mut processors = provider.span_processors().iter().peekable();
                     while let Some(processor) = processors.next() {
                         let span_data = if processors.peek().is_none() {
                             // last loop or single processor/exporter, move data
                             span_data.take()
                         } else {
-                            // clone so each exporter gets owned data
                             span_data.clone()
                         };
 
                         if let Some(span_data) = span_data {
-                            processor.on_end(build_export_data(
-                                span_data,
-                                self.span_context.clone(),
-                                &self.tracer,
-                            ));
+                            processor.on_end(span_data);
                         }
                     }
                 }
@@ -216,7 +330,7 @@ fn build_export_data(
         status_code: data.status_code,
         status_message: data.status_message,
         resource: data.resource,
-        instrumentation_lib: *tracer.instrumentation_library(),
+        instrumentation_lib: tracer.instrumentation_library().clone(),
     }
 }
 
@@ -233,8 +347,9 @@ mod tests {
         let data = SpanData {
             parent_span_id: SpanId::from_u64(0),
             span_kind: trace::SpanKind::Internal,
-            name: "opentelemetry".to_string(),
+            name: "opentelemetry".into(),
             start_time: crate::time::now(),
+            start_instant: None,
             end_time: crate::time::now(),
             attributes: sdk::trace::EvictedHashMap::new(config.max_attributes_per_span, 0),
             message_events: sdk::trace::EvictedQueue::new(config.max_events_per_span),
@@ -265,6 +380,21 @@ mod tests {
         span.with_data(|d| assert_eq!(*d, data));
     }
 
+    #[test]
+    fn exported_data_of_running_span() {
+        let (tracer, data) = init();
+        let span = Span::new(SpanContext::empty_context(), Some(data), tracer);
+        span.set_attribute(KeyValue::new("k", "v"));
+
+        let exported = span.exported_data().expect("no data");
+        assert_eq!(exported.name, "opentelemetry");
+        assert_eq!(exported.attributes.get(&"k".into()), Some(&"v".into()));
+        assert_eq!(exported.instrumentation_lib.name, "opentelemetry");
+
+        span.end();
+        assert!(span.exported_data().is_none());
+    }
+
     #[test]
     fn add_event() {
         let span = create_span();
@@ -299,6 +429,78 @@ mod tests {
         });
     }
 
+    #[test]
+    fn add_event_with_severity() {
+        let span = create_span();
+        span.add_event_with_severity(
+            "payment_failed".to_string(),
+            Severity::Error,
+            Some("card declined".into()),
+            vec![KeyValue::new("k", "v")],
+        );
+        span.with_data(|data| {
+            let event = data.message_events.iter().next().expect("no event");
+            assert_eq!(event.severity, Some(Severity::Error));
+            assert_eq!(event.body, Some("card declined".into()));
+            assert_eq!(event.attributes, vec![KeyValue::new("k", "v")]);
+            assert_eq!(
+                event.attributes_with_severity(),
+                vec![
+                    KeyValue::new("k", "v"),
+                    KeyValue::new("event.severity_number", 17),
+                    KeyValue::new("event.severity_text", "ERROR"),
+                    KeyValue::new("event.body", "card declined"),
+                ]
+            );
+        });
+    }
+
+    #[cfg(feature = "logs")]
+    #[test]
+    fn add_event_with_severity_emits_log_record() {
+        use crate::logs::{LogRecord, Logger, TraceContext};
+        use crate::trace::Tracer as _;
+
+        #[derive(Clone, Debug, Default)]
+        struct RecordingLogger(Arc<Mutex<Vec<LogRecord>>>);
+
+        impl Logger for RecordingLogger {
+            fn emit(&self, record: LogRecord) {
+                self.0.lock().unwrap().push(record);
+            }
+        }
+
+        let logger = RecordingLogger::default();
+        let provider = sdk::trace::TracerProvider::builder()
+            .with_config(sdk::trace::config().with_event_logger(logger.clone()))
+            .build();
+        let span = provider.get_tracer("test", None).start("checkout");
+        span.add_event_with_severity(
+            "payment_failed".to_string(),
+            Severity::Error,
+            Some("card declined".into()),
+            vec![KeyValue::new("k", "v")],
+        );
+        span.add_event("no_severity".to_string(), vec![]);
+
+        let records = logger.0.lock().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(
+            records[0].trace_context,
+            Some(TraceContext::from(span.span_context()))
+        );
+        assert_eq!(records[0].severity_number, Some(Severity::Error));
+        assert_eq!(records[0].severity_text.as_deref(), Some("ERROR"));
+        assert_eq!(records[0].body, Some("card declined".into()));
+        assert_eq!(
+            records[0].attributes,
+            Some(vec![
+                KeyValue::new("k", "v"),
+                KeyValue::new("event.name", "payment_failed"),
+            ])
+        );
+    }
+
     #[test]
     fn record_exception() {
         let span = create_span();
@@ -387,6 +589,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn set_status_transitions() {
+        let span = create_span();
+        span.set_status(StatusCode::Error, "first".to_string());
+        span.set_status(StatusCode::Unset, String::new());
+        span.with_data(|data| {
+            assert_eq!(data.status_code, StatusCode::Error);
+            assert_eq!(data.status_message, "first");
+        });
+
+        span.set_status(StatusCode::Ok, String::new());
+        span.set_status(StatusCode::Error, "second".to_string());
+        span.with_data(|data| {
+            assert_eq!(data.status_code, StatusCode::Ok);
+            assert_eq!(data.status_message, "");
+        });
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_status_codes() {
+        assert_eq!(StatusCode::OK, StatusCode::Ok);
+        assert_eq!(StatusCode::NotFound, StatusCode::Error);
+        assert_eq!(StatusCode::Unauthenticated, StatusCode::Error);
+        // the shims are still usable as patterns
+        assert!(matches!(StatusCode::Ok, StatusCode::OK));
+    }
+
     #[test]
     fn update_name() {
         let span = create_span();
@@ -0,0 +1,233 @@
+//! # Span Metrics
+//!
+//! Derives request rate, error and duration metrics (RED metrics) from the
+//! spans a service records, so it gets them without instrumenting every
+//! operation a second time with metrics.
+//!
+//! For each ended span, the [`SpanMetricsProcessor`] records
+//!
+//! * `span.calls`: a counter of ended spans,
+//! * `span.errors`: a counter of spans that ended with an error status,
+//! * `span.duration`: a value recorder of the span durations in milliseconds,
+//!
+//! labeled with the `span.name` and `span.kind` of the span, and the span
+//! attributes selected as dimensions. Each distinct combination of label
+//! values is a separate time series, so dimensions should only select
+//! attributes with few distinct values, e.g. `http.method` rather than
+//! `http.url`.
+//!
+//! Only spans that are recorded reach span processors, so the metrics reflect
+//! the sampled spans only unless the sampler records all spans.
+//!
+//! # Examples
+//!
+//! ```
+//! use opentelemetry::metrics::MeterProvider;
+//! use opentelemetry::sdk::{
+//!     export::metrics::ExportKindSelector,
+//!     metrics::{controllers, selectors, Aggregation, View},
+//!     trace::{SpanMetricsProcessor, TracerProvider},
+//! };
+//!
+//! let controller = controllers::pull(
+//!     Box::new(selectors::simple::Selector::Inexpensive),
+//!     Box::new(ExportKindSelector::Cumulative),
+//! )
+//! .with_view(
+//!     View::new("span.duration")
+//!         .with_aggregation(Aggregation::Histogram(vec![5.0, 10.0, 50.0, 100.0, 500.0])),
+//! )
+//! .build();
+//! let meter = controller.provider().meter("span-metrics", None);
+//!
+//! let provider = TracerProvider::builder()
+//!     .with_span_processor(
+//!         SpanMetricsProcessor::new(&meter).with_dimensions(vec!["http.method"]),
+//!     )
+//!     .build();
+//! ```
+use crate::metrics::{Counter, Meter, ValueRecorder};
+use crate::sdk::export::trace::SpanData;
+use crate::sdk::trace::{Span, SpanProcessor};
+use crate::trace::{SpanKind, StatusCode, TraceResult};
+use crate::{Context, Key, KeyValue, Unit};
+use std::sync::Arc;
+
+/// Span processor recording metrics of the calls, errors and durations of
+/// ended spans, see the [module docs].
+///
+/// [module docs]: crate::sdk::trace::span_metrics
+#[derive(Debug)]
+pub struct SpanMetricsProcessor {
+    calls: Counter<u64>,
+    errors: Counter<u64>,
+    duration: ValueRecorder<f64>,
+    dimensions: Vec<Key>,
+}
+
+impl SpanMetricsProcessor {
+    /// Create a processor recording the metrics with instruments of `meter`,
+    /// labeled with the span name and kind only.
+    pub fn new(meter: &Meter) -> Self {
+        SpanMetricsProcessor {
+            calls: meter
+                .u64_counter("span.calls")
+                .with_description("The number of ended spans")
+                .init(),
+            errors: meter
+                .u64_counter("span.errors")
+                .with_description("The number of spans that ended with an error status")
+                .init(),
+            duration: meter
+                .f64_value_recorder("span.duration")
+                .with_description("The duration of ended spans")
+                .with_unit(Unit::new("ms"))
+                .init(),
+            dimensions: Vec::new(),
+        }
+    }
+
+    /// Label the metrics with the values of the given span attributes as
+    /// well.
+    ///
+    /// Spans without one of the attributes are recorded without its label.
+    pub fn with_dimensions<I, K>(mut self, dimensions: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        K: Into<Key>,
+    {
+        self.dimensions = dimensions.into_iter().map(Into::into).collect();
+        self
+    }
+
+    fn labels(&self, span: &SpanData) -> Vec<KeyValue> {
+        let mut labels = Vec::with_capacity(2 + self.dimensions.len());
+        labels.push(KeyValue::new("span.name", span.name.clone()));
+        labels.push(KeyValue::new("span.kind", span_kind(&span.span_kind)));
+        for key in &self.dimensions {
+            if let Some(value) = span.attributes.get(key) {
+                labels.push(KeyValue::new(key.clone(), value.clone()));
+            }
+        }
+        labels
+    }
+}
+
+impl SpanProcessor for SpanMetricsProcessor {
+    fn on_start(&self, _span: &Span, _cx: &Context) {}
+
+    fn on_end(&self, span: Arc<SpanData>) {
+        let labels = self.labels(&span);
+        self.calls.add(1, &labels);
+        if span.status_code == StatusCode::Error {
+            self.errors.add(1, &labels);
+        }
+        let duration = span.duration();
+        self.duration.record(
+            duration.as_secs() as f64 * 1000.0 + f64::from(duration.subsec_nanos()) / 1e6,
+            &labels,
+        );
+    }
+
+    fn force_flush(&self) -> TraceResult<()> {
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> TraceResult<()> {
+        Ok(())
+    }
+}
+
+fn span_kind(kind: &SpanKind) -> &'static str {
+    match kind {
+        SpanKind::Client => "client",
+        SpanKind::Server => "server",
+        SpanKind::Producer => "producer",
+        SpanKind::Consumer => "consumer",
+        SpanKind::Internal => "internal",
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::metrics::{MeterProvider, NumberKind};
+    use crate::sdk::export::metrics::{CheckpointSet, ExportKindSelector, Sum};
+    use crate::sdk::metrics::{aggregators, controllers, selectors};
+    use crate::sdk::trace::TracerProvider;
+    use crate::trace::{Span as _, Tracer as _, TracerProvider as _};
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn records_red_metrics() {
+        let mut controller = controllers::pull(
+            Box::new(selectors::simple::Selector::Histogram(vec![10.0, 100.0])),
+            Box::new(ExportKindSelector::Cumulative),
+        )
+        .with_cache_period(Duration::from_secs(0))
+        .build();
+        let meter = controller.provider().meter("span-metrics", None);
+        let provider = TracerProvider::builder()
+            .with_span_processor(SpanMetricsProcessor::new(&meter).with_dimensions(vec!["route"]))
+            .build();
+
+        let tracer = provider.get_tracer("span-metrics", None);
+        for &(millis, route, error) in &[(5, "/", false), (50, "/", true), (20, "/cart", false)] {
+            let start = SystemTime::UNIX_EPOCH;
+            let span = tracer
+                .span_builder("request")
+                .with_kind(SpanKind::Server)
+                .with_start_time(start)
+                .with_attributes(vec![
+                    KeyValue::new("route", route),
+                    KeyValue::new("user", "alice"),
+                ])
+                .start(&tracer);
+            if error {
+                span.set_status(StatusCode::Error, "failed".to_string());
+            }
+            span.end_with_timestamp(start + Duration::from_millis(millis));
+        }
+        controller.collect().unwrap();
+
+        let mut exported = Vec::new();
+        controller
+            .try_for_each(&ExportKindSelector::Cumulative, &mut |record| {
+                let mut labels: Vec<_> = record
+                    .labels()
+                    .iter()
+                    .map(|(key, value)| format!("{}={}", key.as_str(), value.as_str()))
+                    .collect();
+                labels.sort();
+                let aggregator = record.aggregator().unwrap().as_any();
+                let value = match aggregator.downcast_ref::<aggregators::SumAggregator>() {
+                    Some(sum) => sum.sum()?.to_f64(&NumberKind::U64),
+                    None => aggregator
+                        .downcast_ref::<aggregators::HistogramAggregator>()
+                        .unwrap()
+                        .sum()?
+                        .to_f64(&NumberKind::F64),
+                };
+                exported.push((
+                    record.descriptor().name().to_string(),
+                    labels.join(","),
+                    value,
+                ));
+                Ok(())
+            })
+            .unwrap();
+        exported.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+
+        let labels = |route| format!("route={},span.kind=server,span.name=request", route);
+        assert_eq!(
+            exported,
+            vec![
+                ("span.calls".to_string(), labels("/"), 2.0),
+                ("span.calls".to_string(), labels("/cart"), 1.0),
+                ("span.duration".to_string(), labels("/"), 55.0),
+                ("span.duration".to_string(), labels("/cart"), 20.0),
+                ("span.errors".to_string(), labels("/"), 1.0),
+            ]
+        );
+    }
+}
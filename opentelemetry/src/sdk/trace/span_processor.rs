@@ -35,18 +35,36 @@
 //! [`TracerProvider`]: crate::trace::TracerProvider
 
 use crate::global;
-use crate::sdk::trace::Span;
+use crate::runtime::Runtime;
+use crate::sdk::batch::{
+    block_on_with_timeout, env_var, split_batches, with_timeout, BoundedQueue, Coalesce,
+    Reschedule, SharedDelay,
+};
+use crate::sdk::trace::diagnostics::{ExportStats, RecordingExporter};
+use crate::sdk::trace::{
+    sampler::trace_id_ratio_selects, ConfigUpdate, ProcessorDiagnostics, QueueDiagnostics,
+    SamplingResult, Span,
+};
 use crate::{
+    baggage::BaggageExt,
     sdk::export::trace::{ExportResult, SpanData, SpanExporter},
-    trace::{TraceError, TraceResult},
-    Context,
+    trace::{Span as _, TraceError, TraceId, TraceResult},
+    Context, Key, KeyValue,
 };
+use async_trait::async_trait;
 use futures::{
-    channel::mpsc, channel::oneshot, executor, future::BoxFuture, future::Either, pin_mut, Future,
-    Stream, StreamExt,
+    channel::mpsc, channel::oneshot, executor, future::Either, stream::BoxStream, Future,
+    FutureExt, StreamExt,
+};
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::{
+    fmt,
+    sync::{mpsc as std_mpsc, Arc, Mutex, MutexGuard},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
-use std::env;
-use std::{fmt, str::FromStr, sync::Mutex, time::Duration};
 
 /// Delay interval between two consecutive exports.
 const OTEL_BSP_SCHEDULE_DELAY: &str = "OTEL_BSP_SCHEDULE_DELAY";
@@ -65,6 +83,14 @@ const OTEL_BSP_EXPORT_TIMEOUT: &str = "OTEL_BSP_EXPORT_TIMEOUT";
 /// Default maximum allowed time to export data.
 const OTEL_BSP_EXPORT_TIMEOUT_DEFAULT: u64 = 30_000;
 
+/// Take the data of an ended span, cloning it if other processors still share it.
+fn into_owned(span: Arc<SpanData>) -> SpanData {
+    Arc::try_unwrap(span).unwrap_or_else(|span| (*span).clone())
+}
+
+/// Number of panics after which a span processor is no longer called.
+const MAX_PROCESSOR_PANICS: usize = 3;
+
 /// `SpanProcessor` is an interface which allows hooks for span start and end
 /// method invocations. The span processors are invoked only when is_recording
 /// is true.
@@ -73,15 +99,44 @@ pub trait SpanProcessor: Send + Sync + std::fmt::Debug {
     /// synchronously on the thread that started the span, therefore it should
     /// not block or throw exceptions.
     fn on_start(&self, span: &Span, cx: &Context);
+    /// `on_start_with_sampling` is called when a `Span` is started, along
+    /// with the sampling result the span was started with. It is called for
+    /// dropped spans as well, so processors can account for unsampled
+    /// traffic, e.g. in statistics.
+    ///
+    /// Spans with a local parent follow the decision of their parent, they
+    /// are started with a result without attributes and the parent's trace
+    /// state. By default this calls `on_start`.
+    fn on_start_with_sampling(&self, span: &Span, cx: &Context, _sampling_result: &SamplingResult) {
+        self.on_start(span, cx)
+    }
     /// `on_end` is called after a `Span` is ended (i.e., the end timestamp is
     /// already set). This method is called synchronously within the `Span::end`
     /// API, therefore it should not block or throw an exception.
-    fn on_end(&self, span: SpanData);
+    ///
+    /// The span data is shared by all processors of a provider. Processors
+    /// needing owned data, e.g. to export it, should take it with
+    /// [`Arc::try_unwrap`] and only clone it if other processors still hold it.
+    fn on_end(&self, span: Arc<SpanData>);
     /// Force the spans lying in the cache to be exported.
     fn force_flush(&self) -> TraceResult<()>;
     /// Shuts down the processor. Called when SDK is shut down. This is an
     /// opportunity for processors to do any cleanup required.
     fn shutdown(&mut self) -> TraceResult<()>;
+    /// A snapshot of the state of the processor, reported by
+    /// [`TracerProvider::diagnostics`]. By default only the type name of the
+    /// processor is reported.
+    ///
+    /// [`TracerProvider::diagnostics`]: crate::sdk::trace::TracerProvider::diagnostics
+    fn diagnostics(&self) -> ProcessorDiagnostics {
+        ProcessorDiagnostics::new(std::any::type_name::<Self>())
+    }
+    /// Apply a configuration update of the provider while it runs, see
+    /// [`TracerProvider::update_config`]. Processors passing spans on to other
+    /// processors should pass the update on as well. Ignored by default.
+    ///
+    /// [`TracerProvider::update_config`]: crate::sdk::trace::TracerProvider::update_config
+    fn update_config(&self, _update: &ConfigUpdate) {}
 }
 
 /// A [`SpanProcessor`] that exports synchronously when spans are finished.
@@ -106,12 +161,15 @@ pub trait SpanProcessor: Send + Sync + std::fmt::Debug {
 /// ```
 #[derive(Debug)]
 pub struct SimpleSpanProcessor {
-    exporter: Mutex<Box<dyn SpanExporter>>,
+    exporter: Mutex<RecordingExporter>,
+    exports: Arc<ExportStats>,
 }
 
 impl SimpleSpanProcessor {
     pub(crate) fn new(exporter: Box<dyn SpanExporter>) -> Self {
+        let exporter = RecordingExporter::new(exporter);
         SimpleSpanProcessor {
+            exports: exporter.stats(),
             exporter: Mutex::new(exporter),
         }
     }
@@ -122,12 +180,14 @@ impl SpanProcessor for SimpleSpanProcessor {
         // Ignored
     }
 
-    fn on_end(&self, span: SpanData) {
+    fn on_end(&self, span: Arc<SpanData>) {
         let result = self
             .exporter
             .lock()
             .map_err(|_| TraceError::Other("simple span processor mutex poisoned".into()))
-            .and_then(|mut exporter| executor::block_on(exporter.export(vec![span])));
+            .and_then(|mut exporter| {
+                executor::block_on(catch_export_panic(exporter.export(vec![into_owned(span)])))
+            });
 
         if let Err(err) = result {
             global::handle_error(err);
@@ -150,35 +210,529 @@ impl SpanProcessor for SimpleSpanProcessor {
             ))
         }
     }
+
+    fn diagnostics(&self) -> ProcessorDiagnostics {
+        ProcessorDiagnostics {
+            exporter: Some(self.exports.snapshot()),
+            ..ProcessorDiagnostics::new("SimpleSpanProcessor")
+        }
+    }
+}
+
+/// A [`SpanProcessor`] that forwards all spans to a primary processor and a
+/// share of the traces to a secondary one.
+///
+/// This allows migrating to a new backend while the existing pipeline keeps
+/// receiving every span, with the ratio controlling the cost of the new one.
+/// Traces are selected by trace id, in the same way as the
+/// [`Sampler::TraceIdRatioBased`] sampler, so the secondary receives
+/// complete traces.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature="rt-tokio")]
+/// # {
+/// use opentelemetry::{trace as apitrace, sdk::trace as sdktrace, global, runtime};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let current = sdktrace::BatchSpanProcessor::builder(
+///         apitrace::NoopSpanExporter::new(), runtime::Tokio
+///     ).build();
+///     let migration = sdktrace::BatchSpanProcessor::builder(
+///         apitrace::NoopSpanExporter::new(), runtime::Tokio
+///     ).build();
+///
+///     // Send all spans to the current backend and 10% of the traces to the new one.
+///     let provider = sdktrace::TracerProvider::builder()
+///         .with_span_processor(sdktrace::MirroringSpanProcessor::new(current, migration, 0.1))
+///         .build();
+///
+///     let guard = global::set_tracer_provider(provider);
+///     # drop(guard)
+/// }
+/// # }
+/// ```
+///
+/// [`Sampler::TraceIdRatioBased`]: crate::sdk::trace::Sampler::TraceIdRatioBased
+#[derive(Debug)]
+pub struct MirroringSpanProcessor {
+    primary: Box<dyn SpanProcessor>,
+    secondary: Box<dyn SpanProcessor>,
+    ratio: f64,
+}
+
+impl MirroringSpanProcessor {
+    /// Create a new processor sending `ratio` of the traces, between `0.0`
+    /// and `1.0`, to `secondary` in addition to `primary`.
+    pub fn new<P, S>(primary: P, secondary: S, ratio: f64) -> Self
+    where
+        P: SpanProcessor + 'static,
+        S: SpanProcessor + 'static,
+    {
+        MirroringSpanProcessor {
+            primary: Box::new(primary),
+            secondary: Box::new(secondary),
+            ratio,
+        }
+    }
+
+    fn mirrors(&self, trace_id: TraceId) -> bool {
+        trace_id_ratio_selects(trace_id, self.ratio)
+    }
+}
+
+impl SpanProcessor for MirroringSpanProcessor {
+    fn on_start(&self, span: &Span, cx: &Context) {
+        self.primary.on_start(span, cx);
+        if self.mirrors(span.span_context().trace_id()) {
+            self.secondary.on_start(span, cx);
+        }
+    }
+
+    fn on_start_with_sampling(&self, span: &Span, cx: &Context, sampling_result: &SamplingResult) {
+        self.primary
+            .on_start_with_sampling(span, cx, sampling_result);
+        if self.mirrors(span.span_context().trace_id()) {
+            self.secondary
+                .on_start_with_sampling(span, cx, sampling_result);
+        }
+    }
+
+    fn on_end(&self, span: Arc<SpanData>) {
+        if self.mirrors(span.span_context.trace_id()) {
+            self.secondary.on_end(span.clone());
+        }
+        self.primary.on_end(span);
+    }
+
+    fn force_flush(&self) -> TraceResult<()> {
+        let secondary = self.secondary.force_flush();
+        self.primary.force_flush().and(secondary)
+    }
+
+    fn shutdown(&mut self) -> TraceResult<()> {
+        let secondary = self.secondary.shutdown();
+        self.primary.shutdown().and(secondary)
+    }
+
+    fn diagnostics(&self) -> ProcessorDiagnostics {
+        ProcessorDiagnostics {
+            processors: vec![self.primary.diagnostics(), self.secondary.diagnostics()],
+            ..ProcessorDiagnostics::new("MirroringSpanProcessor")
+        }
+    }
+
+    fn update_config(&self, update: &ConfigUpdate) {
+        self.primary.update_config(update);
+        self.secondary.update_config(update);
+    }
+}
+
+/// A [`SpanProcessor`] spreading spans over several processors by trace id.
+///
+/// A single batch processor funnels all ended spans through one queue and
+/// worker, which limits the span rate a process can sustain. Sharding the
+/// spans over several batch processors lets their workers run in parallel on
+/// multi-threaded runtimes. All spans of a trace are sent to the same shard.
+///
+/// Each shard exports independently with its own exporter. Shards sharing a
+/// single exporter can be built with [`BatchSpanProcessorBuilder::build_sharded`].
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature="rt-tokio")]
+/// # {
+/// use opentelemetry::{trace as apitrace, sdk::trace as sdktrace, global, runtime};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let shards = (0..4).map(|_| {
+///         sdktrace::BatchSpanProcessor::builder(
+///             apitrace::NoopSpanExporter::new(), runtime::Tokio
+///         ).build()
+///     });
+///
+///     let provider = sdktrace::TracerProvider::builder()
+///         .with_span_processor(sdktrace::ShardedSpanProcessor::new(shards))
+///         .build();
+///
+///     let guard = global::set_tracer_provider(provider);
+///     # drop(guard)
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ShardedSpanProcessor {
+    shards: Vec<Box<dyn SpanProcessor>>,
+}
+
+impl ShardedSpanProcessor {
+    /// Create a new processor spreading spans over the given shards.
+    pub fn new<I, P>(shards: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: SpanProcessor + 'static,
+    {
+        ShardedSpanProcessor {
+            shards: shards
+                .into_iter()
+                .map(|shard| Box::new(shard) as Box<dyn SpanProcessor>)
+                .collect(),
+        }
+    }
+
+    fn shard(&self, trace_id: TraceId) -> Option<&dyn SpanProcessor> {
+        if self.shards.is_empty() {
+            return None;
+        }
+        // trace ids are random, so the shards receive an even share of traces
+        let index = trace_id.to_u128() % self.shards.len() as u128;
+        Some(self.shards[index as usize].as_ref())
+    }
+}
+
+impl SpanProcessor for ShardedSpanProcessor {
+    fn on_start(&self, span: &Span, cx: &Context) {
+        if let Some(shard) = self.shard(span.span_context().trace_id()) {
+            shard.on_start(span, cx);
+        }
+    }
+
+    fn on_start_with_sampling(&self, span: &Span, cx: &Context, sampling_result: &SamplingResult) {
+        if let Some(shard) = self.shard(span.span_context().trace_id()) {
+            shard.on_start_with_sampling(span, cx, sampling_result);
+        }
+    }
+
+    fn on_end(&self, span: Arc<SpanData>) {
+        if let Some(shard) = self.shard(span.span_context.trace_id()) {
+            shard.on_end(span);
+        }
+    }
+
+    fn force_flush(&self) -> TraceResult<()> {
+        self.shards
+            .iter()
+            .map(|shard| shard.force_flush())
+            .fold(Ok(()), TraceResult::and)
+    }
+
+    fn shutdown(&mut self) -> TraceResult<()> {
+        self.shards
+            .iter_mut()
+            .map(|shard| shard.shutdown())
+            .fold(Ok(()), TraceResult::and)
+    }
+
+    fn diagnostics(&self) -> ProcessorDiagnostics {
+        ProcessorDiagnostics {
+            processors: self
+                .shards
+                .iter()
+                .map(|shard| shard.diagnostics())
+                .collect(),
+            ..ProcessorDiagnostics::new("ShardedSpanProcessor")
+        }
+    }
+
+    fn update_config(&self, update: &ConfigUpdate) {
+        for shard in &self.shards {
+            shard.update_config(update);
+        }
+    }
+}
+
+/// A [`SpanProcessor`] copying baggage entries of the parent context to the
+/// attributes of spans when they start.
+///
+/// Only the entries of an allow-list of names are copied, so that baggage
+/// received from other services does not end up in spans unnoticed. The
+/// attribute names can be prefixed to tell them apart from other attributes.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::{trace as apitrace, sdk::trace as sdktrace, global};
+///
+/// let provider = sdktrace::TracerProvider::builder()
+///     .with_span_processor(
+///         sdktrace::BaggageSpanProcessor::new(vec!["tenant", "user_id"]).with_prefix("baggage."),
+///     )
+///     .with_simple_exporter(apitrace::NoopSpanExporter::new())
+///     .build();
+///
+/// let previous_provider = global::set_tracer_provider(provider);
+/// ```
+#[derive(Debug)]
+pub struct BaggageSpanProcessor {
+    keys: Vec<Key>,
+    attributes: Vec<Key>,
+}
+
+impl BaggageSpanProcessor {
+    /// Create a new processor copying the baggage entries with the given
+    /// names.
+    pub fn new<I, K>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        K: Into<Key>,
+    {
+        let keys: Vec<Key> = keys.into_iter().map(Into::into).collect();
+        BaggageSpanProcessor {
+            attributes: keys.clone(),
+            keys,
+        }
+    }
+
+    /// Prefix the names of the attributes with `prefix`, e.g. `baggage.`.
+    pub fn with_prefix<T: Into<String>>(self, prefix: T) -> Self {
+        let prefix = prefix.into();
+        BaggageSpanProcessor {
+            attributes: self
+                .keys
+                .iter()
+                .map(|key| Key::new(format!("{}{}", prefix, key.as_str())))
+                .collect(),
+            ..self
+        }
+    }
+}
+
+impl SpanProcessor for BaggageSpanProcessor {
+    fn on_start(&self, span: &Span, cx: &Context) {
+        if !span.is_recording() {
+            return;
+        }
+        let baggage = cx.baggage();
+        for (key, attribute) in self.keys.iter().zip(self.attributes.iter()) {
+            if let Some(value) = baggage.get(key.clone()) {
+                span.set_attribute(KeyValue::new(attribute.clone(), value.clone()));
+            }
+        }
+    }
+
+    fn on_end(&self, _span: Arc<SpanData>) {
+        // Ignored
+    }
+
+    fn force_flush(&self) -> TraceResult<()> {
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> TraceResult<()> {
+        Ok(())
+    }
+
+    fn diagnostics(&self) -> ProcessorDiagnostics {
+        ProcessorDiagnostics::new("BaggageSpanProcessor")
+    }
+}
+
+/// An exporter shared by the shards of a sharded batch processor.
+///
+/// Batches of the shards are exported one at a time. The exporter is shut
+/// down along with the last shard.
+#[derive(Debug)]
+struct SharedExporter {
+    exporter: Arc<futures::lock::Mutex<Box<dyn SpanExporter>>>,
+    shards: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl SpanExporter for SharedExporter {
+    async fn export(&mut self, batch: Vec<SpanData>) -> ExportResult {
+        self.exporter.lock().await.export(batch).await
+    }
+
+    fn shutdown(&mut self) {
+        if self.shards.fetch_sub(1, Ordering::AcqRel) == 1 {
+            executor::block_on(self.exporter.lock()).shutdown();
+        }
+    }
+}
+
+/// Wraps the span processors of a provider so a panicking processor does not
+/// unwind into the code ending the span.
+///
+/// Panics are reported to the global error handler. After
+/// [`MAX_PROCESSOR_PANICS`] panics the processor is quarantined: spans are no
+/// longer passed to it, flushing it fails and only its shutdown is attempted.
+pub(crate) struct IsolatedSpanProcessor {
+    processor: Box<dyn SpanProcessor>,
+    panics: AtomicUsize,
+}
+
+impl IsolatedSpanProcessor {
+    pub(crate) fn new(processor: Box<dyn SpanProcessor>) -> Self {
+        IsolatedSpanProcessor {
+            processor,
+            panics: AtomicUsize::new(0),
+        }
+    }
+
+    fn is_quarantined(&self) -> bool {
+        self.panics.load(Ordering::Relaxed) >= MAX_PROCESSOR_PANICS
+    }
+
+    /// Run `f`, reporting a panic and returning `None` instead of unwinding.
+    fn isolate<T>(&self, method: &str, f: impl FnOnce() -> T) -> Option<T> {
+        match panic::catch_unwind(AssertUnwindSafe(f)) {
+            Ok(result) => Some(result),
+            Err(payload) => {
+                let panics = self.panics.fetch_add(1, Ordering::Relaxed) + 1;
+                global::handle_error(TraceError::from(format!(
+                    "span processor {:?} panicked in {}: {}",
+                    self.processor,
+                    method,
+                    panic_message(payload.as_ref())
+                )));
+                if panics == MAX_PROCESSOR_PANICS {
+                    global::handle_error(TraceError::from(format!(
+                        "span processor {:?} panicked {} times and is quarantined",
+                        self.processor, panics
+                    )));
+                }
+                None
+            }
+        }
+    }
+}
+
+impl fmt::Debug for IsolatedSpanProcessor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.processor.fmt(f)
+    }
+}
+
+impl SpanProcessor for IsolatedSpanProcessor {
+    fn on_start(&self, span: &Span, cx: &Context) {
+        if !self.is_quarantined() {
+            self.isolate("on_start", || self.processor.on_start(span, cx));
+        }
+    }
+
+    fn on_start_with_sampling(&self, span: &Span, cx: &Context, sampling_result: &SamplingResult) {
+        if !self.is_quarantined() {
+            self.isolate("on_start", || {
+                self.processor
+                    .on_start_with_sampling(span, cx, sampling_result)
+            });
+        }
+    }
+
+    fn on_end(&self, span: Arc<SpanData>) {
+        if !self.is_quarantined() {
+            self.isolate("on_end", || self.processor.on_end(span));
+        }
+    }
+
+    fn force_flush(&self) -> TraceResult<()> {
+        if self.is_quarantined() {
+            return Err(TraceError::from(
+                "span processor is quarantined after panicking",
+            ));
+        }
+        self.isolate("force_flush", || self.processor.force_flush())
+            .unwrap_or_else(|| Err(TraceError::from("span processor panicked in force_flush")))
+    }
+
+    fn shutdown(&mut self) -> TraceResult<()> {
+        let processor = &mut self.processor;
+        match panic::catch_unwind(AssertUnwindSafe(|| processor.shutdown())) {
+            Ok(result) => result,
+            Err(payload) => Err(TraceError::from(format!(
+                "span processor panicked in shutdown: {}",
+                panic_message(payload.as_ref())
+            ))),
+        }
+    }
+
+    fn diagnostics(&self) -> ProcessorDiagnostics {
+        let mut diagnostics = self
+            .isolate("diagnostics", || self.processor.diagnostics())
+            .unwrap_or_else(|| ProcessorDiagnostics::new(format!("{:?}", self.processor)));
+        diagnostics.quarantined = self.is_quarantined();
+        diagnostics
+    }
+
+    fn update_config(&self, update: &ConfigUpdate) {
+        if !self.is_quarantined() {
+            self.isolate("update_config", || self.processor.update_config(update));
+        }
+    }
+}
+
+/// Resolve a panicking export to an error instead of unwinding into the caller.
+async fn catch_export_panic<F>(export: F) -> ExportResult
+where
+    F: Future<Output = ExportResult>,
+{
+    match AssertUnwindSafe(export).catch_unwind().await {
+        Ok(result) => result,
+        Err(payload) => Err(TraceError::from(format!(
+            "span exporter panicked: {}",
+            panic_message(payload.as_ref())
+        ))),
+    }
+}
+
+/// The message of a panic payload, if it is a string.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic payload"
+    }
 }
 
 /// A [`SpanProcessor`] that asynchronously buffers finished spans and reports
 /// them at a preconfigured interval.
 ///
+/// Ending a span pushes it onto a lock-free queue without waiting for other
+/// threads, the worker takes the queued spans when it exports. Spans ending
+/// while the queue is full are dropped.
+///
+/// If the worker task stops before the processor is shut down, e.g. because
+/// the runtime it was spawned on shut down, it is restarted on the runtime
+/// of the processor when the next span ends. Queued spans are exported by the
+/// restarted worker, spans the stopped worker was exporting are lost. If the
+/// worker stopped during an export the exporter is lost as well, which is
+/// reported once and makes flushing the processor fail from then on.
+///
 /// # Examples
 ///
-/// This processor can be configured with an [`executor`] of your choice to
-/// batch and upload spans asynchronously when they end. If you have added a
-/// library like [`tokio`] or [`async-std`], you can pass in their respective
-/// `spawn` and `interval` functions to have batching performed in those
-/// contexts.
+/// This processor can be configured with the [`Runtime`] of your choice to
+/// batch and upload spans asynchronously when they end. Runtimes for
+/// [`tokio`] and [`async-std`] are available behind the `rt-tokio`,
+/// `rt-tokio-current-thread` and `rt-async-std` features.
+///
+/// Flushing and shutting down the processor block the calling thread until the
+/// worker is done. Applications running a current thread tokio runtime have to
+/// use [`runtime::TokioCurrentThread`], which runs the worker on a background
+/// thread, as a worker spawned on their blocked runtime would never finish.
+/// Applications without an async runtime can batch spans on a dedicated
+/// thread with [`BatchSpanProcessor::with_own_thread`].
+///
+/// [`runtime::TokioCurrentThread`]: crate::runtime::TokioCurrentThread
 ///
 /// ```
-/// # #[cfg(feature="tokio")]
+/// # #[cfg(feature="rt-tokio")]
 /// # {
-/// use futures::{stream};
-/// use opentelemetry::{trace as apitrace, sdk::trace as sdktrace, global, util::tokio_interval_stream};
-/// use std::time::Duration;
+/// use opentelemetry::{trace as apitrace, sdk::trace as sdktrace, global, runtime};
 ///
 /// #[tokio::main]
 /// async fn main() {
 ///     // Configure your preferred exporter
 ///     let exporter = apitrace::NoopSpanExporter::new();
 ///
-///     // Then build a batch processor. You can use whichever executor you have available, for
-///     // example if you are using `async-std` instead of `tokio` you can replace the spawn and
-///     // interval functions with `async_std::task::spawn` and `async_std::stream::interval`.
-///     let batch = sdktrace::BatchSpanProcessor::builder(exporter, tokio::spawn, tokio::time::sleep, tokio_interval_stream)
+///     // Then build a batch processor. You can use whichever runtime you have available, for
+///     // example if you are using `async-std` instead of `tokio` you can use `runtime::AsyncStd`.
+///     let batch = sdktrace::BatchSpanProcessor::builder(exporter, runtime::Tokio)
 ///         .with_max_queue_size(4096)
 ///         .build();
 ///
@@ -193,16 +747,27 @@ impl SpanProcessor for SimpleSpanProcessor {
 /// # }
 /// ```
 ///
-/// [`executor`]: https://docs.rs/futures/0.3/futures/executor/index.html
+/// [`Runtime`]: crate::runtime::Runtime
 /// [`tokio`]: https://tokio.rs
 /// [`async-std`]: https://async.rs
 pub struct BatchSpanProcessor {
-    message_sender: Mutex<mpsc::Sender<BatchMessage>>,
+    spans: Arc<BoundedQueue<Arc<SpanData>>>,
+    message_sender: Mutex<WorkerSender>,
+    start_worker: Box<StartWorker>,
+    exporter_slot: ExporterSlot,
+    worker_starts: AtomicUsize,
+    worker_stops: Arc<AtomicUsize>,
+    worker_lost: AtomicBool,
+    exports: Arc<ExportStats>,
+    dropped: AtomicUsize,
+    scheduled_delay: Arc<SharedDelay>,
+    is_shutdown: bool,
 }
 
 impl fmt::Debug for BatchSpanProcessor {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("BatchSpanProcessor")
+            .field("spans", &self.spans)
             .field("message_sender", &self.message_sender)
             .finish()
     }
@@ -213,207 +778,700 @@ impl SpanProcessor for BatchSpanProcessor {
         // Ignored
     }
 
-    fn on_end(&self, span: SpanData) {
-        let result = self
-            .message_sender
-            .lock()
-            .map_err(|_| TraceError::Other("batch span processor mutex poisoned".into()))
-            .and_then(|mut sender| {
-                sender
-                    .try_send(BatchMessage::ExportSpan(span))
-                    .map_err(|err| TraceError::Other(err.into()))
-            });
+    fn on_end(&self, span: Arc<SpanData>) {
+        // the loss of the worker has been reported already
+        if self.worker_lost.load(Ordering::Relaxed) {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
 
-        if let Err(err) = result {
-            global::handle_error(err);
+        if self.is_shutdown {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            global::handle_error(TraceError::from(PROCESSOR_SHUT_DOWN));
+            return;
+        }
+
+        // the sender is only locked to restart the worker once it stopped
+        if self.worker_stops.load(Ordering::Acquire) >= self.worker_starts.load(Ordering::Acquire) {
+            if let Err(err) = self.sender("batch span processor mutex poisoned") {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                global::handle_error(err);
+                return;
+            }
+        }
+
+        if self.spans.push(span).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            global::handle_error(TraceError::from(QUEUE_FULL));
         }
     }
 
     fn force_flush(&self) -> TraceResult<()> {
-        let mut sender = self.message_sender.lock().map_err(|_| TraceError::from("When force flushing the BatchSpanProcessor, the message sender's lock has been poisoned"))?;
+        let mut sender = self.sender("When force flushing the BatchSpanProcessor, the message sender's lock has been poisoned")?;
         let (res_sender, res_receiver) = oneshot::channel::<Vec<ExportResult>>();
         sender.try_send(BatchMessage::Flush(Some(res_sender)))?;
-        for result in futures::executor::block_on(res_receiver)? {
-            result?;
-        }
-        Ok(())
+        drop(sender);
+        wait_for_results(res_receiver)
     }
 
     fn shutdown(&mut self) -> TraceResult<()> {
-        let mut sender = self.message_sender.lock().map_err(|_| TraceError::from("When shutting down the BatchSpanProcessor, the message sender's lock has been poisoned"))?;
+        let mut sender = self.sender("When shutting down the BatchSpanProcessor, the message sender's lock has been poisoned")?;
         let (res_sender, res_receiver) = oneshot::channel::<Vec<ExportResult>>();
         sender.try_send(BatchMessage::Shutdown(res_sender))?;
-        for result in futures::executor::block_on(res_receiver)? {
-            result?;
+        drop(sender);
+        self.is_shutdown = true;
+        wait_for_results(res_receiver)
+    }
+
+    fn diagnostics(&self) -> ProcessorDiagnostics {
+        ProcessorDiagnostics {
+            queue: Some(QueueDiagnostics {
+                len: self.spans.len(),
+                capacity: self.spans.capacity(),
+                dropped: self.dropped.load(Ordering::Relaxed),
+            }),
+            exporter: Some(self.exports.snapshot()),
+            ..ProcessorDiagnostics::new("BatchSpanProcessor")
         }
-        Ok(())
     }
+
+    fn update_config(&self, update: &ConfigUpdate) {
+        if let Some(delay) = update.scheduled_delay() {
+            self.scheduled_delay.set(delay);
+        }
+    }
+}
+
+/// Wait for the results of the exports triggered by a flush or shutdown.
+#[cfg(not(target_arch = "wasm32"))]
+fn wait_for_results(results: oneshot::Receiver<Vec<ExportResult>>) -> TraceResult<()> {
+    for result in futures::executor::block_on(results)? {
+        result?;
+    }
+    Ok(())
+}
+
+/// The worker shares the only thread of `wasm32` targets, so the exports
+/// can't be waited for. Failed exports are reported by the worker instead.
+#[cfg(target_arch = "wasm32")]
+fn wait_for_results(results: oneshot::Receiver<Vec<ExportResult>>) -> TraceResult<()> {
+    drop(results);
+    Ok(())
 }
 
+const WORKER_LOST: &str =
+    "the BatchSpanProcessor worker stopped and its exporter was lost, spans are dropped";
+
+const QUEUE_FULL: &str = "the BatchSpanProcessor queue is full, the span is dropped";
+
+const PROCESSOR_SHUT_DOWN: &str = "the BatchSpanProcessor is shut down, the span is dropped";
+
+/// Starts a batch worker exporting with the given exporter.
+type StartWorker = dyn Fn(Box<dyn SpanExporter>) -> WorkerSender + Send + Sync;
+
+/// Sends messages to the worker of a [`BatchSpanProcessor`].
 #[derive(Debug)]
-enum BatchMessage {
-    ExportSpan(SpanData),
+enum WorkerSender {
+    /// A worker task spawned on a runtime.
+    Task(mpsc::Sender<BatchMessage>),
+    /// A worker running on its own thread.
+    Thread(std_mpsc::SyncSender<BatchMessage>),
+}
+
+impl WorkerSender {
+    fn try_send(&mut self, message: BatchMessage) -> TraceResult<()> {
+        match self {
+            WorkerSender::Task(sender) => sender
+                .try_send(message)
+                .map_err(|err| TraceError::Other(err.into())),
+            WorkerSender::Thread(sender) => sender
+                .try_send(message)
+                .map_err(|err| TraceError::Other(err.into())),
+        }
+    }
+
+    /// Whether the worker stopped, which is only noticed for worker tasks as
+    /// threads don't stop unless the processor is shut down.
+    fn is_closed(&self) -> bool {
+        match self {
+            WorkerSender::Task(sender) => sender.is_closed(),
+            WorkerSender::Thread(_) => false,
+        }
+    }
+}
+
+/// Holds the exporter of a stopped batch worker until it is restarted.
+type ExporterSlot = Arc<Mutex<Option<Box<dyn SpanExporter>>>>;
+
+/// The exporter of a running batch worker, handed to the [`ExporterSlot`]
+/// when the worker stops, e.g. because its task was dropped on runtime
+/// shutdown. Stopping also counts towards the stops of the processor's
+/// workers, so ending spans notice without locking the sender.
+struct WorkerExporter {
+    exporter: Option<Box<dyn SpanExporter>>,
+    slot: ExporterSlot,
+    stops: Arc<AtomicUsize>,
+}
+
+impl Drop for WorkerExporter {
+    fn drop(&mut self) {
+        if let (Some(exporter), Ok(mut slot)) = (self.exporter.take(), self.slot.lock()) {
+            *slot = Some(exporter);
+        }
+        self.stops.fetch_add(1, Ordering::Release);
+    }
+}
+
+/// Messages to the worker, spans are passed through the queue of the processor.
+#[derive(Debug)]
+pub(crate) enum BatchMessage {
     Flush(Option<oneshot::Sender<Vec<ExportResult>>>),
     Shutdown(oneshot::Sender<Vec<ExportResult>>),
 }
 
 impl BatchSpanProcessor {
-    pub(crate) fn new<S, SO, I, IS, ISI, D, DS>(
-        mut exporter: Box<dyn SpanExporter>,
-        spawn: S,
-        interval: I,
-        delay: D,
+    pub(crate) fn new<R: Runtime>(
+        exporter: Box<dyn SpanExporter>,
+        runtime: R,
         config: BatchConfig,
-    ) -> Self
-    where
-        S: Fn(BoxFuture<'static, ()>) -> SO,
-        I: Fn(Duration) -> IS,
-        IS: Stream<Item = ISI> + Send + 'static,
-        D: (Fn(Duration) -> DS) + Send + Sync + 'static,
-        DS: Future<Output = ()> + 'static + Send + Sync,
-    {
-        let (message_sender, message_receiver) = mpsc::channel(config.max_queue_size);
-        let ticker = interval(config.scheduled_delay).map(|_| BatchMessage::Flush(None));
-
-        // Spawn worker process via user-defined spawn function.
-        spawn(Box::pin(async move {
-            let mut spans = Vec::new();
-            let mut messages = Box::pin(futures::stream::select(message_receiver, ticker));
-
-            while let Some(message) = messages.next().await {
-                match message {
-                    // Span has finished, add to buffer of pending spans.
-                    BatchMessage::ExportSpan(span) => {
-                        if spans.len() < config.max_queue_size {
-                            spans.push(span);
-                        }
+    ) -> Self {
+        let spans = Arc::new(BoundedQueue::new(config.max_queue_size));
+        let queue = spans.clone();
+        let exporter_slot: ExporterSlot = Arc::new(Mutex::new(None));
+        let slot = exporter_slot.clone();
+        let worker_stops = Arc::new(AtomicUsize::new(0));
+        let stops = worker_stops.clone();
+        let scheduled_delay = Arc::new(SharedDelay::new(config.scheduled_delay));
+        let shared_delay = scheduled_delay.clone();
+        let config = Arc::new(config);
+
+        let start_worker = move |exporter: Box<dyn SpanExporter>| {
+            let (message_sender, message_receiver) = mpsc::channel(config.max_queue_size);
+            let shared_delay = shared_delay.clone();
+            let config = config.clone();
+            let worker_runtime = runtime.clone();
+            let queue = queue.clone();
+            // The exporter is moved into the export job while an export is in flight.
+            let mut worker = WorkerExporter {
+                exporter: Some(exporter),
+                slot: slot.clone(),
+                stops: stops.clone(),
+            };
+
+            // Spawn worker process on the runtime.
+            runtime.spawn(Box::pin(async move {
+                let runtime = worker_runtime;
+                // a restarted worker keeps the delay of the last update
+                let scheduled_delay = shared_delay.get();
+                let ticks = schedule(
+                    &runtime,
+                    config.first_flush_delay(crate::time::now()),
+                    scheduled_delay,
+                    config.schedule_jitter,
+                );
+                // after an update the schedule starts over with a full period of the new delay
+                let restart_runtime = runtime.clone();
+                let restart_config = config.clone();
+                let ticks = Reschedule::new(ticks, scheduled_delay, shared_delay, {
+                    move |delay| {
+                        delays(
+                            &restart_runtime,
+                            restart_config.rescheduled_delay(delay, crate::time::now()),
+                            delay,
+                            restart_config.schedule_jitter,
+                        )
                     }
-                    // Span batch interval time reached or a force flush has been invoked, export current spans.
-                    BatchMessage::Flush(Some(ch)) => {
-                        let mut results =
-                            Vec::with_capacity(spans.len() / config.max_export_batch_size + 1);
-                        while !spans.is_empty() {
-                            let batch = spans.split_off(
-                                spans.len().saturating_sub(config.max_export_batch_size),
-                            );
-
-                            results.push(
-                                export_with_timeout(
-                                    config.max_export_timeout,
-                                    exporter.as_mut(),
-                                    &delay,
-                                    batch,
-                                )
-                                .await,
-                            );
-                        }
-                        let send_result = ch.send(results);
-                        if send_result.is_err() {
-                            global::handle_error(TraceError::from("fail to send the export response from worker handle in BatchProcessor"))
+                });
+                let ticker = Coalesce::new(ticks).map(|_| BatchMessage::Flush(None));
+
+                let mut messages = Box::pin(futures::stream::select(message_receiver, ticker));
+                let mut in_flight: Option<oneshot::Receiver<ExportJobResult>> = None;
+
+                loop {
+                    let message = match in_flight.as_mut() {
+                        Some(export) => {
+                            match futures::future::select(export, messages.next()).await {
+                                // Offloaded export has finished, reclaim the exporter.
+                                Either::Left((finished, _)) => {
+                                    in_flight = None;
+                                    worker.exporter = reclaim_exporter(finished);
+                                    if worker.exporter.is_none() {
+                                        break;
+                                    }
+                                    continue;
+                                }
+                                Either::Right((message, _)) => message,
+                            }
                         }
-                    }
-                    BatchMessage::Flush(None) => {
-                        while !spans.is_empty() {
-                            let batch = spans.split_off(
-                                spans.len().saturating_sub(config.max_export_batch_size),
-                            );
-
-                            let result = export_with_timeout(
-                                config.max_export_timeout,
-                                exporter.as_mut(),
-                                &delay,
-                                batch,
-                            )
-                            .await;
-
-                            if let Err(err) = result {
-                                global::handle_error(err);
+                        None => messages.next().await,
+                    };
+
+                    match message {
+                        // Span batch interval time reached, export queued spans. Spans are kept
+                        // for the next tick if an offloaded export is still in flight.
+                        Some(BatchMessage::Flush(None)) => {
+                            if in_flight.is_some() {
+                                continue;
+                            }
+                            let mut spans = drain_spans(&queue);
+                            if spans.is_empty() {
+                                continue;
+                            }
+                            let job = match worker.exporter.take() {
+                                Some(exporter) => export_batches(
+                                    exporter,
+                                    split_batches(&mut spans, config.max_export_batch_size),
+                                    config.max_export_timeout,
+                                    runtime.clone(),
+                                ),
+                                None => break,
+                            };
+
+                            if config.offload_export {
+                                in_flight = Some(spawn_export_job(job, &runtime));
+                            } else {
+                                worker.exporter = Some(report_export_errors(job.await));
                             }
                         }
-                    }
-                    // Stream has terminated or processor is shutdown, return to finish execution.
-                    BatchMessage::Shutdown(ch) => {
-                        let mut results =
-                            Vec::with_capacity(spans.len() / config.max_export_batch_size + 1);
-                        while !spans.is_empty() {
-                            let batch = spans.split_off(
-                                spans.len().saturating_sub(config.max_export_batch_size),
-                            );
-
-                            results.push(
-                                export_with_timeout(
+                        // A force flush has been invoked, export queued spans once any in flight
+                        // export has finished.
+                        Some(BatchMessage::Flush(Some(ch))) => {
+                            if let Some(export) = in_flight.take() {
+                                worker.exporter = reclaim_exporter(export.await);
+                            }
+                            let mut spans = drain_spans(&queue);
+                            let job = match worker.exporter.take() {
+                                Some(exporter) => export_batches(
+                                    exporter,
+                                    split_batches(&mut spans, config.max_export_batch_size),
                                     config.max_export_timeout,
-                                    exporter.as_mut(),
-                                    &delay,
-                                    batch,
-                                )
-                                .await,
-                            );
+                                    runtime.clone(),
+                                ),
+                                None => break,
+                            };
+
+                            match run_export_job(job, config.offload_export, &runtime).await {
+                                Some((returned, results)) => {
+                                    worker.exporter = Some(returned);
+                                    send_results(ch, results);
+                                }
+                                None => {
+                                    global::handle_error(TraceError::from(EXPORT_JOB_DROPPED));
+                                    break;
+                                }
+                            }
                         }
-                        exporter.shutdown();
-                        let send_result = ch.send(results);
-                        if send_result.is_err() {
-                            global::handle_error(TraceError::from("fail to send the export response from worker handle in BatchProcessor"))
+                        // Processor is shutdown, export remaining spans and return to finish execution.
+                        Some(BatchMessage::Shutdown(ch)) => {
+                            if let Some(export) = in_flight.take() {
+                                worker.exporter = reclaim_exporter(export.await);
+                            }
+                            let mut spans = drain_spans(&queue);
+                            let job = match worker.exporter.take() {
+                                Some(exporter) => export_batches(
+                                    exporter,
+                                    split_batches(&mut spans, config.max_export_batch_size),
+                                    config.max_export_timeout,
+                                    runtime.clone(),
+                                ),
+                                None => break,
+                            };
+
+                            match run_export_job(job, config.offload_export, &runtime).await {
+                                Some((mut returned, results)) => {
+                                    returned.shutdown();
+                                    send_results(ch, results);
+                                }
+                                None => global::handle_error(TraceError::from(EXPORT_JOB_DROPPED)),
+                            }
+                            break;
                         }
-                        break;
+                        // Stream has terminated, return to finish execution.
+                        None => break,
                     }
                 }
-            }
-        }));
+            }));
+
+            WorkerSender::Task(message_sender)
+        };
 
         // Return batch processor with link to worker
+        let exporter = RecordingExporter::new(exporter);
         BatchSpanProcessor {
-            message_sender: Mutex::new(message_sender),
+            spans,
+            exports: exporter.stats(),
+            dropped: AtomicUsize::new(0),
+            scheduled_delay,
+            message_sender: Mutex::new(start_worker(Box::new(exporter))),
+            start_worker: Box::new(start_worker),
+            exporter_slot,
+            worker_starts: AtomicUsize::new(1),
+            worker_stops,
+            worker_lost: AtomicBool::new(false),
+            is_shutdown: false,
+        }
+    }
+
+    /// Lock the sender to the worker, restarting the worker if it stopped
+    /// before the processor was shut down.
+    fn sender(&self, poisoned: &'static str) -> TraceResult<MutexGuard<'_, WorkerSender>> {
+        let mut sender = self
+            .message_sender
+            .lock()
+            .map_err(|_| TraceError::from(poisoned))?;
+        if sender.is_closed() && !self.is_shutdown {
+            self.restart_worker(&mut sender)?;
+        }
+        Ok(sender)
+    }
+
+    /// Start a new worker with the exporter left behind by the stopped one.
+    ///
+    /// The exporter is lost if the worker stopped during an export, in which
+    /// case the processor fails from then on.
+    fn restart_worker(&self, sender: &mut WorkerSender) -> TraceResult<()> {
+        let exporter = match self.exporter_slot.lock() {
+            Ok(mut slot) => slot.take(),
+            Err(_) => None,
+        };
+        let restarted = exporter.and_then(|exporter| {
+            // spawning panics if e.g. the runtime of the worker is gone
+            panic::catch_unwind(AssertUnwindSafe(|| (self.start_worker)(exporter))).ok()
+        });
+
+        match restarted {
+            Some(restarted) => {
+                *sender = restarted;
+                self.worker_starts.fetch_add(1, Ordering::Release);
+                global::handle_error(TraceError::from(
+                    "the BatchSpanProcessor worker stopped unexpectedly and was restarted",
+                ));
+                Ok(())
+            }
+            None => {
+                if !self.worker_lost.swap(true, Ordering::Relaxed) {
+                    global::handle_error(TraceError::from(WORKER_LOST));
+                }
+                Err(TraceError::from(WORKER_LOST))
+            }
+        }
+    }
+
+    /// Create a batch processor exporting on its own thread, without an async
+    /// runtime.
+    ///
+    /// Spans are batched on a dedicated thread and exports are driven to
+    /// completion on it, so the exporter must not depend on a runtime either,
+    /// e.g. by spawning tasks or using the timers of one. Exports always run on
+    /// the worker thread, the offloaded export setting is ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use opentelemetry::{sdk::trace as sdktrace, trace as apitrace};
+    /// use std::time::Duration;
+    ///
+    /// let config = sdktrace::BatchConfig::default().with_scheduled_delay(Duration::from_secs(1));
+    /// let batch = sdktrace::BatchSpanProcessor::with_own_thread(
+    ///     apitrace::NoopSpanExporter::new(),
+    ///     config,
+    /// );
+    ///
+    /// let provider = sdktrace::TracerProvider::builder()
+    ///     .with_batch_exporter(batch)
+    ///     .build();
+    /// # drop(provider)
+    /// ```
+    pub fn with_own_thread<E>(exporter: E, config: BatchConfig) -> Self
+    where
+        E: SpanExporter + 'static,
+    {
+        let spans = Arc::new(BoundedQueue::new(config.max_queue_size));
+        let queue = spans.clone();
+        let exporter_slot: ExporterSlot = Arc::new(Mutex::new(None));
+        let slot = exporter_slot.clone();
+        let worker_stops = Arc::new(AtomicUsize::new(0));
+        let stops = worker_stops.clone();
+        let scheduled_delay = Arc::new(SharedDelay::new(config.scheduled_delay));
+        let shared_delay = scheduled_delay.clone();
+        let config = Arc::new(config);
+
+        let start_worker = move |exporter: Box<dyn SpanExporter>| {
+            let (message_sender, message_receiver) = std_mpsc::sync_channel(config.max_queue_size);
+            let shared_delay = shared_delay.clone();
+            let config = config.clone();
+            let worker = WorkerExporter {
+                exporter: Some(exporter),
+                slot: slot.clone(),
+                stops: stops.clone(),
+            };
+            let queue = queue.clone();
+
+            thread::Builder::new()
+                .name("opentelemetry-batch-span-processor".to_string())
+                .spawn(move || {
+                    run_worker_thread(worker, &config, &shared_delay, &queue, message_receiver)
+                })
+                .expect("failed to spawn the batch span processor thread");
+
+            WorkerSender::Thread(message_sender)
+        };
+
+        let exporter = RecordingExporter::new(Box::new(exporter));
+        BatchSpanProcessor {
+            spans,
+            exports: exporter.stats(),
+            dropped: AtomicUsize::new(0),
+            scheduled_delay,
+            message_sender: Mutex::new(start_worker(Box::new(exporter))),
+            start_worker: Box::new(start_worker),
+            exporter_slot,
+            worker_starts: AtomicUsize::new(1),
+            worker_stops,
+            worker_lost: AtomicBool::new(false),
+            is_shutdown: false,
         }
     }
 
     /// Create a new batch processor builder
-    pub fn builder<E, S, SO, I, IO, D, DS>(
-        exporter: E,
-        spawn: S,
-        delay: D,
-        interval: I,
-    ) -> BatchSpanProcessorBuilder<E, S, I, D>
+    pub fn builder<E, R>(exporter: E, runtime: R) -> BatchSpanProcessorBuilder<E, R>
     where
         E: SpanExporter,
-        S: Fn(BoxFuture<'static, ()>) -> SO,
-        I: Fn(Duration) -> IO,
-        D: (Fn(Duration) -> DS) + Send + Sync + 'static,
-        DS: Future<Output = ()> + 'static + Send + Sync,
+        R: Runtime,
     {
         BatchSpanProcessorBuilder {
             exporter,
-            spawn,
-            interval,
-            delay,
+            runtime,
             config: BatchConfig::default(),
         }
     }
 }
 
-async fn export_with_timeout<D, DS, E>(
+/// The ticks of the scheduled exports of a worker task, the first one after
+/// `first_delay` and the following ones every `scheduled_delay`.
+fn schedule<R: Runtime>(
+    runtime: &R,
+    first_delay: Duration,
+    scheduled_delay: Duration,
+    jitter: u8,
+) -> BoxStream<'static, ()> {
+    // The interval is only started once the first flush is due so that the
+    // following flushes keep the same offset.
+    if jitter > 0 {
+        // every period is jittered, so ticks are scheduled one at a time
+        delays(runtime, first_delay, scheduled_delay, jitter)
+    } else if first_delay == Duration::from_secs(0) {
+        runtime.interval(scheduled_delay).map(|_| ()).boxed()
+    } else {
+        let interval_runtime = runtime.clone();
+        futures::stream::once(runtime.delay(first_delay))
+            .map(move |_| interval_runtime.interval(scheduled_delay).map(|_| ()))
+            .flatten()
+            .boxed()
+    }
+}
+
+/// Ticks scheduled one at a time, the first one after `first_delay` and each
+/// following one a jittered `scheduled_delay` after the previous one.
+///
+/// Unlike intervals, whose first tick is immediate on some runtimes, the
+/// first tick is always delayed.
+fn delays<R: Runtime>(
+    runtime: &R,
+    first_delay: Duration,
+    scheduled_delay: Duration,
+    jitter: u8,
+) -> BoxStream<'static, ()> {
+    let tick_runtime = runtime.clone();
+    futures::stream::once(runtime.delay(first_delay).map(|_| ()))
+        .chain(futures::stream::repeat(()).then(move |_| {
+            tick_runtime
+                .delay(crate::util::jittered(scheduled_delay, jitter))
+                .map(|_| ())
+        }))
+        .boxed()
+}
+
+const EXPORT_JOB_DROPPED: &str =
+    "export task in BatchProcessor was dropped before completion, the exporter is lost";
+
+/// The batching loop of a worker running on its own thread, see
+/// [`BatchSpanProcessor::with_own_thread`].
+fn run_worker_thread(
+    mut worker: WorkerExporter,
+    config: &BatchConfig,
+    scheduled_delay: &SharedDelay,
+    queue: &BoundedQueue<Arc<SpanData>>,
+    messages: std_mpsc::Receiver<BatchMessage>,
+) {
+    let mut next_flush = Instant::now() + config.first_flush_delay(crate::time::now());
+
+    loop {
+        let message =
+            match messages.recv_timeout(next_flush.saturating_duration_since(Instant::now())) {
+                Ok(message) => message,
+                Err(std_mpsc::RecvTimeoutError::Timeout) => {
+                    let scheduled_delay = scheduled_delay.get();
+                    next_flush += if config.schedule_jitter > 0 {
+                        crate::util::jittered(scheduled_delay, config.schedule_jitter)
+                    } else {
+                        scheduled_delay
+                    };
+                    BatchMessage::Flush(None)
+                }
+                // The processor was dropped without being shut down.
+                Err(std_mpsc::RecvTimeoutError::Disconnected) => break,
+            };
+        let exporter = match worker.exporter.as_mut() {
+            Some(exporter) => exporter.as_mut(),
+            None => break,
+        };
+
+        let mut spans = drain_spans(queue);
+        match message {
+            BatchMessage::Flush(None) => {
+                if spans.is_empty() {
+                    continue;
+                }
+                report_errors(export_batches_blocking(exporter, &mut spans, config));
+            }
+            BatchMessage::Flush(Some(ch)) => {
+                let results = export_batches_blocking(exporter, &mut spans, config);
+                send_results(ch, results);
+            }
+            BatchMessage::Shutdown(ch) => {
+                let results = export_batches_blocking(exporter, &mut spans, config);
+                exporter.shutdown();
+                send_results(ch, results);
+                break;
+            }
+        }
+    }
+}
+
+/// Take the queued spans, cloning the ones other processors still share.
+fn drain_spans(queue: &BoundedQueue<Arc<SpanData>>) -> Vec<SpanData> {
+    queue.drain().into_iter().map(into_owned).collect()
+}
+
+/// Export the buffered spans in batches, blocking the current thread.
+fn export_batches_blocking(
+    exporter: &mut dyn SpanExporter,
+    spans: &mut Vec<SpanData>,
+    config: &BatchConfig,
+) -> Vec<ExportResult> {
+    let time_out = config.max_export_timeout;
+    split_batches(spans, config.max_export_batch_size)
+        .into_iter()
+        .map(|batch| {
+            block_on_with_timeout(catch_export_panic(exporter.export(batch)), time_out)
+                .unwrap_or(ExportResult::Err(TraceError::ExportTimedOut(time_out)))
+        })
+        .collect()
+}
+
+/// The exporter handed back by an export job along with the result of each batch.
+type ExportJobResult = (Box<dyn SpanExporter>, Vec<ExportResult>);
+
+/// Export each batch in turn, handing the exporter back once all batches are done.
+async fn export_batches<R: Runtime>(
+    mut exporter: Box<dyn SpanExporter>,
+    batches: Vec<Vec<SpanData>>,
+    time_out: Duration,
+    runtime: R,
+) -> ExportJobResult {
+    let mut results = Vec::with_capacity(batches.len());
+    for batch in batches {
+        results.push(export_with_timeout(time_out, exporter.as_mut(), &runtime, batch).await);
+    }
+    (exporter, results)
+}
+
+/// Run an export job on its own task so the worker loop can keep receiving spans.
+fn spawn_export_job<J, R>(job: J, runtime: &R) -> oneshot::Receiver<ExportJobResult>
+where
+    J: Future<Output = ExportJobResult> + Send + 'static,
+    R: Runtime,
+{
+    let (job_sender, job_receiver) = oneshot::channel();
+    runtime.spawn(Box::pin(async move {
+        let _ = job_sender.send(job.await);
+    }));
+    job_receiver
+}
+
+/// Run an export job to completion, either inline or offloaded to its own task.
+///
+/// Returns `None` if an offloaded job was dropped before it completed.
+async fn run_export_job<J, R>(job: J, offload: bool, runtime: &R) -> Option<ExportJobResult>
+where
+    J: Future<Output = ExportJobResult> + Send + 'static,
+    R: Runtime,
+{
+    if offload {
+        spawn_export_job(job, runtime).await.ok()
+    } else {
+        Some(job.await)
+    }
+}
+
+/// Report failed exports through the global error handler and return the exporter.
+fn report_export_errors((exporter, results): ExportJobResult) -> Box<dyn SpanExporter> {
+    report_errors(results);
+    exporter
+}
+
+/// Report failed exports through the global error handler.
+fn report_errors(results: Vec<ExportResult>) {
+    for result in results {
+        if let Err(err) = result {
+            global::handle_error(err);
+        }
+    }
+}
+
+/// Send the results of a flush or shutdown to the processor, reporting failed
+/// exports if it no longer waits for them.
+fn send_results(ch: oneshot::Sender<Vec<ExportResult>>, results: Vec<ExportResult>) {
+    if let Err(results) = ch.send(results) {
+        report_errors(results);
+    }
+}
+
+/// Reclaim the exporter from a finished offloaded export job.
+fn reclaim_exporter(
+    finished: Result<ExportJobResult, oneshot::Canceled>,
+) -> Option<Box<dyn SpanExporter>> {
+    match finished {
+        Ok(finished) => Some(report_export_errors(finished)),
+        Err(_) => {
+            global::handle_error(TraceError::from(EXPORT_JOB_DROPPED));
+            None
+        }
+    }
+}
+
+async fn export_with_timeout<R, E>(
     time_out: Duration,
     exporter: &mut E,
-    delay: &D,
+    runtime: &R,
     batch: Vec<SpanData>,
 ) -> ExportResult
 where
-    D: (Fn(Duration) -> DS) + Send + Sync + 'static,
-    DS: Future<Output = ()> + 'static + Send + Sync,
+    R: Runtime,
     E: SpanExporter + ?Sized,
 {
-    let export = exporter.export(batch);
-    let timeout = delay(time_out);
-    pin_mut!(export);
-    pin_mut!(timeout);
-    match futures::future::select(export, timeout).await {
-        Either::Left((export_res, _)) => export_res,
-        Either::Right((_, _)) => ExportResult::Err(TraceError::ExportTimedOut(time_out)),
-    }
+    with_timeout(
+        catch_export_panic(exporter.export(batch)),
+        time_out,
+        runtime,
+    )
+    .await
+    .unwrap_or(ExportResult::Err(TraceError::ExportTimedOut(time_out)))
 }
 
 /// Batch span processor configuration
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct BatchConfig {
     /// The maximum queue size to buffer spans for delayed processing. If the
     /// queue gets full it drops the spans. The default value of is 2048.
@@ -431,6 +1489,84 @@ pub struct BatchConfig {
 
     /// The maximum duration to export a batch of data.
     max_export_timeout: Duration,
+
+    /// Whether exports run on their own task instead of the worker task, so
+    /// encoding large batches does not hold up receiving new spans. The
+    /// default value is false.
+    offload_export: bool,
+
+    /// Delay before the first scheduled export, the interval between
+    /// exports starts once it elapsed. The default value is zero.
+    first_flush_delay: Duration,
+
+    /// Whether scheduled exports happen at wall clock multiples of the
+    /// scheduled delay, offset by the first flush delay. The default value
+    /// is false.
+    align_to_wall_clock: bool,
+
+    /// Percentage by which each scheduled delay is randomly lengthened or
+    /// shortened. The default value is 0.
+    schedule_jitter: u8,
+}
+
+impl BatchConfig {
+    /// Set max queue size for batches
+    pub fn with_max_queue_size(mut self, size: usize) -> Self {
+        self.max_queue_size = size;
+        self
+    }
+
+    /// Set scheduled delay for batches
+    pub fn with_scheduled_delay(mut self, delay: Duration) -> Self {
+        self.scheduled_delay = delay;
+        self
+    }
+
+    /// Set max timeout for exporting.
+    pub fn with_max_timeout(mut self, timeout: Duration) -> Self {
+        self.max_export_timeout = timeout;
+        self
+    }
+
+    /// Set max export size for batches, should always less than or equals to max queue size.
+    ///
+    /// If input is larger than max queue size, will lower it to be equal to max queue size
+    pub fn with_max_export_batch_size(mut self, size: usize) -> Self {
+        self.max_export_batch_size = size.min(self.max_queue_size);
+        self
+    }
+
+    /// Time to wait at `now` before the first scheduled export.
+    fn first_flush_delay(&self, now: SystemTime) -> Duration {
+        if !self.align_to_wall_clock || self.scheduled_delay == Duration::from_secs(0) {
+            return self.first_flush_delay;
+        }
+
+        let period = self.scheduled_delay.as_nanos();
+        let since_epoch = now
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .checked_sub(self.first_flush_delay)
+            .unwrap_or_default()
+            .as_nanos();
+        let until_next = (period - since_epoch % period) % period;
+
+        Duration::from_nanos(until_next as u64)
+    }
+
+    /// Delay before the first export after the scheduled delay changed to
+    /// `scheduled_delay`, keeping scheduled exports aligned to the wall clock.
+    fn rescheduled_delay(&self, scheduled_delay: Duration, now: SystemTime) -> Duration {
+        if self.align_to_wall_clock {
+            BatchConfig {
+                scheduled_delay,
+                ..self.clone()
+            }
+            .first_flush_delay(now)
+        } else {
+            scheduled_delay
+        }
+    }
 }
 
 impl Default for BatchConfig {
@@ -440,27 +1576,23 @@ impl Default for BatchConfig {
             scheduled_delay: Duration::from_millis(OTEL_BSP_SCHEDULE_DELAY_DEFAULT),
             max_export_batch_size: OTEL_BSP_MAX_EXPORT_BATCH_SIZE_DEFAULT,
             max_export_timeout: Duration::from_millis(OTEL_BSP_EXPORT_TIMEOUT_DEFAULT),
+            offload_export: false,
+            first_flush_delay: Duration::from_secs(0),
+            align_to_wall_clock: false,
+            schedule_jitter: 0,
         };
 
-        if let Some(max_queue_size) = env::var(OTEL_BSP_MAX_QUEUE_SIZE)
-            .ok()
-            .and_then(|queue_size| usize::from_str(&queue_size).ok())
-        {
+        if let Some(max_queue_size) = env_var(&[OTEL_BSP_MAX_QUEUE_SIZE]) {
             config.max_queue_size = max_queue_size;
         }
 
-        if let Some(scheduled_delay) = env::var(OTEL_BSP_SCHEDULE_DELAY)
-            .ok()
-            .or_else(|| env::var("OTEL_BSP_SCHEDULE_DELAY_MILLIS").ok())
-            .and_then(|delay| u64::from_str(&delay).ok())
+        if let Some(scheduled_delay) =
+            env_var(&[OTEL_BSP_SCHEDULE_DELAY, "OTEL_BSP_SCHEDULE_DELAY_MILLIS"])
         {
             config.scheduled_delay = Duration::from_millis(scheduled_delay);
         }
 
-        if let Some(max_export_batch_size) = env::var(OTEL_BSP_MAX_EXPORT_BATCH_SIZE)
-            .ok()
-            .and_then(|batch_size| usize::from_str(&batch_size).ok())
-        {
+        if let Some(max_export_batch_size) = env_var(&[OTEL_BSP_MAX_EXPORT_BATCH_SIZE]) {
             config.max_export_batch_size = max_export_batch_size;
         }
 
@@ -470,10 +1602,8 @@ impl Default for BatchConfig {
             config.max_export_batch_size = config.max_queue_size;
         }
 
-        if let Some(max_export_timeout) = env::var(OTEL_BSP_EXPORT_TIMEOUT)
-            .ok()
-            .or_else(|| env::var("OTEL_BSP_EXPORT_TIMEOUT_MILLIS").ok())
-            .and_then(|timeout| u64::from_str(&timeout).ok())
+        if let Some(max_export_timeout) =
+            env_var(&[OTEL_BSP_EXPORT_TIMEOUT, "OTEL_BSP_EXPORT_TIMEOUT_MILLIS"])
         {
             config.max_export_timeout = Duration::from_millis(max_export_timeout);
         }
@@ -485,43 +1615,34 @@ impl Default for BatchConfig {
 /// A builder for creating [`BatchSpanProcessor`] instances.
 ///
 #[derive(Debug)]
-pub struct BatchSpanProcessorBuilder<E, S, I, D> {
+pub struct BatchSpanProcessorBuilder<E, R> {
     exporter: E,
-    interval: I,
-    spawn: S,
-    delay: D,
+    runtime: R,
     config: BatchConfig,
 }
 
-impl<E, S, SO, I, IS, ISI, D, DS> BatchSpanProcessorBuilder<E, S, I, D>
+impl<E, R> BatchSpanProcessorBuilder<E, R>
 where
     E: SpanExporter + 'static,
-    S: Fn(BoxFuture<'static, ()>) -> SO,
-    I: Fn(Duration) -> IS,
-    IS: Stream<Item = ISI> + Send + 'static,
-    D: (Fn(Duration) -> DS) + Send + Sync + 'static,
-    DS: Future<Output = ()> + 'static + Send + Sync,
+    R: Runtime,
 {
     /// Set max queue size for batches
     pub fn with_max_queue_size(self, size: usize) -> Self {
-        let mut config = self.config;
-        config.max_queue_size = size;
+        let config = self.config.with_max_queue_size(size);
 
         BatchSpanProcessorBuilder { config, ..self }
     }
 
     /// Set scheduled delay for batches
     pub fn with_scheduled_delay(self, delay: Duration) -> Self {
-        let mut config = self.config;
-        config.scheduled_delay = delay;
+        let config = self.config.with_scheduled_delay(delay);
 
         BatchSpanProcessorBuilder { config, ..self }
     }
 
     /// Set max timeout for exporting.
     pub fn with_max_timeout(self, timeout: Duration) -> Self {
-        let mut config = self.config;
-        config.max_export_timeout = timeout;
+        let config = self.config.with_max_timeout(timeout);
 
         BatchSpanProcessorBuilder { config, ..self }
     }
@@ -530,46 +1651,119 @@ where
     ///
     /// If input is larger than max queue size, will lower it to be equal to max queue size
     pub fn with_max_export_batch_size(self, size: usize) -> Self {
+        let config = self.config.with_max_export_batch_size(size);
+
+        BatchSpanProcessorBuilder { config, ..self }
+    }
+
+    /// Run exports on their own task instead of the batch worker task.
+    ///
+    /// Export tasks are spawned on the processor's runtime, so CPU heavy
+    /// encoding can be moved to a blocking pool by providing a runtime
+    /// spawning with e.g. `tokio::task::spawn_blocking`. At most one
+    /// export is in flight at a time, spans ended meanwhile are buffered.
+    pub fn with_offloaded_export(self, offload: bool) -> Self {
         let mut config = self.config;
-        if size > config.max_queue_size {
-            config.max_export_batch_size = config.max_queue_size;
-        } else {
-            config.max_export_batch_size = size;
-        }
+        config.offload_export = offload;
+
+        BatchSpanProcessorBuilder { config, ..self }
+    }
+
+    /// Set the delay before the first scheduled export.
+    ///
+    /// Replicas started at the same time otherwise export in lockstep. A
+    /// per-replica delay, e.g. a random fraction of the scheduled delay,
+    /// spreads their exports over time. The scheduled exports that follow
+    /// keep the offset as the interval only starts once the delay elapsed.
+    pub fn with_first_flush_delay(self, delay: Duration) -> Self {
+        let mut config = self.config;
+        config.first_flush_delay = delay;
+
+        BatchSpanProcessorBuilder { config, ..self }
+    }
+
+    /// Align scheduled exports to wall clock multiples of the scheduled delay.
+    ///
+    /// With a scheduled delay of 5 seconds exports happen at :00, :05, :10
+    /// and so on, shifted by the first flush delay if one is set. The first
+    /// export is delayed until the next such point in time.
+    pub fn with_wall_clock_alignment(self, align: bool) -> Self {
+        let mut config = self.config;
+        config.align_to_wall_clock = align;
+
+        BatchSpanProcessorBuilder { config, ..self }
+    }
+
+    /// Randomly spread each scheduled delay by up to `percentage` percent.
+    ///
+    /// Instances configured with the same scheduled delay would otherwise
+    /// export at the same moments. With a jitter of 20 percent a 5 second
+    /// scheduled delay varies between 4 and 6 seconds. Values above 100 are
+    /// treated as 100. Jittered exports are not aligned to the wall clock.
+    pub fn with_schedule_jitter(self, percentage: u8) -> Self {
+        let mut config = self.config;
+        config.schedule_jitter = percentage.min(100);
 
         BatchSpanProcessorBuilder { config, ..self }
     }
 
     /// Build a batch processor
     pub fn build(self) -> BatchSpanProcessor {
-        BatchSpanProcessor::new(
-            Box::new(self.exporter),
-            self.spawn,
-            self.interval,
-            self.delay,
-            self.config,
-        )
+        BatchSpanProcessor::new(Box::new(self.exporter), self.runtime, self.config)
+    }
+
+    /// Build `shards` batch processors sharing the exporter, with spans
+    /// spread over them by trace id.
+    ///
+    /// Every shard has its own queue of the configured max queue size and its
+    /// own worker, so spans are buffered and batched in parallel. Batches are
+    /// exported one at a time through the shared exporter. A shard count of
+    /// zero is treated as one.
+    pub fn build_sharded(self, shards: usize) -> ShardedSpanProcessor {
+        let shards = shards.max(1);
+        let exporter: Box<dyn SpanExporter> = Box::new(self.exporter);
+        let exporter = Arc::new(futures::lock::Mutex::new(exporter));
+        let remaining = Arc::new(AtomicUsize::new(shards));
+        let (runtime, config) = (self.runtime, self.config);
+
+        ShardedSpanProcessor::new((0..shards).map(|_| {
+            BatchSpanProcessor::new(
+                Box::new(SharedExporter {
+                    exporter: exporter.clone(),
+                    shards: remaining.clone(),
+                }),
+                runtime.clone(),
+                config.clone(),
+            )
+        }))
     }
 }
 
 #[cfg(all(test, feature = "testing", feature = "trace"))]
 mod tests {
     use std::fmt::Debug;
-    use std::time::Duration;
+    use std::time::{Duration, UNIX_EPOCH};
 
     use async_trait::async_trait;
 
+    use crate::runtime::{self, Runtime};
     use crate::sdk::export::trace::{stdout, ExportResult, SpanData, SpanExporter};
-    use crate::sdk::trace::BatchConfig;
-    use crate::testing::trace::{
-        new_test_export_span_data, new_test_exporter, new_tokio_test_exporter,
-    };
+    use crate::sdk::testing::{runtime::ManualRuntime, trace::InMemorySpanExporter};
+    use crate::sdk::trace::{BatchConfig, ConfigUpdate};
+    use crate::testing::trace::{new_test_export_span_data, new_test_exporter};
+    use crate::trace::{SpanContext, SpanId, TraceId, TraceState};
     use crate::util::tokio_interval_stream;
 
-    use futures::Future;
+    use futures::{channel::mpsc, future::BoxFuture, Future};
+    use std::sync::{Arc, Mutex};
+
+    use crate::baggage::BaggageExt;
+    use crate::trace::{Span as _, Tracer as _, TracerProvider as _};
+    use crate::{sdk, Context, KeyValue};
 
     use super::{
-        BatchSpanProcessor, SimpleSpanProcessor, SpanProcessor, OTEL_BSP_EXPORT_TIMEOUT,
+        export_with_timeout, BaggageSpanProcessor, BatchSpanProcessor, MirroringSpanProcessor,
+        ShardedSpanProcessor, SimpleSpanProcessor, SpanProcessor, OTEL_BSP_EXPORT_TIMEOUT,
         OTEL_BSP_MAX_EXPORT_BATCH_SIZE, OTEL_BSP_MAX_QUEUE_SIZE, OTEL_BSP_MAX_QUEUE_SIZE_DEFAULT,
         OTEL_BSP_SCHEDULE_DELAY, OTEL_BSP_SCHEDULE_DELAY_DEFAULT,
     };
@@ -578,10 +1772,38 @@ mod tests {
     fn simple_span_processor_on_end_calls_export() {
         let (exporter, rx_export, _rx_shutdown) = new_test_exporter();
         let processor = SimpleSpanProcessor::new(Box::new(exporter));
-        processor.on_end(new_test_export_span_data());
+        processor.on_end(Arc::new(new_test_export_span_data()));
         assert!(rx_export.try_recv().is_ok());
     }
 
+    #[derive(Debug)]
+    struct PanickingExporter;
+
+    #[async_trait]
+    impl SpanExporter for PanickingExporter {
+        async fn export(&mut self, _batch: Vec<SpanData>) -> ExportResult {
+            panic!("export")
+        }
+    }
+
+    #[test]
+    fn exporter_panics_are_reported_as_errors() {
+        let processor = SimpleSpanProcessor::new(Box::new(PanickingExporter));
+        processor.on_end(Arc::new(new_test_export_span_data()));
+        // the mutex is not poisoned by the panic
+        assert!(processor.exporter.lock().is_ok());
+
+        let result = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(export_with_timeout(
+                Duration::from_secs(1),
+                &mut PanickingExporter,
+                &runtime::Tokio,
+                vec![new_test_export_span_data()],
+            ));
+        assert!(result.is_err());
+    }
+
     #[test]
     fn simple_span_processor_shutdown_calls_shutdown() {
         let (exporter, _rx_export, rx_shutdown) = new_test_exporter();
@@ -590,6 +1812,201 @@ mod tests {
         assert!(rx_shutdown.try_recv().is_ok());
     }
 
+    #[test]
+    fn mirroring_span_processor_sends_ratio_to_secondary() {
+        let (primary, rx_primary, _rx_shutdown) = new_test_exporter();
+        let (secondary, rx_secondary, _rx_shutdown) = new_test_exporter();
+        let processor = MirroringSpanProcessor::new(
+            SimpleSpanProcessor::new(Box::new(primary)),
+            SimpleSpanProcessor::new(Box::new(secondary)),
+            0.5,
+        );
+
+        let mut span_data = new_test_export_span_data();
+        span_data.span_context = SpanContext::new(
+            TraceId::from_u128(0),
+            SpanId::from_u64(1),
+            0,
+            false,
+            TraceState::default(),
+        );
+        processor.on_end(Arc::new(span_data.clone()));
+        assert!(rx_primary.try_recv().is_ok());
+        assert!(rx_secondary.try_recv().is_ok());
+
+        span_data.span_context = SpanContext::new(
+            TraceId::from_u128(u128::MAX),
+            SpanId::from_u64(1),
+            0,
+            false,
+            TraceState::default(),
+        );
+        processor.on_end(Arc::new(span_data));
+        assert!(rx_primary.try_recv().is_ok());
+        assert!(rx_secondary.try_recv().is_err());
+    }
+
+    fn span_data_of_trace(trace_id: u128) -> SpanData {
+        let mut span_data = new_test_export_span_data();
+        span_data.span_context = SpanContext::new(
+            TraceId::from_u128(trace_id),
+            SpanId::from_u64(1),
+            0,
+            false,
+            TraceState::default(),
+        );
+        span_data
+    }
+
+    #[test]
+    fn sharded_span_processor_routes_by_trace_id() {
+        let (first, rx_first, _rx_shutdown) = new_test_exporter();
+        let (second, rx_second, _rx_shutdown) = new_test_exporter();
+        let processor = ShardedSpanProcessor::new(vec![
+            SimpleSpanProcessor::new(Box::new(first)),
+            SimpleSpanProcessor::new(Box::new(second)),
+        ]);
+
+        for trace_id in &[2, 4, 3] {
+            processor.on_end(Arc::new(span_data_of_trace(*trace_id)));
+        }
+        assert_eq!(rx_first.try_iter().count(), 2);
+        assert_eq!(rx_second.try_iter().count(), 1);
+
+        // spans are dropped without shards
+        ShardedSpanProcessor::new(Vec::<SimpleSpanProcessor>::new())
+            .on_end(Arc::new(span_data_of_trace(1)));
+    }
+
+    #[test]
+    fn baggage_span_processor_copies_allowed_entries() {
+        let (exporter, rx_export, _rx_shutdown) = new_test_exporter();
+        let provider = sdk::trace::TracerProvider::builder()
+            .with_span_processor(
+                BaggageSpanProcessor::new(vec!["tenant", "missing"]).with_prefix("baggage."),
+            )
+            .with_simple_exporter(exporter)
+            .build();
+        let tracer = provider.get_tracer("test", None);
+
+        let cx = Context::new().with_baggage(vec![
+            KeyValue::new("tenant", "acme"),
+            KeyValue::new("secret", "hunter2"),
+        ]);
+        tracer.start_with_context("request", cx).end();
+
+        let span_data = rx_export.try_recv().expect("span is exported");
+        let attributes = span_data
+            .attributes
+            .iter()
+            .map(|(key, value)| (key.as_str().to_string(), value.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            attributes,
+            vec![("baggage.tenant".to_string(), "acme".to_string())]
+        );
+    }
+
+    #[test]
+    fn sharded_batch_span_processor_shares_exporter() {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .enable_all()
+            .build()
+            .unwrap();
+        let _guard = runtime.enter();
+        let (exporter, rx_export, rx_shutdown) = new_test_exporter();
+        let mut processor = BatchSpanProcessor::builder(exporter, runtime::Tokio)
+            .with_scheduled_delay(Duration::from_secs(60 * 60 * 24))
+            .build_sharded(3);
+
+        for trace_id in 0..6 {
+            processor.on_end(Arc::new(span_data_of_trace(trace_id)));
+        }
+        assert!(processor.force_flush().is_ok());
+        assert_eq!(rx_export.try_iter().count(), 6);
+
+        // the shared exporter is shut down once, along with the last shard
+        assert!(processor.shutdown().is_ok());
+        assert_eq!(rx_shutdown.try_iter().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_batch_span_processor_on_current_thread_runtime() {
+        let (exporter, rx_export, rx_shutdown) = new_test_exporter();
+        let mut processor = BatchSpanProcessor::builder(exporter, runtime::TokioCurrentThread)
+            .with_scheduled_delay(Duration::from_secs(60 * 60 * 24))
+            .with_offloaded_export(true)
+            .build();
+
+        processor.on_end(Arc::new(new_test_export_span_data()));
+        assert!(processor.force_flush().is_ok());
+        assert_eq!(rx_export.try_iter().count(), 1);
+
+        processor.on_end(Arc::new(new_test_export_span_data()));
+        assert!(processor.shutdown().is_ok());
+        assert_eq!(rx_export.try_iter().count(), 1);
+        assert_eq!(rx_shutdown.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_batch_span_processor_with_own_thread() {
+        let (exporter, rx_export, rx_shutdown) = new_test_exporter();
+        let config = BatchConfig::default()
+            .with_scheduled_delay(Duration::from_millis(10))
+            .with_max_export_batch_size(2);
+        let mut processor = BatchSpanProcessor::with_own_thread(exporter, config);
+
+        // exported by a scheduled flush
+        processor.on_end(Arc::new(new_test_export_span_data()));
+        assert!(rx_export.recv_timeout(Duration::from_secs(5)).is_ok());
+
+        for _ in 0..3 {
+            processor.on_end(Arc::new(new_test_export_span_data()));
+        }
+        assert!(processor.shutdown().is_ok());
+        assert_eq!(rx_export.try_iter().count(), 3);
+        assert_eq!(rx_shutdown.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_batch_span_processor_drops_spans_when_queue_is_full() {
+        let (exporter, rx_export, _rx_shutdown) = new_test_exporter();
+        let config = BatchConfig::default()
+            .with_scheduled_delay(Duration::from_secs(60 * 60 * 24))
+            .with_max_queue_size(2);
+        let processor = BatchSpanProcessor::with_own_thread(exporter, config);
+
+        for _ in 0..3 {
+            processor.on_end(Arc::new(new_test_export_span_data()));
+        }
+        assert!(processor.force_flush().is_ok());
+        assert_eq!(rx_export.try_iter().count(), 2);
+
+        // flushing frees the queue
+        processor.on_end(Arc::new(new_test_export_span_data()));
+        assert!(processor.force_flush().is_ok());
+        assert_eq!(rx_export.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_batch_span_processor_with_own_thread_times_out_exports() {
+        let exporter = BlockingExporter {
+            delay_for: Duration::from_secs(60),
+            delay_fn: |_| futures::future::pending::<()>(),
+        };
+        let config = BatchConfig::default()
+            .with_scheduled_delay(Duration::from_secs(60 * 60 * 24))
+            .with_max_timeout(Duration::from_millis(10));
+        let processor = BatchSpanProcessor::with_own_thread(exporter, config);
+
+        processor.on_end(Arc::new(new_test_export_span_data()));
+        assert!(matches!(
+            processor.force_flush(),
+            Err(crate::trace::TraceError::ExportTimedOut(_))
+        ));
+    }
+
     #[test]
     fn test_build_batch_span_processor_builder() {
         std::env::set_var(OTEL_BSP_MAX_EXPORT_BATCH_SIZE, "500");
@@ -598,9 +2015,7 @@ mod tests {
 
         let mut builder = BatchSpanProcessor::builder(
             stdout::Exporter::new(std::io::stdout(), true),
-            tokio::spawn,
-            tokio::time::sleep,
-            tokio_interval_stream,
+            runtime::Tokio,
         );
         // export batch size cannot exceed max queue size
         assert_eq!(builder.config.max_export_batch_size, 500);
@@ -620,50 +2035,208 @@ mod tests {
         std::env::set_var(OTEL_BSP_MAX_QUEUE_SIZE, "120");
         builder = BatchSpanProcessor::builder(
             stdout::Exporter::new(std::io::stdout(), true),
-            tokio::spawn,
-            tokio::time::sleep,
-            tokio_interval_stream,
+            runtime::Tokio,
         );
 
         assert_eq!(builder.config.max_export_batch_size, 120);
         assert_eq!(builder.config.max_queue_size, 120);
     }
 
-    #[tokio::test]
-    async fn test_batch_span_processor() {
-        let (exporter, mut export_receiver, _shutdown_receiver) = new_tokio_test_exporter();
+    #[test]
+    fn test_first_flush_delay() {
+        let now = UNIX_EPOCH + Duration::from_millis(12_300);
         let config = BatchConfig {
-            scheduled_delay: Duration::from_secs(60 * 60 * 24), // set the tick to 24 hours so we know the span must be exported via force_flush
+            scheduled_delay: Duration::from_secs(5),
             ..Default::default()
         };
-        let spawn = |fut| tokio::task::spawn_blocking(|| futures::executor::block_on(fut));
-        let mut processor = BatchSpanProcessor::new(
-            Box::new(exporter),
-            spawn,
-            tokio_interval_stream,
-            tokio::time::sleep,
-            config,
+        assert_eq!(config.first_flush_delay(now), Duration::from_secs(0));
+
+        let config = BatchConfig {
+            first_flush_delay: Duration::from_millis(1_500),
+            ..config
+        };
+        assert_eq!(config.first_flush_delay(now), Duration::from_millis(1_500));
+
+        // next multiple of 5s is 15s
+        let config = BatchConfig {
+            first_flush_delay: Duration::from_secs(0),
+            align_to_wall_clock: true,
+            ..config
+        };
+        assert_eq!(config.first_flush_delay(now), Duration::from_millis(2_700));
+
+        // shifted by the first flush delay, next flush at 16.5s
+        let config = BatchConfig {
+            first_flush_delay: Duration::from_millis(1_500),
+            ..config
+        };
+        assert_eq!(config.first_flush_delay(now), Duration::from_millis(4_200));
+
+        let aligned = UNIX_EPOCH + Duration::from_secs(15);
+        assert_eq!(
+            config.first_flush_delay(aligned),
+            Duration::from_millis(1_500)
         );
-        let handle = tokio::spawn(async move {
-            loop {
-                if let Some(span) = export_receiver.recv().await {
-                    assert_eq!(span.span_context, new_test_export_span_data().span_context);
-                    break;
-                }
-            }
-        });
-        tokio::time::sleep(Duration::from_secs(1)).await; // skip the first
-        processor.on_end(new_test_export_span_data());
+    }
+
+    #[test]
+    fn test_batch_span_processor() {
+        let runtime = ManualRuntime::new();
+        let exporter = InMemorySpanExporter::default();
+        let config = BatchConfig {
+            scheduled_delay: Duration::from_secs(60 * 60 * 24), // set the tick to 24 hours so we know the span must be exported via force_flush
+            ..Default::default()
+        };
+        let mut processor = BatchSpanProcessor::new(Box::new(exporter.clone()), runtime, config);
+        processor.on_end(Arc::new(new_test_export_span_data()));
         let flush_res = processor.force_flush();
         assert!(flush_res.is_ok());
-        let _shutdown_result = processor.shutdown();
 
-        assert!(
-            tokio::time::timeout(Duration::from_secs(5), handle)
-                .await
-                .is_ok(),
-            "timed out in 5 seconds. force_flush may not export any data when called"
+        let spans = exporter.get_finished_spans().unwrap();
+        assert_eq!(spans.len(), 1, "force_flush must export the buffered span");
+        assert_eq!(
+            spans[0].span_context,
+            new_test_export_span_data().span_context
         );
+        assert!(processor.shutdown().is_ok());
+    }
+
+    #[test]
+    fn test_batch_span_processor_exports_on_schedule() {
+        let runtime = ManualRuntime::new();
+        let exporter = InMemorySpanExporter::default();
+        let config = BatchConfig {
+            scheduled_delay: Duration::from_secs(5),
+            ..Default::default()
+        };
+        let mut processor =
+            BatchSpanProcessor::new(Box::new(exporter.clone()), runtime.clone(), config);
+        processor.on_end(Arc::new(new_test_export_span_data()));
+        runtime.wait_until_idle();
+        assert_eq!(runtime.scheduled(), vec![Duration::from_secs(5)]);
+
+        runtime.advance(Duration::from_secs(4));
+        assert!(exporter.get_finished_spans().unwrap().is_empty());
+        runtime.advance(Duration::from_secs(1));
+        assert_eq!(exporter.get_finished_spans().unwrap().len(), 1);
+        assert_eq!(runtime.scheduled(), vec![Duration::from_secs(10)]);
+
+        assert!(processor.shutdown().is_ok());
+    }
+
+    #[test]
+    fn test_batch_span_processor_updates_scheduled_delay() {
+        let runtime = ManualRuntime::new();
+        let exporter = InMemorySpanExporter::default();
+        let config = BatchConfig {
+            scheduled_delay: Duration::from_secs(5),
+            ..Default::default()
+        };
+        let mut processor =
+            BatchSpanProcessor::new(Box::new(exporter.clone()), runtime.clone(), config);
+        processor.on_end(Arc::new(new_test_export_span_data()));
+        runtime.wait_until_idle();
+
+        // the export scheduled with the previous delay still happens
+        processor.update_config(&ConfigUpdate::new().with_scheduled_delay(Duration::from_secs(2)));
+        runtime.advance(Duration::from_secs(5));
+        assert_eq!(exporter.get_finished_spans().unwrap().len(), 1);
+        assert_eq!(runtime.scheduled(), vec![Duration::from_secs(7)]);
+
+        processor.on_end(Arc::new(new_test_export_span_data()));
+        runtime.advance(Duration::from_secs(2));
+        assert_eq!(exporter.get_finished_spans().unwrap().len(), 2);
+        assert_eq!(runtime.scheduled(), vec![Duration::from_secs(9)]);
+
+        assert!(processor.shutdown().is_ok());
+    }
+
+    #[test]
+    fn test_batch_span_processor_with_schedule_jitter() {
+        let runtime = ManualRuntime::new();
+        let exporter = InMemorySpanExporter::default();
+        let config = BatchConfig {
+            scheduled_delay: Duration::from_secs(10),
+            schedule_jitter: 50,
+            ..Default::default()
+        };
+        let mut processor =
+            BatchSpanProcessor::new(Box::new(exporter.clone()), runtime.clone(), config);
+        processor.on_end(Arc::new(new_test_export_span_data()));
+        runtime.wait_until_idle();
+
+        let scheduled = runtime.scheduled();
+        assert_eq!(scheduled.len(), 1);
+        assert!(scheduled[0] >= Duration::from_secs(5) && scheduled[0] <= Duration::from_secs(15));
+
+        // exported by a scheduled flush, not on shutdown
+        runtime.advance(Duration::from_secs(15));
+        assert_eq!(exporter.get_finished_spans().unwrap().len(), 1);
+        assert!(processor.shutdown().is_ok());
+    }
+
+    /// Runs tasks on the blocking pool, so the worker keeps running while the
+    /// current thread runtime of a test is blocked in `force_flush`.
+    #[derive(Clone, Debug)]
+    struct SpawnBlocking;
+
+    impl Runtime for SpawnBlocking {
+        type Interval = tokio_stream::wrappers::IntervalStream;
+        type Delay = tokio::time::Sleep;
+
+        fn interval(&self, duration: Duration) -> Self::Interval {
+            tokio_interval_stream(duration)
+        }
+
+        fn spawn(&self, future: BoxFuture<'static, ()>) {
+            tokio::task::spawn_blocking(|| futures::executor::block_on(future));
+        }
+
+        fn delay(&self, duration: Duration) -> Self::Delay {
+            tokio::time::sleep(duration)
+        }
+    }
+
+    /// Runs tasks on a tokio runtime the test can replace.
+    #[derive(Clone, Debug)]
+    struct SpawnOn(Arc<Mutex<tokio::runtime::Runtime>>);
+
+    impl Runtime for SpawnOn {
+        type Interval = tokio_stream::wrappers::IntervalStream;
+        type Delay = tokio::time::Sleep;
+
+        fn interval(&self, duration: Duration) -> Self::Interval {
+            tokio_interval_stream(duration)
+        }
+
+        fn spawn(&self, future: BoxFuture<'static, ()>) {
+            self.0.lock().unwrap().spawn(future);
+        }
+
+        fn delay(&self, duration: Duration) -> Self::Delay {
+            tokio::time::sleep(duration)
+        }
+    }
+
+    /// Ticks when the test sends a tick instead of on a schedule.
+    #[derive(Clone, Debug)]
+    struct ManualTicks(Arc<Mutex<Option<mpsc::UnboundedReceiver<()>>>>);
+
+    impl Runtime for ManualTicks {
+        type Interval = mpsc::UnboundedReceiver<()>;
+        type Delay = tokio::time::Sleep;
+
+        fn interval(&self, _duration: Duration) -> Self::Interval {
+            self.0.lock().unwrap().take().unwrap()
+        }
+
+        fn spawn(&self, future: BoxFuture<'static, ()>) {
+            tokio::spawn(future);
+        }
+
+        fn delay(&self, duration: Duration) -> Self::Delay {
+            tokio::time::sleep(duration)
+        }
     }
 
     struct BlockingExporter<D> {
@@ -726,6 +2299,138 @@ mod tests {
         async_std::task::block_on(timeout_test_std_async(false));
     }
 
+    // Blocks the thread it exports on until released, standing in for CPU
+    // heavy encoding of a batch.
+    #[derive(Debug)]
+    struct GatedExporter {
+        started: std::sync::mpsc::Sender<usize>,
+        release: std::sync::mpsc::Receiver<()>,
+    }
+
+    #[async_trait]
+    impl SpanExporter for GatedExporter {
+        async fn export(&mut self, batch: Vec<SpanData>) -> ExportResult {
+            self.started.send(batch.len()).unwrap();
+            self.release.recv().unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_offloaded_export_keeps_receiving_spans() {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .enable_all()
+            .build()
+            .unwrap();
+        let _guard = runtime.enter();
+        let (started_sender, started) = std::sync::mpsc::channel();
+        let (release, release_receiver) = std::sync::mpsc::channel();
+        let exporter = GatedExporter {
+            started: started_sender,
+            release: release_receiver,
+        };
+        let config = BatchConfig {
+            scheduled_delay: Duration::from_millis(10),
+            offload_export: true,
+            ..Default::default()
+        };
+        let mut processor = BatchSpanProcessor::new(Box::new(exporter), runtime::Tokio, config);
+
+        processor.on_end(Arc::new(new_test_export_span_data()));
+        let timeout = Duration::from_secs(5);
+        assert_eq!(started.recv_timeout(timeout), Ok(1));
+
+        // the first export is still blocked, spans ended now are buffered by the worker
+        for _ in 0..3 {
+            processor.on_end(Arc::new(new_test_export_span_data()));
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        release.send(()).unwrap();
+        assert_eq!(started.recv_timeout(timeout), Ok(3));
+        release.send(()).unwrap();
+
+        assert!(processor.shutdown().is_ok());
+    }
+
+    #[test]
+    fn test_batch_span_processor_restarts_stopped_worker() {
+        let new_runtime = || {
+            tokio::runtime::Builder::new_multi_thread()
+                .worker_threads(1)
+                .enable_all()
+                .build()
+                .unwrap()
+        };
+        let runtime = Arc::new(Mutex::new(new_runtime()));
+        let (exporter, rx_export, _rx_shutdown) = new_test_exporter();
+        let mut processor = BatchSpanProcessor::new(
+            Box::new(exporter),
+            SpawnOn(runtime.clone()),
+            BatchConfig::default(),
+        );
+
+        // shutting down the runtime drops the worker task
+        let stopped = std::mem::replace(&mut *runtime.lock().unwrap(), new_runtime());
+        stopped.shutdown_timeout(Duration::from_secs(1));
+
+        processor.on_end(Arc::new(new_test_export_span_data()));
+        assert!(processor.force_flush().is_ok());
+        assert!(rx_export.try_recv().is_ok());
+        assert!(processor.shutdown().is_ok());
+    }
+
+    #[test]
+    fn test_batch_span_processor_coalesces_queued_ticks() {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .enable_all()
+            .build()
+            .unwrap();
+        let _guard = runtime.enter();
+        let (started_sender, started) = std::sync::mpsc::channel();
+        let (release, release_receiver) = std::sync::mpsc::channel();
+        let exporter = GatedExporter {
+            started: started_sender,
+            release: release_receiver,
+        };
+        // ticks are sent by hand to simulate ticks queued up during a slow export
+        let (tick_sender, tick_receiver) = mpsc::unbounded::<()>();
+        let mut processor = BatchSpanProcessor::new(
+            Box::new(exporter),
+            ManualTicks(Arc::new(Mutex::new(Some(tick_receiver)))),
+            BatchConfig::default(),
+        );
+
+        processor.on_end(Arc::new(new_test_export_span_data()));
+        // the tick may overtake the span, keep ticking until the export starts
+        let mut first_export = None;
+        for _ in 0..100 {
+            tick_sender.unbounded_send(()).unwrap();
+            if let Ok(batch_size) = started.recv_timeout(Duration::from_millis(50)) {
+                first_export = Some(batch_size);
+                break;
+            }
+        }
+        assert_eq!(first_export, Some(1));
+
+        // the worker is stuck in the slow export while spans and ticks queue up
+        for _ in 0..3 {
+            processor.on_end(Arc::new(new_test_export_span_data()));
+            tick_sender.unbounded_send(()).unwrap();
+        }
+        for _ in 0..3 {
+            release.send(()).unwrap();
+        }
+        std::thread::sleep(Duration::from_millis(200));
+        assert!(
+            started.try_iter().count() <= 1,
+            "queued ticks must not trigger back to back exports"
+        );
+
+        assert!(processor.shutdown().is_ok());
+    }
+
     // If the time_out is true, then the result suppose to ended with timeout.
     // otherwise the exporter should be able to export within time out duration.
     #[cfg(feature = "rt-async-std")]
@@ -739,14 +2444,8 @@ mod tests {
             delay_for: Duration::from_millis(if !time_out { 5 } else { 60 }),
             delay_fn: async_std::task::sleep,
         };
-        let mut processor = BatchSpanProcessor::new(
-            Box::new(exporter),
-            async_std::task::spawn,
-            async_std::stream::interval,
-            async_std::task::sleep,
-            config,
-        );
-        processor.on_end(new_test_export_span_data());
+        let mut processor = BatchSpanProcessor::new(Box::new(exporter), runtime::AsyncStd, config);
+        processor.on_end(Arc::new(new_test_export_span_data()));
         let flush_res = processor.force_flush();
         if time_out {
             assert!(flush_res.is_err());
@@ -769,16 +2468,9 @@ mod tests {
             delay_for: Duration::from_millis(if !time_out { 5 } else { 60 }),
             delay_fn: tokio::time::sleep,
         };
-        let spawn = |fut| tokio::task::spawn_blocking(|| futures::executor::block_on(fut));
-        let mut processor = BatchSpanProcessor::new(
-            Box::new(exporter),
-            spawn,
-            tokio_interval_stream,
-            tokio::time::sleep,
-            config,
-        );
+        let mut processor = BatchSpanProcessor::new(Box::new(exporter), SpawnBlocking, config);
         tokio::time::sleep(Duration::from_secs(1)).await; // skip the first
-        processor.on_end(new_test_export_span_data());
+        processor.on_end(Arc::new(new_test_export_span_data()));
         let flush_res = processor.force_flush();
         if time_out {
             assert!(flush_res.is_err());
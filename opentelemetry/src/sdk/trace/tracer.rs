@@ -11,7 +11,7 @@ use crate::sdk::{
     trace::{
         provider::{TracerProvider, TracerProviderInner},
         span::{Span, SpanData},
-        EvictedHashMap, EvictedQueue, SamplingDecision, SamplingResult,
+        EvictedHashMap, EvictedQueue, SamplingDecision, SamplingResult, ShouldSample,
     },
     InstrumentationLibrary,
 };
@@ -19,14 +19,15 @@ use crate::trace::{
     Link, SpanBuilder, SpanContext, SpanId, SpanKind, StatusCode, TraceContextExt, TraceId,
     TraceState, TRACE_FLAG_SAMPLED,
 };
-use crate::{Context, KeyValue};
+use crate::{global, Context, KeyValue};
+use std::borrow::Cow;
 use std::fmt;
-use std::sync::Weak;
+use std::sync::{Arc, Weak};
 
 /// `Tracer` implementation to create and manage spans
 #[derive(Clone)]
 pub struct Tracer {
-    instrumentation_lib: InstrumentationLibrary,
+    instrumentation_lib: Arc<InstrumentationLibrary>,
     provider: Weak<TracerProviderInner>,
 }
 
@@ -48,7 +49,7 @@ impl Tracer {
         provider: Weak<TracerProviderInner>,
     ) -> Self {
         Tracer {
-            instrumentation_lib,
+            instrumentation_lib: Arc::new(instrumentation_lib),
             provider,
         }
     }
@@ -67,55 +68,40 @@ impl Tracer {
     #[allow(clippy::too_many_arguments)]
     fn make_sampling_decision(
         &self,
+        sampler: &dyn ShouldSample,
         parent_cx: &Context,
         trace_id: TraceId,
         name: &str,
         span_kind: &SpanKind,
         attributes: &[KeyValue],
         links: &[Link],
-    ) -> Option<(u8, Vec<KeyValue>, TraceState)> {
-        let provider = self.provider()?;
-        let sampler = &provider.config().default_sampler;
-
-        let sampling_result = sampler.should_sample(
+    ) -> SamplingResult {
+        sampler.should_sample(
             Some(parent_cx),
             trace_id,
             name,
             span_kind,
             attributes,
             links,
-        );
-
-        self.process_sampling_result(sampling_result, parent_cx)
+        )
     }
 
     fn process_sampling_result(
         &self,
-        sampling_result: SamplingResult,
+        sampling_result: &SamplingResult,
         parent_cx: &Context,
     ) -> Option<(u8, Vec<KeyValue>, TraceState)> {
-        match sampling_result {
-            SamplingResult {
-                decision: SamplingDecision::Drop,
-                ..
-            } => None,
-            SamplingResult {
-                decision: SamplingDecision::RecordOnly,
-                attributes,
-                trace_state,
-            } => {
-                let trace_flags = parent_cx.span().span_context().trace_flags();
-                Some((trace_flags & !TRACE_FLAG_SAMPLED, attributes, trace_state))
-            }
-            SamplingResult {
-                decision: SamplingDecision::RecordAndSample,
-                attributes,
-                trace_state,
-            } => {
-                let trace_flags = parent_cx.span().span_context().trace_flags();
-                Some((trace_flags | TRACE_FLAG_SAMPLED, attributes, trace_state))
-            }
-        }
+        let trace_flags = parent_cx.span().span_context().trace_flags();
+        let trace_flags = match sampling_result.decision {
+            SamplingDecision::Drop => return None,
+            SamplingDecision::RecordOnly => trace_flags & !TRACE_FLAG_SAMPLED,
+            SamplingDecision::RecordAndSample => trace_flags | TRACE_FLAG_SAMPLED,
+        };
+        Some((
+            trace_flags,
+            sampling_result.attributes.clone(),
+            sampling_result.trace_state.clone(),
+        ))
     }
 }
 
@@ -136,7 +122,10 @@ impl crate::trace::Tracer for Tracer {
     /// trace. A span is said to be a _root span_ if it does not have a parent. Each
     /// trace includes a single root span, which is the shared ancestor of all other
     /// spans in the trace.
-    fn start_with_context(&self, name: &str, cx: Context) -> Self::Span {
+    fn start_with_context<T>(&self, name: T, cx: Context) -> Self::Span
+    where
+        T: Into<Cow<'static, str>>,
+    {
         let mut builder = self.span_builder(name);
         builder.parent_context = Some(cx);
 
@@ -146,8 +135,11 @@ impl crate::trace::Tracer for Tracer {
     /// Creates a span builder
     ///
     /// An ergonomic way for attributes to be configured before the `Span` is started.
-    fn span_builder(&self, name: &str) -> SpanBuilder {
-        SpanBuilder::from_name(name.to_string())
+    fn span_builder<T>(&self, name: T) -> SpanBuilder
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        SpanBuilder::from_name(name)
     }
 
     /// Starts a span from a `SpanBuilder`.
@@ -158,20 +150,40 @@ impl crate::trace::Tracer for Tracer {
     /// trace includes a single root span, which is the shared ancestor of all other
     /// spans in the trace.
     fn build(&self, mut builder: SpanBuilder) -> Self::Span {
-        let provider = self.provider();
-        if provider.is_none() {
-            return Span::new(SpanContext::empty_context(), None, self.clone());
+        let provider = match self.provider() {
+            Some(provider) => provider,
+            None => return Span::new(SpanContext::empty_context(), None, self.clone()),
+        };
+        let config = provider.config();
+
+        if !global::is_telemetry_enabled()
+            || config
+                .disabled_libraries
+                .contains(self.instrumentation_lib.name)
+        {
+            // keep propagating the parent's context without recording anything
+            let cx = builder
+                .parent_context
+                .take()
+                .unwrap_or_else(Context::current);
+            let span_context = if cx.has_active_span() {
+                cx.span().span_context().clone()
+            } else {
+                cx.remote_span_context()
+                    .cloned()
+                    .unwrap_or_else(SpanContext::empty_context)
+            };
+            return Span::new(span_context, None, self.clone());
         }
 
-        let provider = provider.unwrap();
-        let config = provider.config();
+        let live_config = provider.live_config();
         let span_id = builder
             .span_id
             .take()
             .unwrap_or_else(|| config.id_generator.new_span_id());
 
         let span_kind = builder.span_kind.take().unwrap_or(SpanKind::Internal);
-        let mut attribute_options = builder.attributes.take().unwrap_or_else(Vec::new);
+        let mut attribute_options = builder.attributes.take().unwrap_or_default();
         let mut link_options = builder.links.take();
         let mut flags = 0;
         let mut span_trace_state = Default::default();
@@ -198,37 +210,32 @@ impl crate::trace::Tracer for Tracer {
             None
         };
         // Build context for sampling decision
-        let (no_parent, trace_id, parent_span_id, remote_parent, parent_trace_flags) =
-            parent_span_context
-                .as_ref()
-                .map(|ctx| {
-                    (
-                        false,
-                        ctx.trace_id(),
-                        ctx.span_id(),
-                        ctx.is_remote(),
-                        ctx.trace_flags(),
-                    )
-                })
-                .unwrap_or((
-                    true,
-                    builder
-                        .trace_id
-                        .unwrap_or_else(|| config.id_generator.new_trace_id()),
-                    SpanId::invalid(),
-                    false,
-                    0,
-                ));
+        let (no_parent, trace_id, parent_span_id, remote_parent) = parent_span_context
+            .as_ref()
+            .map(|ctx| (false, ctx.trace_id(), ctx.span_id(), ctx.is_remote()))
+            .unwrap_or((
+                true,
+                builder
+                    .trace_id
+                    .unwrap_or_else(|| config.id_generator.new_trace_id()),
+                SpanId::invalid(),
+                false,
+            ));
 
         // There are 3 paths for sampling.
         //
         // * Sampling has occurred elsewhere and is already stored in the builder
         // * There is no parent or a remote parent, in which case make decision now
         // * There is a local parent, in which case defer to the parent's decision
-        let sampling_decision = if let Some(sampling_result) = builder.sampling_result.take() {
-            self.process_sampling_result(sampling_result, &parent_cx)
+        let sampling_result = if let Some(sampling_result) = builder.sampling_result.take() {
+            sampling_result
         } else if no_parent || remote_parent {
+            let sampler = match &live_config.sampler {
+                Some(sampler) => sampler.as_ref(),
+                None => config.default_sampler.as_ref(),
+            };
             self.make_sampling_decision(
+                sampler,
                 &parent_cx,
                 trace_id,
                 &builder.name,
@@ -238,46 +245,78 @@ impl crate::trace::Tracer for Tracer {
             )
         } else {
             // has parent that is local: use parent if sampled, or don't record.
-            parent_span_context
-                .filter(|span_context| span_context.is_sampled())
-                .map(|span_context| {
-                    (
-                        parent_trace_flags,
-                        Vec::new(),
-                        span_context.trace_state().clone(),
-                    )
-                })
+            let parent_span_context = parent_cx.span().span_context();
+            SamplingResult {
+                decision: if parent_span_context.is_sampled() {
+                    SamplingDecision::RecordAndSample
+                } else {
+                    SamplingDecision::Drop
+                },
+                attributes: Vec::new(),
+                trace_state: parent_span_context.trace_state().clone(),
+            }
         };
+        let sampling_decision = self.process_sampling_result(&sampling_result, &parent_cx);
 
         // Build optional inner context, `None` if not recording.
         let inner = sampling_decision.map(|(trace_flags, mut extra_attrs, trace_state)| {
             flags = trace_flags;
             span_trace_state = trace_state;
             attribute_options.append(&mut extra_attrs);
-            let mut attributes =
-                EvictedHashMap::new(config.max_attributes_per_span, attribute_options.len());
-            for attribute in attribute_options {
+            let span_pool = provider.span_pool();
+            let mut attributes = match span_pool {
+                Some(pool) => EvictedHashMap::with_recycler(
+                    live_config.max_attributes_per_span,
+                    attribute_options.len(),
+                    pool.attributes.clone(),
+                ),
+                None => EvictedHashMap::new(
+                    live_config.max_attributes_per_span,
+                    attribute_options.len(),
+                ),
+            };
+            for mut attribute in attribute_options {
+                if let Some(guard) = &config.cardinality_guard {
+                    attribute.value = guard.check(&attribute.key, attribute.value);
+                }
+                if let Some(interner) = &config.string_interner {
+                    attribute.value = interner.intern_value(attribute.value);
+                }
                 attributes.insert(attribute);
             }
-            let mut links = EvictedQueue::new(config.max_links_per_span);
+            let mut links = EvictedQueue::new(live_config.max_links_per_span);
+            if let Some(pool) = span_pool {
+                links.set_recycler(pool.links.clone());
+            }
             if let Some(link_options) = &mut link_options {
                 links.append_vec(link_options);
             }
-            let start_time = builder.start_time.unwrap_or_else(crate::time::now);
+            let (start_time, start_instant) = match builder.start_time {
+                Some(start_time) => (start_time, None),
+                None => (config.clock.now(), config.clock.instant()),
+            };
             let end_time = builder.end_time.unwrap_or(start_time);
-            let mut message_events = EvictedQueue::new(config.max_events_per_span);
+            let mut message_events = EvictedQueue::new(live_config.max_events_per_span);
+            if let Some(pool) = span_pool {
+                message_events.set_recycler(pool.events.clone());
+            }
             if let Some(mut events) = builder.message_events {
                 message_events.append_vec(&mut events);
             }
             let status_code = builder.status_code.unwrap_or(StatusCode::Unset);
-            let status_message = builder.status_message.unwrap_or_else(String::new);
-            let resource = config.resource.clone();
+            // only errors have a description
+            let status_message = match status_code {
+                StatusCode::Error => builder.status_message.unwrap_or_default(),
+                _ => String::new(),
+            };
+            let resource = live_config.resource.clone();
 
             SpanData {
                 parent_span_id,
                 span_kind,
-                name: builder.name.into(),
+                name: builder.name,
                 start_time,
+                start_instant,
                 end_time,
                 attributes,
                 message_events,
@@ -293,7 +332,7 @@ impl crate::trace::Tracer for Tracer {
 
         // Call `on_start` for all processors
         for processor in provider.span_processors() {
-            processor.on_start(&span, &parent_cx)
+            processor.on_start_with_sampling(&span, &parent_cx, &sampling_result)
         }
 
         span
@@ -305,15 +344,22 @@ mod tests {
     use crate::{
         sdk::{
             self,
-            trace::{Config, Sampler, SamplingDecision, SamplingResult, ShouldSample},
+            export::trace::SpanData,
+            trace::{
+                Config, Sampler, SamplingDecision, SamplingResult, ShouldSample, SpanProcessor,
+            },
+            Clock,
         },
-        testing::trace::TestSpan,
+        testing::trace::{new_test_exporter, TestSpan},
         trace::{
             Link, Span, SpanBuilder, SpanContext, SpanId, SpanKind, TraceContextExt, TraceId,
             TraceState, Tracer, TracerProvider, TRACE_FLAG_NOT_SAMPLED, TRACE_FLAG_SAMPLED,
         },
         Context, KeyValue,
     };
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant, SystemTime};
 
     #[derive(Debug)]
     struct TestSampler {}
@@ -411,4 +457,164 @@ mod tests {
 
         assert!(!span.span_context().is_sampled());
     }
+
+    #[test]
+    fn disabled_libraries_create_non_recording_spans() {
+        let config = Config::default().with_disabled_libraries(vec!["noisy_dep"]);
+        let tracer_provider = sdk::trace::TracerProvider::builder()
+            .with_config(config)
+            .build();
+
+        let parent = tracer_provider.get_tracer("app", None).start("parent");
+        assert!(parent.is_recording());
+
+        let cx = Context::current_with_span(parent);
+        let span = tracer_provider
+            .get_tracer("noisy_dep", None)
+            .start_with_context("muted", cx.clone());
+        assert!(!span.is_recording());
+        assert_eq!(span.span_context(), cx.span().span_context());
+    }
+
+    #[derive(Debug, Default)]
+    struct DecisionCollector(Arc<Mutex<Vec<(TraceId, SamplingDecision)>>>);
+
+    impl SpanProcessor for DecisionCollector {
+        fn on_start(&self, _span: &sdk::trace::Span, _cx: &Context) {
+            unreachable!("on_start_with_sampling is overridden")
+        }
+
+        fn on_start_with_sampling(
+            &self,
+            span: &sdk::trace::Span,
+            _cx: &Context,
+            sampling_result: &SamplingResult,
+        ) {
+            self.0.lock().unwrap().push((
+                span.span_context().trace_id(),
+                sampling_result.decision.clone(),
+            ));
+        }
+
+        fn on_end(&self, _span: Arc<SpanData>) {}
+
+        fn force_flush(&self) -> crate::trace::TraceResult<()> {
+            Ok(())
+        }
+
+        fn shutdown(&mut self) -> crate::trace::TraceResult<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn span_processors_observe_sampling_results() {
+        let collector = DecisionCollector::default();
+        let decisions = collector.0.clone();
+        let config = Config::default().with_default_sampler(Sampler::ParentBased(Box::new(
+            Sampler::TraceIdRatioBased(0.5),
+        )));
+        let tracer_provider = sdk::trace::TracerProvider::builder()
+            .with_config(config)
+            .with_span_processor(collector)
+            .build();
+        let tracer = tracer_provider.get_tracer("test", None);
+
+        let sampled = tracer
+            .span_builder("sampled")
+            .with_trace_id(TraceId::from_u128(1))
+            .start(&tracer);
+        let dropped = tracer
+            .span_builder("dropped")
+            .with_trace_id(TraceId::from_u128(u128::MAX))
+            .start(&tracer);
+        tracer.start_with_context("child", Context::current_with_span(sampled));
+        tracer.start_with_context("dropped child", Context::current_with_span(dropped));
+
+        assert_eq!(
+            *decisions.lock().unwrap(),
+            vec![
+                (TraceId::from_u128(1), SamplingDecision::RecordAndSample),
+                (TraceId::from_u128(u128::MAX), SamplingDecision::Drop),
+                (TraceId::from_u128(1), SamplingDecision::RecordAndSample),
+                (TraceId::from_u128(u128::MAX), SamplingDecision::Drop),
+            ]
+        );
+    }
+
+    /// A clock advancing by a second every time it is read.
+    #[derive(Debug, Default)]
+    struct SteppingClock(AtomicU64);
+
+    impl Clock for SteppingClock {
+        fn now(&self) -> SystemTime {
+            SystemTime::UNIX_EPOCH + Duration::from_secs(self.0.fetch_add(1, Ordering::SeqCst))
+        }
+    }
+
+    #[test]
+    fn spans_read_timestamps_from_the_clock() {
+        let (exporter, rx_export, _rx_shutdown) = new_test_exporter();
+        let tracer_provider = sdk::trace::TracerProvider::builder()
+            .with_config(Config::default().with_clock(SteppingClock::default()))
+            .with_simple_exporter(exporter)
+            .build();
+        let tracer = tracer_provider.get_tracer("test", None);
+
+        let span = tracer.start("clocked");
+        span.add_event("event".to_string(), vec![]);
+        span.end();
+        let explicit = tracer
+            .span_builder("explicit")
+            .with_start_time(SystemTime::UNIX_EPOCH + Duration::from_secs(100))
+            .start(&tracer);
+        explicit.end_with_timestamp(SystemTime::UNIX_EPOCH + Duration::from_secs(200));
+
+        let at = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        let clocked = rx_export.recv().unwrap();
+        assert_eq!(clocked.start_time, at(0));
+        assert_eq!(
+            clocked.message_events.iter().next().unwrap().timestamp,
+            at(1)
+        );
+        assert_eq!(clocked.end_time, at(2));
+        let explicit = rx_export.recv().unwrap();
+        assert_eq!(explicit.start_time, at(100));
+        assert_eq!(explicit.end_time, at(200));
+    }
+
+    /// A clock whose time goes back by an hour every time it is read, with
+    /// monotonic readings.
+    #[derive(Debug, Default)]
+    struct AdjustedClock(AtomicU64);
+
+    impl Clock for AdjustedClock {
+        fn now(&self) -> SystemTime {
+            let adjustments = self.0.fetch_add(1, Ordering::SeqCst);
+            SystemTime::UNIX_EPOCH + Duration::from_secs(86_400 - adjustments * 3_600)
+        }
+
+        fn instant(&self) -> Option<Instant> {
+            Some(Instant::now())
+        }
+    }
+
+    #[test]
+    fn span_durations_are_measured_with_monotonic_readings() {
+        let (exporter, rx_export, _rx_shutdown) = new_test_exporter();
+        let tracer_provider = sdk::trace::TracerProvider::builder()
+            .with_config(Config::default().with_clock(AdjustedClock::default()))
+            .with_simple_exporter(exporter)
+            .build();
+        let tracer = tracer_provider.get_tracer("test", None);
+
+        tracer.start("ended").end();
+        drop(tracer.start("dropped"));
+
+        for _ in 0..2 {
+            let span = rx_export.recv().unwrap();
+            assert!(span.end_time >= span.start_time);
+            assert!(span.duration() < Duration::from_secs(60));
+        }
+    }
 }
@@ -43,7 +43,7 @@ pub fn new_test_export_span_data() -> SpanData {
         span_context: SpanContext::empty_context(),
         parent_span_id: SpanId::from_u64(0),
         span_kind: SpanKind::Internal,
-        name: "opentelemetry".to_string(),
+        name: "opentelemetry".into(),
         start_time: crate::time::now(),
         end_time: crate::time::now(),
         attributes: EvictedHashMap::new(config.max_attributes_per_span, 0),
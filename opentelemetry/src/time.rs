@@ -0,0 +1,134 @@
+//! # Timestamps
+//!
+//! Spans and events are timestamped with a [`SystemTime`]. With the `std`
+//! feature it is [`std::time::SystemTime`], without it targets have no system
+//! clock and the time since the [`UNIX_EPOCH`] is provided by the caller:
+//!
+//! ```
+//! use opentelemetry::time::UNIX_EPOCH;
+//! use std::time::Duration;
+//!
+//! // e.g. read from a real-time clock
+//! let since_epoch = Duration::from_secs(1_600_000_000);
+//! let timestamp = UNIX_EPOCH + since_epoch;
+//! assert_eq!(timestamp.duration_since(UNIX_EPOCH).ok(), Some(since_epoch));
+//! ```
+#[cfg(not(feature = "std"))]
+use core::{
+    fmt,
+    ops::{Add, Sub},
+    time::Duration,
+};
+#[cfg(all(feature = "serialize", not(feature = "std")))]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "std")]
+pub use std::time::{SystemTime, SystemTimeError, UNIX_EPOCH};
+
+/// A point in time, without `std` the duration since the [`UNIX_EPOCH`].
+///
+/// It mirrors the subset of [`std::time::SystemTime`] used by exporters, so
+/// code using it builds with and without the `std` feature.
+#[cfg(not(feature = "std"))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SystemTime(Duration);
+
+/// The Unix epoch, 1970-01-01 00:00:00 UTC.
+#[cfg(not(feature = "std"))]
+pub const UNIX_EPOCH: SystemTime = SystemTime::UNIX_EPOCH;
+
+#[cfg(not(feature = "std"))]
+impl SystemTime {
+    /// The Unix epoch, 1970-01-01 00:00:00 UTC.
+    pub const UNIX_EPOCH: SystemTime = SystemTime(Duration::from_secs(0));
+
+    /// Returns the duration elapsed since an earlier point in time, or an
+    /// error holding the difference if `earlier` is later than `self`.
+    pub fn duration_since(&self, earlier: SystemTime) -> Result<Duration, SystemTimeError> {
+        self.0
+            .checked_sub(earlier.0)
+            .ok_or_else(|| SystemTimeError(earlier.0 - self.0))
+    }
+
+    /// Returns the point in time `duration` after this one, if representable.
+    pub fn checked_add(&self, duration: Duration) -> Option<SystemTime> {
+        self.0.checked_add(duration).map(SystemTime)
+    }
+
+    /// Returns the point in time `duration` before this one, if representable.
+    pub fn checked_sub(&self, duration: Duration) -> Option<SystemTime> {
+        self.0.checked_sub(duration).map(SystemTime)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl Add<Duration> for SystemTime {
+    type Output = SystemTime;
+
+    fn add(self, duration: Duration) -> SystemTime {
+        self.checked_add(duration)
+            .expect("overflow when adding duration to instant")
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl Sub<Duration> for SystemTime {
+    type Output = SystemTime;
+
+    fn sub(self, duration: Duration) -> SystemTime {
+        self.checked_sub(duration)
+            .expect("overflow when subtracting duration from instant")
+    }
+}
+
+/// Error returned by [`SystemTime::duration_since`] if the earlier point in
+/// time is later.
+#[cfg(not(feature = "std"))]
+#[derive(Clone, Debug)]
+pub struct SystemTimeError(Duration);
+
+#[cfg(not(feature = "std"))]
+impl SystemTimeError {
+    /// The positive duration between the two points in time.
+    pub fn duration(&self) -> Duration {
+        self.0
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl fmt::Display for SystemTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "second time provided was later than self")
+    }
+}
+
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+pub(crate) fn now() -> SystemTime {
+    SystemTime::now()
+}
+
+#[cfg(all(feature = "std", target_arch = "wasm32"))]
+pub(crate) fn now() -> SystemTime {
+    UNIX_EPOCH + std::time::Duration::from_millis(js_sys::Date::now() as u64)
+}
+
+#[cfg(all(test, not(feature = "std")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duration_since_epoch() {
+        let timestamp = UNIX_EPOCH + Duration::from_millis(1500);
+        assert_eq!(
+            timestamp.duration_since(UNIX_EPOCH).ok(),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(
+            UNIX_EPOCH.duration_since(timestamp).unwrap_err().duration(),
+            Duration::from_millis(1500)
+        );
+        assert_eq!(timestamp - Duration::from_millis(1500), UNIX_EPOCH);
+        assert_eq!(UNIX_EPOCH.checked_sub(Duration::from_nanos(1)), None);
+    }
+}
@@ -1,8 +1,10 @@
 //! Context extensions for tracing
-use crate::{Context, ContextGuard};
-lazy_static::lazy_static! {
-    static ref NOOP_SPAN: crate::trace::NoopSpan = crate::trace::NoopSpan::new();
-}
+use crate::Context;
+#[cfg(feature = "std")]
+use crate::ContextGuard;
+use alloc::boxed::Box;
+
+static NOOP_SPAN: crate::trace::NoopSpan = crate::trace::NoopSpan::new();
 
 struct Span(Box<dyn crate::trace::Span + Send + Sync>);
 
@@ -10,6 +12,8 @@ struct RemoteSpanContext(crate::trace::SpanContext);
 
 /// Methods for storing and retrieving trace data in a context.
 pub trait TraceContextExt {
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
     /// Returns a clone of the current context with the included span.
     ///
     /// This is useful for building tracers.
@@ -26,6 +30,8 @@ pub trait TraceContextExt {
     /// # Examples
     ///
     /// ```
+    /// # #[cfg(feature = "trace")]
+    /// # {
     /// use opentelemetry::{
     ///     sdk::trace as sdktrace,
     ///     trace::{SpanContext, TraceContextExt, Tracer, TracerProvider},
@@ -39,6 +45,7 @@ pub trait TraceContextExt {
     ///     // Returns a reference to the current span if set
     ///     assert_ne!(cx.span().span_context(), &SpanContext::empty_context());
     /// });
+    /// # }
     /// ```
     fn span(&self) -> &dyn crate::trace::Span;
 
@@ -57,9 +64,46 @@ pub trait TraceContextExt {
     ///
     /// This is useful for building tracers.
     fn remote_span_context(&self) -> Option<&crate::trace::SpanContext>;
+
+    /// Returns whether this context belongs to a sampled trace, i.e. whether
+    /// its span is sampled or, without a span, its remote span context.
+    ///
+    /// This is useful for emitting verbose telemetry for sampled traces only.
+    fn is_span_sampled(&self) -> bool;
+
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    /// Returns whether the current context belongs to a sampled trace, see
+    /// [`TraceContextExt::is_span_sampled`].
+    ///
+    /// This is cheaper than `Context::current().is_span_sampled()` as the
+    /// current context is not cloned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use opentelemetry::{
+    ///     sdk::trace as sdktrace,
+    ///     trace::{TraceContextExt, Tracer, TracerProvider},
+    ///     Context,
+    /// };
+    ///
+    /// assert!(!Context::is_current_span_sampled());
+    ///
+    /// let provider = sdktrace::TracerProvider::builder()
+    ///     .with_config(sdktrace::config().with_default_sampler(sdktrace::Sampler::AlwaysOn))
+    ///     .build();
+    /// provider.get_tracer("my-component", None).in_span("my-span", |_cx| {
+    ///     if Context::is_current_span_sampled() {
+    ///         println!("verbose details of a sampled request");
+    ///     }
+    /// });
+    /// ```
+    fn is_current_span_sampled() -> bool;
 }
 
 impl TraceContextExt for Context {
+    #[cfg(feature = "std")]
     fn current_with_span<T: crate::trace::Span + Send + Sync>(span: T) -> Self {
         Context::current_with_value(Span(Box::new(span)))
     }
@@ -72,7 +116,7 @@ impl TraceContextExt for Context {
         if let Some(span) = self.get::<Span>() {
             span.0.as_ref()
         } else {
-            &*NOOP_SPAN
+            &NOOP_SPAN
         }
     }
 
@@ -88,8 +132,24 @@ impl TraceContextExt for Context {
         self.get::<RemoteSpanContext>()
             .map(|span_context| &span_context.0)
     }
+
+    fn is_span_sampled(&self) -> bool {
+        match self.get::<Span>() {
+            Some(span) => span.0.span_context().is_sampled(),
+            None => matches!(
+                self.remote_span_context(),
+                Some(span_context) if span_context.is_sampled()
+            ),
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn is_current_span_sampled() -> bool {
+        Context::map_current(|cx| cx.is_span_sampled())
+    }
 }
 
+#[cfg(feature = "std")]
 /// Mark a given `Span` as active.
 ///
 /// The `Tracer` MUST provide a way to update its active `Span`, and MAY provide convenience
@@ -126,6 +186,7 @@ pub fn mark_span_as_active<T: crate::trace::Span + Send + Sync>(span: T) -> Cont
     cx.attach()
 }
 
+#[cfg(feature = "std")]
 /// Executes a closure with a reference to this thread's current span.
 ///
 /// # Examples
@@ -1,10 +1,9 @@
 //! # OpenTelemetry Trace Event Interface
 
-use crate::KeyValue;
+use crate::{time::SystemTime, KeyValue, Severity, Value};
+use alloc::{borrow::Cow, vec::Vec};
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
-use std::borrow::Cow;
-use std::time::SystemTime;
 
 /// A `Span` has the ability to add events. Events have a time associated
 /// with the moment when they are added to the `Span`.
@@ -17,6 +16,10 @@ pub struct Event {
     pub timestamp: SystemTime,
     /// Event attributes
     pub attributes: Vec<KeyValue>,
+    /// Event severity, e.g. of events recording errors
+    pub severity: Option<Severity>,
+    /// Event body, structured details of what happened
+    pub body: Option<Value>,
 }
 
 impl Event {
@@ -30,15 +33,51 @@ impl Event {
             name: name.into(),
             timestamp,
             attributes,
+            severity: None,
+            body: None,
         }
     }
 
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
     /// Create new `Event` with a given name.
     pub fn with_name<T: Into<Cow<'static, str>>>(name: T) -> Self {
         Event {
             name: name.into(),
             timestamp: crate::time::now(),
             attributes: Vec::new(),
+            severity: None,
+            body: None,
         }
     }
+
+    /// The attributes of the event, followed by its severity and body as
+    /// `event.severity_number`, `event.severity_text` and `event.body`
+    /// attributes, for formats without dedicated fields for them like OTLP
+    /// span events.
+    pub fn attributes_with_severity(&self) -> Vec<KeyValue> {
+        let mut attributes = self.attributes.clone();
+        attributes.extend(severity_attributes(self.severity, self.body.clone()));
+        attributes
+    }
 }
+
+/// The severity and body of an event as attributes.
+pub(crate) fn severity_attributes(
+    severity: Option<Severity>,
+    body: Option<Value>,
+) -> Vec<KeyValue> {
+    let mut attributes = Vec::new();
+    if let Some(severity) = severity {
+        attributes.push(KeyValue::new(EVENT_SEVERITY_NUMBER, severity as i64));
+        attributes.push(KeyValue::new(EVENT_SEVERITY_TEXT, severity.name()));
+    }
+    if let Some(body) = body {
+        attributes.push(KeyValue::new(EVENT_BODY, body));
+    }
+    attributes
+}
+
+const EVENT_SEVERITY_NUMBER: &str = "event.severity_number";
+const EVENT_SEVERITY_TEXT: &str = "event.severity_text";
+const EVENT_BODY: &str = "event.body";
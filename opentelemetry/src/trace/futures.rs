@@ -6,6 +6,15 @@
 //! This main trait is [`FutureExt`], which allows a [`Context`],
 //! to be attached to a future, sink, or stream.
 //!
+//! [`Context::attach_to_task`] does the same starting from the context, e.g.
+//! to run a task within a context built for it.
+//!
+//! With one of the tokio runtime features enabled,
+//! [`spawn_with_current_context`] and [`spawn_blocking_with_current_context`]
+//! spawn tasks which inherit the current context, so that it does not have to
+//! be cloned before every spawn. The context of a task is also kept in a tokio
+//! task-local, see [`Context::scope`] and [`Context::task_context`].
+//!
 //! [`futures`]: std::future::Future
 //! [`Context`]: crate::Context
 use crate::Context as OpenTelemetryContext;
@@ -26,6 +35,77 @@ pub struct WithContext<T> {
 
 impl<T: Sized> FutureExt for T {}
 
+#[cfg(any(feature = "rt-tokio", feature = "rt-tokio-current-thread"))]
+tokio::task_local! {
+    /// The context of the tokio task, set by [`Context::scope`].
+    ///
+    /// [`Context::scope`]: crate::Context::scope
+    static TASK_CONTEXT: OpenTelemetryContext;
+}
+
+impl OpenTelemetryContext {
+    /// Attaches this context to a future, stream, or sink, returning a
+    /// `WithContext` wrapper.
+    ///
+    /// The context is set as current while the wrapped type is being polled,
+    /// like with [`FutureExt::with_context`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use opentelemetry::{Context, KeyValue};
+    /// use opentelemetry::baggage::BaggageExt;
+    ///
+    /// let cx = Context::new().with_baggage(vec![KeyValue::new("tenant", "acme")]);
+    /// let task = cx.attach_to_task(async {
+    ///     Context::current().baggage().get_str("tenant").map(str::to_string)
+    /// });
+    ///
+    /// assert_eq!(futures::executor::block_on(task).as_deref(), Some("acme"));
+    /// ```
+    pub fn attach_to_task<T>(self, task: T) -> WithContext<T> {
+        task.with_context(self)
+    }
+
+    /// Runs a future with this context as the context of the current tokio
+    /// task.
+    ///
+    /// The context is current while the future is polled, as with
+    /// [`attach_to_task`], and returned by [`Context::task_context`] anywhere
+    /// within the future, even while other contexts are attached on top of it.
+    ///
+    /// [`attach_to_task`]: crate::Context::attach_to_task
+    /// [`Context::task_context`]: crate::Context::task_context
+    #[cfg(any(feature = "rt-tokio", feature = "rt-tokio-current-thread"))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(feature = "rt-tokio", feature = "rt-tokio-current-thread")))
+    )]
+    pub async fn scope<F: std::future::Future>(self, future: F) -> F::Output {
+        TASK_CONTEXT
+            .scope(self.clone(), self.attach_to_task(future))
+            .await
+    }
+
+    /// Returns the context of the current tokio task, if it was run with
+    /// [`Context::scope`] or spawned with [`spawn_with_current_context`].
+    ///
+    /// Unlike [`Context::current`] this is not changed by attaching contexts
+    /// within the task, e.g. the context of the request a task was spawned for
+    /// is returned while a child span is active.
+    ///
+    /// [`Context::scope`]: crate::Context::scope
+    /// [`Context::current`]: crate::Context::current
+    #[cfg(any(feature = "rt-tokio", feature = "rt-tokio-current-thread"))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(feature = "rt-tokio", feature = "rt-tokio-current-thread")))
+    )]
+    pub fn task_context() -> Option<Self> {
+        TASK_CONTEXT.try_with(Clone::clone).ok()
+    }
+}
+
 impl<T: std::future::Future> std::future::Future for WithContext<T> {
     type Output = T::Output;
 
@@ -115,3 +195,114 @@ pub trait FutureExt: Sized {
         self.with_context(otel_cx)
     }
 }
+
+/// Spawn a future on the tokio runtime with the current [`Context`] attached.
+///
+/// The spawned task inherits the context of the caller, e.g. the active span,
+/// as if [`FutureExt::with_current_context`] was called before
+/// [`tokio::spawn`]. It is also the task-local context returned by
+/// [`Context::task_context`].
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::{trace::spawn_with_current_context, Context, KeyValue};
+/// use opentelemetry::baggage::BaggageExt;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let _guard = Context::current_with_baggage(vec![KeyValue::new("tenant", "acme")]).attach();
+///
+/// let tenant = spawn_with_current_context(async {
+///     Context::current().baggage().get_str("tenant").map(str::to_string)
+/// })
+/// .await
+/// .unwrap();
+/// assert_eq!(tenant.as_deref(), Some("acme"));
+/// # }
+/// ```
+///
+/// [`Context`]: crate::Context
+/// [`tokio::spawn`]: https://docs.rs/tokio/1/tokio/fn.spawn.html
+#[cfg(any(feature = "rt-tokio", feature = "rt-tokio-current-thread"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "rt-tokio", feature = "rt-tokio-current-thread")))
+)]
+pub fn spawn_with_current_context<F>(future: F) -> tokio::task::JoinHandle<F::Output>
+where
+    F: std::future::Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    tokio::spawn(OpenTelemetryContext::current().scope(future))
+}
+
+/// Run a blocking closure on the blocking threads of the tokio runtime with
+/// the current [`Context`] attached.
+///
+/// Blocking closures are not tokio tasks, so [`Context::task_context`] is not
+/// set within them.
+///
+/// [`Context`]: crate::Context
+/// [`Context::task_context`]: crate::Context::task_context
+#[cfg(any(feature = "rt-tokio", feature = "rt-tokio-current-thread"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "rt-tokio", feature = "rt-tokio-current-thread")))
+)]
+pub fn spawn_blocking_with_current_context<F, R>(f: F) -> tokio::task::JoinHandle<R>
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    let otel_cx = OpenTelemetryContext::current();
+    tokio::task::spawn_blocking(move || {
+        let _guard = otel_cx.attach();
+        f()
+    })
+}
+
+#[cfg(all(test, feature = "rt-tokio"))]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct ValueA(&'static str);
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn spawned_tasks_inherit_context() {
+        let _guard = OpenTelemetryContext::new().with_value(ValueA("a")).attach();
+
+        let in_task = spawn_with_current_context(async {
+            tokio::task::yield_now().await;
+            OpenTelemetryContext::current().get::<ValueA>().is_some()
+        });
+        let in_blocking = spawn_blocking_with_current_context(|| {
+            OpenTelemetryContext::current().get::<ValueA>().is_some()
+        });
+        let in_plain_task =
+            tokio::spawn(async { OpenTelemetryContext::current().get::<ValueA>().is_some() });
+
+        assert!(in_task.await.unwrap());
+        assert!(in_blocking.await.unwrap());
+        assert!(!in_plain_task.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn task_context_outlives_attached_contexts() {
+        let task_cx = OpenTelemetryContext::new().with_value(ValueA("task"));
+
+        let values = task_cx.scope(async {
+            let _guard = OpenTelemetryContext::new()
+                .with_value(ValueA("child"))
+                .attach();
+            let current = OpenTelemetryContext::current().get::<ValueA>().map(|v| v.0);
+            let task =
+                OpenTelemetryContext::task_context().and_then(|cx| cx.get::<ValueA>().map(|v| v.0));
+            (current, task)
+        });
+
+        assert_eq!(values.await, (Some("child"), Some("task")));
+        assert!(OpenTelemetryContext::task_context().is_none());
+    }
+}
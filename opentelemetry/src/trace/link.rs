@@ -1,5 +1,6 @@
 //! # OpenTelemetry Trace Link Interface
 use crate::{trace::SpanContext, KeyValue};
+use alloc::vec::Vec;
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
 
@@ -110,12 +110,16 @@
 //! Please review the W3C specification for details on the [Tracestate
 //! field](https://www.w3.org/TR/trace-context/#tracestate-field).
 //!
+#[cfg(feature = "trace")]
 use ::futures::channel::{mpsc::TrySendError, oneshot::Canceled};
+#[cfg(feature = "trace")]
 use thiserror::Error;
 
 mod context;
 mod event;
+#[cfg(feature = "trace")]
 mod futures;
+#[cfg(feature = "trace")]
 mod id_generator;
 mod link;
 mod noop;
@@ -124,13 +128,20 @@ mod span;
 mod span_context;
 mod tracer;
 
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use self::context::{get_active_span, mark_span_as_active};
+#[cfg(any(feature = "rt-tokio", feature = "rt-tokio-current-thread"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "rt-tokio", feature = "rt-tokio-current-thread")))
+)]
+pub use self::futures::{spawn_blocking_with_current_context, spawn_with_current_context};
 pub use self::{
-    context::{get_active_span, mark_span_as_active, TraceContextExt},
+    context::TraceContextExt,
     event::Event,
-    futures::FutureExt,
-    id_generator::IdGenerator,
     link::Link,
-    noop::{NoopSpan, NoopSpanExporter, NoopTracer, NoopTracerProvider},
+    noop::{NoopSpan, NoopTracer, NoopTracerProvider},
     provider::TracerProvider,
     span::{Span, SpanKind, StatusCode},
     span_context::{
@@ -139,12 +150,25 @@ pub use self::{
     },
     tracer::{SpanBuilder, Tracer},
 };
+#[cfg(feature = "trace")]
+#[cfg_attr(docsrs, doc(cfg(feature = "trace")))]
+pub use self::{
+    futures::{FutureExt, WithContext},
+    id_generator::IdGenerator,
+    noop::NoopSpanExporter,
+};
+#[cfg(feature = "trace")]
 use crate::sdk::export::ExportError;
+#[cfg(feature = "trace")]
 use std::time;
 
+#[cfg(feature = "trace")]
+#[cfg_attr(docsrs, doc(cfg(feature = "trace")))]
 /// Describe the result of operations in tracing API.
 pub type TraceResult<T> = Result<T, TraceError>;
 
+#[cfg(feature = "trace")]
+#[cfg_attr(docsrs, doc(cfg(feature = "trace")))]
 /// Errors returned by the trace API.
 #[derive(Error, Debug)]
 #[non_exhaustive]
@@ -162,6 +186,7 @@ pub enum TraceError {
     Other(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
 }
 
+#[cfg(feature = "trace")]
 impl<T> From<T> for TraceError
 where
     T: ExportError,
@@ -171,30 +196,35 @@ where
     }
 }
 
+#[cfg(feature = "trace")]
 impl<T> From<TrySendError<T>> for TraceError {
     fn from(err: TrySendError<T>) -> Self {
         TraceError::Other(Box::new(err.into_send_error()))
     }
 }
 
+#[cfg(feature = "trace")]
 impl From<Canceled> for TraceError {
     fn from(err: Canceled) -> Self {
         TraceError::Other(Box::new(err))
     }
 }
 
+#[cfg(feature = "trace")]
 impl From<String> for TraceError {
     fn from(err_msg: String) -> Self {
         TraceError::Other(Box::new(Custom(err_msg)))
     }
 }
 
+#[cfg(feature = "trace")]
 impl From<&'static str> for TraceError {
     fn from(err_msg: &'static str) -> Self {
         TraceError::Other(Box::new(Custom(err_msg.into())))
     }
 }
 
+#[cfg(feature = "trace")]
 /// Wrap type for string
 #[derive(Error, Debug)]
 #[error("{0}")]
@@ -3,14 +3,12 @@
 //! This implementation is returned as the global tracer if no `Tracer`
 //! has been set. It is also useful for testing purposes as it is intended
 //! to have minimal resource utilization and runtime impact.
-use crate::{
-    sdk::export::trace::{ExportResult, SpanData, SpanExporter},
-    trace,
-    trace::{TraceContextExt, TraceState},
-    Context, KeyValue,
-};
+#[cfg(feature = "trace")]
+use crate::sdk::export::trace::{ExportResult, SpanData, SpanExporter};
+use crate::{time::SystemTime, trace, trace::TraceContextExt, Context, KeyValue};
+use alloc::{borrow::Cow, string::String, vec::Vec};
+#[cfg(feature = "trace")]
 use async_trait::async_trait;
-use std::time::SystemTime;
 
 /// A no-op instance of a `TracerProvider`.
 #[derive(Debug, Default)]
@@ -48,15 +46,9 @@ impl Default for NoopSpan {
 
 impl NoopSpan {
     /// Creates a new `NoopSpan` instance.
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         NoopSpan {
-            span_context: trace::SpanContext::new(
-                trace::TraceId::invalid(),
-                trace::SpanId::invalid(),
-                0,
-                false,
-                TraceState::default(),
-            ),
+            span_context: trace::SpanContext::NONE,
         }
     }
 }
@@ -102,6 +94,11 @@ impl trace::Span for NoopSpan {
         // Ignored
     }
 
+    /// Ignores `Span` endings
+    fn end(&self) {
+        // Ignored
+    }
+
     /// Ignores `Span` endings
     fn end_with_timestamp(&self, _timestamp: SystemTime) {
         // Ignored
@@ -132,15 +129,21 @@ impl trace::Tracer for NoopTracer {
     /// Starts a new `NoopSpan` with a given context.
     ///
     /// If the context contains a valid span context, it is propagated.
-    fn start_with_context(&self, name: &str, cx: Context) -> Self::Span {
+    fn start_with_context<T>(&self, name: T, cx: Context) -> Self::Span
+    where
+        T: Into<Cow<'static, str>>,
+    {
         let mut builder = self.span_builder(name);
         builder.parent_context = Some(cx);
         self.build(builder)
     }
 
     /// Starts a `SpanBuilder`.
-    fn span_builder(&self, name: &str) -> trace::SpanBuilder {
-        trace::SpanBuilder::from_name(name.to_string())
+    fn span_builder<T>(&self, name: T) -> trace::SpanBuilder
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        trace::SpanBuilder::from_name(name)
     }
 
     /// Builds a `NoopSpan` from a `SpanBuilder`.
@@ -162,6 +165,7 @@ impl trace::Tracer for NoopTracer {
     }
 }
 
+#[cfg(feature = "trace")]
 /// A no-op instance of an [`SpanExporter`].
 ///
 /// [`SpanExporter`]: crate::sdk::export::trace::SpanExporter
@@ -170,6 +174,7 @@ pub struct NoopSpanExporter {
     _private: (),
 }
 
+#[cfg(feature = "trace")]
 impl NoopSpanExporter {
     /// Create a new noop span exporter
     pub fn new() -> Self {
@@ -177,6 +182,7 @@ impl NoopSpanExporter {
     }
 }
 
+#[cfg(feature = "trace")]
 #[async_trait]
 impl SpanExporter for NoopSpanExporter {
     async fn export(&mut self, _batch: Vec<SpanData>) -> ExportResult {
@@ -188,7 +194,7 @@ impl SpanExporter for NoopSpanExporter {
 mod tests {
     use super::*;
     use crate::testing::trace::TestSpan;
-    use crate::trace::{self, Span, Tracer};
+    use crate::trace::{self, Span, TraceState, Tracer};
 
     fn valid_span_context() -> trace::SpanContext {
         trace::SpanContext::new(
@@ -19,8 +19,12 @@
 //!
 //! Implementations might require the user to specify configuration properties at
 //! `TracerProvider` creation time, or rely on external configurations.
+#[cfg(feature = "trace")]
+use crate::trace::TraceResult;
 use crate::trace::Tracer;
-use std::fmt;
+#[cfg(feature = "trace")]
+use alloc::vec::Vec;
+use core::fmt;
 
 /// An interface to create `Tracer` instances.
 pub trait TracerProvider: fmt::Debug + 'static {
@@ -30,4 +34,14 @@ pub trait TracerProvider: fmt::Debug + 'static {
     /// Creates a named tracer instance of `Self::Tracer`.
     /// If the name is an empty string then provider uses default name.
     fn get_tracer(&self, name: &'static str, version: Option<&'static str>) -> Self::Tracer;
+
+    #[cfg(feature = "trace")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "trace")))]
+    /// Export the spans buffered by the provider, returning the result of each
+    /// flush.
+    ///
+    /// Providers without buffered spans return no results.
+    fn force_flush(&self) -> Vec<TraceResult<()>> {
+        Vec::new()
+    }
 }
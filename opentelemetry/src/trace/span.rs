@@ -15,12 +15,15 @@
 //! Vendors may implement the `Span` interface to effect vendor-specific logic. However, alternative
 //! implementations MUST NOT allow callers to create Spans directly. All `Span`s MUST be created
 //! via a Tracer.
-use crate::{trace::SpanContext, KeyValue};
+use crate::{time::SystemTime, trace::SpanContext, KeyValue, Severity, Value};
+use alloc::{string::String, vec::Vec};
+#[cfg(feature = "std")]
+use alloc::{string::ToString, vec};
+use core::fmt;
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
 use std::error::Error;
-use std::fmt;
-use std::time::SystemTime;
 
 /// Interface for a single operation within a trace.
 pub trait Span: fmt::Debug + 'static + Send + Sync {
@@ -35,10 +38,38 @@ pub trait Span: fmt::Debug + 'static + Send + Sync {
     /// Note that the OpenTelemetry project documents certain ["standard event names and
     /// keys"](https://github.com/open-telemetry/opentelemetry-specification/tree/v0.5.0/specification/trace/semantic_conventions/README.md)
     /// which have prescribed semantic meanings.
+    #[cfg(feature = "std")]
     fn add_event(&self, name: String, attributes: Vec<KeyValue>) {
         self.add_event_with_timestamp(name, crate::time::now(), attributes)
     }
 
+    /// An API to record events in the context of a given `Span`.
+    ///
+    /// Without `std` there is no system clock, implementations timestamp the
+    /// event themselves, see [`Span::add_event_with_timestamp`].
+    #[cfg(not(feature = "std"))]
+    fn add_event(&self, name: String, attributes: Vec<KeyValue>);
+
+    /// An API to record events with a severity and a structured body, e.g.
+    /// errors which should show up in both traces and logs.
+    ///
+    /// SDKs keep the severity and body with the event, and may emit the event
+    /// as a log record correlated with the span as well. The default
+    /// implementation records them as `event.severity_number`,
+    /// `event.severity_text` and `event.body` attributes of the event.
+    fn add_event_with_severity(
+        &self,
+        name: String,
+        severity: Severity,
+        body: Option<Value>,
+        mut attributes: Vec<KeyValue>,
+    ) {
+        attributes.extend(super::event::severity_attributes(Some(severity), body));
+        self.add_event(name, attributes)
+    }
+
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
     /// Convenience method to record an exception/error as an `Event`
     ///
     /// An exception SHOULD be recorded as an Event on the span during which it occurred.
@@ -58,6 +89,8 @@ pub trait Span: fmt::Debug + 'static + Send + Sync {
         self.add_event("exception".to_string(), attributes);
     }
 
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
     /// Convenience method to record a exception/error as an `Event` with custom stacktrace
     ///
     /// See `Span:record_exception` method for more details.
@@ -123,10 +156,11 @@ pub trait Span: fmt::Debug + 'static + Send + Sync {
     fn set_attribute(&self, attribute: KeyValue);
 
     /// Sets the status of the `Span`. If used, this will override the default `Span`
-    /// status, which is `Unset`. `message` MUST be ignored when the status is `OK` or `Unset`
+    /// status, which is `Unset`. `message` MUST be ignored when the status is `Ok` or `Unset`
     ///
     /// Only the value of the last call will be recorded, and implementations are free
-    /// to ignore previous calls.
+    /// to ignore previous calls. Setting the status to `Unset` SHOULD be ignored, and
+    /// once the status is `Ok` it is final, later calls SHOULD be ignored.
     fn set_status(&self, code: StatusCode, message: String);
 
     /// Updates the `Span`'s name. After this update, any sampling behavior based on the
@@ -153,10 +187,18 @@ pub trait Span: fmt::Debug + 'static + Send + Sync {
     /// still be running and can be ended later.
     ///
     /// This API MUST be non-blocking.
+    #[cfg(feature = "std")]
     fn end(&self) {
         self.end_with_timestamp(crate::time::now());
     }
 
+    /// Finishes the `Span`.
+    ///
+    /// Without `std` there is no system clock, implementations timestamp the
+    /// end themselves, see [`Span::end_with_timestamp`].
+    #[cfg(not(feature = "std"))]
+    fn end(&self);
+
     /// Finishes the `Span` with given timestamp
     ///
     /// For more details, refer to [`Span::end`]
@@ -238,10 +280,14 @@ impl fmt::Display for SpanKind {
 }
 
 /// The `StatusCode` interface represents the status of a finished `Span`.
-/// It's composed of a canonical code in conjunction with an optional
-/// descriptive message.
+///
+/// A span is `Unset` unless the instrumentation or the application sets its
+/// status. Instrumentation sets `Error` for failed operations, along with an
+/// optional description of the error, which is only valid for `Error`.
+/// Applications may set `Ok` to override an `Error` status of the
+/// instrumentation.
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
-#[derive(Clone, Debug, PartialEq, Copy)]
+#[derive(Clone, Debug, PartialEq, Eq, Copy)]
 pub enum StatusCode {
     /// The default status.
     Unset = 0,
@@ -251,6 +297,63 @@ pub enum StatusCode {
     Error = 2,
 }
 
+/// The gRPC style status codes of earlier versions of the specification,
+/// `OK` maps to [`StatusCode::Ok`] and all others to [`StatusCode::Error`].
+#[allow(non_upper_case_globals)]
+impl StatusCode {
+    /// The operation completed successfully.
+    #[deprecated(since = "0.13.0", note = "use StatusCode::Ok")]
+    pub const OK: StatusCode = StatusCode::Ok;
+    /// The operation was cancelled.
+    #[deprecated(since = "0.13.0", note = "use StatusCode::Error")]
+    pub const Canceled: StatusCode = StatusCode::Error;
+    /// Unknown error.
+    #[deprecated(since = "0.13.0", note = "use StatusCode::Error")]
+    pub const Unknown: StatusCode = StatusCode::Error;
+    /// Client specified an invalid argument.
+    #[deprecated(since = "0.13.0", note = "use StatusCode::Error")]
+    pub const InvalidArgument: StatusCode = StatusCode::Error;
+    /// Deadline expired before operation could complete.
+    #[deprecated(since = "0.13.0", note = "use StatusCode::Error")]
+    pub const DeadlineExceeded: StatusCode = StatusCode::Error;
+    /// Some requested entity was not found.
+    #[deprecated(since = "0.13.0", note = "use StatusCode::Error")]
+    pub const NotFound: StatusCode = StatusCode::Error;
+    /// Some entity that we attempted to create already exists.
+    #[deprecated(since = "0.13.0", note = "use StatusCode::Error")]
+    pub const AlreadyExists: StatusCode = StatusCode::Error;
+    /// The caller does not have permission to execute the specified operation.
+    #[deprecated(since = "0.13.0", note = "use StatusCode::Error")]
+    pub const PermissionDenied: StatusCode = StatusCode::Error;
+    /// Some resource has been exhausted.
+    #[deprecated(since = "0.13.0", note = "use StatusCode::Error")]
+    pub const ResourceExhausted: StatusCode = StatusCode::Error;
+    /// The system is not in a state required for the operation's execution.
+    #[deprecated(since = "0.13.0", note = "use StatusCode::Error")]
+    pub const FailedPrecondition: StatusCode = StatusCode::Error;
+    /// The operation was aborted.
+    #[deprecated(since = "0.13.0", note = "use StatusCode::Error")]
+    pub const Aborted: StatusCode = StatusCode::Error;
+    /// Operation was attempted past the valid range.
+    #[deprecated(since = "0.13.0", note = "use StatusCode::Error")]
+    pub const OutOfRange: StatusCode = StatusCode::Error;
+    /// Operation is not implemented or not supported.
+    #[deprecated(since = "0.13.0", note = "use StatusCode::Error")]
+    pub const Unimplemented: StatusCode = StatusCode::Error;
+    /// Internal error.
+    #[deprecated(since = "0.13.0", note = "use StatusCode::Error")]
+    pub const Internal: StatusCode = StatusCode::Error;
+    /// The service is currently unavailable.
+    #[deprecated(since = "0.13.0", note = "use StatusCode::Error")]
+    pub const Unavailable: StatusCode = StatusCode::Error;
+    /// Unrecoverable data loss or corruption.
+    #[deprecated(since = "0.13.0", note = "use StatusCode::Error")]
+    pub const DataLoss: StatusCode = StatusCode::Error;
+    /// The request does not have valid authentication credentials.
+    #[deprecated(since = "0.13.0", note = "use StatusCode::Error")]
+    pub const Unauthenticated: StatusCode = StatusCode::Error;
+}
+
 impl StatusCode {
     /// Return a static str that represent the status code
     pub fn as_str(&self) -> &'static str {
@@ -10,10 +10,15 @@
 //! The spec can be viewed here: https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/api-tracing.md#SpanContext
 //!
 //! [w3c TraceContext specification]: https://www.w3.org/TR/trace-context/
+use alloc::{
+    collections::VecDeque,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::str::FromStr;
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
-use std::str::FromStr;
 
 /// A SpanContext with TRACE_FLAG_NOT_SAMPLED means the span is not sampled.
 pub const TRACE_FLAG_NOT_SAMPLED: u8 = 0x00;
@@ -35,17 +40,17 @@ pub struct TraceId(u128);
 
 impl TraceId {
     /// Construct a new invalid (zero-valued) TraceId
-    pub fn invalid() -> Self {
+    pub const fn invalid() -> Self {
         TraceId(0)
     }
 
     /// Convert from u128 to TraceId
-    pub fn from_u128(item: u128) -> Self {
+    pub const fn from_u128(item: u128) -> Self {
         TraceId(item)
     }
 
     /// Convert from TraceId to u128
-    pub fn to_u128(self) -> u128 {
+    pub const fn to_u128(self) -> u128 {
         self.0
     }
 
@@ -55,7 +60,7 @@ impl TraceId {
     }
 
     /// Convert from TraceId to Big-Endian byte array
-    pub fn to_byte_array(self) -> [u8; 16] {
+    pub const fn to_byte_array(self) -> [u8; 16] {
         self.0.to_be_bytes()
     }
 
@@ -65,7 +70,7 @@ impl TraceId {
     }
 
     /// Construct a new TraceId from Big-Endian byte array
-    pub fn from_byte_array(byte_array: [u8; 16]) -> Self {
+    pub const fn from_byte_array(byte_array: [u8; 16]) -> Self {
         TraceId(u128::from_be_bytes(byte_array))
     }
 }
@@ -78,17 +83,17 @@ pub struct SpanId(u64);
 
 impl SpanId {
     /// Construct a new invalid (zero-valued) SpanId
-    pub fn invalid() -> Self {
+    pub const fn invalid() -> Self {
         SpanId(0)
     }
 
     /// Convert from u64 to SpanId
-    pub fn from_u64(item: u64) -> Self {
+    pub const fn from_u64(item: u64) -> Self {
         SpanId(item)
     }
 
     /// Convert from SpanId to u64
-    pub fn to_u64(self) -> u64 {
+    pub const fn to_u64(self) -> u64 {
         self.0
     }
 
@@ -98,7 +103,7 @@ impl SpanId {
     }
 
     /// Convert from SpanId to Big-Endian byte array
-    pub fn to_byte_array(self) -> [u8; 8] {
+    pub const fn to_byte_array(self) -> [u8; 8] {
         self.0.to_be_bytes()
     }
 
@@ -108,7 +113,7 @@ impl SpanId {
     }
 
     /// Construct a new SpanId from Big-Endian byte array
-    pub fn from_byte_array(byte_array: [u8; 8]) -> Self {
+    pub const fn from_byte_array(byte_array: [u8; 8]) -> Self {
         SpanId(u64::from_be_bytes(byte_array))
     }
 }
@@ -318,15 +323,19 @@ pub struct SpanContext {
 }
 
 impl SpanContext {
-    /// Create an invalid empty span context
+    /// An invalid span context, with zero ids, no trace flags and an empty
+    /// `TraceState`.
+    pub const NONE: SpanContext = SpanContext {
+        trace_id: TraceId::invalid(),
+        span_id: SpanId::invalid(),
+        trace_flags: TRACE_FLAG_NOT_SAMPLED,
+        is_remote: false,
+        trace_state: TraceState(None),
+    };
+
+    /// Create an invalid empty span context, see [`SpanContext::NONE`].
     pub fn empty_context() -> Self {
-        SpanContext::new(
-            TraceId::invalid(),
-            SpanId::invalid(),
-            0,
-            false,
-            TraceState::default(),
-        )
+        SpanContext::NONE
     }
 
     /// Construct a new `SpanContext`
@@ -347,49 +356,50 @@ impl SpanContext {
     }
 
     /// A valid trace identifier is a non-zero `u128`.
-    pub fn trace_id(&self) -> TraceId {
+    pub const fn trace_id(&self) -> TraceId {
         self.trace_id
     }
 
     /// A valid span identifier is a non-zero `u64`.
-    pub fn span_id(&self) -> SpanId {
+    pub const fn span_id(&self) -> SpanId {
         self.span_id
     }
 
     /// Returns details about the trace. Unlike `TraceState` values, these are
     /// present in all traces. Currently, the only option is a boolean sampled flag.
-    pub fn trace_flags(&self) -> u8 {
+    pub const fn trace_flags(&self) -> u8 {
         self.trace_flags
     }
 
     /// Returns a bool flag which is true if the `SpanContext` has a valid (non-zero) `trace_id`
     /// and a valid (non-zero) `span_id`.
-    pub fn is_valid(&self) -> bool {
-        self.trace_id.0 != 0 && self.span_id.0 != 0
+    pub const fn is_valid(&self) -> bool {
+        // both ids are compared without short-circuiting to avoid a branch
+        (self.trace_id.0 != 0) & (self.span_id.0 != 0)
     }
 
     /// Returns true if the `SpanContext` was propagated from a remote parent.
-    pub fn is_remote(&self) -> bool {
+    pub const fn is_remote(&self) -> bool {
         self.is_remote
     }
 
     /// Returns if the deferred bit is set in the trace flags
-    pub fn is_deferred(&self) -> bool {
+    pub const fn is_deferred(&self) -> bool {
         (self.trace_flags & TRACE_FLAG_DEFERRED) == TRACE_FLAG_DEFERRED
     }
 
     /// Returns if the debug bit is set in the trace flags
-    pub fn is_debug(&self) -> bool {
+    pub const fn is_debug(&self) -> bool {
         (self.trace_flags & TRACE_FLAG_DEBUG) == TRACE_FLAG_DEBUG
     }
 
     /// Returns true if the `SpanContext` is sampled.
-    pub fn is_sampled(&self) -> bool {
+    pub const fn is_sampled(&self) -> bool {
         (self.trace_flags & TRACE_FLAG_SAMPLED) == TRACE_FLAG_SAMPLED
     }
 
     /// Returns the context's `TraceState`.
-    pub fn trace_state(&self) -> &TraceState {
+    pub const fn trace_state(&self) -> &TraceState {
         &self.trace_state
     }
 }
@@ -473,4 +483,31 @@ mod tests {
             assert!(deleted_trace_state.get(test_case.2).is_none());
         }
     }
+
+    const TRACE_ID: TraceId = TraceId::from_byte_array([1; 16]);
+    const SPAN_ID: SpanId = SpanId::from_u64(42);
+
+    #[test]
+    fn test_const_ids() {
+        assert_eq!(
+            TRACE_ID.to_u128(),
+            0x0101_0101_0101_0101_0101_0101_0101_0101
+        );
+        assert_eq!(SpanId::from_byte_array(SPAN_ID.to_byte_array()), SPAN_ID);
+        assert_eq!(TraceId::invalid().to_u128(), 0);
+    }
+
+    #[test]
+    fn test_span_context_validity() {
+        assert!(!SpanContext::NONE.is_valid());
+        assert!(!SpanContext::NONE.is_sampled());
+        assert_eq!(SpanContext::empty_context(), SpanContext::NONE);
+
+        let valid = |trace_id, span_id| {
+            SpanContext::new(trace_id, span_id, 0, false, TraceState::default()).is_valid()
+        };
+        assert!(valid(TRACE_ID, SPAN_ID));
+        assert!(!valid(TRACE_ID, SpanId::invalid()));
+        assert!(!valid(TraceId::invalid(), SPAN_ID));
+    }
 }
@@ -1,11 +1,14 @@
+#[cfg(feature = "trace")]
 use crate::sdk;
+#[cfg(feature = "std")]
+use crate::trace::TraceContextExt;
 use crate::{
-    trace::{Event, Link, Span, SpanId, SpanKind, StatusCode, TraceContextExt, TraceId},
+    time::SystemTime,
+    trace::{Event, Link, Span, SpanContext, SpanId, SpanKind, StatusCode, TraceId},
     Context, KeyValue,
 };
-use std::borrow::Cow;
-use std::fmt;
-use std::time::SystemTime;
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use core::fmt;
 
 /// Interface for constructing `Span`s.
 ///
@@ -34,6 +37,8 @@ use std::time::SystemTime;
 /// Spans can be created and nested manually:
 ///
 /// ```
+/// # #[cfg(feature = "std")]
+/// # {
 /// use opentelemetry::{global, trace::{Span, Tracer, TraceContextExt}, Context};
 ///
 /// let tracer = global::tracer("my-component");
@@ -48,11 +53,14 @@ use std::time::SystemTime;
 ///
 /// child.end();
 /// drop(parent_cx) // end parent
+/// # }
 /// ```
 ///
 /// Spans can also use the current thread's [`Context`] to track which span is active:
 ///
 /// ```
+/// # #[cfg(feature = "std")]
+/// # {
 /// use opentelemetry::{global, trace::{SpanKind, Tracer}};
 ///
 /// let tracer = global::tracer("my-component");
@@ -80,12 +88,15 @@ use std::time::SystemTime;
 ///     // child has ended, parent now the active span again
 /// });
 /// // parent has ended, no active spans
+/// # }
 /// ```
 ///
 /// Spans can also be marked as active, and the resulting guard allows for
 /// greater control over when the span is no longer considered active.
 ///
 /// ```
+/// # #[cfg(feature = "std")]
+/// # {
 /// use opentelemetry::{global, trace::{Span, Tracer, mark_span_as_active}};
 /// let tracer = global::tracer("my-component");
 ///
@@ -106,6 +117,7 @@ use std::time::SystemTime;
 /// drop(parent_active);
 ///
 /// // no active span
+/// # }
 /// ```
 ///
 /// ## In Asynchronous Code
@@ -115,6 +127,8 @@ use std::time::SystemTime;
 /// the following example _will not_ work:
 ///
 /// ```no_run
+/// # #[cfg(feature = "std")]
+/// # {
 /// # use opentelemetry::{global, trace::{Tracer, mark_span_as_active}};
 /// # let tracer = global::tracer("foo");
 /// # let span = tracer.start("foo-span");
@@ -123,6 +137,7 @@ use std::time::SystemTime;
 ///     let _g = mark_span_as_active(span);
 ///     // ...
 /// };
+/// # }
 /// ```
 ///
 /// The context guard `_g` will not exit until the future generated by the
@@ -135,6 +150,8 @@ use std::time::SystemTime;
 /// can be used:
 ///
 /// ```
+/// # #[cfg(feature = "trace")]
+/// # {
 /// # async fn run() -> Result<(), ()> {
 /// use opentelemetry::{trace::FutureExt, Context};
 /// let cx = Context::current();
@@ -148,6 +165,7 @@ use std::time::SystemTime;
 ///     .await;
 /// # Ok(())
 /// # }
+/// # }
 /// ```
 ///
 /// [`Future::with_context`] attaches a context to the future, ensuring that the
@@ -187,8 +205,18 @@ pub trait Tracer: fmt::Debug + 'static {
     /// created in another process. Each propagators' deserialization must set
     /// `is_remote` to true on a parent `SpanContext` so `Span` creation knows if the
     /// parent is remote.
-    fn start(&self, name: &str) -> Self::Span {
-        self.start_with_context(name, Context::current())
+    ///
+    /// Without `std` there is no currently active `Span` and the new `Span`
+    /// is a root span, parents are given with [`Tracer::start_with_context`].
+    fn start<T>(&self, name: T) -> Self::Span
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        #[cfg(feature = "std")]
+        let cx = Context::current();
+        #[cfg(not(feature = "std"))]
+        let cx = Context::new();
+        self.start_with_context(name, cx)
     }
 
     /// Starts a new `Span` with a given context
@@ -214,16 +242,22 @@ pub trait Tracer: fmt::Debug + 'static {
     /// created in another process. Each propagators' deserialization must set
     /// `is_remote` to true on a parent `SpanContext` so `Span` creation knows if the
     /// parent is remote.
-    fn start_with_context(&self, name: &str, context: Context) -> Self::Span;
+    fn start_with_context<T>(&self, name: T, context: Context) -> Self::Span
+    where
+        T: Into<Cow<'static, str>>;
 
     /// Creates a span builder
     ///
     /// An ergonomic way for attributes to be configured before the `Span` is started.
-    fn span_builder(&self, name: &str) -> SpanBuilder;
+    fn span_builder<T>(&self, name: T) -> SpanBuilder
+    where
+        T: Into<Cow<'static, str>>;
 
     /// Create a span from a `SpanBuilder`
     fn build(&self, builder: SpanBuilder) -> Self::Span;
 
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
     /// Start a new span and execute the given closure with reference to the span's
     /// context.
     ///
@@ -262,6 +296,8 @@ pub trait Tracer: fmt::Debug + 'static {
         f(cx)
     }
 
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
     /// Start a new span and execute the given closure with reference to the span's
     /// context.
     ///
@@ -307,6 +343,8 @@ pub trait Tracer: fmt::Debug + 'static {
 /// has started.
 ///
 /// ```rust
+/// # #[cfg(feature = "std")]
+/// # {
 /// use opentelemetry::{
 ///     global,
 ///     trace::{TracerProvider, SpanBuilder, SpanKind, Tracer},
@@ -326,6 +364,7 @@ pub trait Tracer: fmt::Debug + 'static {
 ///     .span_builder("example-span-name")
 ///     .with_kind(SpanKind::Server)
 ///     .start(&tracer);
+/// # }
 /// ```
 #[derive(Clone, Debug, Default)]
 pub struct SpanBuilder {
@@ -351,12 +390,16 @@ pub struct SpanBuilder {
     pub links: Option<Vec<Link>>,
     /// Span status code
     pub status_code: Option<StatusCode>,
-    /// Span status message
+    /// Span status message, only used with the `Error` status code
     pub status_message: Option<String>,
+    #[cfg(feature = "trace")]
     /// Sampling result
     pub sampling_result: Option<sdk::trace::SamplingResult>,
 }
 
+/// Semantic convention key for the ordinal number of a request resend.
+const HTTP_RESEND_COUNT: &str = "http.resend_count";
+
 /// SpanBuilder methods
 impl SpanBuilder {
     /// Create a new span builder from a span name
@@ -374,6 +417,7 @@ impl SpanBuilder {
             links: None,
             status_code: None,
             status_message: None,
+            #[cfg(feature = "trace")]
             sampling_result: None,
         }
     }
@@ -450,6 +494,24 @@ impl SpanBuilder {
         }
     }
 
+    /// Mark the span as a resend of a previous attempt of the same request.
+    ///
+    /// Following the semantic conventions for retries, the span is linked to
+    /// the span of the `previous_attempt` and records how many times the
+    /// request has been resent as `http.resend_count`.
+    pub fn with_resend_of(self, previous_attempt: SpanContext, resend_count: u32) -> Self {
+        let mut links = self.links.unwrap_or_default();
+        links.push(Link::new(previous_attempt, Vec::new()));
+        let mut attributes = self.attributes.unwrap_or_default();
+        attributes.push(KeyValue::new(HTTP_RESEND_COUNT, i64::from(resend_count)));
+
+        SpanBuilder {
+            links: Some(links),
+            attributes: Some(attributes),
+            ..self
+        }
+    }
+
     /// Assign status code
     pub fn with_status_code(self, code: StatusCode) -> Self {
         SpanBuilder {
@@ -458,7 +520,7 @@ impl SpanBuilder {
         }
     }
 
-    /// Assign status message
+    /// Assign status message, only used with the `Error` status code
     pub fn with_status_message(self, message: String) -> Self {
         SpanBuilder {
             status_message: Some(message),
@@ -466,6 +528,8 @@ impl SpanBuilder {
         }
     }
 
+    #[cfg(feature = "trace")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "trace")))]
     /// Assign sampling result
     pub fn with_sampling_result(self, sampling_result: sdk::trace::SamplingResult) -> Self {
         SpanBuilder {
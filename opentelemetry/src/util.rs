@@ -7,3 +7,38 @@ pub fn tokio_interval_stream(
 ) -> tokio_stream::wrappers::IntervalStream {
     tokio_stream::wrappers::IntervalStream::new(tokio::time::interval(period))
 }
+
+/// Randomly spread `duration` by up to `percentage` percent in either direction.
+///
+/// Used to keep many processes with the same schedule, e.g. batch exports or
+/// retry backoff, from hitting a backend at the same moments. Percentages
+/// above 100 are treated as 100.
+#[cfg(feature = "trace")]
+#[cfg_attr(docsrs, doc(cfg(feature = "trace")))]
+pub fn jittered(duration: std::time::Duration, percentage: u8) -> std::time::Duration {
+    use rand::Rng;
+
+    if percentage == 0 {
+        return duration;
+    }
+    let spread = f64::from(percentage.min(100)) / 100.0;
+    let factor = rand::thread_rng().gen_range(1.0 - spread..=1.0 + spread);
+    duration.mul_f64(factor)
+}
+
+#[cfg(all(test, feature = "trace"))]
+mod tests {
+    use super::jittered;
+    use std::time::Duration;
+
+    #[test]
+    fn jittered_stays_within_bounds() {
+        let duration = Duration::from_secs(5);
+        assert_eq!(jittered(duration, 0), duration);
+        for _ in 0..100 {
+            let delay = jittered(duration, 20);
+            assert!(delay >= Duration::from_secs(4) && delay <= Duration::from_secs(6));
+            assert!(jittered(duration, 255) <= Duration::from_secs(10));
+        }
+    }
+}